@@ -0,0 +1,125 @@
+//! Print what changed between two generation blobs, one change per line.
+//!
+//! `cargo run -p boot-contracts --example generation_diff -- OLD NEW`. The
+//! output is deterministic, so two diffs of the same pair compare equal and a
+//! review can quote one. The authority the new generation widens follows the
+//! changes, one `!` line each, and then the digest a release approving them
+//! signs.
+
+use boot_contracts::generation_diff::{
+    Change, Delta, DiffError, Widening, decode, diff, widenings, widenings_digest,
+};
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Identities are shown by their first eight bytes, as the root's markers do.
+fn short(identity: &[u8; 32]) -> String {
+    hex(&identity[..8])
+}
+
+fn line<T: std::fmt::Debug>(section: &str, key: &str, delta: &Delta<T>) -> String {
+    match delta {
+        Delta::Added(new) => format!("+ {section} {key} {new:?}"),
+        Delta::Removed(old) => format!("- {section} {key} {old:?}"),
+        Delta::Changed { old, new } => format!("~ {section} {key} {old:?} -> {new:?}"),
+    }
+}
+
+fn key<T: Copy>(delta: &Delta<T>, name: impl Fn(&T) -> String) -> String {
+    match delta {
+        Delta::Added(record) | Delta::Removed(record) | Delta::Changed { new: record, .. } => {
+            name(record)
+        }
+    }
+}
+
+fn describe(change: &Change<'_>) -> String {
+    match change {
+        Change::Executable(delta) => line("executable", &key(delta, |v| v.name.into()), delta),
+        Change::Instance(delta) => line("instance", &key(delta, |v| v.name.into()), delta),
+        Change::Grant(delta) => {
+            let mut text = line("grant", &key(delta, |v| v.name.into()), delta);
+            if let Delta::Changed { old, new } = delta {
+                text += &format!(
+                    " widened={:#x} narrowed={:#x}",
+                    new.widened_from(old),
+                    new.narrowed_from(old)
+                );
+            }
+            text
+        }
+        Change::State(delta) => line("state", &key(delta, |v| v.name.into()), delta),
+        Change::FabricLimits(delta) => line("fabric-limits", "graph", delta),
+        Change::Route(delta) => line("route", &key(delta, |v| short(&v.identity)), delta),
+        Change::Participant(delta) => line("participant", &key(delta, |v| short(&v.grant)), delta),
+        Change::SharedBufferQuota(delta) => line(
+            "shared-buffer-quota",
+            &key(delta, |v| short(&v.holder_identity)),
+            delta,
+        ),
+    }
+}
+
+fn refusal(path: &str, error: DiffError) -> String {
+    format!("{path}: {error:?}")
+}
+
+fn main() -> Result<(), String> {
+    let mut arguments = std::env::args().skip(1);
+    let (Some(old_path), Some(new_path), None) =
+        (arguments.next(), arguments.next(), arguments.next())
+    else {
+        return Err("usage: generation_diff OLD NEW".into());
+    };
+    let old_bytes = std::fs::read(&old_path).map_err(|error| format!("{old_path}: {error}"))?;
+    let new_bytes = std::fs::read(&new_path).map_err(|error| format!("{new_path}: {error}"))?;
+    let old = decode(&old_bytes).map_err(|error| refusal(&old_path, error))?;
+    let new = decode(&new_bytes).map_err(|error| refusal(&new_path, error))?;
+
+    println!(
+        "generation {} {} (v{}) -> {} {} (v{})",
+        old.number(),
+        short(&old.identity()),
+        old.version(),
+        new.number(),
+        short(&new.identity()),
+        new.version()
+    );
+    if new.parent() != Some(old.identity()) {
+        println!("note: the new generation does not name the old one as its parent");
+    }
+    let count = diff(&old, &new, |change| println!("{}", describe(&change)))
+        .map_err(|error| format!("diff: {error:?}"))?;
+    println!("changes={count}");
+
+    // What a release gate would need an approval for.
+    let widened = widenings(&old, &new, |widening| match widening {
+        Widening::Instance { name, rights } => println!("! instance {name} {rights:?}"),
+        Widening::Capability {
+            process,
+            object,
+            rights,
+        } => println!("! capability {process} {object} {rights:?}"),
+        Widening::Mapping {
+            process,
+            object,
+            rights,
+        } => println!("! mapping {process} {object} {rights:?}"),
+        Widening::Service { process, service } => println!("! service {process} {service}"),
+        Widening::Quota { process } => println!("! quota {process}"),
+        Widening::Schedule { thread } => println!("! schedule {thread}"),
+        Widening::Route { route, grant } => {
+            println!("! route {} grant {}", short(&route), short(&grant))
+        }
+    })
+    .map_err(|error| format!("widenings: {error:?}"))?;
+    println!("widenings={widened}");
+    // The digest a release signs to approve exactly that list.
+    if widened != 0 {
+        let digest = widenings_digest(&old, &new).map_err(|error| format!("digest: {error:?}"))?;
+        println!("approve={}", hex(&digest));
+    }
+    Ok(())
+}
@@ -0,0 +1,686 @@
+//! Export archives: a generation on removable media (M6.7).
+//!
+//! An archive is a GPT disk with one transfer partition, and the partition is
+//! one `contracts/transfer` manifest from its first sector. The manifest
+//! carries every payload, and after the generation's objects the state map its
+//! header names and every object that map names, so the disk is
+//! self-contained: a machine that has never seen the generation can rebuild it
+//! and its state from the archive alone. The host's
+//! `write-removable-image.py` writes boot media; this writes and reads the
+//! archive a running system exports.
+//!
+//! Reading an archive proves only that it is well formed. Admission is
+//! `admit`, which rebuilds the generation, holds the manifest header to it,
+//! and then asks the release the same question staging asks of any release.
+
+use crate::generation::{DecodeError, Generation};
+use crate::gpt::{self, GptError, Partition, SLIME_TRANSFER_TYPE_GUID, SectorReader, SectorWriter};
+#[cfg(feature = "release-crypto")]
+use crate::release::{Release, ReleaseError, TrustRoot};
+use crate::store_disk::SECTOR_BYTES;
+use crate::transfer::{HEADER_TOTAL_LEN_OFFSET, TransferError, TransferManifest};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveError {
+    Gpt(GptError),
+    Transfer(TransferError),
+    /// The manifest does not fit the transfer partition or the caller's
+    /// buffer.
+    TooLarge,
+    /// The rebuilt generation does not decode.
+    Generation(DecodeError),
+    /// The rebuilt generation is not the one the manifest header names, or
+    /// the release sequence disagrees with the release record.
+    Mismatch,
+    #[cfg(feature = "release-crypto")]
+    Release(ReleaseError),
+}
+
+impl From<GptError> for ArchiveError {
+    fn from(error: GptError) -> Self {
+        Self::Gpt(error)
+    }
+}
+
+impl From<TransferError> for ArchiveError {
+    fn from(error: TransferError) -> Self {
+        Self::Transfer(error)
+    }
+}
+
+/// Format a device of `capacity` sectors as an archive and write `manifest`
+/// into its transfer partition, the last sector zero-padded.
+///
+/// The table is written before the manifest is sized against it, so a
+/// manifest that does not fit leaves a formatted disk with no manifest on it,
+/// which `read_archive` refuses.
+pub fn write_archive(
+    writer: &mut SectorWriter<'_>,
+    capacity: u64,
+    disk_guid: [u8; 16],
+    manifest: &[u8],
+) -> Result<Partition, ArchiveError> {
+    let partition =
+        gpt::format_single_partition(writer, capacity, SLIME_TRANSFER_TYPE_GUID, disk_guid)?;
+    let sectors = manifest.len().div_ceil(SECTOR_BYTES) as u64;
+    if sectors > partition.last_lba - partition.first_lba + 1 {
+        return Err(ArchiveError::TooLarge);
+    }
+    for (index, chunk) in manifest.chunks(SECTOR_BYTES).enumerate() {
+        let mut sector = [0u8; SECTOR_BYTES];
+        sector[..chunk.len()].copy_from_slice(chunk);
+        writer(partition.first_lba + index as u64, &sector)?;
+    }
+    Ok(partition)
+}
+
+/// Locate the transfer partition on an archive device and decode the manifest
+/// it holds into `out`.
+///
+/// The first sector is read alone to learn the manifest's declared length,
+/// which is bounded by both the partition and `out` before anything else is
+/// read; the decoder then holds every byte to the manifest's own digest.
+pub fn read_archive<'a>(
+    reader: &mut SectorReader<'_>,
+    capacity: u64,
+    out: &'a mut [u8],
+) -> Result<TransferManifest<'a>, ArchiveError> {
+    let partition = gpt::validate_transfer_partition(reader, capacity)?.partition;
+    let mut sector = [0u8; SECTOR_BYTES];
+    reader(partition.first_lba, &mut sector)?;
+    let total_len = u64::from_le_bytes(
+        sector[HEADER_TOTAL_LEN_OFFSET..HEADER_TOTAL_LEN_OFFSET + 8]
+            .try_into()
+            .unwrap(),
+    );
+    let sectors = total_len.div_ceil(SECTOR_BYTES as u64);
+    if sectors > partition.last_lba - partition.first_lba + 1
+        || sectors as usize * SECTOR_BYTES > out.len()
+    {
+        return Err(ArchiveError::TooLarge);
+    }
+    for index in 0..sectors {
+        let offset = index as usize * SECTOR_BYTES;
+        let chunk: &mut [u8; SECTOR_BYTES] = (&mut out[offset..offset + SECTOR_BYTES])
+            .try_into()
+            .unwrap();
+        reader(partition.first_lba + index, chunk)?;
+    }
+    Ok(TransferManifest::decode(&out[..total_len as usize])?)
+}
+
+/// Rebuild an archive's generation into `out`.
+///
+/// Every carried payload is re-hashed and every travelling state entry
+/// decoded before the generation is rebuilt. The rebuilt generation must be
+/// the one the header names, with the header's parent and authority
+/// manifest, and the objects past its own must be the state closure the
+/// header's state root names. Nothing here reads the release: that is
+/// [`admit`].
+pub fn rebuild<'g>(
+    manifest: &TransferManifest<'_>,
+    out: &'g mut [u8],
+) -> Result<Generation<'g>, ArchiveError> {
+    manifest.verify_closure(|_, _| false)?;
+    for index in 0..manifest.state_count() {
+        manifest.state(index)?;
+    }
+    let bytes = manifest.assemble_generation(out)?;
+    let generation = Generation::decode(bytes).map_err(ArchiveError::Generation)?;
+    if generation.identity != manifest.generation
+        || generation.parent != manifest.parent
+        || generation.authority_manifest_identity() != manifest.authority_manifest
+    {
+        return Err(ArchiveError::Mismatch);
+    }
+    manifest.state_closure(generation.object_count())?;
+    Ok(generation)
+}
+
+/// Admit an archive's generation for staging on this machine, rebuilding it
+/// into `out`.
+///
+/// The generation is [`rebuild`]t first, and the release must agree with the
+/// header on its sequence. Then it is `Release::verify_for_staging` against
+/// this machine's trust root, accepted sequence and `baseline`: the
+/// generation this machine holds under the identity the archive names as
+/// parent, or its accepted generation when the archive names none.
+#[cfg(feature = "release-crypto")]
+pub fn admit<'g>(
+    manifest: &TransferManifest<'_>,
+    baseline: &Generation<'_>,
+    root: &TrustRoot,
+    accepted_sequence: u64,
+    out: &'g mut [u8],
+) -> Result<Generation<'g>, ArchiveError> {
+    let generation = rebuild(manifest, out)?;
+    let release = Release::decode(manifest.release()).map_err(ArchiveError::Release)?;
+    if release.sequence != manifest.release_sequence {
+        return Err(ArchiveError::Mismatch);
+    }
+    release
+        .verify_for_staging(&generation, baseline, root, accepted_sequence)
+        .map_err(ArchiveError::Release)?;
+    Ok(generation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sha256::Sha256;
+    use crate::transfer::*;
+
+    const CAPACITY: u64 = 96;
+
+    /// The smallest manifest the decoder accepts: no objects, no states, a
+    /// zeroed release slot and three bytes of metadata. Enough to carry an
+    /// archive round trip; what a real one carries is `encode_manifest`'s.
+    fn manifest() -> alloc::vec::Vec<u8> {
+        let release_offset = HEADER_LEN;
+        let metadata_offset = release_offset + crate::release::RELEASE_BYTES;
+        let total_len = metadata_offset + 3;
+        let mut bytes = alloc::vec![0u8; total_len];
+        bytes[..8].copy_from_slice(&MAGIC);
+        bytes[HEADER_FORMAT_VERSION_OFFSET..HEADER_FORMAT_VERSION_OFFSET + 4]
+            .copy_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bytes[HEADER_HEADER_SIZE_OFFSET..HEADER_HEADER_SIZE_OFFSET + 4]
+            .copy_from_slice(&(HEADER_LEN as u32).to_le_bytes());
+        bytes[HEADER_GENERATION_OFFSET..HEADER_GENERATION_OFFSET + 32].fill(0x11);
+        for (offset, value) in [
+            (HEADER_GENERATION_LEN_OFFSET, 3),
+            (HEADER_OBJECT_OFFSET_OFFSET, HEADER_LEN),
+            (HEADER_STATE_OFFSET_OFFSET, HEADER_LEN),
+            (HEADER_RELEASE_OFFSET_OFFSET, release_offset),
+            (HEADER_METADATA_OFFSET_OFFSET, metadata_offset),
+            (HEADER_METADATA_LEN_OFFSET, 3),
+            (HEADER_PAYLOAD_OFFSET_OFFSET, total_len),
+            (HEADER_TOTAL_LEN_OFFSET, total_len),
+        ] {
+            bytes[offset..offset + 8].copy_from_slice(&(value as u64).to_le_bytes());
+        }
+        bytes[metadata_offset..].copy_from_slice(b"gen");
+        let mut hasher = Sha256::new();
+        hasher.update(&bytes[..HASH_OFFSET]);
+        hasher.update(&[0; 32]);
+        hasher.update(&bytes[HASH_END..]);
+        let digest = hasher.finalize();
+        bytes[HASH_OFFSET..HASH_END].copy_from_slice(&digest);
+        bytes
+    }
+
+    fn archive(manifest: &[u8]) -> alloc::vec::Vec<[u8; SECTOR_BYTES]> {
+        let mut sectors = alloc::vec![[0xA5u8; SECTOR_BYTES]; CAPACITY as usize];
+        let mut writer = |lba: u64, data: &[u8; SECTOR_BYTES]| -> Result<(), GptError> {
+            *sectors.get_mut(lba as usize).ok_or(GptError::OutOfBounds)? = *data;
+            Ok(())
+        };
+        write_archive(&mut writer, CAPACITY, *b"SLIMEARCHIVEGUID", manifest).expect("fits");
+        sectors
+    }
+
+    fn read(
+        sectors: &[[u8; SECTOR_BYTES]],
+        out: &mut [u8],
+    ) -> Result<(usize, [u8; 32]), ArchiveError> {
+        let mut reader = |lba: u64, sector: &mut [u8; SECTOR_BYTES]| -> Result<(), GptError> {
+            *sector = *sectors.get(lba as usize).ok_or(GptError::OutOfBounds)?;
+            Ok(())
+        };
+        read_archive(&mut reader, CAPACITY, out)
+            .map(|manifest| (manifest.generation_len, manifest.generation))
+    }
+
+    /// What `write_archive` puts on a disk, `read_archive` gives back, and a
+    /// byte changed on the disk after writing is the manifest's digest failing.
+    #[test]
+    fn an_archive_round_trips_and_refuses_a_changed_byte() {
+        let manifest = manifest();
+        let mut sectors = archive(&manifest);
+        let mut out = [0u8; 4 * SECTOR_BYTES];
+        assert_eq!(read(&sectors, &mut out), Ok((3, [0x11; 32])));
+
+        sectors[34][SECTOR_BYTES - 1] ^= 1;
+        assert_eq!(
+            read(&sectors, &mut out),
+            Err(ArchiveError::Transfer(TransferError::BadHash))
+        );
+    }
+
+    /// A manifest too large for the partition is refused at writing, and one
+    /// declaring more than the reader's buffer is refused before it is read.
+    #[test]
+    fn a_manifest_past_the_partition_or_the_buffer_is_too_large() {
+        let mut sectors = alloc::vec![[0u8; SECTOR_BYTES]; CAPACITY as usize];
+        let mut writer = |lba: u64, data: &[u8; SECTOR_BYTES]| -> Result<(), GptError> {
+            sectors[lba as usize] = *data;
+            Ok(())
+        };
+        let oversized = alloc::vec![0u8; (CAPACITY as usize - 66) * SECTOR_BYTES];
+        assert_eq!(
+            write_archive(&mut writer, CAPACITY, [7; 16], &oversized),
+            Err(ArchiveError::TooLarge)
+        );
+
+        let sectors = archive(&manifest());
+        let mut small = [0u8; SECTOR_BYTES];
+        assert_eq!(read(&sectors, &mut small), Err(ArchiveError::TooLarge));
+    }
+
+    /// A store disk is not an archive, however well formed.
+    #[test]
+    fn a_disk_without_a_transfer_partition_is_refused() {
+        let mut sectors = alloc::vec![[0u8; SECTOR_BYTES]; CAPACITY as usize];
+        let mut writer = |lba: u64, data: &[u8; SECTOR_BYTES]| -> Result<(), GptError> {
+            sectors[lba as usize] = *data;
+            Ok(())
+        };
+        gpt::format_single_partition(&mut writer, CAPACITY, gpt::SLIME_STORE_TYPE_GUID, [7; 16])
+            .expect("formats");
+        let mut out = [0u8; 4 * SECTOR_BYTES];
+        assert_eq!(
+            read(&sectors, &mut out),
+            Err(ArchiveError::Gpt(GptError::NoTransferPartition))
+        );
+    }
+
+    /// Admission rebuilds the generation before any release check, so a
+    /// manifest whose metadata is no generation is refused there, with the
+    /// decoder's reason.
+    #[test]
+    fn admission_refuses_what_does_not_rebuild_a_generation() {
+        let bytes = manifest();
+        let manifest = TransferManifest::decode(&bytes).expect("valid");
+        let mut out = [0u8; 64];
+        assert_eq!(
+            rebuild(&manifest, &mut out).err(),
+            Some(ArchiveError::Generation(DecodeError::Truncated))
+        );
+    }
+
+    #[cfg(feature = "release-crypto")]
+    const STATE_MAP_TYPE: u32 = 8;
+    #[cfg(feature = "release-crypto")]
+    const STATE_TYPE: u32 = 9;
+    #[cfg(feature = "release-crypto")]
+    const SETTINGS: &[u8] = b"settings-v1";
+
+    /// The smallest generation `Generation::decode` admits: one bootstrap
+    /// object run by one root instance in one process and thread, holding
+    /// the services an init must and one preserved state binding.
+    #[cfg(feature = "release-crypto")]
+    fn generation() -> alloc::vec::Vec<u8> {
+        use crate::generation::*;
+        const PAYLOAD: &[u8] = b"init-image";
+
+        let mut strings = alloc::vec::Vec::new();
+        let mut string = |value: &str| {
+            let offset = strings.len() as u32;
+            strings.extend_from_slice(&(value.len() as u16).to_le_bytes());
+            strings.extend_from_slice(value.as_bytes());
+            offset
+        };
+        let (init, target, product, settings) = (
+            string("init"),
+            string("x86_64-qemu-virtio"),
+            string("product"),
+            string("settings"),
+        );
+        // Count and record length per section, in header order.
+        let sections = [
+            (1, OBJECT_LEN),
+            (1, EXECUTABLE_LEN),
+            (1, INSTANCE_LEN),
+            (0, DEPENDENCY_LEN),
+            (0, BINDING_LEN),
+            (0, GRANT_LEN),
+            (1, STATE_LEN),
+            (0, HEALTH_LEN),
+            (1, PROCESS_LEN),
+            (1, THREAD_LEN),
+            (5, KERNEL_OBJECT_LEN),
+            (0, MAPPING_LEN),
+            (1, CAP_BINDING_LEN),
+            (5, SERVICE_BINDING_LEN),
+            (1, SCHEDULE_LEN),
+            (1, FAULT_POLICY_LEN),
+            (0, SPAWN_TEMPLATE_LEN),
+            (1, RESOURCE_QUOTA_LEN),
+            (0, MINTED_BINDING_LEN),
+            (0, NOTIFICATION_GRANT_LEN),
+            (0, NOTIFICATION_BINDING_LEN),
+        ];
+        let mut offsets = [0usize; 21];
+        let mut cursor = HEADER_LEN;
+        for (offset, (count, len)) in offsets.iter_mut().zip(sections) {
+            *offset = cursor;
+            cursor += count * len;
+        }
+        let string_offset = cursor;
+        let payload_offset = string_offset + strings.len();
+        let total_len = payload_offset + PAYLOAD.len();
+
+        let mut bytes = alloc::vec![0u8; total_len];
+        let put = |bytes: &mut [u8], offset: usize, value: u32| {
+            bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        };
+        let put64 = |bytes: &mut [u8], offset: usize, value: u64| {
+            bytes[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+        };
+        bytes[..8].copy_from_slice(&MAGIC_V6);
+        put(&mut bytes, 8, FORMAT_VERSION);
+        put(&mut bytes, 12, HEADER_LEN as u32);
+        put64(&mut bytes, 56, 1);
+        put(&mut bytes, 96, target);
+        put(&mut bytes, 100, product);
+        put(&mut bytes, 108, 1);
+        for (index, ((count, _), offset)) in sections.iter().zip(offsets).enumerate() {
+            put(&mut bytes, 112 + index * 4, *count as u32);
+            put64(&mut bytes, 200 + index * 8, offset as u64);
+        }
+        put64(&mut bytes, 368, string_offset as u64);
+        put64(&mut bytes, 376, strings.len() as u64);
+        put64(&mut bytes, 384, payload_offset as u64);
+        put64(&mut bytes, 392, total_len as u64);
+
+        let [
+            object,
+            executable,
+            instance,
+            _,
+            _,
+            _,
+            state,
+            _,
+            process,
+            thread,
+            kernel,
+            _,
+            cap,
+            service,
+            schedule,
+            fault,
+            _,
+            quota,
+            _,
+            _,
+            _,
+        ] = offsets;
+        put(&mut bytes, object, init);
+        put(&mut bytes, object + 4, KIND_BOOTSTRAP);
+        put64(&mut bytes, object + 8, payload_offset as u64);
+        put64(&mut bytes, object + 16, PAYLOAD.len() as u64);
+        bytes[object + 24..object + 56].copy_from_slice(&crate::sha256::digest(PAYLOAD));
+        put(&mut bytes, executable, init);
+        put(&mut bytes, executable + 8, ROLE_INIT);
+        put(&mut bytes, instance, init);
+        put(&mut bytes, instance + 16, 1);
+        put(&mut bytes, state, settings);
+        put(&mut bytes, state + 8, 1);
+        put(&mut bytes, state + 12, POLICY_PRESERVE);
+        // Kernel objects 0..5: cspace, vspace, TCB, IPC buffer, endpoint.
+        put(&mut bytes, process, init);
+        put(&mut bytes, process + 12, 1);
+        put(&mut bytes, thread, init);
+        put(&mut bytes, thread + 8, 2);
+        put(&mut bytes, thread + 20, 3);
+        for kind in 1..=5 {
+            let record = kernel + (kind as usize - 1) * KERNEL_OBJECT_LEN;
+            put(&mut bytes, record, init);
+            put(&mut bytes, record + 4, kind);
+            put(&mut bytes, record + 12, 12);
+            put(&mut bytes, record + 16, 1);
+            put(&mut bytes, record + 20, u32::MAX);
+        }
+        put(&mut bytes, cap + 4, 2);
+        put(&mut bytes, cap + 8, 4);
+        put64(&mut bytes, cap + 12, 1);
+        put(&mut bytes, cap + 28, u32::MAX);
+        for (index, kind) in [
+            SERVICE_LIFECYCLE,
+            SERVICE_SPAWN,
+            SERVICE_SUPERVISION,
+            SERVICE_CAPABILITY_TRANSFER,
+            SERVICE_CONSOLE,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let record = service + index * SERVICE_BINDING_LEN;
+            put(&mut bytes, record + 4, kind);
+            put(
+                &mut bytes,
+                record + 8,
+                if kind == SERVICE_CONSOLE { 32 } else { 1 },
+            );
+            put(&mut bytes, record + 12, 4);
+            put64(&mut bytes, record + 16, 1);
+            put64(&mut bytes, record + 24, index as u64 + 1);
+        }
+        put(&mut bytes, schedule, init);
+        put(&mut bytes, schedule + 8, u32::MAX);
+        put(&mut bytes, schedule + 12, 100);
+        put(&mut bytes, schedule + 16, 100);
+        put(&mut bytes, fault, init);
+        put(&mut bytes, fault + 8, u32::MAX);
+        put(&mut bytes, fault + 12, 4);
+        put(&mut bytes, fault + 24, 1);
+        put(&mut bytes, quota, init);
+        put(&mut bytes, quota + 8, 1);
+        put(&mut bytes, quota + 12, 1);
+        put(&mut bytes, quota + 40, 16);
+        bytes[string_offset..payload_offset].copy_from_slice(&strings);
+        bytes[payload_offset..].copy_from_slice(PAYLOAD);
+        let identity = generation_identity(&bytes);
+        bytes[24..56].copy_from_slice(&identity);
+        bytes
+    }
+
+    /// A release of `generation` at sequence 3, signed by `key` alone.
+    #[cfg(feature = "release-crypto")]
+    fn release(
+        generation: &Generation<'_>,
+        key: &ed25519_dalek::SigningKey,
+    ) -> [u8; crate::release::RELEASE_BYTES] {
+        use crate::release::*;
+        use ed25519_dalek::Signer;
+
+        let target = generation.target.as_bytes();
+        let mut bytes = [0u8; RELEASE_BYTES];
+        bytes[..8].copy_from_slice(&RELEASE_MAGIC);
+        bytes[8..12].copy_from_slice(&RELEASE_VERSION.to_le_bytes());
+        bytes[12..16].copy_from_slice(&(RELEASE_HEADER_BYTES as u32).to_le_bytes());
+        bytes[24..56].copy_from_slice(&generation.identity);
+        bytes[88..96].copy_from_slice(&3u64.to_le_bytes());
+        bytes[96..100].copy_from_slice(&(target.len() as u32).to_le_bytes());
+        bytes[100..104].copy_from_slice(&1u32.to_le_bytes());
+        bytes[104..104 + target.len()].copy_from_slice(target);
+        bytes[136..168].fill(0xE3);
+        bytes[168..200].copy_from_slice(&generation.authority_manifest_identity());
+        bytes[232..236].copy_from_slice(&1u32.to_le_bytes());
+        let signed = ssh_signed_payload(&bytes[..RELEASE_HEADER_BYTES]);
+        let entry = RELEASE_HEADER_BYTES;
+        bytes[entry..entry + 32]
+            .copy_from_slice(&crate::sha256::digest(key.verifying_key().as_bytes()));
+        bytes[entry + 32..entry + RELEASE_SIGNATURE_BYTES]
+            .copy_from_slice(&key.sign(&signed).to_bytes());
+        bytes
+    }
+
+    #[cfg(feature = "release-crypto")]
+    fn trust_root(key: &ed25519_dalek::SigningKey) -> TrustRoot {
+        let mut keys = [[0u8; 32]; crate::release::MAX_TRUST_KEYS];
+        keys[0] = key.verifying_key().to_bytes();
+        TrustRoot {
+            version: 1,
+            threshold: 1,
+            key_count: 1,
+            keys,
+            revoked_count: 0,
+            revoked: [[0; 32]; crate::release::MAX_REVOKED_KEYS],
+        }
+    }
+
+    /// The state map naming `SETTINGS` for the generation's one binding.
+    #[cfg(feature = "release-crypto")]
+    fn state_map() -> [u8; crate::state_policy::MAP_ENTRY_BYTES] {
+        let mut map = [0u8; crate::state_policy::MAP_ENTRY_BYTES];
+        map[..32].copy_from_slice(&crate::recovery::binding_identity("settings"));
+        map[32..64].copy_from_slice(&crate::sha256::digest(SETTINGS));
+        map[64..68].copy_from_slice(&1u32.to_le_bytes());
+        map
+    }
+
+    /// Encode an export of `source` carrying `state`, reading the closure
+    /// from `objects`.
+    #[cfg(feature = "release-crypto")]
+    fn export(
+        source: &[u8],
+        release: &[u8],
+        state: &crate::state_policy::StateMap<'_>,
+        objects: &[(u32, &[u8])],
+    ) -> alloc::vec::Vec<u8> {
+        let mut out = alloc::vec![0u8; 16 * SECTOR_BYTES];
+        let len = encode_manifest(
+            source,
+            release,
+            state,
+            |digest, out| {
+                let (kind, bytes) = objects
+                    .iter()
+                    .find(|(_, bytes)| crate::sha256::digest(bytes) == *digest)?;
+                out.get_mut(..bytes.len())?.copy_from_slice(bytes);
+                Some((*kind, bytes.len()))
+            },
+            &mut out,
+        )
+        .expect("encodes");
+        out.truncate(len);
+        out
+    }
+
+    /// The success path end to end: a signed generation and the state its
+    /// BootState names are exported to a disk, read back, admitted against a
+    /// trust root, and put into a store that held none of it, from which each
+    /// reads back by digest.
+    #[cfg(feature = "release-crypto")]
+    #[test]
+    fn an_archive_admits_its_generation_and_state_into_a_fresh_store() {
+        use crate::gpt::SLIME_STORE_TYPE_GUID;
+        use crate::object_store::{BlockIo, IoError, ObjectStore};
+        use crate::sha256::digest;
+        use crate::state_policy::StateMap;
+
+        struct Disk(alloc::vec::Vec<[u8; SECTOR_BYTES]>);
+        impl BlockIo for Disk {
+            fn read_sector(
+                &mut self,
+                lba: u64,
+                out: &mut [u8; SECTOR_BYTES],
+            ) -> Result<(), IoError> {
+                *out = *self.0.get(lba as usize).ok_or(IoError::Device)?;
+                Ok(())
+            }
+            fn write_sector(&mut self, lba: u64, data: &[u8; SECTOR_BYTES]) -> Result<(), IoError> {
+                *self.0.get_mut(lba as usize).ok_or(IoError::Device)? = *data;
+                Ok(())
+            }
+            fn flush(&mut self) -> Result<(), IoError> {
+                Ok(())
+            }
+        }
+
+        let source = generation();
+        let generation = Generation::decode(&source).expect("generation decodes");
+        let key = ed25519_dalek::SigningKey::from_bytes(&[7; 32]);
+        let release = release(&generation, &key);
+        let map = state_map();
+        let state = StateMap::decode(&map).expect("map decodes");
+        let encoded = export(
+            &source,
+            &release,
+            &state,
+            &[(STATE_MAP_TYPE, &map), (STATE_TYPE, SETTINGS)],
+        );
+
+        let sectors = archive(&encoded);
+        let mut reader = |lba: u64, sector: &mut [u8; SECTOR_BYTES]| -> Result<(), GptError> {
+            *sector = *sectors.get(lba as usize).ok_or(GptError::OutOfBounds)?;
+            Ok(())
+        };
+        let mut read = alloc::vec![0u8; encoded.len()];
+        let manifest = read_archive(&mut reader, CAPACITY, &mut read).expect("archive reads");
+        let mut rebuilt = alloc::vec![0u8; source.len()];
+        let admitted = admit(&manifest, &generation, &trust_root(&key), 2, &mut rebuilt)
+            .expect("archive admits");
+        assert_eq!(admitted.identity, generation.identity);
+        let first = admitted.object_count();
+        assert_eq!(manifest.object_count(), first + 2);
+        assert_eq!(
+            manifest.state_closure(first).map(|map| map.root()),
+            Ok(state.root())
+        );
+
+        let partition = Partition {
+            first_lba: 0,
+            last_lba: 4095,
+            type_guid: SLIME_STORE_TYPE_GUID,
+        };
+        let mut disk = Disk(alloc::vec![[0; SECTOR_BYTES]; 4096]);
+        ObjectStore::format(&mut disk, &partition, None).expect("store formats");
+        let mut store = ObjectStore::open(&mut disk, &partition).expect("store opens");
+        assert_eq!(store.object_count(), 0);
+        store.put(&mut disk, 1, &rebuilt).expect("generation put");
+        store
+            .put(&mut disk, 2, manifest.release())
+            .expect("release put");
+        for index in first..manifest.object_count() {
+            let object = manifest.object(index).expect("closure object");
+            store
+                .put(&mut disk, object.kind, object.payload.expect("carried"))
+                .expect("state put");
+        }
+        for (kind, bytes) in [
+            (1, &source[..]),
+            (2, &release[..]),
+            (STATE_MAP_TYPE, &map[..]),
+            (STATE_TYPE, SETTINGS),
+        ] {
+            let mut out = alloc::vec![0u8; bytes.len()];
+            assert_eq!(
+                store.get(&mut disk, &digest(bytes), &mut out),
+                Ok((kind, bytes.len()))
+            );
+            assert_eq!(out, bytes);
+        }
+    }
+
+    /// A manifest whose header names a state root it does not carry is not a
+    /// self-contained archive, however well its generation rebuilds.
+    #[cfg(feature = "release-crypto")]
+    #[test]
+    fn a_manifest_naming_state_it_does_not_carry_is_refused() {
+        use crate::state_policy::StateMap;
+
+        let source = generation();
+        let generation = Generation::decode(&source).expect("generation decodes");
+        let key = ed25519_dalek::SigningKey::from_bytes(&[7; 32]);
+        let release = release(&generation, &key);
+        let mut encoded = export(&source, &release, &StateMap::EMPTY, &[]);
+        let map = state_map();
+        encoded[HEADER_SOURCE_STATE_ROOT_OFFSET..HEADER_SOURCE_STATE_ROOT_OFFSET + 32]
+            .copy_from_slice(&crate::sha256::digest(&map));
+        let mut hasher = Sha256::new();
+        hasher.update(&encoded[..HASH_OFFSET]);
+        hasher.update(&[0; 32]);
+        hasher.update(&encoded[HASH_END..]);
+        let digest = hasher.finalize();
+        encoded[HASH_OFFSET..HASH_END].copy_from_slice(&digest);
+
+        let manifest = TransferManifest::decode(&encoded).expect("manifest decodes");
+        let mut rebuilt = alloc::vec![0u8; source.len()];
+        assert_eq!(
+            rebuild(&manifest, &mut rebuilt).err(),
+            Some(ArchiveError::Transfer(TransferError::BadEntry))
+        );
+    }
+}
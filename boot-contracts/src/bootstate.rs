@@ -1,3 +1,4 @@
+use crate::release::INITIAL_TRUST_ROOT;
 use crate::sha256::{Sha256, digest};
 
 pub const MAGIC: [u8; 8] = *b"SLIMEBS\0";
@@ -12,6 +13,24 @@ pub struct BootState {
     pub generation_root: [u8; 32],
     pub state_root: [u8; 32],
     pub accepted_release_sequence: u64,
+    /// The rotated trust root releases verify against. `None` is
+    /// `INITIAL_TRUST_ROOT`, compiled into every reader.
+    pub trust_root: Option<TrustRootRef>,
+    /// The state root a rollback of `pending` restores, fixed when it was
+    /// staged: the known-good generation's state, as its rollback policies
+    /// leave it. Present exactly while something is pending, so whichever of
+    /// stage-0, the root or the manager rolls back, the candidate's state never
+    /// outlives it.
+    pub rollback_state_root: Option<[u8; 32]>,
+}
+
+/// A persisted trust root: the store identity of its record and the version
+/// that record carries. The version is held here as well as in the record so
+/// that anti-rollback needs no store read: a rotation must climb past it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrustRootRef {
+    pub object: [u8; 32],
+    pub version: u32,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -26,6 +45,12 @@ pub enum BootStateError {
     BadPendingAttempts,
     NonZeroReserved,
     BadChecksum,
+    /// A trust-root reference with a zero half, or one naming the compiled-in
+    /// version, which is never persisted.
+    BadTrustRoot,
+    /// A rollback state root with nothing pending, or a pending generation
+    /// with none.
+    BadRollbackStateRoot,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -35,6 +60,7 @@ pub enum BootTransitionError {
     WrongRunningGeneration,
     StaleRelease,
     SequenceExhausted,
+    StaleTrustRoot,
 }
 
 impl BootState {
@@ -55,6 +81,15 @@ impl BootState {
         out[136..168].copy_from_slice(&self.state_root);
         out[RELEASE_SEQUENCE_OFFSET..CHECKSUM_OFFSET]
             .copy_from_slice(&self.accepted_release_sequence.to_le_bytes());
+        if let Some(trust_root) = self.trust_root {
+            out[TRUST_ROOT_OFFSET..TRUST_ROOT_VERSION_OFFSET].copy_from_slice(&trust_root.object);
+            out[TRUST_ROOT_VERSION_OFFSET..TRUST_ROOT_END]
+                .copy_from_slice(&trust_root.version.to_le_bytes());
+        }
+        if let Some(rollback_state_root) = self.rollback_state_root {
+            out[ROLLBACK_STATE_ROOT_OFFSET..ROLLBACK_STATE_ROOT_END]
+                .copy_from_slice(&rollback_state_root);
+        }
         let checksum = slot_checksum(&out);
         out[CHECKSUM_OFFSET..CHECKSUM_END].copy_from_slice(&checksum);
         Ok(out)
@@ -74,7 +109,9 @@ impl BootState {
             return Err(BootStateError::UnknownRequiredFlags);
         }
         if bytes[100..104].iter().any(|byte| *byte != 0)
-            || bytes[CHECKSUM_END..].iter().any(|byte| *byte != 0)
+            || bytes[ROLLBACK_STATE_ROOT_END..]
+                .iter()
+                .any(|byte| *byte != 0)
         {
             return Err(BootStateError::NonZeroReserved);
         }
@@ -83,6 +120,15 @@ impl BootState {
             return Err(BootStateError::BadChecksum);
         }
         let pending_bytes: [u8; 32] = bytes[64..96].try_into().unwrap();
+        let rollback_bytes: [u8; 32] = bytes[ROLLBACK_STATE_ROOT_OFFSET..ROLLBACK_STATE_ROOT_END]
+            .try_into()
+            .unwrap();
+        let trust_root = TrustRootRef {
+            object: bytes[TRUST_ROOT_OFFSET..TRUST_ROOT_VERSION_OFFSET]
+                .try_into()
+                .unwrap(),
+            version: read_u32(bytes, TRUST_ROOT_VERSION_OFFSET),
+        };
         let state = Self {
             sequence: read_u64(bytes, 24),
             known_good: bytes[32..64].try_into().unwrap(),
@@ -91,17 +137,42 @@ impl BootState {
             generation_root: bytes[104..136].try_into().unwrap(),
             state_root: bytes[136..168].try_into().unwrap(),
             accepted_release_sequence: read_u64(bytes, RELEASE_SEQUENCE_OFFSET),
+            trust_root: (trust_root != TrustRootRef::COMPILED_IN).then_some(trust_root),
+            rollback_state_root: (rollback_bytes != [0; 32]).then_some(rollback_bytes),
         };
         validate(&state)?;
         Ok(state)
     }
 
+    /// Stage `pending` over `state_root`, with a rollback that restores the
+    /// known-good generation's state as it stands.
     pub fn stage_pending(
         self,
         pending: [u8; 32],
         attempts: u32,
         generation_root: [u8; 32],
         state_root: [u8; 32],
+    ) -> Result<Self, BootTransitionError> {
+        let rollback_state_root = self.known_good_state_root();
+        self.stage_pending_with_rollback(
+            pending,
+            attempts,
+            generation_root,
+            state_root,
+            rollback_state_root,
+        )
+    }
+
+    /// `stage_pending` with the state root a rollback restores named: the
+    /// known-good state after the staged generation's rollback policies, which
+    /// only a reader of that generation can work out.
+    pub fn stage_pending_with_rollback(
+        self,
+        pending: [u8; 32],
+        attempts: u32,
+        generation_root: [u8; 32],
+        state_root: [u8; 32],
+        rollback_state_root: [u8; 32],
     ) -> Result<Self, BootTransitionError> {
         if attempts == 0 {
             return Err(BootTransitionError::AttemptsExhausted);
@@ -114,6 +185,8 @@ impl BootState {
             generation_root,
             state_root,
             accepted_release_sequence: self.accepted_release_sequence,
+            trust_root: self.trust_root,
+            rollback_state_root: Some(rollback_state_root),
         })
     }
 
@@ -148,10 +221,60 @@ impl BootState {
             pending: None,
             remaining_attempts: 0,
             accepted_release_sequence: release_sequence,
+            rollback_state_root: None,
             ..self
         })
     }
 
+    /// Install a rotated trust root. Versions only climb, so a root this
+    /// state has moved past, or the compiled-in one, cannot be installed again.
+    pub fn rotate_trust_root(self, trust_root: TrustRootRef) -> Result<Self, BootTransitionError> {
+        if trust_root.version <= self.trust_root_version() {
+            return Err(BootTransitionError::StaleTrustRoot);
+        }
+        Ok(Self {
+            sequence: next_sequence(self.sequence)?,
+            trust_root: Some(trust_root),
+            ..self
+        })
+    }
+
+    /// The version of the trust root releases must be signed under.
+    pub fn trust_root_version(&self) -> u32 {
+        self.trust_root
+            .map_or(INITIAL_TRUST_ROOT.version, |trust_root| trust_root.version)
+    }
+
+    /// The state root the known-good generation runs over: the one a
+    /// rollback would restore while something is pending, the live one
+    /// otherwise.
+    pub fn known_good_state_root(&self) -> [u8; 32] {
+        self.rollback_state_root.unwrap_or(self.state_root)
+    }
+
+    /// The store objects this root keeps alive: both generations it can
+    /// select, the generation root, both state roots it can boot over, and the
+    /// trust-root record. A store compaction whose root set omits any of them
+    /// could free what the next boot reads.
+    pub fn store_roots(&self) -> impl Iterator<Item = [u8; 32]> {
+        [
+            Some(self.known_good),
+            self.pending,
+            Some(self.generation_root),
+            Some(self.state_root),
+            self.rollback_state_root
+                .filter(|root| *root != self.state_root),
+            self.trust_root.map(|trust_root| trust_root.object),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// Roll the pending generation back, restoring the state root staging
+    /// fixed for it in the same commit, so the known-good generation never
+    /// boots over what its candidate's staging or migrations wrote. With
+    /// nothing pending there is nothing to restore, and the state is returned
+    /// as it was.
     pub fn rollback_pending(self) -> Result<Self, BootTransitionError> {
         if self.pending.is_none() {
             return Ok(self);
@@ -160,11 +283,21 @@ impl BootState {
             sequence: next_sequence(self.sequence)?,
             pending: None,
             remaining_attempts: 0,
+            state_root: self.known_good_state_root(),
+            rollback_state_root: None,
             ..self
         })
     }
 }
 
+impl TrustRootRef {
+    /// The all-zero encoding, which decodes as no reference.
+    const COMPILED_IN: Self = Self {
+        object: [0; 32],
+        version: 0,
+    };
+}
+
 /// Which of the two fixed slots a record came from.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Slot {
@@ -240,6 +373,610 @@ pub fn select_bootstate(
     }
 }
 
+/// The bytes of the shadow-evidence sector.
+pub const SHADOW_EVIDENCE_BYTES: usize = BOOTSTORE_VERDICT_OFFSET - BOOTSTORE_EVIDENCE_OFFSET;
+
+/// What a shadow boot concluded about its candidate. `Requested` is the
+/// manager's ask, before the root has run anything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShadowOutcome {
+    Requested,
+    Healthy,
+    Unhealthy,
+    TimedOut,
+    Refused,
+}
+
+impl ShadowOutcome {
+    pub fn code(self) -> u32 {
+        match self {
+            ShadowOutcome::Requested => 0,
+            ShadowOutcome::Healthy => 1,
+            ShadowOutcome::Unhealthy => 2,
+            ShadowOutcome::TimedOut => 3,
+            ShadowOutcome::Refused => 4,
+        }
+    }
+
+    pub fn from_code(code: u32) -> Option<Self> {
+        Some(match code {
+            0 => ShadowOutcome::Requested,
+            1 => ShadowOutcome::Healthy,
+            2 => ShadowOutcome::Unhealthy,
+            3 => ShadowOutcome::TimedOut,
+            4 => ShadowOutcome::Refused,
+            _ => return None,
+        })
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ShadowOutcome::Requested => "requested",
+            ShadowOutcome::Healthy => "healthy",
+            ShadowOutcome::Unhealthy => "unhealthy",
+            ShadowOutcome::TimedOut => "timed-out",
+            ShadowOutcome::Refused => "refused",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShadowEvidenceError {
+    BadMagic,
+    UnsupportedVersion,
+    BadHeaderSize,
+    UnknownOutcome,
+    ZeroCandidate,
+    NonZeroReserved,
+    BadChecksum,
+}
+
+/// One shadow boot, as the bootstore's evidence sector records it.
+///
+/// The sector outlives the sealed store: the manager writes a request into it
+/// and the root overwrites that request with a verdict, so it sits outside the
+/// directory checksum and carries its own. The counts say what the verdict
+/// rests on — a healthy verdict over zero exercised instances proves nothing,
+/// and `promotes` refuses it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShadowEvidence {
+    pub outcome: ShadowOutcome,
+    /// Health probes the candidate declares that the shadow run could not
+    /// observe: a no-fault window or a route, neither of which a run that
+    /// parks every service call and ends once its instances settle can pass.
+    pub unverified: u32,
+    pub candidate: [u8; 32],
+    pub running: [u8; 32],
+    pub release_sequence: u64,
+    pub required: u32,
+    pub exercised: u32,
+    pub parked: u32,
+    pub completed: u32,
+    pub failed: u32,
+    pub withheld: u32,
+    pub budget_bytes: u64,
+    pub used_bytes: u64,
+}
+
+impl ShadowEvidence {
+    /// The manager's request to shadow `candidate` under `running`.
+    pub fn request(candidate: [u8; 32], running: [u8; 32], release_sequence: u64) -> Self {
+        Self {
+            outcome: ShadowOutcome::Requested,
+            unverified: 0,
+            candidate,
+            running,
+            release_sequence,
+            required: 0,
+            exercised: 0,
+            parked: 0,
+            completed: 0,
+            failed: 0,
+            withheld: 0,
+            budget_bytes: 0,
+            used_bytes: 0,
+        }
+    }
+
+    /// Whether this record asks for a shadow boot of `pending`.
+    pub fn requests(&self, pending: [u8; 32]) -> bool {
+        self.outcome == ShadowOutcome::Requested && self.candidate == pending
+    }
+
+    /// Whether this record is promotion evidence for `candidate` at
+    /// `release_sequence`: a healthy verdict for that exact pair, with every
+    /// required instance exercised, none failed, and no declared probe left
+    /// unverified. A candidate whose probes a shadow cannot observe is
+    /// promoted on the verdict of a boot that ran it, or not at all.
+    pub fn promotes(&self, candidate: [u8; 32], release_sequence: u64) -> bool {
+        self.outcome == ShadowOutcome::Healthy
+            && self.candidate == candidate
+            && self.release_sequence == release_sequence
+            && self.exercised != 0
+            && self.exercised >= self.required
+            && self.failed == 0
+            && self.unverified == 0
+    }
+
+    /// Whether this record rolls `candidate` back: an instance of it failed,
+    /// or the root refused to admit it at all. A timed-out run is neither
+    /// promotion nor rejection, so the candidate waits for better evidence.
+    pub fn rejects(&self, candidate: [u8; 32]) -> bool {
+        matches!(
+            self.outcome,
+            ShadowOutcome::Unhealthy | ShadowOutcome::Refused
+        ) && self.candidate == candidate
+    }
+
+    pub fn encode(self) -> Result<[u8; SHADOW_EVIDENCE_BYTES], ShadowEvidenceError> {
+        if self.candidate == [0; 32] {
+            return Err(ShadowEvidenceError::ZeroCandidate);
+        }
+        let mut out = [0u8; SHADOW_EVIDENCE_BYTES];
+        out[..8].copy_from_slice(&SHADOW_EVIDENCE_MAGIC);
+        out[8..12].copy_from_slice(&SHADOW_EVIDENCE_VERSION.to_le_bytes());
+        out[12..16].copy_from_slice(&(SHADOW_EVIDENCE_BYTES as u32).to_le_bytes());
+        out[16..20].copy_from_slice(&self.outcome.code().to_le_bytes());
+        out[SHADOW_EVIDENCE_UNVERIFIED_OFFSET..SHADOW_EVIDENCE_UNVERIFIED_OFFSET + 4]
+            .copy_from_slice(&self.unverified.to_le_bytes());
+        out[24..56].copy_from_slice(&self.candidate);
+        out[56..88].copy_from_slice(&self.running);
+        out[88..96].copy_from_slice(&self.release_sequence.to_le_bytes());
+        for (index, count) in [
+            self.required,
+            self.exercised,
+            self.parked,
+            self.completed,
+            self.failed,
+            self.withheld,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let offset = SHADOW_EVIDENCE_REQUIRED_OFFSET + index * 4;
+            out[offset..offset + 4].copy_from_slice(&count.to_le_bytes());
+        }
+        out[120..128].copy_from_slice(&self.budget_bytes.to_le_bytes());
+        out[128..136].copy_from_slice(&self.used_bytes.to_le_bytes());
+        let checksum = evidence_checksum(&out);
+        out[SHADOW_EVIDENCE_CHECKSUM_OFFSET..SHADOW_EVIDENCE_CHECKSUM_END]
+            .copy_from_slice(&checksum);
+        Ok(out)
+    }
+
+    /// Decode the evidence sector. An all-zero sector — no request was ever
+    /// written — is `BadMagic`, which callers read as "nothing to do".
+    pub fn decode(bytes: &[u8; SHADOW_EVIDENCE_BYTES]) -> Result<Self, ShadowEvidenceError> {
+        if bytes[..8] != SHADOW_EVIDENCE_MAGIC {
+            return Err(ShadowEvidenceError::BadMagic);
+        }
+        if read_u32(bytes, 8) != SHADOW_EVIDENCE_VERSION {
+            return Err(ShadowEvidenceError::UnsupportedVersion);
+        }
+        if read_u32(bytes, 12) as usize != SHADOW_EVIDENCE_BYTES {
+            return Err(ShadowEvidenceError::BadHeaderSize);
+        }
+        if bytes[SHADOW_EVIDENCE_CHECKSUM_END..]
+            .iter()
+            .any(|byte| *byte != 0)
+        {
+            return Err(ShadowEvidenceError::NonZeroReserved);
+        }
+        let expected: [u8; 32] = bytes
+            [SHADOW_EVIDENCE_CHECKSUM_OFFSET..SHADOW_EVIDENCE_CHECKSUM_END]
+            .try_into()
+            .unwrap();
+        if evidence_checksum(bytes) != expected {
+            return Err(ShadowEvidenceError::BadChecksum);
+        }
+        let outcome = ShadowOutcome::from_code(read_u32(bytes, 16))
+            .ok_or(ShadowEvidenceError::UnknownOutcome)?;
+        let candidate: [u8; 32] = bytes[24..56].try_into().unwrap();
+        if candidate == [0; 32] {
+            return Err(ShadowEvidenceError::ZeroCandidate);
+        }
+        let count = |index: usize| read_u32(bytes, SHADOW_EVIDENCE_REQUIRED_OFFSET + index * 4);
+        Ok(Self {
+            outcome,
+            unverified: read_u32(bytes, SHADOW_EVIDENCE_UNVERIFIED_OFFSET),
+            candidate,
+            running: bytes[56..88].try_into().unwrap(),
+            release_sequence: read_u64(bytes, SHADOW_EVIDENCE_RELEASE_SEQUENCE_OFFSET),
+            required: count(0),
+            exercised: count(1),
+            parked: count(2),
+            completed: count(3),
+            failed: count(4),
+            withheld: count(5),
+            budget_bytes: read_u64(bytes, SHADOW_EVIDENCE_BUDGET_BYTES_OFFSET),
+            used_bytes: read_u64(bytes, SHADOW_EVIDENCE_USED_BYTES_OFFSET),
+        })
+    }
+}
+
+pub fn evidence_checksum(bytes: &[u8; SHADOW_EVIDENCE_BYTES]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(&bytes[..SHADOW_EVIDENCE_CHECKSUM_OFFSET]);
+    hasher.update(&[0u8; 32]);
+    hasher.update(&bytes[SHADOW_EVIDENCE_CHECKSUM_END..]);
+    hasher.finalize()
+}
+
+/// The bytes of the health-verdict sector.
+pub const HEALTH_VERDICT_BYTES: usize = BOOTSTORE_SEALED_OFFSET - BOOTSTORE_VERDICT_OFFSET;
+
+/// How a generation's declared health probes came out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthOutcome {
+    /// Every probe passed.
+    Passed,
+    /// At least one probe failed; `failed_instance` and `failed_probe` name
+    /// the first.
+    Failed,
+}
+
+impl HealthOutcome {
+    pub fn code(self) -> u32 {
+        match self {
+            HealthOutcome::Passed => 1,
+            HealthOutcome::Failed => 2,
+        }
+    }
+
+    pub fn from_code(code: u32) -> Option<Self> {
+        Some(match code {
+            1 => HealthOutcome::Passed,
+            2 => HealthOutcome::Failed,
+            _ => return None,
+        })
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HealthOutcome::Passed => "passed",
+            HealthOutcome::Failed => "failed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthVerdictError {
+    BadMagic,
+    UnsupportedVersion,
+    BadHeaderSize,
+    UnknownOutcome,
+    ZeroGeneration,
+    /// The counts do not support the outcome: a pass with a failure or an
+    /// unevaluated probe in it, or a failure that names none.
+    InconsistentCounts,
+    NonZeroReserved,
+    BadChecksum,
+}
+
+/// The root's verdict on the health probes one generation declares.
+///
+/// Replaces a bare "this pending generation is healthy" flag with the evidence
+/// behind it: how many probes were declared and how many passed, the service
+/// tick the verdict was reached at, and on failure the first instance and
+/// probe that failed. Like the shadow evidence beside it, the sector is written
+/// after the store is sealed and carries its own checksum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthVerdict {
+    pub outcome: HealthOutcome,
+    pub generation: [u8; 32],
+    pub release_sequence: u64,
+    pub probe_count: u32,
+    pub ticks: u64,
+    pub passed: u32,
+    pub failed: u32,
+    /// The first instance with a failed probe. `None` on a pass.
+    pub failed_instance: Option<u32>,
+    /// The probe bit that failed first. Zero on a pass.
+    pub failed_probe: u32,
+}
+
+impl HealthVerdict {
+    /// Whether this verdict promotes `pending` at `release_sequence`: a pass
+    /// for that exact pair, resting on at least one probe, every one passed.
+    pub fn promotes(&self, pending: [u8; 32], release_sequence: u64) -> bool {
+        self.outcome == HealthOutcome::Passed
+            && self.generation == pending
+            && self.release_sequence == release_sequence
+            && self.probe_count != 0
+            && self.passed == self.probe_count
+            && self.failed == 0
+    }
+
+    /// Whether this verdict rolls `pending` back.
+    ///
+    /// Not tied to a release sequence, unlike [`Self::promotes`]: the identity
+    /// is a digest of the generation's bytes, so a probe those bytes failed
+    /// fails again under any release that names them.
+    pub fn rejects(&self, pending: [u8; 32]) -> bool {
+        self.outcome == HealthOutcome::Failed && self.generation == pending
+    }
+
+    pub fn encode(self) -> Result<[u8; HEALTH_VERDICT_BYTES], HealthVerdictError> {
+        if self.generation == [0; 32] {
+            return Err(HealthVerdictError::ZeroGeneration);
+        }
+        if !self.consistent() {
+            return Err(HealthVerdictError::InconsistentCounts);
+        }
+        let mut out = [0u8; HEALTH_VERDICT_BYTES];
+        out[..8].copy_from_slice(&HEALTH_VERDICT_MAGIC);
+        out[8..12].copy_from_slice(&HEALTH_VERDICT_VERSION.to_le_bytes());
+        out[12..16].copy_from_slice(&(HEALTH_VERDICT_BYTES as u32).to_le_bytes());
+        out[16..20].copy_from_slice(&self.outcome.code().to_le_bytes());
+        out[20..24].copy_from_slice(&self.probe_count.to_le_bytes());
+        out[24..56].copy_from_slice(&self.generation);
+        out[56..64].copy_from_slice(&self.release_sequence.to_le_bytes());
+        out[64..72].copy_from_slice(&self.ticks.to_le_bytes());
+        out[72..76].copy_from_slice(&self.passed.to_le_bytes());
+        out[76..80].copy_from_slice(&self.failed.to_le_bytes());
+        out[80..84].copy_from_slice(&self.failed_instance.unwrap_or(u32::MAX).to_le_bytes());
+        out[84..88].copy_from_slice(&self.failed_probe.to_le_bytes());
+        let checksum = verdict_checksum(&out);
+        out[HEALTH_VERDICT_CHECKSUM_OFFSET..HEALTH_VERDICT_CHECKSUM_END].copy_from_slice(&checksum);
+        Ok(out)
+    }
+
+    /// Decode the verdict sector. An all-zero sector — no verdict was ever
+    /// written — is `BadMagic`, which callers read as "no verdict".
+    pub fn decode(bytes: &[u8; HEALTH_VERDICT_BYTES]) -> Result<Self, HealthVerdictError> {
+        if bytes[..8] != HEALTH_VERDICT_MAGIC {
+            return Err(HealthVerdictError::BadMagic);
+        }
+        if read_u32(bytes, 8) != HEALTH_VERDICT_VERSION {
+            return Err(HealthVerdictError::UnsupportedVersion);
+        }
+        if read_u32(bytes, 12) as usize != HEALTH_VERDICT_BYTES {
+            return Err(HealthVerdictError::BadHeaderSize);
+        }
+        if bytes[HEALTH_VERDICT_CHECKSUM_END..]
+            .iter()
+            .any(|byte| *byte != 0)
+        {
+            return Err(HealthVerdictError::NonZeroReserved);
+        }
+        let expected: [u8; 32] = bytes[HEALTH_VERDICT_CHECKSUM_OFFSET..HEALTH_VERDICT_CHECKSUM_END]
+            .try_into()
+            .unwrap();
+        if verdict_checksum(bytes) != expected {
+            return Err(HealthVerdictError::BadChecksum);
+        }
+        let outcome = HealthOutcome::from_code(read_u32(bytes, HEALTH_VERDICT_OUTCOME_OFFSET))
+            .ok_or(HealthVerdictError::UnknownOutcome)?;
+        let generation: [u8; 32] = bytes[24..56].try_into().unwrap();
+        if generation == [0; 32] {
+            return Err(HealthVerdictError::ZeroGeneration);
+        }
+        let failed_instance = read_u32(bytes, HEALTH_VERDICT_FAILED_INSTANCE_OFFSET);
+        let verdict = Self {
+            outcome,
+            generation,
+            release_sequence: read_u64(bytes, HEALTH_VERDICT_RELEASE_SEQUENCE_OFFSET),
+            probe_count: read_u32(bytes, HEALTH_VERDICT_PROBE_COUNT_OFFSET),
+            ticks: read_u64(bytes, HEALTH_VERDICT_TICKS_OFFSET),
+            passed: read_u32(bytes, HEALTH_VERDICT_PASSED_OFFSET),
+            failed: read_u32(bytes, HEALTH_VERDICT_FAILED_OFFSET),
+            failed_instance: (failed_instance != u32::MAX).then_some(failed_instance),
+            failed_probe: read_u32(bytes, HEALTH_VERDICT_FAILED_PROBE_OFFSET),
+        };
+        if !verdict.consistent() {
+            return Err(HealthVerdictError::InconsistentCounts);
+        }
+        Ok(verdict)
+    }
+
+    fn consistent(&self) -> bool {
+        let counted = self
+            .passed
+            .checked_add(self.failed)
+            .is_some_and(|counted| counted <= self.probe_count);
+        counted
+            && match self.outcome {
+                HealthOutcome::Passed => {
+                    self.failed == 0
+                        && self.passed == self.probe_count
+                        && self.failed_instance.is_none()
+                        && self.failed_probe == 0
+                }
+                HealthOutcome::Failed => {
+                    self.failed != 0 && self.failed_instance.is_some() && self.failed_probe != 0
+                }
+            }
+    }
+}
+
+pub fn verdict_checksum(bytes: &[u8; HEALTH_VERDICT_BYTES]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(&bytes[..HEALTH_VERDICT_CHECKSUM_OFFSET]);
+    hasher.update(&[0u8; 32]);
+    hasher.update(&bytes[HEALTH_VERDICT_CHECKSUM_END..]);
+    hasher.finalize()
+}
+
+/// The bytes of one state-journal sector.
+pub const STATE_JOURNAL_BYTES: usize = STATE_JOURNAL_ENTRY_BYTES;
+
+/// What a transition did to one binding's state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalAction {
+    /// Staging kept the binding's object in a snapshot for a later rollback.
+    Snapshot,
+    /// A rollback put the snapshotted object back.
+    Restore,
+    /// A rollback dropped the binding's object.
+    Discard,
+    /// A promotion let the snapshot go: nothing will restore it now.
+    Release,
+    /// Staging translated the binding's state into the staged generation's
+    /// schema: the entry names the fresh object and the version it is in.
+    Migrate,
+}
+
+impl JournalAction {
+    pub fn code(self) -> u32 {
+        match self {
+            JournalAction::Snapshot => 1,
+            JournalAction::Restore => 2,
+            JournalAction::Discard => 3,
+            JournalAction::Release => 4,
+            JournalAction::Migrate => 5,
+        }
+    }
+
+    pub fn from_code(code: u32) -> Option<Self> {
+        Some(match code {
+            1 => JournalAction::Snapshot,
+            2 => JournalAction::Restore,
+            3 => JournalAction::Discard,
+            4 => JournalAction::Release,
+            5 => JournalAction::Migrate,
+            _ => return None,
+        })
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            JournalAction::Snapshot => "snapshot",
+            JournalAction::Restore => "restore",
+            JournalAction::Discard => "discard",
+            JournalAction::Release => "release",
+            JournalAction::Migrate => "migrate",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateJournalError {
+    BadMagic,
+    UnsupportedVersion,
+    BadHeaderSize,
+    UnknownAction,
+    /// A zero journal sequence, generation or binding. Every written entry
+    /// names all three.
+    Unnamed,
+    NonZeroReserved,
+    BadChecksum,
+}
+
+/// One state-policy action, as the journal beside BootState records it.
+///
+/// Entries are written before the BootState commit they belong to and carry
+/// its sequence, so a reader can tell history from an intent an interruption
+/// cut short: see [`Self::committed_by`]. `sequence` orders the journal
+/// itself, and the sector an entry occupies is that sequence modulo
+/// [`STATE_JOURNAL_SECTORS`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateJournalEntry {
+    pub sequence: u64,
+    pub action: JournalAction,
+    /// The policy the binding declared in `generation`.
+    pub policy: u32,
+    pub boot_sequence: u64,
+    pub schema_version: u32,
+    /// The generation staged, rolled back or promoted.
+    pub generation: [u8; 32],
+    pub binding: [u8; 32],
+    /// The state object snapshotted, restored, discarded or released, or the
+    /// one a migration wrote.
+    pub object: [u8; 32],
+    /// The state root BootState names once the commit lands.
+    pub state_root: [u8; 32],
+}
+
+impl StateJournalEntry {
+    /// The sector, of [`STATE_JOURNAL_SECTORS`], this entry is written to.
+    pub fn sector(&self) -> u64 {
+        self.sequence % STATE_JOURNAL_SECTORS as u64
+    }
+
+    /// Whether `state` is, or descends from, the commit this entry was
+    /// written for. An entry past the live sequence, or at it but naming
+    /// another state root, was never committed: the transition it belongs to
+    /// was interrupted, or refused after the journal write.
+    pub fn committed_by(&self, state: &BootState) -> bool {
+        self.boot_sequence < state.sequence
+            || (self.boot_sequence == state.sequence && self.state_root == state.state_root)
+    }
+
+    pub fn encode(self) -> Result<[u8; STATE_JOURNAL_BYTES], StateJournalError> {
+        if self.sequence == 0 || self.generation == [0; 32] || self.binding == [0; 32] {
+            return Err(StateJournalError::Unnamed);
+        }
+        let mut out = [0u8; STATE_JOURNAL_BYTES];
+        out[..8].copy_from_slice(&STATE_JOURNAL_MAGIC);
+        out[8..12].copy_from_slice(&STATE_JOURNAL_VERSION.to_le_bytes());
+        out[12..16].copy_from_slice(&(STATE_JOURNAL_BYTES as u32).to_le_bytes());
+        out[16..20].copy_from_slice(&self.action.code().to_le_bytes());
+        out[20..24].copy_from_slice(&self.policy.to_le_bytes());
+        out[24..32].copy_from_slice(&self.sequence.to_le_bytes());
+        out[32..40].copy_from_slice(&self.boot_sequence.to_le_bytes());
+        out[40..44].copy_from_slice(&self.schema_version.to_le_bytes());
+        out[48..80].copy_from_slice(&self.generation);
+        out[80..112].copy_from_slice(&self.binding);
+        out[112..144].copy_from_slice(&self.object);
+        out[144..176].copy_from_slice(&self.state_root);
+        let checksum = journal_checksum(&out);
+        out[STATE_JOURNAL_CHECKSUM_OFFSET..STATE_JOURNAL_CHECKSUM_END].copy_from_slice(&checksum);
+        Ok(out)
+    }
+
+    /// Decode one journal sector. An all-zero sector — never written — is
+    /// `BadMagic`, which callers read as an empty place in the ring.
+    pub fn decode(bytes: &[u8; STATE_JOURNAL_BYTES]) -> Result<Self, StateJournalError> {
+        if bytes[..8] != STATE_JOURNAL_MAGIC {
+            return Err(StateJournalError::BadMagic);
+        }
+        if read_u32(bytes, 8) != STATE_JOURNAL_VERSION {
+            return Err(StateJournalError::UnsupportedVersion);
+        }
+        if read_u32(bytes, 12) as usize != STATE_JOURNAL_BYTES {
+            return Err(StateJournalError::BadHeaderSize);
+        }
+        if read_u32(bytes, STATE_JOURNAL_RESERVED_OFFSET) != 0
+            || bytes[STATE_JOURNAL_CHECKSUM_END..]
+                .iter()
+                .any(|byte| *byte != 0)
+        {
+            return Err(StateJournalError::NonZeroReserved);
+        }
+        let expected: [u8; 32] = bytes[STATE_JOURNAL_CHECKSUM_OFFSET..STATE_JOURNAL_CHECKSUM_END]
+            .try_into()
+            .unwrap();
+        if journal_checksum(bytes) != expected {
+            return Err(StateJournalError::BadChecksum);
+        }
+        let action = JournalAction::from_code(read_u32(bytes, STATE_JOURNAL_ACTION_OFFSET))
+            .ok_or(StateJournalError::UnknownAction)?;
+        let entry = Self {
+            sequence: read_u64(bytes, STATE_JOURNAL_SEQUENCE_OFFSET),
+            action,
+            policy: read_u32(bytes, STATE_JOURNAL_POLICY_OFFSET),
+            boot_sequence: read_u64(bytes, STATE_JOURNAL_BOOT_SEQUENCE_OFFSET),
+            schema_version: read_u32(bytes, STATE_JOURNAL_SCHEMA_VERSION_OFFSET),
+            generation: bytes[48..80].try_into().unwrap(),
+            binding: bytes[80..112].try_into().unwrap(),
+            object: bytes[112..144].try_into().unwrap(),
+            state_root: bytes[144..176].try_into().unwrap(),
+        };
+        if entry.sequence == 0 || entry.generation == [0; 32] || entry.binding == [0; 32] {
+            return Err(StateJournalError::Unnamed);
+        }
+        Ok(entry)
+    }
+}
+
+pub fn journal_checksum(bytes: &[u8; STATE_JOURNAL_BYTES]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(&bytes[..STATE_JOURNAL_CHECKSUM_OFFSET]);
+    hasher.update(&[0u8; 32]);
+    hasher.update(&bytes[STATE_JOURNAL_CHECKSUM_END..]);
+    hasher.finalize()
+}
+
 fn next_sequence(sequence: u64) -> Result<u64, BootTransitionError> {
     sequence
         .checked_add(1)
@@ -269,6 +1006,16 @@ fn validate(state: &BootState) -> Result<(), BootStateError> {
     if state.generation_root == [0; 32] {
         return Err(BootStateError::ZeroGenerationRoot);
     }
+    if state.trust_root.is_some_and(|trust_root| {
+        trust_root.object == [0; 32] || trust_root.version <= INITIAL_TRUST_ROOT.version
+    }) {
+        return Err(BootStateError::BadTrustRoot);
+    }
+    if state.pending.is_some() != state.rollback_state_root.is_some()
+        || state.rollback_state_root == Some([0; 32])
+    {
+        return Err(BootStateError::BadRollbackStateRoot);
+    }
     match (state.pending, state.remaining_attempts) {
         (None, 0) | (Some(_), _) => Ok(()),
         _ => Err(BootStateError::BadPendingAttempts),
@@ -299,6 +1046,8 @@ mod tests {
             generation_root: GENERATION_ROOT,
             state_root: empty_state_root(),
             accepted_release_sequence: 1,
+            trust_root: None,
+            rollback_state_root: pending.map(|_| empty_state_root()),
         }
     }
 
@@ -356,6 +1105,477 @@ mod tests {
         assert_eq!(rolled_back.rollback_pending(), Ok(rolled_back));
     }
 
+    #[test]
+    fn store_roots_name_pending_only_while_it_is_staged() {
+        let staged = state(Some(G2), 1);
+        let mut roots = staged.store_roots();
+        assert_eq!(roots.next(), Some(G1));
+        assert_eq!(roots.next(), Some(G2));
+        assert_eq!(roots.next(), Some(GENERATION_ROOT));
+        assert_eq!(roots.next(), Some(empty_state_root()));
+        assert_eq!(roots.next(), None);
+
+        let rolled_back = staged.rollback_pending().unwrap();
+        assert_eq!(rolled_back.store_roots().count(), 3);
+        assert!(rolled_back.store_roots().all(|root| root != G2));
+    }
+
+    const TRUST_ROOT: TrustRootRef = TrustRootRef {
+        object: [5; 32],
+        version: 2,
+    };
+
+    /// The reference lives in the checksummed tail, survives every transition,
+    /// and is a store root; an all-zero tail is the compiled-in root.
+    #[test]
+    fn a_rotated_trust_root_round_trips_and_is_kept() {
+        assert_eq!(
+            state(None, 0).trust_root_version(),
+            INITIAL_TRUST_ROOT.version
+        );
+        let rotated = state(None, 0).rotate_trust_root(TRUST_ROOT).unwrap();
+        assert_eq!(rotated.sequence, 2);
+        assert_eq!(rotated.trust_root_version(), 2);
+        let encoded = rotated.encode().unwrap();
+        assert_eq!(
+            &encoded[TRUST_ROOT_OFFSET..TRUST_ROOT_VERSION_OFFSET],
+            &TRUST_ROOT.object
+        );
+        assert_eq!(BootState::decode(&encoded), Ok(rotated));
+        assert!(rotated.store_roots().any(|root| root == TRUST_ROOT.object));
+
+        let staged = rotated
+            .stage_pending(G2, 1, GENERATION_ROOT, empty_state_root())
+            .unwrap();
+        let promoted = staged.promote_pending(G2, 2).unwrap();
+        assert_eq!(staged.trust_root, Some(TRUST_ROOT));
+        assert_eq!(promoted.trust_root, Some(TRUST_ROOT));
+
+        let mut damaged = encoded;
+        damaged[TRUST_ROOT_VERSION_OFFSET] ^= 1;
+        assert_eq!(
+            BootState::decode(&damaged),
+            Err(BootStateError::BadChecksum)
+        );
+    }
+
+    /// Anti-rollback: a rotation must climb past the installed version, and
+    /// the compiled-in version is never persisted.
+    #[test]
+    fn a_trust_root_version_never_goes_back() {
+        let rotated = state(None, 0).rotate_trust_root(TRUST_ROOT).unwrap();
+        for version in [1, 2] {
+            assert_eq!(
+                rotated.rotate_trust_root(TrustRootRef {
+                    object: [6; 32],
+                    version,
+                }),
+                Err(BootTransitionError::StaleTrustRoot),
+                "version {version}",
+            );
+        }
+        assert!(
+            rotated
+                .rotate_trust_root(TrustRootRef {
+                    object: [6; 32],
+                    version: 3,
+                })
+                .is_ok()
+        );
+
+        let mut initial = state(None, 0);
+        initial.trust_root = Some(TrustRootRef {
+            object: [6; 32],
+            version: INITIAL_TRUST_ROOT.version,
+        });
+        assert_eq!(initial.encode(), Err(BootStateError::BadTrustRoot));
+        initial.trust_root = Some(TrustRootRef {
+            object: [0; 32],
+            version: 2,
+        });
+        assert_eq!(initial.encode(), Err(BootStateError::BadTrustRoot));
+    }
+
+    /// Past the reference comes the rollback state root, which only a
+    /// pending slot names, and past that the slot is still reserved zero.
+    #[test]
+    fn bytes_past_the_trust_root_are_reserved() {
+        let resealed = |mut encoded: [u8; SLOT_BYTES]| {
+            let checksum = slot_checksum(&encoded);
+            encoded[CHECKSUM_OFFSET..CHECKSUM_END].copy_from_slice(&checksum);
+            BootState::decode(&encoded)
+        };
+        let mut encoded = state(None, 0).encode().unwrap();
+        encoded[TRUST_ROOT_END] = 1;
+        assert_eq!(resealed(encoded), Err(BootStateError::BadRollbackStateRoot));
+        let mut encoded = state(None, 0).encode().unwrap();
+        encoded[ROLLBACK_STATE_ROOT_END] = 1;
+        assert_eq!(resealed(encoded), Err(BootStateError::NonZeroReserved));
+    }
+
+    mod shadow {
+        use super::*;
+
+        fn verdict(outcome: ShadowOutcome, exercised: u32, failed: u32) -> ShadowEvidence {
+            ShadowEvidence {
+                outcome,
+                required: 2,
+                exercised,
+                parked: exercised - failed,
+                failed,
+                withheld: 1,
+                budget_bytes: 1 << 20,
+                used_bytes: 4096,
+                ..ShadowEvidence::request(G2, G1, 2)
+            }
+        }
+
+        #[test]
+        fn evidence_round_trips_and_fits_its_sector() {
+            let expected = verdict(ShadowOutcome::Healthy, 2, 0);
+            let encoded = expected.encode().unwrap();
+
+            assert_eq!(ShadowEvidence::decode(&encoded), Ok(expected));
+            assert_eq!(SHADOW_EVIDENCE_BYTES, 512);
+            const _: () = assert!(SHADOW_EVIDENCE_PADDING_OFFSET <= SHADOW_EVIDENCE_BYTES);
+            const _: () = assert!(BOOTSTORE_EVIDENCE_OFFSET >= SLOT_BYTES * SLOT_COUNT);
+        }
+
+        #[test]
+        fn an_empty_sector_is_not_a_request() {
+            assert_eq!(
+                ShadowEvidence::decode(&[0; SHADOW_EVIDENCE_BYTES]),
+                Err(ShadowEvidenceError::BadMagic)
+            );
+        }
+
+        #[test]
+        fn a_damaged_record_is_refused() {
+            let mut encoded = verdict(ShadowOutcome::Healthy, 2, 0).encode().unwrap();
+            encoded[SHADOW_EVIDENCE_OUTCOME_OFFSET] = ShadowOutcome::Unhealthy.code() as u8;
+            assert_eq!(
+                ShadowEvidence::decode(&encoded),
+                Err(ShadowEvidenceError::BadChecksum)
+            );
+        }
+
+        #[test]
+        fn a_request_names_only_its_candidate() {
+            let request = ShadowEvidence::request(G2, G1, 2);
+            assert!(request.requests(G2));
+            assert!(!request.requests(G1));
+            assert!(!verdict(ShadowOutcome::Healthy, 2, 0).requests(G2));
+        }
+
+        #[test]
+        fn only_a_complete_healthy_verdict_promotes() {
+            assert!(verdict(ShadowOutcome::Healthy, 2, 0).promotes(G2, 2));
+            assert!(!verdict(ShadowOutcome::Healthy, 2, 0).promotes(G1, 2));
+            assert!(
+                !verdict(ShadowOutcome::Healthy, 2, 0).promotes(G2, 3),
+                "evidence for one release says nothing about the next",
+            );
+            assert!(!verdict(ShadowOutcome::Healthy, 1, 0).promotes(G2, 2));
+            assert!(!verdict(ShadowOutcome::Healthy, 0, 0).promotes(G2, 2));
+            assert!(!verdict(ShadowOutcome::Unhealthy, 2, 1).promotes(G2, 2));
+            assert!(!verdict(ShadowOutcome::TimedOut, 2, 0).promotes(G2, 2));
+            assert!(!ShadowEvidence::request(G2, G1, 2).promotes(G2, 2));
+        }
+
+        #[test]
+        fn a_verdict_with_unverified_probes_does_not_promote() {
+            let unproven = ShadowEvidence {
+                unverified: 1,
+                ..verdict(ShadowOutcome::Healthy, 2, 0)
+            };
+            let encoded = unproven.encode().unwrap();
+            assert_eq!(ShadowEvidence::decode(&encoded), Ok(unproven));
+            assert!(!unproven.promotes(G2, 2));
+            assert!(
+                !unproven.rejects(G2),
+                "nothing failed, so the candidate waits for a boot's verdict",
+            );
+        }
+
+        #[test]
+        fn only_a_failed_or_refused_run_rejects() {
+            assert!(verdict(ShadowOutcome::Unhealthy, 2, 1).rejects(G2));
+            assert!(verdict(ShadowOutcome::Refused, 0, 0).rejects(G2));
+            assert!(!verdict(ShadowOutcome::Unhealthy, 2, 1).rejects(G1));
+            assert!(!verdict(ShadowOutcome::TimedOut, 2, 0).rejects(G2));
+            assert!(!verdict(ShadowOutcome::Healthy, 2, 0).rejects(G2));
+            assert!(!ShadowEvidence::request(G2, G1, 2).rejects(G2));
+        }
+    }
+
+    mod verdict {
+        use super::*;
+
+        fn passed() -> HealthVerdict {
+            HealthVerdict {
+                outcome: HealthOutcome::Passed,
+                generation: G2,
+                release_sequence: 2,
+                probe_count: 3,
+                ticks: 40,
+                passed: 3,
+                failed: 0,
+                failed_instance: None,
+                failed_probe: 0,
+            }
+        }
+
+        fn failed() -> HealthVerdict {
+            HealthVerdict {
+                outcome: HealthOutcome::Failed,
+                passed: 1,
+                failed: 1,
+                failed_instance: Some(4),
+                failed_probe: 2,
+                ..passed()
+            }
+        }
+
+        #[test]
+        fn verdicts_round_trip_and_fit_their_sector() {
+            for expected in [passed(), failed()] {
+                let encoded = expected.encode().unwrap();
+                assert_eq!(HealthVerdict::decode(&encoded), Ok(expected));
+            }
+            assert_eq!(HEALTH_VERDICT_BYTES, 512);
+            const _: () = assert!(HEALTH_VERDICT_PADDING_OFFSET <= HEALTH_VERDICT_BYTES);
+            assert_eq!(
+                BOOTSTORE_VERDICT_OFFSET,
+                BOOTSTORE_EVIDENCE_OFFSET + SHADOW_EVIDENCE_BYTES
+            );
+        }
+
+        #[test]
+        fn an_empty_sector_is_no_verdict() {
+            assert_eq!(
+                HealthVerdict::decode(&[0; HEALTH_VERDICT_BYTES]),
+                Err(HealthVerdictError::BadMagic)
+            );
+        }
+
+        #[test]
+        fn a_damaged_verdict_is_refused() {
+            let mut encoded = failed().encode().unwrap();
+            encoded[HEALTH_VERDICT_OUTCOME_OFFSET] = HealthOutcome::Passed.code() as u8;
+            assert_eq!(
+                HealthVerdict::decode(&encoded),
+                Err(HealthVerdictError::BadChecksum)
+            );
+        }
+
+        #[test]
+        fn counts_must_support_the_outcome() {
+            let partial = HealthVerdict {
+                passed: 2,
+                ..passed()
+            };
+            assert_eq!(
+                partial.encode(),
+                Err(HealthVerdictError::InconsistentCounts)
+            );
+            let unnamed = HealthVerdict {
+                failed_instance: None,
+                ..failed()
+            };
+            assert_eq!(
+                unnamed.encode(),
+                Err(HealthVerdictError::InconsistentCounts)
+            );
+        }
+
+        #[test]
+        fn a_verdict_decides_only_its_own_generation() {
+            assert!(passed().promotes(G2, 2));
+            assert!(!passed().promotes(G1, 2));
+            assert!(
+                !passed().promotes(G2, 3),
+                "a pass for one release says nothing about the next",
+            );
+            assert!(!passed().rejects(G2));
+            assert!(failed().rejects(G2));
+            assert!(!failed().rejects(G1));
+            assert!(!failed().promotes(G2, 2));
+            let vacuous = HealthVerdict {
+                probe_count: 0,
+                passed: 0,
+                ..passed()
+            };
+            assert!(
+                !vacuous.promotes(G2, 2),
+                "a pass over no probes proves nothing"
+            );
+        }
+    }
+
+    mod journal {
+        use super::*;
+
+        fn snapshot() -> StateJournalEntry {
+            StateJournalEntry {
+                sequence: 65,
+                action: JournalAction::Snapshot,
+                policy: crate::generation::POLICY_SNAPSHOT_BEFORE_UPGRADE,
+                boot_sequence: 2,
+                schema_version: 1,
+                generation: G2,
+                binding: [5; 32],
+                object: [6; 32],
+                state_root: [7; 32],
+            }
+        }
+
+        #[test]
+        fn entries_round_trip_and_fit_their_sector() {
+            let expected = snapshot();
+            let encoded = expected.encode().unwrap();
+
+            assert_eq!(StateJournalEntry::decode(&encoded), Ok(expected));
+            assert_eq!(expected.sector(), 1);
+            const _: () = assert!(STATE_JOURNAL_PADDING_OFFSET <= STATE_JOURNAL_BYTES);
+            assert_eq!(
+                StateJournalEntry::decode(&[0; STATE_JOURNAL_BYTES]),
+                Err(StateJournalError::BadMagic)
+            );
+        }
+
+        #[test]
+        fn a_damaged_entry_is_refused() {
+            let mut encoded = snapshot().encode().unwrap();
+            encoded[STATE_JOURNAL_ACTION_OFFSET] = JournalAction::Discard.code() as u8;
+            assert_eq!(
+                StateJournalEntry::decode(&encoded),
+                Err(StateJournalError::BadChecksum)
+            );
+        }
+
+        #[test]
+        fn only_an_entry_the_live_root_reached_is_committed() {
+            let live = BootState {
+                sequence: 2,
+                state_root: [7; 32],
+                ..state(Some(G2), 2)
+            };
+            assert!(snapshot().committed_by(&live));
+            assert!(snapshot().committed_by(&BootState {
+                sequence: 3,
+                ..live
+            }));
+            assert!(
+                !snapshot().committed_by(&BootState {
+                    state_root: [8; 32],
+                    ..live
+                }),
+                "an entry for a commit that named another root never landed",
+            );
+            assert!(!snapshot().committed_by(&BootState {
+                sequence: 1,
+                ..live
+            }));
+        }
+
+        #[test]
+        fn a_rollback_names_the_restored_state_root() {
+            let staged = state(None, 0)
+                .stage_pending_with_rollback(G2, 1, GENERATION_ROOT, [8; 32], [9; 32])
+                .unwrap();
+            let rolled = staged.rollback_pending().unwrap();
+            assert_eq!(rolled.pending, None);
+            assert_eq!(rolled.state_root, [9; 32]);
+            assert_eq!(rolled.rollback_state_root, None);
+            assert_eq!(
+                state(None, 0).rollback_pending(),
+                Ok(state(None, 0)),
+                "nothing pending, nothing restored",
+            );
+        }
+    }
+
+    /// Every rollback goes back to the state staging found, whoever decides
+    /// it: stage-0 and the root call the plain `rollback_pending`, and it must
+    /// not keep what staging or a migration wrote.
+    mod rollback_state {
+        use super::*;
+
+        const PRE_STAGE: [u8; 32] = [9; 32];
+        const MIGRATED: [u8; 32] = [10; 32];
+
+        fn known_good() -> BootState {
+            BootState {
+                state_root: PRE_STAGE,
+                ..state(None, 0)
+            }
+        }
+
+        #[test]
+        fn a_plain_rollback_restores_the_pre_stage_root() {
+            let staged = known_good()
+                .stage_pending(G2, 2, GENERATION_ROOT, MIGRATED)
+                .unwrap();
+            assert_eq!(staged.state_root, MIGRATED);
+            assert_eq!(staged.known_good_state_root(), PRE_STAGE);
+            // Persisted across the commit, as stage-0 finds it on the next boot.
+            let staged = BootState::decode(&staged.encode().unwrap()).unwrap();
+
+            let rolled = staged.rollback_pending().unwrap();
+            assert_eq!(rolled.state_root, PRE_STAGE);
+            assert_eq!(rolled.rollback_state_root, None);
+        }
+
+        #[test]
+        fn attempt_exhaustion_restores_it_too() {
+            let mut staged = known_good()
+                .stage_pending(G2, 2, GENERATION_ROOT, MIGRATED)
+                .unwrap();
+            while staged.remaining_attempts != 0 {
+                staged = staged.consume_pending_attempt().unwrap();
+            }
+            assert_eq!(
+                staged.consume_pending_attempt(),
+                Err(BootTransitionError::AttemptsExhausted)
+            );
+            assert_eq!(staged.rollback_pending().unwrap().state_root, PRE_STAGE);
+        }
+
+        #[test]
+        fn restaging_over_a_candidate_keeps_the_known_good_root() {
+            let restaged = known_good()
+                .stage_pending(G2, 1, GENERATION_ROOT, MIGRATED)
+                .unwrap()
+                .stage_pending(G1, 1, GENERATION_ROOT, [11; 32])
+                .unwrap();
+            assert_eq!(restaged.rollback_pending().unwrap().state_root, PRE_STAGE);
+        }
+
+        #[test]
+        fn promotion_lets_the_rollback_root_go() {
+            let staged = known_good()
+                .stage_pending(G2, 1, GENERATION_ROOT, MIGRATED)
+                .unwrap();
+            assert!(staged.store_roots().any(|root| root == PRE_STAGE));
+            let promoted = staged.promote_pending(G2, 2).unwrap();
+            assert_eq!(promoted.state_root, MIGRATED);
+            assert_eq!(promoted.rollback_state_root, None);
+            assert!(promoted.store_roots().all(|root| root != PRE_STAGE));
+        }
+
+        #[test]
+        fn pending_and_the_rollback_root_come_together() {
+            let mut staged = known_good()
+                .stage_pending(G2, 1, GENERATION_ROOT, MIGRATED)
+                .unwrap();
+            staged.rollback_state_root = None;
+            assert_eq!(staged.encode(), Err(BootStateError::BadRollbackStateRoot));
+            let mut settled = known_good();
+            settled.rollback_state_root = Some(PRE_STAGE);
+            assert_eq!(settled.encode(), Err(BootStateError::BadRollbackStateRoot));
+        }
+    }
+
     /// Slot selection, which moved here from `stage0` so a component could
     /// apply the same rule. It had no tests there.
     mod selection {
@@ -370,6 +1590,8 @@ mod tests {
                 generation_root: GENERATION_ROOT,
                 state_root: empty_state_root(),
                 accepted_release_sequence: 0,
+                trust_root: None,
+                rollback_state_root: None,
             }
             .encode()
             .expect("encodable")
@@ -0,0 +1,342 @@
+//! ChaCha20-Poly1305 authenticated encryption (RFC 8439), in place.
+//!
+//! The object store seals record payloads with it. Like `sha256` it is
+//! hand-written so the crate keeps building with no allocator and no
+//! optional dependency; the RFC's own vectors below are what anchor it.
+
+pub const KEY_BYTES: usize = 32;
+pub const NONCE_BYTES: usize = 12;
+pub const TAG_BYTES: usize = 16;
+
+/// The tag did not authenticate the ciphertext and associated data under
+/// this key and nonce. The buffer is left as it was given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuthenticationFailed;
+
+/// Encrypt `buffer` in place and return the tag over `aad` and the
+/// ciphertext. A nonce must never be reused with the same key for
+/// different input.
+pub fn seal(
+    key: &[u8; KEY_BYTES],
+    nonce: &[u8; NONCE_BYTES],
+    aad: &[u8],
+    buffer: &mut [u8],
+) -> [u8; TAG_BYTES] {
+    apply_keystream(key, 1, nonce, buffer);
+    tag(key, nonce, aad, buffer)
+}
+
+/// Check `tag` over `aad` and the ciphertext in `buffer`, and only then
+/// decrypt it in place.
+pub fn open(
+    key: &[u8; KEY_BYTES],
+    nonce: &[u8; NONCE_BYTES],
+    aad: &[u8],
+    buffer: &mut [u8],
+    tag_in: &[u8; TAG_BYTES],
+) -> Result<(), AuthenticationFailed> {
+    let expected = tag(key, nonce, aad, buffer);
+    let difference = expected
+        .iter()
+        .zip(tag_in)
+        .fold(0u8, |acc, (left, right)| acc | (left ^ right));
+    if difference != 0 {
+        return Err(AuthenticationFailed);
+    }
+    apply_keystream(key, 1, nonce, buffer);
+    Ok(())
+}
+
+fn tag(
+    key: &[u8; KEY_BYTES],
+    nonce: &[u8; NONCE_BYTES],
+    aad: &[u8],
+    ciphertext: &[u8],
+) -> [u8; TAG_BYTES] {
+    let block = chacha20_block(key, 0, nonce);
+    let mut poly = Poly1305::new(block[..32].try_into().expect("one-time key"));
+    poly.padded(aad);
+    poly.padded(ciphertext);
+    let mut lengths = [0u8; 16];
+    lengths[..8].copy_from_slice(&(aad.len() as u64).to_le_bytes());
+    lengths[8..].copy_from_slice(&(ciphertext.len() as u64).to_le_bytes());
+    poly.block(&lengths, true);
+    poly.finalize()
+}
+
+fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
+
+fn chacha20_block(key: &[u8; KEY_BYTES], counter: u32, nonce: &[u8; NONCE_BYTES]) -> [u8; 64] {
+    let mut initial = [0u32; 16];
+    initial[..4].copy_from_slice(&[0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574]);
+    for (word, bytes) in initial[4..12].iter_mut().zip(key.chunks_exact(4)) {
+        *word = u32::from_le_bytes(bytes.try_into().expect("key word"));
+    }
+    initial[12] = counter;
+    for (word, bytes) in initial[13..].iter_mut().zip(nonce.chunks_exact(4)) {
+        *word = u32::from_le_bytes(bytes.try_into().expect("nonce word"));
+    }
+    let mut state = initial;
+    for _ in 0..10 {
+        quarter_round(&mut state, 0, 4, 8, 12);
+        quarter_round(&mut state, 1, 5, 9, 13);
+        quarter_round(&mut state, 2, 6, 10, 14);
+        quarter_round(&mut state, 3, 7, 11, 15);
+        quarter_round(&mut state, 0, 5, 10, 15);
+        quarter_round(&mut state, 1, 6, 11, 12);
+        quarter_round(&mut state, 2, 7, 8, 13);
+        quarter_round(&mut state, 3, 4, 9, 14);
+    }
+    let mut out = [0u8; 64];
+    for ((bytes, word), start) in out.chunks_exact_mut(4).zip(state).zip(initial) {
+        bytes.copy_from_slice(&word.wrapping_add(start).to_le_bytes());
+    }
+    out
+}
+
+fn apply_keystream(
+    key: &[u8; KEY_BYTES],
+    first_counter: u32,
+    nonce: &[u8; NONCE_BYTES],
+    buffer: &mut [u8],
+) {
+    for (index, chunk) in buffer.chunks_mut(64).enumerate() {
+        let block = chacha20_block(key, first_counter.wrapping_add(index as u32), nonce);
+        for (byte, stream) in chunk.iter_mut().zip(block) {
+            *byte ^= stream;
+        }
+    }
+}
+
+/// Poly1305 over 26-bit limbs, so every product fits a u64.
+struct Poly1305 {
+    r: [u32; 5],
+    h: [u32; 5],
+    pad: [u32; 4],
+}
+
+const LIMB: u32 = 0x3ff_ffff;
+
+fn le32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().expect("u32 word"))
+}
+
+impl Poly1305 {
+    fn new(key: &[u8; 32]) -> Self {
+        Self {
+            r: [
+                le32(key, 0) & 0x3ff_ffff,
+                (le32(key, 3) >> 2) & 0x3ff_ff03,
+                (le32(key, 6) >> 4) & 0x3ff_c0ff,
+                (le32(key, 9) >> 6) & 0x3f0_3fff,
+                (le32(key, 12) >> 8) & 0x00f_ffff,
+            ],
+            h: [0; 5],
+            pad: [le32(key, 16), le32(key, 20), le32(key, 24), le32(key, 28)],
+        }
+    }
+
+    /// Absorb `data` zero-padded to a whole number of 16-byte blocks, as the
+    /// AEAD construction does for both the associated data and ciphertext.
+    fn padded(&mut self, data: &[u8]) {
+        for chunk in data.chunks(16) {
+            let mut block = [0u8; 16];
+            block[..chunk.len()].copy_from_slice(chunk);
+            self.block(&block, true);
+        }
+    }
+
+    /// Absorb one block. `full` adds the 2^128 bit every whole block
+    /// carries; only the MAC's own final partial block, which the AEAD never
+    /// produces, would clear it.
+    fn block(&mut self, block: &[u8; 16], full: bool) {
+        let [r0, r1, r2, r3, r4] = self.r.map(u64::from);
+        let (s1, s2, s3, s4) = (r1 * 5, r2 * 5, r3 * 5, r4 * 5);
+        let hibit = if full { 1 << 24 } else { 0 };
+        let h = &mut self.h;
+        h[0] += le32(block, 0) & LIMB;
+        h[1] += (le32(block, 3) >> 2) & LIMB;
+        h[2] += (le32(block, 6) >> 4) & LIMB;
+        h[3] += (le32(block, 9) >> 6) & LIMB;
+        h[4] += (le32(block, 12) >> 8) | hibit;
+        let [h0, h1, h2, h3, h4] = h.map(u64::from);
+
+        let d0 = h0 * r0 + h1 * s4 + h2 * s3 + h3 * s2 + h4 * s1;
+        let mut d1 = h0 * r1 + h1 * r0 + h2 * s4 + h3 * s3 + h4 * s2;
+        let mut d2 = h0 * r2 + h1 * r1 + h2 * r0 + h3 * s4 + h4 * s3;
+        let mut d3 = h0 * r3 + h1 * r2 + h2 * r1 + h3 * r0 + h4 * s4;
+        let mut d4 = h0 * r4 + h1 * r3 + h2 * r2 + h3 * r1 + h4 * r0;
+
+        d1 += d0 >> 26;
+        h[0] = d0 as u32 & LIMB;
+        d2 += d1 >> 26;
+        h[1] = d1 as u32 & LIMB;
+        d3 += d2 >> 26;
+        h[2] = d2 as u32 & LIMB;
+        d4 += d3 >> 26;
+        h[3] = d3 as u32 & LIMB;
+        h[4] = d4 as u32 & LIMB;
+        h[0] += (d4 >> 26) as u32 * 5;
+        h[1] += h[0] >> 26;
+        h[0] &= LIMB;
+    }
+
+    fn finalize(self) -> [u8; TAG_BYTES] {
+        let [mut h0, mut h1, mut h2, mut h3, mut h4] = self.h;
+        h2 += h1 >> 26;
+        h1 &= LIMB;
+        h3 += h2 >> 26;
+        h2 &= LIMB;
+        h4 += h3 >> 26;
+        h3 &= LIMB;
+        h0 += (h4 >> 26) * 5;
+        h4 &= LIMB;
+        h1 += h0 >> 26;
+        h0 &= LIMB;
+
+        // h - p, selected in constant time when it did not go negative.
+        let mut g0 = h0 + 5;
+        let mut g1 = h1 + (g0 >> 26);
+        g0 &= LIMB;
+        let mut g2 = h2 + (g1 >> 26);
+        g1 &= LIMB;
+        let mut g3 = h3 + (g2 >> 26);
+        g2 &= LIMB;
+        let g4 = (h4 + (g3 >> 26)).wrapping_sub(1 << 26);
+        g3 &= LIMB;
+        let keep_g = (g4 >> 31).wrapping_sub(1);
+        let keep_h = !keep_g;
+        h0 = (h0 & keep_h) | (g0 & keep_g);
+        h1 = (h1 & keep_h) | (g1 & keep_g);
+        h2 = (h2 & keep_h) | (g2 & keep_g);
+        h3 = (h3 & keep_h) | (g3 & keep_g);
+        h4 = (h4 & keep_h) | (g4 & keep_g);
+
+        let words = [
+            h0 | (h1 << 26),
+            (h1 >> 6) | (h2 << 20),
+            (h2 >> 12) | (h3 << 14),
+            (h3 >> 18) | (h4 << 8),
+        ];
+        let mut out = [0u8; TAG_BYTES];
+        let mut carry = 0u64;
+        for ((bytes, word), pad) in out.chunks_exact_mut(4).zip(words).zip(self.pad) {
+            let sum = u64::from(word) + u64::from(pad) + carry;
+            bytes.copy_from_slice(&(sum as u32).to_le_bytes());
+            carry = sum >> 32;
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unhex<const N: usize>(text: &str) -> [u8; N] {
+        let mut out = [0u8; N];
+        for (byte, pair) in out.iter_mut().zip(text.as_bytes().chunks_exact(2)) {
+            let digit = |c: u8| (c as char).to_digit(16).expect("hex digit") as u8;
+            *byte = digit(pair[0]) << 4 | digit(pair[1]);
+        }
+        out
+    }
+
+    /// RFC 8439 section 2.3.2: one block of keystream. The block function is
+    /// both the cipher and the source of the MAC key, so it is pinned alone.
+    #[test]
+    fn the_rfc_block_vector_matches() {
+        let key: [u8; 32] = core::array::from_fn(|index| index as u8);
+        let nonce = unhex::<12>("000000090000004a00000000");
+        let expected = unhex::<64>(
+            "10f1e7e4d13b5915500fdd1fa32071c4c7d1f4c733c068030422aa9ac3d46c4e\
+             d2826446079faa0914c2d705d98b02a2b5129cd1de164eb9cbd083e8a2503c4e",
+        );
+        assert_eq!(chacha20_block(&key, 1, &nonce), expected);
+    }
+
+    /// RFC 8439 section 2.5.2: the MAC alone, over a message whose last
+    /// block is partial.
+    #[test]
+    fn the_rfc_poly1305_vector_matches() {
+        let key = unhex::<32>("85d6be7857556d337f4452fe42d506a80103808afb0db2fd4abff6af4149f51b");
+        let message = b"Cryptographic Forum Research Group";
+        let mut poly = Poly1305::new(&key);
+        let mut chunks = message.chunks_exact(16);
+        for chunk in &mut chunks {
+            poly.block(chunk.try_into().expect("whole block"), true);
+        }
+        let mut last = [0u8; 16];
+        let remainder = chunks.remainder();
+        last[..remainder.len()].copy_from_slice(remainder);
+        last[remainder.len()] = 1;
+        poly.block(&last, false);
+        assert_eq!(
+            poly.finalize(),
+            unhex::<16>("a8061dc1305136c6c22b8baf0c0127a9")
+        );
+    }
+
+    /// RFC 8439 section 2.8.2: the whole AEAD, and its inverse.
+    #[test]
+    fn the_rfc_aead_vector_seals_and_opens() {
+        let key: [u8; 32] = core::array::from_fn(|index| 0x80 + index as u8);
+        let nonce = unhex::<12>("070000004041424344454647");
+        let aad = unhex::<12>("50515253c0c1c2c3c4c5c6c7");
+        let plaintext = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+        let mut buffer = *plaintext;
+        let sealed = seal(&key, &nonce, &aad, &mut buffer);
+        assert_eq!(
+            buffer[..16],
+            unhex::<16>("d31a8d34648e60db7b86afbc53ef7ec2")
+        );
+        assert_eq!(buffer[buffer.len() - 2..], unhex::<2>("6116"));
+        assert_eq!(sealed, unhex::<16>("1ae10b594f09e26a7e902ecbd0600691"));
+        open(&key, &nonce, &aad, &mut buffer, &sealed).expect("opens");
+        assert_eq!(buffer, *plaintext);
+    }
+
+    /// A flipped bit anywhere — ciphertext, associated data, or tag — is
+    /// refused, and the refusal leaves the ciphertext undecrypted.
+    #[test]
+    fn any_flipped_bit_is_refused() {
+        let key = [7u8; 32];
+        let nonce = [9u8; 12];
+        let mut sealed_bytes = *b"state object bytes";
+        let sealed = seal(&key, &nonce, b"header", &mut sealed_bytes);
+
+        let mut ciphertext = sealed_bytes;
+        ciphertext[3] ^= 1;
+        let before = ciphertext;
+        assert_eq!(
+            open(&key, &nonce, b"header", &mut ciphertext, &sealed),
+            Err(AuthenticationFailed)
+        );
+        assert_eq!(ciphertext, before);
+
+        let mut buffer = sealed_bytes;
+        assert_eq!(
+            open(&key, &nonce, b"headeR", &mut buffer, &sealed),
+            Err(AuthenticationFailed)
+        );
+        let mut tag = sealed;
+        tag[15] ^= 0x80;
+        assert_eq!(
+            open(&key, &nonce, b"header", &mut buffer, &tag),
+            Err(AuthenticationFailed)
+        );
+        assert_eq!(
+            open(&[8; 32], &nonce, b"header", &mut buffer, &sealed),
+            Err(AuthenticationFailed)
+        );
+    }
+}
@@ -8,8 +8,13 @@ pub const REQUIRED_FLAGS: u64 = 0;
 pub const RELEASE_SEQUENCE_OFFSET: usize = 168;
 pub const CHECKSUM_OFFSET: usize = 176;
 pub const CHECKSUM_END: usize = 208;
+pub const TRUST_ROOT_OFFSET: usize = 208;
+pub const TRUST_ROOT_VERSION_OFFSET: usize = 240;
+pub const TRUST_ROOT_END: usize = 244;
+pub const ROLLBACK_STATE_ROOT_OFFSET: usize = 244;
+pub const ROLLBACK_STATE_ROOT_END: usize = 276;
 pub const BOOTSTORE_MAGIC: [u8; 8] = *b"SLIMEBT\0";
-pub const BOOTSTORE_VERSION: u32 = 1;
+pub const BOOTSTORE_VERSION: u32 = 3;
 pub const BOOTSTORE_HEADER_LEN: usize = 96;
 pub const BOOTSTORE_ENTRY_LEN: usize = 96;
 pub const BOOTSTORE_CAPACITY: usize = 33554432;
@@ -32,3 +37,64 @@ pub const BOOTSTORE_ENTRY_GENERATION_LEN_OFFSET: usize = 40;
 pub const BOOTSTORE_ENTRY_RELEASE_OFFSET_OFFSET: usize = 48;
 pub const BOOTSTORE_ENTRY_RELEASE_LEN_OFFSET: usize = 56;
 pub const BOOTSTORE_ENTRY_PADDING_OFFSET: usize = 64;
+pub const BOOTSTORE_EVIDENCE_OFFSET: usize = 1024;
+pub const BOOTSTORE_VERDICT_OFFSET: usize = 1536;
+pub const BOOTSTORE_SEALED_OFFSET: usize = 2048;
+pub const SHADOW_EVIDENCE_MAGIC: [u8; 8] = *b"SLIMESE\0";
+pub const SHADOW_EVIDENCE_VERSION: u32 = 1;
+pub const SHADOW_EVIDENCE_MAGIC_OFFSET: usize = 0;
+pub const SHADOW_EVIDENCE_FORMAT_VERSION_OFFSET: usize = 8;
+pub const SHADOW_EVIDENCE_HEADER_SIZE_OFFSET: usize = 12;
+pub const SHADOW_EVIDENCE_OUTCOME_OFFSET: usize = 16;
+pub const SHADOW_EVIDENCE_UNVERIFIED_OFFSET: usize = 20;
+pub const SHADOW_EVIDENCE_CANDIDATE_OFFSET: usize = 24;
+pub const SHADOW_EVIDENCE_RUNNING_OFFSET: usize = 56;
+pub const SHADOW_EVIDENCE_RELEASE_SEQUENCE_OFFSET: usize = 88;
+pub const SHADOW_EVIDENCE_REQUIRED_OFFSET: usize = 96;
+pub const SHADOW_EVIDENCE_EXERCISED_OFFSET: usize = 100;
+pub const SHADOW_EVIDENCE_PARKED_OFFSET: usize = 104;
+pub const SHADOW_EVIDENCE_COMPLETED_OFFSET: usize = 108;
+pub const SHADOW_EVIDENCE_FAILED_OFFSET: usize = 112;
+pub const SHADOW_EVIDENCE_WITHHELD_OFFSET: usize = 116;
+pub const SHADOW_EVIDENCE_BUDGET_BYTES_OFFSET: usize = 120;
+pub const SHADOW_EVIDENCE_USED_BYTES_OFFSET: usize = 128;
+pub const SHADOW_EVIDENCE_CHECKSUM_OFFSET: usize = 136;
+pub const SHADOW_EVIDENCE_CHECKSUM_END: usize = 168;
+pub const SHADOW_EVIDENCE_PADDING_OFFSET: usize = 168;
+pub const HEALTH_VERDICT_MAGIC: [u8; 8] = *b"SLIMEHV\0";
+pub const HEALTH_VERDICT_VERSION: u32 = 1;
+pub const HEALTH_VERDICT_MAGIC_OFFSET: usize = 0;
+pub const HEALTH_VERDICT_FORMAT_VERSION_OFFSET: usize = 8;
+pub const HEALTH_VERDICT_HEADER_SIZE_OFFSET: usize = 12;
+pub const HEALTH_VERDICT_OUTCOME_OFFSET: usize = 16;
+pub const HEALTH_VERDICT_PROBE_COUNT_OFFSET: usize = 20;
+pub const HEALTH_VERDICT_GENERATION_OFFSET: usize = 24;
+pub const HEALTH_VERDICT_RELEASE_SEQUENCE_OFFSET: usize = 56;
+pub const HEALTH_VERDICT_TICKS_OFFSET: usize = 64;
+pub const HEALTH_VERDICT_PASSED_OFFSET: usize = 72;
+pub const HEALTH_VERDICT_FAILED_OFFSET: usize = 76;
+pub const HEALTH_VERDICT_FAILED_INSTANCE_OFFSET: usize = 80;
+pub const HEALTH_VERDICT_FAILED_PROBE_OFFSET: usize = 84;
+pub const HEALTH_VERDICT_CHECKSUM_OFFSET: usize = 88;
+pub const HEALTH_VERDICT_CHECKSUM_END: usize = 120;
+pub const HEALTH_VERDICT_PADDING_OFFSET: usize = 120;
+pub const STATE_JOURNAL_MAGIC: [u8; 8] = *b"SLIMESJ\0";
+pub const STATE_JOURNAL_VERSION: u32 = 1;
+pub const STATE_JOURNAL_ENTRY_BYTES: usize = 512;
+pub const STATE_JOURNAL_SECTORS: usize = 64;
+pub const STATE_JOURNAL_MAGIC_OFFSET: usize = 0;
+pub const STATE_JOURNAL_FORMAT_VERSION_OFFSET: usize = 8;
+pub const STATE_JOURNAL_HEADER_SIZE_OFFSET: usize = 12;
+pub const STATE_JOURNAL_ACTION_OFFSET: usize = 16;
+pub const STATE_JOURNAL_POLICY_OFFSET: usize = 20;
+pub const STATE_JOURNAL_SEQUENCE_OFFSET: usize = 24;
+pub const STATE_JOURNAL_BOOT_SEQUENCE_OFFSET: usize = 32;
+pub const STATE_JOURNAL_SCHEMA_VERSION_OFFSET: usize = 40;
+pub const STATE_JOURNAL_RESERVED_OFFSET: usize = 44;
+pub const STATE_JOURNAL_GENERATION_OFFSET: usize = 48;
+pub const STATE_JOURNAL_BINDING_OFFSET: usize = 80;
+pub const STATE_JOURNAL_OBJECT_OFFSET: usize = 112;
+pub const STATE_JOURNAL_STATE_ROOT_OFFSET: usize = 144;
+pub const STATE_JOURNAL_CHECKSUM_OFFSET: usize = 176;
+pub const STATE_JOURNAL_CHECKSUM_END: usize = 208;
+pub const STATE_JOURNAL_PADDING_OFFSET: usize = 208;
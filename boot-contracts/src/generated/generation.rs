@@ -1,7 +1,7 @@
-// @generated by contracts/generation/v5/gen_rust.zt; do not edit.
-// Source contract: contracts/generation/v5/schema.zt
+// @generated by contracts/generation/v6/gen_rust.zt; do not edit.
+// Source contract: contracts/generation/v6/schema.zt
 
-pub const FORMAT_VERSION: u32 = 5;
+pub const FORMAT_VERSION: u32 = 6;
 pub const HEADER_LEN: usize = 512;
 pub const OBJECT_LEN: usize = 64;
 pub const EXECUTABLE_LEN: usize = 32;
@@ -10,7 +10,7 @@ pub const DEPENDENCY_LEN: usize = 4;
 pub const BINDING_LEN: usize = 8;
 pub const GRANT_LEN: usize = 32;
 pub const STATE_LEN: usize = 24;
-pub const HEALTH_LEN: usize = 4;
+pub const HEALTH_LEN: usize = 48;
 pub const PROCESS_LEN: usize = 32;
 pub const THREAD_LEN: usize = 48;
 pub const KERNEL_OBJECT_LEN: usize = 40;
@@ -58,6 +58,10 @@ pub const CAPABILITY_INPUT: u32 = 6;
 pub const CAPABILITY_SUPERVISION: u32 = 7;
 pub const CAPABILITY_SHARED_BUFFER: u32 = 8;
 pub const CAPABILITY_LOAN: u32 = 9;
+pub const CAPABILITY_SCHEDULING_CONTROL: u32 = 10;
+pub const CAPABILITY_MONOTONIC_CLOCK: u32 = 11;
+pub const CAPABILITY_WALL_CLOCK: u32 = 12;
+pub const CAPABILITY_SIMULATED_CLOCK: u32 = 13;
 pub const SERVICE_LIFECYCLE: u32 = 1;
 pub const SERVICE_SPAWN: u32 = 2;
 pub const SERVICE_SUPERVISION: u32 = 3;
@@ -92,7 +96,14 @@ pub const RIGHT_DIRECTORY_DERIVE: u64 = 4194304;
 pub const RIGHT_INPUT_READ: u64 = 8388608;
 pub const RIGHT_BUFFER_CREATE: u64 = 16777216;
 pub const RIGHT_BUFFER_LOAN: u64 = 33554432;
-pub const RIGHT_ALL: u64 = 66977791;
+pub const RIGHT_SCHEDULE: u64 = 67108864;
+pub const RIGHT_CLOCK_READ: u64 = 134217728;
+pub const RIGHT_CLOCK_ADVANCE: u64 = 268435456;
+pub const RIGHT_ALL: u64 = 536739839;
+pub const HEALTH_PROBE_RUNNING: u32 = 1;
+pub const HEALTH_PROBE_NO_FAULT: u32 = 2;
+pub const HEALTH_PROBE_ROUTE: u32 = 4;
+pub const HEALTH_PROBE_ALL: u32 = 7;
 
 /// The rights bit a generation manifest spells `name`, or `None`.
 ///
@@ -127,6 +138,9 @@ pub fn right_named(name: &str) -> Option<u64> {
         "inputRead" => RIGHT_INPUT_READ,
         "bufferCreate" => RIGHT_BUFFER_CREATE,
         "bufferLoan" => RIGHT_BUFFER_LOAN,
+        "schedule" => RIGHT_SCHEDULE,
+        "clockRead" => RIGHT_CLOCK_READ,
+        "clockAdvance" => RIGHT_CLOCK_ADVANCE,
         _ => return None,
     })
 }
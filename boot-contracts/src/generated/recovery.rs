@@ -1,7 +1,7 @@
 // @generated by contracts/recovery/v1/gen_rust.zt; do not edit.
 // Source contract: contracts/recovery/v1/schema.zt
 
-pub const FORMAT_VERSION: u32 = 1;
-pub const HEADER_BYTES: usize = 160;
+pub const FORMAT_VERSION: u32 = 2;
+pub const HEADER_BYTES: usize = 200;
 pub const STATE_ENTRY_BYTES: usize = 72;
 pub const MAX_STATE_OBJECTS: usize = 32;
@@ -1,18 +1,25 @@
 // @generated by contracts/release/v1/gen_rust.zt; do not edit.
 // Source contract: contracts/release/v1/schema.zt
 
-pub const RELEASE_VERSION: u32 = 2;
+pub const RELEASE_VERSION: u32 = 3;
 pub const RELEASE_VERSION_V1: u32 = 1;
-pub const RELEASE_BYTES: usize = 512;
-pub const RELEASE_HEADER_BYTES: usize = 208;
+pub const RELEASE_VERSION_V2: u32 = 2;
+pub const RELEASE_BYTES: usize = 1024;
+pub const RELEASE_HEADER_BYTES: usize = 240;
 pub const RELEASE_SIGNATURE_BYTES: usize = 96;
 pub const MAX_RELEASE_SIGNATURES: usize = 3;
 pub const MAX_TARGET_BYTES: usize = 32;
+pub const RELEASE_FLAG_AUTHORITY_WIDENING: u64 = 1;
 pub const ROTATION_MAGIC: [u8; 8] = *b"SLIMERT\0";
-pub const ROTATION_VERSION: u32 = 1;
+pub const ROTATION_VERSION: u32 = 2;
 pub const ROTATION_BYTES: usize = 1024;
 pub const ROTATION_HEADER_BYTES: usize = 64;
 pub const MAX_TRUST_KEYS: usize = 4;
+pub const MAX_REVOKED_KEYS: usize = 4;
+pub const TRUST_ROOT_MAGIC: [u8; 8] = *b"SLIMETRT";
+pub const TRUST_ROOT_RECORD_VERSION: u32 = 1;
+pub const TRUST_ROOT_RECORD_BYTES: usize = 512;
+pub const TRUST_ROOT_HEADER_BYTES: usize = 48;
 pub const RELEASE_HEADER_MAGIC_OFFSET: usize = 0;
 pub const RELEASE_HEADER_FORMAT_VERSION_OFFSET: usize = 8;
 pub const RELEASE_HEADER_HEADER_SIZE_OFFSET: usize = 12;
@@ -25,8 +32,9 @@ pub const RELEASE_HEADER_TRUST_ROOT_VERSION_OFFSET: usize = 100;
 pub const RELEASE_HEADER_TARGET_OFFSET: usize = 104;
 pub const RELEASE_HEADER_BOOT_BUNDLE_IDENTITY_OFFSET: usize = 136;
 pub const RELEASE_HEADER_AUTHORITY_MANIFEST_OFFSET: usize = 168;
-pub const RELEASE_HEADER_SIGNATURE_COUNT_OFFSET: usize = 200;
-pub const RELEASE_HEADER_RESERVED_OFFSET: usize = 204;
+pub const RELEASE_HEADER_APPROVED_WIDENINGS_OFFSET: usize = 200;
+pub const RELEASE_HEADER_SIGNATURE_COUNT_OFFSET: usize = 232;
+pub const RELEASE_HEADER_RESERVED_OFFSET: usize = 236;
 pub const RELEASE_SIGNATURE_KEY_ID_OFFSET: usize = 0;
 pub const RELEASE_SIGNATURE_SIGNATURE_OFFSET: usize = 32;
 pub const SIGN_NAMESPACE: &[u8] = b"slime-release";
@@ -40,4 +48,14 @@ pub const ROTATION_HEADER_REPLACEMENT_THRESHOLD_OFFSET: usize = 32;
 pub const ROTATION_HEADER_REPLACEMENT_KEY_COUNT_OFFSET: usize = 36;
 pub const ROTATION_HEADER_PREVIOUS_SIGNATURE_COUNT_OFFSET: usize = 40;
 pub const ROTATION_HEADER_REPLACEMENT_SIGNATURE_COUNT_OFFSET: usize = 44;
-pub const ROTATION_HEADER_RESERVED_OFFSET: usize = 48;
+pub const ROTATION_HEADER_REVOKED_COUNT_OFFSET: usize = 48;
+pub const ROTATION_HEADER_RESERVED_OFFSET: usize = 52;
+pub const TRUST_ROOT_HEADER_MAGIC_OFFSET: usize = 0;
+pub const TRUST_ROOT_HEADER_FORMAT_VERSION_OFFSET: usize = 8;
+pub const TRUST_ROOT_HEADER_HEADER_SIZE_OFFSET: usize = 12;
+pub const TRUST_ROOT_HEADER_REQUIRED_FLAGS_OFFSET: usize = 16;
+pub const TRUST_ROOT_HEADER_VERSION_OFFSET: usize = 24;
+pub const TRUST_ROOT_HEADER_THRESHOLD_OFFSET: usize = 28;
+pub const TRUST_ROOT_HEADER_KEY_COUNT_OFFSET: usize = 32;
+pub const TRUST_ROOT_HEADER_REVOKED_COUNT_OFFSET: usize = 36;
+pub const TRUST_ROOT_HEADER_RESERVED_OFFSET: usize = 40;
@@ -1,17 +1,35 @@
-// @generated by contracts/store/disk/v1/gen_rust.zt; do not edit.
-// Source contract: contracts/store/disk/v1/schema.zt
+// @generated by contracts/store/disk/v2/gen_rust.zt; do not edit.
+// Source contract: contracts/store/disk/v2/schema.zt
 
 pub const SUPERBLOCK_MAGIC: [u8; 8] = *b"SLIMESB\0";
 pub const RECORD_MAGIC: [u8; 8] = *b"SLIMEOR\0";
-pub const FORMAT_VERSION: u32 = 1;
+pub const INDEX_MAGIC: [u8; 8] = *b"SLIMEIX\0";
+pub const MANIFEST_MAGIC: [u8; 8] = *b"SLIMEMF\0";
+pub const FORMAT_VERSION: u32 = 2;
 pub const SUPERBLOCK_HEADER: usize = 64;
-pub const RECORD_HEADER: usize = 64;
-pub const RECORD_AREA_START: u64 = 2;
+pub const RECORD_HEADER: usize = 128;
+pub const RECORD_AREA_START: u64 = 18;
 pub const SLOT_A_LBA: u64 = 0;
 pub const SLOT_B_LBA: u64 = 1;
+pub const INDEX_A_LBA: u64 = 2;
+pub const INDEX_B_LBA: u64 = 10;
+pub const INDEX_SECTORS: u64 = 8;
+pub const INDEX_HEADER: usize = 64;
+pub const INDEX_ENTRY: usize = 48;
+pub const V1_FORMAT_VERSION: u32 = 1;
+pub const V1_RECORD_HEADER: usize = 64;
+pub const V1_RECORD_AREA_START: u64 = 2;
 pub const MAX_OBJECTS: usize = 64;
 pub const MAX_OBJECT_PAYLOAD: usize = 32768;
 pub const SECTOR_BYTES: usize = 512;
+pub const TYPE_METADATA_BYTES: usize = 20;
+pub const MANIFEST_OBJECT_TYPE: u32 = 1280265542;
+pub const CHUNK_OBJECT_TYPE: u32 = 1280262984;
+pub const MANIFEST_HEADER: usize = 64;
+pub const MANIFEST_FORMAT_VERSION: u32 = 1;
+pub const SUPERBLOCK_FLAG_ENCRYPTED: u32 = 1;
+pub const RECORD_FLAG_ENCRYPTED: u32 = 1;
+pub const AUTH_TAG_BYTES: usize = 16;
 pub const SUPERBLOCK_MAGIC_OFFSET: usize = 0;
 pub const SUPERBLOCK_FORMAT_VERSION_OFFSET: usize = 8;
 pub const SUPERBLOCK_HEADER_SIZE_OFFSET: usize = 12;
@@ -21,7 +39,7 @@ pub const SUPERBLOCK_OBJECT_COUNT_OFFSET: usize = 32;
 pub const SUPERBLOCK_FLAGS_OFFSET: usize = 36;
 pub const SUPERBLOCK_RECORD_AREA_START_OFFSET: usize = 40;
 pub const SUPERBLOCK_PARTITION_SECTORS_OFFSET: usize = 48;
-pub const SUPERBLOCK_RESERVED_OFFSET: usize = 56;
+pub const SUPERBLOCK_INDEX_CRC32_OFFSET: usize = 56;
 pub const SUPERBLOCK_CRC32_OFFSET: usize = 60;
 pub const RECORD_MAGIC_OFFSET: usize = 0;
 pub const RECORD_FORMAT_VERSION_OFFSET: usize = 8;
@@ -30,3 +48,25 @@ pub const RECORD_OBJ_TYPE_OFFSET: usize = 16;
 pub const RECORD_FLAGS_OFFSET: usize = 20;
 pub const RECORD_PAYLOAD_LEN_OFFSET: usize = 24;
 pub const RECORD_CONTENT_HASH_OFFSET: usize = 32;
+pub const RECORD_CREATION_SEQUENCE_OFFSET: usize = 64;
+pub const RECORD_GENERATION_OFFSET: usize = 72;
+pub const RECORD_TYPE_METADATA_OFFSET: usize = 104;
+pub const RECORD_HEADER_CRC32_OFFSET: usize = 124;
+pub const INDEX_MAGIC_OFFSET: usize = 0;
+pub const INDEX_FORMAT_VERSION_OFFSET: usize = 8;
+pub const INDEX_HEADER_SIZE_OFFSET: usize = 12;
+pub const INDEX_SEQUENCE_OFFSET: usize = 16;
+pub const INDEX_ENTRY_COUNT_OFFSET: usize = 24;
+pub const INDEX_ENTRY_SIZE_OFFSET: usize = 28;
+pub const INDEX_RESERVED_OFFSET: usize = 32;
+pub const INDEX_ENTRY_CONTENT_HASH_OFFSET: usize = 0;
+pub const INDEX_ENTRY_OBJ_TYPE_OFFSET: usize = 32;
+pub const INDEX_ENTRY_PAYLOAD_LEN_OFFSET: usize = 36;
+pub const INDEX_ENTRY_LBA_OFFSET: usize = 40;
+pub const MANIFEST_MAGIC_OFFSET: usize = 0;
+pub const MANIFEST_FORMAT_VERSION_OFFSET: usize = 8;
+pub const MANIFEST_CHUNK_COUNT_OFFSET: usize = 12;
+pub const MANIFEST_OBJ_TYPE_OFFSET: usize = 16;
+pub const MANIFEST_RESERVED_OFFSET: usize = 20;
+pub const MANIFEST_TOTAL_LEN_OFFSET: usize = 24;
+pub const MANIFEST_ROOT_HASH_OFFSET: usize = 32;
@@ -47,3 +47,14 @@ pub const STATE_SCHEMA_VERSION_OFFSET: usize = 64;
 pub const STATE_POLICY_OFFSET: usize = 68;
 pub const STATE_FLAGS_OFFSET: usize = 72;
 pub const STATE_PADDING_OFFSET: usize = 76;
+pub const HOLDINGS_MAGIC: [u8; 8] = *b"SLIMEHLD";
+pub const HOLDINGS_HEADER_LEN: usize = 64;
+pub const MAX_HELD_DIGESTS: usize = 64;
+pub const HOLDINGS_MAGIC_OFFSET: usize = 0;
+pub const HOLDINGS_FORMAT_VERSION_OFFSET: usize = 8;
+pub const HOLDINGS_HEADER_SIZE_OFFSET: usize = 12;
+pub const HOLDINGS_REQUIRED_FLAGS_OFFSET: usize = 16;
+pub const HOLDINGS_COUNT_OFFSET: usize = 24;
+pub const HOLDINGS_RESERVED_OFFSET: usize = 28;
+pub const HOLDINGS_HASH_OFFSET: usize = 32;
+pub const HOLDINGS_HASH_END: usize = 64;
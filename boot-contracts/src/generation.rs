@@ -1,11 +1,14 @@
 use crate::sha256::Sha256;
 use crate::shared_buffer_budget::{self, SharedBufferBudget};
 
+pub mod retired;
+
+pub const MAGIC_V6: [u8; 8] = *b"SLIMEG6\0";
 pub const MAGIC_V5: [u8; 8] = *b"SLIMEG5\0";
 pub const MAGIC_V4: [u8; 8] = *b"SLIMEG4\0";
 pub const MAGIC_V3: [u8; 8] = *b"SLIMEG3\0";
 pub const MAGIC_V2: [u8; 8] = *b"SLIMEG2\0";
-pub const MAGIC: [u8; 8] = MAGIC_V5;
+pub const MAGIC: [u8; 8] = MAGIC_V6;
 include!("generated/generation.rs");
 
 const MAX_TASK_CAPS: usize = 128;
@@ -31,6 +34,13 @@ const KERNEL_OBJECT_NOTIFICATION: u32 = 7;
 const ROOT_SERVICE_SLOT: usize = 1;
 const CONSOLE_SERVICE_SLOT: usize = 32;
 const SERVICE_SEND_RIGHT: Rights = 1;
+/// The granule a process quota's `dynamic_reserve_bytes` is counted in: the
+/// heap pages the root maps one at a time, so a reserve that is not a whole
+/// number of them declares a page nothing can back.
+const HEAP_PAGE_BYTES: u64 = 4096;
+/// v5's wire version and its health record: the instance index alone.
+const V5_FORMAT_VERSION: u32 = 5;
+const V5_HEALTH_LEN: usize = 4;
 
 fn service_for_capability(kind: CapabilityKind) -> Option<u32> {
     match kind {
@@ -40,7 +50,16 @@ fn service_for_capability(kind: CapabilityKind) -> Option<u32> {
         CapabilityKind::Directory => Some(SERVICE_DIRECTORY),
         CapabilityKind::Input => Some(SERVICE_INPUT),
         CapabilityKind::Block => Some(SERVICE_BLOCK),
-        CapabilityKind::Supervision => Some(SERVICE_SUPERVISION),
+        // Scheduling control is exercised on a supervision handle, so its
+        // operations arrive on the supervision service.
+        CapabilityKind::Supervision | CapabilityKind::SchedulingControl => {
+            Some(SERVICE_SUPERVISION)
+        }
+        // Every instance holds the lifecycle service, so the clock grant, not
+        // the endpoint, is what decides whether a component observes time.
+        CapabilityKind::MonotonicClock
+        | CapabilityKind::WallClock
+        | CapabilityKind::SimulatedClock => Some(SERVICE_LIFECYCLE),
         CapabilityKind::Endpoint | CapabilityKind::Executable => None,
     }
 }
@@ -63,6 +82,10 @@ pub enum CapabilityKind {
     Supervision = CAPABILITY_SUPERVISION,
     SharedBuffer = CAPABILITY_SHARED_BUFFER,
     Loan = CAPABILITY_LOAN,
+    SchedulingControl = CAPABILITY_SCHEDULING_CONTROL,
+    MonotonicClock = CAPABILITY_MONOTONIC_CLOCK,
+    WallClock = CAPABILITY_WALL_CLOCK,
+    SimulatedClock = CAPABILITY_SIMULATED_CLOCK,
 }
 
 impl CapabilityKind {
@@ -77,6 +100,10 @@ impl CapabilityKind {
             CAPABILITY_SUPERVISION => Ok(Self::Supervision),
             CAPABILITY_SHARED_BUFFER => Ok(Self::SharedBuffer),
             CAPABILITY_LOAN => Ok(Self::Loan),
+            CAPABILITY_SCHEDULING_CONTROL => Ok(Self::SchedulingControl),
+            CAPABILITY_MONOTONIC_CLOCK => Ok(Self::MonotonicClock),
+            CAPABILITY_WALL_CLOCK => Ok(Self::WallClock),
+            CAPABILITY_SIMULATED_CLOCK => Ok(Self::SimulatedClock),
             _ => Err(DecodeError::BadBounds),
         }
     }
@@ -101,6 +128,9 @@ fn capability_rights_valid(kind: CapabilityKind, rights: Rights) -> bool {
             RIGHT_BUFFER_WRITE | RIGHT_BUFFER_MAP | RIGHT_BUFFER_LOAN | RIGHT_TRANSFER
         }
         CapabilityKind::Loan => RIGHT_BUFFER_WRITE | RIGHT_BUFFER_MAP | RIGHT_TRANSFER,
+        CapabilityKind::SchedulingControl => RIGHT_SCHEDULE,
+        CapabilityKind::MonotonicClock | CapabilityKind::WallClock => RIGHT_CLOCK_READ,
+        CapabilityKind::SimulatedClock => RIGHT_CLOCK_READ | RIGHT_CLOCK_ADVANCE,
     };
     let required = match kind {
         CapabilityKind::Endpoint => RIGHT_SEND | RIGHT_RECV,
@@ -117,6 +147,12 @@ fn capability_rights_valid(kind: CapabilityKind, rights: Rights) -> bool {
         CapabilityKind::Supervision => RIGHT_SUPERVISE,
         CapabilityKind::SharedBuffer => RIGHT_BUFFER_WRITE | RIGHT_BUFFER_MAP | RIGHT_BUFFER_LOAN,
         CapabilityKind::Loan => RIGHT_BUFFER_MAP,
+        CapabilityKind::SchedulingControl => RIGHT_SCHEDULE,
+        // A clock that may be advanced must also be readable: a driver that
+        // moves time without seeing it cannot know where it moved it to.
+        CapabilityKind::MonotonicClock
+        | CapabilityKind::WallClock
+        | CapabilityKind::SimulatedClock => RIGHT_CLOCK_READ,
     };
     rights != 0
         && rights & !allowed == 0
@@ -124,11 +160,12 @@ fn capability_rights_valid(kind: CapabilityKind, rights: Rights) -> bool {
         && (kind != CapabilityKind::Executable
             || rights & (RIGHT_EXEC | RIGHT_SPAWN) == RIGHT_EXEC | RIGHT_SPAWN)
         && (kind != CapabilityKind::Input || rights == RIGHT_INPUT_READ)
+        && (kind != CapabilityKind::SchedulingControl || rights == RIGHT_SCHEDULE)
 }
 pub const KIND_RESOURCE: u32 = 4;
 pub const ROLE_INIT: u32 = 1;
 /// Rights are a bitmask over the vocabulary declared in
-/// `contracts/generation/v5/schema.zt` and generated into `generated/generation.rs`,
+/// `contracts/generation/v6/schema.zt` and generated into `generated/generation.rs`,
 /// which `include!` brings into this module. `RIGHT_ALL` is the union of those
 /// named bits, so an undefined position such as bit 17 is refused rather than
 /// admitted by a bit-width mask (B57).
@@ -182,6 +219,12 @@ pub enum BootAction {
     /// the component graph *and* runs the bounded data path, rather than
     /// asserting the two across separate plane fixtures.
     Demo = 29,
+    /// B48's MCS half: a saturating component held to its reservation beside
+    /// a reserved control loop. Built only for the MCS kernel profile.
+    Isolation = 30,
+    /// One grant of each clock kind to a probe, so the root's clock
+    /// operations are answered on a boot.
+    Clock = 31,
 }
 
 impl BootAction {
@@ -233,6 +276,8 @@ impl BootAction {
         Self::Matrix,
         Self::Traffic,
         Self::Demo,
+        Self::Isolation,
+        Self::Clock,
     ];
 
     /// The composition a wire id names, or `None` for an id this build does not
@@ -275,6 +320,8 @@ impl BootAction {
                 Self::Matrix => Self::Matrix.id(),
                 Self::Traffic => Self::Traffic.id(),
                 Self::Demo => Self::Demo.id(),
+                Self::Isolation => Self::Isolation.id(),
+                Self::Clock => Self::Clock.id(),
             };
             declared == id
         })
@@ -311,6 +358,8 @@ impl BootAction {
             "matrix" => Self::Matrix,
             "traffic" => Self::Traffic,
             "demo" => Self::Demo,
+            "isolation" => Self::Isolation,
+            "clock" => Self::Clock,
             _ => return None,
         })
     }
@@ -372,6 +421,31 @@ pub enum InstanceHealth {
     Required,
 }
 
+/// What healthy means for one required instance: the `HEALTH_PROBE_*` bits
+/// its health record declares, and the operand each one needs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthProbe {
+    pub instance: usize,
+    pub probes: u32,
+    /// Service ticks the instance must run without faulting. Nonzero exactly
+    /// when `HEALTH_PROBE_NO_FAULT` is declared.
+    pub fault_window_ticks: u32,
+    /// The identity of the call route the instance must answer, present
+    /// exactly when `HEALTH_PROBE_ROUTE` is declared.
+    pub route: Option<[u8; 32]>,
+}
+
+impl HealthProbe {
+    pub const fn declares(&self, probe: u32) -> bool {
+        self.probes & probe != 0
+    }
+
+    /// How many probes this record declares.
+    pub const fn count(&self) -> u32 {
+        self.probes.count_ones()
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Instance<'a> {
     pub name: &'a str,
@@ -425,6 +499,16 @@ pub struct StateBinding<'a> {
     pub owner: usize,
     pub schema_version: u32,
     pub policy: u32,
+    pub migration: Option<StateMigration>,
+}
+
+/// The executable that translates a binding's state from one older schema
+/// version to the one the binding declares. A binding declares at most one:
+/// state two versions behind needs the generation between them staged first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateMigration {
+    pub from_version: u32,
+    pub executable: usize,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -621,6 +705,7 @@ pub struct NotificationBinding {
     pub flags: u32,
 }
 
+#[derive(Clone, Copy)]
 pub struct Generation<'a> {
     bytes: &'a [u8],
     pub version: u32,
@@ -675,23 +760,44 @@ pub struct Generation<'a> {
     notification_binding_offset: usize,
     string_offset: usize,
     string_len: usize,
+    health_len: usize,
 }
 
 impl<'a> Generation<'a> {
     pub fn decode(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        Self::decode_format(bytes, MAGIC_V6, FORMAT_VERSION)
+    }
+
+    /// Decodes a v5 generation, so a diff can read the one a v6 release
+    /// replaced.
+    ///
+    /// Nothing boots the result: [`Self::is_v6`] answers false, and every
+    /// boot path asks it. v5's layouts are v6's but for the health record,
+    /// which named only its instance and required it running, so that is the
+    /// one probe [`Self::health_probe`] reports. The state record's migration
+    /// words were reserved in v5, so they read as no migration.
+    pub fn decode_v5(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        Self::decode_format(bytes, MAGIC_V5, V5_FORMAT_VERSION)
+    }
+
+    fn decode_format(
+        bytes: &'a [u8],
+        expected_magic: [u8; 8],
+        expected_version: u32,
+    ) -> Result<Self, DecodeError> {
         if bytes.len() < HEADER_LEN {
             return Err(DecodeError::Truncated);
         }
         let magic: [u8; 8] = bytes[..8].try_into().unwrap();
         let version = u32_at(bytes, 8)?;
-        if magic != MAGIC_V5 {
-            return if matches!(magic, MAGIC_V4 | MAGIC_V3 | MAGIC_V2) {
+        if magic != expected_magic {
+            return if matches!(magic, MAGIC_V6 | MAGIC_V5 | MAGIC_V4 | MAGIC_V3 | MAGIC_V2) {
                 Err(DecodeError::UnsupportedVersion)
             } else {
                 Err(DecodeError::BadMagic)
             };
         }
-        if version != FORMAT_VERSION {
+        if version != expected_version {
             return Err(DecodeError::UnsupportedVersion);
         }
         if u32_at(bytes, 12)? as usize != HEADER_LEN {
@@ -795,6 +901,11 @@ impl<'a> Generation<'a> {
             notification_binding_offset: u64_at(bytes, 360)? as usize,
             string_offset: u64_at(bytes, 368)? as usize,
             string_len: u64_at(bytes, 376)? as usize,
+            health_len: if version == V5_FORMAT_VERSION {
+                V5_HEALTH_LEN
+            } else {
+                HEALTH_LEN
+            },
         };
         if generation.string_len > MAX_STRING_TABLE_BYTES {
             return Err(DecodeError::BadBounds);
@@ -858,7 +969,7 @@ impl<'a> Generation<'a> {
         check_section(
             self.health_offset,
             self.health_count,
-            HEALTH_LEN,
+            self.health_len,
             self.process_offset,
         )?;
         check_section(
@@ -948,7 +1059,7 @@ impl<'a> Generation<'a> {
         Ok(())
     }
 
-    pub const fn is_v5(&self) -> bool {
+    pub const fn is_v6(&self) -> bool {
         self.version == FORMAT_VERSION
     }
     pub const fn object_count(&self) -> usize {
@@ -1193,19 +1304,71 @@ impl<'a> Generation<'a> {
             return Err(DecodeError::BadIndex);
         }
         let offset = self.state_offset + index * STATE_LEN;
-        reserved_zero(self.bytes, offset + 16, offset + STATE_LEN)?;
+        let migrate_from = u32_at(self.bytes, offset + 16)?;
+        let migrator = u32_at(self.bytes, offset + 20)? as usize;
+        // No migration is both fields zero, as they were when these bytes
+        // were reserved.
+        let migration = match (migrate_from, migrator) {
+            (0, 0) => None,
+            (0, _) => return Err(DecodeError::BadState),
+            (from_version, executable) => Some(StateMigration {
+                from_version,
+                executable,
+            }),
+        };
         Ok(StateBinding {
             name: self.string(u32_at(self.bytes, offset)? as usize)?,
             owner: u32_at(self.bytes, offset + 4)? as usize,
             schema_version: u32_at(self.bytes, offset + 8)?,
             policy: u32_at(self.bytes, offset + 12)?,
+            migration,
         })
     }
     pub fn health_instance(&self, index: usize) -> Result<Instance<'a>, DecodeError> {
+        self.instance(self.health_probe(index)?.instance)
+    }
+
+    /// The probes the `index`th health record declares.
+    ///
+    /// Shape only: the bits are known, a window comes with `NO_FAULT` and a
+    /// route with `ROUTE`, and neither comes without. Whether the route names
+    /// a call route the instance serves is a question about the fabric graph,
+    /// which the root's admission answers.
+    pub fn health_probe(&self, index: usize) -> Result<HealthProbe, DecodeError> {
         if index >= self.health_count {
             return Err(DecodeError::BadIndex);
         }
-        self.instance(u32_at(self.bytes, self.health_offset + index * HEALTH_LEN)? as usize)
+        let offset = self.health_offset + index * self.health_len;
+        if self.health_len == V5_HEALTH_LEN {
+            return Ok(HealthProbe {
+                instance: u32_at(self.bytes, offset)? as usize,
+                probes: HEALTH_PROBE_RUNNING,
+                fault_window_ticks: 0,
+                route: None,
+            });
+        }
+        let probes = u32_at(self.bytes, offset + 4)?;
+        let fault_window_ticks = u32_at(self.bytes, offset + 8)?;
+        reserved_zero(self.bytes, offset + 12, offset + 16)?;
+        let route: [u8; 32] = self
+            .bytes
+            .get(offset + 16..offset + HEALTH_LEN)
+            .ok_or(DecodeError::Truncated)?
+            .try_into()
+            .unwrap();
+        if probes == 0
+            || probes & !HEALTH_PROBE_ALL != 0
+            || (probes & HEALTH_PROBE_NO_FAULT != 0) != (fault_window_ticks != 0)
+            || (probes & HEALTH_PROBE_ROUTE != 0) != (route != [0; 32])
+        {
+            return Err(DecodeError::BadHealth);
+        }
+        Ok(HealthProbe {
+            instance: u32_at(self.bytes, offset)? as usize,
+            probes,
+            fault_window_ticks,
+            route: (probes & HEALTH_PROBE_ROUTE != 0).then_some(route),
+        })
     }
 
     pub fn process(&self, index: usize) -> Result<Process<'a>, DecodeError> {
@@ -1308,6 +1471,30 @@ impl<'a> Generation<'a> {
         Ok(None)
     }
 
+    /// The highest priority an instance's main thread may assign to another
+    /// thread: its schedule's `max_controlled_priority`.
+    ///
+    /// The ceiling a `SchedulingControl` capability is exercised under. Read
+    /// from the main thread's record for the reason [`Self::instance_priority`]
+    /// is, and, like it, `None` when no process claims the instance. Admission
+    /// has already refused a record whose priority exceeds it.
+    pub fn instance_max_controlled_priority(
+        &self,
+        instance: usize,
+    ) -> Result<Option<u32>, DecodeError> {
+        for index in 0..self.process_count {
+            let process = self.process(index)?;
+            if process.instance != instance {
+                continue;
+            }
+            let thread = self.thread(process.main_thread)?;
+            return Ok(Some(
+                self.schedule(thread.schedule)?.max_controlled_priority,
+            ));
+        }
+        Ok(None)
+    }
+
     /// The priority the plan declares for thread `thread_index` of
     /// `instance`, counting from zero for the main thread (B48).
     ///
@@ -1323,6 +1510,24 @@ impl<'a> Generation<'a> {
         instance: usize,
         thread_index: usize,
     ) -> Result<Option<u32>, DecodeError> {
+        Ok(self
+            .thread_schedule(instance, thread_index)?
+            .map(|schedule| schedule.priority))
+    }
+
+    /// The whole schedule record for thread `thread_index` of `instance`,
+    /// counting from zero for the main thread, or `None` as
+    /// [`Self::thread_priority`] is.
+    ///
+    /// What the MCS root reads a thread's budget and period from. Admission
+    /// has already refused a record declaring only one of them, or a budget
+    /// above its period; whether the figures are ones the kernel can run is
+    /// the root's to decide.
+    pub fn thread_schedule(
+        &self,
+        instance: usize,
+        thread_index: usize,
+    ) -> Result<Option<Schedule<'a>>, DecodeError> {
         for index in 0..self.process_count {
             let process = self.process(index)?;
             if process.instance != instance {
@@ -1333,7 +1538,7 @@ impl<'a> Generation<'a> {
             // the order the root constructs them.
             if thread_index == 0 {
                 let thread = self.thread(process.main_thread)?;
-                return Ok(Some(self.schedule(thread.schedule)?.priority));
+                return Ok(Some(self.schedule(thread.schedule)?));
             }
             let mut seen = 0;
             for candidate in 0..self.thread_count {
@@ -1343,7 +1548,7 @@ impl<'a> Generation<'a> {
                 }
                 seen += 1;
                 if seen == thread_index {
-                    return Ok(Some(self.schedule(thread.schedule)?.priority));
+                    return Ok(Some(self.schedule(thread.schedule)?));
                 }
             }
             return Ok(None);
@@ -1378,6 +1583,23 @@ impl<'a> Generation<'a> {
         Ok(found)
     }
 
+    /// How many heap pages the plan lets `instance` grow its heap by: its
+    /// process quota's `dynamic_reserve_bytes`, which is zero — no heap —
+    /// unless the manifest declared `heapPages`.
+    ///
+    /// `None` when no process claims the instance.
+    pub fn instance_heap_pages(&self, instance: usize) -> Result<Option<usize>, DecodeError> {
+        for index in 0..self.process_count {
+            let process = self.process(index)?;
+            if process.instance != instance {
+                continue;
+            }
+            let reserve = self.resource_quota(process.quota)?.dynamic_reserve_bytes;
+            return Ok(Some((reserve / HEAP_PAGE_BYTES) as usize));
+        }
+        Ok(None)
+    }
+
     /// The CSpace slots the plan declares for a child's own TCB and fault
     /// endpoint. Classified by the bound object's kind rather than its name,
     /// so a renamed object still resolves.
@@ -1901,6 +2123,10 @@ impl<'a> Generation<'a> {
             let state = self.state(index)?;
             if state.owner >= self.instance_count
                 || state.schema_version == 0
+                || state.migration.is_some_and(|migration| {
+                    migration.from_version == state.schema_version
+                        || migration.executable >= self.executable_count
+                })
                 || !matches!(
                     state.policy,
                     POLICY_IMMUTABLE
@@ -2197,6 +2423,11 @@ impl<'a> Generation<'a> {
                 || (schedule.authority_process != PLAN_NONE
                     && schedule.authority_process >= self.process_count)
                 || schedule.priority > schedule.max_controlled_priority
+                // A reservation is a budget *in* a period: one without the
+                // other, or a budget the period cannot hold, is no
+                // reservation any kernel could run.
+                || (schedule.budget_us == 0) != (schedule.period_us == 0)
+                || schedule.budget_us > schedule.period_us
                 || schedule.flags != 0
                 || self.thread(schedule.thread)?.schedule != index
             {
@@ -2235,6 +2466,7 @@ impl<'a> Generation<'a> {
                 || quota.cnode_count == 0
                 || quota.tcb_count == 0
                 || quota.cslot_count == 0
+                || !quota.dynamic_reserve_bytes.is_multiple_of(HEAP_PAGE_BYTES)
                 || quota.flags != 0
             {
                 return Err(DecodeError::BadIndex);
@@ -2591,6 +2823,19 @@ mod tests {
         ));
     }
 
+    /// v6 widened the health record to carry probes, so a v5 table read at
+    /// the v6 stride would misplace every record after the first.
+    #[test]
+    fn rejects_v5_generations_whose_health_records_are_narrower() {
+        let mut bytes = [0u8; HEADER_LEN];
+        bytes[..8].copy_from_slice(&MAGIC_V5);
+        bytes[8..12].copy_from_slice(&5u32.to_le_bytes());
+        assert!(matches!(
+            Generation::decode(&bytes),
+            Err(DecodeError::UnsupportedVersion)
+        ));
+    }
+
     #[test]
     fn capability_kinds_reject_rights_from_other_classes() {
         assert!(capability_rights_valid(CapabilityKind::Endpoint, 0b11));
@@ -2604,11 +2849,49 @@ mod tests {
         ));
         assert!(!capability_rights_valid(CapabilityKind::Input, 1 << 10));
         assert!(!capability_rights_valid(CapabilityKind::Block, 1 << 23));
+        assert!(capability_rights_valid(
+            CapabilityKind::SchedulingControl,
+            RIGHT_SCHEDULE
+        ));
+        assert!(!capability_rights_valid(
+            CapabilityKind::SchedulingControl,
+            RIGHT_SCHEDULE | RIGHT_TRANSFER
+        ));
+    }
+
+    #[test]
+    fn only_the_simulated_clock_may_be_advanced_and_no_clock_travels() {
+        for kind in [CapabilityKind::MonotonicClock, CapabilityKind::WallClock] {
+            assert!(capability_rights_valid(kind, RIGHT_CLOCK_READ));
+            assert!(!capability_rights_valid(
+                kind,
+                RIGHT_CLOCK_READ | RIGHT_CLOCK_ADVANCE
+            ));
+        }
+        assert!(capability_rights_valid(
+            CapabilityKind::SimulatedClock,
+            RIGHT_CLOCK_READ | RIGHT_CLOCK_ADVANCE
+        ));
+        assert!(!capability_rights_valid(
+            CapabilityKind::SimulatedClock,
+            RIGHT_CLOCK_ADVANCE
+        ));
+        for kind in [
+            CapabilityKind::MonotonicClock,
+            CapabilityKind::WallClock,
+            CapabilityKind::SimulatedClock,
+        ] {
+            assert!(!capability_rights_valid(
+                kind,
+                RIGHT_CLOCK_READ | RIGHT_TRANSFER
+            ));
+            assert_eq!(service_for_capability(kind), Some(SERVICE_LIFECYCLE));
+        }
     }
 
     /// B57: `RIGHT_ALL` is the union of the *named* rights, not a bit-width
     /// mask. Bit 17 is a gap in the numbering — nothing names it and nothing
-    /// uses it — so `(1 << 26) - 1` would admit a grant carrying authority no
+    /// uses it — so `(1 << 29) - 1` would admit a grant carrying authority no
     /// contract defines. Every validator that masks with `!RIGHT_ALL` inherits
     /// this, so pinning the mask is what keeps the hole closed.
     #[test]
@@ -2639,13 +2922,16 @@ mod tests {
             RIGHT_INPUT_READ,
             RIGHT_BUFFER_CREATE,
             RIGHT_BUFFER_LOAN,
+            RIGHT_SCHEDULE,
+            RIGHT_CLOCK_READ,
+            RIGHT_CLOCK_ADVANCE,
         ];
         let union = named
             .iter()
             .fold(0, |accumulator, right| accumulator | right);
         assert_eq!(union, RIGHT_ALL);
         assert_eq!(RIGHT_ALL & (1 << 17), 0);
-        assert_ne!(RIGHT_ALL, (1 << 26) - 1);
+        assert_ne!(RIGHT_ALL, (1 << 29) - 1);
         // The mask is what every grant, mapping, and minted-binding check
         // applies, so an undefined bit must survive none of them.
         assert_ne!((RIGHT_SEND | RIGHT_RECV | 1 << 17) & !RIGHT_ALL, 0);
@@ -2659,6 +2945,10 @@ mod tests {
             CapabilityKind::Supervision,
             CapabilityKind::SharedBuffer,
             CapabilityKind::Loan,
+            CapabilityKind::SchedulingControl,
+            CapabilityKind::MonotonicClock,
+            CapabilityKind::WallClock,
+            CapabilityKind::SimulatedClock,
         ] {
             assert!(!capability_rights_valid(kind, 1 << 17));
         }
@@ -2699,7 +2989,7 @@ mod tests {
     ///
     /// Shared with `boot_action_ids_round_trip`, which uses it as the
     /// independent second source proving `BootAction::ALL` is complete.
-    const FROZEN_BOOT_ACTIONS: [(BootAction, u32); 29] = [
+    const FROZEN_BOOT_ACTIONS: [(BootAction, u32); 31] = [
         (BootAction::Product, 1),
         (BootAction::Boot, 2),
         (BootAction::Call, 3),
@@ -2729,6 +3019,8 @@ mod tests {
         (BootAction::Matrix, 27),
         (BootAction::Traffic, 28),
         (BootAction::Demo, 29),
+        (BootAction::Isolation, 30),
+        (BootAction::Clock, 31),
     ];
 
     #[test]
@@ -2800,6 +3092,8 @@ mod tests {
             ("visibility", BootAction::Visibility),
             ("matrix", BootAction::Matrix),
             ("demo", BootAction::Demo),
+            ("isolation", BootAction::Isolation),
+            ("clock", BootAction::Clock),
         ] {
             assert_eq!(BootAction::parse(spelling), Some(expected));
         }
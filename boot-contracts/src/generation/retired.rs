@@ -0,0 +1,469 @@
+//! The catalogue of a v2, v3 or v4 generation, read for comparison only.
+//!
+//! Nothing boots these formats, and this reader gives nothing a boot path
+//! could use: no dependency or binding tables, no kernel plan (none of them
+//! has one), and no admission beyond what decoding needs to be honest about
+//! what it read. What it does give is every record a release diff compares —
+//! executables, instances, grants, state, and the objects behind them — so a
+//! generation built before v5 can still be the baseline of one after it.
+//!
+//! v2 and v3 have no instance table: a component is the executable and the
+//! one instance of it, owned by the root and started with it, which is all
+//! those formats could say. v3 widened a grant's rights to 64 bits, where v2's
+//! were 32 and `transferable` followed them. v4 split the component into an
+//! executable and instances, with the same instance record v6 has. None of
+//! them declares what kind of capability a grant is, and rights are read as
+//! written: bit 17, since withdrawn from the vocabulary, was a right then.
+
+use super::{
+    BINDING_LEN, DEPENDENCY_LEN, DecodeError, EXECUTABLE_LEN, Executable, GRANT_LEN, INSTANCE_LEN,
+    InstanceHealth, InstanceOwner, KIND_BOOTSTRAP, KIND_COMPONENT, KIND_KERNEL, KIND_RESOURCE,
+    MAGIC_V2, MAGIC_V3, MAGIC_V4, MAX_BINDINGS, MAX_DEPENDENCIES, MAX_EXECUTABLES,
+    MAX_GENERATION_BYTES, MAX_GRANTS, MAX_HEALTH_INSTANCES, MAX_INSTANCES,
+    MAX_OBJECT_PAYLOAD_BYTES, MAX_OBJECTS, MAX_STATES, MAX_STRING_TABLE_BYTES, OBJECT_LEN, Object,
+    Rights, STATE_LEN, StateBinding, bool_at, bounded_count, check_section, generation_identity,
+    read_string, reserved_zero, u32_at, u64_at,
+};
+use crate::sha256::Sha256;
+
+const HEADER_LEN: usize = 256;
+const COMPONENT_LEN: usize = 32;
+const HEALTH_LEN: usize = 4;
+/// v3 raised the component ceiling from v2's 32 (C8.7).
+const V2_MAX_COMPONENTS: usize = 32;
+
+/// One launched instance. In v2 and v3, the component itself.
+#[derive(Debug, Clone, Copy)]
+pub struct Instance<'a> {
+    pub name: &'a str,
+    pub executable: usize,
+    pub owner: InstanceOwner,
+    pub autostart: bool,
+    pub health: InstanceHealth,
+}
+
+/// A grant between two instances, with no capability kind.
+#[derive(Debug, Clone, Copy)]
+pub struct Grant<'a> {
+    pub name: &'a str,
+    pub source: usize,
+    pub target: usize,
+    pub rights: Rights,
+    pub transferable: bool,
+}
+
+pub struct Generation<'a> {
+    bytes: &'a [u8],
+    pub version: u32,
+    pub identity: [u8; 32],
+    pub number: u64,
+    pub parent: Option<[u8; 32]>,
+    pub target: &'a str,
+    object_count: usize,
+    executable_count: usize,
+    instance_count: usize,
+    grant_count: usize,
+    state_count: usize,
+    health_count: usize,
+    object_offset: usize,
+    executable_offset: usize,
+    instance_offset: usize,
+    grant_offset: usize,
+    state_offset: usize,
+    health_offset: usize,
+    string_offset: usize,
+    string_len: usize,
+}
+
+impl<'a> Generation<'a> {
+    pub fn decode(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        if bytes.len() < HEADER_LEN {
+            return Err(DecodeError::Truncated);
+        }
+        let magic: [u8; 8] = bytes[..8].try_into().unwrap();
+        let version = match magic {
+            MAGIC_V2 => 2,
+            MAGIC_V3 => 3,
+            MAGIC_V4 => 4,
+            _ => return Err(DecodeError::BadMagic),
+        };
+        if u32_at(bytes, 8)? != version {
+            return Err(DecodeError::UnsupportedVersion);
+        }
+        if u32_at(bytes, 12)? as usize != HEADER_LEN {
+            return Err(DecodeError::BadHeader);
+        }
+        if u64_at(bytes, 16)? != 0 {
+            return Err(DecodeError::UnknownRequiredFlags);
+        }
+        let identity: [u8; 32] = bytes[24..56].try_into().unwrap();
+        if generation_identity(bytes) != identity {
+            return Err(DecodeError::BadIdentity);
+        }
+        let parent: [u8; 32] = bytes[64..96].try_into().unwrap();
+
+        // The two header shapes differ only in which counts they carry; the
+        // offsets follow the counts in the same order.
+        let generation = if version == 4 {
+            reserved_zero(bytes, 100, 104)?;
+            reserved_zero(bytes, 240, HEADER_LEN)?;
+            Self {
+                object_count: bounded_count(u32_at(bytes, 112)? as usize, 1, MAX_OBJECTS)?,
+                executable_count: bounded_count(u32_at(bytes, 116)? as usize, 1, MAX_EXECUTABLES)?,
+                instance_count: bounded_count(u32_at(bytes, 120)? as usize, 1, MAX_INSTANCES)?,
+                grant_count: bounded_count(u32_at(bytes, 132)? as usize, 0, MAX_GRANTS)?,
+                state_count: bounded_count(u32_at(bytes, 136)? as usize, 0, MAX_STATES)?,
+                health_count: bounded_count(u32_at(bytes, 140)? as usize, 0, MAX_HEALTH_INSTANCES)?,
+                object_offset: u64_at(bytes, 144)? as usize,
+                executable_offset: u64_at(bytes, 152)? as usize,
+                instance_offset: u64_at(bytes, 160)? as usize,
+                grant_offset: u64_at(bytes, 184)? as usize,
+                state_offset: u64_at(bytes, 192)? as usize,
+                health_offset: u64_at(bytes, 200)? as usize,
+                string_offset: u64_at(bytes, 208)? as usize,
+                string_len: u64_at(bytes, 216)? as usize,
+                ..Self::header(bytes, version, identity, parent)?
+            }
+        } else {
+            reserved_zero(bytes, 216, HEADER_LEN)?;
+            let max_components = if version == 2 {
+                V2_MAX_COMPONENTS
+            } else {
+                MAX_EXECUTABLES
+            };
+            let component_count = bounded_count(u32_at(bytes, 116)? as usize, 1, max_components)?;
+            let component_offset = u64_at(bytes, 144)? as usize;
+            Self {
+                object_count: bounded_count(u32_at(bytes, 112)? as usize, 1, MAX_OBJECTS)?,
+                executable_count: component_count,
+                instance_count: component_count,
+                grant_count: bounded_count(u32_at(bytes, 124)? as usize, 0, MAX_GRANTS)?,
+                state_count: bounded_count(u32_at(bytes, 128)? as usize, 0, MAX_STATES)?,
+                health_count: bounded_count(u32_at(bytes, 132)? as usize, 0, MAX_HEALTH_INSTANCES)?,
+                object_offset: u64_at(bytes, 136)? as usize,
+                executable_offset: component_offset,
+                instance_offset: component_offset,
+                grant_offset: u64_at(bytes, 160)? as usize,
+                state_offset: u64_at(bytes, 168)? as usize,
+                health_offset: u64_at(bytes, 176)? as usize,
+                string_offset: u64_at(bytes, 184)? as usize,
+                string_len: u64_at(bytes, 192)? as usize,
+                ..Self::header(bytes, version, identity, parent)?
+            }
+        };
+        let (payload_offset, total_len) = if version == 4 {
+            (u64_at(bytes, 224)? as usize, u64_at(bytes, 232)? as usize)
+        } else {
+            (u64_at(bytes, 200)? as usize, u64_at(bytes, 208)? as usize)
+        };
+        if total_len != bytes.len() || total_len > MAX_GENERATION_BYTES {
+            return Err(DecodeError::BadBounds);
+        }
+        if generation.string_len > MAX_STRING_TABLE_BYTES {
+            return Err(DecodeError::BadBounds);
+        }
+        generation.validate_sections(payload_offset)?;
+        let generation = Self {
+            target: generation.string(u32_at(bytes, 96)? as usize)?,
+            ..generation
+        };
+        generation.validate_objects(payload_offset)?;
+        Ok(generation)
+    }
+
+    /// The fields both header shapes share, with every table empty until the
+    /// caller reads its counts.
+    fn header(
+        bytes: &'a [u8],
+        version: u32,
+        identity: [u8; 32],
+        parent: [u8; 32],
+    ) -> Result<Self, DecodeError> {
+        Ok(Self {
+            bytes,
+            version,
+            identity,
+            number: u64_at(bytes, 56)?,
+            parent: (parent != [0; 32]).then_some(parent),
+            target: "",
+            object_count: 0,
+            executable_count: 0,
+            instance_count: 0,
+            grant_count: 0,
+            state_count: 0,
+            health_count: 0,
+            object_offset: 0,
+            executable_offset: 0,
+            instance_offset: 0,
+            grant_offset: 0,
+            state_offset: 0,
+            health_offset: 0,
+            string_offset: 0,
+            string_len: 0,
+        })
+    }
+
+    const fn has_components(&self) -> bool {
+        self.version < 4
+    }
+
+    /// Every table in wire order, each ending where the next begins. The
+    /// dependency and binding tables are bounded here and not otherwise read.
+    fn validate_sections(&self, payload_offset: usize) -> Result<(), DecodeError> {
+        let bytes = self.bytes;
+        check_section(
+            self.object_offset,
+            self.object_count,
+            OBJECT_LEN,
+            self.executable_offset,
+        )?;
+        if self.has_components() {
+            let dependency_offset = u64_at(bytes, 152)? as usize;
+            check_section(
+                self.executable_offset,
+                self.executable_count,
+                COMPONENT_LEN,
+                dependency_offset,
+            )?;
+            let count = bounded_count(u32_at(bytes, 120)? as usize, 0, MAX_DEPENDENCIES)?;
+            check_section(dependency_offset, count, DEPENDENCY_LEN, self.grant_offset)?;
+        } else {
+            let dependency_offset = u64_at(bytes, 168)? as usize;
+            let binding_offset = u64_at(bytes, 176)? as usize;
+            check_section(
+                self.executable_offset,
+                self.executable_count,
+                EXECUTABLE_LEN,
+                self.instance_offset,
+            )?;
+            check_section(
+                self.instance_offset,
+                self.instance_count,
+                INSTANCE_LEN,
+                dependency_offset,
+            )?;
+            let count = bounded_count(u32_at(bytes, 124)? as usize, 0, MAX_DEPENDENCIES)?;
+            check_section(dependency_offset, count, DEPENDENCY_LEN, binding_offset)?;
+            let count = bounded_count(u32_at(bytes, 128)? as usize, 0, MAX_BINDINGS)?;
+            check_section(binding_offset, count, BINDING_LEN, self.grant_offset)?;
+        }
+        check_section(
+            self.grant_offset,
+            self.grant_count,
+            GRANT_LEN,
+            self.state_offset,
+        )?;
+        check_section(
+            self.state_offset,
+            self.state_count,
+            STATE_LEN,
+            self.health_offset,
+        )?;
+        check_section(
+            self.health_offset,
+            self.health_count,
+            HEALTH_LEN,
+            self.string_offset,
+        )?;
+        if self.object_offset != HEADER_LEN
+            || self.string_offset.checked_add(self.string_len) != Some(payload_offset)
+            || payload_offset > bytes.len()
+        {
+            return Err(DecodeError::BadBounds);
+        }
+        Ok(())
+    }
+
+    /// Objects are what a diff reads a digest from, so each must hash to it.
+    fn validate_objects(&self, payload_offset: usize) -> Result<(), DecodeError> {
+        let mut previous_end = payload_offset;
+        for index in 0..self.object_count {
+            let object = self.object(index)?;
+            if !matches!(
+                object.kind,
+                KIND_KERNEL | KIND_BOOTSTRAP | KIND_COMPONENT | KIND_RESOURCE
+            ) {
+                return Err(DecodeError::UnknownEnum);
+            }
+            let start = u64_at(self.bytes, self.object_offset + index * OBJECT_LEN + 8)? as usize;
+            if start != previous_end {
+                return Err(DecodeError::BadBounds);
+            }
+            previous_end = start + object.bytes.len();
+            let mut hasher = Sha256::new();
+            hasher.update(object.bytes);
+            if hasher.finalize() != object.digest {
+                return Err(DecodeError::BadObjectHash);
+            }
+        }
+        if previous_end != self.bytes.len() {
+            return Err(DecodeError::BadBounds);
+        }
+        Ok(())
+    }
+
+    pub const fn object_count(&self) -> usize {
+        self.object_count
+    }
+    pub const fn executable_count(&self) -> usize {
+        self.executable_count
+    }
+    pub const fn instance_count(&self) -> usize {
+        self.instance_count
+    }
+    pub const fn grant_count(&self) -> usize {
+        self.grant_count
+    }
+    pub const fn state_count(&self) -> usize {
+        self.state_count
+    }
+    pub const fn health_count(&self) -> usize {
+        self.health_count
+    }
+
+    pub fn object(&self, index: usize) -> Result<Object<'a>, DecodeError> {
+        if index >= self.object_count {
+            return Err(DecodeError::BadIndex);
+        }
+        let offset = self.object_offset + index * OBJECT_LEN;
+        reserved_zero(self.bytes, offset + 56, offset + OBJECT_LEN)?;
+        let payload_offset = u64_at(self.bytes, offset + 8)? as usize;
+        let payload_len = u64_at(self.bytes, offset + 16)? as usize;
+        if payload_len > MAX_OBJECT_PAYLOAD_BYTES {
+            return Err(DecodeError::BadBounds);
+        }
+        let end = payload_offset
+            .checked_add(payload_len)
+            .ok_or(DecodeError::BadBounds)?;
+        Ok(Object {
+            id: self.string(u32_at(self.bytes, offset)? as usize)?,
+            kind: u32_at(self.bytes, offset + 4)?,
+            digest: self.bytes[offset + 24..offset + 56].try_into().unwrap(),
+            bytes: self
+                .bytes
+                .get(payload_offset..end)
+                .ok_or(DecodeError::BadBounds)?,
+        })
+    }
+
+    /// An executable, or in v2 and v3 the image half of a component.
+    pub fn executable(&self, index: usize) -> Result<Executable<'a>, DecodeError> {
+        if index >= self.executable_count {
+            return Err(DecodeError::BadIndex);
+        }
+        let (offset, budget) = if self.has_components() {
+            let offset = self.executable_offset + index * COMPONENT_LEN;
+            reserved_zero(self.bytes, offset + 24, offset + COMPONENT_LEN)?;
+            (offset, offset + 20)
+        } else {
+            let offset = self.executable_offset + index * EXECUTABLE_LEN;
+            reserved_zero(self.bytes, offset + 16, offset + EXECUTABLE_LEN)?;
+            (offset, offset + 12)
+        };
+        let object = u32_at(self.bytes, offset + 4)? as usize;
+        if object >= self.object_count {
+            return Err(DecodeError::BadIndex);
+        }
+        Ok(Executable {
+            name: self.string(u32_at(self.bytes, offset)? as usize)?,
+            object,
+            role: u32_at(self.bytes, offset + 8)?,
+            spawn_budget: u16::try_from(u32_at(self.bytes, budget)?)
+                .map_err(|_| DecodeError::BadBounds)?,
+        })
+    }
+
+    /// An instance, or in v2 and v3 the component as launched.
+    pub fn instance(&self, index: usize) -> Result<Instance<'a>, DecodeError> {
+        if index >= self.instance_count {
+            return Err(DecodeError::BadIndex);
+        }
+        if self.has_components() {
+            let mut health = InstanceHealth::Optional;
+            for record in 0..self.health_count {
+                if u32_at(self.bytes, self.health_offset + record * HEALTH_LEN)? as usize == index {
+                    health = InstanceHealth::Required;
+                }
+            }
+            return Ok(Instance {
+                name: self.executable(index)?.name,
+                executable: index,
+                owner: InstanceOwner::Root,
+                autostart: true,
+                health,
+            });
+        }
+        let offset = self.instance_offset + index * INSTANCE_LEN;
+        reserved_zero(self.bytes, offset + 40, offset + INSTANCE_LEN)?;
+        let owner = match u32_at(self.bytes, offset + 8)? {
+            0 => {
+                if u32_at(self.bytes, offset + 12)? != 0 {
+                    return Err(DecodeError::BadOwner);
+                }
+                InstanceOwner::Root
+            }
+            1 => InstanceOwner::Instance(u32_at(self.bytes, offset + 12)? as usize),
+            _ => return Err(DecodeError::UnknownEnum),
+        };
+        Ok(Instance {
+            name: self.string(u32_at(self.bytes, offset)? as usize)?,
+            executable: u32_at(self.bytes, offset + 4)? as usize,
+            owner,
+            autostart: bool_at(self.bytes, offset + 16)?,
+            health: match u32_at(self.bytes, offset + 36)? {
+                0 => InstanceHealth::Optional,
+                1 => InstanceHealth::Required,
+                _ => return Err(DecodeError::UnknownEnum),
+            },
+        })
+    }
+
+    pub fn grant(&self, index: usize) -> Result<Grant<'a>, DecodeError> {
+        if index >= self.grant_count {
+            return Err(DecodeError::BadIndex);
+        }
+        let offset = self.grant_offset + index * GRANT_LEN;
+        let (rights, transferable) = if self.version == 2 {
+            reserved_zero(self.bytes, offset + 20, offset + GRANT_LEN)?;
+            (Rights::from(u32_at(self.bytes, offset + 12)?), offset + 16)
+        } else {
+            reserved_zero(self.bytes, offset + 24, offset + GRANT_LEN)?;
+            (u64_at(self.bytes, offset + 12)?, offset + 20)
+        };
+        let source = u32_at(self.bytes, offset + 4)? as usize;
+        let target = u32_at(self.bytes, offset + 8)? as usize;
+        if source >= self.instance_count || target >= self.instance_count {
+            return Err(DecodeError::BadIndex);
+        }
+        Ok(Grant {
+            name: self.string(u32_at(self.bytes, offset)? as usize)?,
+            source,
+            target,
+            rights,
+            transferable: bool_at(self.bytes, transferable)?,
+        })
+    }
+
+    pub fn state(&self, index: usize) -> Result<StateBinding<'a>, DecodeError> {
+        if index >= self.state_count {
+            return Err(DecodeError::BadIndex);
+        }
+        let offset = self.state_offset + index * STATE_LEN;
+        reserved_zero(self.bytes, offset + 16, offset + STATE_LEN)?;
+        let owner = u32_at(self.bytes, offset + 4)? as usize;
+        if owner >= self.instance_count {
+            return Err(DecodeError::BadState);
+        }
+        Ok(StateBinding {
+            name: self.string(u32_at(self.bytes, offset)? as usize)?,
+            owner,
+            schema_version: u32_at(self.bytes, offset + 8)?,
+            policy: u32_at(self.bytes, offset + 12)?,
+            migration: None,
+        })
+    }
+
+    fn string(&self, offset: usize) -> Result<&'a str, DecodeError> {
+        read_string(self.bytes, self.string_offset, self.string_len, offset)
+    }
+}
@@ -0,0 +1,1669 @@
+//! What changed between two generations, as structured records.
+//!
+//! Approving a release means approving its delta from the generation it
+//! replaces: the instances it adds or drops, the grants it widens, the routes
+//! and QoS its fabric graph now declares, the shared-buffer quotas it hands
+//! out, and the state it keeps or discards. A release names its parent, so the
+//! parent is the baseline; this module turns the two decoded generations into
+//! a list of [`Change`]s a reviewer, a host tool, or a gate can read.
+//!
+//! Records are matched by what a manifest author would call them, never by
+//! index: executables, instances, grants, and state by name; routes by route
+//! identity; fabric participants by grant identity; quotas by holder identity.
+//! An index is a position in one generation's tables and means nothing in the
+//! other, so a grant whose target moved from instance 3 to instance 4 because
+//! an instance was inserted ahead of it is not a change, while one retargeted
+//! to a different instance is.
+//!
+//! The order is fixed, so two runs over the same pair print the same diff:
+//! sections in [`Change`]'s declaration order; within a section, removals and
+//! changes in the old generation's record order, then additions in the new
+//! one's. No allocation: changes are handed to a visitor as they are found.
+//!
+//! [`widenings`] reads the same pair for authority alone: the rights each
+//! instance holds, per capability kind; what each process's kernel plan
+//! installs, maps, invokes, may allocate and may schedule; and the
+//! participants each fabric route admits. It is what a release gate asks,
+//! where [`diff`] is what a reviewer reads.
+//!
+//! Either side may be in any format since v2, because the generation a
+//! release replaces may predate the format the release is built in. [`decode`]
+//! reads v5 and v6 through [`Generation`], and v2–v4 through
+//! [`retired::Generation`], which has the catalogue and instances but no
+//! kernel plan; a plan present on one side only widens by all of it. A v2–v4
+//! grant declares no capability kind, so its rights are held apart from every
+//! kind's, as [`Authority::undeclared`]: measured across that boundary, the
+//! new side widens by everything it holds, since no one can say what the old
+//! grant's rights were over.
+
+use crate::fabric_graph::{self, FabricGraph, GraphLimits, TransportQos, VISIBILITY_GRAPH};
+use crate::generation::{
+    CAPABILITY_SIMULATED_CLOCK, CapabilityKind, DecodeError, Generation, GrantEndpoint,
+    InstanceHealth, InstanceOwner, KIND_RESOURCE, MAGIC_V2, MAGIC_V3, MAGIC_V4, MAGIC_V5,
+    ResourceQuota, Rights, Schedule, retired,
+};
+use crate::sha256::Sha256;
+use crate::shared_buffer_budget::{self, HolderQuota, SharedBufferBudget};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffError {
+    Generation(DecodeError),
+    FabricGraph(fabric_graph::DecodeError),
+    SharedBufferBudget(shared_buffer_budget::DecodeError),
+}
+
+impl From<DecodeError> for DiffError {
+    fn from(error: DecodeError) -> Self {
+        Self::Generation(error)
+    }
+}
+
+/// One side of a diff, in whichever format it was written.
+///
+/// Held by value: a diff builds two and the decoders have no heap to box in.
+#[allow(clippy::large_enum_variant)]
+pub enum Side<'a> {
+    /// v5 or v6: a catalogue, an instance graph, and a kernel plan.
+    Planned(Generation<'a>),
+    /// v2–v4: a catalogue and its instances, with no plan.
+    Catalogue(retired::Generation<'a>),
+}
+
+impl<'a> From<Generation<'a>> for Side<'a> {
+    fn from(generation: Generation<'a>) -> Self {
+        Self::Planned(generation)
+    }
+}
+
+/// Decodes one side of a diff, in any format since v2.
+pub fn decode(bytes: &[u8]) -> Result<Side<'_>, DiffError> {
+    Ok(match bytes.get(..8) {
+        Some(magic) if magic == MAGIC_V5 => Side::Planned(Generation::decode_v5(bytes)?),
+        Some(magic) if magic == MAGIC_V2 || magic == MAGIC_V3 || magic == MAGIC_V4 => {
+            Side::Catalogue(retired::Generation::decode(bytes)?)
+        }
+        _ => Side::Planned(Generation::decode(bytes)?),
+    })
+}
+
+impl<'a> Side<'a> {
+    pub const fn version(&self) -> u32 {
+        match self {
+            Self::Planned(generation) => generation.version,
+            Self::Catalogue(generation) => generation.version,
+        }
+    }
+
+    pub const fn number(&self) -> u64 {
+        match self {
+            Self::Planned(generation) => generation.number,
+            Self::Catalogue(generation) => generation.number,
+        }
+    }
+
+    pub const fn identity(&self) -> [u8; 32] {
+        match self {
+            Self::Planned(generation) => generation.identity,
+            Self::Catalogue(generation) => generation.identity,
+        }
+    }
+
+    pub const fn parent(&self) -> Option<[u8; 32]> {
+        match self {
+            Self::Planned(generation) => generation.parent,
+            Self::Catalogue(generation) => generation.parent,
+        }
+    }
+
+    /// The kernel plan, in the formats that carry one.
+    const fn plan(&self) -> Option<&Generation<'a>> {
+        match self {
+            Self::Planned(generation) => Some(generation),
+            Self::Catalogue(_) => None,
+        }
+    }
+
+    const fn executable_count(&self) -> usize {
+        match self {
+            Self::Planned(generation) => generation.executable_count(),
+            Self::Catalogue(generation) => generation.executable_count(),
+        }
+    }
+
+    const fn instance_count(&self) -> usize {
+        match self {
+            Self::Planned(generation) => generation.instance_count(),
+            Self::Catalogue(generation) => generation.instance_count(),
+        }
+    }
+
+    const fn grant_count(&self) -> usize {
+        match self {
+            Self::Planned(generation) => generation.grant_count(),
+            Self::Catalogue(generation) => generation.grant_count(),
+        }
+    }
+
+    const fn state_count(&self) -> usize {
+        match self {
+            Self::Planned(generation) => generation.state_count(),
+            Self::Catalogue(generation) => generation.state_count(),
+        }
+    }
+}
+
+/// One record's fate between the two generations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delta<T> {
+    Added(T),
+    Removed(T),
+    Changed { old: T, new: T },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutableView<'a> {
+    pub name: &'a str,
+    /// The digest of the object holding the image, so a rebuilt executable
+    /// under an unchanged name is a change.
+    pub digest: [u8; 32],
+    pub role: u32,
+    pub spawn_budget: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InstanceView<'a> {
+    pub name: &'a str,
+    pub executable: &'a str,
+    /// The owning instance, or `None` for the root.
+    pub owner: Option<&'a str>,
+    pub autostart: bool,
+    pub health: InstanceHealth,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GrantView<'a> {
+    pub name: &'a str,
+    /// The endpoints by executable or instance name.
+    pub source: &'a str,
+    pub target: &'a str,
+    pub rights: Rights,
+    pub transferable: bool,
+    /// `None` in v2–v4, which declared no kinds.
+    pub capability_kind: Option<CapabilityKind>,
+}
+
+impl GrantView<'_> {
+    /// Rights this grant carries that `old` did not.
+    pub const fn widened_from(&self, old: &Self) -> Rights {
+        self.rights & !old.rights
+    }
+
+    /// Rights `old` carried that this grant no longer does.
+    pub const fn narrowed_from(&self, old: &Self) -> Rights {
+        old.rights & !self.rights
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateView<'a> {
+    pub name: &'a str,
+    pub owner: &'a str,
+    pub schema_version: u32,
+    pub policy: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RouteView {
+    pub identity: [u8; 32],
+    /// The identity of the interface schema the route carries.
+    pub schema: [u8; 32],
+    pub contract_kind: u32,
+    pub participant_count: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParticipantView {
+    pub grant: [u8; 32],
+    pub component: [u8; 32],
+    /// The identity of the route, not its index.
+    pub route: [u8; 32],
+    pub direction: u32,
+    pub visibility: u32,
+    pub qos: TransportQos,
+}
+
+/// One difference, by section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change<'a> {
+    Executable(Delta<ExecutableView<'a>>),
+    Instance(Delta<InstanceView<'a>>),
+    Grant(Delta<GrantView<'a>>),
+    State(Delta<StateView<'a>>),
+    /// The fabric graph's declared ceilings. Added or removed with the graph.
+    FabricLimits(Delta<GraphLimits>),
+    Route(Delta<RouteView>),
+    /// A route participant, which is where the QoS a grant requests lives.
+    Participant(Delta<ParticipantView>),
+    SharedBufferQuota(Delta<HolderQuota>),
+}
+
+/// Visit every change from `old` to `new`, answering how many there were.
+pub fn diff<'a>(
+    old: &Side<'a>,
+    new: &Side<'a>,
+    mut visit: impl FnMut(Change<'a>),
+) -> Result<usize, DiffError> {
+    let mut count = 0;
+    let mut emit = |change| {
+        count += 1;
+        visit(change);
+    };
+
+    compare(
+        old.executable_count(),
+        |index| executable(old, index),
+        new.executable_count(),
+        |index| executable(new, index),
+        |view| view.name,
+        &mut |delta| emit(Change::Executable(delta)),
+    )?;
+    compare(
+        old.instance_count(),
+        |index| instance(old, index),
+        new.instance_count(),
+        |index| instance(new, index),
+        |view| view.name,
+        &mut |delta| emit(Change::Instance(delta)),
+    )?;
+    compare(
+        old.grant_count(),
+        |index| grant(old, index),
+        new.grant_count(),
+        |index| grant(new, index),
+        |view| view.name,
+        &mut |delta| emit(Change::Grant(delta)),
+    )?;
+    compare(
+        old.state_count(),
+        |index| state(old, index),
+        new.state_count(),
+        |index| state(new, index),
+        |view| view.name,
+        &mut |delta| emit(Change::State(delta)),
+    )?;
+
+    let old_graph = fabric_graph_of(old)?;
+    let new_graph = fabric_graph_of(new)?;
+    let limits = |graph: &Option<FabricGraph<'_>>| graph.as_ref().map(FabricGraph::limits);
+    match (limits(&old_graph), limits(&new_graph)) {
+        (Some(old), Some(new)) if old != new => {
+            emit(Change::FabricLimits(Delta::Changed { old, new }))
+        }
+        (Some(old), None) => emit(Change::FabricLimits(Delta::Removed(old))),
+        (None, Some(new)) => emit(Change::FabricLimits(Delta::Added(new))),
+        _ => {}
+    }
+    let routes = |graph: &Option<FabricGraph<'_>>| graph.as_ref().map_or(0, |g| g.route_count());
+    compare(
+        routes(&old_graph),
+        |index| route(old_graph.as_ref(), index),
+        routes(&new_graph),
+        |index| route(new_graph.as_ref(), index),
+        |view| view.identity,
+        &mut |delta| emit(Change::Route(delta)),
+    )?;
+    let participants =
+        |graph: &Option<FabricGraph<'_>>| graph.as_ref().map_or(0, |g| g.participant_count());
+    compare(
+        participants(&old_graph),
+        |index| participant(old_graph.as_ref(), index),
+        participants(&new_graph),
+        |index| participant(new_graph.as_ref(), index),
+        |view| view.grant,
+        &mut |delta| emit(Change::Participant(delta)),
+    )?;
+
+    let old_budget = shared_buffer_budget_of(old)?;
+    let new_budget = shared_buffer_budget_of(new)?;
+    let holders = |budget: &Option<SharedBufferBudget<'_>>| {
+        budget.as_ref().map_or(0, SharedBufferBudget::holder_count)
+    };
+    compare(
+        holders(&old_budget),
+        |index| quota(old_budget.as_ref(), index),
+        holders(&new_budget),
+        |index| quota(new_budget.as_ref(), index),
+        |quota| quota.holder_identity,
+        &mut |delta| emit(Change::SharedBufferQuota(delta)),
+    )?;
+
+    Ok(count)
+}
+
+/// Capability kinds are numbered from one, and `SimulatedClock` is the last.
+const CAPABILITY_KINDS: usize = CAPABILITY_SIMULATED_CLOCK as usize;
+
+/// The rights one instance holds, kind by kind: the union of every grant that
+/// applies to it and every minted binding it receives.
+///
+/// Kept per kind because a right means nothing apart from the object it is
+/// over — `RIGHT_TRANSFER` on an endpoint and on a directory are different
+/// authority. The bits are the generated `RIGHT_*` vocabulary, with a grant's
+/// `transferable` already folded in as `RIGHT_TRANSFER` by the decoder, so the
+/// algebra here is exactly the one admission enforces.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Authority {
+    kinds: [Rights; CAPABILITY_KINDS],
+    undeclared: Rights,
+}
+
+impl Authority {
+    pub const fn rights(&self, kind: CapabilityKind) -> Rights {
+        self.kinds[kind as usize - 1]
+    }
+
+    /// Rights from grants that name no kind: a v2–v4 generation's, over
+    /// objects of whatever kind they were.
+    pub const fn undeclared(&self) -> Rights {
+        self.undeclared
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        let mut index = 0;
+        while index < CAPABILITY_KINDS {
+            if self.kinds[index] != 0 {
+                return false;
+            }
+            index += 1;
+        }
+        self.undeclared == 0
+    }
+
+    /// What this holds that `old` did not, kind by kind.
+    pub const fn widened_from(&self, old: &Self) -> Self {
+        let mut widened = [0; CAPABILITY_KINDS];
+        let mut index = 0;
+        while index < CAPABILITY_KINDS {
+            widened[index] = self.kinds[index] & !old.kinds[index];
+            index += 1;
+        }
+        Self {
+            kinds: widened,
+            undeclared: self.undeclared & !old.undeclared,
+        }
+    }
+
+    fn hold(&mut self, kind: Option<CapabilityKind>, rights: Rights) {
+        match kind {
+            Some(kind) => self.kinds[kind as usize - 1] |= rights,
+            None => self.undeclared |= rights,
+        }
+    }
+}
+
+/// Authority the new generation confers that the old one did not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Widening<'a> {
+    /// Rights an instance holds that its namesake did not. An instance with no
+    /// namesake widens by everything it holds.
+    Instance { name: &'a str, rights: Authority },
+    /// Rights a process's capability bindings install over a kernel object
+    /// that its namesake's did not install over the namesake object.
+    Capability {
+        process: &'a str,
+        object: &'a str,
+        rights: Rights,
+    },
+    /// Rights a process maps a kernel object with that its namesake did not.
+    Mapping {
+        process: &'a str,
+        object: &'a str,
+        rights: Rights,
+    },
+    /// A root service a process may invoke that its namesake could not.
+    Service { process: &'a str, service: u32 },
+    /// A quota that lets a process hold more of some resource than its
+    /// namesake's let it.
+    Quota { process: &'a str },
+    /// A thread scheduled above its namesake, allowed to hand out higher
+    /// priorities, or given a larger share of the processor.
+    Schedule { thread: &'a str },
+    /// A participant a route did not admit, or one now visible to the whole
+    /// graph. The grant identity folds the component and direction in, so a
+    /// participant that changed either is a new one.
+    Route { route: [u8; 32], grant: [u8; 32] },
+}
+
+/// Visit every widening from `old` to `new`, answering how many there were.
+///
+/// Instances in the new generation's order; then its kernel plan, table by
+/// table in [`Widening`]'s declaration order; then participants in its fabric
+/// graph's order. Kernel-plan records are matched like everything else, by
+/// the names of the process, thread and kernel object they concern, and a
+/// process or thread with no namesake widens by everything it holds.
+/// Narrowing is not reported: dropping authority needs no one's approval.
+pub fn widenings<'a>(
+    old: &Side<'a>,
+    new: &Side<'a>,
+    mut visit: impl FnMut(Widening<'a>),
+) -> Result<usize, DiffError> {
+    let mut count = 0;
+    for index in 0..new.instance_count() {
+        let name = instance(new, index)?.name;
+        let before = match (0..old.instance_count())
+            .find(|&old_index| instance(old, old_index).is_ok_and(|view| view.name == name))
+        {
+            Some(old_index) => authority(old, old_index)?,
+            None => Authority::default(),
+        };
+        let rights = authority(new, index)?.widened_from(&before);
+        if !rights.is_empty() {
+            count += 1;
+            visit(Widening::Instance { name, rights });
+        }
+    }
+
+    if let Some(plan) = new.plan() {
+        count += plan_widenings(old.plan(), plan, &mut visit)?;
+    }
+
+    let old_graph = fabric_graph_of(old)?;
+    let Some(new_graph) = fabric_graph_of(new)? else {
+        return Ok(count);
+    };
+    for index in 0..new_graph.participant_count() {
+        let after = participant(Some(&new_graph), index)?;
+        let before = old_graph
+            .as_ref()
+            .and_then(|graph| graph.participant_for(&after.grant));
+        let widened = match before {
+            None => true,
+            Some(before) => {
+                before.visibility != VISIBILITY_GRAPH && after.visibility == VISIBILITY_GRAPH
+            }
+        };
+        if widened {
+            count += 1;
+            visit(Widening::Route {
+                route: after.route,
+                grant: after.grant,
+            });
+        }
+    }
+    Ok(count)
+}
+
+/// The kernel-plan half of [`widenings`].
+///
+/// The plan is derived from the grants compared above, but the root installs
+/// the plan, not the grants; a plan that drifted from them would otherwise
+/// carry authority no comparison saw.
+/// The digest a release signs to approve the widenings from `old` to `new`,
+/// and no others.
+///
+/// Every [`Widening`] in [`widenings`]' order, each tagged by its variant with
+/// its names length-prefixed, so two lists share a digest only if they are the
+/// same list. An empty list has a digest too, and approves only a generation
+/// that widens nothing.
+pub fn widenings_digest(old: &Side<'_>, new: &Side<'_>) -> Result<[u8; 32], DiffError> {
+    let mut hasher = Sha256::new();
+    hasher.update(WIDENINGS_DOMAIN);
+    widenings(old, new, |widening| absorb_widening(&mut hasher, &widening))?;
+    Ok(hasher.finalize())
+}
+
+const WIDENINGS_DOMAIN: &[u8] = b"slime-authority-widenings-v1";
+
+fn absorb_widening(hasher: &mut Sha256, widening: &Widening<'_>) {
+    fn name(hasher: &mut Sha256, text: &str) {
+        hasher.update(&(text.len() as u32).to_le_bytes());
+        hasher.update(text.as_bytes());
+    }
+    match *widening {
+        Widening::Instance {
+            name: instance,
+            rights,
+        } => {
+            hasher.update(&[0]);
+            name(hasher, instance);
+            for kind in rights.kinds {
+                hasher.update(&kind.to_le_bytes());
+            }
+            hasher.update(&rights.undeclared.to_le_bytes());
+        }
+        Widening::Capability {
+            process,
+            object,
+            rights,
+        } => {
+            hasher.update(&[1]);
+            name(hasher, process);
+            name(hasher, object);
+            hasher.update(&rights.to_le_bytes());
+        }
+        Widening::Mapping {
+            process,
+            object,
+            rights,
+        } => {
+            hasher.update(&[2]);
+            name(hasher, process);
+            name(hasher, object);
+            hasher.update(&rights.to_le_bytes());
+        }
+        Widening::Service { process, service } => {
+            hasher.update(&[3]);
+            name(hasher, process);
+            hasher.update(&service.to_le_bytes());
+        }
+        Widening::Quota { process } => {
+            hasher.update(&[4]);
+            name(hasher, process);
+        }
+        Widening::Schedule { thread } => {
+            hasher.update(&[5]);
+            name(hasher, thread);
+        }
+        Widening::Route { route, grant } => {
+            hasher.update(&[6]);
+            hasher.update(&route);
+            hasher.update(&grant);
+        }
+    }
+}
+
+fn plan_widenings<'a>(
+    old: Option<&Generation<'a>>,
+    new: &Generation<'a>,
+    visit: &mut impl FnMut(Widening<'a>),
+) -> Result<usize, DiffError> {
+    let mut count = 0;
+    let mut report = |widening| {
+        count += 1;
+        visit(widening);
+    };
+
+    for index in 0..new.cap_binding_count() {
+        let binding = new.cap_binding(index)?;
+        let process = new.process(binding.process)?.name;
+        let object = new.kernel_object_record(binding.object)?.name;
+        let before = plan_rights(
+            old,
+            process,
+            object,
+            |generation| generation.cap_binding_count(),
+            |generation, index| {
+                let binding = generation.cap_binding(index)?;
+                Ok((binding.process, binding.object, binding.rights))
+            },
+        )?;
+        let rights = binding.rights & !before;
+        if rights != 0 {
+            report(Widening::Capability {
+                process,
+                object,
+                rights,
+            });
+        }
+    }
+
+    for index in 0..new.mapping_count() {
+        let mapping = new.mapping(index)?;
+        let process = new.process(mapping.process)?.name;
+        let object = new.kernel_object_record(mapping.object)?.name;
+        let before = plan_rights(
+            old,
+            process,
+            object,
+            |generation| generation.mapping_count(),
+            |generation, index| {
+                let mapping = generation.mapping(index)?;
+                Ok((mapping.process, mapping.object, mapping.rights))
+            },
+        )?;
+        let rights = mapping.rights & !before;
+        if rights != 0 {
+            report(Widening::Mapping {
+                process,
+                object,
+                rights,
+            });
+        }
+    }
+
+    for index in 0..new.service_binding_count() {
+        let binding = new.service_binding(index)?;
+        let process = new.process(binding.process)?.name;
+        let mut held = false;
+        if let Some((old, old_process)) = process_named(old, process)? {
+            for old_index in 0..old.service_binding_count() {
+                let before = old.service_binding(old_index)?;
+                held |= before.process == old_process && before.service == binding.service;
+            }
+        }
+        if !held {
+            report(Widening::Service {
+                process,
+                service: binding.service,
+            });
+        }
+    }
+
+    for index in 0..new.process_count() {
+        let process = new.process(index)?;
+        let after = new.resource_quota(process.quota)?;
+        let wider = match process_named(old, process.name)? {
+            Some((old, old_process)) => quota_exceeds(
+                &after,
+                &old.resource_quota(old.process(old_process)?.quota)?,
+            ),
+            None => true,
+        };
+        if wider {
+            report(Widening::Quota {
+                process: process.name,
+            });
+        }
+    }
+
+    for index in 0..new.schedule_count() {
+        let after = new.schedule(index)?;
+        let thread = new.thread(after.thread)?.name;
+        let mut before = None;
+        if let Some(old) = old {
+            for old_index in 0..old.schedule_count() {
+                let schedule = old.schedule(old_index)?;
+                if old.thread(schedule.thread)?.name == thread {
+                    before = Some(schedule);
+                    break;
+                }
+            }
+        }
+        if before.is_none_or(|before| schedule_exceeds(&after, &before)) {
+            report(Widening::Schedule { thread });
+        }
+    }
+    Ok(count)
+}
+
+/// The rights `generation`'s plan gives the process named `process` over the
+/// kernel object named `object`, through the `rows` records that `record` reads
+/// as `(process, object, rights)`: the capability bindings or the mappings.
+fn plan_rights(
+    generation: Option<&Generation<'_>>,
+    process: &str,
+    object: &str,
+    rows: impl Fn(&Generation<'_>) -> usize,
+    record: impl Fn(&Generation<'_>, usize) -> Result<(usize, usize, Rights), DecodeError>,
+) -> Result<Rights, DiffError> {
+    let Some((generation, process)) = process_named(generation, process)? else {
+        return Ok(0);
+    };
+    let mut held = 0;
+    for index in 0..rows(generation) {
+        let (holder, target, rights) = record(generation, index)?;
+        if holder == process && generation.kernel_object_record(target)?.name == object {
+            held |= rights;
+        }
+    }
+    Ok(held)
+}
+
+/// The index of the process named `name`, if `generation` has a plan and it
+/// plans one, with the plan it indexes.
+fn process_named<'g, 'a>(
+    generation: Option<&'g Generation<'a>>,
+    name: &str,
+) -> Result<Option<(&'g Generation<'a>, usize)>, DiffError> {
+    let Some(generation) = generation else {
+        return Ok(None);
+    };
+    for index in 0..generation.process_count() {
+        if generation.process(index)?.name == name {
+            return Ok(Some((generation, index)));
+        }
+    }
+    Ok(None)
+}
+
+/// Whether `new` lets its process hold more of any one resource than `old`.
+fn quota_exceeds(new: &ResourceQuota<'_>, old: &ResourceQuota<'_>) -> bool {
+    new.cnode_count > old.cnode_count
+        || new.tcb_count > old.tcb_count
+        || new.endpoint_count > old.endpoint_count
+        || new.notification_count > old.notification_count
+        || new.frame_count > old.frame_count
+        || new.page_table_count > old.page_table_count
+        || new.mapping_count > old.mapping_count
+        || new.irq_count > old.irq_count
+        || new.cslot_count > old.cslot_count
+        || new.untyped_bytes > old.untyped_bytes
+        || new.dynamic_reserve_bytes > old.dynamic_reserve_bytes
+}
+
+/// Whether `new` schedules its thread above `old`, lets it hand out higher
+/// priorities, or gives it a larger share of the processor. The share is
+/// compared crosswise so neither period needs to divide the other.
+fn schedule_exceeds(new: &Schedule<'_>, old: &Schedule<'_>) -> bool {
+    new.priority > old.priority
+        || new.max_controlled_priority > old.max_controlled_priority
+        || u128::from(new.budget_us) * u128::from(old.period_us)
+            > u128::from(old.budget_us) * u128::from(new.period_us)
+}
+
+/// Everything instance `index` holds, from grants and minted bindings alike.
+///
+/// A v2–v4 grant does not say which of its ends it binds, which is a matter of
+/// its kind, so both ends are charged with it.
+fn authority(side: &Side<'_>, index: usize) -> Result<Authority, DiffError> {
+    let mut held = Authority::default();
+    match side {
+        Side::Planned(generation) => {
+            for grant_index in 0..generation.grant_count() {
+                let grant = generation.grant(grant_index)?;
+                if generation.grant_applies_to_instance(grant, index) {
+                    held.hold(Some(grant.capability_kind), grant.rights);
+                }
+            }
+            for binding_index in 0..generation.minted_binding_count() {
+                let binding = generation.minted_binding(binding_index)?;
+                if binding.holder == index {
+                    held.hold(Some(binding.capability_kind), binding.rights);
+                }
+            }
+        }
+        Side::Catalogue(generation) => {
+            for grant_index in 0..generation.grant_count() {
+                let grant = generation.grant(grant_index)?;
+                if grant.source == index || grant.target == index {
+                    held.hold(None, grant.rights);
+                }
+            }
+        }
+    }
+    Ok(held)
+}
+
+/// Match two record tables by key and report every difference, in the order
+/// the module documents.
+///
+/// A key need not be unique: grants are ordered by name and endpoints, so one
+/// name may cover several. Records equal on both sides match first, and what
+/// is left under each key pairs up in table order, the extras on either side
+/// removed or added. Quadratic in the tables times the records sharing a key,
+/// both bounded by the decoder, which buys a diff with no allocation and no
+/// dependence on either table's sort.
+fn compare<T, K>(
+    old_len: usize,
+    old: impl Fn(usize) -> Result<T, DiffError>,
+    new_len: usize,
+    new: impl Fn(usize) -> Result<T, DiffError>,
+    key: impl Fn(&T) -> K,
+    visit: &mut impl FnMut(Delta<T>),
+) -> Result<(), DiffError>
+where
+    T: Copy + PartialEq,
+    K: PartialEq,
+{
+    let old_left = |index: usize, record: &T| left(record, index, &old, new_len, &new);
+    let new_left = |index: usize, record: &T| left(record, index, &new, old_len, &old);
+
+    for old_index in 0..old_len {
+        let before = old(old_index)?;
+        if !old_left(old_index, &before)? {
+            continue;
+        }
+        let mut rank = 0;
+        for earlier in 0..old_index {
+            let record = old(earlier)?;
+            if key(&record) == key(&before) && old_left(earlier, &record)? {
+                rank += 1;
+            }
+        }
+        let mut matched = None;
+        for new_index in 0..new_len {
+            let after = new(new_index)?;
+            if key(&after) == key(&before) && new_left(new_index, &after)? {
+                if rank == 0 {
+                    matched = Some(after);
+                    break;
+                }
+                rank -= 1;
+            }
+        }
+        visit(match matched {
+            None => Delta::Removed(before),
+            Some(after) => Delta::Changed {
+                old: before,
+                new: after,
+            },
+        });
+    }
+    for new_index in 0..new_len {
+        let after = new(new_index)?;
+        if !new_left(new_index, &after)? {
+            continue;
+        }
+        let mut rank = 0;
+        for earlier in 0..new_index {
+            let record = new(earlier)?;
+            if key(&record) == key(&after) && new_left(earlier, &record)? {
+                rank += 1;
+            }
+        }
+        let mut paired = 0;
+        for old_index in 0..old_len {
+            let record = old(old_index)?;
+            if key(&record) == key(&after) && old_left(old_index, &record)? {
+                paired += 1;
+            }
+        }
+        if rank >= paired {
+            visit(Delta::Added(after));
+        }
+    }
+    Ok(())
+}
+
+/// Whether the `index`th record of `table` is left to pair: equal records
+/// match in table order, so it is left once its equals earlier in its own
+/// table use up every equal on the `other` side.
+fn left<T: PartialEq>(
+    record: &T,
+    index: usize,
+    table: &impl Fn(usize) -> Result<T, DiffError>,
+    other_len: usize,
+    other: &impl Fn(usize) -> Result<T, DiffError>,
+) -> Result<bool, DiffError> {
+    let mut earlier = 0;
+    for before in 0..index {
+        if table(before)? == *record {
+            earlier += 1;
+        }
+    }
+    let mut equals = 0;
+    for candidate in 0..other_len {
+        if other(candidate)? == *record {
+            equals += 1;
+        }
+    }
+    Ok(earlier >= equals)
+}
+
+fn executable<'a>(side: &Side<'a>, index: usize) -> Result<ExecutableView<'a>, DiffError> {
+    let (executable, digest) = match side {
+        Side::Planned(generation) => {
+            let executable = generation.executable(index)?;
+            (executable, generation.object(executable.object)?.digest)
+        }
+        Side::Catalogue(generation) => {
+            let executable = generation.executable(index)?;
+            (executable, generation.object(executable.object)?.digest)
+        }
+    };
+    Ok(ExecutableView {
+        name: executable.name,
+        digest,
+        role: executable.role,
+        spawn_budget: executable.spawn_budget,
+    })
+}
+
+fn instance<'a>(side: &Side<'a>, index: usize) -> Result<InstanceView<'a>, DiffError> {
+    let (name, executable_index, owner, autostart, health) = match side {
+        Side::Planned(generation) => {
+            let instance = generation.instance(index)?;
+            (
+                instance.name,
+                instance.executable,
+                instance.owner,
+                instance.autostart,
+                instance.health,
+            )
+        }
+        Side::Catalogue(generation) => {
+            let instance = generation.instance(index)?;
+            (
+                instance.name,
+                instance.executable,
+                instance.owner,
+                instance.autostart,
+                instance.health,
+            )
+        }
+    };
+    Ok(InstanceView {
+        name,
+        executable: executable(side, executable_index)?.name,
+        owner: match owner {
+            InstanceOwner::Root => None,
+            InstanceOwner::Instance(owner) => Some(instance(side, owner)?.name),
+        },
+        autostart,
+        health,
+    })
+}
+
+fn grant<'a>(side: &Side<'a>, index: usize) -> Result<GrantView<'a>, DiffError> {
+    Ok(match side {
+        Side::Planned(generation) => {
+            let grant = generation.grant(index)?;
+            GrantView {
+                name: grant.name,
+                source: endpoint(generation, grant.source)?,
+                target: endpoint(generation, grant.target)?,
+                rights: grant.rights,
+                transferable: grant.transferable,
+                capability_kind: Some(grant.capability_kind),
+            }
+        }
+        Side::Catalogue(generation) => {
+            let grant = generation.grant(index)?;
+            GrantView {
+                name: grant.name,
+                source: generation.instance(grant.source)?.name,
+                target: generation.instance(grant.target)?.name,
+                rights: grant.rights,
+                transferable: grant.transferable,
+                capability_kind: None,
+            }
+        }
+    })
+}
+
+fn endpoint<'a>(
+    generation: &Generation<'a>,
+    endpoint: GrantEndpoint,
+) -> Result<&'a str, DiffError> {
+    Ok(match endpoint {
+        GrantEndpoint::Executable(index) => generation.executable(index)?.name,
+        GrantEndpoint::Instance(index) => generation.instance(index)?.name,
+    })
+}
+
+fn state<'a>(side: &Side<'a>, index: usize) -> Result<StateView<'a>, DiffError> {
+    let state = match side {
+        Side::Planned(generation) => generation.state(index)?,
+        Side::Catalogue(generation) => generation.state(index)?,
+    };
+    Ok(StateView {
+        name: state.name,
+        owner: instance(side, state.owner)?.name,
+        schema_version: state.schema_version,
+        policy: state.policy,
+    })
+}
+
+fn route(graph: Option<&FabricGraph<'_>>, index: usize) -> Result<RouteView, DiffError> {
+    let graph = graph.ok_or(DiffError::FabricGraph(
+        fabric_graph::DecodeError::MissingReference,
+    ))?;
+    let route = graph.route(index).ok_or(DiffError::FabricGraph(
+        fabric_graph::DecodeError::MissingReference,
+    ))?;
+    let schema = graph
+        .schema(route.schema_index as usize)
+        .ok_or(DiffError::FabricGraph(
+            fabric_graph::DecodeError::MissingReference,
+        ))?;
+    Ok(RouteView {
+        identity: route.route_identity,
+        schema: schema.identity,
+        contract_kind: route.contract_kind,
+        participant_count: route.participant_count,
+    })
+}
+
+fn participant(
+    graph: Option<&FabricGraph<'_>>,
+    index: usize,
+) -> Result<ParticipantView, DiffError> {
+    let graph = graph.ok_or(DiffError::FabricGraph(
+        fabric_graph::DecodeError::MissingReference,
+    ))?;
+    let participant = graph.participant(index).ok_or(DiffError::FabricGraph(
+        fabric_graph::DecodeError::MissingReference,
+    ))?;
+    let route = graph
+        .route(participant.route_index as usize)
+        .ok_or(DiffError::FabricGraph(
+            fabric_graph::DecodeError::MissingReference,
+        ))?;
+    Ok(ParticipantView {
+        grant: participant.grant_identity,
+        component: participant.component_identity,
+        route: route.route_identity,
+        direction: participant.direction,
+        visibility: participant.visibility,
+        qos: participant.qos,
+    })
+}
+
+fn quota(budget: Option<&SharedBufferBudget<'_>>, index: usize) -> Result<HolderQuota, DiffError> {
+    budget
+        .and_then(|budget| budget.holder(index))
+        .ok_or(DiffError::SharedBufferBudget(
+            shared_buffer_budget::DecodeError::BadBounds,
+        ))
+}
+
+/// The payload of the resource object whose bytes begin with `magic`, found
+/// the way admission finds the fabric graph and the shared-buffer budget.
+fn resource<'a>(side: &Side<'a>, magic: &[u8]) -> Result<Option<&'a [u8]>, DiffError> {
+    let count = match side {
+        Side::Planned(generation) => generation.object_count(),
+        Side::Catalogue(generation) => generation.object_count(),
+    };
+    for index in 0..count {
+        let object = match side {
+            Side::Planned(generation) => generation.object(index)?,
+            Side::Catalogue(generation) => generation.object(index)?,
+        };
+        if object.kind == KIND_RESOURCE && object.bytes.starts_with(magic) {
+            return Ok(Some(object.bytes));
+        }
+    }
+    Ok(None)
+}
+
+fn fabric_graph_of<'a>(side: &Side<'a>) -> Result<Option<FabricGraph<'a>>, DiffError> {
+    resource(side, &fabric_graph::MAGIC)?
+        .map(FabricGraph::decode)
+        .transpose()
+        .map_err(DiffError::FabricGraph)
+}
+
+fn shared_buffer_budget_of<'a>(
+    side: &Side<'a>,
+) -> Result<Option<SharedBufferBudget<'a>>, DiffError> {
+    resource(side, &shared_buffer_budget::MAGIC)?
+        .map(SharedBufferBudget::decode)
+        .transpose()
+        .map_err(DiffError::SharedBufferBudget)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::{
+        Authority, Change, Delta, DiffError, GrantView, Side, WIDENINGS_DOMAIN, Widening,
+        absorb_widening, compare, decode, diff, fabric_graph_of, grant, instance, quota_exceeds,
+        schedule_exceeds, shared_buffer_budget_of, state, widenings,
+    };
+    use crate::generation::{
+        CapabilityKind, DecodeError, HEADER_LEN, InstanceHealth, MAGIC_V2, MAGIC_V4, MAGIC_V5,
+        RIGHT_DIRECTORY_READ, RIGHT_EXEC, RIGHT_RECV, RIGHT_SEND, RIGHT_SPAWN, RIGHT_TRANSFER,
+        ResourceQuota, Schedule, generation_identity,
+    };
+    use crate::sha256::Sha256;
+    use alloc::vec::Vec;
+
+    /// The v3 generation `slime-root` keeps as its fixture: the one blob in
+    /// the tree from before v5.
+    const V3: &[u8] = include_bytes!("../../slime-root/fixtures/generation.bin");
+
+    fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    fn u64_at(bytes: &[u8], offset: usize) -> usize {
+        u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap()) as usize
+    }
+
+    fn put_u32(bytes: &mut [u8], offset: usize, value: u32) {
+        bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_u64(bytes: &mut [u8], offset: usize, value: usize) {
+        bytes[offset..offset + 8].copy_from_slice(&(value as u64).to_le_bytes());
+    }
+
+    fn reidentify(bytes: &mut [u8]) {
+        let identity = generation_identity(bytes);
+        bytes[24..56].copy_from_slice(&identity);
+    }
+
+    /// The v3 fixture as v2 wrote it: grant rights in 32 bits, with
+    /// `transferable` right behind them.
+    fn as_v2(v3: &[u8]) -> Vec<u8> {
+        let mut bytes = v3.to_vec();
+        bytes[..8].copy_from_slice(&MAGIC_V2);
+        put_u32(&mut bytes, 8, 2);
+        let (count, start) = (u32_at(v3, 124) as usize, u64_at(v3, 160));
+        for offset in (0..count).map(|index| start + index * 32) {
+            let rights = u32::try_from(u64_at(v3, offset + 12)).unwrap();
+            let transferable = u32_at(v3, offset + 20);
+            put_u32(&mut bytes, offset + 12, rights);
+            put_u32(&mut bytes, offset + 16, transferable);
+            bytes[offset + 20..offset + 32].fill(0);
+        }
+        reidentify(&mut bytes);
+        bytes
+    }
+
+    /// The v3 fixture as v4 wrote it: each component split into an executable
+    /// and one root-owned, autostarted instance of it.
+    fn as_v4(v3: &[u8]) -> Vec<u8> {
+        let [objects, components, dependencies, grants, states, health] =
+            core::array::from_fn(|field| u32_at(v3, 112 + field * 4) as usize);
+        let [
+            object_at,
+            component_at,
+            dependency_at,
+            grant_at,
+            state_at,
+            health_at,
+            strings_at,
+            strings_len,
+            payload_at,
+            total_len,
+        ] = core::array::from_fn(|field| u64_at(v3, 136 + field * 8));
+        let required = |component: usize| {
+            (0..health).any(|index| u32_at(v3, health_at + index * 4) as usize == component)
+        };
+
+        let mut bytes = Vec::from(&v3[..24]);
+        bytes[..8].copy_from_slice(&MAGIC_V4);
+        put_u32(&mut bytes, 8, 4);
+        bytes.extend_from_slice(&[0; 32]);
+        bytes.extend_from_slice(&v3[56..100]);
+        bytes.extend_from_slice(&[0; 4]);
+        bytes.extend_from_slice(&v3[104..112]);
+        for count in [
+            objects,
+            components,
+            components,
+            dependencies,
+            0,
+            grants,
+            states,
+            health,
+        ] {
+            bytes.extend_from_slice(&(count as u32).to_le_bytes());
+        }
+        bytes.resize(256, 0);
+        bytes.extend_from_slice(&v3[object_at..component_at]);
+        let executable_at = bytes.len();
+        for component in (0..components).map(|index| component_at + index * 32) {
+            let mut record = [0u8; 32];
+            record[..12].copy_from_slice(&v3[component..component + 12]);
+            record[12..16].copy_from_slice(&v3[component + 20..component + 24]);
+            bytes.extend_from_slice(&record);
+        }
+        let instance_at = bytes.len();
+        for index in 0..components {
+            let component = component_at + index * 32;
+            let mut record = [0u8; 48];
+            record[..4].copy_from_slice(&v3[component..component + 4]);
+            put_u32(&mut record, 4, index as u32);
+            put_u32(&mut record, 16, 1);
+            record[20..28].copy_from_slice(&v3[component + 12..component + 20]);
+            put_u32(&mut record, 36, u32::from(required(index)));
+            bytes.extend_from_slice(&record);
+        }
+        let shift = bytes.len() - dependency_at;
+        bytes.extend_from_slice(&v3[dependency_at..]);
+        // Everything after the components moves by the instance table, and
+        // the empty binding table sits where the grants begin.
+        let offsets = [
+            object_at,
+            executable_at,
+            instance_at,
+            dependency_at + shift,
+            grant_at + shift,
+            grant_at + shift,
+            state_at + shift,
+            health_at + shift,
+            strings_at + shift,
+            strings_len,
+            payload_at + shift,
+            total_len + shift,
+        ];
+        for (field, offset) in offsets.into_iter().enumerate() {
+            put_u64(&mut bytes, 144 + field * 8, offset);
+        }
+        for index in 0..objects {
+            let record = 256 + index * 64 + 8;
+            let payload = u64_at(&bytes, record);
+            put_u64(&mut bytes, record, payload + shift);
+        }
+        reidentify(&mut bytes);
+        bytes
+    }
+
+    fn changes(old: &Side<'_>, new: &Side<'_>) -> usize {
+        diff(old, new, |_| {}).unwrap()
+    }
+
+    fn widened(old: &Side<'_>, new: &Side<'_>) -> usize {
+        widenings(old, new, |_| {}).unwrap()
+    }
+
+    type Row<'a> = (&'a str, u32);
+
+    fn run<'a>(old: &[Row<'a>], new: &[Row<'a>]) -> ([Option<Delta<Row<'a>>>; 8], usize) {
+        let mut seen = [None; 8];
+        let mut count = 0;
+        compare(
+            old.len(),
+            |index| Ok::<_, DiffError>(old[index]),
+            new.len(),
+            |index| Ok(new[index]),
+            |record| record.0,
+            &mut |delta| {
+                seen[count] = Some(delta);
+                count += 1;
+            },
+        )
+        .unwrap();
+        (seen, count)
+    }
+
+    #[test]
+    fn identical_tables_produce_nothing_whatever_their_order() {
+        let (_, count) = run(&[("a", 1), ("b", 2)], &[("b", 2), ("a", 1)]);
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn removals_and_changes_in_old_order_then_additions_in_new_order() {
+        let (seen, count) = run(
+            &[("keep", 1), ("gone", 2), ("moved", 3)],
+            &[("fresh", 9), ("moved", 4), ("keep", 1), ("later", 5)],
+        );
+        assert_eq!(count, 4);
+        assert_eq!(seen[0], Some(Delta::Removed(("gone", 2))));
+        assert_eq!(
+            seen[1],
+            Some(Delta::Changed {
+                old: ("moved", 3),
+                new: ("moved", 4),
+            })
+        );
+        assert_eq!(seen[2], Some(Delta::Added(("fresh", 9))));
+        assert_eq!(seen[3], Some(Delta::Added(("later", 5))));
+    }
+
+    #[test]
+    fn a_decode_failure_stops_the_diff() {
+        let result = compare(
+            1,
+            |_| {
+                Err::<(&str, u32), _>(DiffError::Generation(
+                    crate::generation::DecodeError::BadIndex,
+                ))
+            },
+            0,
+            |_| unreachable!(),
+            |record| record.0,
+            &mut |_| panic!("nothing is reported past a failure"),
+        );
+        assert!(matches!(result, Err(DiffError::Generation(_))));
+    }
+
+    /// Keys repeat — grants are ordered by name and endpoints — so equal
+    /// records match first and what is left pairs in order.
+    #[test]
+    fn records_sharing_a_key_pair_up_after_equal_ones_match() {
+        let (_, count) = run(&[("rpc", 1), ("rpc", 2)], &[("rpc", 2), ("rpc", 1)]);
+        assert_eq!(count, 0);
+        let (seen, count) = run(
+            &[("rpc", 1), ("rpc", 2), ("rpc", 3)],
+            &[("rpc", 4), ("rpc", 2)],
+        );
+        assert_eq!(count, 2);
+        assert_eq!(
+            seen[0],
+            Some(Delta::Changed {
+                old: ("rpc", 1),
+                new: ("rpc", 4),
+            })
+        );
+        assert_eq!(seen[1], Some(Delta::Removed(("rpc", 3))));
+        let (seen, count) = run(&[("rpc", 1)], &[("rpc", 5), ("rpc", 1), ("rpc", 6)]);
+        assert_eq!(count, 2);
+        assert_eq!(seen[0], Some(Delta::Added(("rpc", 5))));
+        assert_eq!(seen[1], Some(Delta::Added(("rpc", 6))));
+    }
+
+    /// Each retired magic reaches its own reader rather than the v6 decoder's
+    /// refusal, and anything else still reaches the v6 decoder.
+    #[test]
+    fn every_format_since_v2_reaches_a_reader() {
+        let mut bytes = [0u8; HEADER_LEN];
+        bytes[..8].copy_from_slice(&MAGIC_V5);
+        bytes[8..12].copy_from_slice(&5u32.to_le_bytes());
+        bytes[12..16].copy_from_slice(&(HEADER_LEN as u32).to_le_bytes());
+        assert!(!matches!(
+            decode(&bytes).err(),
+            Some(DiffError::Generation(
+                DecodeError::UnsupportedVersion | DecodeError::BadMagic
+            ))
+        ));
+        bytes[..8].copy_from_slice(&MAGIC_V4);
+        bytes[8..12].copy_from_slice(&4u32.to_le_bytes());
+        bytes[12..16].copy_from_slice(&256u32.to_le_bytes());
+        assert_eq!(
+            decode(&bytes).err(),
+            Some(DiffError::Generation(DecodeError::BadIdentity))
+        );
+        bytes[..8].copy_from_slice(b"NOTSLIME");
+        assert_eq!(
+            decode(&bytes).err(),
+            Some(DiffError::Generation(DecodeError::BadMagic))
+        );
+    }
+
+    #[test]
+    fn a_v3_generation_reads_each_component_as_an_executable_and_its_instance() {
+        let side = decode(V3).unwrap();
+        assert!(matches!(side, Side::Catalogue(_)));
+        assert_eq!((side.version(), side.number()), (3, 1));
+        assert_eq!((side.executable_count(), side.instance_count()), (25, 25));
+
+        let named = |name| {
+            (0..side.instance_count())
+                .map(|index| instance(&side, index).unwrap())
+                .find(|view| view.name == name)
+                .unwrap()
+        };
+        let init = named("init");
+        assert_eq!(
+            (init.executable, init.owner, init.autostart),
+            ("init", None, true)
+        );
+        assert_eq!(init.health, InstanceHealth::Required);
+        assert_eq!(named("sysinfo").health, InstanceHealth::Optional);
+
+        let output = (0..side.grant_count())
+            .map(|index| grant(&side, index).unwrap())
+            .find(|view| view.name == "console-output")
+            .unwrap();
+        assert_eq!((output.source, output.target), ("console", "dango"));
+        assert_eq!(output.rights, RIGHT_SEND | RIGHT_TRANSFER);
+        assert!(output.transferable);
+        assert_eq!(output.capability_kind, None);
+        let history = state(&side, 0).unwrap();
+        assert_eq!((history.name, history.owner), ("dango-history", "dango"));
+
+        assert!(fabric_graph_of(&side).unwrap().is_some());
+        assert!(shared_buffer_budget_of(&side).unwrap().is_some());
+    }
+
+    /// Five grants share `generation-management-rpc`, one per client, so a
+    /// diff that matched by name alone would report four of them changed.
+    #[test]
+    fn a_v3_generation_diffs_against_itself_to_nothing() {
+        let side = decode(V3).unwrap();
+        assert_eq!(changes(&side, &side), 0);
+        assert_eq!(widened(&side, &side), 0);
+    }
+
+    #[test]
+    fn v2_v3_and_v4_encodings_of_one_generation_diff_to_nothing() {
+        let (v2, v4) = (as_v2(V3), as_v4(V3));
+        let sides = [
+            decode(&v2).unwrap(),
+            decode(V3).unwrap(),
+            decode(&v4).unwrap(),
+        ];
+        assert_eq!(sides.each_ref().map(Side::version), [2, 3, 4]);
+        for old in &sides {
+            for new in &sides {
+                assert_eq!(changes(old, new), 0);
+                assert_eq!(widened(old, new), 0);
+            }
+        }
+    }
+
+    /// A grant pointed at another component is one change, and its new end
+    /// widens by rights no kind can be named for.
+    #[test]
+    fn a_retargeted_v3_grant_is_a_change_that_widens_its_new_end() {
+        let old = decode(V3).unwrap();
+        let Side::Catalogue(catalogue) = &old else {
+            unreachable!()
+        };
+        let (index, dango) = (
+            (0..catalogue.grant_count())
+                .find(|&index| catalogue.grant(index).unwrap().name == "spawn-service-sysinfo")
+                .unwrap(),
+            (0..catalogue.instance_count())
+                .find(|&index| catalogue.instance(index).unwrap().name == "dango")
+                .unwrap(),
+        );
+        let mut bytes = V3.to_vec();
+        put_u32(&mut bytes, u64_at(V3, 160) + index * 32 + 8, dango as u32);
+        reidentify(&mut bytes);
+        let new = decode(&bytes).unwrap();
+
+        let mut seen = Vec::new();
+        assert_eq!(diff(&old, &new, |change| seen.push(change)).unwrap(), 1);
+        let Change::Grant(Delta::Changed {
+            old: before,
+            new: after,
+        }) = seen[0]
+        else {
+            panic!("expected a changed grant, saw {:?}", seen[0]);
+        };
+        assert_eq!((before.target, after.target), ("sysinfo", "dango"));
+
+        let mut widened = Vec::new();
+        widenings(&old, &new, |widening| widened.push(widening)).unwrap();
+        let [Widening::Instance { name, rights }] = widened[..] else {
+            panic!("expected one instance to widen, saw {widened:?}");
+        };
+        assert_eq!(name, "dango");
+        assert_eq!(rights.undeclared(), RIGHT_EXEC | RIGHT_SPAWN);
+        assert_eq!(rights.rights(CapabilityKind::Executable), 0);
+    }
+
+    fn digest_of(list: &[Widening<'_>]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(WIDENINGS_DOMAIN);
+        for widening in list {
+            absorb_widening(&mut hasher, widening);
+        }
+        hasher.finalize()
+    }
+
+    /// An approval covers one list: a different widening, the same ones in
+    /// another order, or one more or fewer all digest differently, and names
+    /// cannot be slid between fields to forge a match.
+    #[test]
+    fn a_widening_digest_names_exactly_one_list() {
+        let service = Widening::Service {
+            process: "log",
+            service: 2,
+        };
+        let quota = Widening::Quota { process: "log" };
+        let list = [service, quota];
+        assert_eq!(digest_of(&list), digest_of(&list));
+        assert_ne!(digest_of(&list), digest_of(&[quota, service]));
+        assert_ne!(digest_of(&list), digest_of(&[service]));
+        assert_ne!(digest_of(&[]), digest_of(&[service]));
+        assert_ne!(
+            digest_of(&[service]),
+            digest_of(&[Widening::Service {
+                process: "log",
+                service: 3,
+            }])
+        );
+        assert_ne!(
+            digest_of(&[Widening::Capability {
+                process: "ab",
+                object: "c",
+                rights: 1,
+            }]),
+            digest_of(&[Widening::Capability {
+                process: "a",
+                object: "bc",
+                rights: 1,
+            }])
+        );
+        assert_ne!(
+            digest_of(&[Widening::Capability {
+                process: "a",
+                object: "b",
+                rights: 1,
+            }]),
+            digest_of(&[Widening::Mapping {
+                process: "a",
+                object: "b",
+                rights: 1,
+            }])
+        );
+        assert_ne!(
+            digest_of(&[Widening::Instance {
+                name: "a",
+                rights: Authority::default(),
+            }]),
+            digest_of(&[Widening::Quota { process: "a" }])
+        );
+    }
+
+    #[test]
+    fn grant_rights_split_into_widened_and_narrowed() {
+        let old = GrantView {
+            name: "rpc",
+            source: "client",
+            target: "server",
+            rights: RIGHT_SEND | RIGHT_TRANSFER,
+            transferable: true,
+            capability_kind: Some(CapabilityKind::Endpoint),
+        };
+        let new = GrantView {
+            rights: RIGHT_SEND | RIGHT_RECV,
+            transferable: false,
+            ..old
+        };
+        assert_eq!(new.widened_from(&old), RIGHT_RECV);
+        assert_eq!(new.narrowed_from(&old), RIGHT_TRANSFER);
+        assert_eq!(old.widened_from(&old), 0);
+    }
+
+    /// The same right over a different kind of object is new authority: an
+    /// instance that could transfer an endpoint and now transfers a directory
+    /// has widened, even though the union of its bits has not.
+    #[test]
+    fn authority_widens_kind_by_kind() {
+        let mut old = Authority::default();
+        old.hold(Some(CapabilityKind::Endpoint), RIGHT_SEND | RIGHT_TRANSFER);
+        old.hold(Some(CapabilityKind::Directory), RIGHT_DIRECTORY_READ);
+        let mut new = Authority::default();
+        new.hold(Some(CapabilityKind::Endpoint), RIGHT_SEND);
+        new.hold(
+            Some(CapabilityKind::Directory),
+            RIGHT_DIRECTORY_READ | RIGHT_TRANSFER,
+        );
+
+        let widened = new.widened_from(&old);
+        assert_eq!(widened.rights(CapabilityKind::Directory), RIGHT_TRANSFER);
+        assert_eq!(widened.rights(CapabilityKind::Endpoint), 0);
+        assert!(!widened.is_empty());
+        assert_eq!(
+            old.widened_from(&new).rights(CapabilityKind::Endpoint),
+            RIGHT_TRANSFER
+        );
+        assert!(new.widened_from(&new).is_empty());
+    }
+
+    /// Rights a retired grant held over no declared kind cover nothing a kind
+    /// is named for, so a baseline of them widens by all the new side holds.
+    #[test]
+    fn undeclared_rights_neither_cover_nor_are_covered_by_a_kind() {
+        let mut old = Authority::default();
+        old.hold(None, RIGHT_SEND | RIGHT_RECV);
+        let mut new = Authority::default();
+        new.hold(Some(CapabilityKind::Endpoint), RIGHT_SEND);
+        assert_eq!(
+            new.widened_from(&old).rights(CapabilityKind::Endpoint),
+            RIGHT_SEND
+        );
+        assert_eq!(old.widened_from(&new).undeclared(), RIGHT_SEND | RIGHT_RECV);
+        assert!(old.widened_from(&old).is_empty());
+    }
+
+    #[test]
+    fn any_larger_count_widens_a_quota() {
+        let old = ResourceQuota {
+            name: "quota",
+            owner_process: 0,
+            cnode_count: 1,
+            tcb_count: 1,
+            endpoint_count: 2,
+            notification_count: 1,
+            frame_count: 16,
+            page_table_count: 4,
+            mapping_count: 16,
+            irq_count: 0,
+            cslot_count: 32,
+            untyped_bytes: 4096,
+            dynamic_reserve_bytes: 0,
+            flags: 0,
+        };
+        assert!(!quota_exceeds(&old, &old));
+        let fewer_frames = ResourceQuota {
+            frame_count: 8,
+            ..old
+        };
+        assert!(!quota_exceeds(&fewer_frames, &old));
+        let one_irq = ResourceQuota {
+            frame_count: 8,
+            irq_count: 1,
+            ..old
+        };
+        assert!(quota_exceeds(&one_irq, &old));
+        let reserve = ResourceQuota {
+            dynamic_reserve_bytes: 1,
+            ..old
+        };
+        assert!(quota_exceeds(&reserve, &old));
+    }
+
+    /// A share is budget over period, so halving both keeps it and a shorter
+    /// period at the same budget is more of the processor.
+    #[test]
+    fn a_schedule_widens_by_priority_or_by_share() {
+        let old = Schedule {
+            name: "worker",
+            thread: 0,
+            authority_process: 0,
+            priority: 100,
+            max_controlled_priority: 100,
+            budget_us: 1_000,
+            period_us: 10_000,
+            flags: 0,
+        };
+        assert!(!schedule_exceeds(&old, &old));
+        let same_share = Schedule {
+            budget_us: 500,
+            period_us: 5_000,
+            ..old
+        };
+        assert!(!schedule_exceeds(&same_share, &old));
+        let shorter_period = Schedule {
+            period_us: 5_000,
+            ..old
+        };
+        assert!(schedule_exceeds(&shorter_period, &old));
+        let higher = Schedule {
+            priority: 101,
+            budget_us: 1,
+            ..old
+        };
+        assert!(schedule_exceeds(&higher, &old));
+        let controls_more = Schedule {
+            max_controlled_priority: 101,
+            ..old
+        };
+        assert!(schedule_exceeds(&controls_more, &old));
+        let lower = Schedule {
+            priority: 99,
+            budget_us: 500,
+            ..old
+        };
+        assert!(!schedule_exceeds(&lower, &old));
+    }
+}
@@ -8,16 +8,30 @@
 //! agree on disk GUID and table contents, otherwise the device is rejected
 //! as conflicting. Partition selection happens only here, so every store
 //! byte stays inside the validated partition bounds.
+//!
+//! The one writer is `format_single_partition`, which lays out a fresh disk
+//! for an export archive (M6.7); nothing here rewrites an existing table.
 
 use alloc::vec::Vec;
 
 use crate::crc32::crc32;
+use crate::object_store::StoreKey;
 use crate::store_disk::SECTOR_BYTES as SECTOR_SIZE;
 
 /// Partition type GUID marking the Slime OS object-store partition. Stored
 /// and compared as raw GPT bytes on both the host builder and the kernel.
 pub const SLIME_STORE_TYPE_GUID: [u8; 16] = *b"SLIMEOSSTOREGPT!";
 
+/// Partition type GUID of an encrypted object store. A distinct type, so a
+/// reader that predates encryption never selects one and reads ciphertext
+/// as records.
+pub const SLIME_ENCRYPTED_STORE_TYPE_GUID: [u8; 16] = *b"SLIMEOSSTORECRY!";
+
+/// Partition type GUID of an export archive's transfer partition: one
+/// `contracts/transfer` manifest from its first sector. Distinct from both
+/// store types, so an archive disk is never opened as a store.
+pub const SLIME_TRANSFER_TYPE_GUID: [u8; 16] = *b"SLIMEOSTRANSFER!";
+
 const GPT_MAGIC: [u8; 8] = *b"EFI PART";
 const GPT_VERSION: u32 = 0x0001_0000;
 const MIN_HEADER_SIZE: u32 = 92;
@@ -32,11 +46,16 @@ const PMBR_ENTRY_SIZE: usize = 16;
 pub const MAX_PARTITION_ENTRIES: u32 = 128;
 const MIN_ENTRY_SIZE: u32 = 128;
 const MAX_ENTRY_SIZE: u32 = 512;
+const FORMAT_ENTRY_COUNT: u32 = MAX_PARTITION_ENTRIES;
+const FORMAT_ENTRY_SIZE: u32 = MIN_ENTRY_SIZE;
 
 /// Reads one 512-byte sector by absolute LBA into `out`. The store service
 /// backs this with the shared virtio device; tests back it with mock disks.
 pub type SectorReader<'a> = dyn FnMut(u64, &mut [u8; SECTOR_SIZE]) -> Result<(), GptError> + 'a;
 
+/// Writes one 512-byte sector by absolute LBA, for `format_single_partition`.
+pub type SectorWriter<'a> = dyn FnMut(u64, &[u8; SECTOR_SIZE]) -> Result<(), GptError> + 'a;
+
 /// Every way GPT validation can fail. Total: malformed metadata maps to one
 /// of these, never to a panic or an out-of-bounds device request.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -63,6 +82,10 @@ pub enum GptError {
     ConflictingCopies,
     NoStorePartition,
     AmbiguousStorePartition,
+    /// The store partition is encrypted and the caller supplied no key.
+    KeyRequired,
+    NoTransferPartition,
+    AmbiguousTransferPartition,
 }
 
 /// A validated, bounded partition range (inclusive LBAs).
@@ -282,18 +305,12 @@ fn validate_copy(
     Ok((header, entry_bytes))
 }
 
-/// Validate both GPT copies and select the object-store partition.
-///
-/// `reader` fetches one 512-byte sector by absolute LBA; `capacity` is the
-/// device size in sectors. Copy-conflict rule: when both copies validate,
-/// they must agree on disk GUID and entry-array CRC, otherwise the device is
-/// rejected (`ConflictingCopies`) rather than guessed. Partition bounds and
-/// overlaps are checked once on the selected copy, so shared malformed
-/// metadata is a hard reject, not a false recovery.
-pub fn validate_store_partition(
+/// Validate both GPT copies, select one, and bound every in-use partition on
+/// it. Shared by every partition selector, so the copy rule is stated once.
+fn validated_partitions(
     reader: &mut SectorReader<'_>,
     capacity: u64,
-) -> Result<StorePartition, GptError> {
+) -> Result<(Vec<Partition>, Recovery), GptError> {
     if capacity < 3 {
         return Err(GptError::OutOfBounds);
     }
@@ -329,21 +346,173 @@ pub fn validate_store_partition(
     };
 
     let partitions = parse_partitions(&entry_bytes, &header, capacity)?;
-    let mut matches = partitions
-        .iter()
-        .filter(|partition| partition.type_guid == SLIME_STORE_TYPE_GUID);
+    Ok((partitions, recovery))
+}
+
+/// Validate both GPT copies and select the object-store partition.
+///
+/// `reader` fetches one 512-byte sector by absolute LBA; `capacity` is the
+/// device size in sectors. Copy-conflict rule: when both copies validate,
+/// they must agree on disk GUID and entry-array CRC, otherwise the device is
+/// rejected (`ConflictingCopies`) rather than guessed. Partition bounds and
+/// overlaps are checked once on the selected copy, so shared malformed
+/// metadata is a hard reject, not a false recovery.
+///
+/// Either store type is selected, and two store partitions of any mix of
+/// types are ambiguous. An encrypted one is refused with `KeyRequired`
+/// unless the caller holds `key`; whether it is the *right* key is for
+/// `ObjectStore::open_encrypted` to prove against the superblock.
+pub fn validate_store_partition(
+    reader: &mut SectorReader<'_>,
+    capacity: u64,
+    key: Option<&StoreKey>,
+) -> Result<StorePartition, GptError> {
+    let (partitions, recovery) = validated_partitions(reader, capacity)?;
+    let mut matches = partitions.iter().filter(|partition| {
+        partition.type_guid == SLIME_STORE_TYPE_GUID
+            || partition.type_guid == SLIME_ENCRYPTED_STORE_TYPE_GUID
+    });
     let Some(partition) = matches.next() else {
         return Err(GptError::NoStorePartition);
     };
     if matches.next().is_some() {
         return Err(GptError::AmbiguousStorePartition);
     }
+    if partition.type_guid == SLIME_ENCRYPTED_STORE_TYPE_GUID && key.is_none() {
+        return Err(GptError::KeyRequired);
+    }
+    Ok(StorePartition {
+        partition: *partition,
+        recovery,
+    })
+}
+
+/// Validate both GPT copies and select the transfer partition of an export
+/// archive, under the same copy and bounds rules as
+/// [`validate_store_partition`]. Exactly one partition of
+/// [`SLIME_TRANSFER_TYPE_GUID`] is selected; store partitions on the same
+/// disk are neither selected nor a reason to refuse.
+pub fn validate_transfer_partition(
+    reader: &mut SectorReader<'_>,
+    capacity: u64,
+) -> Result<StorePartition, GptError> {
+    let (partitions, recovery) = validated_partitions(reader, capacity)?;
+    let mut matches = partitions
+        .iter()
+        .filter(|partition| partition.type_guid == SLIME_TRANSFER_TYPE_GUID);
+    let Some(partition) = matches.next() else {
+        return Err(GptError::NoTransferPartition);
+    };
+    if matches.next().is_some() {
+        return Err(GptError::AmbiguousTransferPartition);
+    }
     Ok(StorePartition {
         partition: *partition,
         recovery,
     })
 }
 
+/// One header sector, CRC last: it covers the header with its own field
+/// zeroed.
+fn header_sector(
+    my_lba: u64,
+    backup_lba: u64,
+    usable: (u64, u64),
+    disk_guid: [u8; 16],
+    entries_lba: u64,
+    entries_crc: u32,
+) -> [u8; SECTOR_SIZE] {
+    let mut sector = [0u8; SECTOR_SIZE];
+    sector[..8].copy_from_slice(&GPT_MAGIC);
+    sector[8..12].copy_from_slice(&GPT_VERSION.to_le_bytes());
+    sector[12..16].copy_from_slice(&MIN_HEADER_SIZE.to_le_bytes());
+    sector[24..32].copy_from_slice(&my_lba.to_le_bytes());
+    sector[32..40].copy_from_slice(&backup_lba.to_le_bytes());
+    sector[40..48].copy_from_slice(&usable.0.to_le_bytes());
+    sector[48..56].copy_from_slice(&usable.1.to_le_bytes());
+    sector[56..72].copy_from_slice(&disk_guid);
+    sector[72..80].copy_from_slice(&entries_lba.to_le_bytes());
+    sector[80..84].copy_from_slice(&FORMAT_ENTRY_COUNT.to_le_bytes());
+    sector[84..88].copy_from_slice(&FORMAT_ENTRY_SIZE.to_le_bytes());
+    sector[88..92].copy_from_slice(&entries_crc.to_le_bytes());
+    let crc = crc32(&sector[..MIN_HEADER_SIZE as usize]);
+    sector[16..20].copy_from_slice(&crc.to_le_bytes());
+    sector
+}
+
+/// Format a device as a GPT disk with one partition of `type_guid` spanning
+/// the whole usable range, and return that partition.
+///
+/// The table is the UEFI minimum the host builders write too: 128 entries of
+/// 128 bytes. The backup copy is written first and the protective MBR last,
+/// so a format cut short leaves a device validation refuses at its first
+/// sector rather than one holding a half-written table.
+pub fn format_single_partition(
+    writer: &mut SectorWriter<'_>,
+    capacity: u64,
+    type_guid: [u8; 16],
+    disk_guid: [u8; 16],
+) -> Result<Partition, GptError> {
+    let array_bytes = (FORMAT_ENTRY_COUNT * FORMAT_ENTRY_SIZE) as usize;
+    let array_sectors = array_bytes.div_ceil(SECTOR_SIZE) as u64;
+    let backup_lba = capacity.checked_sub(1).ok_or(GptError::OutOfBounds)?;
+    let backup_entries_lba = backup_lba
+        .checked_sub(array_sectors)
+        .ok_or(GptError::OutOfBounds)?;
+    let first_usable = 2 + array_sectors;
+    if backup_entries_lba <= first_usable {
+        return Err(GptError::OutOfBounds);
+    }
+    let partition = Partition {
+        first_lba: first_usable,
+        last_lba: backup_entries_lba - 1,
+        type_guid,
+    };
+
+    let mut entries = alloc::vec![0u8; array_sectors as usize * SECTOR_SIZE];
+    entries[..16].copy_from_slice(&type_guid);
+    entries[16..32].copy_from_slice(&disk_guid);
+    entries[32..40].copy_from_slice(&partition.first_lba.to_le_bytes());
+    entries[40..48].copy_from_slice(&partition.last_lba.to_le_bytes());
+    let entries_crc = crc32(&entries[..array_bytes]);
+    let usable = (partition.first_lba, partition.last_lba);
+
+    for (entries_lba, header_lba, other_lba) in
+        [(backup_entries_lba, backup_lba, 1), (2, 1, backup_lba)]
+    {
+        for (index, chunk) in entries.chunks_exact(SECTOR_SIZE).enumerate() {
+            writer(
+                entries_lba + index as u64,
+                chunk.try_into().expect("sector-sized entry chunk"),
+            )?;
+        }
+        writer(
+            header_lba,
+            &header_sector(
+                header_lba,
+                other_lba,
+                usable,
+                disk_guid,
+                entries_lba,
+                entries_crc,
+            ),
+        )?;
+    }
+
+    // One protective entry covering the device from LBA 1, capped at what its
+    // 32-bit size field can name.
+    let mut pmbr = [0u8; SECTOR_SIZE];
+    let entry = &mut pmbr[PMBR_ENTRIES_OFFSET..PMBR_ENTRIES_OFFSET + PMBR_ENTRY_SIZE];
+    entry[1..4].copy_from_slice(&[0x00, 0x02, 0x00]);
+    entry[4] = PMBR_TYPE;
+    entry[5..8].copy_from_slice(&[0xFF, 0xFF, 0xFF]);
+    entry[8..12].copy_from_slice(&1u32.to_le_bytes());
+    entry[12..16].copy_from_slice(&(backup_lba.min(u64::from(u32::MAX)) as u32).to_le_bytes());
+    pmbr[SECTOR_SIZE - 2..].copy_from_slice(&PMBR_SIGNATURE);
+    writer(0, &pmbr)?;
+    Ok(partition)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -383,7 +552,7 @@ mod tests {
 
         fn validate(&self) -> Result<StorePartition, GptError> {
             let mut reader = self.reader();
-            validate_store_partition(&mut reader, CAPACITY)
+            validate_store_partition(&mut reader, CAPACITY, None)
         }
     }
 
@@ -696,6 +865,57 @@ mod tests {
         assert_eq!(disk.validate(), Err(GptError::AmbiguousStorePartition));
     }
 
+    /// `valid_disk` with its entry table replaced in both copies.
+    fn disk_with_entries(entries: &[u8]) -> Disk {
+        let mut disk = valid_disk();
+        write_entries(&mut disk, PRIMARY_ENTRIES_LBA, entries);
+        write_entries(&mut disk, BACKUP_ENTRIES_LBA, entries);
+        write_header(
+            &mut disk,
+            1,
+            CAPACITY - 1,
+            PRIMARY_ENTRIES_LBA,
+            entries,
+            *b"SLIMEDISKGUID!!!",
+        );
+        write_header(
+            &mut disk,
+            CAPACITY - 1,
+            1,
+            BACKUP_ENTRIES_LBA,
+            entries,
+            *b"SLIMEDISKGUID!!!",
+        );
+        disk
+    }
+
+    /// An encrypted store partition is selected only for a caller holding a
+    /// key, and it counts toward ambiguity like any store partition.
+    #[test]
+    fn an_encrypted_store_partition_needs_a_key() {
+        let mut entries = entry_table();
+        entries[..16].copy_from_slice(&SLIME_ENCRYPTED_STORE_TYPE_GUID);
+        let disk = disk_with_entries(&entries);
+        assert_eq!(disk.validate(), Err(GptError::KeyRequired));
+        let key = StoreKey::new([0x42; 32]);
+        let mut reader = disk.reader();
+        let selected = validate_store_partition(&mut reader, CAPACITY, Some(&key))
+            .expect("a key holder selects it");
+        assert_eq!(
+            selected.partition.type_guid,
+            SLIME_ENCRYPTED_STORE_TYPE_GUID
+        );
+
+        let second = ENTRY_SIZE as usize;
+        entries[second..second + 16].copy_from_slice(&SLIME_STORE_TYPE_GUID);
+        let disk = disk_with_entries(&entries);
+        let mut reader = disk.reader();
+        assert_eq!(
+            validate_store_partition(&mut reader, CAPACITY, Some(&key)),
+            Err(GptError::AmbiguousStorePartition)
+        );
+    }
+
     /// A header whose own CRC does not cover it is a damaged copy, so a disk with
     /// both CRCs broken has no valid copy at all.
     #[test]
@@ -728,7 +948,7 @@ mod tests {
         for capacity in [0u64, 1, 2] {
             let mut reader = disk.reader();
             assert_eq!(
-                validate_store_partition(&mut reader, capacity),
+                validate_store_partition(&mut reader, capacity, None),
                 Err(GptError::OutOfBounds),
                 "capacity {capacity}",
             );
@@ -741,8 +961,69 @@ mod tests {
     fn a_failing_reader_reports_a_device_error() {
         let mut reader = |_lba: u64, _out: &mut [u8; SECTOR_SIZE]| Err(GptError::Device);
         assert_eq!(
-            validate_store_partition(&mut reader, CAPACITY),
+            validate_store_partition(&mut reader, CAPACITY, None),
             Err(GptError::Device)
         );
     }
+
+    /// A formatted disk is exactly what validation accepts: both copies agree,
+    /// the one partition is the transfer partition, and nothing on it is a
+    /// store.
+    #[test]
+    fn a_formatted_disk_validates_with_one_transfer_partition() {
+        const FORMATTED: u64 = 96;
+        let mut sectors = alloc::vec![[0xA5u8; SECTOR_SIZE]; FORMATTED as usize];
+        let mut writer = |lba: u64, data: &[u8; SECTOR_SIZE]| -> Result<(), GptError> {
+            *sectors.get_mut(lba as usize).ok_or(GptError::OutOfBounds)? = *data;
+            Ok(())
+        };
+        let partition = format_single_partition(
+            &mut writer,
+            FORMATTED,
+            SLIME_TRANSFER_TYPE_GUID,
+            *b"SLIMEDISKGUID!!!",
+        )
+        .expect("formats");
+        assert_eq!(
+            (partition.first_lba, partition.last_lba),
+            (34, FORMATTED - 34)
+        );
+
+        let mut reader = |lba: u64, out: &mut [u8; SECTOR_SIZE]| -> Result<(), GptError> {
+            *out = *sectors.get(lba as usize).ok_or(GptError::OutOfBounds)?;
+            Ok(())
+        };
+        let selected = validate_transfer_partition(&mut reader, FORMATTED).expect("valid GPT");
+        assert_eq!(selected.partition, partition);
+        assert_eq!(selected.recovery, Recovery::None);
+        assert_eq!(
+            validate_store_partition(&mut reader, FORMATTED, None),
+            Err(GptError::NoStorePartition)
+        );
+        let store_disk = valid_disk();
+        let mut reader = store_disk.reader();
+        assert_eq!(
+            validate_transfer_partition(&mut reader, CAPACITY),
+            Err(GptError::NoTransferPartition)
+        );
+    }
+
+    /// A device with no room between the two tables has no partition to offer,
+    /// and is refused before anything is written.
+    #[test]
+    fn a_device_too_small_to_format_is_refused_unwritten() {
+        for capacity in [0u64, 1, 34, 67] {
+            let mut writes = 0;
+            let mut writer = |_lba: u64, _data: &[u8; SECTOR_SIZE]| -> Result<(), GptError> {
+                writes += 1;
+                Ok(())
+            };
+            assert_eq!(
+                format_single_partition(&mut writer, capacity, SLIME_TRANSFER_TYPE_GUID, [1; 16]),
+                Err(GptError::OutOfBounds),
+                "capacity {capacity}",
+            );
+            assert_eq!(writes, 0);
+        }
+    }
 }
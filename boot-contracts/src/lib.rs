@@ -9,12 +9,16 @@
 #[cfg(feature = "gpt")]
 extern crate alloc;
 
+#[cfg(feature = "gpt")]
+pub mod archive;
 pub mod boot_layout;
 pub mod bootstate;
+pub mod chacha20poly1305;
 pub mod component_image;
 pub mod crc32;
 pub mod fabric_graph;
 pub mod generation;
+pub mod generation_diff;
 #[cfg(feature = "gpt")]
 pub mod gpt;
 pub mod handoff;
@@ -26,6 +30,7 @@ pub mod recovery;
 pub mod release;
 pub mod sha256;
 pub mod shared_buffer_budget;
+pub mod state_policy;
 pub mod store_disk;
 pub mod stream_history;
 pub mod target_profile;
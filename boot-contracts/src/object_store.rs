@@ -9,43 +9,231 @@
 //! Layout inside a validated GPT partition (LBAs are partition-relative):
 //!
 //! ```text
-//! LBA 0: superblock slot A (one sector)
-//! LBA 1: superblock slot B (one sector)
-//! LBA 2..: append-only object records (header + payload, sector aligned)
+//! LBA 0:      superblock slot A (one sector)
+//! LBA 1:      superblock slot B (one sector)
+//! LBA 2..10:  index for slot A
+//! LBA 10..18: index for slot B
+//! LBA 18..:   append-only object records (header + payload, sector aligned)
 //! ```
 //!
+//! The committed records are one contiguous region, `record_area_start` to
+//! `append_lba`. It starts at LBA 18 until a compaction copies the records a
+//! root still reaches somewhere outside it and commits the copy as the new
+//! region; everything else in the record area is free.
+//!
+//! Each commit writes the record index for the slot it is about to claim,
+//! flushes it with the records, and seals it with a CRC-32 in that slot's
+//! superblock. Open trusts an index only when the CRC, sequence, count, and
+//! record tiling all agree with the superblock; anything else — an index
+//! torn by an interrupted commit, or one left over from an older root — is
+//! ignored and the record headers are rescanned instead.
+//!
 //! Superblock header (64 bytes, CRC-32 over the first 60):
 //!   u8[8] magic, u32 version, u32 header_size, u64 sequence,
 //!   u64 append_lba, u32 object_count, u32 flags,
-//!   u64 record_area_start, u64 partition_sectors, u32 reserved, u32 crc32
+//!   u64 record_area_start, u64 partition_sectors, u32 index_crc32, u32 crc32
+//!
+//! Index (64-byte header, then 48 bytes per record in record order):
+//!   u8[8] magic, u32 version, u32 header_size, u64 sequence,
+//!   u32 entry_count, u32 entry_size, u8[32] reserved;
+//!   entry: u8[32] content_hash, u32 obj_type, u32 payload_len, u64 lba
 //!
-//! Record header (64 bytes):
+//! Record header (128 bytes, CRC-32 over the first 124):
 //!   u8[8] magic, u32 version, u32 header_size, u32 obj_type, u32 flags,
-//!   u64 payload_len, u8[32] content_hash (SHA-256 of payload)
+//!   u64 payload_len, u8[32] content_hash (SHA-256 of payload),
+//!   u64 creation_sequence, u8[32] generation, u8[20] type_metadata,
+//!   u32 header_crc32
+//!
+//! An encrypted store (its own GPT type, and `flags` bit 0 in every
+//! superblock and record header) seals each record payload with
+//! ChaCha20-Poly1305 under a key the caller passes to `open_encrypted`;
+//! the key is never written anywhere. The record header is the associated
+//! data and the tag follows the ciphertext, so a record is header, sealed
+//! payload, tag. Content hashes are still taken over plaintext, so addressing
+//! is the same as in a plaintext store. Each superblock slot carries a tag
+//! of its own, right after its 64-byte header, which is how open tells a
+//! wrong key from a damaged slot before it reads any record.
+//!
+//! A format-1 store — 64-byte record headers ending at the content hash, no
+//! index, records from LBA 2 — still opens, by rescanning, but read-only:
+//! every write path returns `ReadOnly`, so nothing writes a format-2 record
+//! into a format-1 region.
+//!
+//! An object larger than one record is stored chunked: `MAX_OBJECT_PAYLOAD`
+//! byte chunk records, each content-addressed on its own, and one manifest
+//! record naming them in order. The manifest is the object's identity, and it
+//! commits last, so an interrupted `put_large` leaves only unreferenced chunks.
+//!
+//! Manifest payload (64-byte header, then u8[32] per chunk):
+//!   u8[8] magic, u32 version, u32 chunk_count, u32 obj_type, u32 reserved,
+//!   u64 total_len, u8[32] root_hash (SHA-256 of the reassembled content)
+//!
+//! A `Mirror` of two devices is itself a `BlockIo`: every write and flush
+//! goes to both, and a read is served by whichever copy answers. The store
+//! verifies what it reads, so it is the store, not the mirror, that decides
+//! a copy is bad: a superblock slot that does not open, or a record whose
+//! header or payload does not verify, is looked for in each copy and the
+//! first good one is written back over the rest.
 
 use alloc::vec::Vec;
 
-#[cfg(test)]
+use crate::chacha20poly1305::{self, KEY_BYTES, NONCE_BYTES};
 use crate::crc32::crc32;
-use crate::gpt::Partition;
-use crate::sha256;
+use crate::gpt::{Partition, SLIME_ENCRYPTED_STORE_TYPE_GUID};
+use crate::sha256::{self, Sha256};
 
-pub use crate::store_disk::{
-    FORMAT_VERSION, MAX_OBJECT_PAYLOAD, MAX_OBJECTS, RECORD_AREA_START, RECORD_HEADER,
-    RECORD_MAGIC, Superblock, SuperblockError, decode_superblock, encode_superblock,
-};
 use crate::store_disk::{
-    RECORD_CONTENT_HASH_OFFSET, RECORD_FORMAT_VERSION_OFFSET, RECORD_HEADER_SIZE_OFFSET,
-    RECORD_OBJ_TYPE_OFFSET, RECORD_PAYLOAD_LEN_OFFSET, SECTOR_BYTES as SECTOR_SIZE, SLOT_A_LBA,
-    SLOT_B_LBA,
+    AUTH_TAG_BYTES, INDEX_A_LBA, INDEX_B_LBA, INDEX_ENTRY_COUNT_OFFSET, INDEX_ENTRY_LBA_OFFSET,
+    INDEX_ENTRY_OBJ_TYPE_OFFSET, INDEX_ENTRY_PAYLOAD_LEN_OFFSET, INDEX_ENTRY_SIZE_OFFSET,
+    INDEX_FORMAT_VERSION_OFFSET, INDEX_HEADER_SIZE_OFFSET, INDEX_RESERVED_OFFSET,
+    INDEX_SEQUENCE_OFFSET, MANIFEST_CHUNK_COUNT_OFFSET, MANIFEST_FORMAT_VERSION,
+    MANIFEST_FORMAT_VERSION_OFFSET, MANIFEST_MAGIC, MANIFEST_OBJ_TYPE_OFFSET,
+    MANIFEST_RESERVED_OFFSET, MANIFEST_ROOT_HASH_OFFSET, MANIFEST_TOTAL_LEN_OFFSET,
+    RECORD_CONTENT_HASH_OFFSET, RECORD_CREATION_SEQUENCE_OFFSET, RECORD_FLAG_ENCRYPTED,
+    RECORD_FLAGS_OFFSET, RECORD_FORMAT_VERSION_OFFSET, RECORD_GENERATION_OFFSET,
+    RECORD_HEADER_CRC32_OFFSET, RECORD_HEADER_SIZE_OFFSET, RECORD_OBJ_TYPE_OFFSET,
+    RECORD_PAYLOAD_LEN_OFFSET, RECORD_TYPE_METADATA_OFFSET, SECTOR_BYTES as SECTOR_SIZE,
+    SLOT_A_LBA, SLOT_B_LBA, SUPERBLOCK_HEADER, record_header_len,
+};
+pub use crate::store_disk::{
+    CHUNK_OBJECT_TYPE, FORMAT_VERSION, INDEX_ENTRY, INDEX_HEADER, INDEX_MAGIC, MANIFEST_HEADER,
+    MANIFEST_OBJECT_TYPE, MAX_OBJECT_PAYLOAD, MAX_OBJECTS, RECORD_AREA_START, RECORD_HEADER,
+    RECORD_MAGIC, Superblock, SuperblockError, TYPE_METADATA_BYTES, V1_FORMAT_VERSION,
+    decode_superblock, encode_superblock,
 };
 
+/// The most chunks one manifest record can name.
+pub const MAX_MANIFEST_CHUNKS: usize = (MAX_OBJECT_PAYLOAD - MANIFEST_HEADER) / 32;
+
 /// The device surface the store needs. Implemented by `VirtioBlock` for the
 /// syscall service and by mock disks in tests.
 pub trait BlockIo {
     fn read_sector(&mut self, lba: u64, out: &mut [u8; SECTOR_SIZE]) -> Result<(), IoError>;
     fn write_sector(&mut self, lba: u64, data: &[u8; SECTOR_SIZE]) -> Result<(), IoError>;
     fn flush(&mut self) -> Result<(), IoError>;
+
+    /// Independent copies the device keeps of every sector. Only a mirror
+    /// keeps more than one, and only then are the `_copy` methods distinct
+    /// from the plain ones.
+    fn copies(&self) -> usize {
+        1
+    }
+
+    /// Read `lba` from one copy only, bypassing whatever choice
+    /// `read_sector` makes.
+    fn read_copy(
+        &mut self,
+        copy: usize,
+        lba: u64,
+        out: &mut [u8; SECTOR_SIZE],
+    ) -> Result<(), IoError> {
+        let _ = copy;
+        self.read_sector(lba, out)
+    }
+
+    /// Write `lba` on one copy only: the repair of a copy that failed
+    /// verification.
+    fn write_copy(
+        &mut self,
+        copy: usize,
+        lba: u64,
+        data: &[u8; SECTOR_SIZE],
+    ) -> Result<(), IoError> {
+        let _ = copy;
+        self.write_sector(lba, data)
+    }
+}
+
+/// Two devices holding the same store. Writes and flushes go to the
+/// primary, then the secondary, and fail if either does, so a commit is
+/// only reported once both copies hold it. Reads go to the primary and fall
+/// back to the secondary when the primary refuses the sector; a `Timeout`
+/// is returned as is, since the sector may yet arrive.
+///
+/// Both devices must address the store at the same LBAs — two identically
+/// partitioned disks.
+pub struct Mirror<P, S> {
+    primary: P,
+    secondary: S,
+}
+
+impl<P: BlockIo, S: BlockIo> Mirror<P, S> {
+    pub const fn new(primary: P, secondary: S) -> Self {
+        Self { primary, secondary }
+    }
+
+    pub fn primary(&mut self) -> &mut P {
+        &mut self.primary
+    }
+
+    pub fn secondary(&mut self) -> &mut S {
+        &mut self.secondary
+    }
+
+    pub fn into_inner(self) -> (P, S) {
+        (self.primary, self.secondary)
+    }
+}
+
+impl<P: BlockIo, S: BlockIo> BlockIo for Mirror<P, S> {
+    fn read_sector(&mut self, lba: u64, out: &mut [u8; SECTOR_SIZE]) -> Result<(), IoError> {
+        match self.primary.read_sector(lba, out) {
+            Err(IoError::Device) => self.secondary.read_sector(lba, out),
+            result => result,
+        }
+    }
+
+    fn write_sector(&mut self, lba: u64, data: &[u8; SECTOR_SIZE]) -> Result<(), IoError> {
+        self.primary.write_sector(lba, data)?;
+        self.secondary.write_sector(lba, data)
+    }
+
+    fn flush(&mut self) -> Result<(), IoError> {
+        self.primary.flush()?;
+        self.secondary.flush()
+    }
+
+    fn copies(&self) -> usize {
+        2
+    }
+
+    fn read_copy(
+        &mut self,
+        copy: usize,
+        lba: u64,
+        out: &mut [u8; SECTOR_SIZE],
+    ) -> Result<(), IoError> {
+        match copy {
+            0 => self.primary.read_sector(lba, out),
+            _ => self.secondary.read_sector(lba, out),
+        }
+    }
+
+    fn write_copy(
+        &mut self,
+        copy: usize,
+        lba: u64,
+        data: &[u8; SECTOR_SIZE],
+    ) -> Result<(), IoError> {
+        match copy {
+            0 => self.primary.write_sector(lba, data),
+            _ => self.secondary.write_sector(lba, data),
+        }
+    }
+}
+
+/// Write `bytes`, read from copy `good`, over every other copy from `lba`
+/// on, then flush. Every sector is attempted even after one fails; the
+/// result says whether all of them, and the flush, succeeded.
+fn rewrite_copies(io: &mut impl BlockIo, good: usize, lba: u64, bytes: &[u8]) -> bool {
+    let mut rewritten = true;
+    for copy in (0..io.copies()).filter(|copy| *copy != good) {
+        for (index, sector) in bytes.chunks_exact(SECTOR_SIZE).enumerate() {
+            let sector = sector.try_into().expect("sector-aligned bytes");
+            rewritten &= io.write_copy(copy, lba + index as u64, sector).is_ok();
+        }
+    }
+    io.flush().is_ok() && rewritten
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -67,6 +255,46 @@ pub enum StoreError {
     BufferTooSmall,
     DuplicateIdentity,
     HashMismatch,
+    /// The hash names an object, but not one of the type the call reads.
+    WrongObjectType,
+    /// The store is in an older on-disk format, which this build reads but
+    /// never writes.
+    ReadOnly,
+    /// The store is encrypted and was opened without a key.
+    KeyRequired,
+    /// No superblock slot authenticates under the key given.
+    WrongKey,
+    /// A key was given for a store that is not encrypted.
+    NotEncrypted,
+}
+
+/// The key an encrypted store is sealed under. Whoever holds the block
+/// capability supplies it at open; the store keeps it only in memory, and
+/// it is not `Clone` so there is exactly one copy to wipe on drop.
+pub struct StoreKey([u8; KEY_BYTES]);
+
+impl StoreKey {
+    pub const fn new(bytes: [u8; KEY_BYTES]) -> Self {
+        Self(bytes)
+    }
+
+    fn bytes(&self) -> &[u8; KEY_BYTES] {
+        &self.0
+    }
+}
+
+impl core::fmt::Debug for StoreKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("StoreKey(..)")
+    }
+}
+
+impl Drop for StoreKey {
+    fn drop(&mut self) {
+        self.0.fill(0);
+        // Keep the wipe from being elided as a dead store.
+        core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+    }
 }
 
 impl From<IoError> for StoreError {
@@ -75,6 +303,110 @@ impl From<IoError> for StoreError {
     }
 }
 
+/// What one compaction pass kept and gave back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Compaction {
+    /// Records a root reaches, now committed in the new region.
+    pub kept: usize,
+    /// Records no root reaches, dropped from the index.
+    pub reclaimed: usize,
+    /// Sectors between the committed append offset and the partition end.
+    pub free_sectors: u64,
+}
+
+/// Why scrub counted a record as damaged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DamageKind {
+    /// The device refused to read a sector of the record.
+    Unreadable,
+    /// The header no longer matches what `open` indexed.
+    CorruptRecord,
+    /// The payload no longer hashes to the record's identity.
+    HashMismatch,
+}
+
+/// One damaged record, and every root the caller named that reaches it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Damage {
+    pub lba: u64,
+    /// The identity the index expected the payload to hash to.
+    pub hash: [u8; 32],
+    pub obj_type: u32,
+    pub kind: DamageKind,
+    /// Filled by `ObjectStore::attribute`; empty until then, and empty after
+    /// it for a record no named root reaches.
+    pub referenced_by: Vec<[u8; 32]>,
+}
+
+/// What an incremental scrub has found so far.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScrubReport {
+    pub records_checked: usize,
+    pub sectors_read: u64,
+    pub damaged: Vec<Damage>,
+    /// Records damaged in one copy of a mirror and rewritten from another.
+    /// They are not in `damaged`: every copy is good again.
+    pub repaired: usize,
+}
+
+/// Where an incremental scrub resumes. Plain data: it holds no borrow of
+/// the store, so a caller can keep it across reopens and interleave steps
+/// with puts. A compaction between steps moves records under it, so the
+/// next step restarts from the region's start and clears the report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScrubCursor {
+    /// The committed sequence and object count when the walk began or last
+    /// resumed. Every `put` commit adds exactly one of each; any other
+    /// commit is a compaction.
+    origin: Option<(u64, usize)>,
+    next_lba: u64,
+}
+
+impl ScrubCursor {
+    pub const fn new() -> Self {
+        Self {
+            origin: None,
+            next_lba: RECORD_AREA_START,
+        }
+    }
+}
+
+impl Default for ScrubCursor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether a scrub step reached the committed append offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrubProgress {
+    Partial,
+    Complete,
+}
+
+/// How `put_or_reuse` satisfied a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placement {
+    /// A new record was appended and committed.
+    Written,
+    /// A record with this content, type tag and header metadata was already
+    /// committed; nothing was written.
+    Reused,
+}
+
+/// What a format-2 record header carries beyond the content identity.
+/// A format-1 record reads back as all zeros.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RecordMetadata {
+    /// The sequence of the commit that first wrote the record. Compaction
+    /// copies the header unchanged, so this survives a move.
+    pub creation_sequence: u64,
+    /// The generation the writer was producing for, or zeros for none.
+    pub generation: [u8; 32],
+    /// Bytes the object's type gives meaning to; the store never reads them.
+    pub type_metadata: [u8; TYPE_METADATA_BYTES],
+}
+
 /// One indexed object: where it starts and how to address it by content.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Entry {
@@ -104,16 +436,162 @@ impl Slot {
             Slot::B => SLOT_B_LBA,
         }
     }
+
+    fn index_lba(self) -> u64 {
+        match self {
+            Slot::A => INDEX_A_LBA,
+            Slot::B => INDEX_B_LBA,
+        }
+    }
+}
+
+/// Derive the nonce for sealing under `aad`. Every sealed thing in the
+/// store is deterministic in its associated data — a slot header names its
+/// sequence, a record header its content hash — so the nonce is too: equal
+/// nonces only ever seal equal input.
+fn nonce(domain: &[u8], aad: &[u8]) -> [u8; NONCE_BYTES] {
+    let mut hasher = Sha256::new();
+    hasher.update(domain);
+    hasher.update(aad);
+    hasher.finalize()[..NONCE_BYTES]
+        .try_into()
+        .expect("nonce prefix")
+}
+
+const SUPERBLOCK_DOMAIN: &[u8] = b"SLIME store superblock";
+const RECORD_DOMAIN: &[u8] = b"SLIME store record";
+
+/// Encode a superblock slot; under `key`, also tag its header so open can
+/// prove the key before trusting the root.
+fn seal_superblock(
+    superblock: &Superblock,
+    partition_sectors: u64,
+    key: Option<&StoreKey>,
+) -> [u8; SECTOR_SIZE] {
+    let mut sector = encode_superblock(superblock, partition_sectors);
+    if let Some(key) = key {
+        let header = &sector[..SUPERBLOCK_HEADER];
+        let tag = chacha20poly1305::seal(
+            key.bytes(),
+            &nonce(SUPERBLOCK_DOMAIN, header),
+            header,
+            &mut [],
+        );
+        sector[SUPERBLOCK_HEADER..SUPERBLOCK_HEADER + AUTH_TAG_BYTES].copy_from_slice(&tag);
+    }
+    sector
+}
+
+/// Decode one slot and check that it agrees with `key`: an encrypted slot
+/// needs the key its tag was sealed under, a plaintext one needs none.
+fn open_superblock(
+    sector: &[u8; SECTOR_SIZE],
+    partition_sectors: u64,
+    key: Option<&StoreKey>,
+) -> Result<Superblock, StoreError> {
+    let superblock =
+        decode_superblock(sector, partition_sectors).map_err(|_| StoreError::NoValidSuperblock)?;
+    match (superblock.encrypted, key) {
+        (false, None) => Ok(superblock),
+        (true, None) => Err(StoreError::KeyRequired),
+        (false, Some(_)) => Err(StoreError::NotEncrypted),
+        (true, Some(key)) => {
+            let header = &sector[..SUPERBLOCK_HEADER];
+            let tag = sector[SUPERBLOCK_HEADER..SUPERBLOCK_HEADER + AUTH_TAG_BYTES]
+                .try_into()
+                .expect("tag field");
+            chacha20poly1305::open(
+                key.bytes(),
+                &nonce(SUPERBLOCK_DOMAIN, header),
+                header,
+                &mut [],
+                &tag,
+            )
+            .map_err(|_| StoreError::WrongKey)?;
+            Ok(superblock)
+        }
+    }
+}
+
+/// Read and open the superblock slot at `lba`. On a device that keeps
+/// copies, the slot is opened in every copy and the one at the highest
+/// sequence is written back over any copy that does not open or holds an
+/// older root — the secondary of a commit interrupted between its two
+/// superblock writes, which would otherwise open at the previous root on
+/// its own. A slot that opens in no copy — a genesis slot B, say — is left
+/// alone.
+fn read_slot(
+    io: &mut impl BlockIo,
+    lba: u64,
+    partition_sectors: u64,
+    key: Option<&StoreKey>,
+) -> Result<Result<Superblock, StoreError>, IoError> {
+    let mut sector = [0u8; SECTOR_SIZE];
+    io.read_sector(lba, &mut sector)?;
+    let opened = open_superblock(&sector, partition_sectors, key);
+    if io.copies() < 2 {
+        return Ok(opened);
+    }
+    let mut newest: Option<(usize, Superblock, [u8; SECTOR_SIZE])> = None;
+    let mut stale = false;
+    for copy in 0..io.copies() {
+        match io.read_copy(copy, lba, &mut sector) {
+            Ok(()) => {}
+            Err(IoError::Device) => {
+                stale = true;
+                continue;
+            }
+            Err(error) => return Err(error),
+        }
+        let Ok(superblock) = open_superblock(&sector, partition_sectors, key) else {
+            stale = true;
+            continue;
+        };
+        match &newest {
+            Some((_, best, bytes)) if best.sequence >= superblock.sequence => {
+                stale |= *bytes != sector;
+            }
+            best => {
+                stale |= best.is_some();
+                newest = Some((copy, superblock, sector));
+            }
+        }
+    }
+    match newest {
+        Some((copy, superblock, bytes)) => {
+            if stale {
+                rewrite_copies(io, copy, lba, &bytes);
+            }
+            Ok(Ok(superblock))
+        }
+        None => Ok(opened),
+    }
+}
+
+/// Bytes a record spends besides its payload: the header, and in an
+/// encrypted store the tag after the ciphertext.
+fn record_overhead(format_version: u32, encrypted: bool) -> usize {
+    record_header_len(format_version) + if encrypted { AUTH_TAG_BYTES } else { 0 }
 }
 
-fn record_sectors(payload_len: u64) -> Result<u64, StoreError> {
-    let bytes = RECORD_HEADER
+fn record_sectors(overhead: usize, payload_len: u64) -> Result<u64, StoreError> {
+    let bytes = overhead
         .checked_add(payload_len as usize)
         .ok_or(StoreError::CorruptRecord)?;
     Ok(bytes.div_ceil(SECTOR_SIZE) as u64)
 }
 
-pub fn encode_record_header(obj_type: u32, payload: &[u8], hash: &[u8; 32]) -> [u8; RECORD_HEADER] {
+/// Render a format-2 record header. Its CRC covers every field before it,
+/// so a flipped bit in the metadata is caught even though the payload hash
+/// does not cover it. `encrypted` sets the flag saying the payload that
+/// follows is sealed.
+pub fn encode_record_header(
+    obj_type: u32,
+    payload: &[u8],
+    hash: &[u8; 32],
+    metadata: &RecordMetadata,
+    encrypted: bool,
+) -> [u8; RECORD_HEADER] {
     let mut header = [0u8; RECORD_HEADER];
     header[..8].copy_from_slice(&RECORD_MAGIC);
     header[RECORD_FORMAT_VERSION_OFFSET..RECORD_HEADER_SIZE_OFFSET]
@@ -122,20 +600,48 @@ pub fn encode_record_header(obj_type: u32, payload: &[u8], hash: &[u8; 32]) -> [
         .copy_from_slice(&(RECORD_HEADER as u32).to_le_bytes());
     header[RECORD_OBJ_TYPE_OFFSET..RECORD_OBJ_TYPE_OFFSET + 4]
         .copy_from_slice(&obj_type.to_le_bytes());
+    let flags = if encrypted { RECORD_FLAG_ENCRYPTED } else { 0 };
+    header[RECORD_FLAGS_OFFSET..RECORD_PAYLOAD_LEN_OFFSET].copy_from_slice(&flags.to_le_bytes());
     header[RECORD_PAYLOAD_LEN_OFFSET..RECORD_CONTENT_HASH_OFFSET]
         .copy_from_slice(&(payload.len() as u64).to_le_bytes());
-    header[RECORD_CONTENT_HASH_OFFSET..RECORD_HEADER].copy_from_slice(hash);
+    header[RECORD_CONTENT_HASH_OFFSET..RECORD_CREATION_SEQUENCE_OFFSET].copy_from_slice(hash);
+    header[RECORD_CREATION_SEQUENCE_OFFSET..RECORD_GENERATION_OFFSET]
+        .copy_from_slice(&metadata.creation_sequence.to_le_bytes());
+    header[RECORD_GENERATION_OFFSET..RECORD_TYPE_METADATA_OFFSET]
+        .copy_from_slice(&metadata.generation);
+    header[RECORD_TYPE_METADATA_OFFSET..RECORD_HEADER_CRC32_OFFSET]
+        .copy_from_slice(&metadata.type_metadata);
+    let crc = crc32(&header[..RECORD_HEADER_CRC32_OFFSET]);
+    header[RECORD_HEADER_CRC32_OFFSET..RECORD_HEADER].copy_from_slice(&crc.to_le_bytes());
     header
 }
 
-pub fn decode_record_header(sector: &[u8; SECTOR_SIZE]) -> Result<Entry, StoreError> {
+/// Decode the header of a record written in `format_version`. A format-2
+/// header must also pass its own CRC, and its flags must agree with whether
+/// the store is `encrypted`.
+pub fn decode_record_header(
+    sector: &[u8; SECTOR_SIZE],
+    format_version: u32,
+    encrypted: bool,
+) -> Result<Entry, StoreError> {
+    let header_len = record_header_len(format_version);
     if sector[..8] != RECORD_MAGIC {
         return Err(StoreError::CorruptRecord);
     }
-    if u32_field(sector, RECORD_FORMAT_VERSION_OFFSET) != FORMAT_VERSION {
+    if u32_field(sector, RECORD_FORMAT_VERSION_OFFSET) != format_version {
+        return Err(StoreError::CorruptRecord);
+    }
+    if u32_field(sector, RECORD_HEADER_SIZE_OFFSET) != header_len as u32 {
+        return Err(StoreError::CorruptRecord);
+    }
+    if format_version == FORMAT_VERSION
+        && u32_field(sector, RECORD_HEADER_CRC32_OFFSET)
+            != crc32(&sector[..RECORD_HEADER_CRC32_OFFSET])
+    {
         return Err(StoreError::CorruptRecord);
     }
-    if u32_field(sector, RECORD_HEADER_SIZE_OFFSET) != RECORD_HEADER as u32 {
+    let flags = if encrypted { RECORD_FLAG_ENCRYPTED } else { 0 };
+    if format_version == FORMAT_VERSION && u32_field(sector, RECORD_FLAGS_OFFSET) != flags {
         return Err(StoreError::CorruptRecord);
     }
     let payload_len = u64_field(sector, RECORD_PAYLOAD_LEN_OFFSET);
@@ -143,7 +649,7 @@ pub fn decode_record_header(sector: &[u8; SECTOR_SIZE]) -> Result<Entry, StoreEr
         return Err(StoreError::CorruptRecord);
     }
     Ok(Entry {
-        hash: sector[RECORD_CONTENT_HASH_OFFSET..RECORD_HEADER]
+        hash: sector[RECORD_CONTENT_HASH_OFFSET..RECORD_CREATION_SEQUENCE_OFFSET]
             .try_into()
             .expect("hash field"),
         obj_type: u32_field(sector, RECORD_OBJ_TYPE_OFFSET),
@@ -152,6 +658,175 @@ pub fn decode_record_header(sector: &[u8; SECTOR_SIZE]) -> Result<Entry, StoreEr
     })
 }
 
+/// Render the index a commit pairs with its superblock: the header, then
+/// one entry per record in record order. The superblock's `index_crc32` is
+/// the CRC-32 of exactly these bytes.
+pub fn encode_index(sequence: u64, entries: &[Entry]) -> Vec<u8> {
+    let mut index = alloc::vec![0u8; INDEX_HEADER + entries.len() * INDEX_ENTRY];
+    index[..8].copy_from_slice(&INDEX_MAGIC);
+    index[INDEX_FORMAT_VERSION_OFFSET..INDEX_HEADER_SIZE_OFFSET]
+        .copy_from_slice(&FORMAT_VERSION.to_le_bytes());
+    index[INDEX_HEADER_SIZE_OFFSET..INDEX_SEQUENCE_OFFSET]
+        .copy_from_slice(&(INDEX_HEADER as u32).to_le_bytes());
+    index[INDEX_SEQUENCE_OFFSET..INDEX_ENTRY_COUNT_OFFSET].copy_from_slice(&sequence.to_le_bytes());
+    index[INDEX_ENTRY_COUNT_OFFSET..INDEX_ENTRY_SIZE_OFFSET]
+        .copy_from_slice(&(entries.len() as u32).to_le_bytes());
+    index[INDEX_ENTRY_SIZE_OFFSET..INDEX_RESERVED_OFFSET]
+        .copy_from_slice(&(INDEX_ENTRY as u32).to_le_bytes());
+    for (entry, slot) in entries
+        .iter()
+        .zip(index[INDEX_HEADER..].chunks_exact_mut(INDEX_ENTRY))
+    {
+        slot[..INDEX_ENTRY_OBJ_TYPE_OFFSET].copy_from_slice(&entry.hash);
+        slot[INDEX_ENTRY_OBJ_TYPE_OFFSET..INDEX_ENTRY_PAYLOAD_LEN_OFFSET]
+            .copy_from_slice(&entry.obj_type.to_le_bytes());
+        slot[INDEX_ENTRY_PAYLOAD_LEN_OFFSET..INDEX_ENTRY_LBA_OFFSET]
+            .copy_from_slice(&entry.payload_len.to_le_bytes());
+        slot[INDEX_ENTRY_LBA_OFFSET..INDEX_ENTRY].copy_from_slice(&entry.lba.to_le_bytes());
+    }
+    index
+}
+
+/// Decide whether `index` is the one `superblock` committed, and if so
+/// return its entries. `None` is not an error: it means the records must be
+/// rescanned. Entries must tile the committed region exactly — each record
+/// starting where the one before it ends — so a trusted index can never
+/// name a sector the superblock does not.
+pub fn decode_index(index: &[u8], superblock: &Superblock) -> Option<Vec<Entry>> {
+    let count = superblock.object_count as usize;
+    let len = INDEX_HEADER + count * INDEX_ENTRY;
+    if count > MAX_OBJECTS || index.len() < len {
+        return None;
+    }
+    let index = &index[..len];
+    if crc32(index) != superblock.index_crc32
+        || index[..8] != INDEX_MAGIC
+        || u32_field(index, INDEX_FORMAT_VERSION_OFFSET) != FORMAT_VERSION
+        || u32_field(index, INDEX_HEADER_SIZE_OFFSET) != INDEX_HEADER as u32
+        || u64_field(index, INDEX_SEQUENCE_OFFSET) != superblock.sequence
+        || u32_field(index, INDEX_ENTRY_COUNT_OFFSET) as usize != count
+        || u32_field(index, INDEX_ENTRY_SIZE_OFFSET) != INDEX_ENTRY as u32
+        || index[INDEX_RESERVED_OFFSET..INDEX_HEADER]
+            .iter()
+            .any(|byte| *byte != 0)
+    {
+        return None;
+    }
+    let mut entries = Vec::with_capacity(count);
+    let overhead = record_overhead(FORMAT_VERSION, superblock.encrypted);
+    let mut lba = superblock.record_start;
+    for slot in index[INDEX_HEADER..].chunks_exact(INDEX_ENTRY) {
+        let entry = Entry {
+            hash: slot[..INDEX_ENTRY_OBJ_TYPE_OFFSET]
+                .try_into()
+                .expect("hash field"),
+            obj_type: u32_field(slot, INDEX_ENTRY_OBJ_TYPE_OFFSET),
+            payload_len: u32_field(slot, INDEX_ENTRY_PAYLOAD_LEN_OFFSET),
+            lba: u64_field(slot, INDEX_ENTRY_LBA_OFFSET),
+        };
+        if entry.lba != lba || entry.payload_len as usize > MAX_OBJECT_PAYLOAD {
+            return None;
+        }
+        lba = lba.checked_add(record_sectors(overhead, entry.payload_len as u64).ok()?)?;
+        entries.push(entry);
+    }
+    (lba == superblock.append_lba).then_some(entries)
+}
+
+/// A chunked object's manifest, decoded and bounds-checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Manifest {
+    /// The caller's type for the reassembled object.
+    pub obj_type: u32,
+    pub total_len: u64,
+    pub chunk_count: u32,
+    /// SHA-256 of the reassembled content.
+    pub root_hash: [u8; 32],
+}
+
+/// Render the manifest for `payload` split into `MAX_OBJECT_PAYLOAD` chunks.
+pub fn encode_manifest(obj_type: u32, payload: &[u8]) -> Result<Vec<u8>, StoreError> {
+    let mut manifest = manifest_for(obj_type, payload.len() as u64)?;
+    manifest[MANIFEST_ROOT_HASH_OFFSET..MANIFEST_HEADER].copy_from_slice(&sha256::digest(payload));
+    for (index, chunk) in payload.chunks(MAX_OBJECT_PAYLOAD).enumerate() {
+        let offset = MANIFEST_HEADER + index * 32;
+        manifest[offset..offset + 32].copy_from_slice(&sha256::digest(chunk));
+    }
+    Ok(manifest)
+}
+
+/// A manifest for `total_len` bytes of `obj_type`, with its root hash and
+/// chunk hashes still zero for the caller to fill in.
+fn manifest_for(obj_type: u32, total_len: u64) -> Result<Vec<u8>, StoreError> {
+    let chunk_count = usize::try_from(total_len.div_ceil(MAX_OBJECT_PAYLOAD as u64))
+        .ok()
+        .filter(|count| *count <= MAX_MANIFEST_CHUNKS)
+        .ok_or(StoreError::PayloadTooLarge)?;
+    let mut manifest = alloc::vec![0u8; MANIFEST_HEADER + chunk_count * 32];
+    manifest[..8].copy_from_slice(&MANIFEST_MAGIC);
+    manifest[MANIFEST_FORMAT_VERSION_OFFSET..MANIFEST_CHUNK_COUNT_OFFSET]
+        .copy_from_slice(&MANIFEST_FORMAT_VERSION.to_le_bytes());
+    manifest[MANIFEST_CHUNK_COUNT_OFFSET..MANIFEST_OBJ_TYPE_OFFSET]
+        .copy_from_slice(&(chunk_count as u32).to_le_bytes());
+    manifest[MANIFEST_OBJ_TYPE_OFFSET..MANIFEST_RESERVED_OFFSET]
+        .copy_from_slice(&obj_type.to_le_bytes());
+    manifest[MANIFEST_TOTAL_LEN_OFFSET..MANIFEST_ROOT_HASH_OFFSET]
+        .copy_from_slice(&total_len.to_le_bytes());
+    Ok(manifest)
+}
+
+/// Decide whether a payload is a manifest this reader may follow.
+///
+/// The chunk count must be exactly the one `total_len` implies: a manifest
+/// that listed a spare chunk, or too few, would reassemble to a length other
+/// than the one it declares, and the root hash would be the only thing left
+/// to notice.
+pub fn decode_manifest(payload: &[u8]) -> Result<Manifest, StoreError> {
+    if payload.len() < MANIFEST_HEADER || payload[..8] != MANIFEST_MAGIC {
+        return Err(StoreError::CorruptRecord);
+    }
+    if u32_field(payload, MANIFEST_FORMAT_VERSION_OFFSET) != MANIFEST_FORMAT_VERSION
+        || u32_field(payload, MANIFEST_RESERVED_OFFSET) != 0
+    {
+        return Err(StoreError::CorruptRecord);
+    }
+    let manifest = Manifest {
+        obj_type: u32_field(payload, MANIFEST_OBJ_TYPE_OFFSET),
+        total_len: u64_field(payload, MANIFEST_TOTAL_LEN_OFFSET),
+        chunk_count: u32_field(payload, MANIFEST_CHUNK_COUNT_OFFSET),
+        root_hash: payload[MANIFEST_ROOT_HASH_OFFSET..MANIFEST_HEADER]
+            .try_into()
+            .expect("root hash field"),
+    };
+    let chunk_count = manifest.chunk_count as usize;
+    if chunk_count > MAX_MANIFEST_CHUNKS
+        || payload.len() != MANIFEST_HEADER + chunk_count * 32
+        || manifest.total_len.div_ceil(MAX_OBJECT_PAYLOAD as u64) != chunk_count as u64
+    {
+        return Err(StoreError::CorruptRecord);
+    }
+    Ok(manifest)
+}
+
+/// The hash of a decoded manifest's `index`th chunk.
+fn manifest_chunk(payload: &[u8], index: usize) -> [u8; 32] {
+    let offset = MANIFEST_HEADER + index * 32;
+    payload[offset..offset + 32]
+        .try_into()
+        .expect("chunk hash field")
+}
+
+/// Report the chunks a manifest names. Shaped as `compact`'s `references`
+/// callback, so a large object stays live for as long as its manifest does.
+pub fn manifest_references(obj_type: u32, payload: &[u8], out: &mut Vec<[u8; 32]>) {
+    if obj_type != MANIFEST_OBJECT_TYPE {
+        return;
+    }
+    if let Ok(manifest) = decode_manifest(payload) {
+        out.extend((0..manifest.chunk_count as usize).map(|index| manifest_chunk(payload, index)));
+    }
+}
+
 fn u32_field(sector: &[u8], offset: usize) -> u32 {
     u32::from_le_bytes(sector[offset..offset + 4].try_into().expect("u32 field"))
 }
@@ -160,83 +835,242 @@ fn u64_field(sector: &[u8], offset: usize) -> u64 {
     u64::from_le_bytes(sector[offset..offset + 8].try_into().expect("u64 field"))
 }
 
+/// Read the index sectors `superblock` needs from `index_lba` and decode
+/// them. A sector the device will not return is treated like a stale index:
+/// the rescan that follows reads the records themselves and reports any
+/// failure that matters.
+fn read_index(
+    io: &mut impl BlockIo,
+    index_lba: u64,
+    superblock: &Superblock,
+) -> Option<Vec<Entry>> {
+    let count = superblock.object_count as usize;
+    if count > MAX_OBJECTS {
+        return None;
+    }
+    let sectors = (INDEX_HEADER + count * INDEX_ENTRY).div_ceil(SECTOR_SIZE);
+    let mut bytes = alloc::vec![0u8; sectors * SECTOR_SIZE];
+    for (index, sector) in bytes.chunks_exact_mut(SECTOR_SIZE).enumerate() {
+        let sector: &mut [u8; SECTOR_SIZE] = sector.try_into().expect("sector-sized chunk");
+        io.read_sector(index_lba + index as u64, sector).ok()?;
+    }
+    decode_index(&bytes, superblock)
+}
+
+/// Walk the record headers of the committed region, as every open did
+/// before the index existed.
+fn scan(
+    io: &mut impl BlockIo,
+    first_lba: u64,
+    superblock: &Superblock,
+) -> Result<Vec<Entry>, StoreError> {
+    let overhead = record_overhead(superblock.format_version, superblock.encrypted);
+    let mut entries = Vec::new();
+    let mut lba = superblock.record_start;
+    while lba < superblock.append_lba {
+        let mut header_sector = [0u8; SECTOR_SIZE];
+        io.read_sector(first_lba + lba, &mut header_sector)?;
+        let mut entry = decode_record_header(
+            &header_sector,
+            superblock.format_version,
+            superblock.encrypted,
+        )?;
+        let sectors = record_sectors(overhead, entry.payload_len as u64)?;
+        let end = lba.checked_add(sectors).ok_or(StoreError::CorruptRecord)?;
+        if end > superblock.append_lba {
+            return Err(StoreError::CorruptRecord);
+        }
+        if entries.len() >= MAX_OBJECTS {
+            return Err(StoreError::TooManyObjects);
+        }
+        entry.lba = lba;
+        entries.push(entry);
+        lba = end;
+    }
+    if entries.len() != superblock.object_count as usize {
+        return Err(StoreError::CorruptRecord);
+    }
+    Ok(entries)
+}
+
+/// The span of `partition`, refused if it cannot hold a record past the
+/// metadata area.
+fn store_sectors(partition: &Partition) -> Result<u64, StoreError> {
+    let partition_sectors = partition
+        .last_lba
+        .checked_sub(partition.first_lba)
+        .and_then(|span| span.checked_add(1))
+        .ok_or(StoreError::PartitionTooSmall)?;
+    if partition_sectors < RECORD_AREA_START + 1 {
+        return Err(StoreError::PartitionTooSmall);
+    }
+    Ok(partition_sectors)
+}
+
+/// The partition type says whether the store is encrypted before any
+/// sector is read; a key must be given exactly when it is.
+fn check_partition_type(partition: &Partition, key: Option<&StoreKey>) -> Result<(), StoreError> {
+    match (partition.type_guid == SLIME_ENCRYPTED_STORE_TYPE_GUID, key) {
+        (true, None) => Err(StoreError::KeyRequired),
+        (false, Some(_)) => Err(StoreError::NotEncrypted),
+        _ => Ok(()),
+    }
+}
+
 /// An open object store: validated metadata plus the bounded object index.
 pub struct ObjectStore {
     first_lba: u64,
     partition_sectors: u64,
+    format_version: u32,
+    opened_from_index: bool,
     sequence: u64,
+    record_start: u64,
     append_lba: u64,
     active: Slot,
     entries: Vec<Entry>,
+    key: Option<StoreKey>,
 }
 
 impl ObjectStore {
     /// Open the store in `partition`: validate both superblock slots, pick
-    /// the newest valid root, and scan the committed record area. Records
-    /// beyond the committed append offset (interrupted appends) are never
-    /// examined. All arithmetic is checked; malformed committed metadata
-    /// fails before any out-of-bounds device request.
+    /// the newest valid root, and load its index — or, when the index does
+    /// not match the root, scan the committed record area. Records beyond
+    /// the committed append offset (interrupted appends) are never examined.
+    /// All arithmetic is checked; malformed committed metadata fails before
+    /// any out-of-bounds device request. An encrypted store is refused with
+    /// `KeyRequired`.
     pub fn open(io: &mut impl BlockIo, partition: &Partition) -> Result<Self, StoreError> {
-        let partition_sectors = partition
-            .last_lba
-            .checked_sub(partition.first_lba)
-            .and_then(|span| span.checked_add(1))
-            .ok_or(StoreError::PartitionTooSmall)?;
-        if partition_sectors < RECORD_AREA_START + 1 {
-            return Err(StoreError::PartitionTooSmall);
-        }
+        Self::open_with(io, partition, None)
+    }
+
+    /// `open` for an encrypted store. The newest slot that authenticates
+    /// under `key` is the root; if none does, the key is `WrongKey`. A
+    /// plaintext store is refused with `NotEncrypted` rather than opened
+    /// with a key it would ignore.
+    pub fn open_encrypted(
+        io: &mut impl BlockIo,
+        partition: &Partition,
+        key: StoreKey,
+    ) -> Result<Self, StoreError> {
+        Self::open_with(io, partition, Some(key))
+    }
 
-        let mut slot_sector = [0u8; SECTOR_SIZE];
-        io.read_sector(partition.first_lba + SLOT_A_LBA, &mut slot_sector)?;
-        let slot_a = decode_superblock(&slot_sector, partition_sectors).ok();
-        io.read_sector(partition.first_lba + SLOT_B_LBA, &mut slot_sector)?;
-        let slot_b = decode_superblock(&slot_sector, partition_sectors).ok();
+    fn open_with(
+        io: &mut impl BlockIo,
+        partition: &Partition,
+        key: Option<StoreKey>,
+    ) -> Result<Self, StoreError> {
+        let partition_sectors = store_sectors(partition)?;
+        check_partition_type(partition, key.as_ref())?;
+
+        let slot_a = read_slot(
+            io,
+            partition.first_lba + SLOT_A_LBA,
+            partition_sectors,
+            key.as_ref(),
+        )?;
+        let slot_b = read_slot(
+            io,
+            partition.first_lba + SLOT_B_LBA,
+            partition_sectors,
+            key.as_ref(),
+        )?;
 
         let (active, superblock) = match (slot_a, slot_b) {
-            (Some(a), Some(b)) => {
+            (Ok(a), Ok(b)) => {
                 if a.sequence >= b.sequence {
                     (Slot::A, a)
                 } else {
                     (Slot::B, b)
                 }
             }
-            (Some(a), None) => (Slot::A, a),
-            (None, Some(b)) => (Slot::B, b),
-            (None, None) => return Err(StoreError::NoValidSuperblock),
+            (Ok(a), Err(_)) => (Slot::A, a),
+            (Err(_), Ok(b)) => (Slot::B, b),
+            // A slot that decoded but refused the key says more than one
+            // that did not decode at all.
+            (Err(StoreError::NoValidSuperblock), Err(error)) | (Err(error), Err(_)) => {
+                return Err(error);
+            }
         };
 
-        let mut entries = Vec::new();
-        let mut lba = RECORD_AREA_START;
-        while lba < superblock.append_lba {
-            let mut header_sector = [0u8; SECTOR_SIZE];
-            io.read_sector(partition.first_lba + lba, &mut header_sector)?;
-            let mut entry = decode_record_header(&header_sector)?;
-            let sectors = record_sectors(entry.payload_len as u64)?;
-            let end = lba.checked_add(sectors).ok_or(StoreError::CorruptRecord)?;
-            if end > superblock.append_lba {
-                return Err(StoreError::CorruptRecord);
-            }
-            if entries.len() >= MAX_OBJECTS {
-                return Err(StoreError::TooManyObjects);
-            }
-            entry.lba = lba;
-            entries.push(entry);
-            lba = end;
-        }
-        if entries.len() != superblock.object_count as usize {
-            return Err(StoreError::CorruptRecord);
-        }
+        let format_version = superblock.format_version;
+        let indexed = if format_version == FORMAT_VERSION {
+            read_index(io, partition.first_lba + active.index_lba(), &superblock)
+        } else {
+            None
+        };
+        let opened_from_index = indexed.is_some();
+        let entries = match indexed {
+            Some(entries) => entries,
+            None => scan(io, partition.first_lba, &superblock)?,
+        };
 
         Ok(Self {
             first_lba: partition.first_lba,
             partition_sectors,
+            format_version,
+            opened_from_index,
             sequence: superblock.sequence,
+            record_start: superblock.record_start,
             append_lba: superblock.append_lba,
             active,
             entries,
+            key,
         })
     }
 
+    /// Write an empty store into `partition`: the genesis root in slot A,
+    /// its empty index, and a cleared slot B, so no root left by an earlier
+    /// store outranks it. Under `key` every slot is sealed and the store can
+    /// only be opened with `open_encrypted` and that key.
+    pub fn format(
+        io: &mut impl BlockIo,
+        partition: &Partition,
+        key: Option<&StoreKey>,
+    ) -> Result<(), StoreError> {
+        let partition_sectors = store_sectors(partition)?;
+        check_partition_type(partition, key)?;
+        let index = encode_index(1, &[]);
+        let mut sector = [0u8; SECTOR_SIZE];
+        sector[..index.len()].copy_from_slice(&index);
+        io.write_sector(partition.first_lba + INDEX_A_LBA, &sector)?;
+        let genesis = Superblock {
+            format_version: FORMAT_VERSION,
+            sequence: 1,
+            record_start: RECORD_AREA_START,
+            append_lba: RECORD_AREA_START,
+            object_count: 0,
+            index_crc32: crc32(&index),
+            encrypted: key.is_some(),
+        };
+        let sector = seal_superblock(&genesis, partition_sectors, key);
+        io.write_sector(partition.first_lba + SLOT_A_LBA, &sector)?;
+        io.write_sector(partition.first_lba + SLOT_B_LBA, &[0; SECTOR_SIZE])?;
+        io.flush()?;
+        Ok(())
+    }
+
+    /// Whether record payloads are sealed under a key.
+    pub fn encrypted(&self) -> bool {
+        self.key.is_some()
+    }
+
+    /// The on-disk format of the open store.
+    pub fn format_version(&self) -> u32 {
+        self.format_version
+    }
+
+    /// Whether this build may write the store. Only the current format is
+    /// writable; older ones open for reading and migration.
+    pub fn read_only(&self) -> bool {
+        self.format_version != FORMAT_VERSION
+    }
+
+    /// Whether `open` loaded the committed index rather than rescanning.
+    pub fn opened_from_index(&self) -> bool {
+        self.opened_from_index
+    }
+
     pub fn sequence(&self) -> u64 {
         self.sequence
     }
@@ -249,6 +1083,41 @@ impl ObjectStore {
         self.append_lba
     }
 
+    /// Whether any record holds this content, without touching the device.
+    pub fn exists(&self, hash: &[u8; 32]) -> bool {
+        self.entries.iter().any(|entry| &entry.hash == hash)
+    }
+
+    /// Every committed record holding this content, in record order. The
+    /// same bytes stored under two type tags are two records with one hash;
+    /// `stat` and `get` answer for the first of them.
+    pub fn records(&self, hash: &[u8; 32]) -> impl Iterator<Item = &Entry> + '_ {
+        let hash = *hash;
+        self.entries.iter().filter(move |entry| entry.hash == hash)
+    }
+
+    /// Every committed record, in record order. What a receiver reads to
+    /// answer which objects a transfer need not carry.
+    pub fn entries(&self) -> impl Iterator<Item = &Entry> + '_ {
+        self.entries.iter()
+    }
+
+    /// Every committed record of one type, in record order. A service that
+    /// keeps its own records in the store finds them by tag, since their
+    /// content hashes are what it is looking for.
+    pub fn records_of_type(&self, obj_type: u32) -> impl Iterator<Item = &Entry> + '_ {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.obj_type == obj_type)
+    }
+
+    /// How many committed records hold this content. Not a reference count:
+    /// a record is not freed when this drops, only when compaction finds no
+    /// root reaching its hash.
+    pub fn record_count(&self, hash: &[u8; 32]) -> usize {
+        self.records(hash).count()
+    }
+
     /// Look up an object by content hash without touching the device.
     pub fn stat(&self, hash: &[u8; 32]) -> Option<(u32, u32)> {
         self.entries
@@ -275,37 +1144,213 @@ impl ObjectStore {
         if out.len() < len {
             return Err(StoreError::BufferTooSmall);
         }
-        let payload = self.read_payload(io, &entry)?;
-        if sha256::digest(&payload) != *hash {
-            return Err(StoreError::HashMismatch);
-        }
+        let payload = self.read_verified(io, &entry)?;
         out[..len].copy_from_slice(&payload);
         Ok((entry.obj_type, len))
     }
 
     /// Re-read and hash every committed object record. Opening validates the
     /// superblock and record bounds; scrub additionally proves payload
-    /// integrity for objects outside the selected state closure.
+    /// integrity for objects outside the selected state closure. Stops at
+    /// the first damaged record; `scrub_step` is the resumable walk that
+    /// lists them all.
     pub fn scrub(&self, io: &mut impl BlockIo) -> Result<(), StoreError> {
         for entry in &self.entries {
-            let payload = self.read_payload(io, entry)?;
-            if sha256::digest(&payload) != entry.hash {
-                return Err(StoreError::HashMismatch);
-            }
+            self.read_verified(io, entry)?;
         }
         Ok(())
     }
 
-    /// Append and seal a new object. Identical content already present is an
-    /// idempotent no-op returning the existing identity; the same identity
-    /// with different payload bytes is rejected. Commit order is record
-    /// sectors, flush, superblock into the older slot, flush — an
-    /// interruption anywhere leaves the previously committed root intact.
-    pub fn put(
-        &mut self,
+    /// Check records from `cursor` onward until the next record would take
+    /// the step past `sector_budget` sectors, adding each damaged one to
+    /// `report`. A step always checks at least one record, so a budget
+    /// smaller than the largest record still makes progress.
+    ///
+    /// Damage is reported, not returned: a record that cannot be read,
+    /// whose header changed, or whose payload no longer hashes is listed and
+    /// the walk moves on. On a mirror, a damaged record with a good copy is
+    /// rewritten from it and counted in `repaired` instead. Only a `Timeout`
+    /// is an error, and it leaves the cursor on the record that timed out so
+    /// the next step retries it.
+    /// Records appended after the cursor was started are reached too; the
+    /// step that finds the cursor at the append offset is `Complete`.
+    pub fn scrub_step(
+        &self,
+        io: &mut impl BlockIo,
+        cursor: &mut ScrubCursor,
+        sector_budget: u64,
+        report: &mut ScrubReport,
+    ) -> Result<ScrubProgress, StoreError> {
+        match cursor.origin {
+            None => cursor.next_lba = self.record_start,
+            Some((sequence, count))
+                if self.sequence.checked_sub(sequence)
+                    == self
+                        .entries
+                        .len()
+                        .checked_sub(count)
+                        .map(|puts| puts as u64) => {}
+            Some(_) => {
+                cursor.next_lba = self.record_start;
+                *report = ScrubReport::default();
+            }
+        }
+        cursor.origin = Some((self.sequence, self.entries.len()));
+        let mut spent = 0u64;
+        let start = cursor.next_lba;
+        for entry in self.entries.iter().filter(|entry| entry.lba >= start) {
+            let sectors = self.record_sectors(entry.payload_len as u64)?;
+            if spent > 0 && spent + sectors > sector_budget {
+                return Ok(ScrubProgress::Partial);
+            }
+            let mut damage = None;
+            if io.copies() < 2 {
+                damage = self.check_record(io, entry, None)?;
+            } else {
+                // Reads are served by one copy, so rot in another would
+                // never surface there; scrub is what checks them all.
+                for copy in 0..io.copies() {
+                    if let Some(kind) = self.check_record(io, entry, Some(copy))? {
+                        damage.get_or_insert(kind);
+                    }
+                }
+                if damage.is_some() && matches!(self.repair_record(io, entry)?, Some((_, true))) {
+                    report.repaired += 1;
+                    damage = None;
+                }
+            }
+            if let Some(kind) = damage {
+                report.damaged.push(Damage {
+                    lba: entry.lba,
+                    hash: entry.hash,
+                    obj_type: entry.obj_type,
+                    kind,
+                    referenced_by: Vec::new(),
+                });
+            }
+            spent += sectors;
+            report.records_checked += 1;
+            report.sectors_read += sectors;
+            cursor.next_lba = entry.lba + sectors;
+        }
+        Ok(ScrubProgress::Complete)
+    }
+
+    /// Name, for each damaged record in `report`, every root in `roots`
+    /// whose closure reaches it. `references` is `compact`'s callback: it is
+    /// what turns a generation or directory snapshot into the hashes it
+    /// names. Damaged records are never expanded — their bytes cannot be
+    /// trusted to say what they reference — and intact ones are re-verified
+    /// before they are, so a record scrub has not yet reached cannot lend
+    /// false edges either.
+    pub fn attribute(
+        &self,
+        io: &mut impl BlockIo,
+        report: &mut ScrubReport,
+        roots: &[[u8; 32]],
+        mut references: impl FnMut(u32, &[u8], &mut Vec<[u8; 32]>),
+    ) -> Result<(), StoreError> {
+        for root in roots {
+            let mut seen = alloc::vec![false; self.entries.len()];
+            let mut pending = alloc::vec![*root];
+            while let Some(hash) = pending.pop() {
+                for damage in report
+                    .damaged
+                    .iter_mut()
+                    .filter(|damage| damage.hash == hash)
+                {
+                    if !damage.referenced_by.contains(root) {
+                        damage.referenced_by.push(*root);
+                    }
+                }
+                for (entry, seen) in self.entries.iter().zip(seen.iter_mut()) {
+                    if entry.hash != hash || *seen {
+                        continue;
+                    }
+                    *seen = true;
+                    if report.damaged.iter().any(|damage| damage.lba == entry.lba) {
+                        continue;
+                    }
+                    let payload = match self.read_verified(io, entry) {
+                        Ok(payload) => payload,
+                        Err(StoreError::HashMismatch | StoreError::Io(IoError::Device)) => {
+                            continue;
+                        }
+                        Err(error) => return Err(error),
+                    };
+                    references(entry.obj_type, &payload, &mut pending);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Re-read one record, from `copy` or from whichever copy `read_sector`
+    /// chooses, and say what, if anything, is wrong with it.
+    fn check_record(
+        &self,
+        io: &mut impl BlockIo,
+        entry: &Entry,
+        copy: Option<usize>,
+    ) -> Result<Option<DamageKind>, StoreError> {
+        let mut header = [0u8; SECTOR_SIZE];
+        let lba = self.first_lba + entry.lba;
+        let read = match copy {
+            Some(copy) => io.read_copy(copy, lba, &mut header),
+            None => io.read_sector(lba, &mut header),
+        };
+        match read {
+            Ok(()) => {}
+            Err(IoError::Device) => return Ok(Some(DamageKind::Unreadable)),
+            Err(error) => return Err(error.into()),
+        }
+        match decode_record_header(&header, self.format_version, self.encrypted()) {
+            Ok(found)
+                if found.hash == entry.hash
+                    && found.obj_type == entry.obj_type
+                    && found.payload_len == entry.payload_len => {}
+            _ => return Ok(Some(DamageKind::CorruptRecord)),
+        }
+        let payload = match self
+            .read_record(io, copy, entry)
+            .and_then(|bytes| self.unseal(bytes, entry))
+        {
+            Ok(payload) => payload,
+            Err(StoreError::Io(IoError::Device)) => return Ok(Some(DamageKind::Unreadable)),
+            Err(StoreError::HashMismatch) => return Ok(Some(DamageKind::HashMismatch)),
+            Err(error) => return Err(error),
+        };
+        if sha256::digest(&payload) != entry.hash {
+            return Ok(Some(DamageKind::HashMismatch));
+        }
+        Ok(None)
+    }
+
+    /// Append and seal a new object. Identical content already present is an
+    /// idempotent no-op returning the existing identity; the same identity
+    /// with different payload bytes is rejected. Commit order is record
+    /// sectors and the index, flush, superblock into the older slot, flush —
+    /// an interruption anywhere leaves the previously committed root intact.
+    pub fn put(
+        &mut self,
+        io: &mut impl BlockIo,
+        obj_type: u32,
+        payload: &[u8],
+    ) -> Result<[u8; 32], StoreError> {
+        self.put_with_metadata(io, obj_type, payload, &[0; 32], &[0; TYPE_METADATA_BYTES])
+    }
+
+    /// `put`, recording the generation the object belongs to and bytes for
+    /// its type in the new record's header. When the content is already
+    /// present nothing is written, and the existing record keeps the
+    /// metadata it was first committed with.
+    pub fn put_with_metadata(
+        &mut self,
         io: &mut impl BlockIo,
         obj_type: u32,
         payload: &[u8],
+        generation: &[u8; 32],
+        type_metadata: &[u8; TYPE_METADATA_BYTES],
     ) -> Result<[u8; 32], StoreError> {
         if payload.len() > MAX_OBJECT_PAYLOAD {
             return Err(StoreError::PayloadTooLarge);
@@ -318,10 +1363,63 @@ impl ObjectStore {
             }
             return Err(StoreError::DuplicateIdentity);
         }
+        self.append(io, obj_type, payload, hash, generation, type_metadata)
+    }
+
+    /// The metadata the first record holding `hash` was committed with,
+    /// re-read from its header so a damaged header is not reported as data.
+    pub fn metadata(
+        &self,
+        io: &mut impl BlockIo,
+        hash: &[u8; 32],
+    ) -> Result<RecordMetadata, StoreError> {
+        let entry = *self.records(hash).next().ok_or(StoreError::NotFound)?;
+        self.record_metadata(io, &entry)
+    }
+
+    /// `metadata`, for one particular record. A caller walking `records` or
+    /// `records_of_type` asks this rather than `metadata`, which would answer
+    /// for whichever record of the hash came first.
+    pub fn record_metadata(
+        &self,
+        io: &mut impl BlockIo,
+        entry: &Entry,
+    ) -> Result<RecordMetadata, StoreError> {
+        if self.read_only() {
+            return Ok(RecordMetadata::default());
+        }
+        let mut header = [0u8; SECTOR_SIZE];
+        io.read_sector(self.first_lba + entry.lba, &mut header)?;
+        let found = decode_record_header(&header, self.format_version, self.encrypted())?;
+        if found.hash != entry.hash || found.obj_type != entry.obj_type {
+            return Err(StoreError::CorruptRecord);
+        }
+        Ok(RecordMetadata {
+            creation_sequence: u64_field(&header, RECORD_CREATION_SEQUENCE_OFFSET),
+            generation: header[RECORD_GENERATION_OFFSET..RECORD_TYPE_METADATA_OFFSET]
+                .try_into()
+                .expect("generation field"),
+            type_metadata: header[RECORD_TYPE_METADATA_OFFSET..RECORD_HEADER_CRC32_OFFSET]
+                .try_into()
+                .expect("type metadata field"),
+        })
+    }
+
+    /// Append one record for `payload`, already hashed, and commit it.
+    fn append(
+        &mut self,
+        io: &mut impl BlockIo,
+        obj_type: u32,
+        payload: &[u8],
+        hash: [u8; 32],
+        generation: &[u8; 32],
+        type_metadata: &[u8; TYPE_METADATA_BYTES],
+    ) -> Result<[u8; 32], StoreError> {
+        self.writable()?;
         if self.entries.len() >= MAX_OBJECTS {
             return Err(StoreError::StoreFull);
         }
-        let sectors = record_sectors(payload.len() as u64)?;
+        let sectors = self.record_sectors(payload.len() as u64)?;
         let end = self
             .append_lba
             .checked_add(sectors)
@@ -333,10 +1431,25 @@ impl ObjectStore {
         // a wrapped commit could make a stale slot outrank the new root.
         let next_sequence = self.sequence.checked_add(1).ok_or(StoreError::StoreFull)?;
 
-        let header = encode_record_header(obj_type, payload, &hash);
+        let metadata = RecordMetadata {
+            creation_sequence: next_sequence,
+            generation: *generation,
+            type_metadata: *type_metadata,
+        };
+        let header = encode_record_header(obj_type, payload, &hash, &metadata, self.encrypted());
         let mut record = alloc::vec![0u8; sectors as usize * SECTOR_SIZE];
         record[..RECORD_HEADER].copy_from_slice(&header);
-        record[RECORD_HEADER..RECORD_HEADER + payload.len()].copy_from_slice(payload);
+        let end_of_payload = RECORD_HEADER + payload.len();
+        record[RECORD_HEADER..end_of_payload].copy_from_slice(payload);
+        if let Some(key) = &self.key {
+            let tag = chacha20poly1305::seal(
+                key.bytes(),
+                &nonce(RECORD_DOMAIN, &header),
+                &header,
+                &mut record[RECORD_HEADER..end_of_payload],
+            );
+            record[end_of_payload..end_of_payload + AUTH_TAG_BYTES].copy_from_slice(&tag);
+        }
         for index in 0..sectors {
             let start = index as usize * SECTOR_SIZE;
             let sector: &[u8; SECTOR_SIZE] = record[start..start + SECTOR_SIZE]
@@ -344,53 +1457,512 @@ impl ObjectStore {
                 .expect("sector-aligned record");
             io.write_sector(self.first_lba + self.append_lba + index, sector)?;
         }
-        io.flush()?;
 
-        let target = self.active.other();
-        let entry = Entry {
+        let mut entries = self.entries.clone();
+        entries.push(Entry {
             hash,
             obj_type,
             payload_len: payload.len() as u32,
             lba: self.append_lba,
+        });
+        self.commit(io, self.record_start, end, entries)?;
+        Ok(hash)
+    }
+
+    /// Store `payload` under `obj_type`, or confirm it is already stored so.
+    ///
+    /// Unlike `put`, the type tag is part of what must match: identical bytes
+    /// committed only under another tag get a record of their own, so a
+    /// caller that reads the tag back never sees someone else's. A matching
+    /// record is re-read and compared before it is reused, exactly as `put`
+    /// does; bytes that hash alike but differ are still `DuplicateIdentity`.
+    pub fn put_or_reuse(
+        &mut self,
+        io: &mut impl BlockIo,
+        obj_type: u32,
+        payload: &[u8],
+    ) -> Result<([u8; 32], Placement), StoreError> {
+        self.put_or_reuse_with_metadata(io, obj_type, payload, &[0; 32], &[0; TYPE_METADATA_BYTES])
+    }
+
+    /// `put_or_reuse`, where the header metadata must match as well: a record
+    /// of the same bytes and tag written for another generation is not this
+    /// one, and a caller that finds its records by generation would never
+    /// see it.
+    pub fn put_or_reuse_with_metadata(
+        &mut self,
+        io: &mut impl BlockIo,
+        obj_type: u32,
+        payload: &[u8],
+        generation: &[u8; 32],
+        type_metadata: &[u8; TYPE_METADATA_BYTES],
+    ) -> Result<([u8; 32], Placement), StoreError> {
+        if payload.len() > MAX_OBJECT_PAYLOAD {
+            return Err(StoreError::PayloadTooLarge);
+        }
+        let hash = sha256::digest(payload);
+        if let Some(entry) = self.records(&hash).next().copied()
+            && self.read_payload(io, &entry)? != payload
+        {
+            return Err(StoreError::DuplicateIdentity);
+        }
+        let candidates: Vec<Entry> = self
+            .records(&hash)
+            .filter(|entry| entry.obj_type == obj_type)
+            .copied()
+            .collect();
+        for entry in &candidates {
+            let metadata = self.record_metadata(io, entry)?;
+            if metadata.generation == *generation && metadata.type_metadata == *type_metadata {
+                return Ok((hash, Placement::Reused));
+            }
+        }
+        self.append(io, obj_type, payload, hash, generation, type_metadata)
+            .map(|hash| (hash, Placement::Written))
+    }
+
+    /// Store an object of `len` bytes, up to `MAX_MANIFEST_CHUNKS` chunks, and
+    /// return the manifest's hash as its identity.
+    ///
+    /// The bytes come from `source`, which fills its buffer with the object
+    /// from the offset it is given. It is asked for each `MAX_OBJECT_PAYLOAD`
+    /// chunk in order, twice: once to hash the chunks and the whole, which is
+    /// all the manifest and the room check need, and once more to write them.
+    /// Nothing larger than one chunk is held, so an object need never exist
+    /// whole in the caller's memory. A chunk that reads differently the
+    /// second time is `HashMismatch`, and no manifest is written. `source` is
+    /// handed the store and the device as well, so an edit can read the
+    /// chunks of the object it replaces while it writes this one.
+    ///
+    /// Each chunk is an ordinary `put`, so a chunk already present — from
+    /// this object or any other — is reused rather than written again. Room
+    /// for every new record is checked before the first write: a refusal for
+    /// space leaves the store unchanged, and an I/O failure part-way leaves
+    /// only chunks no manifest names yet.
+    pub fn put_large<IO: BlockIo>(
+        &mut self,
+        io: &mut IO,
+        obj_type: u32,
+        len: u64,
+        mut source: impl FnMut(&Self, &mut IO, u64, &mut [u8]) -> Result<(), StoreError>,
+    ) -> Result<[u8; 32], StoreError> {
+        self.writable()?;
+        let mut manifest = manifest_for(obj_type, len)?;
+        let chunk_count = (manifest.len() - MANIFEST_HEADER) / 32;
+        let mut buffer = alloc::vec![0u8; len.min(MAX_OBJECT_PAYLOAD as u64) as usize];
+        let mut chunk_at = |store: &Self, io: &mut IO, index: usize, buffer: &mut [u8]| {
+            let offset = (index * MAX_OBJECT_PAYLOAD) as u64;
+            let chunk_len = (len - offset).min(MAX_OBJECT_PAYLOAD as u64) as usize;
+            source(store, io, offset, &mut buffer[..chunk_len]).map(|()| chunk_len)
+        };
+
+        let mut root = Sha256::new();
+        let mut fresh: Vec<[u8; 32]> = Vec::new();
+        let mut sectors = 0u64;
+        for index in 0..chunk_count {
+            let chunk_len = chunk_at(self, io, index, &mut buffer)?;
+            let chunk = &buffer[..chunk_len];
+            let hash = sha256::digest(chunk);
+            root.update(chunk);
+            manifest[MANIFEST_HEADER + index * 32..][..32].copy_from_slice(&hash);
+            if self.stat(&hash).is_none() && !fresh.contains(&hash) {
+                fresh.push(hash);
+                sectors += self.record_sectors(chunk.len() as u64)?;
+            }
+        }
+        manifest[MANIFEST_ROOT_HASH_OFFSET..MANIFEST_HEADER].copy_from_slice(&root.finalize());
+        let hash = sha256::digest(&manifest);
+        if self.stat(&hash).is_none() {
+            fresh.push(hash);
+            sectors += self.record_sectors(manifest.len() as u64)?;
+        }
+        if self.entries.len() + fresh.len() > MAX_OBJECTS
+            || self
+                .append_lba
+                .checked_add(sectors)
+                .is_none_or(|end| end > self.partition_sectors)
+        {
+            return Err(StoreError::StoreFull);
+        }
+
+        for index in 0..chunk_count {
+            let chunk_len = chunk_at(self, io, index, &mut buffer)?;
+            let chunk = &buffer[..chunk_len];
+            if sha256::digest(chunk) != manifest_chunk(&manifest, index) {
+                return Err(StoreError::HashMismatch);
+            }
+            self.put(io, CHUNK_OBJECT_TYPE, chunk)?;
+        }
+        self.put(io, MANIFEST_OBJECT_TYPE, &manifest)
+    }
+
+    /// Stream a chunked object into `sink`, one chunk at a time, once all of
+    /// it has verified.
+    ///
+    /// The manifest is re-verified against `hash`, each chunk against the
+    /// hash the manifest lists for it, and the reassembled content against
+    /// the manifest's root hash, all before the first chunk reaches `sink`: a
+    /// damaged object is refused with nothing delivered. The chunks are then
+    /// read again to be delivered, each re-verified against the same hash, so
+    /// the call reads the object twice and holds one chunk at a time. Only a
+    /// device that fails between the two reads can end a delivery part-way,
+    /// and then with an error and with every byte delivered belonging to the
+    /// object that verified.
+    pub fn get_large(
+        &self,
+        io: &mut impl BlockIo,
+        hash: &[u8; 32],
+        mut sink: impl FnMut(&[u8]),
+    ) -> Result<(u32, u64), StoreError> {
+        let entry = *self
+            .entries
+            .iter()
+            .find(|entry| &entry.hash == hash)
+            .ok_or(StoreError::NotFound)?;
+        if entry.obj_type != MANIFEST_OBJECT_TYPE {
+            return Err(StoreError::WrongObjectType);
+        }
+        let payload = self.read_verified(io, &entry)?;
+        let manifest = decode_manifest(&payload)?;
+        let mut chunks = Vec::with_capacity(manifest.chunk_count as usize);
+        let mut remaining = manifest.total_len;
+        for index in 0..manifest.chunk_count as usize {
+            let chunk_hash = manifest_chunk(&payload, index);
+            let chunk = *self
+                .entries
+                .iter()
+                .find(|entry| entry.hash == chunk_hash)
+                .ok_or(StoreError::NotFound)?;
+            let expected = remaining.min(MAX_OBJECT_PAYLOAD as u64);
+            if chunk.payload_len as u64 != expected {
+                return Err(StoreError::CorruptRecord);
+            }
+            chunks.push(chunk);
+            remaining -= expected;
+        }
+
+        let mut hasher = Sha256::new();
+        for chunk in &chunks {
+            hasher.update(&self.read_verified(io, chunk)?);
+        }
+        if hasher.finalize() != manifest.root_hash {
+            return Err(StoreError::HashMismatch);
+        }
+        for chunk in &chunks {
+            sink(&self.read_verified(io, chunk)?);
+        }
+        Ok((manifest.obj_type, manifest.total_len))
+    }
+
+    /// Reclaim every record no root reaches.
+    ///
+    /// Mark: each hash in `roots` the store holds is live, and so is every
+    /// hash `references` reports for a live object's type and payload — a
+    /// generation naming its executables, a directory snapshot naming its
+    /// children. A live hash keeps every record holding it, whatever its type
+    /// tag. A root the store does not hold is skipped: it names nothing
+    /// this pass could free. Each live payload is re-verified as it is marked,
+    /// so a damaged record stops the pass before any write rather than being
+    /// copied forward as valid.
+    ///
+    /// Compact, in at most two commits, each into the older superblock slot:
+    ///
+    /// 1. *Trim.* The region shrinks to span only the first through the last
+    ///    live record. Nothing is copied; garbage at either end simply falls
+    ///    outside the committed root.
+    /// 2. *Copy.* If garbage remains between live records, or the region
+    ///    does not start at the record area, the live records are copied in
+    ///    index order into sectors the trimmed root does not reference — the
+    ///    start of the record area if they fit below it, otherwise past its
+    ///    end — and committed as the new region; the commit's flush makes
+    ///    the copies durable before its superblock is written.
+    ///
+    /// No write ever lands inside the region the last commit named, so an
+    /// interruption at any point leaves that root and every object in it
+    /// readable. A live set that fits on neither side of the trimmed region
+    /// keeps whatever the trim reclaimed and is otherwise `StoreFull`.
+    pub fn compact(
+        &mut self,
+        io: &mut impl BlockIo,
+        roots: &[[u8; 32]],
+        references: impl FnMut(u32, &[u8], &mut Vec<[u8; 32]>),
+    ) -> Result<Compaction, StoreError> {
+        self.compact_below(io, self.partition_sectors, roots, references)
+    }
+
+    /// `compact` on a partition whose sectors from `limit` on hold something
+    /// the store does not own — the generation partition keeps BootState
+    /// there. A copy never lands at or past `limit`, and `free_sectors`
+    /// counts only what lies below it.
+    pub fn compact_below(
+        &mut self,
+        io: &mut impl BlockIo,
+        limit: u64,
+        roots: &[[u8; 32]],
+        mut references: impl FnMut(u32, &[u8], &mut Vec<[u8; 32]>),
+    ) -> Result<Compaction, StoreError> {
+        self.writable()?;
+        let limit = limit.min(self.partition_sectors);
+        let mut live = alloc::vec![false; self.entries.len()];
+        let mut pending = roots.to_vec();
+        while let Some(hash) = pending.pop() {
+            for (entry, live) in self.entries.iter().zip(live.iter_mut()) {
+                if entry.hash != hash || *live {
+                    continue;
+                }
+                *live = true;
+                let payload = self.read_verified(io, entry)?;
+                references(entry.obj_type, &payload, &mut pending);
+            }
+        }
+
+        let before = self.entries.len();
+        let report = |store: &Self| Compaction {
+            kept: store.entries.len(),
+            reclaimed: before - store.entries.len(),
+            free_sectors: limit.saturating_sub(store.append_lba),
+        };
+        let (Some(first), Some(last)) = (
+            live.iter().position(|live| *live),
+            live.iter().rposition(|live| *live),
+        ) else {
+            if before == 0 {
+                return Ok(report(self));
+            }
+            self.commit(io, RECORD_AREA_START, RECORD_AREA_START, Vec::new())?;
+            return Ok(report(self));
+        };
+        let gapless = live[first..=last].iter().all(|live| *live);
+        if first != 0 || last + 1 != before {
+            let start = self.entries[first].lba;
+            let end = self.entries[last].lba
+                + self.record_sectors(self.entries[last].payload_len as u64)?;
+            let trimmed = self.entries[first..=last].to_vec();
+            self.commit(io, start, end, trimmed)?;
+            live = live[first..=last].to_vec();
+        }
+        if gapless && self.record_start == RECORD_AREA_START {
+            return Ok(report(self));
+        }
+
+        let mut kept: Vec<Entry> = self
+            .entries
+            .iter()
+            .zip(&live)
+            .filter(|(_, live)| **live)
+            .map(|(entry, _)| *entry)
+            .collect();
+        let mut live_sectors = 0u64;
+        for entry in &kept {
+            live_sectors = live_sectors
+                .checked_add(self.record_sectors(entry.payload_len as u64)?)
+                .ok_or(StoreError::CorruptRecord)?;
+        }
+        // The low placement is preferred: it leaves the whole tail free. A
+        // gapless region that cannot move down is already as compact as a
+        // copy past its end would make it.
+        let start = if RECORD_AREA_START + live_sectors <= self.record_start {
+            RECORD_AREA_START
+        } else if gapless {
+            return Ok(report(self));
+        } else if self
+            .append_lba
+            .checked_add(live_sectors)
+            .is_some_and(|end| end <= limit)
+        {
+            self.append_lba
+        } else if self.entries.len() < before {
+            return Ok(report(self));
+        } else {
+            return Err(StoreError::StoreFull);
         };
+
+        let mut cursor = start;
+        for entry in &mut kept {
+            let sectors = self.record_sectors(entry.payload_len as u64)?;
+            let mut sector = [0u8; SECTOR_SIZE];
+            for index in 0..sectors {
+                io.read_sector(self.first_lba + entry.lba + index, &mut sector)?;
+                io.write_sector(self.first_lba + cursor + index, &sector)?;
+            }
+            entry.lba = cursor;
+            cursor += sectors;
+        }
+        self.commit(io, start, cursor, kept)?;
+        Ok(report(self))
+    }
+
+    /// Commit a region whose records are already written: write its index
+    /// into the older slot's index area, flush, write the older slot's
+    /// superblock, flush. Neither write lands anywhere the active root names.
+    fn commit(
+        &mut self,
+        io: &mut impl BlockIo,
+        record_start: u64,
+        append_lba: u64,
+        entries: Vec<Entry>,
+    ) -> Result<(), StoreError> {
+        self.writable()?;
+        let sequence = self.sequence.checked_add(1).ok_or(StoreError::StoreFull)?;
+        let target = self.active.other();
+        let index = encode_index(sequence, &entries);
+        for (offset, chunk) in index.chunks(SECTOR_SIZE).enumerate() {
+            let mut sector = [0u8; SECTOR_SIZE];
+            sector[..chunk.len()].copy_from_slice(chunk);
+            io.write_sector(self.first_lba + target.index_lba() + offset as u64, &sector)?;
+        }
+        io.flush()?;
+
         let superblock = Superblock {
-            sequence: next_sequence,
-            append_lba: end,
-            object_count: (self.entries.len() + 1) as u32,
+            format_version: FORMAT_VERSION,
+            sequence,
+            record_start,
+            append_lba,
+            object_count: entries.len() as u32,
+            index_crc32: crc32(&index),
+            encrypted: self.encrypted(),
         };
-        let sector = encode_superblock(&superblock, self.partition_sectors);
+        let sector = seal_superblock(&superblock, self.partition_sectors, self.key.as_ref());
         io.write_sector(self.first_lba + target.lba(), &sector)?;
 
         // Once the superblock write has completed, a flush failure is
         // ambiguous: the new root may already be durable. Advance the live
         // handle before reporting that error so a caller that retries cannot
-        // overwrite the record the on-disk root may now reference.
+        // overwrite a record the on-disk root may now reference.
         self.sequence = superblock.sequence;
-        self.append_lba = end;
+        self.record_start = record_start;
+        self.append_lba = append_lba;
         self.active = target;
-        self.entries.push(entry);
+        self.entries = entries;
         io.flush()?;
-        Ok(hash)
+        Ok(())
     }
 
     fn read_payload(&self, io: &mut impl BlockIo, entry: &Entry) -> Result<Vec<u8>, StoreError> {
-        let sectors = record_sectors(entry.payload_len as u64)?;
+        let bytes = self.read_record(io, None, entry)?;
+        self.unseal(bytes, entry)
+    }
+
+    /// `read_payload`, checked against the entry's hash. On a device that
+    /// keeps copies, a record that fails the check or that the device
+    /// refuses is looked for in each copy and repaired from the first good
+    /// one.
+    fn read_verified(&self, io: &mut impl BlockIo, entry: &Entry) -> Result<Vec<u8>, StoreError> {
+        let error = match self.read_payload(io, entry) {
+            Ok(payload) if sha256::digest(&payload) == entry.hash => return Ok(payload),
+            Ok(_) => StoreError::HashMismatch,
+            Err(error @ (StoreError::HashMismatch | StoreError::Io(IoError::Device))) => error,
+            Err(error) => return Err(error),
+        };
+        match self.repair_record(io, entry)? {
+            Some((payload, _)) => Ok(payload),
+            None => Err(error),
+        }
+    }
+
+    /// Find a copy of `entry` whose header and payload both verify, and
+    /// write it over every other copy. Returns the payload and whether every
+    /// rewrite reached the device, or `None` when no copy is good — always,
+    /// on a device with one copy.
+    fn repair_record(
+        &self,
+        io: &mut impl BlockIo,
+        entry: &Entry,
+    ) -> Result<Option<(Vec<u8>, bool)>, StoreError> {
+        if io.copies() < 2 {
+            return Ok(None);
+        }
+        for copy in 0..io.copies() {
+            let bytes = match self.read_record(io, Some(copy), entry) {
+                Ok(bytes) => bytes,
+                Err(StoreError::Io(IoError::Device)) => continue,
+                Err(error) => return Err(error),
+            };
+            let header = bytes[..SECTOR_SIZE].try_into().expect("header sector");
+            match decode_record_header(header, self.format_version, self.encrypted()) {
+                Ok(found)
+                    if found.hash == entry.hash
+                        && found.obj_type == entry.obj_type
+                        && found.payload_len == entry.payload_len => {}
+                _ => continue,
+            }
+            let payload = match self.unseal(bytes.clone(), entry) {
+                Ok(payload) if sha256::digest(&payload) == entry.hash => payload,
+                _ => continue,
+            };
+            let rewritten = rewrite_copies(io, copy, self.first_lba + entry.lba, &bytes);
+            return Ok(Some((payload, rewritten)));
+        }
+        Ok(None)
+    }
+
+    /// Every sector of `entry`'s record, from `copy` or from whichever copy
+    /// `read_sector` chooses.
+    fn read_record(
+        &self,
+        io: &mut impl BlockIo,
+        copy: Option<usize>,
+        entry: &Entry,
+    ) -> Result<Vec<u8>, StoreError> {
+        let sectors = self.record_sectors(entry.payload_len as u64)?;
         let mut bytes = alloc::vec![0u8; sectors as usize * SECTOR_SIZE];
         for index in 0..sectors {
             let start = index as usize * SECTOR_SIZE;
             let sector: &mut [u8; SECTOR_SIZE] = (&mut bytes[start..start + SECTOR_SIZE])
                 .try_into()
                 .expect("sector-aligned buffer");
-            io.read_sector(self.first_lba + entry.lba + index, sector)?;
+            let lba = self.first_lba + entry.lba + index;
+            match copy {
+                Some(copy) => io.read_copy(copy, lba, sector)?,
+                None => io.read_sector(lba, sector)?,
+            }
         }
-        let len = entry.payload_len as usize;
-        Ok(bytes[RECORD_HEADER..RECORD_HEADER + len].to_vec())
+        Ok(bytes)
+    }
+
+    /// The payload of a record read by `read_record`, opened first if the
+    /// store is encrypted.
+    fn unseal(&self, mut bytes: Vec<u8>, entry: &Entry) -> Result<Vec<u8>, StoreError> {
+        let header = record_header_len(self.format_version);
+        let end = header + entry.payload_len as usize;
+        if let Some(key) = &self.key {
+            // A tag that fails is damage to the sealed bytes, reported the
+            // same way a plaintext payload that no longer hashes is.
+            let (aad, sealed) = bytes.split_at_mut(header);
+            let tag = sealed[end - header..end - header + AUTH_TAG_BYTES]
+                .try_into()
+                .expect("tag field");
+            chacha20poly1305::open(
+                key.bytes(),
+                &nonce(RECORD_DOMAIN, aad),
+                aad,
+                &mut sealed[..end - header],
+                &tag,
+            )
+            .map_err(|_| StoreError::HashMismatch)?;
+        }
+        Ok(bytes[header..end].to_vec())
+    }
+
+    fn record_sectors(&self, payload_len: u64) -> Result<u64, StoreError> {
+        record_sectors(
+            record_overhead(self.format_version, self.encrypted()),
+            payload_len,
+        )
+    }
+
+    fn writable(&self) -> Result<(), StoreError> {
+        if self.read_only() {
+            return Err(StoreError::ReadOnly);
+        }
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::store_disk::record_area_start;
 
     const FIRST_LBA: u64 = 8;
     const SECTORS: u64 = 96;
@@ -407,16 +1979,23 @@ mod tests {
         fail_write_after: Option<usize>,
         flushes: usize,
         fail_flush_after: Option<usize>,
+        /// One sector that refuses reads, and how.
+        unreadable: Option<(u64, IoError)>,
     }
 
     impl MemoryDisk {
         fn new() -> Self {
+            Self::with_sectors(SECTORS)
+        }
+
+        fn with_sectors(partition_sectors: u64) -> Self {
             Self {
-                sectors: alloc::vec![[0u8; SECTOR_SIZE]; (FIRST_LBA + SECTORS) as usize],
+                sectors: alloc::vec![[0u8; SECTOR_SIZE]; (FIRST_LBA + partition_sectors) as usize],
                 writes: 0,
                 fail_write_after: None,
                 flushes: 0,
                 fail_flush_after: None,
+                unreadable: None,
             }
         }
 
@@ -435,6 +2014,11 @@ mod tests {
 
     impl BlockIo for MemoryDisk {
         fn read_sector(&mut self, lba: u64, out: &mut [u8; SECTOR_SIZE]) -> Result<(), IoError> {
+            if let Some((bad, error)) = self.unreadable
+                && bad == lba
+            {
+                return Err(error);
+            }
             let sector = self.sectors.get(lba as usize).ok_or(IoError::Device)?;
             out.copy_from_slice(sector);
             Ok(())
@@ -463,9 +2047,13 @@ mod tests {
     }
 
     fn partition() -> Partition {
+        partition_of(SECTORS)
+    }
+
+    fn partition_of(partition_sectors: u64) -> Partition {
         Partition {
             first_lba: FIRST_LBA,
-            last_lba: FIRST_LBA + SECTORS - 1,
+            last_lba: FIRST_LBA + partition_sectors - 1,
             type_guid: crate::gpt::SLIME_STORE_TYPE_GUID,
         }
     }
@@ -473,18 +2061,22 @@ mod tests {
     /// A freshly formatted store: slot A carries the genesis root and the record
     /// area is empty.
     fn formatted() -> MemoryDisk {
-        let mut disk = MemoryDisk::new();
-        let genesis = Superblock {
-            sequence: 1,
-            append_lba: RECORD_AREA_START,
-            object_count: 0,
-        };
-        let sector = encode_superblock(&genesis, SECTORS);
-        disk.sectors[(FIRST_LBA + SLOT_A_LBA) as usize] = sector;
+        formatted_with(SECTORS)
+    }
+
+    fn formatted_with(partition_sectors: u64) -> MemoryDisk {
+        let mut disk = MemoryDisk::with_sectors(partition_sectors);
+        write_genesis(&mut disk, partition_sectors);
         disk.writes = 0;
+        disk.flushes = 0;
         disk
     }
 
+    /// Slot A's genesis root and the empty index it seals.
+    fn write_genesis(disk: &mut MemoryDisk, partition_sectors: u64) {
+        ObjectStore::format(disk, &partition_of(partition_sectors), None).expect("format");
+    }
+
     fn open(disk: &mut MemoryDisk) -> ObjectStore {
         ObjectStore::open(disk, &partition()).expect("store opens")
     }
@@ -541,11 +2133,12 @@ mod tests {
             (store.sequence(), store.object_count(), store.append_lba())
         };
 
-        // A put issues one write per record sector, then one superblock write.
-        // The payload below spans two sectors, so the boundaries are: first
-        // record sector, second record sector, superblock. Interrupting each in
-        // turn is the whole commit protocol.
-        for boundary in 0..3 {
+        // A put issues one write per record sector, one per index sector, then
+        // one superblock write. The payload below spans two sectors and the
+        // index one, so the boundaries are: first record sector, second record
+        // sector, index, superblock. Interrupting each in turn is the whole
+        // commit protocol.
+        for boundary in 0..4 {
             let mut interrupted = formatted();
             {
                 let mut store = open(&mut interrupted);
@@ -634,6 +2227,7 @@ mod tests {
             fail_write_after: None,
             flushes: 0,
             fail_flush_after: None,
+            unreadable: None,
         };
         let newest_lba = if slot_a.sequence > slot_b.sequence {
             SLOT_A_LBA
@@ -782,12 +2376,7 @@ mod tests {
         write_header(&mut disk, CAPACITY - 1, 1, BACKUP_ENTRIES_LBA);
 
         // Genesis the store inside the partition GPT will resolve.
-        let genesis = Superblock {
-            sequence: 1,
-            append_lba: RECORD_AREA_START,
-            object_count: 0,
-        };
-        disk.sectors[(FIRST_LBA + SLOT_A_LBA) as usize] = encode_superblock(&genesis, SECTORS);
+        write_genesis(&mut disk, SECTORS);
 
         let resolved = {
             let snapshot = disk.sectors.clone();
@@ -799,7 +2388,7 @@ mod tests {
                 );
                 Ok(())
             };
-            validate_store_partition(&mut reader, CAPACITY).expect("GPT resolves the store")
+            validate_store_partition(&mut reader, CAPACITY, None).expect("GPT resolves the store")
         };
         assert_eq!(resolved.partition.first_lba, FIRST_LBA);
 
@@ -825,4 +2414,1296 @@ mod tests {
             Some(StoreError::PartitionTooSmall)
         );
     }
+
+    fn no_references(_: u32, _: &[u8], _: &mut Vec<[u8; 32]>) {}
+
+    /// A record no root reaches is gone after a compaction and a reopen; a
+    /// record a root names is still readable, byte for byte, from its new LBA.
+    #[test]
+    fn compaction_keeps_what_a_root_reaches_and_drops_the_rest() {
+        let mut disk = formatted();
+        let (kept, dropped) = {
+            let mut store = open(&mut disk);
+            let dropped = store.put(&mut disk, 1, b"superseded").expect("put");
+            let kept = store.put(&mut disk, 2, b"known good").expect("put");
+            let report = store
+                .compact(&mut disk, &[kept], no_references)
+                .expect("compact");
+            assert_eq!((report.kept, report.reclaimed), (1, 1));
+            (kept, dropped)
+        };
+        let store = open(&mut disk);
+        assert_eq!(store.object_count(), 1);
+        assert_eq!(store.stat(&dropped), None);
+        let mut out = alloc::vec![0u8; 64];
+        let (obj_type, read) = store.get(&mut disk, &kept, &mut out).expect("get");
+        assert_eq!((obj_type, &out[..read]), (2, &b"known good"[..]));
+        store
+            .scrub(&mut disk)
+            .expect("the compacted store scrubs clean");
+    }
+
+    /// The case the pass exists for: a store that refuses a put as full
+    /// accepts it once the garbage is compacted away.
+    #[test]
+    fn compaction_makes_a_full_store_appendable_again() {
+        let mut disk = formatted();
+        let mut store = open(&mut disk);
+        let payload = |index: u8| alloc::vec![index; SECTOR_SIZE * 4];
+        let mut last = [0u8; 32];
+        let mut index = 0u8;
+        loop {
+            match store.put(&mut disk, 1, &payload(index)) {
+                Ok(hash) => last = hash,
+                Err(StoreError::StoreFull) => break,
+                Err(error) => panic!("unexpected {error:?}"),
+            }
+            index += 1;
+        }
+        let report = store
+            .compact(&mut disk, &[last], no_references)
+            .expect("compact");
+        assert_eq!(report.kept, 1);
+        store
+            .put(&mut disk, 1, &payload(index))
+            .expect("the reclaimed space takes the refused put");
+        let reopened = open(&mut disk);
+        assert_eq!(reopened.object_count(), 2);
+        assert!(reopened.stat(&last).is_some());
+    }
+
+    /// Marking follows what a live object references, not only the roots.
+    #[test]
+    fn an_object_a_live_object_references_is_kept() {
+        let mut disk = formatted();
+        let mut store = open(&mut disk);
+        let child = store.put(&mut disk, 2, b"child").expect("put");
+        let orphan = store.put(&mut disk, 2, b"orphan").expect("put");
+        let parent = store.put(&mut disk, 1, &child).expect("put");
+        let report = store
+            .compact(&mut disk, &[parent], |obj_type, payload, out| {
+                if obj_type == 1 {
+                    out.push(payload.try_into().expect("one child hash"));
+                }
+            })
+            .expect("compact");
+        assert_eq!((report.kept, report.reclaimed), (2, 1));
+        assert!(store.stat(&child).is_some());
+        assert_eq!(store.stat(&orphan), None);
+    }
+
+    /// Compaction is a commit like any other: an interruption at each of its
+    /// writes — every copied sector, then the superblock — must leave the
+    /// previous root and every object in it intact.
+    #[test]
+    fn an_interrupted_compaction_leaves_the_previous_root_committed() {
+        let build = || {
+            let mut disk = formatted();
+            let mut store = open(&mut disk);
+            let hashes = [
+                store.put(&mut disk, 1, b"first").expect("put"),
+                store.put(&mut disk, 1, b"garbage").expect("put"),
+                store
+                    .put(&mut disk, 2, &alloc::vec![0x5Au8; SECTOR_SIZE])
+                    .expect("put"),
+            ];
+            (disk, hashes)
+        };
+        // The garbage sits between the two live records, so there is no trim
+        // and the copy goes past the append offset. The live records span one
+        // and two sectors, so the boundaries are: three copied sectors, the
+        // index sector, then the superblock.
+        for boundary in 0..5 {
+            let (mut disk, [first, garbage, live]) = build();
+            let committed = {
+                let store = open(&mut disk);
+                (store.sequence(), store.object_count(), store.append_lba())
+            };
+            disk.writes = 0;
+            disk.fail_write_after = Some(boundary);
+            {
+                let mut store = open(&mut disk);
+                let outcome = store.compact(&mut disk, &[first, live], no_references);
+                assert!(outcome.is_err(), "boundary {boundary} should fail");
+            }
+            disk.fail_write_after = None;
+            let store = open(&mut disk);
+            assert_eq!(
+                (store.sequence(), store.object_count(), store.append_lba()),
+                committed,
+                "boundary {boundary} moved the committed root",
+            );
+            assert!(store.stat(&garbage).is_some());
+            store.scrub(&mut disk).expect("the old region is untouched");
+        }
+    }
+
+    /// A damaged live record stops the pass before it writes anything, so a
+    /// corrupt object is never re-sealed under a fresh commit.
+    #[test]
+    fn a_damaged_live_record_is_refused_before_any_write() {
+        let mut disk = formatted();
+        let live = {
+            let mut store = open(&mut disk);
+            store.put(&mut disk, 1, b"garbage").expect("put");
+            store.put(&mut disk, 2, b"live").expect("put")
+        };
+        let live_lba = (FIRST_LBA + RECORD_AREA_START + 1) as usize;
+        disk.sectors[live_lba][RECORD_HEADER] ^= 0xFF;
+        let mut store = open(&mut disk);
+        let writes_before = disk.writes;
+        assert_eq!(
+            store.compact(&mut disk, &[live], no_references),
+            Err(StoreError::HashMismatch)
+        );
+        assert_eq!(disk.writes, writes_before, "a refused compaction wrote");
+    }
+
+    /// Nothing to reclaim is nothing to write: a pass over an all-live store
+    /// must not burn a superblock commit.
+    #[test]
+    fn a_store_with_no_garbage_is_left_alone() {
+        let mut disk = formatted();
+        let mut store = open(&mut disk);
+        let hash = store.put(&mut disk, 1, b"live").expect("put");
+        let (sequence, writes) = (store.sequence(), disk.writes);
+        let report = store
+            .compact(&mut disk, &[hash, [0xEE; 32]], no_references)
+            .expect("compact");
+        assert_eq!((report.kept, report.reclaimed), (1, 0));
+        assert_eq!((store.sequence(), disk.writes), (sequence, writes));
+    }
+
+    /// Garbage only at the ends is trimmed by a single commit — one index
+    /// sector and one superblock: nothing is copied when the live records
+    /// are already adjacent.
+    #[test]
+    fn garbage_at_the_ends_is_trimmed_without_copying() {
+        let mut disk = formatted();
+        let mut store = open(&mut disk);
+        let live = store.put(&mut disk, 1, b"live").expect("put");
+        store.put(&mut disk, 1, b"garbage").expect("put");
+        let writes = disk.writes;
+        let report = store
+            .compact(&mut disk, &[live], no_references)
+            .expect("compact");
+        assert_eq!((report.kept, report.reclaimed), (1, 1));
+        assert_eq!(
+            disk.writes,
+            writes + 2,
+            "one index sector and one superblock, no record copies"
+        );
+        assert_eq!(store.append_lba(), RECORD_AREA_START + 1);
+    }
+
+    /// A live set with no room below its region is copied past the append
+    /// offset; the next pass finds the low area free and moves it back down.
+    #[test]
+    fn a_live_set_that_cannot_move_down_is_copied_past_the_end() {
+        let mut disk = formatted();
+        let mut store = open(&mut disk);
+        let first = store.put(&mut disk, 1, b"first").expect("put");
+        store.put(&mut disk, 1, b"garbage one").expect("put");
+        let second = store.put(&mut disk, 1, b"second").expect("put");
+        let old_append = store.append_lba();
+        store
+            .compact(&mut disk, &[first, second], no_references)
+            .expect("first compaction");
+        assert_eq!(
+            store.record_start, old_append,
+            "copied past the append offset"
+        );
+
+        store.put(&mut disk, 1, b"garbage two").expect("put");
+        store
+            .compact(&mut disk, &[first, second], no_references)
+            .expect("second compaction");
+        assert_eq!(store.record_start, RECORD_AREA_START, "moved back down");
+        let reopened = open(&mut disk);
+        assert_eq!(reopened.object_count(), 2);
+        reopened.scrub(&mut disk).expect("intact");
+    }
+
+    /// Under a limit at the append offset the same live set has nowhere to
+    /// go: the pass refuses rather than copying over what lies past it.
+    #[test]
+    fn a_copy_never_crosses_the_limit() {
+        let mut disk = formatted();
+        let mut store = open(&mut disk);
+        let first = store.put(&mut disk, 1, b"first").expect("put");
+        store.put(&mut disk, 1, b"garbage").expect("put");
+        let second = store.put(&mut disk, 1, b"second").expect("put");
+        let (limit, writes) = (store.append_lba(), disk.writes);
+        assert_eq!(
+            store.compact_below(&mut disk, limit, &[first, second], no_references),
+            Err(StoreError::StoreFull)
+        );
+        assert_eq!(disk.writes, writes, "a refused compaction wrote");
+        assert_eq!(open(&mut disk).object_count(), 3);
+    }
+
+    /// An empty root set frees everything and leaves an empty region that
+    /// reopens and appends like a freshly formatted store.
+    #[test]
+    fn an_empty_root_set_empties_the_store() {
+        let mut disk = formatted();
+        let mut store = open(&mut disk);
+        store.put(&mut disk, 1, b"garbage").expect("put");
+        let report = store
+            .compact(&mut disk, &[], no_references)
+            .expect("compact");
+        assert_eq!((report.kept, report.reclaimed), (0, 1));
+        let mut reopened = open(&mut disk);
+        assert_eq!(reopened.object_count(), 0);
+        reopened.put(&mut disk, 1, b"fresh").expect("put");
+    }
+
+    /// Room for a three-chunk object and its manifest.
+    const LARGE_SECTORS: u64 = 256;
+
+    /// Content that spans two full chunks and a short third, with every
+    /// chunk distinct so reassembly order is observable.
+    fn large_content() -> Vec<u8> {
+        (0..2 * MAX_OBJECT_PAYLOAD + 100)
+            .map(|index| (index % 251) as u8)
+            .collect()
+    }
+
+    fn open_large(disk: &mut MemoryDisk) -> ObjectStore {
+        ObjectStore::open(disk, &partition_of(LARGE_SECTORS)).expect("store opens")
+    }
+
+    /// `put_large` over content the test already holds whole.
+    fn put_whole(
+        store: &mut ObjectStore,
+        disk: &mut MemoryDisk,
+        obj_type: u32,
+        content: &[u8],
+    ) -> Result<[u8; 32], StoreError> {
+        store.put_large(disk, obj_type, content.len() as u64, |_, _, offset, out| {
+            out.copy_from_slice(&content[offset as usize..][..out.len()]);
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn a_large_object_round_trips_through_a_reopen() {
+        let mut disk = formatted_with(LARGE_SECTORS);
+        let content = large_content();
+        let hash = {
+            let mut store = open_large(&mut disk);
+            put_whole(&mut store, &mut disk, 9, &content).expect("put_large")
+        };
+        let store = open_large(&mut disk);
+        assert_eq!(store.object_count(), 4, "three chunks and a manifest");
+        let mut out = Vec::new();
+        let (obj_type, len) = store
+            .get_large(&mut disk, &hash, |chunk| out.extend_from_slice(chunk))
+            .expect("get_large");
+        assert_eq!((obj_type, len), (9, content.len() as u64));
+        assert_eq!(out, content);
+    }
+
+    /// Chunks are ordinary content-addressed records: repeated chunks within
+    /// one object, and chunks shared with an earlier object, are stored once.
+    #[test]
+    fn identical_chunks_are_stored_once() {
+        let mut disk = formatted_with(LARGE_SECTORS);
+        let mut store = open_large(&mut disk);
+        let repeated = alloc::vec![0x5a; 2 * MAX_OBJECT_PAYLOAD];
+        let hash = put_whole(&mut store, &mut disk, 9, &repeated).expect("put_large");
+        assert_eq!(store.object_count(), 2, "one chunk and a manifest");
+
+        let mut longer = repeated.clone();
+        longer.extend_from_slice(b"tail");
+        put_whole(&mut store, &mut disk, 9, &longer).expect("put_large");
+        assert_eq!(store.object_count(), 4, "one new chunk and a manifest");
+        assert_eq!(
+            put_whole(&mut store, &mut disk, 9, &repeated),
+            Ok(hash),
+            "idempotent"
+        );
+        assert_eq!(store.object_count(), 4);
+    }
+
+    #[test]
+    fn an_empty_object_is_a_manifest_with_no_chunks() {
+        let mut disk = formatted();
+        let mut store = open(&mut disk);
+        let hash = put_whole(&mut store, &mut disk, 9, b"").expect("put_large");
+        assert_eq!(store.object_count(), 1);
+        let mut calls = 0;
+        let read = store.get_large(&mut disk, &hash, |_| calls += 1);
+        assert_eq!(read, Ok((9, 0)));
+        assert_eq!(calls, 0);
+    }
+
+    /// A flipped byte in a later chunk fails the object before any chunk is
+    /// delivered, the good ones before it included.
+    #[test]
+    fn a_corrupted_chunk_is_never_delivered() {
+        let mut disk = formatted_with(LARGE_SECTORS);
+        let content = large_content();
+        let mut store = open_large(&mut disk);
+        let hash = put_whole(&mut store, &mut disk, 9, &content).expect("put_large");
+        let second = store
+            .entries
+            .iter()
+            .find(|entry| {
+                entry.hash == sha256::digest(&content[MAX_OBJECT_PAYLOAD..][..MAX_OBJECT_PAYLOAD])
+            })
+            .expect("second chunk indexed")
+            .lba;
+        disk.sectors[(FIRST_LBA + second + 3) as usize][7] ^= 0xff;
+
+        let mut delivered = Vec::new();
+        let read = store.get_large(&mut disk, &hash, |chunk| delivered.push(chunk.to_vec()));
+        assert_eq!(read, Err(StoreError::HashMismatch));
+        assert!(delivered.is_empty(), "a verified first chunk was delivered");
+    }
+
+    /// `put_large` reads its source twice; a source that answers the second
+    /// read differently is refused before the manifest, so no object names
+    /// bytes that were never hashed into it.
+    #[test]
+    fn a_source_that_changes_between_reads_writes_no_manifest() {
+        let mut disk = formatted_with(LARGE_SECTORS);
+        let content = large_content();
+        let manifest_hash = sha256::digest(&encode_manifest(9, &content).expect("manifest"));
+        let mut store = open_large(&mut disk);
+        let mut reads = 0;
+        let outcome = store.put_large(&mut disk, 9, content.len() as u64, |_, _, offset, out| {
+            out.copy_from_slice(&content[offset as usize..][..out.len()]);
+            reads += 1;
+            if reads > 3 {
+                out[0] ^= 0xff;
+            }
+            Ok(())
+        });
+        assert_eq!(outcome, Err(StoreError::HashMismatch));
+        assert_eq!(store.stat(&manifest_hash), None);
+        assert_eq!(open_large(&mut disk).stat(&manifest_hash), None);
+    }
+
+    /// An edit reads the object it replaces through the store `source` is
+    /// handed, a chunk at a time, while the edited object is written.
+    #[test]
+    fn a_source_can_read_the_object_it_replaces() {
+        let mut disk = formatted_with(LARGE_SECTORS);
+        let content = large_content();
+        let old = encode_manifest(9, &content).expect("manifest");
+        let mut store = open_large(&mut disk);
+        put_whole(&mut store, &mut disk, 9, &content).expect("put_large");
+        let mut edited = content.clone();
+        edited[MAX_OBJECT_PAYLOAD] ^= 0xff;
+        let hash = store
+            .put_large(
+                &mut disk,
+                9,
+                edited.len() as u64,
+                |store, io, offset, out| {
+                    let index = offset as usize / MAX_OBJECT_PAYLOAD;
+                    store.get(io, &manifest_chunk(&old, index), out)?;
+                    if index == 1 {
+                        out[0] ^= 0xff;
+                    }
+                    Ok(())
+                },
+            )
+            .expect("edit");
+        let mut read = Vec::new();
+        store
+            .get_large(&mut disk, &hash, |chunk| read.extend_from_slice(chunk))
+            .expect("get_large");
+        assert_eq!(read, edited);
+    }
+
+    #[test]
+    fn get_large_refuses_a_hash_that_is_not_a_manifest() {
+        let mut disk = formatted();
+        let mut store = open(&mut disk);
+        let hash = store.put(&mut disk, 9, b"small").expect("put");
+        assert_eq!(
+            store.get_large(&mut disk, &hash, |_| {}),
+            Err(StoreError::WrongObjectType)
+        );
+        assert_eq!(
+            store.get_large(&mut disk, &[0; 32], |_| {}),
+            Err(StoreError::NotFound)
+        );
+    }
+
+    /// A manifest whose chunk list disagrees with its declared length is
+    /// refused before any chunk is read.
+    #[test]
+    fn a_manifest_with_the_wrong_chunk_count_is_corrupt() {
+        let content = large_content();
+        let manifest = encode_manifest(9, &content).expect("manifest");
+        assert!(decode_manifest(&manifest).is_ok());
+
+        let mut short = manifest.clone();
+        short.truncate(manifest.len() - 32);
+        short[MANIFEST_CHUNK_COUNT_OFFSET..MANIFEST_OBJ_TYPE_OFFSET]
+            .copy_from_slice(&2u32.to_le_bytes());
+        assert_eq!(decode_manifest(&short), Err(StoreError::CorruptRecord));
+
+        let mut reserved = manifest.clone();
+        reserved[MANIFEST_RESERVED_OFFSET] = 1;
+        assert_eq!(decode_manifest(&reserved), Err(StoreError::CorruptRecord));
+
+        let mut magic = manifest;
+        magic[0] ^= 0xff;
+        assert_eq!(decode_manifest(&magic), Err(StoreError::CorruptRecord));
+    }
+
+    /// Refusals for size or space happen before the first device write.
+    #[test]
+    fn put_large_refuses_before_writing_anything() {
+        let mut disk = formatted();
+        let mut store = open(&mut disk);
+        let writes = disk.writes;
+        assert_eq!(
+            put_whole(&mut store, &mut disk, 9, &large_content()),
+            Err(StoreError::StoreFull)
+        );
+        let oversized = alloc::vec![0u8; (MAX_MANIFEST_CHUNKS + 1) * MAX_OBJECT_PAYLOAD];
+        assert_eq!(
+            put_whole(&mut store, &mut disk, 9, &oversized),
+            Err(StoreError::PayloadTooLarge)
+        );
+        assert_eq!(disk.writes, writes);
+        assert_eq!(store.object_count(), 0);
+    }
+
+    /// The manifest commits last: an interruption anywhere in `put_large`
+    /// leaves no object under the manifest's hash, only stray chunks that
+    /// the next compaction reclaims.
+    #[test]
+    fn an_interrupted_put_large_leaves_no_manifest() {
+        let content = large_content();
+        let manifest_hash = sha256::digest(&encode_manifest(9, &content).expect("manifest"));
+        let total_writes = {
+            let mut disk = formatted_with(LARGE_SECTORS);
+            let mut store = open_large(&mut disk);
+            put_whole(&mut store, &mut disk, 9, &content).expect("put_large");
+            disk.writes
+        };
+        for limit in [0, 65, 66, 67, 132, 133, total_writes - 1] {
+            let mut disk = formatted_with(LARGE_SECTORS);
+            disk.fail_write_after = Some(limit);
+            let mut store = open_large(&mut disk);
+            assert!(put_whole(&mut store, &mut disk, 9, &content).is_err());
+            disk.fail_write_after = None;
+
+            let mut reopened = open_large(&mut disk);
+            assert_eq!(reopened.stat(&manifest_hash), None, "limit {limit}");
+            reopened
+                .compact(&mut disk, &[], manifest_references)
+                .expect("compact");
+            assert_eq!(reopened.object_count(), 0);
+        }
+    }
+
+    /// `manifest_references` keeps a large object's chunks alive through
+    /// compaction while an unrelated record is reclaimed.
+    #[test]
+    fn compaction_follows_manifests_to_their_chunks() {
+        let mut disk = formatted_with(LARGE_SECTORS);
+        let content = large_content();
+        let mut store = open_large(&mut disk);
+        store.put(&mut disk, 1, b"garbage").expect("put");
+        let hash = put_whole(&mut store, &mut disk, 9, &content).expect("put_large");
+        let report = store
+            .compact(&mut disk, &[hash], manifest_references)
+            .expect("compact");
+        assert_eq!((report.kept, report.reclaimed), (4, 1));
+
+        let store = open_large(&mut disk);
+        let mut out = Vec::new();
+        store
+            .get_large(&mut disk, &hash, |chunk| out.extend_from_slice(chunk))
+            .expect("get_large");
+        assert_eq!(out, content);
+    }
+
+    #[test]
+    fn put_or_reuse_writes_nothing_for_content_already_stored_under_its_type() {
+        let mut disk = formatted();
+        let mut store = open(&mut disk);
+        let (hash, placement) = store.put_or_reuse(&mut disk, 3, b"same").expect("first");
+        assert_eq!(placement, Placement::Written);
+        let writes = disk.writes;
+        let sequence = store.sequence();
+        assert_eq!(
+            store.put_or_reuse(&mut disk, 3, b"same"),
+            Ok((hash, Placement::Reused))
+        );
+        assert_eq!(disk.writes, writes);
+        assert_eq!(store.sequence(), sequence, "no commit");
+        assert_eq!(store.record_count(&hash), 1);
+    }
+
+    /// The same bytes under another type tag are a second record of the same
+    /// hash, and the index reports both after a reopen.
+    #[test]
+    fn put_or_reuse_keeps_a_record_per_type_tag() {
+        let mut disk = formatted();
+        let hash = {
+            let mut store = open(&mut disk);
+            let (hash, _) = store.put_or_reuse(&mut disk, 3, b"same").expect("first");
+            assert_eq!(
+                store.put_or_reuse(&mut disk, 4, b"same"),
+                Ok((hash, Placement::Written))
+            );
+            hash
+        };
+        let store = open(&mut disk);
+        assert!(store.exists(&hash));
+        assert!(!store.exists(&sha256::digest(b"other")));
+        assert_eq!(store.record_count(&hash), 2);
+        let tags: Vec<u32> = store.records(&hash).map(|entry| entry.obj_type).collect();
+        assert_eq!(tags, [3, 4]);
+        assert_eq!(
+            store.stat(&hash),
+            Some((3, 4)),
+            "stat answers for the first"
+        );
+    }
+
+    /// The same bytes and tag staged for two generations are two records,
+    /// and each reports its own generation after a reopen.
+    #[test]
+    fn put_or_reuse_with_metadata_keeps_a_record_per_generation() {
+        let mut disk = formatted();
+        let none = [0; TYPE_METADATA_BYTES];
+        let hash = {
+            let mut store = open(&mut disk);
+            let (hash, placement) = store
+                .put_or_reuse_with_metadata(&mut disk, 3, b"same", &[1; 32], &none)
+                .expect("first");
+            assert_eq!(placement, Placement::Written);
+            assert_eq!(
+                store.put_or_reuse_with_metadata(&mut disk, 3, b"same", &[1; 32], &none),
+                Ok((hash, Placement::Reused))
+            );
+            assert_eq!(
+                store.put_or_reuse_with_metadata(&mut disk, 3, b"same", &[2; 32], &none),
+                Ok((hash, Placement::Written))
+            );
+            hash
+        };
+        let store = open(&mut disk);
+        let generations: Vec<[u8; 32]> = store
+            .records(&hash)
+            .map(|entry| {
+                store
+                    .record_metadata(&mut disk, entry)
+                    .expect("metadata")
+                    .generation
+            })
+            .collect();
+        assert_eq!(generations, [[1; 32], [2; 32]]);
+    }
+
+    /// Records are found by tag in the order they were committed, and a
+    /// reopen rebuilds that order from the device.
+    #[test]
+    fn records_of_a_type_are_listed_in_commit_order() {
+        let mut disk = formatted();
+        let (first, second) = {
+            let mut store = open(&mut disk);
+            let first = store.put(&mut disk, 5, b"first").expect("first");
+            store.put(&mut disk, 6, b"other").expect("other");
+            let second = store.put(&mut disk, 5, b"second").expect("second");
+            (first, second)
+        };
+        let store = open(&mut disk);
+        let listed: Vec<[u8; 32]> = store.records_of_type(5).map(|entry| entry.hash).collect();
+        assert_eq!(listed, [first, second]);
+        assert_eq!(store.records_of_type(9).count(), 0);
+    }
+
+    #[test]
+    fn put_or_reuse_refuses_what_put_refuses() {
+        let mut disk = formatted();
+        let mut store = open(&mut disk);
+        let hash = store.put(&mut disk, 3, b"same").expect("put");
+        let lba = store.records(&hash).next().expect("indexed").lba;
+        disk.sectors[(FIRST_LBA + lba) as usize][RECORD_HEADER] ^= 0xff;
+        assert_eq!(
+            store.put_or_reuse(&mut disk, 3, b"same"),
+            Err(StoreError::DuplicateIdentity)
+        );
+        let oversized = alloc::vec![0u8; MAX_OBJECT_PAYLOAD + 1];
+        assert_eq!(
+            store.put_or_reuse(&mut disk, 3, &oversized),
+            Err(StoreError::PayloadTooLarge)
+        );
+    }
+
+    /// A live hash keeps every record holding it; dropping one type's record
+    /// while the other stays referenced would make `records` lie.
+    #[test]
+    fn compaction_keeps_every_record_of_a_live_hash() {
+        let mut disk = formatted();
+        let mut store = open(&mut disk);
+        store.put(&mut disk, 1, b"garbage").expect("put");
+        let (hash, _) = store.put_or_reuse(&mut disk, 3, b"same").expect("first");
+        store.put_or_reuse(&mut disk, 4, b"same").expect("second");
+        let report = store
+            .compact(&mut disk, &[hash], no_references)
+            .expect("compact");
+        assert_eq!((report.kept, report.reclaimed), (2, 1));
+        assert_eq!(open(&mut disk).record_count(&hash), 2);
+    }
+
+    /// Runs steps of `budget` sectors to completion and returns how many it
+    /// took.
+    fn scrub_to_end(
+        store: &ObjectStore,
+        disk: &mut impl BlockIo,
+        cursor: &mut ScrubCursor,
+        budget: u64,
+        report: &mut ScrubReport,
+    ) -> usize {
+        let mut steps = 1;
+        while store
+            .scrub_step(disk, cursor, budget, report)
+            .expect("scrub step")
+            == ScrubProgress::Partial
+        {
+            steps += 1;
+        }
+        steps
+    }
+
+    #[test]
+    fn an_incremental_scrub_lists_every_damaged_record() {
+        let mut disk = formatted();
+        let mut store = open(&mut disk);
+        for payload in [b"one", b"two", b"six", b"ten"] {
+            store.put(&mut disk, 1, payload).expect("put");
+        }
+        let lbas: Vec<u64> = store.entries.iter().map(|entry| entry.lba).collect();
+        disk.sectors[(FIRST_LBA + lbas[1]) as usize][RECORD_HEADER] ^= 0xff;
+        disk.sectors[(FIRST_LBA + lbas[2]) as usize][RECORD_OBJ_TYPE_OFFSET] ^= 0xff;
+        assert_eq!(store.scrub(&mut disk), Err(StoreError::HashMismatch));
+
+        let mut cursor = ScrubCursor::new();
+        let mut report = ScrubReport::default();
+        let steps = scrub_to_end(&store, &mut disk, &mut cursor, 1, &mut report);
+        assert_eq!(steps, 4, "one single-sector record per step");
+        assert_eq!(report.records_checked, 4);
+        assert_eq!(report.sectors_read, 4);
+        let found: Vec<(u64, DamageKind)> = report
+            .damaged
+            .iter()
+            .map(|damage| (damage.lba, damage.kind))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (lbas[1], DamageKind::HashMismatch),
+                (lbas[2], DamageKind::CorruptRecord)
+            ]
+        );
+        assert_eq!(report.damaged[0].hash, sha256::digest(b"two"));
+        assert_eq!(
+            store.scrub_step(&mut disk, &mut cursor, 1, &mut report),
+            Ok(ScrubProgress::Complete),
+            "a finished cursor stays finished"
+        );
+        assert_eq!(report.records_checked, 4);
+    }
+
+    /// A device that refuses a sector is damage to report; a timeout is not,
+    /// and the next step retries the same record.
+    #[test]
+    fn an_unreadable_record_is_damage_and_a_timeout_is_retried() {
+        let mut disk = formatted();
+        let mut store = open(&mut disk);
+        store.put(&mut disk, 1, b"first").expect("put");
+        let second = store.put(&mut disk, 1, b"second").expect("put");
+        let lba = store.records(&second).next().expect("indexed").lba;
+
+        let mut cursor = ScrubCursor::new();
+        let mut report = ScrubReport::default();
+        disk.unreadable = Some((FIRST_LBA + lba, IoError::Timeout));
+        assert_eq!(
+            store.scrub_step(&mut disk, &mut cursor, 8, &mut report),
+            Err(StoreError::Io(IoError::Timeout))
+        );
+        assert_eq!(report.records_checked, 1);
+
+        disk.unreadable = Some((FIRST_LBA + lba, IoError::Device));
+        assert_eq!(
+            store.scrub_step(&mut disk, &mut cursor, 8, &mut report),
+            Ok(ScrubProgress::Complete)
+        );
+        assert_eq!(report.records_checked, 2);
+        assert_eq!(report.damaged.len(), 1);
+        assert_eq!(report.damaged[0].kind, DamageKind::Unreadable);
+        assert_eq!(report.damaged[0].lba, lba);
+    }
+
+    /// The cursor is plain data: it survives a reopen, and it reaches records
+    /// committed after the scrub began.
+    #[test]
+    fn a_scrub_resumes_across_a_reopen_and_reaches_later_puts() {
+        let mut disk = formatted();
+        let mut cursor = ScrubCursor::new();
+        let mut report = ScrubReport::default();
+        {
+            let mut store = open(&mut disk);
+            store.put(&mut disk, 1, b"one").expect("put");
+            store.put(&mut disk, 1, b"two").expect("put");
+            assert_eq!(
+                store.scrub_step(&mut disk, &mut cursor, 1, &mut report),
+                Ok(ScrubProgress::Partial)
+            );
+            store.put(&mut disk, 1, b"three").expect("put");
+        }
+        let store = open(&mut disk);
+        scrub_to_end(&store, &mut disk, &mut cursor, 1, &mut report);
+        assert_eq!(report.records_checked, 3);
+        assert!(report.damaged.is_empty());
+    }
+
+    #[test]
+    fn a_compaction_that_moves_the_region_restarts_the_scrub() {
+        let mut disk = formatted();
+        let mut store = open(&mut disk);
+        store.put(&mut disk, 1, b"garbage").expect("put");
+        let live = store.put(&mut disk, 1, b"live").expect("put");
+        let mut cursor = ScrubCursor::new();
+        let mut report = ScrubReport::default();
+        scrub_to_end(&store, &mut disk, &mut cursor, 8, &mut report);
+        assert_eq!(report.records_checked, 2);
+
+        store
+            .compact(&mut disk, &[live], no_references)
+            .expect("compact");
+        scrub_to_end(&store, &mut disk, &mut cursor, 8, &mut report);
+        assert_eq!(report.records_checked, 1, "the report restarted too");
+    }
+
+    /// Attribution names the roots whose closure reaches each damaged
+    /// record, and leaves a record nothing reaches unattributed.
+    #[test]
+    fn attribution_names_the_roots_that_reach_a_damaged_record() {
+        const PARENT: u32 = 2;
+        let parent_references = |obj_type: u32, payload: &[u8], out: &mut Vec<[u8; 32]>| {
+            if obj_type == PARENT {
+                out.push(payload.try_into().expect("a parent names one child"));
+            }
+        };
+        let mut disk = formatted();
+        let mut store = open(&mut disk);
+        let child = store.put(&mut disk, 1, b"child").expect("put");
+        let parent = store.put(&mut disk, PARENT, &child).expect("put");
+        let other = store.put(&mut disk, 1, b"other").expect("put");
+        let orphan = store.put(&mut disk, 1, b"orphan").expect("put");
+        for hash in [child, orphan] {
+            let lba = store.records(&hash).next().expect("indexed").lba;
+            disk.sectors[(FIRST_LBA + lba) as usize][RECORD_HEADER] ^= 0xff;
+        }
+
+        let mut cursor = ScrubCursor::new();
+        let mut report = ScrubReport::default();
+        scrub_to_end(&store, &mut disk, &mut cursor, 64, &mut report);
+        store
+            .attribute(&mut disk, &mut report, &[parent, other], parent_references)
+            .expect("attribute");
+        let attributed: Vec<([u8; 32], Vec<[u8; 32]>)> = report
+            .damaged
+            .iter()
+            .map(|damage| (damage.hash, damage.referenced_by.clone()))
+            .collect();
+        assert_eq!(
+            attributed,
+            [(child, alloc::vec![parent]), (orphan, Vec::new())]
+        );
+    }
+
+    /// A reopen after a commit trusts the index that commit sealed, and
+    /// reconstructs exactly the entries a full rescan would.
+    #[test]
+    fn a_reopen_loads_the_committed_index() {
+        let mut disk = formatted();
+        let expected = {
+            let mut store = open(&mut disk);
+            store.put(&mut disk, 1, b"first").expect("put");
+            store
+                .put(&mut disk, 2, &alloc::vec![0x5Au8; SECTOR_SIZE])
+                .expect("put");
+            store.entries.clone()
+        };
+        let store = open(&mut disk);
+        assert!(store.opened_from_index());
+        assert_eq!(store.entries, expected);
+    }
+
+    /// An index that does not match its superblock is ignored, not obeyed:
+    /// the records are rescanned and every object is still found.
+    #[test]
+    fn a_stale_or_damaged_index_falls_back_to_a_rescan() {
+        let mut disk = formatted();
+        let (hash, index_lba) = {
+            let mut store = open(&mut disk);
+            let hash = store.put(&mut disk, 1, b"indexed").expect("put");
+            (hash, store.active.index_lba())
+        };
+        let index_sector = (FIRST_LBA + index_lba) as usize;
+        disk.sectors[index_sector][INDEX_HEADER + 40] ^= 0x01;
+        let store = open(&mut disk);
+        assert!(!store.opened_from_index());
+        assert_eq!(store.stat(&hash), Some((1, b"indexed".len() as u32)));
+
+        // A structurally valid index from an older commit is stale too.
+        let mut stale = alloc::vec![0u8; SECTOR_SIZE];
+        let older = encode_index(1, &[]);
+        stale[..older.len()].copy_from_slice(&older);
+        disk.sectors[index_sector].copy_from_slice(&stale);
+        let store = open(&mut disk);
+        assert!(!store.opened_from_index());
+        assert_eq!(store.object_count(), 1);
+    }
+
+    /// Entries that do not tile the committed region exactly are refused
+    /// even under a matching CRC.
+    #[test]
+    fn an_index_that_misplaces_a_record_is_refused() {
+        let entry = Entry {
+            hash: [7; 32],
+            obj_type: 1,
+            payload_len: 16,
+            lba: RECORD_AREA_START,
+        };
+        let superblock = |entries: &[Entry], append_lba| Superblock {
+            format_version: FORMAT_VERSION,
+            sequence: 4,
+            record_start: RECORD_AREA_START,
+            append_lba,
+            object_count: entries.len() as u32,
+            index_crc32: crc32(&encode_index(4, entries)),
+            encrypted: false,
+        };
+        let end = RECORD_AREA_START + 1;
+        let good = [entry];
+        assert_eq!(
+            decode_index(&encode_index(4, &good), &superblock(&good, end)),
+            Some(good.to_vec())
+        );
+        let shifted = [Entry { lba: end, ..entry }];
+        assert_eq!(
+            decode_index(&encode_index(4, &shifted), &superblock(&shifted, end + 1)),
+            None
+        );
+        assert_eq!(
+            decode_index(&encode_index(4, &good), &superblock(&good, end + 1)),
+            None
+        );
+    }
+
+    /// Metadata given at put time is in the record header after a reopen
+    /// and after compaction moves the record.
+    #[test]
+    fn record_metadata_survives_a_reopen_and_a_move() {
+        let mut disk = formatted();
+        let generation = [0x42; 32];
+        let type_metadata = [0x17; TYPE_METADATA_BYTES];
+        let (hash, garbage) = {
+            let mut store = open(&mut disk);
+            let garbage = store.put(&mut disk, 1, b"garbage").expect("put");
+            let hash = store
+                .put_with_metadata(&mut disk, 3, b"tagged", &generation, &type_metadata)
+                .expect("put");
+            (hash, garbage)
+        };
+        let expected = RecordMetadata {
+            creation_sequence: 3,
+            generation,
+            type_metadata,
+        };
+        let mut store = open(&mut disk);
+        assert_eq!(store.metadata(&mut disk, &hash), Ok(expected));
+        assert_eq!(
+            store.metadata(&mut disk, &garbage),
+            Ok(RecordMetadata {
+                creation_sequence: 2,
+                ..RecordMetadata::default()
+            })
+        );
+
+        let before = store.records(&hash).next().expect("indexed").lba;
+        store
+            .compact(&mut disk, &[hash], no_references)
+            .expect("compact");
+        assert_ne!(store.records(&hash).next().expect("kept").lba, before);
+        assert_eq!(store.metadata(&mut disk, &hash), Ok(expected));
+    }
+
+    /// The header CRC catches a flipped metadata bit the payload hash does
+    /// not cover: scrub reports it and `metadata` refuses it.
+    #[test]
+    fn a_flipped_metadata_bit_is_caught_by_the_header_crc() {
+        let mut disk = formatted();
+        let hash = {
+            let mut store = open(&mut disk);
+            store.put(&mut disk, 1, b"checked").expect("put")
+        };
+        disk.sectors[(FIRST_LBA + RECORD_AREA_START) as usize][RECORD_GENERATION_OFFSET] ^= 0x01;
+
+        let store = open(&mut disk);
+        assert_eq!(
+            store.metadata(&mut disk, &hash),
+            Err(StoreError::CorruptRecord)
+        );
+        let mut cursor = ScrubCursor::new();
+        let mut report = ScrubReport::default();
+        scrub_to_end(&store, &mut disk, &mut cursor, 64, &mut report);
+        assert_eq!(report.damaged.len(), 1);
+        assert_eq!(report.damaged[0].kind, DamageKind::CorruptRecord);
+    }
+
+    /// A format-1 disk: a genesis root in slot A, records from LBA 2 with
+    /// 64-byte headers, and no index.
+    fn format_one(objects: &[(u32, &[u8])]) -> MemoryDisk {
+        let mut disk = MemoryDisk::new();
+        let mut lba = record_area_start(V1_FORMAT_VERSION);
+        for (obj_type, payload) in objects {
+            let hash = sha256::digest(payload);
+            let mut header =
+                encode_record_header(*obj_type, payload, &hash, &RecordMetadata::default(), false);
+            header[RECORD_FORMAT_VERSION_OFFSET..RECORD_HEADER_SIZE_OFFSET]
+                .copy_from_slice(&V1_FORMAT_VERSION.to_le_bytes());
+            header[RECORD_HEADER_SIZE_OFFSET..RECORD_OBJ_TYPE_OFFSET]
+                .copy_from_slice(&(record_header_len(V1_FORMAT_VERSION) as u32).to_le_bytes());
+            let header = &header[..record_header_len(V1_FORMAT_VERSION)];
+            let mut record = header.to_vec();
+            record.extend_from_slice(payload);
+            for chunk in record.chunks(SECTOR_SIZE) {
+                disk.sectors[(FIRST_LBA + lba) as usize][..chunk.len()].copy_from_slice(chunk);
+                lba += 1;
+            }
+        }
+        let genesis = Superblock {
+            format_version: V1_FORMAT_VERSION,
+            sequence: 1 + objects.len() as u64,
+            record_start: record_area_start(V1_FORMAT_VERSION),
+            append_lba: lba,
+            object_count: objects.len() as u32,
+            index_crc32: 0,
+            encrypted: false,
+        };
+        disk.sectors[(FIRST_LBA + SLOT_A_LBA) as usize] = encode_superblock(&genesis, SECTORS);
+        disk.writes = 0;
+        disk
+    }
+
+    /// A format-1 store still reads, by rescanning, but every path that
+    /// would write refuses before touching the device.
+    #[test]
+    fn a_format_one_store_opens_read_only() {
+        let mut disk = format_one(&[(1, b"old"), (2, &[0x33; SECTOR_SIZE])]);
+        let mut store = open(&mut disk);
+        assert_eq!(store.format_version(), V1_FORMAT_VERSION);
+        assert!(store.read_only());
+        assert!(!store.opened_from_index());
+        assert_eq!(store.object_count(), 2);
+
+        let hash = sha256::digest(b"old");
+        let mut out = [0u8; 3];
+        assert_eq!(store.get(&mut disk, &hash, &mut out), Ok((1, 3)));
+        assert_eq!(&out, b"old");
+        assert_eq!(
+            store.metadata(&mut disk, &hash),
+            Ok(RecordMetadata::default())
+        );
+        store.scrub(&mut disk).expect("format-1 records verify");
+
+        assert_eq!(store.put(&mut disk, 1, b"old"), Ok(hash), "already stored");
+        assert_eq!(store.put(&mut disk, 1, b"new"), Err(StoreError::ReadOnly));
+        assert_eq!(
+            put_whole(&mut store, &mut disk, 1, b"new"),
+            Err(StoreError::ReadOnly)
+        );
+        assert_eq!(
+            store.compact(&mut disk, &[], no_references),
+            Err(StoreError::ReadOnly)
+        );
+        assert_eq!(disk.writes, 0);
+    }
+
+    const KEY: [u8; 32] = [0x5a; 32];
+
+    fn encrypted_partition() -> Partition {
+        Partition {
+            type_guid: crate::gpt::SLIME_ENCRYPTED_STORE_TYPE_GUID,
+            ..partition()
+        }
+    }
+
+    /// A store formatted under `KEY`, as `formatted` is in plaintext.
+    fn formatted_encrypted() -> MemoryDisk {
+        let mut disk = MemoryDisk::new();
+        ObjectStore::format(&mut disk, &encrypted_partition(), Some(&StoreKey::new(KEY)))
+            .expect("format");
+        disk.writes = 0;
+        disk.flushes = 0;
+        disk
+    }
+
+    fn open_encrypted(disk: &mut MemoryDisk) -> Result<ObjectStore, StoreError> {
+        ObjectStore::open_encrypted(disk, &encrypted_partition(), StoreKey::new(KEY))
+    }
+
+    /// The same operations as in plaintext, with the same content hashes;
+    /// only the bytes on disk differ, and they never contain the payload.
+    #[test]
+    fn an_encrypted_store_round_trips_without_plaintext_on_disk() {
+        let mut disk = formatted_encrypted();
+        let payload = b"a secret worth sealing";
+        let hash = {
+            let mut store = open_encrypted(&mut disk).expect("store opens");
+            assert!(store.encrypted());
+            store.put(&mut disk, 7, payload).expect("put")
+        };
+        assert_eq!(hash, sha256::digest(payload), "addressing is unchanged");
+        assert!(
+            !disk
+                .sectors
+                .iter()
+                .any(|sector| sector.windows(payload.len()).any(|bytes| bytes == payload)),
+            "the payload is never written in the clear"
+        );
+
+        let store = open_encrypted(&mut disk).expect("store reopens");
+        assert!(store.opened_from_index());
+        let mut out = alloc::vec![0u8; payload.len()];
+        assert_eq!(
+            store.get(&mut disk, &hash, &mut out),
+            Ok((7, payload.len()))
+        );
+        assert_eq!(&out, payload);
+        store.scrub(&mut disk).expect("sealed records verify");
+    }
+
+    /// Without the key, or with another one, the store does not open; a
+    /// key offered to a plaintext store is refused rather than ignored.
+    #[test]
+    fn an_encrypted_store_opens_only_with_its_key() {
+        let mut disk = formatted_encrypted();
+        assert_eq!(
+            ObjectStore::open(&mut disk, &encrypted_partition()).err(),
+            Some(StoreError::KeyRequired)
+        );
+        // Even behind a plaintext partition type, the slots refuse.
+        assert_eq!(
+            ObjectStore::open(&mut disk, &partition()).err(),
+            Some(StoreError::KeyRequired)
+        );
+        assert_eq!(
+            ObjectStore::open_encrypted(&mut disk, &encrypted_partition(), StoreKey::new([1; 32]))
+                .err(),
+            Some(StoreError::WrongKey)
+        );
+
+        let mut plain = formatted();
+        assert_eq!(
+            ObjectStore::open_encrypted(&mut plain, &partition(), StoreKey::new(KEY)).err(),
+            Some(StoreError::NotEncrypted)
+        );
+        assert_eq!(
+            ObjectStore::open_encrypted(&mut plain, &encrypted_partition(), StoreKey::new(KEY))
+                .err(),
+            Some(StoreError::NotEncrypted)
+        );
+    }
+
+    /// A flipped ciphertext bit fails the tag: `get` refuses it as it would
+    /// a payload that no longer hashes, and scrub reports it as such.
+    #[test]
+    fn tampered_ciphertext_is_reported_as_damage() {
+        let mut disk = formatted_encrypted();
+        let mut store = open_encrypted(&mut disk).expect("store opens");
+        let hash = store.put(&mut disk, 1, b"sealed").expect("put");
+        let lba = store.entries[0].lba;
+        disk.sectors[(FIRST_LBA + lba) as usize][RECORD_HEADER] ^= 0x01;
+
+        let mut out = [0u8; 16];
+        assert_eq!(
+            store.get(&mut disk, &hash, &mut out),
+            Err(StoreError::HashMismatch)
+        );
+        let mut cursor = ScrubCursor::new();
+        let mut report = ScrubReport::default();
+        scrub_to_end(&store, &mut disk, &mut cursor, 8, &mut report);
+        assert_eq!(report.damaged.len(), 1);
+        assert_eq!(report.damaged[0].kind, DamageKind::HashMismatch);
+    }
+
+    /// Compaction moves sealed records without opening them: the header
+    /// they are bound to moves with them, so they still open afterwards.
+    #[test]
+    fn compaction_keeps_sealed_records_readable() {
+        let mut disk = formatted_encrypted();
+        let mut store = open_encrypted(&mut disk).expect("store opens");
+        store.put(&mut disk, 1, b"garbage").expect("put");
+        let kept = store.put(&mut disk, 1, &[0x44; SECTOR_SIZE]).expect("put");
+        let compaction = store
+            .compact(&mut disk, &[kept], no_references)
+            .expect("compact");
+        assert_eq!((compaction.kept, compaction.reclaimed), (1, 1));
+
+        let store = open_encrypted(&mut disk).expect("store reopens");
+        let mut out = alloc::vec![0u8; SECTOR_SIZE];
+        assert_eq!(store.get(&mut disk, &kept, &mut out), Ok((1, SECTOR_SIZE)));
+        assert_eq!(out, [0x44; SECTOR_SIZE]);
+    }
+
+    /// Two formatted disks behind one mirror.
+    fn mirrored() -> Mirror<MemoryDisk, MemoryDisk> {
+        Mirror::new(formatted(), formatted())
+    }
+
+    fn record_sectors_of(disk: &MemoryDisk, lba: u64) -> [u8; SECTOR_SIZE] {
+        disk.sectors[(FIRST_LBA + lba) as usize]
+    }
+
+    /// Every commit lands on both copies: each leg opens alone at the same
+    /// root, and the two are byte-identical.
+    #[test]
+    fn a_mirror_commits_to_both_copies() {
+        let mut mirror = mirrored();
+        let mut store = ObjectStore::open(&mut mirror, &partition()).expect("store opens");
+        let hash = store.put(&mut mirror, 3, b"mirrored").expect("put");
+        let (mut primary, mut secondary) = mirror.into_inner();
+        assert_eq!(primary.sectors, secondary.sectors);
+        for leg in [&mut primary, &mut secondary] {
+            let store = open(leg);
+            assert_eq!(store.sequence(), 2);
+            assert_eq!(store.stat(&hash), Some((3, b"mirrored".len() as u32)));
+        }
+    }
+
+    /// A record that rotted on one copy is read from the other, and the bad
+    /// copy is rewritten from the good one on the way.
+    #[test]
+    fn a_mirror_repairs_a_bad_copy_on_read() {
+        let mut mirror = mirrored();
+        let mut store = ObjectStore::open(&mut mirror, &partition()).expect("store opens");
+        let hash = store.put(&mut mirror, 1, b"precious").expect("put");
+        let lba = store.entries[0].lba;
+        mirror.primary().sectors[(FIRST_LBA + lba) as usize][RECORD_HEADER] ^= 0x20;
+
+        let mut out = [0u8; 8];
+        assert_eq!(store.get(&mut mirror, &hash, &mut out), Ok((1, 8)));
+        assert_eq!(&out, b"precious");
+        let good = record_sectors_of(mirror.secondary(), lba);
+        assert_eq!(record_sectors_of(mirror.primary(), lba), good);
+
+        // Without the mirror, the same damage is only an error.
+        let mut single = formatted();
+        let mut store = open(&mut single);
+        store.put(&mut single, 1, b"precious").expect("put");
+        single.sectors[(FIRST_LBA + lba) as usize][RECORD_HEADER] ^= 0x20;
+        assert_eq!(
+            store.get(&mut single, &hash, &mut out),
+            Err(StoreError::HashMismatch)
+        );
+    }
+
+    /// A leg that refuses a sector is read around; a leg that refuses a
+    /// write fails the commit, and the previous root stands on both.
+    #[test]
+    fn a_failing_leg_is_read_around_and_fails_writes() {
+        let mut mirror = mirrored();
+        let mut store = ObjectStore::open(&mut mirror, &partition()).expect("store opens");
+        let hash = store.put(&mut mirror, 1, b"kept").expect("put");
+        let lba = store.entries[0].lba;
+        mirror.primary().unreadable = Some((FIRST_LBA + lba, IoError::Device));
+        let mut out = [0u8; 4];
+        assert_eq!(store.get(&mut mirror, &hash, &mut out), Ok((1, 4)));
+
+        mirror.secondary().fail_write_after = Some(mirror.secondary().writes);
+        assert_eq!(
+            store.put(&mut mirror, 1, b"lost"),
+            Err(StoreError::Io(IoError::Device))
+        );
+        let (mut primary, mut secondary) = mirror.into_inner();
+        primary.unreadable = None;
+        for leg in [&mut primary, &mut secondary] {
+            let store = open(leg);
+            assert_eq!(store.sequence(), 2, "the failed commit is on neither copy");
+            assert_eq!(store.object_count(), 1);
+        }
+    }
+
+    /// A superblock slot that no longer opens on one copy is restored from
+    /// the other at open, so the newest root is still the one selected.
+    #[test]
+    fn a_mirror_restores_a_damaged_slot_at_open() {
+        let mut mirror = mirrored();
+        let mut store = ObjectStore::open(&mut mirror, &partition()).expect("store opens");
+        store.put(&mut mirror, 1, b"newest").expect("put");
+        let slot = (FIRST_LBA + store.active.lba()) as usize;
+        mirror.primary().sectors[slot][20] ^= 0x01;
+
+        let store = ObjectStore::open(&mut mirror, &partition()).expect("store reopens");
+        assert_eq!(store.sequence(), 2);
+        let good = mirror.secondary().sectors[slot];
+        assert_eq!(mirror.primary().sectors[slot], good);
+    }
+
+    /// A slot that still opens on one copy but at an older root — the
+    /// secondary's write of a commit that never landed — is brought up to
+    /// the newest copy at open, so either leg alone opens at the same root.
+    #[test]
+    fn a_mirror_reconciles_a_stale_slot_at_open() {
+        let mut mirror = mirrored();
+        let mut store = ObjectStore::open(&mut mirror, &partition()).expect("store opens");
+        store.put(&mut mirror, 1, b"older").expect("put");
+        let before = mirror.secondary().sectors.clone();
+        store.put(&mut mirror, 1, b"newest").expect("put");
+        let slot = (FIRST_LBA + store.active.lba()) as usize;
+        mirror.secondary().sectors[slot] = before[slot];
+        assert_eq!(
+            open(mirror.secondary()).sequence(),
+            2,
+            "the leg alone is stale"
+        );
+
+        let store = ObjectStore::open(&mut mirror, &partition()).expect("store reopens");
+        assert_eq!(store.sequence(), 3);
+        let good = mirror.primary().sectors[slot];
+        assert_eq!(mirror.secondary().sectors[slot], good);
+        assert_eq!(open(mirror.secondary()).sequence(), 3);
+    }
+
+    /// Scrub checks every copy: rot on the copy reads never touch is found
+    /// and repaired, and only damage in every copy is reported.
+    #[test]
+    fn a_mirrored_scrub_repairs_one_copy_and_reports_both() {
+        let mut mirror = mirrored();
+        let mut store = ObjectStore::open(&mut mirror, &partition()).expect("store opens");
+        store.put(&mut mirror, 1, b"one").expect("put");
+        store.put(&mut mirror, 1, b"two").expect("put");
+        let lbas: Vec<u64> = store.entries.iter().map(|entry| entry.lba).collect();
+        mirror.secondary().sectors[(FIRST_LBA + lbas[0]) as usize][RECORD_HEADER] ^= 0x01;
+        mirror.primary().sectors[(FIRST_LBA + lbas[1]) as usize][RECORD_HEADER] ^= 0x01;
+        mirror.secondary().sectors[(FIRST_LBA + lbas[1]) as usize][RECORD_HEADER] ^= 0x01;
+
+        let mut cursor = ScrubCursor::new();
+        let mut report = ScrubReport::default();
+        scrub_to_end(&store, &mut mirror, &mut cursor, 8, &mut report);
+        assert_eq!(report.repaired, 1);
+        assert_eq!(report.damaged.len(), 1);
+        assert_eq!(
+            (report.damaged[0].lba, report.damaged[0].kind),
+            (lbas[1], DamageKind::HashMismatch)
+        );
+        let good = record_sectors_of(mirror.primary(), lbas[0]);
+        assert_eq!(record_sectors_of(mirror.secondary(), lbas[0]), good);
+    }
 }
@@ -1,3 +1,5 @@
+use crate::bootstate::TrustRootRef;
+use crate::release::INITIAL_TRUST_ROOT;
 use crate::sha256::Sha256;
 
 pub const MAGIC: [u8; 8] = *b"SLIMERC\0";
@@ -32,6 +34,9 @@ pub struct RecoveryIndex<'a> {
     pub target_pci_bdf: u32,
     pub state_first_lba: u64,
     pub state_last_lba: u64,
+    /// The rotated trust root the target was accepted under, as the BootState
+    /// it replaces recorded it. `None` is the compiled-in root.
+    pub trust_root: Option<TrustRootRef>,
     bytes: &'a [u8],
     state_count: usize,
 }
@@ -58,7 +63,7 @@ impl<'a> RecoveryIndex<'a> {
         {
             return Err(DecodeError::BadBounds);
         }
-        if bytes[156..HEADER_BYTES].iter().any(|byte| *byte != 0) {
+        if bytes[192..HEADER_BYTES].iter().any(|byte| *byte != 0) {
             return Err(DecodeError::NonZeroReserved);
         }
         let mut previous = [0u8; 32];
@@ -91,6 +96,17 @@ impl<'a> RecoveryIndex<'a> {
         {
             return Err(DecodeError::BadBounds);
         }
+        // All zero is the compiled-in root. Anything else must be a reference
+        // a BootState could carry: a named record at a rotated version.
+        let trust_root = TrustRootRef {
+            object: bytes[156..188].try_into().unwrap(),
+            version: u32_at(bytes, 188)?,
+        };
+        let trust_root = match (trust_root.object == [0; 32], trust_root.version) {
+            (true, 0) => None,
+            (false, version) if version > INITIAL_TRUST_ROOT.version => Some(trust_root),
+            _ => return Err(DecodeError::BadBounds),
+        };
         Ok(Self {
             target_generation,
             generation_root,
@@ -99,6 +115,7 @@ impl<'a> RecoveryIndex<'a> {
             target_pci_bdf: u32_at(bytes, 128)?,
             state_first_lba,
             state_last_lba,
+            trust_root,
             bytes,
             state_count,
         })
@@ -224,6 +241,7 @@ mod tests {
         assert_eq!(index.target_pci_bdf, 0x0000_0100);
         assert_eq!(index.state_first_lba, 64);
         assert_eq!(index.state_last_lba, 96);
+        assert_eq!(index.trust_root, None);
         assert_eq!(index.state_count(), 2);
 
         assert_eq!(
@@ -328,6 +346,39 @@ mod tests {
         }
     }
 
+    /// A rotated trust root is carried through so reconstruction restores it
+    /// rather than falling back to the compiled-in root the rotation replaced.
+    #[test]
+    fn a_rotated_trust_root_decodes_with_its_version() {
+        let mut bytes = valid();
+        bytes[156..188].fill(0xC3);
+        bytes[188..192].copy_from_slice(&2u32.to_le_bytes());
+        let index = RecoveryIndex::decode(&bytes).expect("rotated root");
+        assert_eq!(
+            index.trust_root,
+            Some(TrustRootRef {
+                object: [0xC3; 32],
+                version: 2,
+            })
+        );
+    }
+
+    /// A reference with only one half set, or at a version no rotation can
+    /// produce, names no trust root a BootState could hold.
+    #[test]
+    fn a_half_set_or_unrotated_trust_root_is_out_of_bounds() {
+        for (object, version) in [(0xC3u8, 0u32), (0, 2), (0xC3, INITIAL_TRUST_ROOT.version)] {
+            let mut bytes = valid();
+            bytes[156..188].fill(object);
+            bytes[188..192].copy_from_slice(&version.to_le_bytes());
+            assert_eq!(
+                RecoveryIndex::decode(&bytes).err(),
+                Some(DecodeError::BadBounds),
+                "object {object:#x} version {version}",
+            );
+        }
+    }
+
     /// An inverted LBA span would describe a region the reader cannot walk. An
     /// equal pair is a single sector and stays legal.
     #[test]
@@ -395,7 +446,7 @@ mod tests {
         );
 
         let mut bytes = valid();
-        bytes[156] = 1;
+        bytes[192] = 1;
         assert_eq!(
             RecoveryIndex::decode(&bytes).err(),
             Some(DecodeError::NonZeroReserved)
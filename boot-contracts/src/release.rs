@@ -3,6 +3,11 @@ use core::str;
 #[cfg(feature = "release-crypto")]
 use crate::generation::Generation;
 #[cfg(feature = "release-crypto")]
+use crate::generation_diff;
+use crate::generation_diff::DiffError;
+#[cfg(feature = "release-crypto")]
+use crate::generation_diff::Side;
+#[cfg(feature = "release-crypto")]
 use ed25519_dalek::{Signature, VerifyingKey};
 
 pub const RELEASE_MAGIC: [u8; 8] = *b"SLIMERL\0";
@@ -18,6 +23,8 @@ pub const INITIAL_TRUST_ROOT: TrustRoot = TrustRoot {
         hex32(*b"af5f0d3a5f47127874aab49d1c53508ddcacde17f25358afd32588a50e0d3934"),
         [0; 32],
     ],
+    revoked_count: 0,
+    revoked: [[0; 32]; MAX_REVOKED_KEYS],
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -26,6 +33,11 @@ pub struct TrustRoot {
     pub threshold: u32,
     pub key_count: u32,
     pub keys: [[u8; 32]; MAX_TRUST_KEYS],
+    /// Key ids, in ascending order, whose signatures count toward no
+    /// threshold. A revoked key may stay in `keys`: revocation needs no
+    /// replacement signer, only a rotation the remaining keys can meet.
+    pub revoked_count: u32,
+    pub revoked: [[u8; 32]; MAX_REVOKED_KEYS],
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -38,6 +50,12 @@ pub struct Release<'a> {
     pub trust_root_version: u32,
     pub boot_bundle: [u8; 32],
     pub authority_manifest: [u8; 32],
+    /// The signers approve authority wider than the baseline's. Set by
+    /// `RELEASE_FLAG_AUTHORITY_WIDENING`, inside the signed header.
+    pub authority_widening: bool,
+    /// The [`generation_diff::widenings_digest`] the approval covers, zero
+    /// unless `authority_widening` is set.
+    pub approved_widenings: [u8; 32],
     signature_count: usize,
 }
 
@@ -62,6 +80,19 @@ pub enum ReleaseError {
     UnknownKey,
     BadSignature,
     BadRotation,
+    /// The generation holds authority its parent did not, and the release
+    /// does not approve it.
+    AuthorityWidened,
+    /// The release approves a widening, but not the one this baseline and
+    /// generation differ by.
+    WideningNotApproved,
+    /// The baseline or the generation could not be read for authority.
+    Authority(DiffError),
+    /// Enough signatures, but too few from keys the trust root has not
+    /// revoked.
+    RevokedKey,
+    /// A persisted trust-root record that does not decode.
+    BadTrustRootRecord,
 }
 
 impl TrustRoot {
@@ -83,8 +114,97 @@ impl TrustRoot {
         if self.keys[count..].iter().any(|key| *key != [0; 32]) {
             return Err(ReleaseError::NonZeroReserved);
         }
+        let revoked = self.revoked_count as usize;
+        if revoked > MAX_REVOKED_KEYS {
+            return Err(ReleaseError::BadBounds);
+        }
+        for index in 0..revoked {
+            if self.revoked[index] == [0; 32]
+                || (index > 0 && self.revoked[index] <= self.revoked[index - 1])
+            {
+                return Err(ReleaseError::DuplicateKey);
+            }
+        }
+        if self.revoked[revoked..]
+            .iter()
+            .any(|key_id| *key_id != [0; 32])
+        {
+            return Err(ReleaseError::NonZeroReserved);
+        }
+        let live = self.keys[..count]
+            .iter()
+            .filter(|key| !self.revokes(&crate::sha256::digest(key.as_slice())))
+            .count();
+        if (live as u32) < self.threshold {
+            return Err(ReleaseError::BadBounds);
+        }
         Ok(())
     }
+
+    /// Whether signatures by `key_id` are refused.
+    pub fn revokes(&self, key_id: &[u8; 32]) -> bool {
+        self.revoked[..(self.revoked_count as usize).min(MAX_REVOKED_KEYS)].contains(key_id)
+    }
+
+    /// The record the generation-management service stores. Its store
+    /// identity, the SHA-256 of these bytes, is what BootState names.
+    pub fn encode(&self) -> Result<[u8; TRUST_ROOT_RECORD_BYTES], ReleaseError> {
+        self.validate()?;
+        let mut out = [0u8; TRUST_ROOT_RECORD_BYTES];
+        out[..8].copy_from_slice(&TRUST_ROOT_MAGIC);
+        out[TRUST_ROOT_HEADER_FORMAT_VERSION_OFFSET..TRUST_ROOT_HEADER_HEADER_SIZE_OFFSET]
+            .copy_from_slice(&TRUST_ROOT_RECORD_VERSION.to_le_bytes());
+        out[TRUST_ROOT_HEADER_HEADER_SIZE_OFFSET..TRUST_ROOT_HEADER_REQUIRED_FLAGS_OFFSET]
+            .copy_from_slice(&(TRUST_ROOT_HEADER_BYTES as u32).to_le_bytes());
+        for (offset, value) in [
+            (TRUST_ROOT_HEADER_VERSION_OFFSET, self.version),
+            (TRUST_ROOT_HEADER_THRESHOLD_OFFSET, self.threshold),
+            (TRUST_ROOT_HEADER_KEY_COUNT_OFFSET, self.key_count),
+            (TRUST_ROOT_HEADER_REVOKED_COUNT_OFFSET, self.revoked_count),
+        ] {
+            out[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        }
+        for (index, key) in self.keys.iter().chain(&self.revoked).enumerate() {
+            let offset = TRUST_ROOT_HEADER_BYTES + index * 32;
+            out[offset..offset + 32].copy_from_slice(key);
+        }
+        Ok(out)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, ReleaseError> {
+        let bytes: &[u8; TRUST_ROOT_RECORD_BYTES] =
+            bytes.try_into().map_err(|_| ReleaseError::BadSize)?;
+        if bytes[..8] != TRUST_ROOT_MAGIC
+            || read_u32(bytes, TRUST_ROOT_HEADER_FORMAT_VERSION_OFFSET) != TRUST_ROOT_RECORD_VERSION
+            || read_u32(bytes, TRUST_ROOT_HEADER_HEADER_SIZE_OFFSET) as usize
+                != TRUST_ROOT_HEADER_BYTES
+            || read_u64(bytes, TRUST_ROOT_HEADER_REQUIRED_FLAGS_OFFSET) != 0
+        {
+            return Err(ReleaseError::BadTrustRootRecord);
+        }
+        let lists_end = TRUST_ROOT_HEADER_BYTES + (MAX_TRUST_KEYS + MAX_REVOKED_KEYS) * 32;
+        if bytes[TRUST_ROOT_HEADER_RESERVED_OFFSET..TRUST_ROOT_HEADER_BYTES]
+            .iter()
+            .chain(&bytes[lists_end..])
+            .any(|byte| *byte != 0)
+        {
+            return Err(ReleaseError::NonZeroReserved);
+        }
+        let mut root = TrustRoot {
+            version: read_u32(bytes, TRUST_ROOT_HEADER_VERSION_OFFSET),
+            threshold: read_u32(bytes, TRUST_ROOT_HEADER_THRESHOLD_OFFSET),
+            key_count: read_u32(bytes, TRUST_ROOT_HEADER_KEY_COUNT_OFFSET),
+            keys: [[0; 32]; MAX_TRUST_KEYS],
+            revoked_count: read_u32(bytes, TRUST_ROOT_HEADER_REVOKED_COUNT_OFFSET),
+            revoked: [[0; 32]; MAX_REVOKED_KEYS],
+        };
+        for (index, key) in root.keys.iter_mut().chain(&mut root.revoked).enumerate() {
+            let offset = TRUST_ROOT_HEADER_BYTES + index * 32;
+            key.copy_from_slice(&bytes[offset..offset + 32]);
+        }
+        root.validate()?;
+        Ok(root)
+    }
 }
 
 impl<'a> Release<'a> {
@@ -99,7 +219,8 @@ impl<'a> Release<'a> {
         {
             return Err(ReleaseError::UnsupportedVersion);
         }
-        if read_u64(bytes, RELEASE_HEADER_REQUIRED_FLAGS_OFFSET) != 0 {
+        let required_flags = read_u64(bytes, RELEASE_HEADER_REQUIRED_FLAGS_OFFSET);
+        if required_flags & !RELEASE_FLAG_AUTHORITY_WIDENING != 0 {
             return Err(ReleaseError::UnknownRequiredFlags);
         }
         let target_len = read_u32(bytes, RELEASE_HEADER_TARGET_LEN_OFFSET) as usize;
@@ -138,6 +259,16 @@ impl<'a> Release<'a> {
         if version == RELEASE_VERSION && boot_bundle == [0; 32] {
             return Err(ReleaseError::WrongBootBundle);
         }
+        let authority_widening = required_flags & RELEASE_FLAG_AUTHORITY_WIDENING != 0;
+        let approved_widenings: [u8; 32] = bytes
+            [RELEASE_HEADER_APPROVED_WIDENINGS_OFFSET..RELEASE_HEADER_SIGNATURE_COUNT_OFFSET]
+            .try_into()
+            .unwrap();
+        // Only an approval names a widening list: without the flag the digest
+        // is a field nothing reads, held to zero like the rest of them.
+        if !authority_widening && approved_widenings != [0; 32] {
+            return Err(ReleaseError::NonZeroReserved);
+        }
         Ok(Self {
             bytes,
             generation: bytes
@@ -149,10 +280,12 @@ impl<'a> Release<'a> {
             target,
             trust_root_version: read_u32(bytes, RELEASE_HEADER_TRUST_ROOT_VERSION_OFFSET),
             boot_bundle,
-            authority_manifest: bytes
-                [RELEASE_HEADER_AUTHORITY_MANIFEST_OFFSET..RELEASE_HEADER_SIGNATURE_COUNT_OFFSET]
+            authority_manifest: bytes[RELEASE_HEADER_AUTHORITY_MANIFEST_OFFSET
+                ..RELEASE_HEADER_APPROVED_WIDENINGS_OFFSET]
                 .try_into()
                 .unwrap(),
+            authority_widening,
+            approved_widenings,
             signature_count,
         })
     }
@@ -176,7 +309,7 @@ impl<'a> Release<'a> {
         if self.target != generation.target {
             return Err(ReleaseError::WrongTarget);
         }
-        if !generation.is_v5() {
+        if !generation.is_v6() {
             return Err(ReleaseError::WrongBootBundle);
         }
         if self.authority_manifest != generation.authority_manifest_identity() {
@@ -193,10 +326,20 @@ impl<'a> Release<'a> {
         Ok(())
     }
 
+    /// Everything `verify_generation` checks, plus what only staging can: a
+    /// sequence past the accepted one, and authority no wider than
+    /// `baseline`'s unless this release approves the widening.
+    ///
+    /// `baseline` is what the authority is measured from: the generation the
+    /// release names as its parent, or, for a release naming none, the
+    /// generation this machine last accepted. A release without a parent is
+    /// still a change to what runs here, so it is held to the same check
+    /// against what it would replace.
     #[cfg(feature = "release-crypto")]
     pub fn verify_for_staging(
         &self,
         generation: &Generation<'_>,
+        baseline: &Generation<'_>,
         root: &TrustRoot,
         accepted_sequence: u64,
     ) -> Result<(), ReleaseError> {
@@ -204,6 +347,44 @@ impl<'a> Release<'a> {
         if self.sequence <= accepted_sequence {
             return Err(ReleaseError::StaleSequence);
         }
+        if self
+            .parent
+            .is_some_and(|parent| parent != baseline.identity)
+        {
+            return Err(ReleaseError::WrongParent);
+        }
+        self.verify_authority(baseline, generation)
+    }
+
+    /// Refuse authority in `generation` that `baseline` did not confer, unless
+    /// the release approves exactly that widening.
+    ///
+    /// An approval is the digest of the widening list, not a blanket flag: a
+    /// release signed for one baseline approves nothing measured from another
+    /// that widens differently. The digest sits in the signed header, so it
+    /// carries the same threshold of signatures as the rest of the release;
+    /// this check alone proves nothing about them, and `verify_for_staging`
+    /// runs it only after they verify.
+    #[cfg(feature = "release-crypto")]
+    pub fn verify_authority(
+        &self,
+        baseline: &Generation<'_>,
+        generation: &Generation<'_>,
+    ) -> Result<(), ReleaseError> {
+        let (baseline, generation) = (Side::from(*baseline), Side::from(*generation));
+        if self.authority_widening {
+            let digest = generation_diff::widenings_digest(&baseline, &generation)
+                .map_err(ReleaseError::Authority)?;
+            if digest != self.approved_widenings {
+                return Err(ReleaseError::WideningNotApproved);
+            }
+            return Ok(());
+        }
+        let widened = generation_diff::widenings(&baseline, &generation, |_| {})
+            .map_err(ReleaseError::Authority)?;
+        if widened != 0 {
+            return Err(ReleaseError::AuthorityWidened);
+        }
         Ok(())
     }
 
@@ -245,6 +426,7 @@ pub fn apply_rotation(current: &TrustRoot, bytes: &[u8]) -> Result<TrustRoot, Re
         read_u32(bytes, ROTATION_HEADER_PREVIOUS_SIGNATURE_COUNT_OFFSET) as usize;
     let replacement_signature_count =
         read_u32(bytes, ROTATION_HEADER_REPLACEMENT_SIGNATURE_COUNT_OFFSET) as usize;
+    let revoked_count = read_u32(bytes, ROTATION_HEADER_REVOKED_COUNT_OFFSET);
     if previous_version != current.version
         || replacement_version
             != current
@@ -264,13 +446,28 @@ pub fn apply_rotation(current: &TrustRoot, bytes: &[u8]) -> Result<TrustRoot, Re
         threshold: replacement_threshold,
         key_count: replacement_key_count,
         keys: [[0; 32]; MAX_TRUST_KEYS],
+        revoked_count,
+        revoked: [[0; 32]; MAX_REVOKED_KEYS],
     };
-    for index in 0..MAX_TRUST_KEYS {
+    for (index, key) in replacement
+        .keys
+        .iter_mut()
+        .chain(&mut replacement.revoked)
+        .enumerate()
+    {
         let offset = ROTATION_HEADER_BYTES + index * 32;
-        replacement.keys[index].copy_from_slice(&bytes[offset..offset + 32]);
+        key.copy_from_slice(&bytes[offset..offset + 32]);
     }
     replacement.validate()?;
-    let previous_offset = ROTATION_HEADER_BYTES + MAX_TRUST_KEYS * 32;
+    // Revocation is permanent: a rotation that drops a revoked key id would
+    // let its signatures count again.
+    if current.revoked[..current.revoked_count as usize]
+        .iter()
+        .any(|key_id| !replacement.revokes(key_id))
+    {
+        return Err(ReleaseError::BadRotation);
+    }
+    let previous_offset = ROTATION_HEADER_BYTES + (MAX_TRUST_KEYS + MAX_REVOKED_KEYS) * 32;
     let replacement_offset = previous_offset + MAX_RELEASE_SIGNATURES * RELEASE_SIGNATURE_BYTES;
     if bytes
         [previous_offset + previous_signature_count * RELEASE_SIGNATURE_BYTES..replacement_offset]
@@ -322,6 +519,7 @@ fn verify_signature_entries(
         return Err(ReleaseError::MissingSignatures);
     }
     let mut previous = [0; 32];
+    let mut counted = 0;
     for index in 0..count {
         let offset = index * RELEASE_SIGNATURE_BYTES;
         let key_id: [u8; 32] = entries[offset..offset + 32].try_into().unwrap();
@@ -329,6 +527,11 @@ fn verify_signature_entries(
             return Err(ReleaseError::DuplicateKey);
         }
         previous = key_id;
+        // Skipped rather than refused, so a release carrying one revoked
+        // signature beside a threshold of live ones still verifies.
+        if root.revokes(&key_id) {
+            continue;
+        }
         let key = root.keys[..root.key_count as usize]
             .iter()
             .find(|key| crate::sha256::digest(key.as_slice()) == key_id)
@@ -337,11 +540,15 @@ fn verify_signature_entries(
             .try_into()
             .map_err(|_| ReleaseError::BadSignature)?;
         verify_ed25519(key, payload, &signature)?;
+        counted += 1;
+    }
+    if counted < root.threshold as usize {
+        return Err(ReleaseError::RevokedKey);
     }
     Ok(())
 }
 #[cfg(feature = "release-crypto")]
-fn ssh_signed_payload(payload: &[u8]) -> [u8; 73] {
+pub(crate) fn ssh_signed_payload(payload: &[u8]) -> [u8; 73] {
     let mut signed = [0u8; 73];
     let mut offset = 0;
     signed[offset..offset + 6].copy_from_slice(b"SSHSIG");
@@ -403,9 +610,15 @@ mod tests {
             threshold,
             key_count,
             keys,
+            revoked_count: 0,
+            revoked: [[0; 32]; MAX_REVOKED_KEYS],
         }
     }
 
+    fn key_id(key: &[u8; 32]) -> [u8; 32] {
+        crate::sha256::digest(key)
+    }
+
     /// One signed release naming a parent, a target, and a kernel digest. The
     /// signature area stays zeroed: `decode` requires the tail past
     /// `signature_count` entries to be zero, and a count of zero means all of
@@ -540,7 +753,7 @@ mod tests {
     #[test]
     fn a_nonzero_reserved_byte_is_refused_wherever_it_sits() {
         let target_len = "x86_64-qemu-virtio".len();
-        for offset in [104 + target_len, 204, RELEASE_HEADER_BYTES] {
+        for offset in [104 + target_len, 236, RELEASE_HEADER_BYTES] {
             let mut bytes = valid();
             bytes[offset] = 1;
             assert_eq!(
@@ -551,13 +764,61 @@ mod tests {
         }
     }
 
+    /// Every required flag but the one this version defines is refused, so a
+    /// release needing a check this reader lacks is never staged without it.
     #[test]
     fn a_nonzero_required_flag_is_refused() {
+        for bit in 1..64 {
+            let mut bytes = valid();
+            bytes[16..24].copy_from_slice(&(1u64 << bit).to_le_bytes());
+            assert_eq!(
+                Release::decode(&bytes).err(),
+                Some(ReleaseError::UnknownRequiredFlags),
+                "bit {bit}",
+            );
+        }
+    }
+
+    /// The approval and the widening digest it covers are both read from the
+    /// signed header, and their absence is the default a release without the
+    /// flag decodes to. A digest without the flag approves nothing and is
+    /// refused like any other reserved byte.
+    #[test]
+    fn the_authority_widening_flag_is_an_approval() {
+        let bytes = valid();
+        let release = Release::decode(&bytes).expect("valid");
+        assert!(!release.authority_widening);
+        assert_eq!(release.approved_widenings, [0; 32]);
+        let mut approved = valid();
+        approved[16..24].copy_from_slice(&RELEASE_FLAG_AUTHORITY_WIDENING.to_le_bytes());
+        approved[200..232].fill(0xA5);
+        let release = Release::decode(&approved).expect("approved release");
+        assert!(release.authority_widening);
+        assert_eq!(release.approved_widenings, [0xA5; 32]);
+        assert_eq!(
+            &release.signed_payload()[16..24],
+            &RELEASE_FLAG_AUTHORITY_WIDENING.to_le_bytes()
+        );
+        assert_eq!(&release.signed_payload()[200..232], &[0xA5; 32]);
+
+        let mut unflagged = valid();
+        unflagged[200..232].fill(0xA5);
+        assert_eq!(
+            Release::decode(&unflagged).err(),
+            Some(ReleaseError::NonZeroReserved)
+        );
+    }
+
+    /// Version 2 has no widening digest, and keeps its signature count where
+    /// version 3 keeps the digest.
+    #[test]
+    fn release_v2_is_rejected_after_v3_cutover() {
         let mut bytes = valid();
-        bytes[16] = 1;
+        bytes[RELEASE_HEADER_FORMAT_VERSION_OFFSET..RELEASE_HEADER_FORMAT_VERSION_OFFSET + 4]
+            .copy_from_slice(&RELEASE_VERSION_V2.to_le_bytes());
         assert_eq!(
             Release::decode(&bytes).err(),
-            Some(ReleaseError::UnknownRequiredFlags)
+            Some(ReleaseError::UnsupportedVersion)
         );
     }
 
@@ -566,7 +827,7 @@ mod tests {
     #[test]
     fn more_signatures_than_the_record_holds_is_refused() {
         let mut bytes = valid();
-        bytes[200..204].copy_from_slice(&(MAX_RELEASE_SIGNATURES as u32 + 1).to_le_bytes());
+        bytes[232..236].copy_from_slice(&(MAX_RELEASE_SIGNATURES as u32 + 1).to_le_bytes());
         assert_eq!(
             Release::decode(&bytes).err(),
             Some(ReleaseError::NonZeroReserved)
@@ -579,7 +840,7 @@ mod tests {
     #[test]
     fn a_declared_signature_slot_admits_its_own_bytes_only() {
         let mut bytes = valid();
-        bytes[200..204].copy_from_slice(&1u32.to_le_bytes());
+        bytes[232..236].copy_from_slice(&1u32.to_le_bytes());
         bytes[RELEASE_HEADER_BYTES] = 0xAA;
         Release::decode(&bytes).expect("one declared signature admits its bytes");
 
@@ -637,4 +898,132 @@ mod tests {
         trailing.keys[2].fill(0x99);
         assert_eq!(trailing.validate(), Err(ReleaseError::NonZeroReserved));
     }
+
+    /// Revoke the first `count` keys of `root`, in key-id order.
+    fn revoking(mut root: TrustRoot, count: usize) -> TrustRoot {
+        for index in 0..count {
+            root.revoked[index] = key_id(&root.keys[index]);
+        }
+        root.revoked[..count].sort();
+        root.revoked_count = count as u32;
+        root
+    }
+
+    /// The list is sorted so that it has one encoding, and a revocation may
+    /// not leave fewer live keys than the threshold, since such a root could
+    /// verify nothing.
+    #[test]
+    fn a_revocation_list_is_ordered_and_leaves_a_live_threshold() {
+        assert_eq!(revoking(root(2, 4), 2).validate(), Ok(()));
+        assert_eq!(
+            revoking(root(2, 3), 2).validate(),
+            Err(ReleaseError::BadBounds)
+        );
+
+        let mut unordered = revoking(root(2, 4), 2);
+        unordered.revoked.swap(0, 1);
+        assert_eq!(unordered.validate(), Err(ReleaseError::DuplicateKey));
+
+        let mut trailing = revoking(root(2, 4), 1);
+        trailing.revoked[1].fill(0x77);
+        assert_eq!(trailing.validate(), Err(ReleaseError::NonZeroReserved));
+
+        let mut over = root(2, 4);
+        over.revoked_count = MAX_REVOKED_KEYS as u32 + 1;
+        assert_eq!(over.validate(), Err(ReleaseError::BadBounds));
+
+        let revoked = revoking(root(2, 4), 1);
+        assert!(revoked.revokes(&key_id(&revoked.keys[0])));
+        assert!(!revoked.revokes(&key_id(&revoked.keys[1])));
+    }
+
+    /// The persisted record carries the whole root, revocations included, and
+    /// nothing a future version might mean is read past.
+    #[test]
+    fn a_trust_root_record_round_trips_and_refuses_damage() {
+        let root = revoking(root(2, 4), 1);
+        let record = root.encode().expect("valid root");
+        assert_eq!(TrustRoot::decode(&record), Ok(root));
+        assert_eq!(
+            TrustRoot::decode(&INITIAL_TRUST_ROOT.encode().expect("initial root")),
+            Ok(INITIAL_TRUST_ROOT)
+        );
+
+        let mut magic = record;
+        magic[0] ^= 1;
+        assert_eq!(
+            TrustRoot::decode(&magic),
+            Err(ReleaseError::BadTrustRootRecord)
+        );
+        for offset in [
+            TRUST_ROOT_HEADER_RESERVED_OFFSET,
+            TRUST_ROOT_RECORD_BYTES - 1,
+        ] {
+            let mut reserved = record;
+            reserved[offset] = 1;
+            assert_eq!(
+                TrustRoot::decode(&reserved),
+                Err(ReleaseError::NonZeroReserved),
+                "offset {offset}",
+            );
+        }
+        assert_eq!(
+            TrustRoot::decode(&record[..TRUST_ROOT_RECORD_BYTES - 1]),
+            Err(ReleaseError::BadSize)
+        );
+    }
+
+    /// Two well-formed signature entries, both from revoked keys: the count
+    /// meets the threshold, and the refusal names revocation. Revoked entries
+    /// are skipped before their signatures are checked, so no signing key is
+    /// needed to show it.
+    #[cfg(feature = "release-crypto")]
+    #[test]
+    fn signatures_by_revoked_keys_do_not_count() {
+        let root = revoking(root(2, 4), 2);
+        let mut bytes = valid();
+        bytes[232..236].copy_from_slice(&2u32.to_le_bytes());
+        for index in 0..2 {
+            let offset = RELEASE_HEADER_BYTES + index * RELEASE_SIGNATURE_BYTES;
+            bytes[offset..offset + 32].copy_from_slice(&root.revoked[index]);
+            bytes[offset + 32..offset + RELEASE_SIGNATURE_BYTES].fill(0x5A);
+        }
+        let release = Release::decode(&bytes).expect("valid release");
+        assert_eq!(
+            release.verify_signatures(&root),
+            Err(ReleaseError::RevokedKey)
+        );
+    }
+
+    /// A rotation from a root with a revocation to one without is refused
+    /// before any signature is read: no threshold can un-revoke a key.
+    #[cfg(feature = "release-crypto")]
+    #[test]
+    fn a_rotation_cannot_drop_a_revocation() {
+        let current = revoking(root(2, 4), 1);
+        let replacement = root(2, 4);
+        let mut bytes = [0u8; ROTATION_BYTES];
+        bytes[..8].copy_from_slice(&ROTATION_MAGIC);
+        for (offset, value) in [
+            (ROTATION_HEADER_FORMAT_VERSION_OFFSET, ROTATION_VERSION),
+            (
+                ROTATION_HEADER_HEADER_SIZE_OFFSET,
+                ROTATION_HEADER_BYTES as u32,
+            ),
+            (ROTATION_HEADER_PREVIOUS_VERSION_OFFSET, 1),
+            (ROTATION_HEADER_REPLACEMENT_VERSION_OFFSET, 2),
+            (ROTATION_HEADER_REPLACEMENT_THRESHOLD_OFFSET, 2),
+            (ROTATION_HEADER_REPLACEMENT_KEY_COUNT_OFFSET, 4),
+        ] {
+            bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        }
+        for (index, key) in replacement.keys.iter().enumerate() {
+            let offset = ROTATION_HEADER_BYTES + index * 32;
+            bytes[offset..offset + 32].copy_from_slice(key);
+        }
+        assert_eq!(
+            apply_rotation(&current, &bytes),
+            Err(ReleaseError::BadRotation)
+        );
+    }
 }
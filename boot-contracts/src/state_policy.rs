@@ -0,0 +1,766 @@
+//! The state a generation binds, and what its state policies do to it when
+//! the generation is staged, rolled back, or promoted.
+//!
+//! BootState names one state root. It is the SHA-256 of a state map: the
+//! recovery index's state entries — binding identity, object identity, schema
+//! version, 68 bytes each in ascending binding order — with nothing between
+//! them, which is exactly what the recovery index hashes for its own root. So
+//! the map record's store identity is the root BootState names, and the empty
+//! map is `empty_state_root`.
+//!
+//! Two policies act at transitions. Staging a generation that declares a
+//! `POLICY_SNAPSHOT_BEFORE_UPGRADE` binding keeps the map as it was in a
+//! snapshot record tagged with that generation; rolling the generation back
+//! puts those bindings back as the snapshot had them and drops every
+//! `POLICY_DISCARD_ON_ROLLBACK` binding; promoting it lets the snapshot go.
+//! What each did is reported per binding, for the state journal beside
+//! BootState to record. Device I/O is the caller's: nothing here reads the
+//! store or the journal.
+//!
+//! The rollback's map is worked out when the generation is staged, by
+//! `rollback_at_staging`, and the staged BootState names its root. Stage-0 and
+//! the root roll back without reading a generation, so a rollback decided
+//! anywhere restores the same state.
+//!
+//! A binding whose state is in another schema version than the staged
+//! generation declares must be migrated before that generation boots: the
+//! generation names the executable that translates from the version the map
+//! holds, or the stage is refused. The migrated map is what the staged
+//! BootState names, and the snapshot keeps the entry as it was, so a rollback
+//! hands the generation it returns to the state in its own schema whatever
+//! the binding's policy.
+
+use crate::bootstate::JournalAction;
+use crate::generation::{
+    DecodeError, Generation, POLICY_DISCARD_ON_ROLLBACK, POLICY_SNAPSHOT_BEFORE_UPGRADE,
+    StateMigration,
+};
+use crate::recovery::{MAX_STATE_OBJECTS, StateEntry, binding_identity};
+use crate::sha256::digest;
+
+/// One map entry: binding identity, object identity, schema version.
+pub const MAP_ENTRY_BYTES: usize = 68;
+pub const MAX_MAP_BYTES: usize = MAX_STATE_OBJECTS * MAP_ENTRY_BYTES;
+/// A snapshot record: the generation it was taken for, then the map.
+pub const MAX_SNAPSHOT_BYTES: usize = 32 + MAX_MAP_BYTES;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateError {
+    /// Not a whole number of entries, or more than a map holds.
+    BadLength,
+    /// Bindings out of ascending order, repeated, or an entry with a zero
+    /// half or schema version — what the recovery index refuses too.
+    BadOrder,
+    /// A snapshot taken for another generation.
+    WrongGeneration,
+    /// State in a schema version the generation neither declares nor
+    /// migrates from.
+    Unmigrated,
+    Generation(DecodeError),
+    /// The output buffer cannot hold the result.
+    TooSmall,
+}
+
+impl From<DecodeError> for StateError {
+    fn from(error: DecodeError) -> Self {
+        Self::Generation(error)
+    }
+}
+
+/// A decoded state map, borrowing its record.
+#[derive(Debug, Clone, Copy)]
+pub struct StateMap<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> StateMap<'a> {
+    pub const EMPTY: StateMap<'static> = StateMap { bytes: &[] };
+
+    pub fn decode(bytes: &'a [u8]) -> Result<Self, StateError> {
+        if !bytes.len().is_multiple_of(MAP_ENTRY_BYTES) || bytes.len() > MAX_MAP_BYTES {
+            return Err(StateError::BadLength);
+        }
+        let map = Self { bytes };
+        let mut previous: Option<[u8; 32]> = None;
+        for entry in map.entries() {
+            if entry.binding_identity == [0; 32]
+                || entry.object_identity == [0; 32]
+                || entry.schema_version == 0
+                || previous.is_some_and(|previous| entry.binding_identity <= previous)
+            {
+                return Err(StateError::BadOrder);
+            }
+            previous = Some(entry.binding_identity);
+        }
+        Ok(map)
+    }
+
+    /// The state root naming this map: its record's identity.
+    pub fn root(&self) -> [u8; 32] {
+        digest(self.bytes)
+    }
+
+    pub fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len() / MAP_ENTRY_BYTES
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = StateEntry> + 'a {
+        self.bytes
+            .chunks_exact(MAP_ENTRY_BYTES)
+            .map(|entry| StateEntry {
+                binding_identity: entry[..32].try_into().unwrap(),
+                object_identity: entry[32..64].try_into().unwrap(),
+                schema_version: u32::from_le_bytes(entry[64..68].try_into().unwrap()),
+            })
+    }
+
+    pub fn find(&self, binding: &[u8; 32]) -> Option<StateEntry> {
+        self.entries()
+            .find(|entry| entry.binding_identity == *binding)
+    }
+}
+
+/// One migration staging a generation runs: the binding's entry as the map
+/// holds it, and the version and migrator the generation declares for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Migration {
+    pub entry: StateEntry,
+    pub policy: u32,
+    pub to_version: u32,
+    /// The migrator, as an index into the generation's executables.
+    pub executable: usize,
+}
+
+/// What a generation declares for one binding.
+#[derive(Debug, Clone, Copy)]
+struct Declared {
+    policy: u32,
+    schema_version: u32,
+    migration: Option<StateMigration>,
+}
+
+/// The snapshot record staging `generation` over `map` keeps.
+pub fn encode_snapshot(
+    generation: &[u8; 32],
+    map: &StateMap<'_>,
+    out: &mut [u8],
+) -> Result<usize, StateError> {
+    let len = 32 + map.bytes.len();
+    let out = out.get_mut(..len).ok_or(StateError::TooSmall)?;
+    out[..32].copy_from_slice(generation);
+    out[32..].copy_from_slice(map.bytes);
+    Ok(len)
+}
+
+/// The map a snapshot record kept, provided it was taken for `generation`.
+pub fn decode_snapshot<'a>(
+    generation: &[u8; 32],
+    bytes: &'a [u8],
+) -> Result<StateMap<'a>, StateError> {
+    let (taken_for, map) = bytes
+        .split_first_chunk::<32>()
+        .ok_or(StateError::BadLength)?;
+    if taken_for != generation {
+        return Err(StateError::WrongGeneration);
+    }
+    StateMap::decode(map)
+}
+
+/// The entries of `map` whose bindings `generation` declares
+/// snapshot-before-upgrade, in map order, and their number.
+///
+/// At staging these are what the snapshot keeps; at promotion they are what
+/// it stops keeping. A generation with none needs no snapshot record.
+pub fn snapshotted(
+    generation: &Generation<'_>,
+    map: &StateMap<'_>,
+    each: impl FnMut(StateEntry),
+) -> Result<usize, StateError> {
+    snapshotted_by(|binding| declared(generation, binding), map, each)
+}
+
+fn snapshotted_by(
+    policy_of: impl Fn(&[u8; 32]) -> Result<Option<u32>, StateError>,
+    map: &StateMap<'_>,
+    mut each: impl FnMut(StateEntry),
+) -> Result<usize, StateError> {
+    let mut count = 0;
+    for entry in map.entries() {
+        if policy_of(&entry.binding_identity)? == Some(POLICY_SNAPSHOT_BEFORE_UPGRADE) {
+            each(entry);
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+/// The migrations staging `generation` over `map` needs, in map order, and
+/// their number.
+///
+/// A binding the generation declares at another schema version than the map
+/// holds needs one, and the generation must declare it from exactly that
+/// version; `Unmigrated` refuses the stage otherwise. A binding the generation
+/// does not declare is left as it is.
+pub fn migrations(
+    generation: &Generation<'_>,
+    map: &StateMap<'_>,
+    each: impl FnMut(Migration),
+) -> Result<usize, StateError> {
+    migrations_by(|binding| declaration(generation, binding), map, each)
+}
+
+fn migrations_by(
+    declared_of: impl Fn(&[u8; 32]) -> Result<Option<Declared>, StateError>,
+    map: &StateMap<'_>,
+    mut each: impl FnMut(Migration),
+) -> Result<usize, StateError> {
+    let mut count = 0;
+    for entry in map.entries() {
+        let Some(declared) = declared_of(&entry.binding_identity)? else {
+            continue;
+        };
+        if declared.schema_version == entry.schema_version {
+            continue;
+        }
+        let migration = declared
+            .migration
+            .filter(|migration| migration.from_version == entry.schema_version)
+            .ok_or(StateError::Unmigrated)?;
+        each(Migration {
+            entry,
+            policy: declared.policy,
+            to_version: declared.schema_version,
+            executable: migration.executable,
+        });
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// `current` with each of `migrated` in place of the entry for its binding,
+/// written to `out`. Every migrated binding must be one `current` holds.
+pub fn apply_migrations(
+    current: &StateMap<'_>,
+    migrated: &[StateEntry],
+    out: &mut [u8],
+) -> Result<usize, StateError> {
+    if migrated
+        .iter()
+        .any(|entry| current.find(&entry.binding_identity).is_none())
+    {
+        return Err(StateError::BadOrder);
+    }
+    let mut len = 0;
+    for entry in current.entries() {
+        let entry = migrated
+            .iter()
+            .find(|migrated| migrated.binding_identity == entry.binding_identity)
+            .copied()
+            .unwrap_or(entry);
+        len = put_entry(out, len, entry)?;
+    }
+    Ok(len)
+}
+
+/// The map a rollback of `generation` leaves, written to `out`, with each
+/// binding it changed reported as a restore or a discard.
+///
+/// A discard-on-rollback binding is dropped. A snapshot-before-upgrade
+/// binding goes back to what `snapshot` kept, and is dropped if the snapshot
+/// had none of it: the upgrade created it. Without a snapshot those bindings
+/// are left as they are, since nothing says what they were. A binding the
+/// snapshot holds in another schema version was migrated at staging and goes
+/// back too, whatever its policy. Every other binding, and every binding
+/// `generation` does not declare, keeps its current object.
+pub fn roll_back(
+    generation: &Generation<'_>,
+    current: &StateMap<'_>,
+    snapshot: Option<&StateMap<'_>>,
+    out: &mut [u8],
+    each: impl FnMut(JournalAction, u32, StateEntry),
+) -> Result<usize, StateError> {
+    roll_back_by(
+        |binding| declared(generation, binding),
+        current,
+        snapshot,
+        out,
+        each,
+    )
+}
+
+/// The map a rollback of `generation` will restore, worked out as it is
+/// staged: `staged` over `inherited`, with `inherited` as the snapshot. Every
+/// binding goes back to what `inherited` held except the discard-on-rollback
+/// ones, which are dropped; with none of those the result is `inherited`
+/// itself. The staged BootState names this root, so no reader of the
+/// generation is needed when the rollback comes.
+pub fn rollback_at_staging(
+    generation: &Generation<'_>,
+    inherited: &StateMap<'_>,
+    staged: &StateMap<'_>,
+    out: &mut [u8],
+) -> Result<usize, StateError> {
+    roll_back(generation, staged, Some(inherited), out, |_, _, _| {})
+}
+
+fn roll_back_by(
+    policy_of: impl Fn(&[u8; 32]) -> Result<Option<u32>, StateError>,
+    current: &StateMap<'_>,
+    snapshot: Option<&StateMap<'_>>,
+    out: &mut [u8],
+    mut each: impl FnMut(JournalAction, u32, StateEntry),
+) -> Result<usize, StateError> {
+    let mut len = 0;
+    let mut current_entries = current.entries().peekable();
+    let mut snapshot_entries = snapshot
+        .map(|map| map.entries())
+        .into_iter()
+        .flatten()
+        .peekable();
+    loop {
+        // The two maps merged by binding, so the result stays ascending.
+        let (now, then) = match (current_entries.peek(), snapshot_entries.peek()) {
+            (None, None) => break,
+            (Some(now), Some(then)) if now.binding_identity == then.binding_identity => {
+                (current_entries.next(), snapshot_entries.next())
+            }
+            (Some(now), Some(then)) if now.binding_identity < then.binding_identity => {
+                (current_entries.next(), None)
+            }
+            (Some(_), None) => (current_entries.next(), None),
+            (_, Some(_)) => (None, snapshot_entries.next()),
+        };
+        let binding = now.or(then).expect("one side is present").binding_identity;
+        let kept = match policy_of(&binding)? {
+            Some(POLICY_DISCARD_ON_ROLLBACK) => {
+                if let Some(now) = now {
+                    each(JournalAction::Discard, POLICY_DISCARD_ON_ROLLBACK, now);
+                }
+                None
+            }
+            Some(POLICY_SNAPSHOT_BEFORE_UPGRADE) if snapshot.is_some() => {
+                match (now, then) {
+                    (_, Some(then)) if now != Some(then) => {
+                        each(JournalAction::Restore, POLICY_SNAPSHOT_BEFORE_UPGRADE, then);
+                    }
+                    (Some(now), None) => {
+                        each(JournalAction::Discard, POLICY_SNAPSHOT_BEFORE_UPGRADE, now);
+                    }
+                    _ => {}
+                }
+                then
+            }
+            Some(policy) if was_migrated(now, then) => {
+                if let Some(then) = then {
+                    each(JournalAction::Restore, policy, then);
+                }
+                then
+            }
+            _ => now,
+        };
+        if let Some(entry) = kept {
+            len = put_entry(out, len, entry)?;
+        }
+    }
+    Ok(len)
+}
+
+/// Whether a binding's schema version moved between the snapshot and now,
+/// which only a migration at staging does.
+fn was_migrated(now: Option<StateEntry>, then: Option<StateEntry>) -> bool {
+    now.zip(then)
+        .is_some_and(|(now, then)| now.schema_version != then.schema_version)
+}
+
+/// Write `entry` as the map entry at `len` in `out`, answering the new length.
+fn put_entry(out: &mut [u8], len: usize, entry: StateEntry) -> Result<usize, StateError> {
+    let slot = out
+        .get_mut(len..len + MAP_ENTRY_BYTES)
+        .ok_or(StateError::TooSmall)?;
+    slot[..32].copy_from_slice(&entry.binding_identity);
+    slot[32..64].copy_from_slice(&entry.object_identity);
+    slot[64..].copy_from_slice(&entry.schema_version.to_le_bytes());
+    Ok(len + MAP_ENTRY_BYTES)
+}
+
+/// The policy `generation` declares for `binding`, if it binds it at all.
+fn declared(generation: &Generation<'_>, binding: &[u8; 32]) -> Result<Option<u32>, StateError> {
+    Ok(declaration(generation, binding)?.map(|declared| declared.policy))
+}
+
+fn declaration(
+    generation: &Generation<'_>,
+    binding: &[u8; 32],
+) -> Result<Option<Declared>, StateError> {
+    for index in 0..generation.state_count() {
+        let state = generation.state(index)?;
+        if binding_identity(state.name) == *binding {
+            return Ok(Some(Declared {
+                policy: state.policy,
+                schema_version: state.schema_version,
+                migration: state.migration,
+            }));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bootstate::BootState;
+    use crate::generation::POLICY_PRESERVE;
+
+    const GENERATION: [u8; 32] = [0x22; 32];
+
+    fn entry(binding: u8, object: u8) -> StateEntry {
+        StateEntry {
+            binding_identity: [binding; 32],
+            object_identity: [object; 32],
+            schema_version: 1,
+        }
+    }
+
+    fn map(entries: &[StateEntry], out: &mut [u8; MAX_MAP_BYTES]) -> usize {
+        for (index, entry) in entries.iter().enumerate() {
+            let slot = &mut out[index * MAP_ENTRY_BYTES..(index + 1) * MAP_ENTRY_BYTES];
+            slot[..32].copy_from_slice(&entry.binding_identity);
+            slot[32..64].copy_from_slice(&entry.object_identity);
+            slot[64..].copy_from_slice(&entry.schema_version.to_le_bytes());
+        }
+        entries.len() * MAP_ENTRY_BYTES
+    }
+
+    /// Binding 1 is snapshotted, 2 discarded, 3 preserved; 4 is undeclared.
+    fn policy_of(binding: &[u8; 32]) -> Result<Option<u32>, StateError> {
+        Ok(match binding[0] {
+            1 => Some(POLICY_SNAPSHOT_BEFORE_UPGRADE),
+            2 => Some(POLICY_DISCARD_ON_ROLLBACK),
+            3 => Some(POLICY_PRESERVE),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn the_state_root_is_the_recovery_index_root_of_the_same_entries() {
+        let mut bytes = [0u8; MAX_MAP_BYTES];
+        let len = map(&[entry(1, 9), entry(2, 8)], &mut bytes);
+        let decoded = StateMap::decode(&bytes[..len]).unwrap();
+
+        let mut hasher = crate::sha256::Sha256::new();
+        for entry in [entry(1, 9), entry(2, 8)] {
+            hasher.update(&entry.binding_identity);
+            hasher.update(&entry.object_identity);
+            hasher.update(&entry.schema_version.to_le_bytes());
+        }
+        assert_eq!(decoded.root(), hasher.finalize());
+        assert_eq!(StateMap::EMPTY.root(), crate::bootstate::empty_state_root());
+        assert_eq!(decoded.find(&[2; 32]), Some(entry(2, 8)));
+    }
+
+    #[test]
+    fn a_map_out_of_order_or_partial_is_refused() {
+        let mut bytes = [0u8; MAX_MAP_BYTES];
+        let len = map(&[entry(2, 9), entry(1, 8)], &mut bytes);
+        assert_eq!(
+            StateMap::decode(&bytes[..len]).unwrap_err(),
+            StateError::BadOrder
+        );
+        assert_eq!(
+            StateMap::decode(&bytes[..len - 1]).unwrap_err(),
+            StateError::BadLength
+        );
+    }
+
+    #[test]
+    fn a_snapshot_answers_only_for_its_generation() {
+        let mut bytes = [0u8; MAX_MAP_BYTES];
+        let len = map(&[entry(1, 9)], &mut bytes);
+        let kept = StateMap::decode(&bytes[..len]).unwrap();
+        let mut record = [0u8; MAX_SNAPSHOT_BYTES];
+        let record_len = encode_snapshot(&GENERATION, &kept, &mut record).unwrap();
+
+        let decoded = decode_snapshot(&GENERATION, &record[..record_len]).unwrap();
+        assert_eq!(decoded.root(), kept.root());
+        assert_eq!(
+            decode_snapshot(&[0x33; 32], &record[..record_len]).unwrap_err(),
+            StateError::WrongGeneration
+        );
+    }
+
+    #[test]
+    fn staging_snapshots_only_the_bindings_that_ask_for_it() {
+        let mut bytes = [0u8; MAX_MAP_BYTES];
+        let len = map(&[entry(1, 9), entry(2, 8), entry(3, 7)], &mut bytes);
+        let mut seen = None;
+        let count = snapshotted_by(
+            policy_of,
+            &StateMap::decode(&bytes[..len]).unwrap(),
+            |entry| seen = Some(entry),
+        )
+        .unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(seen, Some(entry(1, 9)));
+    }
+
+    #[test]
+    fn a_rollback_restores_snapshots_and_drops_discarded_state() {
+        let mut before = [0u8; MAX_MAP_BYTES];
+        let before_len = map(&[entry(1, 9), entry(2, 8), entry(3, 7)], &mut before);
+        // The candidate rewrote 1, 2 and 3, and created 4 and 5.
+        let mut after = [0u8; MAX_MAP_BYTES];
+        let after_len = map(
+            &[entry(1, 19), entry(2, 18), entry(3, 17), entry(4, 16)],
+            &mut after,
+        );
+        let snapshot = StateMap::decode(&before[..before_len]).unwrap();
+        let mut out = [0u8; MAX_MAP_BYTES];
+        let mut actions = [None; 4];
+        let mut count = 0;
+        let len = roll_back_by(
+            policy_of,
+            &StateMap::decode(&after[..after_len]).unwrap(),
+            Some(&snapshot),
+            &mut out,
+            |action, policy, entry| {
+                actions[count] = Some((action, policy, entry));
+                count += 1;
+            },
+        )
+        .unwrap();
+
+        let mut expected = [0u8; MAX_MAP_BYTES];
+        let expected_len = map(&[entry(1, 9), entry(3, 17), entry(4, 16)], &mut expected);
+        assert_eq!(&out[..len], &expected[..expected_len]);
+        assert_eq!(count, 2);
+        assert_eq!(
+            actions[0],
+            Some((
+                JournalAction::Restore,
+                POLICY_SNAPSHOT_BEFORE_UPGRADE,
+                entry(1, 9)
+            ))
+        );
+        assert_eq!(
+            actions[1],
+            Some((
+                JournalAction::Discard,
+                POLICY_DISCARD_ON_ROLLBACK,
+                entry(2, 18)
+            ))
+        );
+    }
+
+    #[test]
+    fn state_the_upgrade_created_goes_with_it_but_only_with_a_snapshot() {
+        let mut after = [0u8; MAX_MAP_BYTES];
+        let after_len = map(&[entry(1, 19)], &mut after);
+        let current = StateMap::decode(&after[..after_len]).unwrap();
+        let mut out = [0u8; MAX_MAP_BYTES];
+
+        let mut discarded = None;
+        let len = roll_back_by(
+            policy_of,
+            &current,
+            Some(&StateMap::EMPTY),
+            &mut out,
+            |action, _, entry| discarded = Some((action, entry)),
+        )
+        .unwrap();
+        assert_eq!(len, 0);
+        assert_eq!(discarded, Some((JournalAction::Discard, entry(1, 19))));
+
+        let len = roll_back_by(policy_of, &current, None, &mut out, |_, _, _| {
+            panic!("nothing says what the binding was")
+        })
+        .unwrap();
+        assert_eq!(&out[..len], current.bytes());
+    }
+
+    /// Binding 1 moves to version 2 and migrates from 1; binding 2 stays at
+    /// version 1; binding 3 moves to version 3 but migrates only from 2.
+    fn declared_of(binding: &[u8; 32]) -> Result<Option<Declared>, StateError> {
+        let declare = |schema_version, migration| {
+            Some(Declared {
+                policy: POLICY_PRESERVE,
+                schema_version,
+                migration,
+            })
+        };
+        Ok(match binding[0] {
+            1 => declare(
+                2,
+                Some(StateMigration {
+                    from_version: 1,
+                    executable: 4,
+                }),
+            ),
+            2 => declare(1, None),
+            3 => declare(
+                3,
+                Some(StateMigration {
+                    from_version: 2,
+                    executable: 5,
+                }),
+            ),
+            _ => None,
+        })
+    }
+
+    fn versioned(binding: u8, object: u8, schema_version: u32) -> StateEntry {
+        StateEntry {
+            schema_version,
+            ..entry(binding, object)
+        }
+    }
+
+    #[test]
+    fn staging_migrates_from_exactly_the_version_the_map_holds() {
+        let mut bytes = [0u8; MAX_MAP_BYTES];
+        let len = map(&[entry(1, 9), entry(2, 8), entry(4, 6)], &mut bytes);
+        let mut planned = None;
+        let count = migrations_by(
+            declared_of,
+            &StateMap::decode(&bytes[..len]).unwrap(),
+            |migration| planned = Some(migration),
+        )
+        .unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(
+            planned,
+            Some(Migration {
+                entry: entry(1, 9),
+                policy: POLICY_PRESERVE,
+                to_version: 2,
+                executable: 4,
+            })
+        );
+
+        let len = map(&[entry(3, 7)], &mut bytes);
+        assert_eq!(
+            migrations_by(
+                declared_of,
+                &StateMap::decode(&bytes[..len]).unwrap(),
+                |_| panic!("no migrator reads version 1"),
+            )
+            .unwrap_err(),
+            StateError::Unmigrated
+        );
+    }
+
+    #[test]
+    fn a_migration_replaces_only_its_own_binding() {
+        let mut bytes = [0u8; MAX_MAP_BYTES];
+        let len = map(&[entry(1, 9), entry(2, 8)], &mut bytes);
+        let current = StateMap::decode(&bytes[..len]).unwrap();
+        let mut out = [0u8; MAX_MAP_BYTES];
+
+        let out_len = apply_migrations(&current, &[versioned(1, 19, 2)], &mut out).unwrap();
+        let mut expected = [0u8; MAX_MAP_BYTES];
+        let expected_len = map(&[versioned(1, 19, 2), entry(2, 8)], &mut expected);
+        assert_eq!(&out[..out_len], &expected[..expected_len]);
+        assert_eq!(
+            apply_migrations(&current, &[versioned(3, 19, 2)], &mut out).unwrap_err(),
+            StateError::BadOrder
+        );
+    }
+
+    /// What the manager's staging does, then a rollback by a reader that
+    /// never saw the generation: stage-0 or the root, through the plain
+    /// `BootState::rollback_pending`.
+    fn staged_and_rolled_back(inherited: &[StateEntry], migrated: &[StateEntry]) -> [u8; 32] {
+        let mut before = [0u8; MAX_MAP_BYTES];
+        let before_len = map(inherited, &mut before);
+        let inherited = StateMap::decode(&before[..before_len]).unwrap();
+        let mut after = [0u8; MAX_MAP_BYTES];
+        let after_len = apply_migrations(&inherited, migrated, &mut after).unwrap();
+        let staged = StateMap::decode(&after[..after_len]).unwrap();
+        let mut rollback = [0u8; MAX_MAP_BYTES];
+        let rollback_len = roll_back_by(
+            policy_of,
+            &staged,
+            Some(&inherited),
+            &mut rollback,
+            |_, _, _| {},
+        )
+        .unwrap();
+        let rollback = StateMap::decode(&rollback[..rollback_len]).unwrap();
+
+        let known_good = BootState {
+            sequence: 1,
+            known_good: [1; 32],
+            pending: None,
+            remaining_attempts: 0,
+            generation_root: [3; 32],
+            state_root: inherited.root(),
+            accepted_release_sequence: 1,
+            trust_root: None,
+            rollback_state_root: None,
+        };
+        let pending = known_good
+            .stage_pending_with_rollback(GENERATION, 1, [3; 32], staged.root(), rollback.root())
+            .unwrap();
+        assert_eq!(pending.state_root, staged.root());
+        let pending = BootState::decode(&pending.encode().unwrap()).unwrap();
+        pending.rollback_pending().unwrap().state_root
+    }
+
+    #[test]
+    fn a_plain_rollback_restores_the_state_staging_found() {
+        let inherited = [entry(1, 9), entry(3, 7), entry(4, 6)];
+        let mut bytes = [0u8; MAX_MAP_BYTES];
+        let len = map(&inherited, &mut bytes);
+        let pre_stage = StateMap::decode(&bytes[..len]).unwrap().root();
+
+        assert_eq!(
+            staged_and_rolled_back(&inherited, &[versioned(1, 19, 2), versioned(3, 17, 2)]),
+            pre_stage,
+            "the migrated objects do not outlive the rollback",
+        );
+        assert_eq!(staged_and_rolled_back(&inherited, &[]), pre_stage);
+    }
+
+    #[test]
+    fn a_plain_rollback_drops_discarded_state() {
+        let rolled = staged_and_rolled_back(&[entry(1, 9), entry(2, 8)], &[versioned(2, 18, 2)]);
+        let mut bytes = [0u8; MAX_MAP_BYTES];
+        let len = map(&[entry(1, 9)], &mut bytes);
+        assert_eq!(rolled, StateMap::decode(&bytes[..len]).unwrap().root());
+    }
+
+    #[test]
+    fn a_rollback_returns_migrated_state_to_the_schema_it_left() {
+        let mut before = [0u8; MAX_MAP_BYTES];
+        let before_len = map(&[entry(3, 7), entry(4, 6)], &mut before);
+        let mut after = [0u8; MAX_MAP_BYTES];
+        let after_len = map(&[versioned(3, 17, 2), entry(4, 16)], &mut after);
+        let mut out = [0u8; MAX_MAP_BYTES];
+        let mut restored = None;
+        let len = roll_back_by(
+            policy_of,
+            &StateMap::decode(&after[..after_len]).unwrap(),
+            Some(&StateMap::decode(&before[..before_len]).unwrap()),
+            &mut out,
+            |action, policy, entry| restored = Some((action, policy, entry)),
+        )
+        .unwrap();
+
+        // Preserved state goes back only because its schema moved; the
+        // undeclared binding keeps what the candidate wrote.
+        let mut expected = [0u8; MAX_MAP_BYTES];
+        let expected_len = map(&[entry(3, 7), entry(4, 16)], &mut expected);
+        assert_eq!(&out[..len], &expected[..expected_len]);
+        assert_eq!(
+            restored,
+            Some((JournalAction::Restore, POLICY_PRESERVE, entry(3, 7)))
+        );
+    }
+}
@@ -1,8 +1,10 @@
 //! On-disk object-store layout (M5.4), and the superblock validator that
 //! decides whether a sector read off a real disk may be believed.
 //!
-//! The layout is pinned by `contracts/store/disk/v1/schema.zt`; every constant
-//! below is generated from it. The append/commit machinery — which sector to
+//! The layout is pinned by `contracts/store/disk/v2/schema.zt`; every constant
+//! below is generated from it. Format 1 superblocks still decode, so a reader
+//! can open a format-1 store read-only; its three differing constants are
+//! generated under a `V1_` prefix. The append/commit machinery — which sector to
 //! write next, which slot to overwrite, how to recover the previously
 //! committed root — is a *device* concern and stays with whichever kernel owns
 //! the block device.
@@ -36,6 +38,8 @@ pub enum SuperblockError {
     BadHeaderSize,
     /// The recorded CRC-32 does not cover the bytes present.
     BadCrc,
+    /// A flag bit this reader does not implement is set.
+    UnsupportedFlags,
     /// Internally consistent, but the fields contradict the partition
     /// geometry or this format's ceilings.
     BadBounds,
@@ -44,12 +48,42 @@ pub enum SuperblockError {
 /// The mutable state one committed superblock records.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Superblock {
+    /// `FORMAT_VERSION`, or `V1_FORMAT_VERSION` for a store this reader may
+    /// open but not write.
+    pub format_version: u32,
     /// Commit counter. The higher of the two slots is the live one.
     pub sequence: u64,
+    /// First LBA of the live record region. `RECORD_AREA_START` until the
+    /// first compaction, which commits wherever it copied the live records.
+    pub record_start: u64,
     /// First free LBA in the record area.
     pub append_lba: u64,
     /// Committed objects in the index.
     pub object_count: u32,
+    /// CRC-32 of the index paired with this slot. Always 0 in format 1,
+    /// which has no index.
+    pub index_crc32: u32,
+    /// Records are sealed under a key the disk does not hold. The slot's
+    /// own tag, which proves the key, is the object store's to check.
+    pub encrypted: bool,
+}
+
+/// First LBA a record may occupy in a store of `format_version`.
+pub fn record_area_start(format_version: u32) -> u64 {
+    if format_version == V1_FORMAT_VERSION {
+        V1_RECORD_AREA_START
+    } else {
+        RECORD_AREA_START
+    }
+}
+
+/// Bytes of record header in a store of `format_version`.
+pub fn record_header_len(format_version: u32) -> usize {
+    if format_version == V1_FORMAT_VERSION {
+        V1_RECORD_HEADER
+    } else {
+        RECORD_HEADER
+    }
 }
 
 fn u32_field(sector: &[u8; SECTOR_BYTES], offset: usize) -> u32 {
@@ -75,7 +109,7 @@ pub fn encode_superblock(superblock: &Superblock, partition_sectors: u64) -> [u8
     let mut sector = [0u8; SECTOR_BYTES];
     sector[..8].copy_from_slice(&SUPERBLOCK_MAGIC);
     sector[SUPERBLOCK_FORMAT_VERSION_OFFSET..SUPERBLOCK_HEADER_SIZE_OFFSET]
-        .copy_from_slice(&FORMAT_VERSION.to_le_bytes());
+        .copy_from_slice(&superblock.format_version.to_le_bytes());
     sector[SUPERBLOCK_HEADER_SIZE_OFFSET..SUPERBLOCK_SEQUENCE_OFFSET]
         .copy_from_slice(&(SUPERBLOCK_HEADER as u32).to_le_bytes());
     sector[SUPERBLOCK_SEQUENCE_OFFSET..SUPERBLOCK_APPEND_LBA_OFFSET]
@@ -84,12 +118,19 @@ pub fn encode_superblock(superblock: &Superblock, partition_sectors: u64) -> [u8
         .copy_from_slice(&superblock.append_lba.to_le_bytes());
     sector[SUPERBLOCK_OBJECT_COUNT_OFFSET..SUPERBLOCK_FLAGS_OFFSET]
         .copy_from_slice(&superblock.object_count.to_le_bytes());
+    let flags = if superblock.encrypted {
+        SUPERBLOCK_FLAG_ENCRYPTED
+    } else {
+        0
+    };
     sector[SUPERBLOCK_FLAGS_OFFSET..SUPERBLOCK_RECORD_AREA_START_OFFSET]
-        .copy_from_slice(&0u32.to_le_bytes());
+        .copy_from_slice(&flags.to_le_bytes());
     sector[SUPERBLOCK_RECORD_AREA_START_OFFSET..SUPERBLOCK_PARTITION_SECTORS_OFFSET]
-        .copy_from_slice(&RECORD_AREA_START.to_le_bytes());
-    sector[SUPERBLOCK_PARTITION_SECTORS_OFFSET..SUPERBLOCK_RESERVED_OFFSET]
+        .copy_from_slice(&superblock.record_start.to_le_bytes());
+    sector[SUPERBLOCK_PARTITION_SECTORS_OFFSET..SUPERBLOCK_INDEX_CRC32_OFFSET]
         .copy_from_slice(&partition_sectors.to_le_bytes());
+    sector[SUPERBLOCK_INDEX_CRC32_OFFSET..SUPERBLOCK_CRC32_OFFSET]
+        .copy_from_slice(&superblock.index_crc32.to_le_bytes());
     let crc = crc32(&sector[..SUPERBLOCK_CRC32_OFFSET]);
     sector[SUPERBLOCK_CRC32_OFFSET..SUPERBLOCK_HEADER].copy_from_slice(&crc.to_le_bytes());
     sector
@@ -108,7 +149,8 @@ pub fn decode_superblock(
     if sector[..8] != SUPERBLOCK_MAGIC {
         return Err(SuperblockError::BadMagic);
     }
-    if u32_field(sector, SUPERBLOCK_FORMAT_VERSION_OFFSET) != FORMAT_VERSION {
+    let format_version = u32_field(sector, SUPERBLOCK_FORMAT_VERSION_OFFSET);
+    if format_version != FORMAT_VERSION && format_version != V1_FORMAT_VERSION {
         return Err(SuperblockError::UnsupportedVersion);
     }
     if u32_field(sector, SUPERBLOCK_HEADER_SIZE_OFFSET) != SUPERBLOCK_HEADER as u32 {
@@ -120,19 +162,39 @@ pub fn decode_superblock(
     if crc32(&sector[..SUPERBLOCK_CRC32_OFFSET]) != stored_crc {
         return Err(SuperblockError::BadCrc);
     }
+    // Format 1 wrote its reserved word and its flags as zero but never
+    // checked either; neither is read back as something nothing wrote.
+    let (index_crc32, flags) = if format_version == V1_FORMAT_VERSION {
+        (0, 0)
+    } else {
+        (
+            u32_field(sector, SUPERBLOCK_INDEX_CRC32_OFFSET),
+            u32_field(sector, SUPERBLOCK_FLAGS_OFFSET),
+        )
+    };
+    if flags & !SUPERBLOCK_FLAG_ENCRYPTED != 0 {
+        return Err(SuperblockError::UnsupportedFlags);
+    }
     let superblock = Superblock {
+        format_version,
         sequence: u64_field(sector, SUPERBLOCK_SEQUENCE_OFFSET),
+        record_start: u64_field(sector, SUPERBLOCK_RECORD_AREA_START_OFFSET),
         append_lba: u64_field(sector, SUPERBLOCK_APPEND_LBA_OFFSET),
         object_count: u32_field(sector, SUPERBLOCK_OBJECT_COUNT_OFFSET),
+        index_crc32,
+        encrypted: flags == SUPERBLOCK_FLAG_ENCRYPTED,
     };
-    let record_area_start = u64_field(sector, SUPERBLOCK_RECORD_AREA_START_OFFSET);
+    let area_start = record_area_start(format_version);
     let recorded_partition = u64_field(sector, SUPERBLOCK_PARTITION_SECTORS_OFFSET);
     // `sequence == u64::MAX` is refused rather than saturated: the next commit
     // must produce a strictly higher number to win the slot comparison, and a
     // store that cannot commit again is not a store this reader should open.
-    if record_area_start != RECORD_AREA_START
+    // The live region may start anywhere in the record area a compaction put
+    // it, but never inside the slots or indexes and never after its own end.
+    if superblock.record_start < area_start
+        || superblock.record_start > superblock.append_lba
         || recorded_partition != partition_sectors
-        || superblock.append_lba < RECORD_AREA_START
+        || superblock.append_lba < area_start
         || superblock.append_lba > partition_sectors
         || superblock.object_count as usize > MAX_OBJECTS
         || superblock.sequence == u64::MAX
@@ -150,9 +212,13 @@ mod tests {
 
     fn valid() -> Superblock {
         Superblock {
+            format_version: FORMAT_VERSION,
             sequence: 7,
-            append_lba: 9,
+            record_start: RECORD_AREA_START,
+            append_lba: RECORD_AREA_START + 7,
             object_count: 3,
+            index_crc32: 0x1234_5678,
+            encrypted: false,
         }
     }
 
@@ -176,7 +242,7 @@ mod tests {
             ("magic", |s| s[0] ^= 0xFF, SuperblockError::BadMagic),
             (
                 "version",
-                |s| s[SUPERBLOCK_FORMAT_VERSION_OFFSET..][..4].copy_from_slice(&2u32.to_le_bytes()),
+                |s| s[SUPERBLOCK_FORMAT_VERSION_OFFSET..][..4].copy_from_slice(&3u32.to_le_bytes()),
                 SuperblockError::UnsupportedVersion,
             ),
             (
@@ -241,10 +307,25 @@ mod tests {
     /// `BadCrc` and prove nothing about the bounds.
     #[test]
     fn each_out_of_range_field_is_refused_on_its_own() {
-        let cases: [(&str, Superblock); 4] = [
+        let cases: [(&str, Superblock); 6] = [
+            (
+                "record_start inside the superblock slots or indexes",
+                Superblock {
+                    record_start: RECORD_AREA_START - 1,
+                    ..valid()
+                },
+            ),
+            (
+                "record_start past append_lba",
+                Superblock {
+                    record_start: RECORD_AREA_START + 8,
+                    ..valid()
+                },
+            ),
             (
                 "append_lba below the record area",
                 Superblock {
+                    record_start: RECORD_AREA_START - 1,
                     append_lba: RECORD_AREA_START - 1,
                     ..valid()
                 },
@@ -303,6 +384,13 @@ mod tests {
                     ..valid()
                 },
             ),
+            (
+                "an empty region a compaction moved past the first record LBA",
+                Superblock {
+                    record_start: RECORD_AREA_START + 7,
+                    ..valid()
+                },
+            ),
             (
                 "a full index",
                 Superblock {
@@ -327,6 +415,59 @@ mod tests {
         }
     }
 
+    /// A format-1 superblock still decodes, against format 1's record area:
+    /// its region may start at `V1_RECORD_AREA_START`, where a format-2
+    /// region would overlap the indexes, and its reserved word is not read
+    /// as an index CRC.
+    #[test]
+    fn a_format_one_superblock_decodes_with_its_own_record_area() {
+        let v1 = Superblock {
+            format_version: V1_FORMAT_VERSION,
+            record_start: V1_RECORD_AREA_START,
+            index_crc32: 0,
+            ..valid()
+        };
+        let mut sector = encode_superblock(&v1, PARTITION_SECTORS);
+        assert_eq!(decode_superblock(&sector, PARTITION_SECTORS), Ok(v1));
+
+        sector[SUPERBLOCK_INDEX_CRC32_OFFSET] = 0x5a;
+        let crc = crc32(&sector[..SUPERBLOCK_CRC32_OFFSET]);
+        sector[SUPERBLOCK_CRC32_OFFSET..SUPERBLOCK_HEADER].copy_from_slice(&crc.to_le_bytes());
+        assert_eq!(decode_superblock(&sector, PARTITION_SECTORS), Ok(v1));
+
+        let v2 = Superblock {
+            format_version: FORMAT_VERSION,
+            ..v1
+        };
+        assert_eq!(
+            decode_superblock(
+                &encode_superblock(&v2, PARTITION_SECTORS),
+                PARTITION_SECTORS
+            ),
+            Err(SuperblockError::BadBounds)
+        );
+    }
+
+    /// The encrypted flag round-trips; any other flag bit is a feature this
+    /// reader would silently ignore, so it is refused instead.
+    #[test]
+    fn only_the_encrypted_flag_is_accepted() {
+        let encrypted = Superblock {
+            encrypted: true,
+            ..valid()
+        };
+        let mut sector = encode_superblock(&encrypted, PARTITION_SECTORS);
+        assert_eq!(decode_superblock(&sector, PARTITION_SECTORS), Ok(encrypted));
+
+        sector[SUPERBLOCK_FLAGS_OFFSET] |= 0x02;
+        let crc = crc32(&sector[..SUPERBLOCK_CRC32_OFFSET]);
+        sector[SUPERBLOCK_CRC32_OFFSET..SUPERBLOCK_HEADER].copy_from_slice(&crc.to_le_bytes());
+        assert_eq!(
+            decode_superblock(&sector, PARTITION_SECTORS),
+            Err(SuperblockError::UnsupportedFlags)
+        );
+    }
+
     /// An all-zero sector — an erased or never-written slot — is refused as
     /// "not a superblock" rather than decoded as a zeroed one.
     ///
@@ -354,5 +495,17 @@ mod tests {
         assert_eq!(MAX_OBJECT_PAYLOAD % SECTOR_BYTES, 0);
         assert!(SLOT_A_LBA < RECORD_AREA_START && SLOT_B_LBA < RECORD_AREA_START);
         assert_ne!(SLOT_A_LBA, SLOT_B_LBA);
+        assert_eq!(RECORD_HEADER_CRC32_OFFSET + 4, RECORD_HEADER);
+        assert_eq!(
+            RECORD_CREATION_SEQUENCE_OFFSET, V1_RECORD_HEADER,
+            "a format-1 header is a prefix of a format-2 one"
+        );
+        assert_eq!(INDEX_ENTRY_LBA_OFFSET + 8, INDEX_ENTRY);
+        assert!(
+            INDEX_HEADER + MAX_OBJECTS * INDEX_ENTRY <= INDEX_SECTORS as usize * SECTOR_BYTES,
+            "a full index fits its region"
+        );
+        assert_eq!(INDEX_A_LBA + INDEX_SECTORS, INDEX_B_LBA);
+        assert_eq!(INDEX_B_LBA + INDEX_SECTORS, RECORD_AREA_START);
     }
 }
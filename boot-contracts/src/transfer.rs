@@ -1,7 +1,18 @@
+use crate::generation::{
+    Generation, POLICY_IMMUTABLE, POLICY_PRESERVE, POLICY_SNAPSHOT_BEFORE_UPGRADE,
+};
+use crate::release::{RELEASE_BYTES, Release};
 use crate::sha256::Sha256;
+use crate::state_policy::StateMap;
+use crate::store_disk::SECTOR_BYTES;
 
 include!("generated/transfer.rs");
 
+/// The most objects a manifest names: a generation's, then its state
+/// closure — the state map and every object the map names.
+pub const MAX_TRANSFER_OBJECTS: usize =
+    crate::generation::MAX_OBJECTS + 1 + crate::recovery::MAX_STATE_OBJECTS;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TransferError {
     Truncated,
@@ -11,6 +22,14 @@ pub enum TransferError {
     BadBounds,
     BadHash,
     BadEntry,
+    /// A carried payload whose length or content does not match its entry.
+    BadPayload,
+    /// An omitted payload the receiver does not hold, or a state object the
+    /// sender cannot read.
+    MissingObject,
+    /// The generation or release handed to the encoder does not decode, or
+    /// the release is not for that generation.
+    BadSource,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -30,6 +49,14 @@ pub struct TransferState {
     pub flags: u32,
 }
 
+/// What [`TransferManifest::verify_closure`] found: every object named was
+/// either carried and re-hashed, or omitted and already held.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Closure {
+    pub carried: usize,
+    pub held: usize,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct TransferManifest<'a> {
     bytes: &'a [u8],
@@ -70,9 +97,7 @@ impl<'a> TransferManifest<'a> {
         }
         let object_count = u32_at(bytes, HEADER_OBJECT_COUNT_OFFSET)? as usize;
         let state_count = u32_at(bytes, HEADER_STATE_COUNT_OFFSET)? as usize;
-        if object_count > crate::generation::MAX_OBJECTS
-            || state_count > crate::generation::MAX_STATES
-        {
+        if object_count > MAX_TRANSFER_OBJECTS || state_count > crate::generation::MAX_STATES {
             return Err(TransferError::BadBounds);
         }
         let object_offset = u64_at(bytes, HEADER_OBJECT_OFFSET_OFFSET)? as usize;
@@ -113,12 +138,7 @@ impl<'a> TransferManifest<'a> {
         {
             return Err(TransferError::BadBounds);
         }
-        let expected: [u8; 32] = bytes[HASH_OFFSET..HASH_END].try_into().unwrap();
-        let mut hasher = Sha256::new();
-        hasher.update(&bytes[..HASH_OFFSET]);
-        hasher.update(&[0; 32]);
-        hasher.update(&bytes[HASH_END..]);
-        if hasher.finalize() != expected {
+        if self_excluding_digest(bytes, HASH_OFFSET, HASH_END) != bytes[HASH_OFFSET..HASH_END] {
             return Err(TransferError::BadHash);
         }
         let parent: [u8; 32] = bytes[HEADER_PARENT_OFFSET..HEADER_PARENT_OFFSET + 32]
@@ -204,6 +224,37 @@ impl<'a> TransferManifest<'a> {
         })
     }
 
+    /// Verify the object closure. A carried payload must re-hash to its
+    /// digest; an omitted one must be answered for by `held`, given its digest
+    /// and length. A negotiated manifest omits what the receiver said it
+    /// holds, so this is where that answer is checked against the store
+    /// rather than believed.
+    pub fn verify_closure(
+        &self,
+        mut held: impl FnMut(&[u8; 32], usize) -> bool,
+    ) -> Result<Closure, TransferError> {
+        let mut closure = Closure {
+            carried: 0,
+            held: 0,
+        };
+        for object in self.objects() {
+            let object = object?;
+            match object.payload {
+                Some(payload) => {
+                    let mut hasher = Sha256::new();
+                    hasher.update(payload);
+                    if payload.len() != object.length || hasher.finalize() != object.digest {
+                        return Err(TransferError::BadPayload);
+                    }
+                    closure.carried += 1;
+                }
+                None if held(&object.digest, object.length) => closure.held += 1,
+                None => return Err(TransferError::MissingObject),
+            }
+        }
+        Ok(closure)
+    }
+
     pub fn state(&self, index: usize) -> Result<TransferState, TransferError> {
         if index >= self.state_count {
             return Err(TransferError::BadEntry);
@@ -237,6 +288,359 @@ impl<'a> TransferManifest<'a> {
     pub fn metadata(&self) -> &'a [u8] {
         &self.bytes[self.metadata_offset..self.metadata_offset + self.metadata_len]
     }
+
+    /// Rebuild the generation this manifest carries into `out`: the metadata,
+    /// then payloads in table order, which is how a generation lays its
+    /// objects out, until `generation_len` is reached. What follows is the
+    /// state closure, not the generation. Every generation object must be
+    /// carried, as it is in an export archive. Nothing here checks the
+    /// result; `Generation::decode` does.
+    pub fn assemble_generation<'o>(&self, out: &'o mut [u8]) -> Result<&'o [u8], TransferError> {
+        let out = out
+            .get_mut(..self.generation_len)
+            .ok_or(TransferError::Truncated)?;
+        let mut cursor = self.metadata_len;
+        out.get_mut(..cursor)
+            .ok_or(TransferError::BadBounds)?
+            .copy_from_slice(self.metadata());
+        for object in self.objects() {
+            if cursor == self.generation_len {
+                break;
+            }
+            let payload = object?.payload.ok_or(TransferError::MissingObject)?;
+            let end = cursor
+                .checked_add(payload.len())
+                .ok_or(TransferError::BadBounds)?;
+            out.get_mut(cursor..end)
+                .ok_or(TransferError::BadBounds)?
+                .copy_from_slice(payload);
+            cursor = end;
+        }
+        if cursor != self.generation_len {
+            return Err(TransferError::BadBounds);
+        }
+        Ok(out)
+    }
+
+    /// The state closure, from table index `first`: the generation's object
+    /// count. Its first entry is the map `source_state_root` names, carried,
+    /// and the rest are the objects that map names, one per entry in map
+    /// order, and nothing after them. The empty map is named without being
+    /// carried. Payload hashes are `verify_closure`'s to check.
+    pub fn state_closure(&self, first: usize) -> Result<StateMap<'a>, TransferError> {
+        if self.source_state_root == StateMap::EMPTY.root() {
+            return if first == self.object_count {
+                Ok(StateMap::EMPTY)
+            } else {
+                Err(TransferError::BadEntry)
+            };
+        }
+        let map = self.object(first)?;
+        if map.digest != self.source_state_root {
+            return Err(TransferError::BadEntry);
+        }
+        let map = StateMap::decode(map.payload.ok_or(TransferError::MissingObject)?)
+            .map_err(|_| TransferError::BadPayload)?;
+        if self.object_count != first + 1 + map.len() {
+            return Err(TransferError::BadEntry);
+        }
+        for (index, entry) in map.entries().enumerate() {
+            let object = self.object(first + 1 + index)?;
+            if object.digest != entry.object_identity {
+                return Err(TransferError::BadEntry);
+            }
+            if object.payload.is_none() {
+                return Err(TransferError::MissingObject);
+            }
+        }
+        Ok(map)
+    }
+}
+
+/// The state flags a binding of `policy` travels with, or `None` when it
+/// stays behind: immutable state read-only, preserved and snapshotted state
+/// writable. The set `build-transfer.py` selects.
+fn travelling_flags(policy: u32) -> Option<u32> {
+    match policy {
+        POLICY_IMMUTABLE => Some(STATE_FLAG_TRAVEL | STATE_FLAG_READ_ONLY),
+        POLICY_PRESERVE | POLICY_SNAPSHOT_BEFORE_UPGRADE => Some(STATE_FLAG_TRAVEL),
+        _ => None,
+    }
+}
+
+/// Encode a manifest carrying `source` and every one of its payloads into
+/// `out` and return its length, padded to whole sectors as the host builder
+/// pads. This is the export archive's manifest: nothing is taken as held.
+///
+/// `release` must be a release record for `source`. Each travelling state is
+/// named at `state`'s root, the one root BootState keeps for them all, and
+/// the manifest carries `state` and every object it names after the
+/// generation's objects. `state_object` reads one of those by digest into
+/// the buffer it is given and returns its kind and length; `state` itself
+/// must read back as the map it is.
+pub fn encode_manifest(
+    source: &[u8],
+    release: &[u8],
+    state: &StateMap<'_>,
+    mut state_object: impl FnMut(&[u8; 32], &mut [u8]) -> Option<(u32, usize)>,
+    out: &mut [u8],
+) -> Result<usize, TransferError> {
+    let generation = Generation::decode(source).map_err(|_| TransferError::BadSource)?;
+    let sequence = match Release::decode(release) {
+        Ok(release) if release.generation == generation.identity => release.sequence,
+        _ => return Err(TransferError::BadSource),
+    };
+    let mut payload_bytes = 0usize;
+    for index in 0..generation.object_count() {
+        let object = generation
+            .object(index)
+            .map_err(|_| TransferError::BadSource)?;
+        payload_bytes += object.bytes.len();
+    }
+    let mut state_count = 0;
+    for index in 0..generation.state_count() {
+        let state = generation
+            .state(index)
+            .map_err(|_| TransferError::BadSource)?;
+        state_count += usize::from(travelling_flags(state.policy).is_some());
+    }
+
+    let source_state_root = state.root();
+    let closure_count = if state.is_empty() { 0 } else { 1 + state.len() };
+    let object_count = generation.object_count() + closure_count;
+
+    let metadata_len = source.len() - payload_bytes;
+    let object_offset = HEADER_LEN;
+    let state_offset = object_offset + object_count * OBJECT_LEN;
+    let release_offset = state_offset + state_count * STATE_LEN;
+    let metadata_offset = release_offset + RELEASE_BYTES;
+    let payload_offset = metadata_offset + metadata_len;
+    // The closure's lengths are learnt as it is read, so the generation is
+    // bounded here and the whole manifest once the closure is in.
+    let limit = out.len().min(MAX_TRANSFER_BYTES);
+    if payload_offset + payload_bytes > MAX_TRANSFER_BYTES {
+        return Err(TransferError::BadBounds);
+    }
+    if payload_offset + payload_bytes > limit {
+        return Err(TransferError::Truncated);
+    }
+    out[..payload_offset].fill(0);
+
+    out[..8].copy_from_slice(&MAGIC);
+    put_u32(out, HEADER_FORMAT_VERSION_OFFSET, FORMAT_VERSION);
+    put_u32(out, HEADER_HEADER_SIZE_OFFSET, HEADER_LEN as u32);
+    out[HEADER_GENERATION_OFFSET..HEADER_GENERATION_OFFSET + 32]
+        .copy_from_slice(&generation.identity);
+    out[HEADER_PARENT_OFFSET..HEADER_PARENT_OFFSET + 32]
+        .copy_from_slice(&generation.parent.unwrap_or([0; 32]));
+    out[HEADER_SOURCE_STATE_ROOT_OFFSET..HEADER_SOURCE_STATE_ROOT_OFFSET + 32]
+        .copy_from_slice(&source_state_root);
+    out[HEADER_AUTHORITY_MANIFEST_OFFSET..HEADER_AUTHORITY_MANIFEST_OFFSET + 32]
+        .copy_from_slice(&generation.authority_manifest_identity());
+    put_u64(out, HEADER_RELEASE_SEQUENCE_OFFSET, sequence);
+    put_u64(out, HEADER_GENERATION_LEN_OFFSET, source.len() as u64);
+    put_u32(out, HEADER_OBJECT_COUNT_OFFSET, object_count as u32);
+    put_u32(out, HEADER_STATE_COUNT_OFFSET, state_count as u32);
+
+    let mut cursor = payload_offset;
+    for index in 0..generation.object_count() {
+        let object = generation
+            .object(index)
+            .map_err(|_| TransferError::BadSource)?;
+        let entry = object_offset + index * OBJECT_LEN;
+        out[entry..entry + 32].copy_from_slice(&object.digest);
+        put_u64(out, entry + OBJECT_LENGTH_OFFSET, object.bytes.len() as u64);
+        put_u64(out, entry + OBJECT_PAYLOAD_OFFSET_OFFSET, cursor as u64);
+        put_u32(out, entry + OBJECT_KIND_OFFSET, object.kind);
+        put_u32(out, entry + OBJECT_FLAGS_OFFSET, OBJECT_FLAG_PAYLOAD);
+        out[cursor..cursor + object.bytes.len()].copy_from_slice(object.bytes);
+        cursor += object.bytes.len();
+    }
+    let closure = (!state.is_empty())
+        .then_some(source_state_root)
+        .into_iter()
+        .chain(state.entries().map(|entry| entry.object_identity));
+    for (index, digest) in closure.enumerate() {
+        let (kind, len) = state_object(&digest, &mut out[cursor..limit])
+            .filter(|(_, len)| *len <= limit - cursor)
+            .ok_or(TransferError::MissingObject)?;
+        if index == 0 && out[cursor..cursor + len] != *state.bytes() {
+            return Err(TransferError::BadSource);
+        }
+        let entry = object_offset + (generation.object_count() + index) * OBJECT_LEN;
+        out[entry..entry + 32].copy_from_slice(&digest);
+        put_u64(out, entry + OBJECT_LENGTH_OFFSET, len as u64);
+        put_u64(out, entry + OBJECT_PAYLOAD_OFFSET_OFFSET, cursor as u64);
+        put_u32(out, entry + OBJECT_KIND_OFFSET, kind);
+        put_u32(out, entry + OBJECT_FLAGS_OFFSET, OBJECT_FLAG_PAYLOAD);
+        cursor += len;
+    }
+    let total_len = cursor.next_multiple_of(SECTOR_BYTES);
+    if total_len > MAX_TRANSFER_BYTES {
+        return Err(TransferError::BadBounds);
+    }
+    let out = out.get_mut(..total_len).ok_or(TransferError::Truncated)?;
+    out[cursor..].fill(0);
+    for (offset, value) in [
+        (HEADER_OBJECT_OFFSET_OFFSET, object_offset),
+        (HEADER_STATE_OFFSET_OFFSET, state_offset),
+        (HEADER_RELEASE_OFFSET_OFFSET, release_offset),
+        (HEADER_METADATA_OFFSET_OFFSET, metadata_offset),
+        (HEADER_METADATA_LEN_OFFSET, metadata_len),
+        (HEADER_PAYLOAD_OFFSET_OFFSET, payload_offset),
+        (HEADER_TOTAL_LEN_OFFSET, total_len),
+    ] {
+        put_u64(out, offset, value as u64);
+    }
+    let mut entry = state_offset;
+    for index in 0..generation.state_count() {
+        let state = generation
+            .state(index)
+            .map_err(|_| TransferError::BadSource)?;
+        let Some(flags) = travelling_flags(state.policy) else {
+            continue;
+        };
+        out[entry..entry + 32].copy_from_slice(&crate::recovery::binding_identity(state.name));
+        out[entry + STATE_STATE_ROOT_OFFSET..entry + STATE_STATE_ROOT_OFFSET + 32]
+            .copy_from_slice(&source_state_root);
+        put_u32(
+            out,
+            entry + STATE_SCHEMA_VERSION_OFFSET,
+            state.schema_version,
+        );
+        put_u32(out, entry + STATE_POLICY_OFFSET, state.policy);
+        put_u32(out, entry + STATE_FLAGS_OFFSET, flags);
+        entry += STATE_LEN;
+    }
+    out[release_offset..metadata_offset].copy_from_slice(release);
+    out[metadata_offset..payload_offset].copy_from_slice(&source[..metadata_len]);
+
+    let digest = self_excluding_digest(out, HASH_OFFSET, HASH_END);
+    out[HASH_OFFSET..HASH_END].copy_from_slice(&digest);
+    Ok(total_len)
+}
+
+/// The receiver's answer in a negotiated transfer: the object digests its
+/// store already holds, strictly ascending, sealed like a manifest.
+#[derive(Debug, Clone, Copy)]
+pub struct Holdings<'a> {
+    digests: &'a [u8],
+}
+
+impl<'a> Holdings<'a> {
+    pub fn decode(bytes: &'a [u8]) -> Result<Self, TransferError> {
+        if bytes.len() < HOLDINGS_HEADER_LEN {
+            return Err(TransferError::Truncated);
+        }
+        if bytes[..8] != HOLDINGS_MAGIC {
+            return Err(TransferError::BadMagic);
+        }
+        if u32_at(bytes, HOLDINGS_FORMAT_VERSION_OFFSET)? != FORMAT_VERSION
+            || u32_at(bytes, HOLDINGS_HEADER_SIZE_OFFSET)? as usize != HOLDINGS_HEADER_LEN
+        {
+            return Err(TransferError::UnsupportedVersion);
+        }
+        if u64_at(bytes, HOLDINGS_REQUIRED_FLAGS_OFFSET)? != 0
+            || u32_at(bytes, HOLDINGS_RESERVED_OFFSET)? != 0
+        {
+            return Err(TransferError::UnknownFlags);
+        }
+        let count = u32_at(bytes, HOLDINGS_COUNT_OFFSET)? as usize;
+        if count > MAX_HELD_DIGESTS || bytes.len() != HOLDINGS_HEADER_LEN + count * 32 {
+            return Err(TransferError::BadBounds);
+        }
+        if self_excluding_digest(bytes, HOLDINGS_HASH_OFFSET, HOLDINGS_HASH_END)
+            != bytes[HOLDINGS_HASH_OFFSET..HOLDINGS_HASH_END]
+        {
+            return Err(TransferError::BadHash);
+        }
+        let digests = &bytes[HOLDINGS_HEADER_LEN..];
+        // Strictly ascending, so `holds` can search and a digest is answered
+        // for once.
+        if digests
+            .chunks_exact(32)
+            .zip(digests.chunks_exact(32).skip(1))
+            .any(|(previous, next)| previous >= next)
+        {
+            return Err(TransferError::BadEntry);
+        }
+        Ok(Self { digests })
+    }
+
+    pub fn len(&self) -> usize {
+        self.digests.len() / 32
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.digests.is_empty()
+    }
+
+    pub fn holds(&self, digest: &[u8; 32]) -> bool {
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let middle = low + (high - low) / 2;
+            match self.digests[middle * 32..middle * 32 + 32].cmp(digest) {
+                core::cmp::Ordering::Less => low = middle + 1,
+                core::cmp::Ordering::Greater => high = middle,
+                core::cmp::Ordering::Equal => return true,
+            }
+        }
+        false
+    }
+}
+
+/// Encode a holdings answer into `out` and return its length. `held` is
+/// sorted and deduplicated in place first, so a store that keeps one content
+/// under two type tags answers for it once.
+pub fn encode_holdings(held: &mut [[u8; 32]], out: &mut [u8]) -> Result<usize, TransferError> {
+    held.sort_unstable();
+    let mut count = 0;
+    for index in 0..held.len() {
+        if index == 0 || held[index] != held[count - 1] {
+            held[count] = held[index];
+            count += 1;
+        }
+    }
+    if count > MAX_HELD_DIGESTS {
+        return Err(TransferError::BadBounds);
+    }
+    let len = HOLDINGS_HEADER_LEN + count * 32;
+    let out = out.get_mut(..len).ok_or(TransferError::Truncated)?;
+    out.fill(0);
+    out[..8].copy_from_slice(&HOLDINGS_MAGIC);
+    out[HOLDINGS_FORMAT_VERSION_OFFSET..HOLDINGS_FORMAT_VERSION_OFFSET + 4]
+        .copy_from_slice(&FORMAT_VERSION.to_le_bytes());
+    out[HOLDINGS_HEADER_SIZE_OFFSET..HOLDINGS_HEADER_SIZE_OFFSET + 4]
+        .copy_from_slice(&(HOLDINGS_HEADER_LEN as u32).to_le_bytes());
+    out[HOLDINGS_COUNT_OFFSET..HOLDINGS_COUNT_OFFSET + 4]
+        .copy_from_slice(&(count as u32).to_le_bytes());
+    for (slot, digest) in out[HOLDINGS_HEADER_LEN..]
+        .chunks_exact_mut(32)
+        .zip(&held[..count])
+    {
+        slot.copy_from_slice(digest);
+    }
+    let digest = self_excluding_digest(out, HOLDINGS_HASH_OFFSET, HOLDINGS_HASH_END);
+    out[HOLDINGS_HASH_OFFSET..HOLDINGS_HASH_END].copy_from_slice(&digest);
+    Ok(len)
+}
+
+/// SHA-256 over `bytes` with the hash field itself read as zeros, which is
+/// how both records seal themselves.
+fn self_excluding_digest(bytes: &[u8], offset: usize, end: usize) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(&bytes[..offset]);
+    hasher.update(&[0; 32]);
+    hasher.update(&bytes[end..]);
+    hasher.finalize()
+}
+
+fn put_u32(bytes: &mut [u8], offset: usize, value: u32) {
+    bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+fn put_u64(bytes: &mut [u8], offset: usize, value: u64) {
+    bytes[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
 }
 
 fn u32_at(bytes: &[u8], offset: usize) -> Result<u32, TransferError> {
@@ -504,15 +908,12 @@ mod tests {
         }
     }
 
-    /// A count past the generation's own ceiling is refused at the header,
-    /// before any per-entry offset is computed from it.
+    /// A count past the generation's own ceiling, and its state closure's, is
+    /// refused at the header, before any per-entry offset is computed from it.
     #[test]
     fn a_count_over_the_generation_ceiling_is_out_of_bounds() {
         for (offset, over) in [
-            (
-                HEADER_OBJECT_COUNT_OFFSET,
-                crate::generation::MAX_OBJECTS + 1,
-            ),
+            (HEADER_OBJECT_COUNT_OFFSET, MAX_TRANSFER_OBJECTS + 1),
             (HEADER_STATE_COUNT_OFFSET, crate::generation::MAX_STATES + 1),
         ] {
             let mut bytes = valid();
@@ -598,6 +999,127 @@ mod tests {
         }
     }
 
+    /// `valid()` with its one object's digest made true, so the carried payload
+    /// re-hashes to it.
+    fn hashed() -> alloc::vec::Vec<u8> {
+        let mut bytes = valid();
+        let mut hasher = Sha256::new();
+        hasher.update(b"object-payload");
+        bytes[HEADER_LEN..HEADER_LEN + 32].copy_from_slice(&hasher.finalize());
+        seal(&mut bytes);
+        bytes
+    }
+
+    /// A carried payload is checked against its digest and never asks the
+    /// store; an omitted one is the store's to answer for, and a store that
+    /// does not hold it fails the closure.
+    #[test]
+    fn a_closure_carries_verified_payloads_and_omits_only_held_ones() {
+        let bytes = hashed();
+        let manifest = TransferManifest::decode(&bytes).expect("valid");
+        assert_eq!(
+            manifest.verify_closure(|_, _| panic!("a carried object was looked up")),
+            Ok(Closure {
+                carried: 1,
+                held: 0
+            }),
+        );
+
+        let mut bytes = hashed();
+        let object = HEADER_LEN;
+        bytes[object + OBJECT_PAYLOAD_OFFSET_OFFSET..object + OBJECT_PAYLOAD_OFFSET_OFFSET + 8]
+            .fill(0);
+        bytes[object + OBJECT_FLAGS_OFFSET..object + OBJECT_FLAGS_OFFSET + 4].fill(0);
+        seal(&mut bytes);
+        let manifest = TransferManifest::decode(&bytes).expect("valid");
+        let digest = manifest.object(0).expect("object 0").digest;
+        assert_eq!(
+            manifest.verify_closure(|held, length| *held == digest && length == 14),
+            Ok(Closure {
+                carried: 0,
+                held: 1
+            }),
+        );
+        assert_eq!(
+            manifest.verify_closure(|_, _| false),
+            Err(TransferError::MissingObject)
+        );
+    }
+
+    #[test]
+    fn a_carried_payload_that_does_not_hash_to_its_digest_is_refused() {
+        let bytes = valid();
+        assert_eq!(
+            TransferManifest::decode(&bytes)
+                .expect("valid")
+                .verify_closure(|_, _| true),
+            Err(TransferError::BadPayload),
+        );
+    }
+
+    /// The answer is sorted and deduplicated on the way out, so a decoder can
+    /// insist on strict order and still accept anything the encoder wrote.
+    #[test]
+    fn holdings_round_trip_sorted_and_deduplicated() {
+        let mut held = [[0x30; 32], [0x10; 32], [0x30; 32], [0x20; 32]];
+        let mut out = [0u8; HOLDINGS_HEADER_LEN + 4 * 32];
+        let len = encode_holdings(&mut held, &mut out).expect("encodes");
+        assert_eq!(len, HOLDINGS_HEADER_LEN + 3 * 32);
+        let holdings = Holdings::decode(&out[..len]).expect("decodes");
+        assert_eq!(holdings.len(), 3);
+        for digest in [[0x10; 32], [0x20; 32], [0x30; 32]] {
+            assert!(holdings.holds(&digest));
+        }
+        assert!(!holdings.holds(&[0x25; 32]));
+
+        let len = encode_holdings(&mut [], &mut out).expect("encodes");
+        assert!(Holdings::decode(&out[..len]).expect("decodes").is_empty());
+    }
+
+    #[test]
+    fn damaged_holdings_are_refused_with_their_own_error() {
+        let mut held = [[0x10; 32], [0x20; 32]];
+        let mut valid = [0u8; HOLDINGS_HEADER_LEN + 2 * 32];
+        let len = encode_holdings(&mut held, &mut valid).expect("encodes");
+        let reseal = |bytes: &mut [u8]| {
+            let digest = self_excluding_digest(bytes, HOLDINGS_HASH_OFFSET, HOLDINGS_HASH_END);
+            bytes[HOLDINGS_HASH_OFFSET..HOLDINGS_HASH_END].copy_from_slice(&digest);
+        };
+
+        let mut bytes = valid;
+        bytes[len - 1] ^= 1;
+        assert_eq!(Holdings::decode(&bytes).err(), Some(TransferError::BadHash));
+        assert_eq!(
+            Holdings::decode(&valid[..len - 32]).err(),
+            Some(TransferError::BadBounds)
+        );
+
+        let mut bytes = valid;
+        bytes[HOLDINGS_RESERVED_OFFSET] = 1;
+        reseal(&mut bytes);
+        assert_eq!(
+            Holdings::decode(&bytes).err(),
+            Some(TransferError::UnknownFlags)
+        );
+
+        let mut bytes = valid;
+        bytes[HOLDINGS_HEADER_LEN..HOLDINGS_HEADER_LEN + 32].fill(0x20);
+        reseal(&mut bytes);
+        assert_eq!(
+            Holdings::decode(&bytes).err(),
+            Some(TransferError::BadEntry)
+        );
+
+        let mut over = [[0u8; 32]; MAX_HELD_DIGESTS + 1];
+        for (index, digest) in over.iter_mut().enumerate() {
+            digest[0] = index as u8;
+        }
+        assert_eq!(
+            encode_holdings(&mut over, &mut [0u8; 4096]),
+            Err(TransferError::BadBounds)
+        );
+    }
+
     /// Per-entry padding is reserved for the same reason the header's is.
     #[test]
     fn nonzero_entry_padding_is_a_bad_entry() {
@@ -622,4 +1144,66 @@ mod tests {
             Err(TransferError::BadEntry),
         );
     }
+
+    /// The metadata and then each payload in table order is the generation
+    /// back; a length that disagrees, or an omitted payload, is not.
+    #[test]
+    fn a_carried_generation_reassembles_in_table_order() {
+        let rebuilt_len = b"transfer-metadata".len() + b"object-payload".len();
+        let mut bytes = valid();
+        bytes[HEADER_GENERATION_LEN_OFFSET..HEADER_GENERATION_LEN_OFFSET + 8]
+            .copy_from_slice(&(rebuilt_len as u64).to_le_bytes());
+        seal(&mut bytes);
+        let mut out = [0u8; 64];
+        assert_eq!(
+            TransferManifest::decode(&bytes)
+                .expect("valid")
+                .assemble_generation(&mut out),
+            Ok(&b"transfer-metadataobject-payload"[..])
+        );
+        assert_eq!(
+            TransferManifest::decode(&bytes)
+                .expect("valid")
+                .assemble_generation(&mut out[..rebuilt_len - 1]),
+            Err(TransferError::Truncated)
+        );
+
+        let bytes = valid();
+        assert_eq!(
+            TransferManifest::decode(&bytes)
+                .expect("valid")
+                .assemble_generation(&mut out),
+            Err(TransferError::BadBounds)
+        );
+
+        let mut bytes = valid();
+        let object = HEADER_LEN;
+        bytes[object + OBJECT_PAYLOAD_OFFSET_OFFSET..object + OBJECT_PAYLOAD_OFFSET_OFFSET + 8]
+            .fill(0);
+        bytes[object + OBJECT_FLAGS_OFFSET..object + OBJECT_FLAGS_OFFSET + 4].fill(0);
+        seal(&mut bytes);
+        assert_eq!(
+            TransferManifest::decode(&bytes)
+                .expect("valid")
+                .assemble_generation(&mut out),
+            Err(TransferError::MissingObject)
+        );
+    }
+
+    /// The encoder takes a generation and its release, and nothing that only
+    /// resembles them.
+    #[test]
+    fn the_encoder_refuses_a_source_that_is_not_a_generation() {
+        let mut out = [0u8; 4096];
+        assert_eq!(
+            encode_manifest(
+                b"not a generation",
+                &[0; RELEASE_BYTES],
+                &StateMap::EMPTY,
+                |_, _| None,
+                &mut out
+            ),
+            Err(TransferError::BadSource)
+        );
+    }
 }
@@ -31,20 +31,28 @@ fn main() {
     }
     println!("cargo:rerun-if-env-changed=SLIME_FABRIC_PROXY_EARLY_EXIT");
     println!("cargo:rerun-if-env-changed=SLIME_FABRIC_STREAM_EARLY_EXIT");
+    println!("cargo:rerun-if-env-changed=SLIME_FABRIC_CALL_SERVER_EARLY_EXIT");
     println!("cargo:rerun-if-env-changed=SLIME_GENERATION_CANDIDATE");
     println!("cargo:rerun-if-env-changed=SLIME_GENERATION_CMD_SCENARIO");
+    println!("cargo:rerun-if-env-changed=SLIME_GENERATION_ROTATION");
     if let Ok(value) = std::env::var("SLIME_FABRIC_PROXY_EARLY_EXIT") {
         println!("cargo:rustc-env=SLIME_FABRIC_PROXY_EARLY_EXIT={value}");
     }
     if let Ok(value) = std::env::var("SLIME_FABRIC_STREAM_EARLY_EXIT") {
         println!("cargo:rustc-env=SLIME_FABRIC_STREAM_EARLY_EXIT={value}");
     }
+    if let Ok(value) = std::env::var("SLIME_FABRIC_CALL_SERVER_EARLY_EXIT") {
+        println!("cargo:rustc-env=SLIME_FABRIC_CALL_SERVER_EARLY_EXIT={value}");
+    }
     if let Ok(value) = std::env::var("SLIME_GENERATION_CANDIDATE") {
         println!("cargo:rustc-env=SLIME_GENERATION_CANDIDATE={value}");
     }
     if let Ok(value) = std::env::var("SLIME_GENERATION_CMD_SCENARIO") {
         println!("cargo:rustc-env=SLIME_GENERATION_CMD_SCENARIO={value}");
     }
+    if let Ok(value) = std::env::var("SLIME_GENERATION_ROTATION") {
+        println!("cargo:rustc-env=SLIME_GENERATION_ROTATION={value}");
+    }
     generate_command_profile(manifest_dir);
     generate_fabric_profile(manifest_dir);
 }
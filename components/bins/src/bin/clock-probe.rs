@@ -0,0 +1,105 @@
+//! The clock plane's subject: the root's three clock operations.
+//!
+//! Holds one grant of each clock kind and asks the root about each in turn,
+//! so `CLOCK NOW`, `CLOCK WAIT` and `CLOCK ADVANCE` are each answered on a
+//! boot rather than only in the root's host tests. Four claims:
+//!
+//! * the monotonic clock moves forward, and a wait on it is reached once its
+//!   deadline has passed;
+//! * the wall clock reads a date after 2020, so the PL031 anchor reached it,
+//!   and it takes no waits;
+//! * the simulated clock stays at zero until this component advances it, the
+//!   advance releases the wait recorded on it, and it cannot go backwards;
+//! * a slot with no clock grant is refused, and so is an advance through a
+//!   grant that does not carry `clockAdvance`.
+
+#![no_std]
+#![no_main]
+
+slime_rt::entry!(main);
+
+const MONOTONIC_SLOT: u32 = 0;
+const WALL_SLOT: u32 = 1;
+/// Granted with `clockAdvance`.
+const SIMULATED_SLOT: u32 = 2;
+/// Inside the table's bounds, and granted nothing.
+const EMPTY_SLOT: u32 = 3;
+
+/// How far past the first monotonic reading the wait's deadline sits.
+const MONOTONIC_WAIT_NS: u64 = 1_000_000;
+/// 2020-01-01T00:00:00Z. QEMU's PL031 starts at the host's date, so a reading
+/// before it is an anchor that never happened or one read wrong.
+const WALL_FLOOR_NS: u64 = 1_577_836_800 * 1_000_000_000;
+/// Where the simulated clock is advanced to.
+const SIMULATED_DEADLINE_NS: u64 = 1_000;
+
+fn main(_startup_arg: u32) {
+    slime_rt::debug_write(b"[clock-probe] running\n");
+
+    // Refusals first, so a root that ignored the capability could not pass
+    // the arms below by accident.
+    if slime_rt::clock_now(EMPTY_SLOT).is_ok() {
+        fail(b"an ungranted slot answered");
+    }
+    if slime_rt::clock_advance(MONOTONIC_SLOT, u64::MAX).is_ok() {
+        fail(b"a monotonic clock was advanced");
+    }
+    slime_rt::debug_write(b"[clock-probe] ungranted slot and advance refused\n");
+
+    let first = now(MONOTONIC_SLOT, b"monotonic read");
+    if now(MONOTONIC_SLOT, b"monotonic read") < first {
+        fail(b"the monotonic clock went backwards");
+    }
+    let deadline = first.saturating_add(MONOTONIC_WAIT_NS);
+    match slime_rt::clock_sleep_until(MONOTONIC_SLOT, deadline) {
+        Ok(reached) if reached >= deadline => {}
+        Ok(_) => fail(b"a monotonic wait was reached early"),
+        Err(_) => fail(b"monotonic wait"),
+    }
+    slime_rt::debug_write(b"[clock-probe] monotonic wait reached\n");
+
+    if now(WALL_SLOT, b"wall read") < WALL_FLOOR_NS {
+        fail(b"the wall clock reads before 2020");
+    }
+    if slime_rt::clock_wait(WALL_SLOT, 0).is_ok() {
+        fail(b"the wall clock took a wait");
+    }
+    slime_rt::debug_write(b"[clock-probe] wall clock anchored and not waitable\n");
+
+    if now(SIMULATED_SLOT, b"simulated read") != 0 {
+        fail(b"the simulated clock moved on its own");
+    }
+    match slime_rt::clock_wait(SIMULATED_SLOT, SIMULATED_DEADLINE_NS) {
+        Ok(None) => {}
+        Ok(Some(_)) => fail(b"a simulated wait was reached before any advance"),
+        Err(_) => fail(b"simulated wait"),
+    }
+    slime_rt::debug_write(b"[clock-probe] simulated wait pending at zero\n");
+    match slime_rt::clock_advance(SIMULATED_SLOT, SIMULATED_DEADLINE_NS) {
+        Ok(1) => {}
+        Ok(_) => fail(b"the advance released the wrong number of waits"),
+        Err(_) => fail(b"simulated advance"),
+    }
+    slime_rt::debug_write(b"[clock-probe] simulated advance released=1\n");
+    match slime_rt::clock_wait(SIMULATED_SLOT, SIMULATED_DEADLINE_NS) {
+        Ok(Some(SIMULATED_DEADLINE_NS)) => {}
+        _ => fail(b"the advanced wait was not reached"),
+    }
+    if slime_rt::clock_advance(SIMULATED_SLOT, SIMULATED_DEADLINE_NS - 1).is_ok() {
+        fail(b"the simulated clock went backwards");
+    }
+    slime_rt::debug_write(b"[clock-probe] simulated wait reached now_ns=1000\n");
+
+    slime_rt::debug_write(b"[clock-probe] clock plane complete\n");
+}
+
+fn now(slot: u32, what: &[u8]) -> u64 {
+    slime_rt::clock_now(slot).unwrap_or_else(|_| fail(what))
+}
+
+fn fail(reason: &[u8]) -> ! {
+    slime_rt::debug_write(b"[clock-probe] fail: ");
+    slime_rt::debug_write(reason);
+    slime_rt::debug_write(b"\n");
+    slime_rt::exit(1)
+}
@@ -16,7 +16,7 @@ use slime_rt::{
 };
 
 // B59: rights bit numbering is generated from
-// `contracts/generation/v5/schema.zt`. The powerbox/fs protocols carry a
+// `contracts/generation/v6/schema.zt`. The powerbox/fs protocols carry a
 // 32-bit rights field, so the generated `u64` constants are narrowed here
 // rather than re-spelled as separate `u32` literals.
 const RIGHT_TRANSFER: u32 = boot_contracts::generation::RIGHT_TRANSFER as u32;
@@ -209,7 +209,8 @@ fn spawn(launch: &Launch<'_>) -> WireSpawnReply {
         arguments: launch.arguments.bytes,
         environment: launch.environment.bytes,
         grant_rights: 0,
-        reserved: [0; 6],
+        scheduling_class: 0,
+        reserved: [0; 5],
     };
     let reply = send_request(request, &caps[..cap_count]);
     if reply.status == 0
@@ -343,7 +344,8 @@ fn shutdown_service() {
         arguments: [0; 8],
         environment: [0; 8],
         grant_rights: 0,
-        reserved: [0; 6],
+        scheduling_class: 0,
+        reserved: [0; 5],
     };
     let encoded = request.encode();
     loop {
@@ -1,13 +1,17 @@
 #![no_std]
 #![no_main]
 
+use boot_contracts::sha256::Sha256;
 use slime_proto::{
-    capability_transfer::OBJECT_KIND_DIRECTORY,
+    capability_transfer::{OBJECT_KIND_DIRECTORY, OBJECT_KIND_SHARED_BUFFER_LOAN},
     fs::{self, WireFsReply, WireFsRequest},
 };
-use slime_rt::{CapabilityDisposition, ERR_BAD_CAP, ERR_WOULDBLOCK, MAX_CAPS_PER_MSG, MAX_MSG};
+use slime_rt::{
+    CapabilityDisposition, Delegation, ERR_BAD_CAP, ERR_SUCCESS, ERR_WOULDBLOCK, MAX_CAPS_PER_MSG,
+    MAX_MSG,
+};
 // B59: rights bit numbering is generated from
-// `contracts/generation/v5/schema.zt`. The powerbox/fs protocols carry a
+// `contracts/generation/v6/schema.zt`. The powerbox/fs protocols carry a
 // 32-bit rights field, so the generated `u64` constants are narrowed at the
 // declaration rather than re-spelled as separate `u32` literals.
 const RIGHT_TRANSFER: u32 = boot_contracts::generation::RIGHT_TRANSFER as u32;
@@ -15,17 +19,35 @@ const RIGHT_DIRECTORY_WRITE: u32 = boot_contracts::generation::RIGHT_DIRECTORY_W
 const RIGHT_DIRECTORY_DERIVE: u32 = boot_contracts::generation::RIGHT_DIRECTORY_DERIVE as u32;
 const RIGHT_DIRECTORY_READ: u32 = boot_contracts::generation::RIGHT_DIRECTORY_READ as u32;
 const RIGHT_DIRECTORY_LIST: u32 = boot_contracts::generation::RIGHT_DIRECTORY_LIST as u32;
+const RIGHT_BUFFER_MAP: u64 = boot_contracts::generation::RIGHT_BUFFER_MAP;
 
 slime_rt::entry!(main);
 
 const RPC_SLOT: u32 = 0;
 const DIRECTORY_SLOT: u32 = 1;
+/// This probe's own `SharedBufferFactory`, for the bytes a range write lends.
+const FACTORY_SLOT: u32 = 2;
+const PAGE: u64 = 4096;
+/// Where a range write's buffer is mapped while it is filled.
+const BUFFER_BASE: u64 = 0x0000_0018_0000_0000;
+/// Where a range read's loan is mapped while it is checked.
+const LOAN_BASE: u64 = 0x0000_0019_0000_0000;
 const PAYLOAD_HASH: [u8; 32] = [
     0x80, 0xe6, 0xbb, 0x6b, 0x33, 0x8c, 0x72, 0xd3, 0xdd, 0x0f, 0xdc, 0x6d, 0x94, 0x25, 0x70, 0x4b,
     0xa6, 0xa0, 0x3f, 0x8d, 0x0c, 0xd8, 0x19, 0x47, 0x0c, 0xf1, 0x04, 0xc6, 0x57, 0x2e, 0x53, 0xd6,
 ];
 const PAYLOAD_LEN: u32 = 30;
 const ZERO_HASH: [u8; 32] = [0; 32];
+const DEEP_NAME_LEN: usize = 26;
+const DEEP_PATH: &[u8] = b"src/pkg/include/slime/detail/a-name-v1-could-not-hold.h";
+/// One more than a v1 snapshot's entire capacity.
+const MANY_ENTRIES: usize = fs::V1_MAX_ENTRIES + 1;
+/// Where the range write lands: inside the 30-byte file, so the write both
+/// overwrites and extends it.
+const RANGE_OFFSET: u64 = 10;
+/// Larger than a message many times over and across a page boundary, so the
+/// bytes can only have crossed as a loan.
+const RANGE_LEN: u32 = 6000;
 /// The oracle's own client, unmodified in the sense the gate checks: no
 /// compile-time product selector, no seL4 branch.
 ///
@@ -41,39 +63,155 @@ fn main(_startup_arg: u32) {
     }
     slime_rt::debug_write(b"[directory-probe] no-cap denied\n");
 
-    let (initial, _) = call(request(fs::OP_READ, b"note", 0, [0; 32]), DIRECTORY_SLOT);
+    let (initial, _) = call(request(fs::OP_READ, 0, ZERO_HASH), DIRECTORY_SLOT, b"note");
     if initial.status != 0 || reply_hash(initial) != PAYLOAD_HASH {
         fail();
     }
 
     let interrupted = call(
-        request(fs::OP_WRITE, b"orphan.txt", PAYLOAD_LEN, ZERO_HASH),
+        request(fs::OP_WRITE, PAYLOAD_LEN, ZERO_HASH),
         DIRECTORY_SLOT,
+        b"orphan.txt",
     )
     .0;
     if interrupted.status == 0 {
         fail();
     }
-    let (still_readable, _) = call(request(fs::OP_READ, b"note", 0, ZERO_HASH), DIRECTORY_SLOT);
+    let (still_readable, _) = call(request(fs::OP_READ, 0, ZERO_HASH), DIRECTORY_SLOT, b"note");
     if still_readable.status != 0 || reply_hash(still_readable) != PAYLOAD_HASH {
         fail();
     }
     slime_rt::debug_write(b"[directory-probe] interrupted transition preserved root\n");
 
     let (write, _) = call(
-        request(fs::OP_WRITE, b"new.txt", PAYLOAD_LEN, PAYLOAD_HASH),
+        request(fs::OP_WRITE, PAYLOAD_LEN, PAYLOAD_HASH),
         DIRECTORY_SLOT,
+        b"new.txt",
     );
     if write.status != 0 {
         fail();
     }
-    let (read, _) = call(request(fs::OP_READ, b"new.txt", 0, [0; 32]), DIRECTORY_SLOT);
+    let (read, _) = call(
+        request(fs::OP_READ, 0, ZERO_HASH),
+        DIRECTORY_SLOT,
+        b"new.txt",
+    );
     if read.status != 0 || reply_hash(read) != PAYLOAD_HASH || read.payload_len != PAYLOAD_LEN {
         fail();
     }
     slime_rt::debug_write(b"[directory-probe] root transition committed\n");
 
-    let (derived, derived_slot) = call(request(fs::OP_DERIVE, b"docs", 0, [0; 32]), DIRECTORY_SLOT);
+    // fs/v2's bounds, each past v1's: a path five directories deep that did not
+    // exist, ending in a name longer than v1's sixteen bytes. The write creates
+    // every missing parent on the way down.
+    let (deep, _) = call(
+        request(fs::OP_WRITE, PAYLOAD_LEN, PAYLOAD_HASH),
+        DIRECTORY_SLOT,
+        DEEP_PATH,
+    );
+    let (deep_read, _) = call(
+        request(fs::OP_READ, 0, ZERO_HASH),
+        DIRECTORY_SLOT,
+        DEEP_PATH,
+    );
+    let (deep_list, _) = call(
+        request(fs::OP_LIST, 0, ZERO_HASH),
+        DIRECTORY_SLOT,
+        &DEEP_PATH[..DEEP_PATH.len() - DEEP_NAME_LEN - 1],
+    );
+    if deep.status != 0
+        || deep_read.status != 0
+        || reply_hash(deep_read) != PAYLOAD_HASH
+        || deep_list.status != 0
+        || deep_list.entry_count != 1
+    {
+        fail();
+    }
+    slime_rt::debug_write(b"[directory-probe] deep path written\n");
+
+    // More entries in one directory than a v1 snapshot could hold at all.
+    let mut path = *b"many/entry-00";
+    for index in 0..MANY_ENTRIES {
+        path[11] = b'0' + (index / 10) as u8;
+        path[12] = b'0' + (index % 10) as u8;
+        let (written, _) = call(
+            request(fs::OP_WRITE, PAYLOAD_LEN, PAYLOAD_HASH),
+            DIRECTORY_SLOT,
+            &path,
+        );
+        if written.status != 0 || written.entry_count != index as u32 + 1 {
+            fail();
+        }
+    }
+    let (many, _) = call(request(fs::OP_LIST, 0, ZERO_HASH), DIRECTORY_SLOT, b"many");
+    let (last, _) = call(request(fs::OP_READ, 0, ZERO_HASH), DIRECTORY_SLOT, &path);
+    if many.status != 0
+        || many.entry_count != MANY_ENTRIES as u32
+        || last.status != 0
+        || reply_hash(last) != PAYLOAD_HASH
+    {
+        fail();
+    }
+    slime_rt::debug_write(b"[directory-probe] large directory listed\n");
+
+    // A range edit of a file of its own, so the arms below still find
+    // `new.txt` exactly as the write above left it.
+    let (range_file, _) = call(
+        request(fs::OP_WRITE, PAYLOAD_LEN, PAYLOAD_HASH),
+        DIRECTORY_SLOT,
+        b"range.txt",
+    );
+    if range_file.status != 0 {
+        fail();
+    }
+    // A loan whose bytes are not the ones the request names is refused before
+    // anything is stored.
+    let mut mismatched = request(fs::OP_WRITE_RANGE, RANGE_LEN, PAYLOAD_HASH);
+    mismatched.offset = RANGE_OFFSET;
+    if call_with_loan(mismatched, b"range.txt", range_loan()).status != -1 {
+        fail();
+    }
+    let mut range_write = request(fs::OP_WRITE_RANGE, RANGE_LEN, range_hash());
+    range_write.offset = RANGE_OFFSET;
+    let edited = call_with_loan(range_write, b"range.txt", range_loan());
+    if edited.status != 0
+        || edited.payload_len != RANGE_OFFSET as u32 + RANGE_LEN
+        || reply_hash(edited) == PAYLOAD_HASH
+    {
+        fail();
+    }
+    let mut range_read = request(fs::OP_READ_RANGE, RANGE_LEN, ZERO_HASH);
+    range_read.offset = RANGE_OFFSET;
+    let (read_back, loan) = call(range_read, DIRECTORY_SLOT, b"range.txt");
+    let loan = match loan {
+        Some(loan) => loan,
+        None => fail(),
+    };
+    if read_back.status != 0
+        || read_back.payload_len != RANGE_LEN
+        || reply_hash(read_back) != reply_hash(edited)
+        || !loan_holds_range(loan)
+    {
+        fail();
+    }
+    // The file now ends where the write did: a range there is empty and lends
+    // nothing, and one past it is refused.
+    let mut at_end = request(fs::OP_READ_RANGE, 1, ZERO_HASH);
+    at_end.offset = RANGE_OFFSET + u64::from(RANGE_LEN);
+    let (empty, no_loan) = call(at_end, DIRECTORY_SLOT, b"range.txt");
+    let mut past_end = at_end;
+    past_end.offset += 1;
+    let (refused, _) = call(past_end, DIRECTORY_SLOT, b"range.txt");
+    if empty.status != 0 || empty.payload_len != 0 || no_loan.is_some() || refused.status != -1 {
+        fail();
+    }
+    slime_rt::debug_write(b"[directory-probe] byte range round-tripped\n");
+
+    let (derived, derived_slot) = call(
+        request(fs::OP_DERIVE, 0, ZERO_HASH),
+        DIRECTORY_SLOT,
+        b"docs",
+    );
     let derived_slot = match derived_slot {
         Some(slot) => slot,
         None => fail(),
@@ -91,12 +229,14 @@ fn main(_startup_arg: u32) {
         fail();
     }
     slime_rt::debug_write(b"[directory-probe] derive narrowed\n");
-    let (scoped_read, _) = call(request(fs::OP_READ, b"note", 0, [0; 32]), derived_slot);
+    // `docs` is still the fixture's v1 snapshot: no write has gone through it,
+    // so this read resolves a v1 directory under a v2 root.
+    let (scoped_read, _) = call(request(fs::OP_READ, 0, ZERO_HASH), derived_slot, b"note");
     if scoped_read.status != 0 || reply_hash(scoped_read) != PAYLOAD_HASH {
         fail();
     }
     slime_rt::debug_write(b"[directory-probe] scoped read ok\n");
-    let (outside_scope, _) = call(request(fs::OP_READ, b"new.txt", 0, [0; 32]), derived_slot);
+    let (outside_scope, _) = call(request(fs::OP_READ, 0, ZERO_HASH), derived_slot, b"new.txt");
     if outside_scope.status != -3 {
         fail();
     }
@@ -105,8 +245,9 @@ fn main(_startup_arg: u32) {
     // out of it to hand over. That is a stronger denial than the service's own
     // `-2` — the request never crosses — and it is the mechanism's, not policy's.
     if try_call(
-        request(fs::OP_WRITE, b"blocked.txt", PAYLOAD_LEN, PAYLOAD_HASH),
+        request(fs::OP_WRITE, PAYLOAD_LEN, PAYLOAD_HASH),
         derived_slot,
+        b"blocked.txt",
     )
     .is_some_and(|(reply, _)| reply.status != -2)
     {
@@ -115,10 +256,105 @@ fn main(_startup_arg: u32) {
     let _ = slime_rt::cap_drop(derived_slot);
     slime_rt::debug_write(b"[directory-probe] scoped boundary enforced\n");
 
-    let (malformed, _) = call(
-        request(fs::OP_READ, b"bad/name", 0, [0; 32]),
+    // A snapshot pins the live root as a read-only view: a later write is
+    // invisible through it, and nothing can be committed through it.
+    let (snapshot, snapshot_slot) =
+        call(request(fs::OP_SNAPSHOT, 0, ZERO_HASH), DIRECTORY_SLOT, b"");
+    let snapshot_slot = match snapshot_slot {
+        Some(slot) => slot,
+        None => fail(),
+    };
+    let pinned = reply_hash(snapshot);
+    let mut inspected = [0u8; 32];
+    if snapshot.status != 0
+        || slime_rt::directory_inspect(
+            snapshot_slot,
+            RIGHT_DIRECTORY_READ,
+            &mut inspected,
+            &mut [0; slime_rt::MAX_DIRECTORY_PATH],
+        )
+        .is_err()
+        || inspected != pinned
+        || slime_rt::directory_commit(snapshot_slot, &pinned, &pinned) != ERR_BAD_CAP
+    {
+        fail();
+    }
+    let (later, _) = call(
+        request(fs::OP_WRITE, PAYLOAD_LEN, PAYLOAD_HASH),
+        DIRECTORY_SLOT,
+        b"later.txt",
+    );
+    if later.status != 0 {
+        fail();
+    }
+    let (unseen, _) = call(
+        request(fs::OP_READ, 0, ZERO_HASH),
+        snapshot_slot,
+        b"later.txt",
+    );
+    let (pinned_read, _) = call(
+        request(fs::OP_READ, 0, ZERO_HASH),
+        snapshot_slot,
+        b"new.txt",
+    );
+    if unseen.status != -3 || pinned_read.status != 0 {
+        fail();
+    }
+    if try_call(
+        request(fs::OP_WRITE, PAYLOAD_LEN, PAYLOAD_HASH),
+        snapshot_slot,
+        b"blocked.txt",
+    )
+    .is_some_and(|(reply, _)| reply.status != -2)
+    {
+        fail();
+    }
+    slime_rt::debug_write(b"[directory-probe] snapshot pinned\n");
+
+    // A root's hash is not authority to install it: a restore that does not
+    // carry the pin is refused, however real the tree it names.
+    let (unpinned, _) = call(request(fs::OP_RESTORE, 0, pinned), DIRECTORY_SLOT, b"");
+    if unpinned.status != -2 {
+        fail();
+    }
+    // Restore swaps the live root back to the pin through the same atomic
+    // commit a write uses, so the later write is gone and the earlier tree is
+    // whole.
+    let restore = call_with_pin(request(fs::OP_RESTORE, 0, pinned), snapshot_slot);
+    if restore.status != 0 || reply_hash(restore) != pinned {
+        fail();
+    }
+    let (rolled_back, _) = call(
+        request(fs::OP_READ, 0, ZERO_HASH),
+        DIRECTORY_SLOT,
+        b"later.txt",
+    );
+    let (kept, _) = call(
+        request(fs::OP_READ, 0, ZERO_HASH),
         DIRECTORY_SLOT,
+        b"new.txt",
     );
+    if rolled_back.status != -3 || kept.status != 0 || reply_hash(kept) != PAYLOAD_HASH {
+        fail();
+    }
+    let _ = slime_rt::cap_drop(snapshot_slot);
+    slime_rt::debug_write(b"[directory-probe] snapshot restored\n");
+
+    // Two malformed shapes, refused in two places: a path that escapes never
+    // becomes a scope, so the root will not derive the copy at all; a request
+    // with bits set that v2 reserves reaches the service and is refused there.
+    if try_call(
+        request(fs::OP_READ, 0, ZERO_HASH),
+        DIRECTORY_SLOT,
+        b"../note",
+    )
+    .is_some()
+    {
+        fail();
+    }
+    let mut flagged = request(fs::OP_READ, 0, ZERO_HASH);
+    flagged.flags = 1;
+    let (malformed, _) = call(flagged, DIRECTORY_SLOT, b"note");
     if malformed.status != -1 {
         fail();
     }
@@ -126,19 +362,17 @@ fn main(_startup_arg: u32) {
     slime_rt::debug_write(b"[directory-probe] done\n");
 }
 
-fn request(op: u8, name: &[u8], payload_len: u32, hash: [u8; 32]) -> WireFsRequest {
-    let mut encoded_name = [0u8; fs::MAX_NAME_BYTES];
-    encoded_name[..name.len()].copy_from_slice(name);
+fn request(op: u8, payload_len: u32, hash: [u8; 32]) -> WireFsRequest {
     let (hash0, hash1, hash2, hash3) = hash_words(hash);
     WireFsRequest {
         magic: fs::FS_MAGIC,
         version: fs::FORMAT_VERSION,
         op,
         flags: 0,
-        name_len: name.len() as u8,
         reserved0: 0,
-        name: encoded_name,
         payload_len,
+        offset: 0,
+        reserved1: [0; 8],
         hash0,
         hash1,
         hash2,
@@ -146,51 +380,187 @@ fn request(op: u8, name: &[u8], payload_len: u32, hash: [u8; 32]) -> WireFsReque
     }
 }
 
-fn call(request: WireFsRequest, directory_slot: u32) -> (WireFsReply, Option<u32>) {
-    try_call(request, directory_slot).unwrap_or_else(|| fail())
+fn call(request: WireFsRequest, directory_slot: u32, path: &[u8]) -> (WireFsReply, Option<u32>) {
+    try_call(request, directory_slot, path).unwrap_or_else(|| fail())
+}
+
+/// A range write on `path`, carrying `loan` in the same message as the
+/// request's Directory copy.
+fn call_with_loan(request: WireFsRequest, path: &[u8], loan: u32) -> WireFsReply {
+    let loan = Delegation {
+        slot: loan,
+        disposition: CapabilityDisposition::Move,
+        kind: OBJECT_KIND_SHARED_BUFFER_LOAN,
+        rights: RIGHT_BUFFER_MAP,
+    };
+    exchange(request, DIRECTORY_SLOT, path, Some(loan))
+        .unwrap_or_else(|| fail())
+        .0
+}
+
+/// A restore, carrying a copy of the snapshot it restores in the same message
+/// as the whole-namespace view it commits through.
+fn call_with_pin(request: WireFsRequest, snapshot_slot: u32) -> WireFsReply {
+    let pin = slime_rt::directory_derive(
+        snapshot_slot,
+        b"",
+        RIGHT_DIRECTORY_READ | RIGHT_TRANSFER | RIGHT_DIRECTORY_DERIVE,
+    )
+    .unwrap_or_else(|_| fail());
+    let pin = Delegation {
+        slot: pin,
+        disposition: CapabilityDisposition::Move,
+        kind: OBJECT_KIND_DIRECTORY,
+        rights: u64::from(RIGHT_DIRECTORY_READ),
+    };
+    exchange(request, DIRECTORY_SLOT, b"", Some(pin))
+        .unwrap_or_else(|| fail())
+        .0
+}
+
+/// The byte at `index` of the range this probe writes and reads back.
+const fn range_byte(index: usize) -> u8 {
+    (index % 251) as u8
+}
+
+fn range_hash() -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for index in 0..RANGE_LEN as usize {
+        hasher.update(&[range_byte(index)]);
+    }
+    hasher.finalize()
+}
+
+/// The range, written into a buffer from this probe's factory, sealed, and
+/// lent read-only to the service.
+///
+/// The buffer itself is released at once. Its pages stay charged here until
+/// the service returns the loan, which it does before it replies, so the one
+/// buffer the budget allows is free again by the time the next range goes out.
+fn range_loan() -> u32 {
+    let length = u64::from(RANGE_LEN).div_ceil(PAGE) * PAGE;
+    let buffer = slime_rt::shared_buffer_create(FACTORY_SLOT, (length / PAGE) as usize, true)
+        .unwrap_or_else(|_| fail());
+    if slime_rt::shared_buffer_map(buffer.slot, BUFFER_BASE, 0, length, true) != ERR_SUCCESS {
+        fail();
+    }
+    // SAFETY: the map above installed a writable mapping of `length` bytes at
+    // `BUFFER_BASE`, at least `RANGE_LEN`, which stays until the unmap below.
+    unsafe {
+        let bytes = BUFFER_BASE as *mut u8;
+        for index in 0..RANGE_LEN as usize {
+            bytes.add(index).write_volatile(range_byte(index));
+        }
+    }
+    if slime_rt::shared_buffer_unmap(buffer.slot, BUFFER_BASE) != ERR_SUCCESS
+        || slime_rt::shared_buffer_seal(buffer.slot) != ERR_SUCCESS
+    {
+        fail();
+    }
+    let loan = slime_rt::shared_buffer_loan(buffer.slot, RPC_SLOT, 0, u64::from(RANGE_LEN), false)
+        .unwrap_or_else(|_| fail());
+    if slime_rt::shared_buffer_release(buffer.slot) != ERR_SUCCESS {
+        fail();
+    }
+    loan.slot
+}
+
+/// Whether the service's loan holds exactly the range, settling it either way.
+fn loan_holds_range(loan: u32) -> bool {
+    if slime_rt::shared_buffer_loan_map(loan, LOAN_BASE, 0, u64::from(RANGE_LEN)) != ERR_SUCCESS {
+        fail();
+    }
+    // SAFETY: the loan map above installed a read-only mapping of exactly
+    // `RANGE_LEN` bytes at `LOAN_BASE`, which stays until the unmap below.
+    let matches = unsafe {
+        let bytes = LOAN_BASE as *const u8;
+        (0..RANGE_LEN as usize).all(|index| bytes.add(index).read_volatile() == range_byte(index))
+    };
+    if slime_rt::shared_buffer_unmap(loan, LOAN_BASE) != ERR_SUCCESS
+        || slime_rt::shared_buffer_return(loan) != ERR_SUCCESS
+    {
+        fail();
+    }
+    matches
 }
 
-/// One request, or `None` when the mechanism refuses to hand the view over.
+/// One request on `path`, or `None` when the mechanism refuses to hand the view
+/// over.
 ///
 /// A request travels as a narrowed transferable copy of the caller's view, so a
 /// view carrying neither the operation's right nor `directoryDerive` cannot make
 /// that request at all — the denial happens in the root, before the service sees
 /// anything. That is a real answer for a boundary arm and a failure anywhere
-/// else, which is why the two spellings are separate.
-fn try_call(request: WireFsRequest, directory_slot: u32) -> Option<(WireFsReply, Option<u32>)> {
+/// else, which is why the two spellings are separate. Under fs/v2 the copy is
+/// also how the path crosses: it is derived with `path` as its relative scope,
+/// so the root checks the path and the service reads it back from the copy.
+fn try_call(
+    request: WireFsRequest,
+    directory_slot: u32,
+    path: &[u8],
+) -> Option<(WireFsReply, Option<u32>)> {
+    exchange(request, directory_slot, path, None)
+}
+
+/// `try_call`, with an optional second capability sent in the same message:
+/// a range write's loan or a restore's pin.
+///
+/// Whatever capability comes back with the reply — a derived view, a pin, or
+/// the service's loan of a range read — is claimed and returned alongside it.
+fn exchange(
+    request: WireFsRequest,
+    directory_slot: u32,
+    path: &[u8],
+    beside: Option<Delegation>,
+) -> Option<(WireFsReply, Option<u32>)> {
     let rights = match request.op {
         fs::OP_LIST => RIGHT_DIRECTORY_LIST,
-        fs::OP_READ => RIGHT_DIRECTORY_READ,
-        fs::OP_WRITE => RIGHT_DIRECTORY_WRITE,
+        fs::OP_READ | fs::OP_READ_RANGE => RIGHT_DIRECTORY_READ,
+        fs::OP_WRITE | fs::OP_RESTORE | fs::OP_WRITE_RANGE => RIGHT_DIRECTORY_WRITE,
         // A derive hands the service the authority it must place *on the result*
         // as well as the right to derive at all, and `transfer` besides: the
         // service returns the narrowed view by delegating it back, which the
         // root refuses from a view that cannot be transferred.
-        fs::OP_DERIVE => {
+        // A snapshot is the same shape: the pinned view it returns carries
+        // exactly the rights a derived one does.
+        fs::OP_DERIVE | fs::OP_SNAPSHOT => {
             RIGHT_DIRECTORY_DERIVE | RIGHT_DIRECTORY_READ | RIGHT_DIRECTORY_LIST | RIGHT_TRANSFER
         }
         _ => fail(),
     };
     // The copy that crosses. Derived per request rather than reused, because a
     // delegate is a `Move`: the caller keeps its own view and gives up only the
-    // copy. A source that cannot produce one — no `directoryDerive`, or not the
-    // right this operation needs — is the refusal this returns `None` for.
+    // copy. A source that cannot produce one — no `directoryDerive`, not the
+    // right this operation needs, or a path the root will not scope — is the
+    // refusal this returns `None` for.
     let transfer_slot = slime_rt::directory_derive(
         directory_slot,
-        b"",
+        path,
         rights | RIGHT_TRANSFER | RIGHT_DIRECTORY_DERIVE,
     )
     .ok()?;
     let encoded = request.encode();
+    let view = Delegation {
+        slot: transfer_slot,
+        disposition: CapabilityDisposition::Move,
+        kind: OBJECT_KIND_DIRECTORY,
+        rights: u64::from(rights),
+    };
     loop {
-        match slime_rt::capability_delegate(
-            RPC_SLOT,
-            transfer_slot,
-            CapabilityDisposition::Move,
-            OBJECT_KIND_DIRECTORY,
-            u64::from(rights),
-            &encoded,
-        ) {
+        let sent = match beside {
+            // The second capability crosses with the view, so the service
+            // holds both the moment it has the request.
+            Some(beside) => slime_rt::capability_delegate_all(RPC_SLOT, &[view, beside], &encoded),
+            None => slime_rt::capability_delegate(
+                RPC_SLOT,
+                view.slot,
+                view.disposition,
+                view.kind,
+                view.rights,
+                &encoded,
+            ),
+        };
+        match sent {
             ERR_WOULDBLOCK => slime_rt::yield_now(),
             result if result < 0 => fail(),
             _ => break,
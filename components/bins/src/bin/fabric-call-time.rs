@@ -1,6 +1,13 @@
 #![no_std]
 #![no_main]
 
+//! The C8.6 call plane's time driver.
+//!
+//! Holds the plane's `simulatedClock` with `clockAdvance`; the broker holds a
+//! read-only copy of the same clock. Each step advances the clock and then
+//! sends the record that wakes the broker to read it, one step per phase
+//! signal from client A.
+
 #[path = "../fabric_call_scenario.rs"]
 mod scenario;
 
@@ -8,6 +15,9 @@ use slime_rt::{ERR_WOULDBLOCK, MAX_CAPS_PER_MSG, MAX_MSG};
 
 slime_rt::entry!(main);
 
+/// The plane's simulated clock, held with `clockAdvance`.
+const CLOCK_SLOT: u32 = 2;
+
 fn main(_startup_arg: u32) {
     // The boot plane declares this component but gives it no work, and the
     // discriminator is the build profile rather than a startup argument: the
@@ -20,15 +30,23 @@ fn main(_startup_arg: u32) {
     scenario::resolve_wake_slot();
     let mut phases = PhaseBuffer::new(None);
     phases.wait(1);
-    scenario::send_time(0, 1_000_025);
+    advance(1_000_025);
     phases.wait(2);
-    scenario::send_time(0, 2_000_050);
+    advance(2_000_050);
     // Phase 3 is a completion barrier sent only after client A has observed the
     // peer-death terminal. It must not advance the clock.
     phases.wait(3);
     slime_rt::debug_write(b"[fabric-call-time] bounded time completed\n");
 }
 
+/// Move the clock, then tell the broker it moved.
+fn advance(now_ns: u64) {
+    if slime_rt::clock_advance(CLOCK_SLOT, now_ns).is_err() {
+        scenario::fail(b"time clock advance");
+    }
+    scenario::send_time(0, now_ns);
+}
+
 struct PhaseBuffer {
     ready: u8,
 }
@@ -45,6 +45,8 @@ const SERVER_SUPERVISION_SLOT: u32 = 8;
 /// native Endpoint reports no peer death, and this plane's clock is a separately
 /// declared instance, so the server's handle says nothing about it (B76).
 const TIME_SUPERVISION_SLOT: u32 = 9;
+/// The call plane's simulated clock, read-only.
+const CLOCK_SLOT: u32 = 10;
 
 fn main(_startup_arg: u32) {
     call_broker::Broker::new(
@@ -52,6 +54,7 @@ fn main(_startup_arg: u32) {
         [CLIENT_A_SLOT, CLIENT_B_SLOT],
         SERVER_SLOT,
         TIME_SLOT,
+        CLOCK_SLOT,
         [
             CLIENT_A_SUPERVISION_SLOT,
             CLIENT_B_SUPERVISION_SLOT,
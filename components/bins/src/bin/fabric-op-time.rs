@@ -1,14 +1,16 @@
 #![no_std]
 #![no_main]
 
-//! The C8.7 operation plane's monotonic-time input.
+//! The C8.7 operation plane's time driver.
 //!
-//! Time reaches the fabric only through this capability-routed record, never a
-//! poll or a kernel clock, so every expiry and deadline transition in the gate
-//! happens at an instant the transcript can name. The component advances the
-//! clock one step per phase signal from client A and then exits, which is what
-//! makes "the result expired" and "the goal timed out" orderable observations
-//! rather than a race.
+//! Holds the plane's `simulatedClock` with `clockAdvance`; the broker holds a
+//! read-only copy of the same clock. Each step advances the clock and then
+//! sends a record naming the new instant, which wakes the broker to read the
+//! clock and check the two agree, so every expiry and deadline transition in
+//! the gate happens at an instant the transcript can name. The component
+//! advances the clock one step per phase signal from client A and then exits,
+//! which is what makes "the result expired" and "the goal timed out" orderable
+//! observations rather than a race.
 
 #[path = "../fabric_operation_scenario.rs"]
 mod scenario;
@@ -21,6 +23,8 @@ slime_rt::entry!(main);
 const CONTROL_SLOT: u32 = 0;
 /// Its half of the phase channel client A signals on.
 const PHASE_SLOT: u32 = 1;
+/// The plane's simulated clock, held with `clockAdvance`.
+const CLOCK_SLOT: u32 = 2;
 
 fn main(_startup_arg: u32) {
     // The boot plane declares this component but gives it no work, and the
@@ -33,6 +37,9 @@ fn main(_startup_arg: u32) {
     loop {
         match try_wait_phase(3) {
             Some(()) => {
+                if slime_rt::clock_advance(CLOCK_SLOT, 4_000_100).is_err() {
+                    scenario::fail(b"time clock advance");
+                }
                 scenario::send_time(CONTROL_SLOT, 4_000_100);
                 slime_rt::debug_write(b"[fabric-op-time] bounded time advanced\n");
                 return;
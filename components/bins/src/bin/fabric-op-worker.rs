@@ -42,12 +42,15 @@ const REPLACEMENT_SUPERVISION_SLOT: u32 = 11;
 /// declares. The boot-parked plane never reached `pump_replacement`'s
 /// admission arm (client B parks rather than exiting), so it never needed one.
 const RESTART_START_SLOT: u32 = 12;
+/// The operation plane's simulated clock, read-only.
+const CLOCK_SLOT: u32 = 13;
 
 fn main(_startup_arg: u32) {
     operation_broker::Broker::new(
         [CLIENT_A_SLOT, CLIENT_B_SLOT],
         SERVER_SLOT,
         TIME_SLOT,
+        CLOCK_SLOT,
         REPLACEMENT_SLOT,
         Some(RESTART_START_SLOT),
         BACKUP_ROUTE_SLOT,
@@ -44,7 +44,7 @@ use slime_proto::sample_descriptor::{
 use slime_proto::{valid_capability_transfer, valid_stream_event};
 use slime_rt::{CapabilityDisposition, ERR_SUCCESS, ERR_WOULDBLOCK, MAX_CAPS_PER_MSG, MAX_MSG};
 // B59: the capability-rights vocabulary is generated from
-// `contracts/generation/v5/schema.zt`; these were local copies of the same
+// `contracts/generation/v6/schema.zt`; these were local copies of the same
 // bit numbering.
 use boot_contracts::generation::RIGHT_SEND;
 
@@ -73,9 +73,13 @@ const FACTORY_SLOT: u32 = 1;
 /// the fabric loans this component its ring in the other direction, and each
 /// spawning before the other is impossible.
 const FABRIC_SLOT: u32 = 0;
-/// Generation-granted simulated monotonic-time input. This is a separate
+/// The endpoint a time record and its credit travel over. This is a separate
 /// capability from both publish routes; possessing a route grants no clock.
 const TIME_SLOT: u32 = 3;
+/// The QoS plane's `simulatedClock`, held with `clockAdvance`. The broker
+/// holds a read-only copy and takes the instant from it; a record on
+/// `TIME_SLOT` only says that it moved.
+const CLOCK_SLOT: u32 = 4;
 
 /// The visibility plane's declared diagnostics ingress edge, send-only.
 ///
@@ -562,7 +566,11 @@ fn inline_sample(type_tag: u64, sequence: u64, flags: u32) -> WireStreamSample {
     }
 }
 
+/// Move the clock, then tell the broker it moved.
 fn advance_time(now_ns: u64) {
+    if slime_rt::clock_advance(CLOCK_SLOT, now_ns).is_err() {
+        fail(b"time clock advance");
+    }
     let message = WireTimeAdvance {
         magic: TIME_ADVANCE_MAGIC,
         version: TIME_VERSION,
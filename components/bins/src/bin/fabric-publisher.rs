@@ -31,7 +31,7 @@ use slime_proto::ring::{Ring, RingError};
 use slime_proto::valid_capability_transfer;
 use slime_rt::{ERR_SUCCESS, ERR_WOULDBLOCK, MAX_CAPS_PER_MSG, MAX_MSG};
 // B59: the capability-rights vocabulary is generated from
-// `contracts/generation/v5/schema.zt`; these were local copies of the same
+// `contracts/generation/v6/schema.zt`; these were local copies of the same
 // bit numbering.
 use boot_contracts::generation::{RIGHT_RECV, RIGHT_SEND};
 
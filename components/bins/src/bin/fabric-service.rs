@@ -98,7 +98,7 @@ use slime_rt::{
     MAX_MSG,
 };
 // B59: the capability-rights vocabulary is generated from
-// `contracts/generation/v5/schema.zt`; these were local copies of the same
+// `contracts/generation/v6/schema.zt`; these were local copies of the same
 // bit numbering.
 use boot_contracts::generation::{RIGHT_BUFFER_MAP, RIGHT_BUFFER_WRITE};
 
@@ -141,6 +141,24 @@ fn time_slot() -> u32 {
     unsafe { *core::ptr::addr_of_mut!(TIME_SLOT_CACHE) = slot };
     slot
 }
+/// The QoS clock: the `simulatedClock` `fabric-publisher-b` advances before it
+/// sends a time record, granted here read-only. Resolved by its minted name and
+/// cached, on `time_slot`'s reasoning: init places it at 13 under
+/// `sel4-qos.zti` and at 16 under `sel4-traffic.zti`.
+static mut CLOCK_SLOT_CACHE: u32 = u32::MAX;
+
+fn clock_slot() -> u32 {
+    // SAFETY: single-threaded, and every reader is on the one dispatch loop.
+    let cached = unsafe { *core::ptr::addr_of!(CLOCK_SLOT_CACHE) };
+    if cached != u32::MAX {
+        return cached;
+    }
+    let slot = slime_rt::resolve_binding(b"minted:fabric-service-qos-clock")
+        .unwrap_or_else(|_| fail(b"qos clock slot"));
+    // SAFETY: as above.
+    unsafe { *core::ptr::addr_of_mut!(CLOCK_SLOT_CACHE) = slot };
+    slot
+}
 /// The component that owns the other end of `time_slot()`. Named rather than
 /// numbered because its supervision handle is what reports the clock's exit:
 /// no `ERR_PEER_DEAD` reaches a native Endpoint.
@@ -398,6 +416,8 @@ fn main(_startup_arg: u32) {
             controls.clients,
             controls.server,
             controls.time,
+            // The simulated clock init narrows to read-only, after the handles.
+            10,
             // Client A, client B, server, then the clock's own handle: a
             // separately declared instance whose exit the server's handle does
             // not report (B76).
@@ -413,6 +433,8 @@ fn main(_startup_arg: u32) {
             controls.clients,
             controls.server,
             controls.time,
+            // The simulated clock init narrows to read-only, after the handles.
+            13,
             6,
             Some(OPERATION_REPLACEMENT_START_SLOT),
             7,
@@ -1287,6 +1309,14 @@ fn broker(
             } else {
                 None
             };
+            // The private-memory pair likewise. No sampling loop precedes it:
+            // every private page enters through a root operation, so the root
+            // holds the high-water mark and one read answers both records.
+            let settled_memory = if GENERATION_BOOT_ACTION == "traffic" {
+                slime_rt::memory_occupancy().ok()
+            } else {
+                None
+            };
             // Resource evidence before the terminal. The frame counter carries
             // two records: the historical peak this run reached, and — read
             // after `release_retained` drains every reference — the count
@@ -1323,6 +1353,12 @@ fn broker(
                         slots.declared_peak,
                     );
                 }
+                if let Some(memory) = settled_memory {
+                    let _ = trace.resource(
+                        slime_proto::fabric_trace::RESOURCE_PRIVATE_PAGES,
+                        memory.private_peak,
+                    );
+                }
             }
             let baseline_frames = frames.iter().filter(|frame| frame.refs > 0).count() as u32;
             let _ = trace.resource(slime_proto::fabric_trace::RESOURCE_FRAMES, baseline_frames);
@@ -1379,6 +1415,15 @@ fn broker(
                         slots.declared,
                     );
                 }
+                // Private pages still mapped once the scenario drained. Equal to
+                // the peak, because nothing returns a page before the task
+                // exits; a lower baseline would be a page lost, not released.
+                if let Some(memory) = settled_memory {
+                    let _ = trace.resource(
+                        slime_proto::fabric_trace::RESOURCE_PRIVATE_PAGES,
+                        memory.private,
+                    );
+                }
             }
             let _ = trace.terminal();
             trace.flush(b"stream");
@@ -2303,6 +2348,14 @@ fn receive_time(pending_time: &mut Option<u64>, time_dead: &mut bool) {
     if !slime_proto::valid_time_advance(&value) {
         fail(b"non-monotonic time")
     }
+    // The record is only the wake-up: the instant is the granted clock's,
+    // advanced by the same publisher before it sent the record. A record
+    // naming any other instant did not come from the clock's holder.
+    match slime_rt::clock_now(clock_slot()) {
+        Ok(now_ns) if now_ns == value.now_ns => {}
+        Ok(_) => fail(b"time disagrees with its clock"),
+        Err(_) => fail(b"qos clock read"),
+    }
     update_time_liveness(pending_time, time_dead, TimeReceive::Advance(value.now_ns));
 }
 
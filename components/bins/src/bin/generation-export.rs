@@ -0,0 +1,21 @@
+#![no_std]
+#![no_main]
+
+#[path = "../generation_command.rs"]
+mod command;
+
+slime_rt::entry!(main);
+
+fn main(_startup_arg: u32) {
+    let identity = command::selected_identity();
+    let reply = command::run(slime_proto::generation::OP_EXPORT, identity);
+    if reply.status != 0 || command::reply_identity(reply) != identity {
+        slime_rt::debug_write(b"[generation-export] status=");
+        command::write_i32(reply.status);
+        slime_rt::debug_write(b"\n");
+        command::fail();
+    }
+    slime_rt::debug_write(b"[generation-export] exported sectors=");
+    command::write_u32(reply.count);
+    slime_rt::debug_write(b"\n");
+}
@@ -0,0 +1,21 @@
+#![no_std]
+#![no_main]
+
+#[path = "../generation_command.rs"]
+mod command;
+
+slime_rt::entry!(main);
+
+fn main(_startup_arg: u32) {
+    // The archive names the generation; the request names none.
+    let reply = command::run(slime_proto::generation::OP_IMPORT, command::zero_identity());
+    if reply.status != 0 {
+        slime_rt::debug_write(b"[generation-import] rejected status=");
+        command::write_i32(reply.status);
+        slime_rt::debug_write(b"\n");
+        command::fail();
+    }
+    slime_rt::debug_write(b"[generation-import] admitted objects=");
+    command::write_u32(reply.count);
+    slime_rt::debug_write(b"\n");
+}
@@ -21,4 +21,17 @@ fn main(_startup_arg: u32) {
     slime_rt::debug_write(b" flags=");
     command::write_u32(reply.flags);
     slime_rt::debug_write(b"\n");
+
+    // What the inspected generation changes against known-good. The manager
+    // lists each change itself; a generation it holds no record of has no diff
+    // to show, which is not a failure of the inspection.
+    let diff = command::run(slime_proto::generation::OP_DIFF, identity);
+    if diff.status == 0 {
+        slime_rt::debug_write(b"[generation-inspect] diff changes=");
+        command::write_u32(diff.count);
+    } else {
+        slime_rt::debug_write(b"[generation-inspect] diff unavailable status=");
+        command::write_u32(diff.status.unsigned_abs());
+    }
+    slime_rt::debug_write(b"\n");
 }
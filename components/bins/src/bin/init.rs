@@ -18,11 +18,11 @@ use supervision_plane::drive_supervision_plane;
 
 use slime_rt::{Rights, SpawnGrant};
 // B59: the capability-rights vocabulary is generated from
-// `contracts/generation/v5/schema.zt`; these were local copies of the same
+// `contracts/generation/v6/schema.zt`; these were local copies of the same
 // bit numbering.
 use boot_contracts::generation::{
-    RIGHT_BUFFER_CREATE, RIGHT_DIRECTORY_READ, RIGHT_DIRECTORY_WRITE, RIGHT_EXEC, RIGHT_SEND,
-    RIGHT_SPAWN, RIGHT_SUPERVISE, RIGHT_TRANSFER,
+    RIGHT_BUFFER_CREATE, RIGHT_CLOCK_ADVANCE, RIGHT_CLOCK_READ, RIGHT_DIRECTORY_READ,
+    RIGHT_DIRECTORY_WRITE, RIGHT_EXEC, RIGHT_SEND, RIGHT_SPAWN, RIGHT_SUPERVISE, RIGHT_TRANSFER,
 };
 
 /// Whether the child this instance owns declares a minted binding called
@@ -149,6 +149,10 @@ mod boot_action {
     pub const TRAFFIC: u32 = 28;
     /// RP2's demo-scoped AArch64 vertical slice.
     pub const DEMO: u32 = 29;
+    /// B48's MCS isolation plane.
+    pub const ISOLATION: u32 = 30;
+    /// The clock plane.
+    pub const CLOCK: u32 = 31;
 
     // The table above is a hand copy of the contract's numbering, and the two
     // are an ABI: the root passes one of these words to this thread and this
@@ -185,6 +189,8 @@ mod boot_action {
     const _: () = assert!(MATRIX == BootAction::Matrix.id());
     const _: () = assert!(TRAFFIC == BootAction::Traffic.id());
     const _: () = assert!(DEMO == BootAction::Demo.id());
+    const _: () = assert!(ISOLATION == BootAction::Isolation.id());
+    const _: () = assert!(CLOCK == BootAction::Clock.id());
 }
 
 /// Compose the graph the generation selected.
@@ -366,6 +372,20 @@ fn compose_declared_graph(startup_arg: u32) {
             slime_rt::debug_write(b"[init] stress plane complete\n");
             slime_rt::exit(0)
         }
+        // Likewise for the isolation plane: its two components are the root's
+        // own autostart instances, and the evidence is whether the control
+        // loop finishes while the saturator is still spinning. init has no
+        // part in that and exits rather than take CPU from either (B48).
+        action::ISOLATION => {
+            slime_rt::debug_write(b"[init] isolation plane launched\n");
+            slime_rt::exit(0)
+        }
+        // The clock probe is the root's own instance and holds its clocks
+        // directly, so init has nothing to place.
+        action::CLOCK => {
+            slime_rt::debug_write(b"[init] clock plane launched\n");
+            slime_rt::exit(0)
+        }
         action::PRODUCT => {}
         // An action this image does not implement is a generation the graph
         // cannot compose, which is a boot failure rather than a silent
@@ -426,7 +446,8 @@ fn main(startup_arg: u32) {
         arguments: [0; 8],
         environment: [0; 8],
         grant_rights: 0,
-        reserved: [0; 6],
+        scheduling_class: 0,
+        reserved: [0; 5],
     };
     if slime_rt::send(resolve_spawn_service_rpc(), &shutdown.encode(), &[]) != slime_rt::ERR_SUCCESS
     {
@@ -591,9 +612,13 @@ fn drive_traffic_plane() -> ! {
     slime_rt::debug_write(b"[init] traffic control channels minted\n");
     let publisher = spawn_boot(b"executable:fabric-publisher");
     let subscriber = spawn_boot(b"executable:fabric-subscriber");
+    let qos_clock = resolve_clock(b"init-qos-clock");
     let publisher_b = spawn_boot_with(
         b"executable:fabric-publisher-b",
-        &[grant(resolve_own_buffer_factory(), RIGHT_BUFFER_CREATE)],
+        &[
+            grant(resolve_own_buffer_factory(), RIGHT_BUFFER_CREATE),
+            grant(qos_clock, RIGHT_CLOCK_READ | RIGHT_CLOCK_ADVANCE),
+        ],
     );
     let subscriber_b = spawn_boot(b"executable:fabric-subscriber-b");
     let observer = spawn_boot(b"executable:fabric-observer");
@@ -611,6 +636,7 @@ fn drive_traffic_plane() -> ! {
             grant(subscriber_b, RIGHT_SUPERVISE),
             grant(observer, RIGHT_SUPERVISE),
             grant(proxy, RIGHT_SUPERVISE),
+            grant(qos_clock, RIGHT_CLOCK_READ),
         ],
     );
     slime_rt::debug_write(b"[init] traffic stream broker spawned\n");
@@ -624,7 +650,11 @@ fn drive_traffic_plane() -> ! {
         b"executable:fabric-call-server",
         &[grant(resolve_own_buffer_factory(), RIGHT_BUFFER_CREATE)],
     );
-    let call_time = spawn_boot(b"executable:fabric-call-time");
+    let call_clock = resolve_clock(b"init-call-clock");
+    let call_time = spawn_boot_with(
+        b"executable:fabric-call-time",
+        &[grant(call_clock, RIGHT_CLOCK_READ | RIGHT_CLOCK_ADVANCE)],
+    );
     let call_worker = spawn_boot_with(
         b"executable:fabric-call-worker",
         &[
@@ -633,6 +663,7 @@ fn drive_traffic_plane() -> ! {
             grant(call_client_b, RIGHT_SUPERVISE),
             grant(call_server, RIGHT_SUPERVISE),
             grant(call_time, RIGHT_SUPERVISE),
+            grant(call_clock, RIGHT_CLOCK_READ),
         ],
     );
     slime_rt::debug_write(b"[init] traffic call plane spawned\n");
@@ -640,7 +671,11 @@ fn drive_traffic_plane() -> ! {
     let op_client = spawn_boot(b"executable:fabric-op-client");
     let op_client_b = spawn_boot(b"executable:fabric-op-client-b");
     let op_server = spawn_boot(b"executable:fabric-op-server");
-    let op_time = spawn_boot(b"executable:fabric-op-time");
+    let op_clock = resolve_clock(b"init-operation-clock");
+    let op_time = spawn_boot_with(
+        b"executable:fabric-op-time",
+        &[grant(op_clock, RIGHT_CLOCK_READ | RIGHT_CLOCK_ADVANCE)],
+    );
     let op_restart = spawn_boot(b"executable:fabric-op-client-b-restart");
     let op_worker = spawn_boot_with(
         b"executable:fabric-op-worker",
@@ -649,6 +684,7 @@ fn drive_traffic_plane() -> ! {
             grant(op_client_b, RIGHT_SUPERVISE),
             grant(op_server, RIGHT_SUPERVISE),
             grant(op_restart, RIGHT_SUPERVISE),
+            grant(op_clock, RIGHT_CLOCK_READ),
         ],
     );
     slime_rt::debug_write(b"[init] traffic operation plane spawned\n");
@@ -758,6 +794,16 @@ fn resolve_own_buffer_factory() -> u32 {
     slime_rt::resolve_binding(b"init-shared-buffer-factory").unwrap_or_else(|_| slime_rt::exit(1))
 }
 
+/// One fabric plane's `simulatedClock`, by grant name.
+///
+/// By name for `resolve_own_buffer_factory`'s reason: the traffic generation
+/// binds init three, one per scenario, so a kind query would be ambiguous. Each
+/// is a separate clock, and init hands its time driver the advancing copy and
+/// its broker a read-only one.
+fn resolve_clock(name: &[u8]) -> u32 {
+    slime_rt::resolve_binding(name).unwrap_or_else(|_| slime_rt::exit(1))
+}
+
 /// Spawn one boot participant that its manifest grants nothing, returning the
 /// supervision handle init keeps.
 fn spawn_boot(executable: &[u8]) -> u32 {
@@ -895,9 +941,11 @@ fn launch_fabric_operations() {
     slime_rt::debug_write(b"[init] operation participants spawned\n");
     slime_rt::debug_write(b"[init] operation replacement introduced\n");
     // What init still passes is exactly what the generation cannot place: the
-    // shared-buffer factory it holds, and one supervision handle per
-    // participant, which only exist once those tasks do. Matching is positional
-    // against ascending declared slot: factory at 1, then the handles.
+    // shared-buffer factory it holds, one supervision handle per participant,
+    // which only exist once those tasks do, and a read-only copy of the plane's
+    // clock. Matching is positional against ascending declared slot: factory
+    // at 1, then the handles, then the clock at 13.
+    let clock = resolve_clock(b"init-operation-clock");
     let service = slime_rt::spawn(
         resolve_executable(b"executable:fabric-service"),
         &[
@@ -906,13 +954,17 @@ fn launch_fabric_operations() {
             grant(client_b.supervision_slot, RIGHT_SUPERVISE),
             grant(server.supervision_slot, RIGHT_SUPERVISE),
             grant(replacement.supervision_slot, RIGHT_SUPERVISE),
+            grant(clock, RIGHT_CLOCK_READ),
         ],
     )
     .unwrap_or_else(|_| slime_rt::exit(1));
     slime_rt::debug_write(b"[init] operation fabric spawned\n");
     slime_rt::debug_write(b"[init] operation supervision delegated\n");
-    let time = slime_rt::spawn(resolve_executable(b"executable:fabric-op-time"), &[])
-        .unwrap_or_else(|_| slime_rt::exit(1));
+    let time = slime_rt::spawn(
+        resolve_executable(b"executable:fabric-op-time"),
+        &[grant(clock, RIGHT_CLOCK_READ | RIGHT_CLOCK_ADVANCE)],
+    )
+    .unwrap_or_else(|_| slime_rt::exit(1));
     slime_rt::debug_write(b"[init] operation replacement released\n");
     wait_clean(&[
         client.supervision_slot,
@@ -949,9 +1001,10 @@ fn launch_fabric_calls() {
     .unwrap_or_else(|_| slime_rt::exit(1));
     slime_rt::debug_write(b"[init] call participants spawned\n");
     // What init still passes is exactly what the generation cannot place: the
-    // shared-buffer factory it holds, and one supervision handle per
-    // participant, which only exist once those tasks do. Matching is positional
-    // against ascending declared slot: factory at 1, then the handles.
+    // shared-buffer factory it holds, one supervision handle per participant,
+    // which only exist once those tasks do, and a read-only copy of the plane's
+    // clock. Matching is positional against ascending declared slot: factory
+    // at 1, then the handles, then the clock at 10.
     //
     // The clock is spawned here, before the broker, for the same reason the
     // three participants are: the broker is granted a supervision handle naming
@@ -960,8 +1013,12 @@ fn launch_fabric_calls() {
     // which names a different task. A clock that exits while the server lives
     // was observed by nothing, and the exit predicate that gates the trace
     // flush waited forever.
-    let time = slime_rt::spawn(resolve_executable(b"executable:fabric-call-time"), &[])
-        .unwrap_or_else(|_| slime_rt::exit(1));
+    let clock = resolve_clock(b"init-call-clock");
+    let time = slime_rt::spawn(
+        resolve_executable(b"executable:fabric-call-time"),
+        &[grant(clock, RIGHT_CLOCK_READ | RIGHT_CLOCK_ADVANCE)],
+    )
+    .unwrap_or_else(|_| slime_rt::exit(1));
     let service = slime_rt::spawn(
         resolve_executable(b"executable:fabric-service"),
         &[
@@ -970,6 +1027,7 @@ fn launch_fabric_calls() {
             grant(client_b.supervision_slot, RIGHT_SUPERVISE),
             grant(server.supervision_slot, RIGHT_SUPERVISE),
             grant(time.supervision_slot, RIGHT_SUPERVISE),
+            grant(clock, RIGHT_CLOCK_READ),
         ],
     )
     .unwrap_or_else(|_| slime_rt::exit(1));
@@ -1248,10 +1306,12 @@ fn fail_demo(reason: &[u8]) -> ! {
 ///
 /// `fabric-intruder` is spawned holding a real control endpoint on purpose.
 /// The denial under test is not "no channel" but "no declared edge".
-/// `qos` records which plane booted; both compose identically here, because the
-/// QoS graph's extra edge — the capability-routed clock between
+/// `qos` records which plane booted; both compose identically here. The QoS
+/// graph's extra edge — the endpoint time records travel over between
 /// `fabric-publisher-b` and the broker — is a declared grant the root installs
-/// rather than anything init places. The distinction is the generation's own
+/// rather than anything init places. The simulated clock those records report
+/// is placed by init, but `launch_fabric_graph` asks the manifest whether one
+/// is declared rather than taking this flag's word for it. The distinction is the generation's own
 /// `bootAction`, delivered at activation, so the QoS generation needs no paired
 /// build flag: the service and participants select behavior from the same
 /// generated boot action.
@@ -1719,9 +1779,21 @@ fn launch_fabric_graph(plane: &[u8], service_spawned: &[u8]) {
         .unwrap_or_else(|_| slime_rt::exit(1));
     let subscriber = slime_rt::spawn(resolve_executable(b"executable:fabric-subscriber"), &[])
         .unwrap_or_else(|_| slime_rt::exit(1));
+    // The QoS plane's simulated clock. Only `sel4-qos` declares it, so its
+    // presence is asked of the manifest like the proxy's handle below. The
+    // publisher that drives time gets the advancing copy after its factory.
+    let qos_clock =
+        declares_minted(b"fabric-service-qos-clock").then(|| resolve_clock(b"init-qos-clock"));
+    let publisher_b_grants = [
+        grant(resolve_own_buffer_factory(), RIGHT_BUFFER_CREATE),
+        grant(
+            qos_clock.unwrap_or_default(),
+            RIGHT_CLOCK_READ | RIGHT_CLOCK_ADVANCE,
+        ),
+    ];
     let publisher_b = slime_rt::spawn(
         resolve_executable(b"executable:fabric-publisher-b"),
-        &[grant(resolve_own_buffer_factory(), RIGHT_BUFFER_CREATE)],
+        &publisher_b_grants[..1 + usize::from(qos_clock.is_some())],
     )
     .unwrap_or_else(|_| slime_rt::exit(1));
     let subscriber_b = slime_rt::spawn(resolve_executable(b"executable:fabric-subscriber-b"), &[])
@@ -1762,26 +1834,32 @@ fn launch_fabric_graph(plane: &[u8], service_spawned: &[u8]) {
     // reaches *this* launcher, verified directly: `sel4-stream`, `sel4-qos`,
     // `sel4-visibility`, and `sel4-demo`. The resolver has other callers across
     // the boot and traffic planes; this note surveys only the ones on this path.
-    let grants = [
-        grant(resolve_own_buffer_factory(), RIGHT_BUFFER_CREATE),
-        grant(publisher.supervision_slot, RIGHT_SUPERVISE),
-        grant(subscriber.supervision_slot, RIGHT_SUPERVISE),
-        grant(intruder.supervision_slot, RIGHT_SUPERVISE),
-        grant(publisher_b.supervision_slot, RIGHT_SUPERVISE),
-        grant(subscriber_b.supervision_slot, RIGHT_SUPERVISE),
-    ];
-    let without_proxy = [grants[0], grants[1], grants[2], grants[4], grants[5]];
+    //
+    // The QoS clock's read-only copy comes last: `sel4-qos` declares it at 13,
+    // above every handle.
+    //
     // A failed resolve *is* absence, stated by the generation rather than
     // papered over at build time -- the same reading the notification axis
     // established when it replaced an always-emitted `SLOT_ABSENT`.
     let interposes = declares_minted(b"fabric-intruder-supervision");
+    let declared = [
+        Some(grant(resolve_own_buffer_factory(), RIGHT_BUFFER_CREATE)),
+        Some(grant(publisher.supervision_slot, RIGHT_SUPERVISE)),
+        Some(grant(subscriber.supervision_slot, RIGHT_SUPERVISE)),
+        interposes.then(|| grant(intruder.supervision_slot, RIGHT_SUPERVISE)),
+        Some(grant(publisher_b.supervision_slot, RIGHT_SUPERVISE)),
+        Some(grant(subscriber_b.supervision_slot, RIGHT_SUPERVISE)),
+        qos_clock.map(|clock| grant(clock, RIGHT_CLOCK_READ)),
+    ];
+    let mut grants = [grant(0, 0); 7];
+    let mut count = 0;
+    for next in declared.into_iter().flatten() {
+        grants[count] = next;
+        count += 1;
+    }
     let service = slime_rt::spawn(
         resolve_executable(b"executable:fabric-service"),
-        if interposes {
-            &grants[..]
-        } else {
-            &without_proxy[..]
-        },
+        &grants[..count],
     )
     .unwrap_or_else(|_| slime_rt::exit(1));
     plane_marker(plane, service_spawned);
@@ -0,0 +1,117 @@
+//! The reserved control loop of the MCS isolation plane (B48).
+//!
+//! Runs below `isolation-saturator`'s priority, on a reservation of its own,
+//! and must finish a fixed number of ticks while the saturator is still
+//! spinning. Each tick is a bounded step of work on the main thread followed by
+//! a native send to this process's worker thread, which receives every one and
+//! acknowledges the last.
+//!
+//! The worker's receive is the other half of what this plane checks. Under MCS
+//! a receive names a Reply object, and the worker receives through its own
+//! rather than the main thread's; before each thread had one, this receive was
+//! refused with `ERR_BAD_CAP`.
+//!
+//! Both threads also allocate from the growable heap, and the worker runs
+//! below the main thread (`workerPriority`), so the main thread regularly
+//! finds the heap's lock held by a thread it outranks. It must block on the
+//! task's thread-lock Notification for the worker to finish; a lock that spun
+//! would hold the CPU the holder needs until the budget ran out, every tick.
+
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+slime_rt::entry!(main, worker = worker);
+
+/// The declared loopback endpoint slot.
+const LOOPBACK_SLOT: u32 = 0;
+
+/// Ticks the loop must complete.
+const CONTROL_TICKS: u8 = 64;
+
+/// Work in one tick: small beside the saturator's whole spin, so the loop
+/// finishes first only if the kernel leaves it CPU.
+const TICK_SPINS: u64 = 200_000;
+
+/// The instance's declared `heapPages` in `sel4-isolation.zti`.
+const HEAP_PAGES: usize = 4;
+
+fn main(_startup_arg: u32) {
+    slime_rt::debug_write(b"[isolation-control-loop] control loop running\n");
+    let mut sink = 0u64;
+    let mut sinks = Vec::new();
+    for tick in 0..CONTROL_TICKS {
+        for step in 0..TICK_SPINS {
+            sink = sink.wrapping_add(step).rotate_left(1);
+            core::hint::spin_loop();
+        }
+        sinks.push(sink);
+        // Blocks until the worker takes it: a kernel rendezvous with no root
+        // in the path, so every tick also proves the worker was scheduled.
+        if slime_rt::send(LOOPBACK_SLOT, &[tick], &[]) < 0 {
+            fail(b"tick send failed");
+        }
+    }
+    if sink == u64::MAX {
+        slime_rt::debug_write(b"[isolation-control-loop] spin sink saturated\n");
+    }
+    // Wait for the worker's acknowledgement before reporting. Returning from
+    // here ends the process, and with it a worker that had not yet printed.
+    let mut ack = [0u8; slime_rt::MAX_MSG];
+    let mut caps = [0u64; slime_rt::MAX_CAPS_PER_MSG];
+    if slime_rt::recv_blocking(LOOPBACK_SLOT, &mut ack, &mut caps) != 1 || ack[0] != CONTROL_TICKS {
+        fail(b"worker acknowledgement missing");
+    }
+    if sinks.len() != usize::from(CONTROL_TICKS) {
+        fail(b"tick history lost");
+    }
+    let pages = slime_rt::heap_pages();
+    if slime_rt::heap_refusal().is_some() || !(1..=HEAP_PAGES).contains(&pages) {
+        fail(b"heap outside its declared pages");
+    }
+    slime_rt::debug_write(b"[isolation-control-loop] heap shared by both threads pages=");
+    slime_rt::debug_write(&[b'0' + pages as u8]);
+    slime_rt::debug_write(b"\n");
+    slime_rt::debug_write(b"[isolation-control-loop] control loop complete ticks=64\n");
+}
+
+/// The second thread's body: receive every tick, in order, then acknowledge.
+///
+/// Each tick also churns the heap, a buffer sized by the tick allocated and
+/// freed, so the worker holds the heap's lock often while the main thread is
+/// spinning above it.
+fn worker(_startup_arg: u32) {
+    let mut received = [0u8; slime_rt::MAX_MSG];
+    let mut caps = [0u64; slime_rt::MAX_CAPS_PER_MSG];
+    let mut ticks = Vec::new();
+    for _ in 0..CONTROL_TICKS {
+        let length = slime_rt::recv_blocking(LOOPBACK_SLOT, &mut received, &mut caps);
+        if length != 1 {
+            fail(b"worker receive failed");
+        }
+        let tick = received[0];
+        let echo = vec![tick; 1 + usize::from(tick) * 8];
+        if echo.iter().any(|byte| *byte != tick) {
+            fail(b"heap block corrupted");
+        }
+        ticks.push(tick);
+    }
+    if !ticks.iter().copied().eq(0..CONTROL_TICKS) {
+        fail(b"tick out of order");
+    }
+    slime_rt::debug_write(b"[isolation-control-loop] worker received ticks=64\n");
+    if slime_rt::send(LOOPBACK_SLOT, &[CONTROL_TICKS], &[]) < 0 {
+        fail(b"worker acknowledgement failed");
+    }
+}
+
+fn fail(reason: &[u8]) -> ! {
+    slime_rt::debug_write(b"[isolation-control-loop] fail: ");
+    slime_rt::debug_write(reason);
+    slime_rt::debug_write(b"\n");
+    slime_rt::exit(1)
+}
@@ -0,0 +1,41 @@
+//! The saturating half of the MCS isolation plane (B48).
+//!
+//! Spins without yielding or making a syscall, at a priority above the control
+//! loop it shares the CPU with. On the product kernel that is enough to starve
+//! the loop outright: nothing preempts a runnable thread for a lower-priority
+//! one. Under MCS the generation holds this instance to its declared
+//! reservation, so the kernel throttles it once its budget is spent and the
+//! loop runs in what is left of each period.
+//!
+//! The plane asserts the control loop finishes before this spin does. The
+//! spin's bound is far larger than the loop's work, so that order can only be
+//! observed if the kernel enforced the budget.
+
+#![no_std]
+#![no_main]
+
+slime_rt::entry!(main);
+
+/// Iterations the saturator spins for.
+///
+/// Long enough that, even throttled to its reservation, it outlasts the control
+/// loop by a wide margin; short enough that the instance still exits cleanly
+/// once the plane has its evidence.
+const SATURATION_SPINS: u64 = 2_000_000_000;
+
+fn main(_startup_arg: u32) {
+    slime_rt::debug_write(b"[isolation-saturator] saturating\n");
+
+    // No `yield_now` anywhere in here, for the reason `sample-worker` gives: a
+    // voluntary handover would prove nothing about enforcement.
+    let mut sink = 0u64;
+    for step in 0..SATURATION_SPINS {
+        // Opaque enough that the optimizer cannot fold the loop away.
+        sink = sink.wrapping_add(step).rotate_left(1);
+        core::hint::spin_loop();
+    }
+    if sink == u64::MAX {
+        slime_rt::debug_write(b"[isolation-saturator] spin sink saturated\n");
+    }
+    slime_rt::debug_write(b"[isolation-saturator] saturation ended\n");
+}
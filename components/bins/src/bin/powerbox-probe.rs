@@ -7,7 +7,7 @@ use slime_proto::{
 };
 use slime_rt::{ERR_BAD_CAP, ERR_WOULDBLOCK, MAX_CAPS_PER_MSG, MAX_MSG};
 // B59: rights bit numbering is generated from
-// `contracts/generation/v5/schema.zt`. The powerbox/fs protocols carry a
+// `contracts/generation/v6/schema.zt`. The powerbox/fs protocols carry a
 // 32-bit rights field, so the generated `u64` constants are narrowed at the
 // declaration rather than re-spelled as separate `u32` literals.
 const RIGHT_TRANSFER: u32 = boot_contracts::generation::RIGHT_TRANSFER as u32;
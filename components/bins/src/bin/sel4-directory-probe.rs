@@ -13,7 +13,9 @@
 //! * an **atomic compare-and-swap** commit, so a writer building on a stale
 //!   root is refused rather than silently discarding another's work;
 //! * a commit gate requiring an **unscoped** writer, so a holder of `docs`
-//!   cannot promote its subtree to the whole namespace.
+//!   cannot promote its subtree to the whole namespace;
+//! * **snapshots** that pin a root the caller saw as a read-only view, which
+//!   later commits do not move.
 //!
 //! The oracle proves the same properties in `kernel/src/capability/mod.rs` and
 //! three syscalls. Nothing about them needed a kernel — they needed a place
@@ -21,7 +23,7 @@
 
 use slime_rt::{DIRECTORY_ROOT_BYTES, MAX_DIRECTORY_PATH};
 // B59: rights bit numbering is generated from
-// `contracts/generation/v5/schema.zt`. The powerbox/fs protocols carry a
+// `contracts/generation/v6/schema.zt`. The powerbox/fs protocols carry a
 // 32-bit rights field, so the generated `u64` constants are narrowed at the
 // declaration rather than re-spelled as separate `u32` literals.
 const RIGHT_DIRECTORY_READ: u32 = boot_contracts::generation::RIGHT_DIRECTORY_READ as u32;
@@ -199,6 +201,46 @@ fn main(_startup_arg: u32) {
     }
     slime_rt::debug_write(b"[sel4-directory-probe] the namespace is shared across views\n");
 
+    // A snapshot pins the root a view sees, keeping its scope. Only the root
+    // the caller actually read can be pinned, and a pin never carries
+    // `directoryWrite`.
+    if slime_rt::directory_snapshot(docs, &FIRST_ROOT, RIGHT_DIRECTORY_READ)
+        != Err(slime_rt::ERR_WOULDBLOCK)
+    {
+        fail(b"a stale snapshot was pinned");
+    }
+    if slime_rt::directory_snapshot(docs, &SECOND_ROOT, RIGHTS_ALL).is_ok() {
+        fail(b"a writable snapshot was pinned");
+    }
+    let Ok(pinned) = slime_rt::directory_snapshot(
+        docs,
+        &SECOND_ROOT,
+        RIGHT_DIRECTORY_READ | RIGHT_DIRECTORY_LIST,
+    ) else {
+        fail(b"snapshot");
+    };
+    if slime_rt::directory_commit(ROOT_DIRECTORY_SLOT, &SECOND_ROOT, &FIRST_ROOT) < 0 {
+        fail(b"commit past the snapshot");
+    }
+    let (root, scope) = inspect(pinned, RIGHT_DIRECTORY_READ);
+    if root != SECOND_ROOT || &*scope != b"docs" {
+        fail(b"the snapshot moved with the namespace");
+    }
+    if slime_rt::directory_commit(pinned, &SECOND_ROOT, &FIRST_ROOT) >= 0 {
+        fail(b"commit through a snapshot accepted");
+    }
+    slime_rt::debug_write(b"[sel4-directory-probe] snapshot pinned the root\n");
+
+    // Restoring is the ordinary commit with the pinned root as the new one.
+    if slime_rt::directory_commit(ROOT_DIRECTORY_SLOT, &FIRST_ROOT, &root) < 0 {
+        fail(b"restore");
+    }
+    let (restored, _) = inspect(ROOT_DIRECTORY_SLOT, RIGHT_DIRECTORY_READ);
+    if restored != SECOND_ROOT {
+        fail(b"restore not visible");
+    }
+    slime_rt::debug_write(b"[sel4-directory-probe] restore committed the pinned root\n");
+
     slime_rt::debug_write(b"[sel4-directory-probe] directory plane complete\n");
 }
 
@@ -5,42 +5,58 @@
 //!
 //! The directory *mechanism* is the root's — a shared namespace root, scoped
 //! views, an atomic commit. This is what sits on top: a service that resolves
-//! names inside a snapshot tree, reads and writes objects, and derives
-//! subdirectory capabilities on request.
+//! paths inside a directory tree, reads and writes objects, derives
+//! subdirectory capabilities on request, and pins or restores whole roots.
 //!
 //! Derived from the oracle's `filesystem-service.rs`, and deliberately so. That
-//! component is *policy* — snapshot layout, path resolution, entry bounds, root
-//! transitions — and policy ports. What differs is one thing: the oracle asks
-//! the kernel to move object bytes through `store_transact` and an ambient
+//! component is *policy* — directory layout, path resolution, entry bounds,
+//! root transitions — and policy ports. What differs is one thing: the oracle
+//! asks the kernel to move object bytes through `store_transact` and an ambient
 //! `buffer_addr` pointer; here the same objects come out of
 //! `boot_contracts::object_store`, driven over a granted block capability, with
 //! payloads crossing in this component's own memory.
 //!
-//! Everything else — every bound, every refusal, the whole request surface —
-//! is the oracle's, because the contract is `contracts/fs/v1` on both.
+//! The contract is `contracts/fs/v2`. A request carries no name: its target is
+//! the scope of the Directory capability it arrives with, which the client
+//! derived for exactly that path and the root validated on the way. The tree is
+//! `slime_components::fs_directory`'s paged layout, and a v1 snapshot anywhere
+//! in it — the boot fixture is one — still resolves.
+//!
+//! A byte range is the one payload that crosses this service rather than
+//! sitting in the store already, and it crosses as a loan, never as message
+//! bytes: a range read is copied into a buffer from this service's own factory,
+//! sealed, and lent back with the reply; a range write arrives as the client's
+//! loan, beside the Directory copy in the request's own message, and is checked
+//! against the request's hash before a byte of it is stored.
+//!
+//! The store may be mirrored. A generation that grants a second block device
+//! at `MIRROR_SLOT`, on a boot that attached one the same size, gets every
+//! write on both disks through `object_store::Mirror`, and the service scrubs
+//! both copies once at open, rewriting from the good copy whatever rotted on
+//! one of them.
 
 extern crate alloc;
 
 use boot_contracts::gpt::{self, GptError};
-use boot_contracts::object_store::{BlockIo, IoError, ObjectStore};
+use boot_contracts::object_store::{
+    BlockIo, IoError, Mirror, ObjectStore, ScrubCursor, ScrubReport, StoreError,
+};
+use slime_components::fs_directory::{
+    self, ChunkSource, DirectoryError, Fetch, Head, Objects, Path, STAGING_BYTES,
+};
 use slime_proto::block::{self, WireBlockReply, WireBlockRequest};
 use slime_proto::{
-    capability_transfer::OBJECT_KIND_DIRECTORY,
-    fs::{
-        self, OFF_SNAPSHOT_COUNT, OFF_SNAPSHOT_ENTRY_HASH, OFF_SNAPSHOT_ENTRY_KIND,
-        OFF_SNAPSHOT_ENTRY_NAME, OFF_SNAPSHOT_ENTRY_NAME_LEN, OFF_SNAPSHOT_ENTRY_OBJECT_TYPE,
-        OFF_SNAPSHOT_ENTRY_PAYLOAD_LEN, OFF_SNAPSHOT_ENTRY_RESERVED1, OFF_SNAPSHOT_VERSION,
-        SNAPSHOT_BYTES, SNAPSHOT_ENTRY_BYTES, SNAPSHOT_HEADER, SNAPSHOT_MAGIC,
-        SNAPSHOT_OBJECT_TYPE, SNAPSHOT_VERSION, WireFsReply, WireFsRequest,
-    },
+    capability_transfer::{OBJECT_KIND_DIRECTORY, OBJECT_KIND_SHARED_BUFFER_LOAN},
+    fs::{self, MAX_FILE_BYTES, MAX_RANGE_BYTES, SNAPSHOT_OBJECT_TYPE, WireFsReply, WireFsRequest},
     valid_fs_request,
 };
 use slime_rt::{
-    CapabilityDisposition, ERR_WOULDBLOCK, MAX_CAPS_PER_MSG, MAX_DIRECTORY_PATH, MAX_MSG,
+    CapabilityDisposition, ERR_SUCCESS, ERR_WOULDBLOCK, MAX_CAPS_PER_MSG, MAX_DIRECTORY_PATH,
+    MAX_MSG,
 };
 
 // B59: rights bit numbering is generated from
-// `contracts/generation/v5/schema.zt`. The fs protocol carries a 32-bit rights
+// `contracts/generation/v6/schema.zt`. The fs protocol carries a 32-bit rights
 // field, so the generated `u64` constants are narrowed here rather than
 // re-spelled as separate `u32` literals.
 const RIGHT_TRANSFER: u32 = boot_contracts::generation::RIGHT_TRANSFER as u32;
@@ -48,10 +64,21 @@ const RIGHT_DIRECTORY_READ: u32 = boot_contracts::generation::RIGHT_DIRECTORY_RE
 const RIGHT_DIRECTORY_WRITE: u32 = boot_contracts::generation::RIGHT_DIRECTORY_WRITE as u32;
 const RIGHT_DIRECTORY_LIST: u32 = boot_contracts::generation::RIGHT_DIRECTORY_LIST as u32;
 const RIGHT_DIRECTORY_DERIVE: u32 = boot_contracts::generation::RIGHT_DIRECTORY_DERIVE as u32;
+const RIGHT_BUFFER_MAP: u64 = boot_contracts::generation::RIGHT_BUFFER_MAP;
 
 slime_rt::entry!(main);
 
 const RPC_SLOT: u32 = 0;
+/// This service's own unscoped view of the namespace, granted by the
+/// generation, through which a write commits.
+///
+/// A client's request copy proves it may write *there* — it carries
+/// `directoryWrite` and is scoped to the file's path — but the commit replaces
+/// the namespace-wide root, which the root refuses through a scoped view. So
+/// the service checks the proof and commits through this one, with the root
+/// the client's copy resolved as the expected value: if anything committed in
+/// between, the swap fails and the client sees a stale root.
+const NAMESPACE_SLOT: u32 = 1;
 /// The block device, granted to this component by the generation.
 ///
 /// Slot 2, not 1: the root installs a child's declared authority in a fixed
@@ -74,31 +101,25 @@ const READY_SLOT: u32 = 3;
 /// a supervision handle and closes this service on the same edge it announced
 /// readiness on, which is the only party that can: init spawned the client.
 const CLOSE: &[u8] = b"SLIME.FILESYSTEM.CLOSE";
+/// This service's own `SharedBufferFactory`, granted by the generation and
+/// bounded by its `sharedBufferBudget` entry: one buffer of `MAX_RANGE_BYTES`,
+/// which is all a range read ever has outstanding. A self-grant, like the view
+/// and the device, so the root places it at spawn and init supplies nothing.
+const BUFFER_FACTORY_SLOT: u32 = 4;
+/// The mirror's second disk: a block self-grant declared after the first, so
+/// the root gives it device 1. Optional — a generation that grants none, or a
+/// boot that attached one disk, leaves a slot no read succeeds on, and the
+/// store is the first disk alone.
+const MIRROR_SLOT: u32 = 5;
+const PAGE: u64 = 4096;
+/// Where a range read's buffer is mapped while it is filled, before the seal.
+const REPLY_BASE: u64 = 0x0000_0016_0000_0000;
+/// Where a client's range-write loan is mapped while it is copied out.
+const LOAN_BASE: u64 = 0x0000_0017_0000_0000;
 const SECTOR_BYTES: usize = 512;
-const MAX_OBJECT_PAYLOAD: u32 = 32 * 1024;
+const MAX_OBJECT_PAYLOAD: u32 = MAX_FILE_BYTES as u32;
 const ZERO_HASH: [u8; 32] = [0; 32];
 
-#[derive(Clone, Copy)]
-struct Entry {
-    kind: u8,
-    name_len: u8,
-    name: [u8; fs::MAX_NAME_BYTES],
-    object_type: u32,
-    payload_len: u32,
-    hash: [u8; 32],
-}
-
-impl Entry {
-    const EMPTY: Self = Self {
-        kind: 0,
-        name_len: 0,
-        name: [0; fs::MAX_NAME_BYTES],
-        object_type: 0,
-        payload_len: 0,
-        hash: ZERO_HASH,
-    };
-}
-
 fn main(_startup_arg: u32) {
     if open_store().is_err() {
         slime_rt::debug_write(b"[filesystem] fail: store open\n");
@@ -122,21 +143,76 @@ fn main(_startup_arg: u32) {
             ERR_WOULDBLOCK => slime_rt::yield_now(),
             n if n < 0 => slime_rt::exit(1),
             n => {
+                let message = &message[..n as usize];
                 // A directory capability has no kernel object to travel in the
                 // message, so its export arrives alone and is claimed here
                 // rather than read out of the received-capability array. That
-                // array carries only native Endpoint handles now (B46).
-                let claimed = slime_rt::capability_import().ok();
-                let (reply, received_directory, derived_cap) =
-                    handle(&message[..n as usize], claimed);
-                send_reply(reply, derived_cap);
+                // array carries only native Endpoint handles now (B46). Only a
+                // Directory is claimed, so a loan is never taken for one.
+                let claimed = slime_rt::capability_import_kind(OBJECT_KIND_DIRECTORY).ok();
+                let beside = claimed.and_then(|_| receive_beside(message));
+                let op = WireFsRequest::decode(message).map(|request| request.op);
+                let loan = beside.filter(|_| op == Some(fs::OP_WRITE_RANGE));
+                let pin = beside.filter(|_| op == Some(fs::OP_RESTORE));
+                let (reply, received_directory, attached) = handle(message, claimed, loan, pin);
+                // A loan is settled by its receiver whether or not its bytes
+                // were used, and before the reply: the client's buffer stays
+                // charged to it until then, so a client that sent its next
+                // range the moment it was answered would find its budget spent.
+                if let Some(slot) = loan
+                    && slime_rt::shared_buffer_return(slot) != ERR_SUCCESS
+                {
+                    slime_rt::exit(1);
+                }
+                send_reply(reply, attached);
                 drop_capability(received_directory);
+                drop_capability(pin);
             }
         }
     }
 }
 
-fn handle(message: &[u8], claimed: Option<u32>) -> (WireFsReply, Option<u32>, Option<u32>) {
+/// The capability a reply carries back to the client, if any.
+#[derive(Clone, Copy)]
+enum Attached {
+    /// A derived or pinned view, from `OP_DERIVE` or `OP_SNAPSHOT`.
+    Directory(u32),
+    /// A read-only loan of a range read's bytes.
+    Loan(u32),
+}
+
+impl Attached {
+    const fn slot(self) -> u32 {
+        match self {
+            Self::Directory(slot) | Self::Loan(slot) => slot,
+        }
+    }
+}
+
+/// Take up the capability a range write or a restore carries beside its
+/// Directory copy: a range write's loan, or the pin a restore proves its root
+/// with.
+///
+/// Both cross in the request's own message — the client finalizes every export
+/// before the one send — so the capability is either here already or was never
+/// sent, and nothing waits for it. It is claimed from the peer the Directory
+/// copy came from, so another client's export is never taken for it; a request
+/// that arrives without it gets an error reply from `dispatch`.
+fn receive_beside(message: &[u8]) -> Option<u32> {
+    let kind = match WireFsRequest::decode(message)?.op {
+        fs::OP_WRITE_RANGE => OBJECT_KIND_SHARED_BUFFER_LOAN,
+        fs::OP_RESTORE => OBJECT_KIND_DIRECTORY,
+        _ => return None,
+    };
+    slime_rt::capability_import_alongside(kind).ok()
+}
+
+fn handle(
+    message: &[u8],
+    claimed: Option<u32>,
+    loan: Option<u32>,
+    pin: Option<u32>,
+) -> (WireFsReply, Option<u32>, Option<Attached>) {
     let Some(directory_slot) = claimed else {
         return (reply(-2, 0, 0, 0, ZERO_HASH), None, None);
     };
@@ -151,6 +227,10 @@ fn handle(message: &[u8], claimed: Option<u32>) -> (WireFsReply, Option<u32>, Op
         fs::OP_READ => RIGHT_DIRECTORY_READ,
         fs::OP_WRITE => RIGHT_DIRECTORY_WRITE,
         fs::OP_DERIVE => RIGHT_DIRECTORY_DERIVE,
+        fs::OP_SNAPSHOT => RIGHT_DIRECTORY_READ,
+        fs::OP_RESTORE => RIGHT_DIRECTORY_WRITE,
+        fs::OP_READ_RANGE => RIGHT_DIRECTORY_READ,
+        fs::OP_WRITE_RANGE => RIGHT_DIRECTORY_WRITE,
         _ => return (reply(-1, 0, 0, 0, ZERO_HASH), Some(directory_slot), None),
     };
     let mut root = ZERO_HASH;
@@ -160,46 +240,33 @@ fn handle(message: &[u8], claimed: Option<u32>) -> (WireFsReply, Option<u32>, Op
     else {
         return (reply(-2, 0, 0, 0, ZERO_HASH), Some(directory_slot), None);
     };
-    if request.op == fs::OP_WRITE && scope_len != 0 {
+    // A restore replaces the namespace-wide root with a whole other tree,
+    // which only a holder of the whole tree may do.
+    if request.op == fs::OP_RESTORE && scope_len != 0 {
         return (reply(-2, 0, 0, 0, ZERO_HASH), Some(directory_slot), None);
     }
-    let mut root_entries = [Entry::EMPTY; fs::MAX_ENTRIES];
-    let entry_count = match load_snapshot(root, &mut root_entries) {
-        Ok(count) => count,
-        Err(status) => {
-            return (
-                reply(status, 0, 0, 0, ZERO_HASH),
-                Some(directory_slot),
-                None,
-            );
-        }
-    };
-    let mut entries = root_entries;
-    let scoped = match resolve_scope(
-        &root_entries,
-        entry_count,
-        &scope[..scope_len],
-        &mut entries,
-    ) {
-        Ok(count) => count,
-        Err(status) => {
-            return (
-                reply(status, 0, 0, 0, ZERO_HASH),
-                Some(directory_slot),
-                None,
-            );
-        }
+    // The root admitted the scope as a path; the segment bound is the
+    // contract's, which the root does not know.
+    let Ok(path) = Path::parse(&scope[..scope_len]) else {
+        return (reply(-1, 0, 0, 0, ZERO_HASH), Some(directory_slot), None);
     };
-    let (reply, derived_cap) = dispatch(request, directory_slot, &mut entries, scoped, root);
-    (reply, Some(directory_slot), derived_cap)
+    let (reply, attached) = dispatch(request, directory_slot, root, &path, loan, pin);
+    (reply, Some(directory_slot), attached)
 }
 
 fn operation_fields_valid(request: &WireFsRequest) -> bool {
     match request.op {
-        fs::OP_LIST | fs::OP_READ | fs::OP_DERIVE => {
+        fs::OP_LIST | fs::OP_READ | fs::OP_DERIVE | fs::OP_SNAPSHOT => {
             request.payload_len == 0 && request_hash(request) == ZERO_HASH
         }
         fs::OP_WRITE => request.payload_len <= MAX_OBJECT_PAYLOAD,
+        fs::OP_RESTORE => request.payload_len == 0 && request_hash(request) != ZERO_HASH,
+        fs::OP_READ_RANGE => {
+            request.payload_len as usize <= MAX_RANGE_BYTES && request_hash(request) == ZERO_HASH
+        }
+        fs::OP_WRITE_RANGE => {
+            request.payload_len as usize <= MAX_RANGE_BYTES && request_hash(request) != ZERO_HASH
+        }
         _ => false,
     }
 }
@@ -207,229 +274,277 @@ fn operation_fields_valid(request: &WireFsRequest) -> bool {
 fn dispatch(
     request: WireFsRequest,
     directory_slot: u32,
-    entries: &mut [Entry; fs::MAX_ENTRIES],
-    entry_count: usize,
     root: [u8; 32],
-) -> (WireFsReply, Option<u32>) {
-    let name = &request.name[..request.name_len as usize];
+    path: &Path<'_>,
+    loan: Option<u32>,
+    pin: Option<u32>,
+) -> (WireFsReply, Option<Attached>) {
+    let store = &mut Store;
     match request.op {
-        fs::OP_LIST => (reply(0, entry_count as u32, 0, 0, ZERO_HASH), None),
+        fs::OP_LIST => (
+            match fs_directory::resolve_directory(store, &root, path, path.depth()) {
+                Ok(directory) => reply(0, directory.count() as u32, 0, 0, ZERO_HASH),
+                Err(error) => failure(error),
+            },
+            None,
+        ),
         fs::OP_READ => (
-            match find_entry(entries, entry_count, name) {
-                Some(entry) if entry.kind == 1 => {
-                    reply(0, 0, entry.object_type, entry.payload_len, entry.hash)
-                }
-                _ => reply(-3, 0, 0, 0, ZERO_HASH),
+            match fs_directory::resolve_file(store, &root, path) {
+                Ok(entry) => reply(0, 0, entry.object_type, entry.payload_len, entry.hash),
+                Err(error) => failure(error),
             },
             None,
         ),
+        // The request copy is already scoped to the subdirectory, so the view
+        // handed back is that copy narrowed to the rights a reader needs — once
+        // the path is known to name a directory rather than a file.
         fs::OP_DERIVE => {
-            if !matches!(find_entry(entries, entry_count, name), Some(entry) if entry.kind == 2) {
-                return (reply(-3, 0, 0, 0, ZERO_HASH), None);
+            if path.depth() == 0 {
+                return (reply(-1, 0, 0, 0, ZERO_HASH), None);
+            }
+            if let Err(error) = fs_directory::resolve_directory(store, &root, path, path.depth()) {
+                return (failure(error), None);
             }
             match slime_rt::directory_derive(
                 directory_slot,
-                name,
+                b"",
                 RIGHT_DIRECTORY_READ
                     | RIGHT_DIRECTORY_LIST
                     | RIGHT_DIRECTORY_DERIVE
                     | RIGHT_TRANSFER,
             ) {
-                Ok(slot) => (reply(0, 0, 0, 0, ZERO_HASH), Some(slot)),
+                Ok(slot) => (
+                    reply(0, 0, 0, 0, ZERO_HASH),
+                    Some(Attached::Directory(slot)),
+                ),
                 Err(_) => (reply(-2, 0, 0, 0, ZERO_HASH), None),
             }
         }
-        fs::OP_WRITE => (
-            write_entry(request, directory_slot, entries, entry_count, root),
-            None,
-        ),
+        fs::OP_WRITE => (write_entry(request, root, path), None),
+        // The pin is the root this request was resolved against, so the reply
+        // names exactly the tree the snapshot capability will keep seeing. If
+        // another writer committed since, the root refuses the stale pin.
+        fs::OP_SNAPSHOT => {
+            let count = match fs_directory::resolve_directory(store, &root, path, path.depth()) {
+                Ok(directory) => directory.count() as u32,
+                Err(error) => return (failure(error), None),
+            };
+            match slime_rt::directory_snapshot(
+                directory_slot,
+                &root,
+                RIGHT_DIRECTORY_READ
+                    | RIGHT_DIRECTORY_LIST
+                    | RIGHT_DIRECTORY_DERIVE
+                    | RIGHT_TRANSFER,
+            ) {
+                Ok(slot) => (reply(0, count, 0, 0, root), Some(Attached::Directory(slot))),
+                Err(ERR_WOULDBLOCK) => (reply(-6, 0, 0, 0, ZERO_HASH), None),
+                Err(_) => (reply(-2, 0, 0, 0, ZERO_HASH), None),
+            }
+        }
+        fs::OP_RESTORE => match pin {
+            Some(pin) => (restore_root(request, directory_slot, root, pin), None),
+            None => (reply(-2, 0, 0, 0, ZERO_HASH), None),
+        },
+        fs::OP_READ_RANGE => read_range(request, root, path),
+        fs::OP_WRITE_RANGE => match loan {
+            Some(loan) => (write_range(request, root, path, loan), None),
+            None => (reply(-2, 0, 0, 0, ZERO_HASH), None),
+        },
         _ => (reply(-1, 0, 0, 0, ZERO_HASH), None),
     }
 }
 
-fn write_entry(
+/// Copy a range of the file out of the store and lend it to the client.
+///
+/// The reply names the file's hash, so the client knows which version of the
+/// file the bytes came from, and how many bytes the loan holds. A range that
+/// starts exactly at the file's end is empty and lends nothing.
+fn read_range(
     request: WireFsRequest,
-    directory_slot: u32,
-    entries: &mut [Entry; fs::MAX_ENTRIES],
-    entry_count: usize,
     root: [u8; 32],
-) -> WireFsReply {
-    let name = &request.name[..request.name_len as usize];
-    let hash = request_hash(&request);
-    let Ok((object_type, payload_len)) = store_stat(hash) else {
-        return reply(-3, 0, 0, 0, ZERO_HASH);
+    path: &Path<'_>,
+) -> (WireFsReply, Option<Attached>) {
+    let (file_staging, range_staging) = staging();
+    let out = &mut range_staging[..request.payload_len as usize];
+    let (file, len) = match fs_directory::read_range(
+        &mut Store,
+        &root,
+        path,
+        request.offset,
+        out,
+        file_staging,
+    ) {
+        Ok(read) => read,
+        Err(error) => return (failure(error), None),
     };
-    if payload_len != request.payload_len {
-        return reply(-1, 0, 0, 0, ZERO_HASH);
+    if len == 0 {
+        return (reply(0, 0, file.object_type, 0, file.hash), None);
     }
-    let index = match find_index(entries, entry_count, name) {
-        Some(index) if entries[index].kind == 1 => index,
-        Some(_) => return reply(-1, 0, 0, 0, ZERO_HASH),
-        None if entry_count < fs::MAX_ENTRIES => entry_count,
-        None => return reply(-4, 0, 0, 0, ZERO_HASH),
-    };
-    entries[index] = Entry {
-        kind: 1,
-        name_len: name.len() as u8,
-        name: request.name,
-        object_type,
-        payload_len,
-        hash,
-    };
-    let new_count = entry_count.max(index + 1);
-    sort_entries(entries, new_count);
-    let snapshot = encode_snapshot(entries, new_count);
-    let Ok(new_root) = store_put(SNAPSHOT_OBJECT_TYPE, &snapshot) else {
-        return reply(-5, 0, 0, 0, ZERO_HASH);
+    match lend(&out[..len]) {
+        Some(loan) => (
+            reply(0, 0, file.object_type, len as u32, file.hash),
+            Some(Attached::Loan(loan)),
+        ),
+        None => (reply(-2, 0, 0, 0, ZERO_HASH), None),
+    }
+}
+
+/// Put `bytes` in a sealed buffer and loan it read-only to the client.
+///
+/// The buffer is released as soon as the loan exists: its pages stay charged
+/// to this service until the client returns the loan, and nothing here needs
+/// the handle again, so the one buffer the budget allows is free for the next
+/// read the moment the client is done with this one.
+fn lend(bytes: &[u8]) -> Option<u32> {
+    let length = (bytes.len() as u64).div_ceil(PAGE) * PAGE;
+    let buffer =
+        slime_rt::shared_buffer_create(BUFFER_FACTORY_SLOT, (length / PAGE) as usize, true).ok()?;
+    let loan = if fill_and_seal(buffer.slot, bytes, length) {
+        slime_rt::shared_buffer_loan(buffer.slot, RPC_SLOT, 0, bytes.len() as u64, false).ok()
+    } else {
+        None
     };
-    match slime_rt::directory_commit(directory_slot, &root, &new_root) {
-        0 => reply(0, new_count as u32, object_type, payload_len, hash),
-        ERR_WOULDBLOCK => reply(-6, 0, 0, 0, ZERO_HASH),
-        _ => reply(-2, 0, 0, 0, ZERO_HASH),
+    if slime_rt::shared_buffer_release(buffer.slot) != ERR_SUCCESS {
+        slime_rt::exit(1);
     }
+    loan.map(|loan| loan.slot)
 }
 
-fn resolve_scope(
-    root_entries: &[Entry; fs::MAX_ENTRIES],
-    root_count: usize,
-    scope: &[u8],
-    out: &mut [Entry; fs::MAX_ENTRIES],
-) -> Result<usize, i32> {
-    if scope.is_empty() {
-        return Ok(root_count);
+/// Copy `bytes` into the first `length` bytes of `buffer` and seal it. A loan
+/// needs a sealed source: the client reads bytes this service has finished
+/// writing, and nothing here can change them afterwards.
+fn fill_and_seal(buffer: u32, bytes: &[u8], length: u64) -> bool {
+    if slime_rt::shared_buffer_map(buffer, REPLY_BASE, 0, length, true) != ERR_SUCCESS {
+        return false;
     }
-    let mut current = *root_entries;
-    let mut count = root_count;
-    for segment in scope.split(|byte| *byte == b'/') {
-        let entry = find_entry(&current, count, segment).ok_or(-3)?;
-        if entry.kind != 2 {
-            return Err(-3);
+    // SAFETY: the map above installed a writable mapping of `length` bytes at
+    // `REPLY_BASE`, at least `bytes.len()`, which stays until the unmap below.
+    unsafe {
+        let destination = REPLY_BASE as *mut u8;
+        for (index, byte) in bytes.iter().enumerate() {
+            destination.add(index).write_volatile(*byte);
         }
-        count = load_snapshot(entry.hash, out)?;
-        current = *out;
     }
-    *out = current;
-    Ok(count)
-}
-
-fn load_snapshot(root: [u8; 32], out: &mut [Entry; fs::MAX_ENTRIES]) -> Result<usize, i32> {
-    let mut bytes = [0u8; SNAPSHOT_BYTES];
-    store_get(root, &mut bytes)?;
-    decode_snapshot(&bytes, out)
+    slime_rt::shared_buffer_unmap(buffer, REPLY_BASE) == ERR_SUCCESS
+        && slime_rt::shared_buffer_seal(buffer) == ERR_SUCCESS
 }
 
-fn decode_snapshot(
-    bytes: &[u8; SNAPSHOT_BYTES],
-    entries: &mut [Entry; fs::MAX_ENTRIES],
-) -> Result<usize, i32> {
-    if bytes[..8] != SNAPSHOT_MAGIC
-        || u32::from_le_bytes(
-            bytes[OFF_SNAPSHOT_VERSION..OFF_SNAPSHOT_VERSION + 4]
-                .try_into()
-                .unwrap(),
-        ) != SNAPSHOT_VERSION
-    {
-        return Err(-7);
-    }
-    let count = u32::from_le_bytes(
-        bytes[OFF_SNAPSHOT_COUNT..OFF_SNAPSHOT_COUNT + 4]
-            .try_into()
-            .unwrap(),
-    ) as usize;
-    if count > fs::MAX_ENTRIES {
-        return Err(-7);
-    }
-    let mut previous: Option<&[u8]> = None;
-    for (index, entry) in entries.iter_mut().take(count).enumerate() {
-        let offset = SNAPSHOT_HEADER + index * SNAPSHOT_ENTRY_BYTES;
-        let kind = bytes[offset + OFF_SNAPSHOT_ENTRY_KIND];
-        let name_len = bytes[offset + OFF_SNAPSHOT_ENTRY_NAME_LEN] as usize;
-        let name_bytes = &bytes[offset + OFF_SNAPSHOT_ENTRY_NAME
-            ..offset + OFF_SNAPSHOT_ENTRY_NAME + fs::MAX_NAME_BYTES];
-        if !matches!(kind, 1 | 2)
-            || name_len == 0
-            || name_len > fs::MAX_NAME_BYTES
-            || name_bytes[name_len..].iter().any(|byte| *byte != 0)
-            || name_bytes[..name_len]
-                .iter()
-                .any(|byte| !(byte.is_ascii_alphanumeric() || matches!(*byte, b'.' | b'_' | b'-')))
-        {
-            return Err(-7);
-        }
-        if previous.is_some_and(|name| name >= &name_bytes[..name_len]) {
-            return Err(-7);
+/// Edit a range of the file with the bytes the client's loan carries, and
+/// commit the result.
+///
+/// The loan's bytes must hash to what the request names: the request is what
+/// the client's Directory copy was checked against, and a loan that disagrees
+/// with it is one this service cannot tell was meant for this write.
+fn write_range(request: WireFsRequest, root: [u8; 32], path: &Path<'_>, loan: u32) -> WireFsReply {
+    let (file_staging, range_staging) = staging();
+    let bytes = &mut range_staging[..request.payload_len as usize];
+    if slime_rt::shared_buffer_loan_map(loan, LOAN_BASE, 0, bytes.len() as u64) != ERR_SUCCESS {
+        return reply(-2, 0, 0, 0, ZERO_HASH);
+    }
+    // SAFETY: the loan map above installed a read-only mapping of exactly
+    // `bytes.len()` bytes at `LOAN_BASE`, which stays until the unmap below.
+    unsafe {
+        let source = LOAN_BASE as *const u8;
+        for (index, byte) in bytes.iter_mut().enumerate() {
+            *byte = source.add(index).read_volatile();
         }
-        let mut name = [0u8; fs::MAX_NAME_BYTES];
-        name.copy_from_slice(name_bytes);
-        let mut hash = [0u8; 32];
-        hash.copy_from_slice(
-            &bytes[offset + OFF_SNAPSHOT_ENTRY_HASH..offset + OFF_SNAPSHOT_ENTRY_RESERVED1],
-        );
-        *entry = Entry {
-            kind,
-            name_len: name_len as u8,
-            name,
-            object_type: u32::from_le_bytes(
-                bytes[offset + OFF_SNAPSHOT_ENTRY_OBJECT_TYPE
-                    ..offset + OFF_SNAPSHOT_ENTRY_PAYLOAD_LEN]
-                    .try_into()
-                    .unwrap(),
-            ),
-            payload_len: u32::from_le_bytes(
-                bytes[offset + OFF_SNAPSHOT_ENTRY_PAYLOAD_LEN..offset + OFF_SNAPSHOT_ENTRY_HASH]
-                    .try_into()
-                    .unwrap(),
-            ),
-            hash,
-        };
-        previous = Some(&entry.name[..name_len]);
     }
-    if bytes[SNAPSHOT_HEADER + count * SNAPSHOT_ENTRY_BYTES..]
-        .iter()
-        .any(|byte| *byte != 0)
-    {
-        return Err(-7);
+    if slime_rt::shared_buffer_unmap(loan, LOAN_BASE) != ERR_SUCCESS {
+        slime_rt::exit(1);
     }
-    Ok(count)
-}
-
-fn encode_snapshot(entries: &[Entry; fs::MAX_ENTRIES], count: usize) -> [u8; SNAPSHOT_BYTES] {
-    let mut bytes = [0u8; SNAPSHOT_BYTES];
-    bytes[..8].copy_from_slice(&SNAPSHOT_MAGIC);
-    bytes[OFF_SNAPSHOT_VERSION..OFF_SNAPSHOT_COUNT]
-        .copy_from_slice(&SNAPSHOT_VERSION.to_le_bytes());
-    bytes[OFF_SNAPSHOT_COUNT..SNAPSHOT_HEADER].copy_from_slice(&(count as u32).to_le_bytes());
-    for (index, entry) in entries.iter().take(count).enumerate() {
-        let offset = SNAPSHOT_HEADER + index * SNAPSHOT_ENTRY_BYTES;
-        bytes[offset + OFF_SNAPSHOT_ENTRY_KIND] = entry.kind;
-        bytes[offset + OFF_SNAPSHOT_ENTRY_NAME_LEN] = entry.name_len;
-        bytes[offset + OFF_SNAPSHOT_ENTRY_NAME
-            ..offset + OFF_SNAPSHOT_ENTRY_NAME + fs::MAX_NAME_BYTES]
-            .copy_from_slice(&entry.name);
-        bytes[offset + OFF_SNAPSHOT_ENTRY_OBJECT_TYPE..offset + OFF_SNAPSHOT_ENTRY_PAYLOAD_LEN]
-            .copy_from_slice(&entry.object_type.to_le_bytes());
-        bytes[offset + OFF_SNAPSHOT_ENTRY_PAYLOAD_LEN..offset + OFF_SNAPSHOT_ENTRY_HASH]
-            .copy_from_slice(&entry.payload_len.to_le_bytes());
-        bytes[offset + OFF_SNAPSHOT_ENTRY_HASH..offset + OFF_SNAPSHOT_ENTRY_RESERVED1]
-            .copy_from_slice(&entry.hash);
+    if boot_contracts::sha256::digest(bytes) != request_hash(&request) {
+        return reply(-1, 0, 0, 0, ZERO_HASH);
+    }
+    let (new_root, count, file) = match fs_directory::write_range(
+        &mut Store,
+        &root,
+        path,
+        request.offset,
+        bytes,
+        file_staging,
+    ) {
+        Ok(written) => written,
+        Err(error) => return failure(error),
+    };
+    match slime_rt::directory_commit(NAMESPACE_SLOT, &root, &new_root) {
+        0 => reply(
+            0,
+            count as u32,
+            file.object_type,
+            file.payload_len,
+            file.hash,
+        ),
+        ERR_WOULDBLOCK => reply(-6, 0, 0, 0, ZERO_HASH),
+        _ => reply(-2, 0, 0, 0, ZERO_HASH),
     }
-    bytes
 }
 
-fn find_entry<'a>(entries: &'a [Entry], count: usize, name: &[u8]) -> Option<&'a Entry> {
-    find_index(entries, count, name).map(|index| &entries[index])
+/// Swap the namespace root back to an earlier snapshot's root.
+///
+/// Nothing is rebuilt: a snapshot root is already a committed directory object,
+/// so restoring it is the same compare-and-swap a write ends in. The hash must
+/// be the root `pin` holds, unscoped — a hash alone names any tree in the
+/// store, including ones this client was never shown — and must name a
+/// directory that decodes page by page, so a restore cannot install an
+/// ordinary object, or a damaged tree, as the root every reader resolves from.
+/// The root checks the pin again at the swap; this check is what lets the
+/// service refuse before walking the tree.
+fn restore_root(
+    request: WireFsRequest,
+    directory_slot: u32,
+    root: [u8; 32],
+    pin: u32,
+) -> WireFsReply {
+    let hash = request_hash(&request);
+    let mut pinned = ZERO_HASH;
+    let mut scope = [0u8; MAX_DIRECTORY_PATH];
+    match slime_rt::directory_inspect(pin, RIGHT_DIRECTORY_READ, &mut pinned, &mut scope) {
+        Ok(0) if pinned == hash => {}
+        _ => return reply(-2, 0, 0, 0, ZERO_HASH),
+    }
+    match store_stat(hash) {
+        Ok((SNAPSHOT_OBJECT_TYPE, _)) => {}
+        Ok(_) => return reply(-1, 0, 0, 0, ZERO_HASH),
+        Err(_) => return reply(-3, 0, 0, 0, ZERO_HASH),
+    }
+    let store = &mut Store;
+    let restored = match Head::load(store, &hash) {
+        Ok(restored) => restored,
+        Err(error) => return failure(error),
+    };
+    if let Err(error) = restored.verify(store) {
+        return failure(error);
+    }
+    match slime_rt::directory_restore(directory_slot, pin, &root, &hash) {
+        0 => reply(0, restored.count() as u32, SNAPSHOT_OBJECT_TYPE, 0, hash),
+        ERR_WOULDBLOCK => reply(-6, 0, 0, 0, ZERO_HASH),
+        _ => reply(-2, 0, 0, 0, ZERO_HASH),
+    }
 }
 
-fn find_index(entries: &[Entry], count: usize, name: &[u8]) -> Option<usize> {
-    entries[..count]
-        .iter()
-        .position(|entry| &entry.name[..entry.name_len as usize] == name)
+fn write_entry(request: WireFsRequest, root: [u8; 32], path: &Path<'_>) -> WireFsReply {
+    let hash = request_hash(&request);
+    let Ok((object_type, payload_len)) = store_stat(hash) else {
+        return reply(-3, 0, 0, 0, ZERO_HASH);
+    };
+    if payload_len != request.payload_len {
+        return reply(-1, 0, 0, 0, ZERO_HASH);
+    }
+    let (new_root, count) =
+        match fs_directory::write_file(&mut Store, &root, path, object_type, payload_len, hash) {
+            Ok(written) => written,
+            Err(error) => return failure(error),
+        };
+    match slime_rt::directory_commit(NAMESPACE_SLOT, &root, &new_root) {
+        0 => reply(0, count as u32, object_type, payload_len, hash),
+        ERR_WOULDBLOCK => reply(-6, 0, 0, 0, ZERO_HASH),
+        _ => reply(-2, 0, 0, 0, ZERO_HASH),
+    }
 }
 
-fn sort_entries(entries: &mut [Entry], count: usize) {
-    entries[..count].sort_unstable_by(|left, right| {
-        left.name[..left.name_len as usize].cmp(&right.name[..right.name_len as usize])
-    });
+fn failure(error: DirectoryError) -> WireFsReply {
+    reply(error.status(), 0, 0, 0, ZERO_HASH)
 }
 
 fn request_hash(request: &WireFsRequest) -> [u8; 32] {
@@ -455,28 +570,90 @@ fn hash_words(hash: [u8; 32]) -> (u64, u64, u64, u64) {
 
 /// The object store, opened once over the granted block capability.
 ///
-/// A `static mut` because the three helpers below are called from deep inside
-/// the request handlers the oracle wrote, and threading a `&mut ObjectStore`
-/// through all of them would be a rewrite of code whose whole value is that it
-/// is unchanged. The component is single-threaded and the store is opened
-/// before the serve loop starts.
-static mut STORE: Option<(ObjectStore, BlockCapability)> = None;
+/// A `static mut` because the helpers below are reached from deep inside the
+/// request handlers and the directory layout's page walks, and threading a
+/// `&mut ObjectStore` through all of them would put the store in every
+/// signature of code that only ever asks it for one object. The component is
+/// single-threaded and the store is opened before the serve loop starts.
+static mut STORE: Option<(ObjectStore, Disk)> = None;
+
+/// A whole file — or a chunked file's manifest and one chunk — and one range
+/// of it, as a range operation holds them. Static rather than on the stack,
+/// which is far smaller than either, and rather than on the heap, which frees
+/// only its newest allocation and would run out a few requests in.
+static mut FILE_STAGING: [u8; STAGING_BYTES] = [0; STAGING_BYTES];
+static mut RANGE_STAGING: [u8; MAX_RANGE_BYTES] = [0; MAX_RANGE_BYTES];
+
+fn staging() -> (&'static mut [u8; STAGING_BYTES], &'static mut [u8]) {
+    let file = &raw mut FILE_STAGING;
+    // SAFETY: single-threaded, and each request handler takes these once and
+    // is done with them before it returns.
+    unsafe {
+        (
+            &mut *file,
+            core::slice::from_raw_parts_mut((&raw mut RANGE_STAGING).cast(), MAX_RANGE_BYTES),
+        )
+    }
+}
 
 /// Open the store. Called once, before any request is served.
+///
+/// A second disk is mirrored only when it is the first one's size: the mirror
+/// addresses both at the same LBAs, so a disk partitioned differently is
+/// refused rather than written. Opening a mirror already reconciles the
+/// superblock slots; the scrub that follows checks every record on both.
 fn open_store() -> Result<(), i32> {
-    let mut io = BlockCapability;
-    let capacity = device_capacity(&mut io).ok_or(-1)?;
+    let mut primary = BlockCapability(BLOCK_SLOT);
+    let capacity = device_capacity(&mut primary).ok_or(-1)?;
+    let mut secondary = BlockCapability(MIRROR_SLOT);
+    let mut io = match device_capacity(&mut secondary) {
+        None => Disk::Single(primary),
+        Some(mirrored) if mirrored == capacity => Disk::Mirrored(Mirror::new(primary, secondary)),
+        Some(_) => {
+            slime_rt::debug_write(b"[filesystem] fail: mirror size differs\n");
+            return Err(-1);
+        }
+    };
     let mut reader = |lba: u64, out: &mut [u8; SECTOR_BYTES]| -> Result<(), GptError> {
         io.read_sector(lba, out).map_err(|_| GptError::Device)
     };
-    let selected = gpt::validate_store_partition(&mut reader, capacity).map_err(|_| -1)?;
+    let selected = gpt::validate_store_partition(&mut reader, capacity, None).map_err(|_| -1)?;
     let store = ObjectStore::open(&mut io, &selected.partition).map_err(|_| -1)?;
+    if io.copies() > 1 {
+        let mut cursor = ScrubCursor::new();
+        let mut report = ScrubReport::default();
+        store
+            .scrub_step(&mut io, &mut cursor, u64::MAX, &mut report)
+            .map_err(|_| -1)?;
+        slime_rt::debug_write(b"[filesystem] mirrored copies=2 records=");
+        write_decimal(report.records_checked);
+        slime_rt::debug_write(b" repaired=");
+        write_decimal(report.repaired);
+        slime_rt::debug_write(b" damaged=");
+        write_decimal(report.damaged.len());
+        slime_rt::debug_write(b"\n");
+    }
     // SAFETY: single-threaded, and this runs before the serve loop.
     unsafe { STORE = Some((store, io)) };
     Ok(())
 }
 
-fn with_store<T>(body: impl FnOnce(&mut ObjectStore, &mut BlockCapability) -> T) -> T {
+fn write_decimal(value: usize) {
+    let mut digits = [0u8; 20];
+    let mut start = digits.len();
+    let mut rest = value;
+    loop {
+        start -= 1;
+        digits[start] = b'0' + (rest % 10) as u8;
+        rest /= 10;
+        if rest == 0 {
+            break;
+        }
+    }
+    slime_rt::debug_write(&digits[start..]);
+}
+
+fn with_store<T>(body: impl FnOnce(&mut ObjectStore, &mut Disk) -> T) -> T {
     // SAFETY: as `open_store`. The reference does not escape `body`.
     let (store, io) = unsafe { (&raw mut STORE).as_mut() }
         .and_then(Option::as_mut)
@@ -488,27 +665,128 @@ fn store_stat(hash: [u8; 32]) -> Result<(u32, u32), i32> {
     with_store(|store, _| store.stat(&hash).ok_or(-2))
 }
 
-fn store_get(hash: [u8; 32], out: &mut [u8]) -> Result<(), i32> {
-    with_store(|store, io| match store.get(io, &hash, out) {
-        Ok((_, len)) if len == out.len() => Ok(()),
-        Ok(_) => Err(-1),
-        Err(_) => Err(-2),
-    })
+/// The opened store, as the directory layout reads and writes it.
+struct Store;
+
+impl Objects for Store {
+    fn get(&mut self, hash: &[u8; 32], out: &mut [u8]) -> Result<(u32, usize), DirectoryError> {
+        with_store(|store, io| store.get(io, hash, out).map_err(|_| DirectoryError::Store))
+    }
+
+    fn put(&mut self, object_type: u32, payload: &[u8]) -> Result<[u8; 32], DirectoryError> {
+        with_store(|store, io| {
+            store
+                .put_or_reuse(io, object_type, payload)
+                .map(|(hash, _)| hash)
+                .map_err(|_| DirectoryError::Persist)
+        })
+    }
+
+    /// `put_large`, which asks `source` for each chunk twice and holds one at
+    /// a time. What `source` refused is kept aside and reported as itself
+    /// rather than as the store's failure to persist.
+    fn put_chunked(
+        &mut self,
+        object_type: u32,
+        len: usize,
+        source: &mut ChunkSource<'_>,
+    ) -> Result<[u8; 32], DirectoryError> {
+        let mut refused = None;
+        let written = with_store(|store, io| {
+            store.put_large(io, object_type, len as u64, |store, io, offset, out| {
+                source(&mut Fetched(store, io), offset as usize, out).map_err(|error| {
+                    refused = Some(error);
+                    StoreError::HashMismatch
+                })
+            })
+        });
+        match (written, refused) {
+            (Ok(hash), _) => Ok(hash),
+            (Err(_), Some(error)) => Err(error),
+            (Err(_), None) => Err(DirectoryError::Persist),
+        }
+    }
 }
 
-fn store_put(object_type: u32, payload: &[u8]) -> Result<[u8; 32], i32> {
-    with_store(|store, io| store.put(io, object_type, payload).map_err(|_| -1))
+/// The store as a chunk source reads it while `put_large` is writing.
+struct Fetched<'a>(&'a ObjectStore, &'a mut Disk);
+
+impl Fetch for Fetched<'_> {
+    fn get(&mut self, hash: &[u8; 32], out: &mut [u8]) -> Result<(u32, usize), DirectoryError> {
+        self.0
+            .get(self.1, hash, out)
+            .map_err(|_| DirectoryError::Store)
+    }
+}
+
+/// The store's device: one disk, or two behind a mirror.
+enum Disk {
+    Single(BlockCapability),
+    Mirrored(Mirror<BlockCapability, BlockCapability>),
 }
 
-/// The device, reached through the granted capability.
-struct BlockCapability;
+impl BlockIo for Disk {
+    fn read_sector(&mut self, lba: u64, out: &mut [u8; SECTOR_BYTES]) -> Result<(), IoError> {
+        match self {
+            Self::Single(disk) => disk.read_sector(lba, out),
+            Self::Mirrored(mirror) => mirror.read_sector(lba, out),
+        }
+    }
+
+    fn write_sector(&mut self, lba: u64, data: &[u8; SECTOR_BYTES]) -> Result<(), IoError> {
+        match self {
+            Self::Single(disk) => disk.write_sector(lba, data),
+            Self::Mirrored(mirror) => mirror.write_sector(lba, data),
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), IoError> {
+        match self {
+            Self::Single(disk) => disk.flush(),
+            Self::Mirrored(mirror) => mirror.flush(),
+        }
+    }
+
+    fn copies(&self) -> usize {
+        match self {
+            Self::Single(disk) => disk.copies(),
+            Self::Mirrored(mirror) => mirror.copies(),
+        }
+    }
+
+    fn read_copy(
+        &mut self,
+        copy: usize,
+        lba: u64,
+        out: &mut [u8; SECTOR_BYTES],
+    ) -> Result<(), IoError> {
+        match self {
+            Self::Single(disk) => disk.read_copy(copy, lba, out),
+            Self::Mirrored(mirror) => mirror.read_copy(copy, lba, out),
+        }
+    }
+
+    fn write_copy(
+        &mut self,
+        copy: usize,
+        lba: u64,
+        data: &[u8; SECTOR_BYTES],
+    ) -> Result<(), IoError> {
+        match self {
+            Self::Single(disk) => disk.write_copy(copy, lba, data),
+            Self::Mirrored(mirror) => mirror.write_copy(copy, lba, data),
+        }
+    }
+}
+
+/// A device, reached through the granted capability in this slot.
+struct BlockCapability(u32);
 
 impl BlockIo for BlockCapability {
     fn read_sector(&mut self, lba: u64, out: &mut [u8; SECTOR_BYTES]) -> Result<(), IoError> {
         let request = block_request(block::OP_READ, lba);
         let mut reply = [0u8; block::REPLY_LEN];
-        let status =
-            slime_rt::block_transact_sector(BLOCK_SLOT, &request.encode(), &mut reply, out);
+        let status = slime_rt::block_transact_sector(self.0, &request.encode(), &mut reply, out);
         if status < 0 || decode_block_reply(&reply).sectors_done != 1 {
             return Err(IoError::Device);
         }
@@ -518,8 +796,7 @@ impl BlockIo for BlockCapability {
     fn write_sector(&mut self, lba: u64, data: &[u8; SECTOR_BYTES]) -> Result<(), IoError> {
         let request = block_request(block::OP_WRITE, lba);
         let mut reply = [0u8; block::REPLY_LEN];
-        let status =
-            slime_rt::block_transact_write(BLOCK_SLOT, &request.encode(), data, &mut reply);
+        let status = slime_rt::block_transact_write(self.0, &request.encode(), data, &mut reply);
         if status < 0 || decode_block_reply(&reply).sectors_done != 1 {
             return Err(IoError::Device);
         }
@@ -529,7 +806,7 @@ impl BlockIo for BlockCapability {
     fn flush(&mut self) -> Result<(), IoError> {
         let request = block_request(block::OP_FLUSH, 0);
         let mut reply = [0u8; block::REPLY_LEN];
-        if slime_rt::block_transact(BLOCK_SLOT, &request.encode(), &mut reply) < 0 {
+        if slime_rt::block_transact(self.0, &request.encode(), &mut reply) < 0 {
             return Err(IoError::Device);
         }
         Ok(())
@@ -603,11 +880,11 @@ fn reply(
     }
 }
 
-fn send_reply(reply: WireFsReply, derived_cap: Option<u32>) {
+fn send_reply(reply: WireFsReply, attached: Option<Attached>) {
     let encoded = reply.encode();
     loop {
-        let result = match derived_cap {
-            Some(slot) => slime_rt::capability_delegate(
+        let result = match attached {
+            Some(Attached::Directory(slot)) => slime_rt::capability_delegate(
                 RPC_SLOT,
                 slot,
                 CapabilityDisposition::Move,
@@ -631,12 +908,22 @@ fn send_reply(reply: WireFsReply, derived_cap: Option<u32>) {
                 ),
                 &encoded,
             ),
+            // The client may only map what it was lent; it has nothing to pass
+            // on and nothing to write.
+            Some(Attached::Loan(slot)) => slime_rt::capability_delegate(
+                RPC_SLOT,
+                slot,
+                CapabilityDisposition::Move,
+                OBJECT_KIND_SHARED_BUFFER_LOAN,
+                RIGHT_BUFFER_MAP,
+                &encoded,
+            ),
             None => slime_rt::send(RPC_SLOT, &encoded, &[]),
         };
         match result {
             ERR_WOULDBLOCK => slime_rt::yield_now(),
             result if result < 0 => {
-                drop_capability(derived_cap);
+                drop_capability(attached.map(Attached::slot));
                 slime_rt::exit(1);
             }
             _ => return,
@@ -11,13 +11,24 @@
 //!
 //! The arms:
 //!
-//! * LIST and INSPECT return the live root's identity;
+//! * LIST and INSPECT return the live root's identity, and LIST of it names
+//!   the candidate, the generation whose release names it as parent;
 //! * INSPECT of a generation no closure contains is refused;
 //! * STAGE of the candidate succeeds; STAGE of an unknown generation is refused
 //!   *before* BootState changes, which the gate checks against the disk image;
 //! * ROLLBACK clears the staged generation;
 //! * STAGE then SELECT promotes, and SELECT naming the wrong generation is
-//!   refused;
+//!   refused; STAGE of the generation the promotion superseded, held and
+//!   signed but released at a sequence already accepted, is refused unverified;
+//! * a bisect from the promoted candidate to the tip of the fixture's release
+//!   chain, found by LIST, stages each midpoint, refuses a bound no release carries and a
+//!   verdict on anything but the staged midpoint, names the first bad
+//!   generation, and is reset;
+//! * EXPORT of a generation the store does not hold is refused; EXPORT of the
+//!   candidate's child writes it, its release and the root's state to the
+//!   archive disk, and IMPORT reads the archive back and admits that child;
+//! * ROTATE_TRUST of the fixture's signed rotation installs trust root version
+//!   2, and replaying it is refused, since it was signed against version 1;
 //! * a direct `BlockTransact` is refused, because no slot this component holds
 //!   names a device — the authority claim, checked rather than asserted.
 
@@ -29,12 +40,32 @@ const RPC_SLOT: u32 = 0;
 /// A slot naming no device — every slot this component holds, in fact.
 const NO_DEVICE_SLOT: u32 = RPC_SLOT;
 
-/// Kept in step with `sel4-generation-manager`.
-const KNOWN_GOOD: [u8; 32] = [0x11; 32];
-const CANDIDATE: [u8; 32] = [0x22; 32];
 const UNKNOWN: [u8; 32] = [0x99; 32];
+/// Kept in step with the bisect chain in `build-store-fixture.py`: seven
+/// generations past the candidate, each the previous one's child. Their
+/// identities are content hashes, so they are found by LIST, as the candidate
+/// is.
+const BISECT_CHAIN: usize = 7;
+/// What the tester knows and the manager has to find: generations from this
+/// one on, counted past the candidate, are bad.
+const FIRST_BAD: usize = 4;
+/// The first midpoint of the eight-generation chain, and the verdicts a chain
+/// that long can need.
+const FIRST_MIDPOINT: usize = 2;
+const BISECT_VERDICTS: u32 = 3;
+/// The fixture's signed trust-root rotation, by store identity: hashed by
+/// `build-sel4.py` from the record `build-store-fixture.py` seeds. Content
+/// addressed like every identity here, and there is no operation that lists
+/// rotations, so it is compiled in.
+const ROTATION: [u8; 32] = match option_env!("SLIME_GENERATION_ROTATION") {
+    Some(hex) => decode_hex32(hex),
+    None => [0; 32],
+};
+/// The version the rotation installs: the compiled-in root's, plus one.
+const ROTATED_VERSION: u32 = 2;
 
 const STATUS_OK: i32 = 0;
+const STATUS_BAD_REQUEST: i32 = -1;
 const STATUS_UNKNOWN_GENERATION: i32 = -2;
 const STATUS_NO_PENDING: i32 = -3;
 
@@ -46,11 +77,19 @@ fn main(_startup_arg: u32) {
         slime_rt::exit(0);
     }
 
-    // LIST: the live root, through the service.
+    // LIST: the live root, through the service, and the candidate after it.
+    // Identities are content hashes, so the service is the only place this
+    // component can learn them.
     let listed = call(generation::OP_LIST, [0; 32]);
-    if listed.status != STATUS_OK || identity_of(&listed) != KNOWN_GOOD {
+    let known_good = identity_of(&listed);
+    if listed.status != STATUS_OK || known_good == [0; 32] {
         fail(b"list");
     }
+    let listed = call(generation::OP_LIST, known_good);
+    if listed.status != STATUS_OK || identity_of(&listed) == known_good {
+        fail(b"list the candidate");
+    }
+    let candidate = identity_of(&listed);
     slime_rt::debug_write(b"[sel4-generation-client] listed the known-good root\n");
 
     // INSPECT of a generation the closure does not contain.
@@ -67,14 +106,14 @@ fn main(_startup_arg: u32) {
     slime_rt::debug_write(b"[sel4-generation-client] unknown stage refused\n");
 
     // STAGE the candidate, then roll it back.
-    let staged = call(generation::OP_STAGE, CANDIDATE);
-    if staged.status != STATUS_OK || identity_of(&staged) != CANDIDATE {
+    let staged = call(generation::OP_STAGE, candidate);
+    if staged.status != STATUS_OK || identity_of(&staged) != candidate {
         fail(b"stage");
     }
     slime_rt::debug_write(b"[sel4-generation-client] staged the candidate\n");
 
     let rolled = call(generation::OP_ROLLBACK, [0; 32]);
-    if rolled.status != STATUS_OK || identity_of(&rolled) != KNOWN_GOOD {
+    if rolled.status != STATUS_OK || identity_of(&rolled) != known_good {
         fail(b"rollback");
     }
     slime_rt::debug_write(b"[sel4-generation-client] rolled back to known-good\n");
@@ -88,20 +127,31 @@ fn main(_startup_arg: u32) {
 
     // Stage again, then confirm health. SELECT naming the wrong generation is
     // refused: only the generation actually staged may be promoted.
-    if call(generation::OP_STAGE, CANDIDATE).status != STATUS_OK {
+    if call(generation::OP_STAGE, candidate).status != STATUS_OK {
         fail(b"restage");
     }
-    if call(generation::OP_SELECT, KNOWN_GOOD).status != STATUS_UNKNOWN_GENERATION {
+    if call(generation::OP_SELECT, known_good).status != STATUS_UNKNOWN_GENERATION {
         fail(b"wrong select accepted");
     }
     slime_rt::debug_write(b"[sel4-generation-client] wrong select refused\n");
 
-    let selected = call(generation::OP_SELECT, CANDIDATE);
-    if selected.status != STATUS_OK || identity_of(&selected) != CANDIDATE {
+    let selected = call(generation::OP_SELECT, candidate);
+    if selected.status != STATUS_OK || identity_of(&selected) != candidate {
         fail(b"select");
     }
     slime_rt::debug_write(b"[sel4-generation-client] promoted the candidate\n");
 
+    // The generation the promotion superseded is still held and still signed,
+    // but its release is one this machine has moved past.
+    if call(generation::OP_STAGE, known_good).status != STATUS_BAD_REQUEST {
+        fail(b"superseded stage accepted");
+    }
+    slime_rt::debug_write(b"[sel4-generation-client] superseded stage refused\n");
+
+    bisect(candidate);
+    archive(candidate);
+    rotate();
+
     // The authority claim. This component was granted one endpoint; there is no
     // slot it holds that names a block device, so it cannot forge a transition
     // even though it knows the on-disk format perfectly well.
@@ -125,6 +175,150 @@ fn main(_startup_arg: u32) {
     slime_rt::debug_write(b"[sel4-generation-client] generation client complete\n");
 }
 
+/// Bisect from the candidate, now known-good, to the tip of the release chain.
+/// This component plays the tester: it judges each staged midpoint against
+/// `FIRST_BAD`, which only it knows.
+fn bisect(candidate: [u8; 32]) {
+    let mut chain = [[0u8; 32]; BISECT_CHAIN];
+    let mut parent = candidate;
+    for link in &mut chain {
+        let listed = call(generation::OP_LIST, parent);
+        if listed.status != STATUS_OK || identity_of(&listed) == parent {
+            fail(b"list the bisect chain");
+        }
+        *link = identity_of(&listed);
+        parent = *link;
+    }
+    let first_bad = chain[FIRST_BAD];
+
+    if call(generation::OP_BISECT_STATUS, [0; 32]).status != STATUS_NO_PENDING {
+        fail(b"a bisect session before any bound");
+    }
+    if call(generation::OP_BISECT_GOOD, candidate).status != STATUS_OK {
+        fail(b"bisect good bound");
+    }
+    // No release names it, so there is no chain to walk back from it.
+    if call(generation::OP_BISECT_BAD, UNKNOWN).status != STATUS_UNKNOWN_GENERATION {
+        fail(b"bisect bound without a release accepted");
+    }
+    slime_rt::debug_write(b"[sel4-generation-client] bisect bound without a release refused\n");
+
+    let mut step = call(generation::OP_BISECT_BAD, chain[BISECT_CHAIN - 1]);
+    if step.status != STATUS_OK
+        || step.flags & generation::REPLY_FLAG_STAGED == 0
+        || identity_of(&step) != chain[FIRST_MIDPOINT]
+        || step.count != BISECT_VERDICTS
+    {
+        fail(b"bisect start");
+    }
+    slime_rt::debug_write(b"[sel4-generation-client] bisect staged the midpoint\n");
+
+    // Only the staged midpoint can be judged: a verdict on another generation
+    // would be about a boot that never happened.
+    if call(generation::OP_BISECT_GOOD, chain[0]).status != STATUS_UNKNOWN_GENERATION {
+        fail(b"verdict on an unstaged generation accepted");
+    }
+    slime_rt::debug_write(b"[sel4-generation-client] verdict on an unstaged generation refused\n");
+
+    let mut verdicts = 0;
+    while step.flags & generation::REPLY_FLAG_BISECT_DONE == 0 {
+        let staged = identity_of(&step);
+        if step.flags & generation::REPLY_FLAG_STAGED == 0 || verdicts == BISECT_VERDICTS {
+            fail(b"bisect midpoint");
+        }
+        let op = if chain[FIRST_BAD..].contains(&staged) {
+            generation::OP_BISECT_BAD
+        } else {
+            generation::OP_BISECT_GOOD
+        };
+        step = call(op, staged);
+        verdicts += 1;
+        // STATUS reads the session back from the store, where the verdict just
+        // recorded must already be.
+        let status = call(generation::OP_BISECT_STATUS, [0; 32]);
+        if step.status != STATUS_OK || status != step || step.generation_number != verdicts {
+            fail(b"bisect verdict");
+        }
+    }
+    // The search promotes nothing: the known-good root is still the one it
+    // started from.
+    if identity_of(&step) != first_bad
+        || identity_of(&call(generation::OP_LIST, [0; 32])) != candidate
+    {
+        fail(b"bisect result");
+    }
+    slime_rt::debug_write(b"[sel4-generation-client] bisect named the first bad generation\n");
+
+    if call(generation::OP_BISECT_BAD, first_bad).status != STATUS_BAD_REQUEST {
+        fail(b"verdict after the search accepted");
+    }
+    if call(generation::OP_BISECT_RESET, [0; 32]).status != STATUS_OK
+        || call(generation::OP_BISECT_STATUS, [0; 32]).status != STATUS_NO_PENDING
+    {
+        fail(b"bisect reset");
+    }
+    slime_rt::debug_write(b"[sel4-generation-client] bisect session reset\n");
+}
+
+/// Export the candidate's child to the archive disk and import it back. The
+/// child is released past the sequence the promotion accepted and names the
+/// candidate as parent, so the archive is one this machine would admit; its
+/// records are already held, so the import reuses them rather than growing the
+/// store, which the gate checks.
+fn archive(candidate: [u8; 32]) {
+    if call(generation::OP_EXPORT, UNKNOWN).status != STATUS_UNKNOWN_GENERATION {
+        fail(b"unknown export accepted");
+    }
+    slime_rt::debug_write(b"[sel4-generation-client] unknown export refused\n");
+
+    let listed = call(generation::OP_LIST, candidate);
+    let child = identity_of(&listed);
+    if listed.status != STATUS_OK || child == candidate {
+        fail(b"list the export");
+    }
+    let exported = call(generation::OP_EXPORT, child);
+    if exported.status != STATUS_OK || identity_of(&exported) != child || exported.count == 0 {
+        fail(b"export");
+    }
+    slime_rt::debug_write(b"[sel4-generation-client] exported the archive\n");
+
+    // The archive names its generation, so the request names none.
+    let imported = call(generation::OP_IMPORT, [0; 32]);
+    if imported.status != STATUS_OK || identity_of(&imported) != child || imported.count == 0 {
+        fail(b"import");
+    }
+    slime_rt::debug_write(b"[sel4-generation-client] imported the archive\n");
+}
+
+/// Rotate the trust root once the bisect has left the promoted candidate as
+/// the root. The fixture re-released the candidate under the replacement, so
+/// the manager can rotate without stranding the generation it would boot.
+fn rotate() {
+    if ROTATION == [0; 32] {
+        fail(b"no rotation identity compiled in");
+    }
+    if call(generation::OP_ROTATE_TRUST, UNKNOWN).status != STATUS_UNKNOWN_GENERATION {
+        fail(b"unknown rotation accepted");
+    }
+    slime_rt::debug_write(b"[sel4-generation-client] unknown rotation refused\n");
+
+    let rotated = call(generation::OP_ROTATE_TRUST, ROTATION);
+    if rotated.status != STATUS_OK
+        || rotated.count != ROTATED_VERSION
+        || identity_of(&rotated) == [0; 32]
+    {
+        fail(b"rotate");
+    }
+    slime_rt::debug_write(b"[sel4-generation-client] rotated the trust root\n");
+
+    // The record names version 1 as the root it replaces, so against the root
+    // it just installed it is a stale rotation, not a second one.
+    if call(generation::OP_ROTATE_TRUST, ROTATION).status != STATUS_BAD_REQUEST {
+        fail(b"replayed rotation accepted");
+    }
+    slime_rt::debug_write(b"[sel4-generation-client] replayed rotation refused\n");
+}
+
 /// One request/reply round trip with the manager.
 fn call(op: u8, identity: [u8; 32]) -> WireGenerationReply {
     let words = identity_words(identity);
@@ -202,6 +396,27 @@ fn identity_of(reply: &WireGenerationReply) -> [u8; 32] {
     identity
 }
 
+const fn decode_hex32(value: &str) -> [u8; 32] {
+    let bytes = value.as_bytes();
+    assert!(bytes.len() == 64);
+    let mut out = [0u8; 32];
+    let mut index = 0;
+    while index < 32 {
+        out[index] = (hex_nibble(bytes[index * 2]) << 4) | hex_nibble(bytes[index * 2 + 1]);
+        index += 1;
+    }
+    out
+}
+
+const fn hex_nibble(byte: u8) -> u8 {
+    match byte {
+        b'0'..=b'9' => byte - b'0',
+        b'a'..=b'f' => byte - b'a' + 10,
+        b'A'..=b'F' => byte - b'A' + 10,
+        _ => panic!("invalid rotation identity"),
+    }
+}
+
 fn fail(reason: &[u8]) -> ! {
     slime_rt::debug_write(b"[sel4-generation-client] fail: ");
     slime_rt::debug_write(reason);
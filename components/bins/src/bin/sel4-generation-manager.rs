@@ -15,6 +15,91 @@
 //! because it is the same on-disk structure and the same invariant — no
 //! transition overwrites the only valid root.
 //!
+//! STAGE also asks for a shadow boot: it writes a [`ShadowEvidence`] request
+//! naming the candidate and the release it would be accepted at into the
+//! evidence sector, for a root to run the candidate beside the running graph
+//! and overwrite with its verdict. Evidence already there for the same
+//! candidate and release is kept, so staging it again does not throw away a
+//! run that was already made.
+//!
+//! SELECT promotes only on evidence. A root that booted the pending generation
+//! and evaluated its declared probes leaves a [`HealthVerdict`] in the verdict
+//! sector; a shadow boot leaves its verdict in the evidence sector. A failure
+//! in either rolls the candidate back whatever the client asked, and a
+//! promotion needs one of them to pass for the candidate at the release it is
+//! promoted to. A shadow passes only the probes it can observe, so a candidate
+//! declaring a no-fault window or a route is promoted on a boot's verdict
+//! alone. The client's confirmation alone is refused: it names the
+//! generation, and the evidence decides.
+//!
+//! The slots and both evidence sectors are where a boot-selector root reads
+//! them when the generation grants a boot disk — the boot store's first
+//! sectors — and past the object store on its own partition when it does not.
+//!
+//! Four more run a bisect: BISECT_GOOD and BISECT_BAD name the bounds and then
+//! judge each midpoint, BISECT_STATUS reports the search, and BISECT_RESET ends
+//! it. The chain comes from release records in the object store on this same
+//! partition, and the session is a record there too — read back on every
+//! request, so a manager that restarts mid-search carries on from the newest
+//! one. A midpoint is held to everything STAGE holds a generation to before
+//! it is staged: the chain only says where to look. Only bisect, diff, rotation, export and import requests, and
+//! transitions of a generation whose record is here, open anything in the
+//! store: the heap gives back only its newest allocation.
+//!
+//! DIFF compares the known-good generation with the one the client names,
+//! reading both from generation records in the same store and reporting one
+//! line per change and their number in the reply. A generation with no record
+//! here is unknown to DIFF, whatever BootState says of it.
+//!
+//! ROTATE_TRUST applies a rotation record from the store to the trust root
+//! BootState names, stores the rotated root as a record of its own, and then
+//! commits BootState naming it. The record goes first, so an interruption
+//! leaves an unreferenced record rather than a reference to nothing. The
+//! version in BootState only climbs, and a rotation is refused before either
+//! write if a generation BootState can select has no release the new root
+//! verifies: a rotation must not strand what the next boot reads.
+//!
+//! EXPORT writes the generation the client names, with its release, to a
+//! second block device as an export archive: a GPT disk whose one transfer
+//! partition holds a manifest carrying every payload and the travelling state
+//! roots. IMPORT reads such a disk and admits its generation through
+//! `Release::verify_for_staging`, against the trust root and accepted release
+//! BootState names here, before storing the generation and release records a
+//! later STAGE or DIFF reads. Neither touches BootState, and a generation with
+//! no export device answers both as bad requests.
+//!
+//! STAGE, SELECT and ROLLBACK carry out the state policies the generation
+//! they move declares, when the store here holds its record. Staging keeps the
+//! state map BootState names in a snapshot record for every
+//! snapshot-before-upgrade binding; each rollback, whichever request causes
+//! it, restores those bindings from that snapshot and drops the
+//! discard-on-rollback ones, and commits BootState naming the map that leaves;
+//! promotion lets the snapshot go. Every action is an entry in the state
+//! journal past the slots, written before the commit it belongs to, so an
+//! interrupted transition leaves an entry no BootState reached rather than a
+//! state change no entry explains.
+//!
+//! Staging also runs the migrations the staged generation declares for state
+//! in an older schema: the migrator reads a sealed copy of the old object and
+//! writes a buffer of this component's, and the pending BootState names a map
+//! with the result in the old one's place. A migrator is spawned from the
+//! executable the known-good generation grants this component, so one only the
+//! staged generation carries is refused, as is any migration that fails; every
+//! one finishes before BootState is written, so a refusal leaves the
+//! known-good map and the objects it names as they were. A migrator has until
+//! a deadline on this component's monotonic clock to answer and is killed
+//! past it, and a refused stage compacts away what the migrations before the
+//! failure wrote. A rollback hands a migrated binding back its snapshot, since
+//! nothing translates it back.
+//!
+//! Between requests, once half the room below the slots is spent, the store
+//! is compacted down to what a transition could still read: what BootState
+//! names, the records of every generation on the selectable ones' release
+//! chains or imported onto them, the pending generation's snapshot, the
+//! objects the live state maps name, the newest bisect session, and any
+//! rotation still to apply. Superseded maps, released snapshots, migrations'
+//! inputs and abandoned branches are what it gives back.
+//!
 //! What the oracle does in `generation_service::transact` behind syscall
 //! `SYS_GENERATION_TRANSACT`, gated on a `GenerationControl` capability with
 //! `RIGHT_BOOT_UPDATE`. Here the block capability *is* the gate: a client
@@ -23,16 +108,46 @@
 
 extern crate alloc;
 
+use alloc::vec::Vec;
+
+use boot_contracts::archive;
 use boot_contracts::bootstate::{
-    BootState, SLOT_BYTES, SelectionError, Slot, empty_state_root, select_bootstate,
+    BOOTSTORE_CAPACITY, BOOTSTORE_EVIDENCE_OFFSET, BOOTSTORE_VERDICT_OFFSET, BootState,
+    HEALTH_VERDICT_BYTES, HealthVerdict, JournalAction, SHADOW_EVIDENCE_BYTES, SLOT_BYTES,
+    STATE_JOURNAL_BYTES, STATE_JOURNAL_SECTORS, SelectionError, ShadowEvidence, ShadowOutcome,
+    Slot, StateJournalEntry, TrustRootRef, empty_state_root, select_bootstate,
+};
+use boot_contracts::generation::{
+    self as contract, Generation, GrantEndpoint, RIGHT_BUFFER_MAP, RIGHT_BUFFER_WRITE,
+    generation_identity,
 };
+use boot_contracts::generation_diff::{self, Change, Delta};
 use boot_contracts::gpt::{self, GptError};
-use boot_contracts::object_store::{BlockIo, IoError};
+use boot_contracts::object_store::{BlockIo, IoError, ObjectStore, StoreError};
+use boot_contracts::recovery::{MAX_STATE_OBJECTS, StateEntry};
+use boot_contracts::release::{
+    INITIAL_TRUST_ROOT, RELEASE_BYTES, ROTATION_BYTES, Release, TRUST_ROOT_RECORD_BYTES, TrustRoot,
+    apply_rotation,
+};
+use boot_contracts::state_policy::{self, MAX_MAP_BYTES, MAX_SNAPSHOT_BYTES, StateMap};
+use boot_contracts::store_disk::{
+    MAX_OBJECT_PAYLOAD, MAX_OBJECTS, RECORD_HEADER, TYPE_METADATA_BYTES,
+};
+use boot_contracts::transfer;
+use slime_components::generation_bisect::{BisectError, SESSION_BYTES, Session, Verdict};
+use slime_components::generation_retention::{self, Link};
 use slime_proto::block::{self, WireBlockReply, WireBlockRequest};
-use slime_proto::generation::{self, WireGenerationReply, WireGenerationRequest};
+use slime_proto::capability_transfer::OBJECT_KIND_SHARED_BUFFER_LOAN;
+use slime_proto::generation::{
+    self, WireGenerationReply, WireGenerationRequest, WireMigrationReply, WireMigrationRequest,
+};
+use slime_rt::CapabilityDisposition;
 
 /// The block capability the generation grants this component.
 const BLOCK_SLOT: u32 = 1;
+/// The export device EXPORT writes and IMPORT reads, when the generation
+/// grants one.
+const EXPORT_SLOT: u32 = 4;
 /// The preinstalled direct endpoint shared with the client.
 const CLIENT_SLOT: u32 = 0;
 /// The supervision handle naming the client, minted by init at spawn.
@@ -43,23 +158,140 @@ const CLIENT_SLOT: u32 = 0;
 /// why this handle is granted at all: it cannot exist before the task it names,
 /// so it is a `MintedBinding` rather than a static grant.
 const CLIENT_SUPERVISION_SLOT: u32 = 3;
+/// The migrator executable, when the generation grants this component one:
+/// what STAGE spawns to carry a binding's state into a new schema.
+const MIGRATOR_EXECUTABLE_SLOT: u32 = 5;
+/// The declared edge to the migrator, whose other end it holds from spawn. A
+/// loan names its receiver through it, and the answer comes back on it.
+const MIGRATION_SLOT: u32 = 6;
+/// This component's own `SharedBufferFactory`: the sealed copy of the state a
+/// migrator reads, and the buffer it writes.
+const BUFFER_FACTORY_SLOT: u32 = 7;
+/// The monotonic clock a migration's deadline is read from. A generation that
+/// grants no clock here runs no migration: a migrator nothing can time out
+/// could hold STAGE for good.
+const MIGRATION_CLOCK_SLOT: u32 = 8;
+/// The boot disk a boot-selector root selects from, when the generation grants
+/// one. BootState, the health verdict and the shadow evidence are then kept
+/// in the boot store's own sectors, so the request a STAGE writes is the one
+/// the next boot's root reads and shadows, and the verdict it leaves is the
+/// one SELECT reads. Without one they stay past the object store on
+/// `BLOCK_SLOT`, where no root looks for them.
+const BOOT_SLOT: u32 = 9;
+/// How long one migrator has, from its spawn to its answer, before it is
+/// stopped and the stage refused. One record's translation, with room for an
+/// emulated machine's slow start.
+const MIGRATION_DEADLINE_NS: u64 = 2_000_000_000;
+/// The instance a generation grants the migrator executable to.
+const MANAGER_INSTANCE: &str = "sel4-generation-manager";
+const PAGE: u64 = 4096;
+/// The most a migrator may write: one store record.
+const MIGRATION_BYTES: usize = MAX_OBJECT_PAYLOAD;
+const MIGRATION_PAGES: usize = MIGRATION_BYTES / PAGE as usize;
+/// Where the old state is mapped while it is copied in, before the seal.
+const LEND_BASE: u64 = 0x0000_0018_0000_0000;
+/// Where the migrator's buffer is mapped while its answer is copied out.
+const MIGRATION_BASE: u64 = 0x0000_0019_0000_0000;
 
 const SECTOR_BYTES: usize = 512;
-/// The BootState slots, partition-relative — the same layout the rollback and
-/// recovery planes use, because it is the same structure.
+/// The BootState slots when they share the store's partition, partition-
+/// relative — the same layout the rollback and recovery planes use, because it
+/// is the same structure. The store's records end below them either way.
 const STATE_SLOT_A: u64 = 1024;
 const STATE_SLOT_B: u64 = 1025;
+/// The health verdict on the pending generation, when a boot has left one.
+const HEALTH_VERDICT: u64 = 1026;
+/// The state journal's ring of `STATE_JOURNAL_SECTORS` sectors, past the
+/// regions the store fixture reserves above the slots.
+const STATE_JOURNAL: u64 = 1046;
+/// The shadow-evidence sector: the manager's request, then a root's verdict.
+const SHADOW_EVIDENCE: u64 = STATE_JOURNAL + STATE_JOURNAL_SECTORS as u64;
 
-/// The generation this plane starts from and the one a client may stage.
-///
-/// Anything else is outside the closure: `serve` recognizes exactly these two,
-/// so staging or inspecting a third is refused before BootState changes, which
-/// is M6.5's "fail before BootState changes on missing objects".
-const KNOWN_GOOD: [u8; 32] = [0x11; 32];
-const CANDIDATE: [u8; 32] = [0x22; 32];
 const GENERATION_ROOT: [u8; 32] = [0x44; 32];
 const STAGE_ATTEMPTS: u32 = 2;
 
+/// The largest generation DIFF, EXPORT or IMPORT reads: one store record. A
+/// larger generation is chunked, and reading it chunk by chunk costs heap the
+/// manager cannot give back.
+const DIFF_GENERATION_BYTES: usize = MAX_OBJECT_PAYLOAD;
+
+/// The most state an archive carries: the objects BootState's map names,
+/// together no larger than one store record. A state past it does not
+/// export, rather than growing a buffer the manager holds for good.
+const ARCHIVE_STATE_BYTES: usize = MAX_OBJECT_PAYLOAD;
+
+/// The largest manifest an archive of such a generation needs: full tables,
+/// the release, the generation itself, and its state closure — the map and
+/// `ARCHIVE_STATE_BYTES` of the objects it names — in whole sectors.
+const ARCHIVE_BYTES: usize = (transfer::HEADER_LEN
+    + transfer::MAX_TRANSFER_OBJECTS * transfer::OBJECT_LEN
+    + contract::MAX_STATES * transfer::STATE_LEN
+    + RELEASE_BYTES
+    + DIFF_GENERATION_BYTES
+    + MAX_MAP_BYTES
+    + ARCHIVE_STATE_BYTES)
+    .next_multiple_of(SECTOR_BYTES);
+
+/// The tags this manager reads its own records back by. A state object an
+/// archive carries under one of them is refused rather than written, so an
+/// import cannot plant a release, a rotation or a trust root.
+const MANAGER_OBJECT_TYPES: [u32; 7] = [
+    generation::GENERATION_OBJECT_TYPE,
+    generation::RELEASE_OBJECT_TYPE,
+    generation::ROTATION_OBJECT_TYPE,
+    generation::TRUST_ROOT_OBJECT_TYPE,
+    generation::STATE_MAP_OBJECT_TYPE,
+    generation::STATE_SNAPSHOT_OBJECT_TYPE,
+    generation::BISECT_SESSION_OBJECT_TYPE,
+];
+
+/// The two generations a DIFF compares, or an IMPORT's generation and its
+/// parent. Static rather than on the stack, which is far smaller than either.
+static mut OLD_STAGING: [u8; DIFF_GENERATION_BYTES] = [0; DIFF_GENERATION_BYTES];
+static mut NEW_STAGING: [u8; DIFF_GENERATION_BYTES] = [0; DIFF_GENERATION_BYTES];
+static mut ARCHIVE_STAGING: [u8; ARCHIVE_BYTES] = [0; ARCHIVE_BYTES];
+
+fn diff_staging() -> (&'static mut [u8], &'static mut [u8]) {
+    // SAFETY: single-threaded, and each arm that takes these takes them once
+    // and is done with them before it returns.
+    unsafe {
+        (
+            core::slice::from_raw_parts_mut((&raw mut OLD_STAGING).cast(), DIFF_GENERATION_BYTES),
+            core::slice::from_raw_parts_mut((&raw mut NEW_STAGING).cast(), DIFF_GENERATION_BYTES),
+        )
+    }
+}
+
+fn archive_staging() -> &'static mut [u8] {
+    // SAFETY: as `diff_staging`; only EXPORT and IMPORT take it.
+    unsafe { core::slice::from_raw_parts_mut((&raw mut ARCHIVE_STAGING).cast(), ARCHIVE_BYTES) }
+}
+
+/// The state map BootState names, the snapshot a rollback restores from, and
+/// the map a transition leaves.
+static mut MAP_STAGING: [u8; MAX_MAP_BYTES] = [0; MAX_MAP_BYTES];
+static mut SNAPSHOT_STAGING: [u8; MAX_SNAPSHOT_BYTES] = [0; MAX_SNAPSHOT_BYTES];
+static mut ROLLED_STAGING: [u8; MAX_MAP_BYTES] = [0; MAX_MAP_BYTES];
+/// The map a rollback of a generation being staged will restore.
+static mut ROLLBACK_STAGING: [u8; MAX_MAP_BYTES] = [0; MAX_MAP_BYTES];
+
+fn state_staging() -> (&'static mut [u8], &'static mut [u8], &'static mut [u8]) {
+    // SAFETY: as `diff_staging`; only the state-policy helpers take these,
+    // once per transition.
+    unsafe {
+        (
+            core::slice::from_raw_parts_mut((&raw mut MAP_STAGING).cast(), MAX_MAP_BYTES),
+            core::slice::from_raw_parts_mut((&raw mut SNAPSHOT_STAGING).cast(), MAX_SNAPSHOT_BYTES),
+            core::slice::from_raw_parts_mut((&raw mut ROLLED_STAGING).cast(), MAX_MAP_BYTES),
+        )
+    }
+}
+
+fn rollback_staging() -> &'static mut [u8] {
+    // SAFETY: as `state_staging`; only staging takes it, once per stage.
+    unsafe { core::slice::from_raw_parts_mut((&raw mut ROLLBACK_STAGING).cast(), MAX_MAP_BYTES) }
+}
+
 const STATUS_OK: i32 = 0;
 const STATUS_BAD_REQUEST: i32 = -1;
 const STATUS_UNKNOWN_GENERATION: i32 = -2;
@@ -73,36 +305,73 @@ fn main(_startup_arg: u32) {
         slime_rt::exit(0);
     }
 
-    let mut io = BlockCapability;
+    let mut io = BlockCapability(BLOCK_SLOT);
     let Some(partition) = locate_partition(&mut io) else {
         fail(b"partition");
     };
-    let slots = StateSlots {
-        first_lba: partition.first_lba,
+    let journal_lba = partition.first_lba + STATE_JOURNAL;
+    let mut boot = BlockCapability(BOOT_SLOT);
+    let slots = if device_capacity(&mut boot).is_some() {
+        let Some(boot_store) = locate_boot_store(&mut boot) else {
+            fail(b"boot partition");
+        };
+        StateSlots {
+            device: BOOT_SLOT,
+            first_lba: boot_store.first_lba,
+            last_lba: boot_store.last_lba,
+            sectors: BOOT_STORE_SECTORS,
+            journal_lba,
+        }
+    } else {
+        StateSlots {
+            device: BLOCK_SLOT,
+            first_lba: partition.first_lba,
+            last_lba: partition.last_lba,
+            sectors: STORE_SECTORS,
+            journal_lba,
+        }
+    };
+
+    // The store on the same partition, holding generations, releases and
+    // bisect sessions. Its records end below `STATE_SLOT_A`; `session_fits`
+    // keeps them there.
+    let Ok(mut store) = ObjectStore::open(&mut io, &partition) else {
+        fail(b"store");
     };
 
     // Initialize genesis only when neither redundant slot contains a valid
     // BootState. Rewriting slot A on every process start destroys the durable
     // attempt/promotion history the selector and manager share across boots.
-    match slots.select(&mut io) {
+    match slots.select() {
         Ok(_) => {}
+        // A boot store's BootState names its directory's root, which whoever
+        // wrote the store computed; there is no genesis to make here.
+        Err(SelectionError::NoValidBootState) if slots.on_boot_store() => {
+            fail(b"boot store without bootstate")
+        }
         Err(SelectionError::NoValidBootState) => {
+            let Some(known_good) = first_release(&mut io, &store) else {
+                fail(b"no first release");
+            };
             let genesis = BootState {
                 sequence: 1,
-                known_good: KNOWN_GOOD,
+                known_good,
                 pending: None,
                 remaining_attempts: 0,
                 generation_root: GENERATION_ROOT,
                 state_root: empty_state_root(),
                 accepted_release_sequence: 1,
+                trust_root: None,
+                rollback_state_root: None,
             };
-            if slots.write(&mut io, Slot::A, &genesis).is_err() {
+            if slots.write(Slot::A, &genesis).is_err() {
                 fail(b"genesis");
             }
         }
         Err(SelectionError::ConflictingSlots) => fail(b"conflicting bootstate"),
     }
     slime_rt::debug_write(b"[sel4-generation-manager] ready\n");
+    let mut compacted = store.sequence();
 
     // Serve until the client is gone. One client, and a bounded script: the
     // plane's subject is the authority split, not concurrency, and a second
@@ -131,7 +400,13 @@ fn main(_startup_arg: u32) {
         if caps.iter().any(|attached| *attached != 0) {
             fail(b"the client attached a capability");
         }
-        let reply = serve(&mut io, &slots, &bytes[..received as usize]);
+        let reply = serve(&mut io, &slots, &mut store, &bytes[..received as usize]);
+        // Between requests, when no transition is half done, BootState names
+        // everything a compaction must keep.
+        if store.sequence() != compacted {
+            compact_store(&mut io, &slots, &mut store);
+            compacted = store.sequence();
+        }
         let encoded = reply.encode();
         loop {
             match slime_rt::send(CLIENT_SLOT, &encoded, &[]) {
@@ -148,7 +423,12 @@ fn main(_startup_arg: u32) {
 /// One request. Decoding failures and unknown operations are answered, not
 /// faulted: a client that sends garbage learns it did, and the manager stays up
 /// for the others.
-fn serve(io: &mut BlockCapability, slots: &StateSlots, bytes: &[u8]) -> WireGenerationReply {
+fn serve(
+    io: &mut BlockCapability,
+    slots: &StateSlots,
+    store: &mut ObjectStore,
+    bytes: &[u8],
+) -> WireGenerationReply {
     let Some(request) = WireGenerationRequest::decode(bytes) else {
         return reply(STATUS_BAD_REQUEST, None, 0);
     };
@@ -158,21 +438,31 @@ fn serve(io: &mut BlockCapability, slots: &StateSlots, bytes: &[u8]) -> WireGene
         return reply(STATUS_BAD_REQUEST, None, 0);
     }
     let identity = identity_bytes(&request);
-    let selected = match slots.select(io) {
+    let selected = match slots.select() {
         Ok(selected) => selected,
         Err(SelectionError::NoValidBootState) => return reply(STATUS_NO_PENDING, None, 0),
         Err(_) => return reply(STATUS_BAD_REQUEST, None, 0),
     };
 
     match request.op {
-        // Read-only. Two generations are known: the current known-good and the
-        // candidate a client may stage.
+        // Read-only. LIST names the known-good, or, given a generation, the
+        // one whose release names it as its parent: a client walks the chain
+        // forward from what runs rather than knowing identities it cannot
+        // compute.
         generation::OP_LIST => {
             report(b"list", &selected.state);
-            reply(STATUS_OK, Some(selected.state.known_good), 2)
+            if identity == [0; 32] {
+                return reply(STATUS_OK, Some(selected.state.known_good), 1);
+            }
+            match child_of(io, store, &identity) {
+                Some(child) => reply(STATUS_OK, Some(child), 1),
+                None => reply(STATUS_UNKNOWN_GENERATION, None, 0),
+            }
         }
         generation::OP_INSPECT => {
-            if identity != selected.state.known_good && identity != CANDIDATE {
+            if identity != selected.state.known_good
+                && generation_record(io, store, &identity, diff_staging().1).is_none()
+            {
                 report(b"inspect-unknown", &selected.state);
                 return reply(STATUS_UNKNOWN_GENERATION, None, 0);
             }
@@ -182,35 +472,92 @@ fn serve(io: &mut BlockCapability, slots: &StateSlots, bytes: &[u8]) -> WireGene
         // Transitions. Each validates before it writes, so a refusal leaves
         // BootState exactly as it was.
         generation::OP_STAGE => {
-            if identity != CANDIDATE {
-                // The closure does not contain it. Refused *before* any write,
-                // which is the property the gate checks against the image.
-                report(b"stage-refused", &selected.state);
-                return reply(STATUS_UNKNOWN_GENERATION, None, 0);
+            // Refused *before* any write, which is the property the gate
+            // checks against the image.
+            match verify_staging(io, store, &selected.state, &identity) {
+                Ok(()) => {}
+                Err(StageRefusal::Unknown) => {
+                    report(b"stage-refused", &selected.state);
+                    return reply(STATUS_UNKNOWN_GENERATION, None, 0);
+                }
+                Err(StageRefusal::Unverified) => {
+                    report(b"stage-unverified", &selected.state);
+                    return reply(STATUS_BAD_REQUEST, None, 0);
+                }
             }
             let Ok(staged) = selected.state.stage_pending(
                 identity,
                 STAGE_ATTEMPTS,
-                GENERATION_ROOT,
-                empty_state_root(),
+                selected.state.generation_root,
+                selected.state.known_good_state_root(),
             ) else {
                 return reply(STATUS_BAD_REQUEST, None, 0);
             };
-            let live = slots.commit(io, selected.slot, &staged);
+            let Ok(staged) = stage_state(io, slots, store, &selected.state, staged) else {
+                report(b"stage-refused", &selected.state);
+                return reply(STATUS_BAD_REQUEST, None, 0);
+            };
+            let live = slots.commit(selected.slot, &staged);
+            // The request follows the commit, so a root that finds it also
+            // finds the candidate pending; one interrupted before it boots the
+            // candidate in place, as a stage always did.
+            let release = selected.state.accepted_release_sequence + 1;
+            if !slots.shadow().is_some_and(|evidence| {
+                evidence.outcome != ShadowOutcome::Requested
+                    && evidence.candidate == identity
+                    && evidence.release_sequence == release
+            }) {
+                let request = ShadowEvidence::request(identity, selected.state.known_good, release);
+                slots.write_shadow(&request);
+            }
             report(b"stage", &live.state);
             reply(STATUS_OK, Some(identity), 1)
         }
         // SELECT is the health confirmation: promote the pending generation the
-        // client names, which must be the one actually staged.
+        // client names, which must be the one actually staged, on the evidence
+        // a root left for it. Evidence is consumed with the transition it
+        // decided.
         generation::OP_SELECT => {
-            let Ok(promoted) = selected
-                .state
-                .promote_pending(identity, selected.state.accepted_release_sequence + 1)
-            else {
+            if selected.state.pending != Some(identity) {
+                report(b"select-refused", &selected.state);
+                return reply(STATUS_UNKNOWN_GENERATION, None, 0);
+            }
+            // Evidence on any other generation is stale and left alone, and
+            // an unanswered request is not evidence.
+            let verdict = slots
+                .verdict()
+                .filter(|verdict| verdict.generation == identity);
+            let shadow = slots.shadow().filter(|evidence| {
+                evidence.candidate == identity && evidence.outcome != ShadowOutcome::Requested
+            });
+            if verdict.is_some_and(|verdict| verdict.rejects(identity))
+                || shadow.is_some_and(|evidence| evidence.rejects(identity))
+            {
+                let Some(rolled) = roll_back_state(io, slots, store, &selected.state) else {
+                    return reply(STATUS_BAD_REQUEST, None, 0);
+                };
+                let live = slots.commit(selected.slot, &rolled);
+                slots.consume_evidence(verdict.is_some(), shadow.is_some());
+                report(b"select-rollback", &live.state);
+                return reply(STATUS_UNKNOWN_GENERATION, Some(live.state.known_good), 0);
+            }
+            let release = verdict
+                .map(|verdict| verdict.release_sequence)
+                .or(shadow.map(|evidence| evidence.release_sequence))
+                .unwrap_or(selected.state.accepted_release_sequence + 1);
+            if !verdict.is_some_and(|verdict| verdict.promotes(identity, release))
+                && !shadow.is_some_and(|evidence| evidence.promotes(identity, release))
+            {
+                report(b"select-unproven", &selected.state);
+                return reply(STATUS_BAD_REQUEST, None, 0);
+            }
+            let Ok(promoted) = selected.state.promote_pending(identity, release) else {
                 report(b"select-refused", &selected.state);
                 return reply(STATUS_UNKNOWN_GENERATION, None, 0);
             };
-            let live = slots.commit(io, selected.slot, &promoted);
+            release_state(io, slots, store, &promoted, identity);
+            let live = slots.commit(selected.slot, &promoted);
+            slots.consume_evidence(verdict.is_some(), shadow.is_some());
             report(b"select", &live.state);
             reply(STATUS_OK, Some(live.state.known_good), 1)
         }
@@ -219,17 +566,1281 @@ fn serve(io: &mut BlockCapability, slots: &StateSlots, bytes: &[u8]) -> WireGene
                 report(b"rollback-nothing", &selected.state);
                 return reply(STATUS_NO_PENDING, Some(selected.state.known_good), 0);
             }
-            let Ok(rolled) = selected.state.rollback_pending() else {
+            let Some(rolled) = roll_back_state(io, slots, store, &selected.state) else {
+                report(b"rollback-refused", &selected.state);
                 return reply(STATUS_BAD_REQUEST, None, 0);
             };
-            let live = slots.commit(io, selected.slot, &rolled);
+            let live = slots.commit(selected.slot, &rolled);
             report(b"rollback", &live.state);
             reply(STATUS_OK, Some(live.state.known_good), 1)
         }
+        generation::OP_BISECT_GOOD => bisect(io, slots, store, &selected, identity, Verdict::Good),
+        generation::OP_BISECT_BAD => bisect(io, slots, store, &selected, identity, Verdict::Bad),
+        generation::OP_BISECT_STATUS => {
+            let Some(session) = live_session(io, store) else {
+                report(b"bisect-refused", &selected.state);
+                return reply(STATUS_BAD_REQUEST, None, 0);
+            };
+            if session.is_empty() {
+                report(b"bisect-none", &selected.state);
+                return reply(STATUS_NO_PENDING, None, 0);
+            }
+            report(b"bisect-status", &selected.state);
+            bisect_reply(&session, &selected.state)
+        }
+        // The staged midpoint goes with the session; a pending generation the
+        // search did not stage is left alone.
+        generation::OP_BISECT_RESET => {
+            let Some(session) = live_session(io, store) else {
+                report(b"bisect-refused", &selected.state);
+                return reply(STATUS_BAD_REQUEST, None, 0);
+            };
+            let reset = session.reset();
+            if !session_fits(store, &reset) {
+                report(b"bisect-refused", &selected.state);
+                return reply(STATUS_BAD_REQUEST, None, 0);
+            }
+            let mut state = selected.state;
+            if session.candidate().is_some() && state.pending == session.candidate() {
+                let Some(rolled) = roll_back_state(io, slots, store, &state) else {
+                    return reply(STATUS_BAD_REQUEST, None, 0);
+                };
+                state = slots.commit(selected.slot, &rolled).state;
+            }
+            persist_session(io, store, &reset);
+            report(b"bisect-reset", &state);
+            reply(STATUS_OK, Some(state.known_good), 0)
+        }
+        // Read-only, like INSPECT: nothing here writes BootState or the store.
+        generation::OP_DIFF => {
+            let (old_bytes, new_bytes) = diff_staging();
+            let (Some(old), Some(new)) = (
+                generation_record(io, store, &selected.state.known_good, old_bytes),
+                generation_record(io, store, &identity, new_bytes),
+            ) else {
+                report(b"diff-unknown", &selected.state);
+                return reply(STATUS_UNKNOWN_GENERATION, None, 0);
+            };
+            let (Ok(old), Ok(new)) = (generation_diff::decode(old), generation_diff::decode(new))
+            else {
+                report(b"diff-refused", &selected.state);
+                return reply(STATUS_BAD_REQUEST, None, 0);
+            };
+            let Ok(count) = generation_diff::diff(&old, &new, |change| report_change(&change))
+            else {
+                report(b"diff-refused", &selected.state);
+                return reply(STATUS_BAD_REQUEST, None, 0);
+            };
+            report(b"diff", &selected.state);
+            reply(STATUS_OK, Some(identity), count as u32)
+        }
+        // The request names the rotation record; the reply names the stored
+        // root and carries its version in `count`.
+        generation::OP_ROTATE_TRUST => {
+            let Some(current) = installed_trust_root(io, store, &selected.state) else {
+                report(b"rotate-refused", &selected.state);
+                return reply(STATUS_BAD_REQUEST, None, 0);
+            };
+            let mut rotation = [0u8; ROTATION_BYTES];
+            let Ok((generation::ROTATION_OBJECT_TYPE, len)) =
+                store.get(io, &identity, &mut rotation)
+            else {
+                report(b"rotate-unknown", &selected.state);
+                return reply(STATUS_UNKNOWN_GENERATION, None, 0);
+            };
+            let Ok(replacement) = apply_rotation(&current, &rotation[..len]) else {
+                report(b"rotate-refused", &selected.state);
+                return reply(STATUS_BAD_REQUEST, None, 0);
+            };
+            let stranded = [Some(selected.state.known_good), selected.state.pending]
+                .into_iter()
+                .flatten()
+                .any(|selectable| !released_under(io, store, &selectable, &replacement));
+            let Ok(record) = replacement.encode() else {
+                return reply(STATUS_BAD_REQUEST, None, 0);
+            };
+            if stranded || !record_fits(store, record.len()) || !slots.trust_root_fits() {
+                report(b"rotate-refused", &selected.state);
+                return reply(STATUS_BAD_REQUEST, None, 0);
+            }
+            let Ok(object) = store.put(io, generation::TRUST_ROOT_OBJECT_TYPE, &record) else {
+                fail(b"trust root record");
+            };
+            slots.write_trust_root(&record);
+            let Ok(rotated) = selected.state.rotate_trust_root(TrustRootRef {
+                object,
+                version: replacement.version,
+            }) else {
+                report(b"rotate-refused", &selected.state);
+                return reply(STATUS_BAD_REQUEST, None, 0);
+            };
+            let live = slots.commit(selected.slot, &rotated);
+            report(b"rotate", &live.state);
+            reply(STATUS_OK, Some(object), replacement.version)
+        }
+        // Read-only on this device. The archive carries the state BootState
+        // names with the generation, and is written whole before the reply,
+        // which carries the sectors it takes.
+        generation::OP_EXPORT => {
+            let (source, _) = diff_staging();
+            let mut release = [0u8; RELEASE_BYTES];
+            let (Some(source), Some(release)) = (
+                generation_record(io, store, &identity, source),
+                release_record(io, store, &identity, &mut release),
+            ) else {
+                report(b"export-unknown", &selected.state);
+                return reply(STATUS_UNKNOWN_GENERATION, None, 0);
+            };
+            let (map_bytes, _, _) = state_staging();
+            let Some(state) = state_map(io, store, &selected.state.state_root, map_bytes) else {
+                report(b"export-refused", &selected.state);
+                return reply(STATUS_BAD_REQUEST, None, 0);
+            };
+            let manifest = archive_staging();
+            let Ok(len) = transfer::encode_manifest(
+                source,
+                release,
+                &state,
+                |digest, out| store.get(io, digest, out).ok(),
+                manifest,
+            ) else {
+                report(b"export-refused", &selected.state);
+                return reply(STATUS_BAD_REQUEST, None, 0);
+            };
+            let mut device = BlockCapability(EXPORT_SLOT);
+            let Some(capacity) = device_capacity(&mut device) else {
+                report(b"export-unavailable", &selected.state);
+                return reply(STATUS_BAD_REQUEST, None, 0);
+            };
+            let mut disk_guid = [0u8; 16];
+            disk_guid.copy_from_slice(&identity[..16]);
+            let mut writer = |lba: u64, data: &[u8; SECTOR_BYTES]| -> Result<(), GptError> {
+                device.write_sector(lba, data).map_err(|_| GptError::Device)
+            };
+            if archive::write_archive(&mut writer, capacity, disk_guid, &manifest[..len]).is_err() {
+                report(b"export-refused", &selected.state);
+                return reply(STATUS_BAD_REQUEST, None, 0);
+            }
+            if device.flush().is_err() {
+                fail(b"export flush");
+            }
+            report(b"export", &selected.state);
+            reply(STATUS_OK, Some(identity), (len / SECTOR_BYTES) as u32)
+        }
+        // The request's identity is unused: the archive names its generation,
+        // and the reply names it back with its object count, state closure
+        // included. The state is put beside the generation for a STAGE to
+        // name; nothing here moves BootState.
+        generation::OP_IMPORT => {
+            let Some(root) = installed_trust_root(io, store, &selected.state) else {
+                report(b"import-refused", &selected.state);
+                return reply(STATUS_BAD_REQUEST, None, 0);
+            };
+            let mut device = BlockCapability(EXPORT_SLOT);
+            let Some(capacity) = device_capacity(&mut device) else {
+                report(b"import-unavailable", &selected.state);
+                return reply(STATUS_BAD_REQUEST, None, 0);
+            };
+            let mut reader = |lba: u64, out: &mut [u8; SECTOR_BYTES]| -> Result<(), GptError> {
+                device.read_sector(lba, out).map_err(|_| GptError::Device)
+            };
+            let Ok(manifest) = archive::read_archive(&mut reader, capacity, archive_staging())
+            else {
+                report(b"import-refused", &selected.state);
+                return reply(STATUS_BAD_REQUEST, None, 0);
+            };
+            // The baseline the release's authority is measured from must be a
+            // generation held here: the parent the archive names, or the
+            // known-good when it names none.
+            let (baseline_bytes, rebuilt) = diff_staging();
+            let baseline = manifest.parent.unwrap_or(selected.state.known_good);
+            let Some(Ok(baseline)) =
+                generation_record(io, store, &baseline, baseline_bytes).map(Generation::decode)
+            else {
+                report(b"import-unknown", &selected.state);
+                return reply(STATUS_UNKNOWN_GENERATION, None, 0);
+            };
+            let (imported, first) = match archive::admit(
+                &manifest,
+                &baseline,
+                &root,
+                selected.state.accepted_release_sequence,
+                rebuilt,
+            ) {
+                Ok(generation) => (generation.identity, generation.object_count()),
+                Err(_) => {
+                    report(b"import-refused", &selected.state);
+                    return reply(STATUS_BAD_REQUEST, None, 0);
+                }
+            };
+            let generation_bytes = &rebuilt[..manifest.generation_len];
+            // `admit` held the closure to the header's state root; its map
+            // goes in under the map's tag, and every other object under the
+            // tag it was exported with, unless that is one of ours.
+            let closure = first..manifest.object_count();
+            let mut lens = [0usize; 3 + MAX_STATE_OBJECTS];
+            lens[..2].copy_from_slice(&[generation_bytes.len(), RELEASE_BYTES]);
+            for (len, index) in lens[2..].iter_mut().zip(closure.clone()) {
+                match manifest.object(index) {
+                    Ok(object)
+                        if index == first || !MANAGER_OBJECT_TYPES.contains(&object.kind) =>
+                    {
+                        *len = object.length;
+                    }
+                    _ => {
+                        report(b"import-refused", &selected.state);
+                        return reply(STATUS_BAD_REQUEST, None, 0);
+                    }
+                }
+            }
+            if !records_fit(store, &lens[..2 + closure.len()]) {
+                report(b"import-refused", &selected.state);
+                return reply(STATUS_BAD_REQUEST, None, 0);
+            }
+            for (object_type, bytes) in [
+                (generation::GENERATION_OBJECT_TYPE, generation_bytes),
+                (generation::RELEASE_OBJECT_TYPE, manifest.release()),
+            ] {
+                if store
+                    .put_or_reuse_with_metadata(
+                        io,
+                        object_type,
+                        bytes,
+                        &imported,
+                        &[0; TYPE_METADATA_BYTES],
+                    )
+                    .is_err()
+                {
+                    fail(b"import record");
+                }
+            }
+            for index in closure {
+                let Ok(transfer::TransferObject {
+                    kind,
+                    payload: Some(payload),
+                    ..
+                }) = manifest.object(index)
+                else {
+                    fail(b"import state");
+                };
+                let kind = if index == first {
+                    generation::STATE_MAP_OBJECT_TYPE
+                } else {
+                    kind
+                };
+                if store.put_or_reuse(io, kind, payload).is_err() {
+                    fail(b"import state");
+                }
+            }
+            report(b"import", &selected.state);
+            reply(STATUS_OK, Some(imported), manifest.object_count() as u32)
+        }
         _ => reply(STATUS_BAD_REQUEST, None, 0),
     }
 }
 
+/// BISECT_GOOD and BISECT_BAD.
+///
+/// BootState moves first and the session record second. An interruption
+/// between them leaves the previous session naming the generation just judged,
+/// so a client repeating the same verdict reaches the same midpoint again
+/// rather than being refused for naming a generation the search moved past.
+///
+/// Each midpoint goes through `verify_staging` before it is staged, as STAGE's
+/// generation does: a parent link is read from an unverified release, and a
+/// bisect that staged whatever the links reached would be a way round the
+/// trust root. A refused midpoint leaves the session where it was.
+fn bisect(
+    io: &mut BlockCapability,
+    slots: &StateSlots,
+    store: &mut ObjectStore,
+    selected: &boot_contracts::bootstate::SelectedBootState,
+    identity: [u8; 32],
+    verdict: Verdict,
+) -> WireGenerationReply {
+    let Some(session) = live_session(io, store) else {
+        report(b"bisect-refused", &selected.state);
+        return reply(STATUS_BAD_REQUEST, None, 0);
+    };
+    let marked = session.mark(identity, verdict, |child| parent_of(io, store, child));
+    let next = match marked {
+        Ok(next) if session_fits(store, &next) => next,
+        Ok(_) => {
+            report(b"bisect-refused", &selected.state);
+            return reply(STATUS_BAD_REQUEST, None, 0);
+        }
+        Err(error) => {
+            report(b"bisect-refused", &selected.state);
+            let status = match error {
+                BisectError::WrongGeneration | BisectError::UnknownRelease => {
+                    STATUS_UNKNOWN_GENERATION
+                }
+                BisectError::NotAnAncestor | BisectError::Finished | BisectError::Corrupt => {
+                    STATUS_BAD_REQUEST
+                }
+            };
+            return reply(status, None, 0);
+        }
+    };
+
+    let mut state = selected.state;
+    if let Some(candidate) = next.candidate() {
+        match verify_staging(io, store, &state, &candidate) {
+            Ok(()) => {}
+            Err(StageRefusal::Unknown) => {
+                report(b"bisect-refused", &state);
+                return reply(STATUS_UNKNOWN_GENERATION, None, 0);
+            }
+            Err(StageRefusal::Unverified) => {
+                report(b"bisect-unverified", &state);
+                return reply(STATUS_BAD_REQUEST, None, 0);
+            }
+        }
+        let Ok(staged) = state.stage_pending(
+            candidate,
+            STAGE_ATTEMPTS,
+            state.generation_root,
+            state.known_good_state_root(),
+        ) else {
+            return reply(STATUS_BAD_REQUEST, None, 0);
+        };
+        let Ok(staged) = stage_state(io, slots, store, &state, staged) else {
+            report(b"bisect-refused", &state);
+            return reply(STATUS_BAD_REQUEST, None, 0);
+        };
+        state = slots.commit(selected.slot, &staged).state;
+        report(b"bisect-stage", &state);
+    } else if next.first_bad().is_some() {
+        // The last midpoint was the generation just judged. Booting it again
+        // proves nothing, so the search hands the device back to known-good.
+        if state.pending == Some(identity) {
+            let Some(rolled) = roll_back_state(io, slots, store, &state) else {
+                return reply(STATUS_BAD_REQUEST, None, 0);
+            };
+            state = slots.commit(selected.slot, &rolled).state;
+        }
+        report(b"bisect-done", &state);
+    } else {
+        report(b"bisect-bound", &state);
+    }
+    persist_session(io, store, &next);
+    bisect_reply(&next, &state)
+}
+
+/// A session as a reply: the midpoint being tested, or the first bad
+/// generation once it is known, with the verdicts still needed in `count`
+/// and those recorded so far in `generation_number`.
+fn bisect_reply(session: &Session, state: &BootState) -> WireGenerationReply {
+    let mut answer = reply(STATUS_OK, None, session.remaining());
+    answer.generation_number = session.verdicts();
+    answer.release_sequence = state.accepted_release_sequence as u32;
+    if let Some(candidate) = session.candidate() {
+        answer = with_identity(answer, candidate);
+        if state.pending == Some(candidate) {
+            answer.flags = generation::REPLY_FLAG_PENDING | generation::REPLY_FLAG_STAGED;
+            answer.remaining_attempts = state.remaining_attempts;
+        }
+    } else if let Some(first_bad) = session.first_bad() {
+        answer = with_identity(answer, first_bad);
+        answer.flags = generation::REPLY_FLAG_BISECT_DONE;
+    }
+    answer
+}
+
+fn with_identity(mut answer: WireGenerationReply, identity: [u8; 32]) -> WireGenerationReply {
+    let words = identity_words(identity);
+    answer.generation0 = words[0];
+    answer.generation1 = words[1];
+    answer.generation2 = words[2];
+    answer.generation3 = words[3];
+    answer
+}
+
+/// The newest session record, an empty session when there is none, or `None`
+/// when the newest one does not decode. Every change advances the serial, so
+/// no two records share content and the newest is simply the last committed.
+fn live_session(io: &mut BlockCapability, store: &ObjectStore) -> Option<Session> {
+    let Some(hash) = store
+        .records_of_type(generation::BISECT_SESSION_OBJECT_TYPE)
+        .last()
+        .map(|entry| entry.hash)
+    else {
+        return Some(Session::new());
+    };
+    let mut bytes = [0u8; SESSION_BYTES];
+    let (_, len) = store.get(io, &hash, &mut bytes).ok()?;
+    Session::decode(&bytes[..len]).ok()
+}
+
+/// Whether the session's record fits below the BootState slots and in the
+/// index. Checked before BootState moves, so a full store refuses the request
+/// rather than leaving a staged midpoint no session names.
+fn session_fits(store: &ObjectStore, session: &Session) -> bool {
+    let mut bytes = [0u8; SESSION_BYTES];
+    record_fits(store, session.encode(&mut bytes))
+}
+
+/// Whether a record of `len` payload bytes fits below the BootState slots and
+/// in the index.
+fn record_fits(store: &ObjectStore, len: usize) -> bool {
+    records_fit(store, &[len])
+}
+
+/// Whether records of these payload lengths all fit, appended together.
+fn records_fit(store: &ObjectStore, lens: &[usize]) -> bool {
+    let sectors: u64 = lens
+        .iter()
+        .map(|len| (RECORD_HEADER + len).div_ceil(SECTOR_BYTES) as u64)
+        .sum();
+    store.object_count() + lens.len() <= MAX_OBJECTS && store.append_lba() + sectors <= STATE_SLOT_A
+}
+
+/// Reclaim the records no selectable generation reaches, once half the room
+/// below the BootState slots is spent.
+///
+/// A pass re-reads every live record and this manager's heap barely frees, so
+/// it waits until there is something worth reclaiming rather than following
+/// every write. Copies never reach the slots: the store's region ends below
+/// `STATE_SLOT_A`, and a live set with nowhere to go below it keeps what the
+/// trim freed and leaves the next `records_fit` to refuse.
+fn compact_store(io: &mut BlockCapability, slots: &StateSlots, store: &mut ObjectStore) {
+    if store.append_lba() * 2 <= STATE_SLOT_A && store.object_count() * 2 <= MAX_OBJECTS {
+        return;
+    }
+    reclaim(io, slots, store);
+}
+
+/// Compact the store down to `store_roots` of the committed BootState now,
+/// whatever the room left.
+fn reclaim(io: &mut BlockCapability, slots: &StateSlots, store: &mut ObjectStore) {
+    let Ok(selected) = slots.select() else {
+        return;
+    };
+    let mut roots = Vec::new();
+    store_roots(io, store, &selected.state, &mut roots);
+    match store.compact_below(io, STATE_SLOT_A, &roots, state_references) {
+        Ok(compaction) => {
+            let mut line = [0u8; 96];
+            let mut len = copy(&mut line, b"[sel4-generation-manager] compact kept=");
+            len += copy(&mut line[len..], &decimal(compaction.kept as u64));
+            len += copy(&mut line[len..], b" reclaimed=");
+            len += copy(&mut line[len..], &decimal(compaction.reclaimed as u64));
+            len += copy(&mut line[len..], b"\n");
+            slime_rt::debug_write(&line[..len]);
+        }
+        Err(StoreError::StoreFull) => {}
+        Err(_) => fail(b"compaction"),
+    }
+}
+
+/// What a compaction of this store keeps: every object BootState names, the
+/// generation and release records of each generation
+/// `generation_retention::retained` keeps, the snapshot staging the pending
+/// generation kept, the newest bisect session, and each rotation the
+/// installed trust root can still take.
+fn store_roots(
+    io: &mut BlockCapability,
+    store: &ObjectStore,
+    state: &BootState,
+    roots: &mut Vec<[u8; 32]>,
+) {
+    roots.extend(state.store_roots());
+    let selectable: Vec<[u8; 32]> = [Some(state.known_good), state.pending]
+        .into_iter()
+        .flatten()
+        .collect();
+    // Each release read once: the walks below run over this table, not the
+    // device.
+    let mut links: Vec<Link> = Vec::new();
+    let mut bytes = [0u8; RELEASE_BYTES];
+    for entry in store.records_of_type(generation::RELEASE_OBJECT_TYPE) {
+        let Ok((_, len)) = store.get(io, &entry.hash, &mut bytes) else {
+            continue;
+        };
+        if let Ok(release) = Release::decode(&bytes[..len]) {
+            links.push((release.generation, release.parent));
+        }
+    }
+    for entry in store.entries() {
+        let tagged = match entry.obj_type {
+            generation::GENERATION_OBJECT_TYPE | generation::RELEASE_OBJECT_TYPE => {
+                store.record_metadata(io, entry).is_ok_and(|metadata| {
+                    generation_retention::retained(&links, &selectable, &metadata.generation)
+                })
+            }
+            generation::STATE_SNAPSHOT_OBJECT_TYPE => state.pending.is_some_and(|pending| {
+                store
+                    .record_metadata(io, entry)
+                    .is_ok_and(|metadata| metadata.generation == pending)
+            }),
+            _ => false,
+        };
+        if tagged {
+            roots.push(entry.hash);
+        }
+    }
+    roots.extend(
+        store
+            .records_of_type(generation::BISECT_SESSION_OBJECT_TYPE)
+            .last()
+            .map(|entry| entry.hash),
+    );
+    let Some(current) = installed_trust_root(io, store, state) else {
+        return;
+    };
+    let mut rotation = [0u8; ROTATION_BYTES];
+    for entry in store.records_of_type(generation::ROTATION_OBJECT_TYPE) {
+        if store
+            .get(io, &entry.hash, &mut rotation)
+            .is_ok_and(|(_, len)| apply_rotation(&current, &rotation[..len]).is_ok())
+        {
+            roots.push(entry.hash);
+        }
+    }
+}
+
+/// What a live state record names: a state map its entries' objects, and a
+/// snapshot the objects of the map it kept. Shaped as `compact`'s
+/// `references` callback.
+fn state_references(obj_type: u32, payload: &[u8], out: &mut Vec<[u8; 32]>) {
+    let map = match obj_type {
+        generation::STATE_MAP_OBJECT_TYPE => payload,
+        generation::STATE_SNAPSHOT_OBJECT_TYPE => payload.get(32..).unwrap_or_default(),
+        _ => return,
+    };
+    if let Ok(map) = StateMap::decode(map) {
+        out.extend(map.entries().map(|entry| entry.object_identity));
+    }
+}
+
+fn persist_session(io: &mut BlockCapability, store: &mut ObjectStore, session: &Session) {
+    let mut bytes = [0u8; SESSION_BYTES];
+    let len = session.encode(&mut bytes);
+    if store
+        .put(io, generation::BISECT_SESSION_OBJECT_TYPE, &bytes[..len])
+        .is_err()
+    {
+        fail(b"bisect session");
+    }
+}
+
+/// The parent the release for `child` names.
+///
+/// A release's store identity is the hash of its bytes, which is not what a
+/// parent link names, so releases are found by tag and by the generation their
+/// record header carries. The header only narrows the search: the parent is
+/// read from the release itself, and only if it names `child` too.
+fn parent_of(
+    io: &mut BlockCapability,
+    store: &ObjectStore,
+    child: &[u8; 32],
+) -> Result<Option<[u8; 32]>, BisectError> {
+    let mut bytes = [0u8; RELEASE_BYTES];
+    let release =
+        release_record(io, store, child, &mut bytes).ok_or(BisectError::UnknownRelease)?;
+    Release::decode(release)
+        .map(|release| release.parent)
+        .map_err(|_| BisectError::UnknownRelease)
+}
+
+/// The generation whose release names `parent` as its parent, found the way
+/// `parent_of` finds a release.
+fn child_of(io: &mut BlockCapability, store: &ObjectStore, parent: &[u8; 32]) -> Option<[u8; 32]> {
+    let mut bytes = [0u8; RELEASE_BYTES];
+    store
+        .records_of_type(generation::RELEASE_OBJECT_TYPE)
+        .find_map(|entry| {
+            let (_, len) = store.get(io, &entry.hash, &mut bytes).ok()?;
+            let release = Release::decode(&bytes[..len]).ok()?;
+            (release.parent == Some(*parent)).then_some(release.generation)
+        })
+}
+
+/// The generation the store's first release is for: what this machine was
+/// installed with, and the known-good a genesis BootState names.
+fn first_release(io: &mut BlockCapability, store: &ObjectStore) -> Option<[u8; 32]> {
+    let mut bytes = [0u8; RELEASE_BYTES];
+    store
+        .records_of_type(generation::RELEASE_OBJECT_TYPE)
+        .find_map(|entry| {
+            let (_, len) = store.get(io, &entry.hash, &mut bytes).ok()?;
+            let release = Release::decode(&bytes[..len]).ok()?;
+            (release.sequence == 1).then_some(release.generation)
+        })
+}
+
+/// Why STAGE refused a generation.
+enum StageRefusal {
+    /// Its generation or release record is not here.
+    Unknown,
+    /// Both are, and the release does not admit the generation.
+    Unverified,
+}
+
+/// Hold `identity` to what IMPORT holds an archive to before it may be
+/// staged: its release verified by `Release::verify_for_staging` under the
+/// installed trust root, past the accepted release sequence, and no wider in
+/// authority than its baseline — the parent the release names, or the
+/// known-good when it names none. A baseline this manager does not hold is
+/// one authority cannot be measured from, so the stage is unverified.
+fn verify_staging(
+    io: &mut BlockCapability,
+    store: &ObjectStore,
+    state: &BootState,
+    identity: &[u8; 32],
+) -> Result<(), StageRefusal> {
+    let (baseline_bytes, generation_bytes) = diff_staging();
+    let mut release_bytes = [0u8; RELEASE_BYTES];
+    let (Some(release), Some(generation)) = (
+        release_record(io, store, identity, &mut release_bytes),
+        generation_record(io, store, identity, generation_bytes),
+    ) else {
+        return Err(StageRefusal::Unknown);
+    };
+    let release = Release::decode(release).map_err(|_| StageRefusal::Unverified)?;
+    let generation = Generation::decode(generation).map_err(|_| StageRefusal::Unverified)?;
+    let baseline = release.parent.unwrap_or(state.known_good);
+    let baseline =
+        generation_record(io, store, &baseline, baseline_bytes).ok_or(StageRefusal::Unverified)?;
+    let baseline = Generation::decode(baseline).map_err(|_| StageRefusal::Unverified)?;
+    let root = installed_trust_root(io, store, state).ok_or(StageRefusal::Unverified)?;
+    release
+        .verify_for_staging(
+            &generation,
+            &baseline,
+            &root,
+            state.accepted_release_sequence,
+        )
+        .map_err(|_| StageRefusal::Unverified)
+}
+
+/// The release record for `identity`, read into `out`: found by tag and by
+/// the generation the record header carries, and accepted only if it decodes
+/// and names that generation itself.
+fn release_record<'a>(
+    io: &mut BlockCapability,
+    store: &ObjectStore,
+    identity: &[u8; 32],
+    out: &'a mut [u8; RELEASE_BYTES],
+) -> Option<&'a [u8]> {
+    let len = store
+        .records_of_type(generation::RELEASE_OBJECT_TYPE)
+        .find_map(|entry| {
+            if !store
+                .record_metadata(io, entry)
+                .is_ok_and(|metadata| metadata.generation == *identity)
+            {
+                return None;
+            }
+            let (_, len) = store.get(io, &entry.hash, out).ok()?;
+            Release::decode(&out[..len])
+                .is_ok_and(|release| release.generation == *identity)
+                .then_some(len)
+        })?;
+    Some(&out[..len])
+}
+
+/// `staged` over the state it inherits from `state`'s known-good generation,
+/// migrated into the staged generation's schema, with what the inherited state
+/// was kept in a snapshot record and the map a rollback restores named in the
+/// staged BootState. The migrated objects and both maps are stored, and each
+/// binding kept or migrated is journalled, before the caller commits.
+///
+/// Only a generation with a record here declares anything this manager can
+/// read; one without stages as it always did. `Err` refuses the stage with
+/// BootState unwritten and the inherited map as it was: a record that does not
+/// decode, a state root whose map is not here, state no declared migration
+/// reads, a migrator that cannot run or fails, or a record that does not fit.
+///
+/// A refusal also takes back what the attempt stored. The migrations that
+/// finished before one failed left objects nothing names, so the store is
+/// compacted there and then rather than carrying them until the next pass
+/// finds it half full.
+fn stage_state(
+    io: &mut BlockCapability,
+    slots: &StateSlots,
+    store: &mut ObjectStore,
+    state: &BootState,
+    staged: BootState,
+) -> Result<BootState, ()> {
+    let written = store.sequence();
+    let result = migrate_state(io, slots, store, state, staged);
+    if result.is_err() && store.sequence() != written {
+        reclaim(io, slots, store);
+    }
+    result
+}
+
+/// `stage_state` up to the refusal, which leaves any cleanup to it.
+fn migrate_state(
+    io: &mut BlockCapability,
+    slots: &StateSlots,
+    store: &mut ObjectStore,
+    state: &BootState,
+    staged: BootState,
+) -> Result<BootState, ()> {
+    let Some(pending) = staged.pending else {
+        return Ok(staged);
+    };
+    let (object_bytes, generation_bytes) = diff_staging();
+    let Some(bytes) = generation_record(io, store, &pending, generation_bytes) else {
+        return Ok(staged);
+    };
+    let generation = Generation::decode(bytes).map_err(|_| ())?;
+    let (map_bytes, snapshot_bytes, migrated_bytes) = state_staging();
+    let map = state_map(io, store, &state.known_good_state_root(), map_bytes).ok_or(())?;
+    let kept = state_policy::snapshotted(&generation, &map, |_| {}).map_err(|_| ())?;
+    let mut planned = [None; contract::MAX_STATES];
+    let mut count = 0;
+    state_policy::migrations(&generation, &map, |migration| {
+        planned[count] = Some(migration);
+        count += 1;
+    })
+    .map_err(|_| ())?;
+    let rollback_bytes = rollback_staging();
+    let rollback_len = state_policy::rollback_at_staging(&generation, &map, &map, rollback_bytes)
+        .map_err(|_| ())?;
+    if kept == 0 && count == 0 && rollback_bytes[..rollback_len] == *map.bytes() {
+        return Ok(staged);
+    }
+    let planned = &planned[..count];
+    if count != 0 && !migrators_spawnable(io, store, state, &generation, planned, object_bytes) {
+        return Err(());
+    }
+
+    // Every migration runs before anything names its result: a failed one
+    // leaves fresh objects nothing refers to, and the inherited map intact.
+    let mut migrated = [StateEntry {
+        binding_identity: [0; 32],
+        object_identity: [0; 32],
+        schema_version: 0,
+    }; contract::MAX_STATES];
+    for (planned, migrated) in planned.iter().flatten().zip(&mut migrated) {
+        *migrated = migrate(io, store, planned, object_bytes).ok_or(())?;
+    }
+    let len =
+        state_policy::apply_migrations(&map, &migrated[..count], migrated_bytes).map_err(|_| ())?;
+    let migrated_map = StateMap::decode(&migrated_bytes[..len]).map_err(|_| ())?;
+    // Worked out now, over the map just migrated, so stage-0 and the root
+    // restore the same state without reading the generation.
+    let rollback_len =
+        state_policy::rollback_at_staging(&generation, &map, &migrated_map, rollback_bytes)
+            .map_err(|_| ())?;
+    let rollback_map = StateMap::decode(&rollback_bytes[..rollback_len]).map_err(|_| ())?;
+    let staged = state
+        .stage_pending_with_rollback(
+            pending,
+            staged.remaining_attempts,
+            staged.generation_root,
+            migrated_map.root(),
+            rollback_map.root(),
+        )
+        .map_err(|_| ())?;
+    let snapshot_len =
+        state_policy::encode_snapshot(&pending, &map, snapshot_bytes).map_err(|_| ())?;
+    if !records_fit(store, &[snapshot_len, len, rollback_len]) {
+        return Err(());
+    }
+    for stored in [&migrated_map, &rollback_map] {
+        if !stored.is_empty()
+            && store
+                .put_or_reuse(io, generation::STATE_MAP_OBJECT_TYPE, stored.bytes())
+                .is_err()
+        {
+            fail(b"state map record");
+        }
+    }
+    if store
+        .put_or_reuse_with_metadata(
+            io,
+            generation::STATE_SNAPSHOT_OBJECT_TYPE,
+            &snapshot_bytes[..snapshot_len],
+            &pending,
+            &[0; TYPE_METADATA_BYTES],
+        )
+        .is_err()
+    {
+        fail(b"state snapshot record");
+    }
+    let mut journal = slots.journal(io);
+    state_policy::snapshotted(&generation, &map, |entry| {
+        journal.append(
+            io,
+            JournalAction::Snapshot,
+            contract::POLICY_SNAPSHOT_BEFORE_UPGRADE,
+            pending,
+            &staged,
+            entry,
+        );
+    })
+    .map_err(|_| ())?;
+    for (planned, migrated) in planned.iter().flatten().zip(&migrated) {
+        // The snapshot keeps a migrated binding whatever its policy, since a
+        // rollback hands it back in the schema it was written in.
+        if planned.policy != contract::POLICY_SNAPSHOT_BEFORE_UPGRADE {
+            journal.append(
+                io,
+                JournalAction::Snapshot,
+                planned.policy,
+                pending,
+                &staged,
+                planned.entry,
+            );
+        }
+        journal.append(
+            io,
+            JournalAction::Migrate,
+            planned.policy,
+            pending,
+            &staged,
+            *migrated,
+        );
+    }
+    journal.flush(io);
+    Ok(staged)
+}
+
+/// Whether every migrator `planned` names is one this manager can spawn and
+/// time: the executable the known-good generation grants it at
+/// `MIGRATOR_EXECUTABLE_SLOT`, with the very object the staged generation
+/// names, and a clock at `MIGRATION_CLOCK_SLOT` to hold it to its deadline.
+///
+/// A spawn materializes what the running generation declared, never bytes
+/// from the store, so a migrator only the staged generation carries cannot
+/// run here, and a stage that needs one is refused.
+fn migrators_spawnable(
+    io: &mut BlockCapability,
+    store: &ObjectStore,
+    state: &BootState,
+    generation: &Generation<'_>,
+    planned: &[Option<state_policy::Migration>],
+    out: &mut [u8],
+) -> bool {
+    let Some(known_good) = generation_record(io, store, &state.known_good, out)
+        .and_then(|bytes| Generation::decode(bytes).ok())
+    else {
+        return false;
+    };
+    let Some(granted) = granted_migrator(&known_good) else {
+        return false;
+    };
+    if slime_rt::clock_now(MIGRATION_CLOCK_SLOT).is_err() {
+        return false;
+    }
+    planned.iter().flatten().all(|planned| {
+        generation
+            .executable(planned.executable)
+            .and_then(|executable| generation.object(executable.object))
+            .is_ok_and(|object| object.digest == granted)
+    })
+}
+
+/// The object digest of the executable `generation` grants this manager's
+/// instance at `MIGRATOR_EXECUTABLE_SLOT`, if it grants one.
+fn granted_migrator(generation: &Generation<'_>) -> Option<[u8; 32]> {
+    let manager = generation.instance_named(MANAGER_INSTANCE)?;
+    (0..manager.binding_count()).find_map(|index| {
+        let binding = generation.binding(manager, index).ok()?;
+        if binding.slot != MIGRATOR_EXECUTABLE_SLOT as usize {
+            return None;
+        }
+        let GrantEndpoint::Executable(executable) = generation.grant(binding.grant).ok()?.target
+        else {
+            return None;
+        };
+        let executable = generation.executable(executable).ok()?;
+        Some(generation.object(executable.object).ok()?.digest)
+    })
+}
+
+/// Run the migrator over the state object `planned` names, and store what it
+/// writes as a fresh object of the same type: the entry the staged map names
+/// in its place.
+///
+/// The migrator reads a sealed copy and writes a buffer of this manager's, so
+/// the object it read stays in the store as it was whatever it does. `None`
+/// if it cannot be spawned, answers with a failure, dies first, or is still
+/// unanswered at its deadline; one that has not ended by then is killed.
+fn migrate(
+    io: &mut BlockCapability,
+    store: &mut ObjectStore,
+    planned: &state_policy::Migration,
+    bytes: &mut [u8],
+) -> Option<StateEntry> {
+    let (object_type, len) = store.get(io, &planned.entry.object_identity, bytes).ok()?;
+    let request = WireMigrationRequest {
+        magic: generation::GENERATION_MAGIC,
+        version: generation::FORMAT_VERSION,
+        from_version: planned.entry.schema_version,
+        to_version: planned.to_version,
+        length: len as u32,
+        reserved: [0; 12],
+        binding: planned.entry.binding_identity,
+    };
+    let deadline = slime_rt::clock_now(MIGRATION_CLOCK_SLOT)
+        .ok()?
+        .saturating_add(MIGRATION_DEADLINE_NS);
+    let spawned = slime_rt::spawn(MIGRATOR_EXECUTABLE_SLOT, &[]).ok()?;
+    let Some(written) = exchange(spawned.supervision_slot, request, bytes, deadline) else {
+        // Nothing it does from here is read, so whatever is still running is
+        // stopped. One that already ended answers `ERR_BAD_CAP`, which is as
+        // good.
+        if slime_rt::supervision_kill(spawned.supervision_slot) == slime_rt::ERR_SUCCESS {
+            slime_rt::debug_write(b"[sel4-generation-manager] migrator killed\n");
+        }
+        return None;
+    };
+    if !record_fits(store, written) {
+        return None;
+    }
+    let Ok((object, _)) = store.put_or_reuse(io, object_type, &bytes[..written]) else {
+        fail(b"migrated state");
+    };
+    Some(StateEntry {
+        binding_identity: planned.entry.binding_identity,
+        object_identity: object,
+        schema_version: planned.to_version,
+    })
+}
+
+/// Lend the migrator the first `request.length` bytes of `bytes` to read and
+/// a fresh buffer to write, and copy what it wrote back into `bytes`. `None`
+/// for any failure, the migrator's and its deadline's included.
+fn exchange(
+    supervision: u32,
+    request: WireMigrationRequest,
+    bytes: &mut [u8],
+    deadline: u64,
+) -> Option<usize> {
+    let fresh = slime_rt::shared_buffer_create(BUFFER_FACTORY_SLOT, MIGRATION_PAGES, true).ok()?;
+    let answered =
+        slime_rt::shared_buffer_loan(fresh.slot, MIGRATION_SLOT, 0, MIGRATION_BYTES as u64, true)
+            .ok()
+            .and_then(|loan| {
+                let answered = lend_and_wait(supervision, request, bytes, loan.slot, deadline);
+                // The migrator returns the loan before it answers; one that did not
+                // loses it here, so nothing it still runs writes under the copy. A
+                // loan already returned has nothing left to revoke.
+                let _ = slime_rt::shared_buffer_revoke(fresh.slot, loan.id);
+                answered
+            });
+    let written = answered.and_then(|written| {
+        let length = (written as u64).div_ceil(PAGE) * PAGE;
+        if written == 0 {
+            return Some(0);
+        }
+        if slime_rt::shared_buffer_map(fresh.slot, MIGRATION_BASE, 0, length, false)
+            != slime_rt::ERR_SUCCESS
+        {
+            return None;
+        }
+        // SAFETY: the map above installed a read-only mapping of `length`
+        // bytes at `MIGRATION_BASE`, at least `written`, which stays until the
+        // unmap below.
+        unsafe {
+            let source = MIGRATION_BASE as *const u8;
+            for (index, byte) in bytes[..written].iter_mut().enumerate() {
+                *byte = source.add(index).read_volatile();
+            }
+        }
+        (slime_rt::shared_buffer_unmap(fresh.slot, MIGRATION_BASE) == slime_rt::ERR_SUCCESS)
+            .then_some(written)
+    });
+    if slime_rt::shared_buffer_release(fresh.slot) != slime_rt::ERR_SUCCESS {
+        fail(b"migration buffer");
+    }
+    written
+}
+
+/// Send `request` twice, with a read-only loan of the old state and then the
+/// writable loan in `fresh`, and wait for the migrator's answer: the bytes it
+/// wrote, if it succeeded and they fit, and it did so before `deadline`.
+fn lend_and_wait(
+    supervision: u32,
+    request: WireMigrationRequest,
+    bytes: &[u8],
+    fresh: u32,
+    deadline: u64,
+) -> Option<usize> {
+    let old = lend(&bytes[..request.length as usize])?;
+    let encoded = request.encode();
+    if !delegate(old, RIGHT_BUFFER_MAP, &encoded, deadline) {
+        // Undelivered, the loan is still this component's to give up.
+        if slime_rt::cap_drop(old) != slime_rt::ERR_SUCCESS {
+            fail(b"migration loan");
+        }
+        return None;
+    }
+    if !delegate(
+        fresh,
+        RIGHT_BUFFER_MAP | RIGHT_BUFFER_WRITE,
+        &encoded,
+        deadline,
+    ) {
+        return None;
+    }
+    let mut message = [0u8; slime_rt::MAX_MSG];
+    let mut caps = [0u64; slime_rt::MAX_CAPS_PER_MSG];
+    let received = loop {
+        match slime_rt::recv(MIGRATION_SLOT, &mut message, &mut caps) {
+            slime_rt::ERR_WOULDBLOCK => {
+                // A migrator that dies unanswered has answered no.
+                if !matches!(slime_rt::supervision_status(supervision), Ok(None))
+                    || expired(deadline)
+                {
+                    return None;
+                }
+                slime_rt::yield_now();
+            }
+            result if result < 0 => return None,
+            result => break result as usize,
+        }
+    };
+    let answer = WireMigrationReply::decode(&message[..received])?;
+    (answer.magic == generation::GENERATION_MAGIC
+        && answer.version == generation::FORMAT_VERSION
+        && answer.status == STATUS_OK
+        && answer.length as usize <= MIGRATION_BYTES.min(bytes.len()))
+    .then_some(answer.length as usize)
+}
+
+/// Put `bytes` in a sealed buffer and loan it read-only to the migrator. The
+/// buffer is released once the loan exists: its pages stay until the
+/// migrator returns the loan or dies.
+fn lend(bytes: &[u8]) -> Option<u32> {
+    let length = (bytes.len() as u64).div_ceil(PAGE).max(1) * PAGE;
+    let buffer =
+        slime_rt::shared_buffer_create(BUFFER_FACTORY_SLOT, (length / PAGE) as usize, true).ok()?;
+    let filled = slime_rt::shared_buffer_map(buffer.slot, LEND_BASE, 0, length, true)
+        == slime_rt::ERR_SUCCESS;
+    if filled {
+        // SAFETY: the map above installed a writable mapping of `length`
+        // bytes at `LEND_BASE`, at least `bytes.len()`, which stays until the
+        // unmap below.
+        unsafe {
+            let destination = LEND_BASE as *mut u8;
+            for (index, byte) in bytes.iter().enumerate() {
+                destination.add(index).write_volatile(*byte);
+            }
+        }
+    }
+    let loan = (filled
+        && slime_rt::shared_buffer_unmap(buffer.slot, LEND_BASE) == slime_rt::ERR_SUCCESS
+        && slime_rt::shared_buffer_seal(buffer.slot) == slime_rt::ERR_SUCCESS)
+        .then(|| slime_rt::shared_buffer_loan(buffer.slot, MIGRATION_SLOT, 0, length, false).ok())
+        .flatten();
+    if slime_rt::shared_buffer_release(buffer.slot) != slime_rt::ERR_SUCCESS {
+        fail(b"migration buffer");
+    }
+    loan.map(|loan| loan.slot)
+}
+
+/// Move the loan in `slot` to the migrator with `request` as its descriptor,
+/// if it takes it before `deadline`.
+fn delegate(
+    slot: u32,
+    rights: u64,
+    request: &[u8; generation::MIGRATION_REQUEST_LEN],
+    deadline: u64,
+) -> bool {
+    loop {
+        match slime_rt::capability_delegate(
+            MIGRATION_SLOT,
+            slot,
+            CapabilityDisposition::Move,
+            OBJECT_KIND_SHARED_BUFFER_LOAN,
+            rights,
+            request,
+        ) {
+            slime_rt::ERR_WOULDBLOCK if expired(deadline) => return false,
+            slime_rt::ERR_WOULDBLOCK => slime_rt::yield_now(),
+            result => return result >= 0,
+        }
+    }
+}
+
+/// Whether the migration clock has reached `deadline`. A clock that cannot
+/// be read has: nothing else could end the wait.
+fn expired(deadline: u64) -> bool {
+    !slime_rt::clock_now(MIGRATION_CLOCK_SLOT).is_ok_and(|now| now < deadline)
+}
+
+/// `state` with its pending generation rolled back to the state root staging
+/// fixed for it, as stage-0 and the root roll back: snapshot-before-upgrade
+/// bindings as staging kept them, discard-on-rollback bindings gone. Each
+/// binding that changed is journalled before the caller commits.
+///
+/// `None` refuses the rollback unwritten, as `stage_state` refuses a stage.
+fn roll_back_state(
+    io: &mut BlockCapability,
+    slots: &StateSlots,
+    store: &mut ObjectStore,
+    state: &BootState,
+) -> Option<BootState> {
+    let rolled = state.rollback_pending().ok()?;
+    let Some(pending) = state.pending else {
+        return Some(rolled);
+    };
+    let (_, generation_bytes) = diff_staging();
+    let Some(bytes) = generation_record(io, store, &pending, generation_bytes) else {
+        return Some(rolled);
+    };
+    let generation = Generation::decode(bytes).ok()?;
+    let (map_bytes, snapshot_bytes, rolled_bytes) = state_staging();
+    let current = state_map(io, store, &state.state_root, map_bytes)?;
+    let snapshot = snapshot_record(io, store, &pending, snapshot_bytes);
+    // The state itself was fixed at staging; this replays the same plan only
+    // to journal what it restored and dropped under the commit it belongs to.
+    let mut journal = slots.journal(io);
+    state_policy::roll_back(
+        &generation,
+        &current,
+        snapshot.as_ref(),
+        rolled_bytes,
+        |action, policy, entry| journal.append(io, action, policy, pending, &rolled, entry),
+    )
+    .ok()?;
+    journal.flush(io);
+    Some(rolled)
+}
+
+/// Journal that promoting `promoted`'s new known-good generation let its
+/// snapshot go. Nothing is removed from the store: the journal is what says
+/// no rollback will read it again.
+fn release_state(
+    io: &mut BlockCapability,
+    slots: &StateSlots,
+    store: &ObjectStore,
+    promoted: &BootState,
+    identity: [u8; 32],
+) {
+    let (_, generation_bytes) = diff_staging();
+    let Some(generation) = generation_record(io, store, &identity, generation_bytes)
+        .and_then(|bytes| Generation::decode(bytes).ok())
+    else {
+        return;
+    };
+    let (_, snapshot_bytes, _) = state_staging();
+    let Some(snapshot) = snapshot_record(io, store, &identity, snapshot_bytes) else {
+        return;
+    };
+    let mut journal = slots.journal(io);
+    let released = state_policy::snapshotted(&generation, &snapshot, |entry| {
+        journal.append(
+            io,
+            JournalAction::Release,
+            contract::POLICY_SNAPSHOT_BEFORE_UPGRADE,
+            identity,
+            promoted,
+            entry,
+        );
+    });
+    if released.is_ok_and(|count| count != 0) {
+        journal.flush(io);
+    }
+}
+
+/// The state map `root` names: empty for the empty root, otherwise its
+/// record, which the store re-hashes to `root` on the way out.
+fn state_map<'a>(
+    io: &mut BlockCapability,
+    store: &ObjectStore,
+    root: &[u8; 32],
+    out: &'a mut [u8],
+) -> Option<StateMap<'a>> {
+    if *root == empty_state_root() {
+        return Some(StateMap::EMPTY);
+    }
+    let (_, len) = store.get(io, root, out).ok()?;
+    StateMap::decode(&out[..len]).ok()
+}
+
+/// The newest snapshot staging `identity` kept, found by tag the way
+/// `generation_record` finds a generation.
+fn snapshot_record<'a>(
+    io: &mut BlockCapability,
+    store: &ObjectStore,
+    identity: &[u8; 32],
+    out: &'a mut [u8],
+) -> Option<StateMap<'a>> {
+    let entry = store
+        .records_of_type(generation::STATE_SNAPSHOT_OBJECT_TYPE)
+        .filter(|entry| {
+            store
+                .record_metadata(io, entry)
+                .is_ok_and(|metadata| metadata.generation == *identity)
+        })
+        .last()?;
+    let (_, len) = store.get(io, &entry.hash, out).ok()?;
+    state_policy::decode_snapshot(identity, &out[..len]).ok()
+}
+
+/// The generation record for `identity`, read into `out`.
+///
+/// Found the way `parent_of` finds a release: by tag and by the generation the
+/// record header carries, and accepted only if the bytes are that generation.
+fn generation_record<'a>(
+    io: &mut BlockCapability,
+    store: &ObjectStore,
+    identity: &[u8; 32],
+    out: &'a mut [u8],
+) -> Option<&'a [u8]> {
+    let entry = store
+        .records_of_type(generation::GENERATION_OBJECT_TYPE)
+        .find(|entry| {
+            store
+                .record_metadata(io, entry)
+                .is_ok_and(|metadata| metadata.generation == *identity)
+        })?;
+    let (_, len) = store.get(io, &entry.hash, out).ok()?;
+    (generation_identity(&out[..len]) == *identity).then_some(&out[..len])
+}
+
+/// The trust root BootState names, read back from its record and held to the
+/// version BootState carries, or the compiled-in root when it names none.
+/// `None` when the named record is missing or is not that version.
+fn installed_trust_root(
+    io: &mut BlockCapability,
+    store: &ObjectStore,
+    state: &BootState,
+) -> Option<TrustRoot> {
+    let Some(reference) = state.trust_root else {
+        return Some(INITIAL_TRUST_ROOT);
+    };
+    let mut bytes = [0u8; TRUST_ROOT_RECORD_BYTES];
+    let (_, len) = store.get(io, &reference.object, &mut bytes).ok()?;
+    TrustRoot::decode(&bytes[..len])
+        .ok()
+        .filter(|root| root.version == reference.version)
+}
+
+/// Whether a release record for `identity` carries signatures `root`
+/// accepts. Found the way `parent_of` finds one.
+fn released_under(
+    io: &mut BlockCapability,
+    store: &ObjectStore,
+    identity: &[u8; 32],
+    root: &TrustRoot,
+) -> bool {
+    store
+        .records_of_type(generation::RELEASE_OBJECT_TYPE)
+        .any(|entry| {
+            if !store
+                .record_metadata(io, entry)
+                .is_ok_and(|metadata| metadata.generation == *identity)
+            {
+                return false;
+            }
+            let mut bytes = [0u8; RELEASE_BYTES];
+            let Ok((_, len)) = store.get(io, &entry.hash, &mut bytes) else {
+                return false;
+            };
+            Release::decode(&bytes[..len]).is_ok_and(|release| {
+                release.generation == *identity && release.verify_signatures(root).is_ok()
+            })
+        })
+}
+
 fn reply(status: i32, identity: Option<[u8; 32]>, count: u32) -> WireGenerationReply {
     let words = identity.map(identity_words).unwrap_or([0; 4]);
     WireGenerationReply {
@@ -248,24 +1859,67 @@ fn reply(status: i32, identity: Option<[u8; 32]>, count: u32) -> WireGenerationR
     }
 }
 
-/// The two BootState slots and the older-slot-first commit rule.
+/// Where BootState, the health verdict and the shadow evidence sit on one
+/// partition, in sectors from its start.
+#[derive(Clone, Copy)]
+struct StateSectors {
+    slot_a: u64,
+    slot_b: u64,
+    verdict: u64,
+    evidence: u64,
+    /// Where a root reads a rotated trust root back, if it reads one here.
+    trust_root: Option<u64>,
+}
+
+/// Past the object store's records on its own partition.
+const STORE_SECTORS: StateSectors = StateSectors {
+    slot_a: STATE_SLOT_A,
+    slot_b: STATE_SLOT_B,
+    verdict: HEALTH_VERDICT,
+    evidence: SHADOW_EVIDENCE,
+    trust_root: None,
+};
+
+/// A boot store's first sectors: where a boot-selector root reads them.
+const BOOT_STORE_SECTORS: StateSectors = StateSectors {
+    slot_a: 0,
+    slot_b: 1,
+    verdict: (BOOTSTORE_VERDICT_OFFSET / SECTOR_BYTES) as u64,
+    evidence: (BOOTSTORE_EVIDENCE_OFFSET / SECTOR_BYTES) as u64,
+    // The sector straight after the boot store: `TRUST_ROOT_SECTOR` in
+    // slime-root/src/boot_selector.rs.
+    trust_root: Some((BOOTSTORE_CAPACITY / SECTOR_BYTES) as u64),
+};
+
+/// The two BootState slots and the older-slot-first commit rule, the
+/// evidence sectors beside them, and the journal on the store's partition.
 struct StateSlots {
+    /// The block capability the slots and evidence are read through.
+    device: u32,
     first_lba: u64,
+    last_lba: u64,
+    sectors: StateSectors,
+    /// The journal's first sector, always on the store's device.
+    journal_lba: u64,
 }
 
 impl StateSlots {
+    /// Whether BootState lives on a boot store, where a root selects from it,
+    /// rather than beside the object store.
+    fn on_boot_store(&self) -> bool {
+        self.device == BOOT_SLOT
+    }
+
     fn lba(&self, slot: Slot) -> u64 {
         self.first_lba
             + match slot {
-                Slot::A => STATE_SLOT_A,
-                Slot::B => STATE_SLOT_B,
+                Slot::A => self.sectors.slot_a,
+                Slot::B => self.sectors.slot_b,
             }
     }
 
-    fn select(
-        &self,
-        io: &mut BlockCapability,
-    ) -> Result<boot_contracts::bootstate::SelectedBootState, SelectionError> {
+    fn select(&self) -> Result<boot_contracts::bootstate::SelectedBootState, SelectionError> {
+        let mut io = BlockCapability(self.device);
         let mut a = [0u8; SLOT_BYTES];
         let mut b = [0u8; SLOT_BYTES];
         if io.read_sector(self.lba(Slot::A), &mut a).is_err()
@@ -276,24 +1930,121 @@ impl StateSlots {
         select_bootstate(&a, &b)
     }
 
-    fn write(&self, io: &mut BlockCapability, slot: Slot, state: &BootState) -> Result<(), ()> {
+    fn write(&self, slot: Slot, state: &BootState) -> Result<(), ()> {
+        let mut io = BlockCapability(self.device);
         let encoded = state.encode().map_err(|_| ())?;
         io.write_sector(self.lba(slot), &encoded).map_err(|_| ())?;
         io.flush().map_err(|_| ())
     }
 
+    /// The verdict sector, decoded. Damaged or absent reads as no verdict.
+    fn verdict(&self) -> Option<HealthVerdict> {
+        let mut bytes = [0u8; HEALTH_VERDICT_BYTES];
+        BlockCapability(self.device)
+            .read_sector(self.first_lba + self.sectors.verdict, &mut bytes)
+            .ok()?;
+        HealthVerdict::decode(&bytes).ok()
+    }
+
+    /// The shadow-evidence sector, decoded. Damaged or absent reads as none.
+    fn shadow(&self) -> Option<ShadowEvidence> {
+        let mut bytes = [0u8; SHADOW_EVIDENCE_BYTES];
+        BlockCapability(self.device)
+            .read_sector(self.first_lba + self.sectors.evidence, &mut bytes)
+            .ok()?;
+        ShadowEvidence::decode(&bytes).ok()
+    }
+
+    fn write_shadow(&self, request: &ShadowEvidence) {
+        let mut io = BlockCapability(self.device);
+        let Ok(bytes) = request.encode() else {
+            fail(b"shadow request encode");
+        };
+        if io
+            .write_sector(self.first_lba + self.sectors.evidence, &bytes)
+            .and_then(|()| io.flush())
+            .is_err()
+        {
+            fail(b"shadow request write");
+        }
+    }
+
+    /// Whether a rotated root has somewhere to go that a root would read it
+    /// from: nowhere is needed beside the object store, whose record the
+    /// manager reads back itself.
+    fn trust_root_fits(&self) -> bool {
+        self.sectors
+            .trust_root
+            .is_none_or(|sector| self.first_lba + sector <= self.last_lba)
+    }
+
+    /// Write the record a committed BootState will name where a root reads
+    /// it back. Before the commit, so a reference never precedes its bytes.
+    fn write_trust_root(&self, record: &[u8; TRUST_ROOT_RECORD_BYTES]) {
+        let Some(sector) = self.sectors.trust_root else {
+            return;
+        };
+        let mut io = BlockCapability(self.device);
+        if io
+            .write_sector(self.first_lba + sector, record)
+            .and_then(|()| io.flush())
+            .is_err()
+        {
+            fail(b"trust root write");
+        }
+    }
+
+    /// Zero the evidence a transition has used, so a later candidate with the
+    /// same identity is judged by its own boot.
+    fn consume_evidence(&self, verdict: bool, shadow: bool) {
+        let mut io = BlockCapability(self.device);
+        for (used, lba) in [
+            (verdict, self.sectors.verdict),
+            (shadow, self.sectors.evidence),
+        ] {
+            if used
+                && io
+                    .write_sector(self.first_lba + lba, &[0; SECTOR_BYTES])
+                    .and_then(|()| io.flush())
+                    .is_err()
+            {
+                fail(b"evidence write");
+            }
+        }
+    }
+
+    /// The journal, positioned after its newest entry.
+    fn journal(&self, io: &mut BlockCapability) -> Journal {
+        let mut newest = 0;
+        for sector in 0..STATE_JOURNAL_SECTORS as u64 {
+            let mut bytes = [0u8; STATE_JOURNAL_BYTES];
+            if io
+                .read_sector(self.journal_lba + sector, &mut bytes)
+                .is_err()
+            {
+                fail(b"journal read");
+            }
+            if let Ok(entry) = StateJournalEntry::decode(&bytes) {
+                newest = newest.max(entry.sequence);
+            }
+        }
+        Journal {
+            first_lba: self.journal_lba,
+            next: newest + 1,
+        }
+    }
+
     /// Write to the slot that was not selected, then re-select off the device.
     fn commit(
         &self,
-        io: &mut BlockCapability,
         selected: Slot,
         state: &BootState,
     ) -> boot_contracts::bootstate::SelectedBootState {
         let target = selected.other();
-        if self.write(io, target, state).is_err() {
+        if self.write(target, state).is_err() {
             fail(b"commit write");
         }
-        let live = match self.select(io) {
+        let live = match self.select() {
             Ok(live) => live,
             Err(_) => fail(b"commit select"),
         };
@@ -304,15 +2055,62 @@ impl StateSlots {
     }
 }
 
-/// The device, reached through the granted capability.
-struct BlockCapability;
+/// The state journal's ring and the sequence its next entry takes. A damaged
+/// sector reads as empty, and is overwritten in its turn.
+struct Journal {
+    first_lba: u64,
+    next: u64,
+}
+
+impl Journal {
+    /// Write one entry for the commit of `state`. Unflushed until `flush`.
+    fn append(
+        &mut self,
+        io: &mut BlockCapability,
+        action: JournalAction,
+        policy: u32,
+        generation: [u8; 32],
+        state: &BootState,
+        entry: StateEntry,
+    ) {
+        let record = StateJournalEntry {
+            sequence: self.next,
+            action,
+            policy,
+            boot_sequence: state.sequence,
+            schema_version: entry.schema_version,
+            generation,
+            binding: entry.binding_identity,
+            object: entry.object_identity,
+            state_root: state.state_root,
+        };
+        let Ok(bytes) = record.encode() else {
+            fail(b"journal entry");
+        };
+        if io
+            .write_sector(self.first_lba + record.sector(), &bytes)
+            .is_err()
+        {
+            fail(b"journal write");
+        }
+        self.next += 1;
+    }
+
+    fn flush(&self, io: &mut BlockCapability) {
+        if io.flush().is_err() {
+            fail(b"journal flush");
+        }
+    }
+}
+
+/// A device, reached through the capability in this slot.
+struct BlockCapability(u32);
 
 impl BlockIo for BlockCapability {
     fn read_sector(&mut self, lba: u64, out: &mut [u8; SECTOR_BYTES]) -> Result<(), IoError> {
         let request = block_request(block::OP_READ, lba);
         let mut reply = [0u8; block::REPLY_LEN];
-        let status =
-            slime_rt::block_transact_sector(BLOCK_SLOT, &request.encode(), &mut reply, out);
+        let status = slime_rt::block_transact_sector(self.0, &request.encode(), &mut reply, out);
         if status < 0 || decode_block_reply(&reply).sectors_done != 1 {
             return Err(IoError::Device);
         }
@@ -322,8 +2120,7 @@ impl BlockIo for BlockCapability {
     fn write_sector(&mut self, lba: u64, data: &[u8; SECTOR_BYTES]) -> Result<(), IoError> {
         let request = block_request(block::OP_WRITE, lba);
         let mut reply = [0u8; block::REPLY_LEN];
-        let status =
-            slime_rt::block_transact_write(BLOCK_SLOT, &request.encode(), data, &mut reply);
+        let status = slime_rt::block_transact_write(self.0, &request.encode(), data, &mut reply);
         if status < 0 || decode_block_reply(&reply).sectors_done != 1 {
             return Err(IoError::Device);
         }
@@ -333,20 +2130,38 @@ impl BlockIo for BlockCapability {
     fn flush(&mut self) -> Result<(), IoError> {
         let request = block_request(block::OP_FLUSH, 0);
         let mut reply = [0u8; block::REPLY_LEN];
-        if slime_rt::block_transact(BLOCK_SLOT, &request.encode(), &mut reply) < 0 {
+        if slime_rt::block_transact(self.0, &request.encode(), &mut reply) < 0 {
             return Err(IoError::Device);
         }
         Ok(())
     }
 }
 
+/// The boot disk's store partition, refused unless it holds a whole boot
+/// store, as the root's selector refuses it.
+fn locate_boot_store(io: &mut BlockCapability) -> Option<gpt::Partition> {
+    let capacity = device_capacity(io)?;
+    let mut reader = |lba: u64, out: &mut [u8; SECTOR_BYTES]| -> Result<(), GptError> {
+        io.read_sector(lba, out).map_err(|_| GptError::Device)
+    };
+    let selected = gpt::validate_store_partition(&mut reader, capacity, None).ok()?;
+    let last = selected
+        .partition
+        .first_lba
+        .checked_add((BOOTSTORE_CAPACITY / SECTOR_BYTES) as u64 - 1)?;
+    (last <= selected.partition.last_lba).then_some(selected.partition)
+}
+
 fn locate_partition(io: &mut BlockCapability) -> Option<gpt::Partition> {
     let capacity = device_capacity(io)?;
     let mut reader = |lba: u64, out: &mut [u8; SECTOR_BYTES]| -> Result<(), GptError> {
         io.read_sector(lba, out).map_err(|_| GptError::Device)
     };
-    let selected = gpt::validate_store_partition(&mut reader, capacity).ok()?;
-    let last = selected.partition.first_lba.checked_add(STATE_SLOT_B)?;
+    let selected = gpt::validate_store_partition(&mut reader, capacity, None).ok()?;
+    let last = selected
+        .partition
+        .first_lba
+        .checked_add(STATE_JOURNAL + STATE_JOURNAL_SECTORS as u64 - 1)?;
     (last <= selected.partition.last_lba).then_some(selected.partition)
 }
 
@@ -445,6 +2260,76 @@ fn report(op: &[u8], state: &BootState) {
     slime_rt::debug_write(&line[..len]);
 }
 
+/// One change, as `+`, `-` or `~`, its table, and the record's key: a name, or
+/// the first eight bytes of an identity.
+fn report_change(change: &Change<'_>) {
+    let (sign, table, key) = match change {
+        Change::Executable(delta) => (sign(delta), "executable", name_key(delta, |v| v.name)),
+        Change::Instance(delta) => (sign(delta), "instance", name_key(delta, |v| v.name)),
+        Change::Grant(delta) => (sign(delta), "grant", name_key(delta, |v| v.name)),
+        Change::State(delta) => (sign(delta), "state", name_key(delta, |v| v.name)),
+        Change::FabricLimits(delta) => (sign(delta), "fabric-limits", Key::Name("graph")),
+        Change::Route(delta) => (sign(delta), "route", identity_key(delta, |v| v.identity)),
+        Change::Participant(delta) => {
+            (sign(delta), "participant", identity_key(delta, |v| v.grant))
+        }
+        Change::SharedBufferQuota(delta) => (
+            sign(delta),
+            "shared-buffer-quota",
+            identity_key(delta, |v| v.holder_identity),
+        ),
+    };
+    let mut line = [0u8; 160];
+    let mut len = 0;
+    len += copy(&mut line[len..], b"[sel4-generation-manager] diff-change ");
+    len += copy(&mut line[len..], sign);
+    len += copy(&mut line[len..], b" ");
+    len += copy(&mut line[len..], table.as_bytes());
+    len += copy(&mut line[len..], b" ");
+    match key {
+        Key::Name(name) => len += copy(&mut line[len..], name.as_bytes()),
+        Key::Identity(identity) => {
+            const DIGITS: &[u8; 16] = b"0123456789abcdef";
+            for byte in &identity[..8] {
+                let digits = [DIGITS[(byte >> 4) as usize], DIGITS[(byte & 0xf) as usize]];
+                len += copy(&mut line[len..], &digits);
+            }
+        }
+    }
+    len += copy(&mut line[len..], b"\n");
+    slime_rt::debug_write(&line[..len]);
+}
+
+enum Key<'a> {
+    Name(&'a str),
+    Identity([u8; 32]),
+}
+
+fn sign<T>(delta: &Delta<T>) -> &'static [u8] {
+    match delta {
+        Delta::Added(_) => b"+",
+        Delta::Removed(_) => b"-",
+        Delta::Changed { .. } => b"~",
+    }
+}
+
+/// The record a delta is about: the new one, unless it was removed.
+fn subject<T>(delta: &Delta<T>) -> &T {
+    match delta {
+        Delta::Added(record) | Delta::Removed(record) | Delta::Changed { new: record, .. } => {
+            record
+        }
+    }
+}
+
+fn name_key<'a, T>(delta: &Delta<T>, name: impl Fn(&T) -> &'a str) -> Key<'a> {
+    Key::Name(name(subject(delta)))
+}
+
+fn identity_key<T>(delta: &Delta<T>, identity: impl Fn(&T) -> [u8; 32]) -> Key<'static> {
+    Key::Identity(identity(subject(delta)))
+}
+
 fn copy(out: &mut [u8], source: &[u8]) -> usize {
     let len = source.len().min(out.len());
     out[..len].copy_from_slice(&source[..len]);
@@ -16,9 +16,14 @@
 //!   executed on an unverified root;
 //! * the recovery index decodes: bounds, ascending binding order, and a
 //!   content-addressed state root over every binding;
+//! * the object store is scrubbed in bounded steps, and each damaged record is
+//!   reported with every generation and directory snapshot that reaches it;
 //! * every state object the index names is retrieved from the object store and
 //!   its payload re-hashed, so a closure with a missing or corrupted object
 //!   fails before anything is written;
+//! * a rotated trust root the index names is retrieved, re-hashed, and decoded
+//!   at the version the index records, so a reconstructed root keeps the
+//!   rotation rather than reverting to the compiled-in keys;
 //! * the reconstructed BootState is written to both slots at sequences 1 and 2,
 //!   each flushed, so an interruption after the first still leaves one verified
 //!   root;
@@ -39,8 +44,12 @@ use boot_contracts::bootstate::{
     BootState, SLOT_BYTES, SelectionError, Slot, empty_state_root, select_bootstate,
 };
 use boot_contracts::gpt::{self, GptError};
-use boot_contracts::object_store::{BlockIo, IoError, ObjectStore};
+use boot_contracts::object_store::{
+    BlockIo, IoError, ObjectStore, ScrubCursor, ScrubProgress, ScrubReport,
+};
 use boot_contracts::recovery::RecoveryIndex;
+use boot_contracts::release::{TRUST_ROOT_RECORD_BYTES, TrustRoot};
+use slime_components::store_owners::{self, Owner, Root};
 use slime_proto::block::{self, WireBlockReply, WireBlockRequest};
 
 /// The primary device this component may reconstruct.
@@ -61,6 +70,9 @@ const INDEX_SECTORS: u64 = 4;
 /// Where the index records its own total length
 /// (`RECOVERY_INDEX_TOTAL_LEN_OFFSET` in the generated layout).
 const INDEX_BYTES_OFFSET: usize = 136;
+/// Sectors one scrub step may read. Small enough that the walk really is
+/// incremental on the fixture's store, which is what the step count shows.
+const SCRUB_BUDGET: u64 = 4;
 
 slime_rt::entry!(main);
 
@@ -132,6 +144,65 @@ fn main(_startup_arg: u32) {
         Ok(store) => store,
         Err(_) => fail(b"store open"),
     };
+    // The whole store, scrubbed in bounded steps and attributed to what each
+    // damaged record would cost: the generations whose records reach it, and
+    // the directory snapshots whose trees do. The index's state objects are
+    // its target generation's, whether or not a snapshot record names them,
+    // since they are what this reconstruction is about to need.
+    let mut cursor = ScrubCursor::new();
+    let mut report = ScrubReport::default();
+    let mut steps = 1u64;
+    while store
+        .scrub_step(&mut io, &mut cursor, SCRUB_BUDGET, &mut report)
+        .unwrap_or_else(|_| fail(b"scrub step"))
+        == ScrubProgress::Partial
+    {
+        steps += 1;
+    }
+    let mut roots = store_owners::roots(&mut io, &store);
+    roots.extend(
+        (0..index.state_count())
+            .filter_map(|position| index.state(position))
+            .map(|entry| Root {
+                hash: entry.object_identity,
+                owner: Owner::Generation(index.target_generation),
+            }),
+    );
+    let hashes: alloc::vec::Vec<[u8; 32]> = roots.iter().map(|root| root.hash).collect();
+    store
+        .attribute(&mut io, &mut report, &hashes, store_owners::references)
+        .unwrap_or_else(|_| fail(b"scrub attribution"));
+    for damage in &report.damaged {
+        write_pair(
+            b"[sel4-recovery-probe] scrub damaged lba=",
+            damage.lba,
+            b" owners=",
+            damage.referenced_by.len() as u64,
+        );
+        let mut reported: alloc::vec::Vec<Owner> = alloc::vec::Vec::new();
+        for root in roots
+            .iter()
+            .filter(|root| damage.referenced_by.contains(&root.hash))
+        {
+            if !reported.contains(&root.owner) {
+                reported.push(root.owner);
+                write_owner(damage.lba, &root.owner);
+            }
+        }
+    }
+    write_pair(
+        b"[sel4-recovery-probe] scrub records=",
+        report.records_checked as u64,
+        b" damaged=",
+        report.damaged.len() as u64,
+    );
+    write_pair(
+        b"[sel4-recovery-probe] scrub steps=",
+        steps,
+        b" sectors=",
+        report.sectors_read,
+    );
+
     for position in 0..index.state_count() {
         let Some(entry) = index.state(position) else {
             fail(b"index state entry");
@@ -155,6 +226,14 @@ fn main(_startup_arg: u32) {
     );
 
     reconstruct(&mut io, &partition, first, &index, b"reconstruction");
+    if let Some(trust_root) = index.trust_root {
+        write_pair(
+            b"[sel4-recovery-probe] trust root restored version=",
+            trust_root.version as u64,
+            b" release=",
+            index.accepted_release_sequence,
+        );
+    }
 
     // Re-selected off the device: the root a fresh boot would pick must be the
     // index's target, at the higher of the two sequences.
@@ -167,6 +246,7 @@ fn main(_startup_arg: u32) {
     if selected.state.known_good != index.target_generation
         || selected.state.pending.is_some()
         || selected.state.accepted_release_sequence != index.accepted_release_sequence
+        || selected.state.trust_root != index.trust_root
     {
         fail(b"reconstructed root");
     }
@@ -274,6 +354,23 @@ fn reconstruct(
             .get(io, &entry.object_identity, &mut payload)
             .unwrap_or_else(|_| fail(context));
     }
+    // The trust root the target was accepted under. A BootState naming a record
+    // the store cannot produce would leave stage 0 unable to verify anything,
+    // so it is retrieved and decoded at the recorded version before any write.
+    if let Some(trust_root) = index.trust_root {
+        let mut record = [0u8; TRUST_ROOT_RECORD_BYTES];
+        match store.stat(&trust_root.object) {
+            Some((_, len)) if len as usize == TRUST_ROOT_RECORD_BYTES => {}
+            _ => fail(b"the trust root the index names is absent"),
+        }
+        store
+            .get(io, &trust_root.object, &mut record)
+            .unwrap_or_else(|_| fail(b"the trust root failed verification"));
+        match TrustRoot::decode(&record) {
+            Ok(root) if root.version == trust_root.version => {}
+            _ => fail(b"the trust root record disagrees with the index"),
+        }
+    }
     drop(store);
     for (slot, sequence) in [(Slot::A, 1u64), (Slot::B, 2)] {
         let state = BootState {
@@ -288,6 +385,8 @@ fn reconstruct(
                 index.state_root
             },
             accepted_release_sequence: index.accepted_release_sequence,
+            trust_root: index.trust_root,
+            rollback_state_root: None,
         };
         let encoded = state.encode().unwrap_or_else(|_| fail(context));
         let lba = first
@@ -351,7 +450,7 @@ fn locate_partition(io: &mut BlockCapability) -> Option<gpt::Partition> {
     let mut reader = |lba: u64, out: &mut [u8; SECTOR_BYTES]| -> Result<(), GptError> {
         io.read_sector(lba, out).map_err(|_| GptError::Device)
     };
-    let selected = gpt::validate_store_partition(&mut reader, capacity).ok()?;
+    let selected = gpt::validate_store_partition(&mut reader, capacity, None).ok()?;
     let last = selected
         .partition
         .first_lba
@@ -415,6 +514,27 @@ fn write_pair(prefix: &[u8], first: u64, middle: &[u8], second: u64) {
     slime_rt::debug_write(&line[..len]);
 }
 
+/// `scrub owner lba=N generation=<id>` or `snapshot=<head>`, the identity
+/// abbreviated to its first eight bytes as the manager's diff lines are.
+fn write_owner(lba: u64, owner: &Owner) {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let (label, identity): (&[u8], _) = match owner {
+        Owner::Generation(identity) => (b" generation=", identity),
+        Owner::Snapshot(head) => (b" snapshot=", head),
+    };
+    let mut line = [0u8; 128];
+    let mut len = 0;
+    len += copy(&mut line[len..], b"[sel4-recovery-probe] scrub owner lba=");
+    len += copy(&mut line[len..], &decimal(lba));
+    len += copy(&mut line[len..], label);
+    for byte in &identity[..8] {
+        let digits = [DIGITS[(byte >> 4) as usize], DIGITS[(byte & 0xf) as usize]];
+        len += copy(&mut line[len..], &digits);
+    }
+    len += copy(&mut line[len..], b"\n");
+    slime_rt::debug_write(&line[..len]);
+}
+
 fn copy(out: &mut [u8], source: &[u8]) -> usize {
     let len = source.len().min(out.len());
     out[..len].copy_from_slice(&source[..len]);
@@ -94,6 +94,8 @@ fn main(_startup_arg: u32) {
         generation_root: GENERATION_ROOT,
         state_root: empty_state_root(),
         accepted_release_sequence: 0,
+        trust_root: None,
+        rollback_state_root: None,
     };
     if slots.write(&mut io, Slot::A, &genesis).is_err() {
         fail(b"genesis write");
@@ -355,7 +357,7 @@ fn locate_partition(io: &mut BlockCapability) -> Option<gpt::Partition> {
     let mut reader = |lba: u64, out: &mut [u8; SECTOR_BYTES]| -> Result<(), GptError> {
         io.read_sector(lba, out).map_err(|_| GptError::Device)
     };
-    let selected = gpt::validate_store_partition(&mut reader, capacity).ok()?;
+    let selected = gpt::validate_store_partition(&mut reader, capacity, None).ok()?;
     // Both slots must fall inside the partition.
     let last = selected.partition.first_lba.checked_add(STATE_SLOT_B)?;
     (last <= selected.partition.last_lba).then_some(selected.partition)
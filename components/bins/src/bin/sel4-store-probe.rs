@@ -74,7 +74,7 @@ fn main(_startup_arg: u32) {
     let mut reader = |lba: u64, out: &mut [u8; SECTOR_BYTES]| -> Result<(), GptError> {
         io.read_sector(lba, out).map_err(|_| GptError::Device)
     };
-    let selected = match gpt::validate_store_partition(&mut reader, capacity) {
+    let selected = match gpt::validate_store_partition(&mut reader, capacity, None) {
         Ok(selected) => selected,
         Err(error) => {
             // A refusal is a result, not a crash. Three fixtures are *supposed*
@@ -397,6 +397,9 @@ fn report_gpt(error: GptError) {
         GptError::ConflictingCopies => b"conflicting-copies",
         GptError::NoStorePartition => b"no-store-partition",
         GptError::AmbiguousStorePartition => b"ambiguous-store-partition",
+        GptError::KeyRequired => b"key-required",
+        GptError::NoTransferPartition => b"no-transfer-partition",
+        GptError::AmbiguousTransferPartition => b"ambiguous-transfer-partition",
     };
     slime_rt::debug_write(b"[sel4-store-probe] gpt error=");
     slime_rt::debug_write(name);
@@ -416,6 +419,11 @@ fn report_store(error: StoreError) {
         StoreError::BufferTooSmall => b"buffer-too-small",
         StoreError::DuplicateIdentity => b"duplicate-identity",
         StoreError::HashMismatch => b"hash-mismatch",
+        StoreError::WrongObjectType => b"wrong-object-type",
+        StoreError::ReadOnly => b"read-only",
+        StoreError::KeyRequired => b"key-required",
+        StoreError::WrongKey => b"wrong-key",
+        StoreError::NotEncrypted => b"not-encrypted",
     };
     slime_rt::debug_write(b"[sel4-store-probe] store error=");
     slime_rt::debug_write(name);
@@ -14,9 +14,12 @@
 //!   self-excluding SHA-256 over the whole record;
 //! * a tampered byte anywhere fails that digest, so an altered manifest cannot
 //!   install;
+//! * the receiver answers with the digests its store already holds, and the
+//!   manifest omits the payload of those objects and no others;
 //! * its **closure** is verified — every object it carries re-hashes to the
-//!   identity it declares — before any BootState write, so an incomplete
-//!   transfer costs the receiver nothing and consumes no attempt;
+//!   identity it declares, and every object it omits is read back from the
+//!   receiver's store — before any BootState write, so an incomplete transfer
+//!   costs the receiver nothing and consumes no attempt;
 //! * state travels by declared policy, so nothing the source marked as not
 //!   travelling is shipped;
 //! * the generation stages **pending**, leaving the existing known-good root
@@ -33,10 +36,11 @@ use boot_contracts::bootstate::{
     BootState, SLOT_BYTES, SelectionError, Slot, empty_state_root, select_bootstate,
 };
 use boot_contracts::gpt::{self, GptError};
-use boot_contracts::object_store::{BlockIo, IoError};
+use boot_contracts::object_store::{BlockIo, IoError, ObjectStore};
 use boot_contracts::recovery::binding_identity;
 use boot_contracts::transfer::{
-    self, STATE_FLAG_READ_ONLY, STATE_FLAG_TRAVEL, TransferError, TransferManifest,
+    self, HOLDINGS_HEADER_LEN, Holdings, MAX_HELD_DIGESTS, STATE_FLAG_READ_ONLY, TransferError,
+    TransferManifest,
 };
 use slime_proto::block::{self, WireBlockReply, WireBlockRequest};
 
@@ -101,12 +105,43 @@ fn main(_startup_arg: u32) {
         generation_root: RECEIVER_GENERATION_ROOT,
         state_root: empty_state_root(),
         accepted_release_sequence: 1,
+        trust_root: None,
+        rollback_state_root: None,
     };
     if slots.write(&mut receiver, Slot::A, &genesis).is_err() {
         fail(b"receiver genesis");
     }
     slime_rt::debug_write(b"[sel4-transfer-probe] receiver holds a known-good root\n");
 
+    // The receiver's answer: every digest its store already holds. A sender
+    // reads this before building the manifest and omits those payloads; the
+    // fixture's manifest was built against this same store.
+    let Ok(store) = ObjectStore::open(&mut receiver, &receiver_partition) else {
+        fail(b"receiver store");
+    };
+    let mut held = [[0u8; 32]; MAX_HELD_DIGESTS];
+    let mut count = 0;
+    for entry in store.entries() {
+        let Some(slot) = held.get_mut(count) else {
+            fail(b"the receiver holds more than one answer can name");
+        };
+        *slot = entry.hash;
+        count += 1;
+    }
+    let mut answer = [0u8; HOLDINGS_HEADER_LEN + MAX_HELD_DIGESTS * 32];
+    let Ok(len) = transfer::encode_holdings(&mut held[..count], &mut answer) else {
+        fail(b"holdings encode");
+    };
+    let Ok(holdings) = Holdings::decode(&answer[..len]) else {
+        fail(b"holdings decode");
+    };
+    write_pair(
+        b"[sel4-transfer-probe] holdings answered objects=",
+        holdings.len() as u64,
+        b" bytes=",
+        len as u64,
+    );
+
     // Read the manifest off the source.
     let Some(source_partition) = locate_partition(&mut source) else {
         fail(b"source partition");
@@ -167,28 +202,48 @@ fn main(_startup_arg: u32) {
     }
     slime_rt::debug_write(b"[sel4-transfer-probe] tampered manifest refused\n");
 
-    // The closure, verified before any BootState write.
-    let mut carried = 0;
-    for index in 0..manifest.object_count() {
-        let Ok(object) = manifest.object(index) else {
+    // The manifest omits exactly what the receiver answered for: an omitted
+    // payload the answer does not name would leave the closure incomplete.
+    for object in manifest.objects() {
+        let Ok(object) = object else {
             fail(b"manifest object entry");
         };
-        let Some(payload) = object.payload else {
-            fail(b"the closure is incomplete");
-        };
-        if payload.len() != object.length {
-            fail(b"object length");
-        }
-        if slime_rt::sha256(payload) != object.digest {
-            fail(b"object content hash");
+        if object.payload.is_none() != holdings.holds(&object.digest) {
+            fail(b"the manifest disagrees with the receiver's holdings");
         }
-        carried += 1;
     }
+
+    // A receiver that lacks an omitted object refuses the whole closure.
+    if manifest.verify_closure(|_, _| false) != Err(TransferError::MissingObject) {
+        fail(b"a closure missing a held object was accepted");
+    }
+    slime_rt::debug_write(b"[sel4-transfer-probe] missing held object refused\n");
+
+    // The closure, verified before any BootState write. An omitted object is
+    // read back from the receiver's store, which re-hashes it, rather than
+    // taken on the answer's word.
+    let closure = manifest.verify_closure(|digest, length| {
+        if store
+            .stat(digest)
+            .is_none_or(|(_, stored)| stored as usize != length)
+        {
+            return false;
+        }
+        let mut payload = alloc::vec![0u8; length];
+        store.get(&mut receiver, digest, &mut payload).is_ok()
+    });
+    let closure = match closure {
+        Ok(closure) => closure,
+        Err(error) => {
+            report_transfer(error);
+            fail(b"the closure is incomplete");
+        }
+    };
     write_pair(
-        b"[sel4-transfer-probe] closure verified objects=",
-        carried,
-        b" of=",
-        manifest.object_count() as u64,
+        b"[sel4-transfer-probe] closure verified carried=",
+        closure.carried as u64,
+        b" held=",
+        closure.held as u64,
     );
 
     // Validate the manifest against the source's state set, not against its own
@@ -377,7 +432,7 @@ fn locate_partition(io: &mut Device) -> Option<gpt::Partition> {
     let mut reader = |lba: u64, out: &mut [u8; SECTOR_BYTES]| -> Result<(), GptError> {
         io.read_sector(lba, out).map_err(|_| GptError::Device)
     };
-    gpt::validate_store_partition(&mut reader, capacity)
+    gpt::validate_store_partition(&mut reader, capacity, None)
         .ok()
         .map(|selected| selected.partition)
 }
@@ -436,6 +491,9 @@ fn report_transfer(error: TransferError) {
         TransferError::BadBounds => b"bad-bounds",
         TransferError::BadHash => b"bad-hash",
         TransferError::BadEntry => b"bad-entry",
+        TransferError::BadPayload => b"bad-payload",
+        TransferError::MissingObject => b"missing-object",
+        TransferError::BadSource => b"bad-source",
     };
     slime_rt::debug_write(b"[sel4-transfer-probe] manifest error=");
     slime_rt::debug_write(name);
@@ -7,14 +7,15 @@ use slime_proto::{
         CAPABILITY_ROLE_STDIN, CAPABILITY_ROLE_WORKING_DIRECTORY, REQUEST_FLAG_SHUTDOWN,
         REQUEST_FLAG_WAIT, REQUEST_LEN, WireSpawnReply, WireSpawnRequest,
     },
+    syscall_abi::SCHEDULING_CLASS_BACKGROUND,
     valid_spawn_request,
 };
 use slime_rt::{
     CapabilityDisposition, ERR_BAD_CAP, ERR_INVALID_ARG, ERR_OUT_OF_MEMORY, ERR_WOULDBLOCK,
-    MAX_CAPS_PER_MSG, MAX_MSG, SpawnGrant, Termination,
+    MAX_CAPS_PER_MSG, MAX_MSG, SchedulingClass, SpawnGrant, Termination,
 };
 // B59: the capability-rights vocabulary is generated from
-// `contracts/generation/v5/schema.zt`; these were local copies of the same
+// `contracts/generation/v6/schema.zt`; these were local copies of the same
 // bit numbering.
 use boot_contracts::generation::{RIGHT_DIRECTORY_READ, RIGHT_SUPERVISE};
 
@@ -172,7 +173,15 @@ fn handle_inner(
     } else {
         &directory_grant[..0]
     };
-    match slime_rt::spawn(executable_slot, grants) {
+    // Passed through as asked: the root refuses nothing a spawn can request,
+    // since the only class a client can name beside the declared one gives
+    // time away.
+    let class = if u64::from(request.scheduling_class) == SCHEDULING_CLASS_BACKGROUND {
+        SchedulingClass::Background
+    } else {
+        SchedulingClass::Declared
+    };
+    match slime_rt::spawn_in_class(executable_slot, grants, class) {
         Ok(spawned) => {
             if send_context(context_slot, &request).is_err() {
                 while let Ok(None) = slime_rt::supervision_status(spawned.supervision_slot) {
@@ -156,6 +156,9 @@ pub struct Broker {
     clients: [Option<u32>; CLIENTS],
     server_slot: Option<u32>,
     time_control: u32,
+    /// The plane's simulated clock, granted read-only. A time record only
+    /// wakes the broker; the instant it moves to is this clock's reading.
+    clock: u32,
     supervision: [u32; SUPERVISION_HANDLES],
     calls: [Call; MAX_CALLS],
     high_water: [u64; 2],
@@ -232,6 +235,11 @@ pub struct Broker {
     /// name the edge by this identity rather than by a slot or a component
     /// name, so the artifact is comparable across boots.
     route: u64,
+    /// The graph's index for the route, as the root names it.
+    route_index: usize,
+    /// The root has been told this route was answered. Once is enough: the
+    /// probe it credits passes and stays passed.
+    answer_reported: bool,
 }
 
 impl Broker {
@@ -240,6 +248,7 @@ impl Broker {
         clients: [u32; CLIENTS],
         server_slot: u32,
         time_control: u32,
+        clock: u32,
         supervision: [u32; SUPERVISION_HANDLES],
     ) -> Self {
         Self {
@@ -247,6 +256,7 @@ impl Broker {
             clients: [Some(clients[0]), Some(clients[1])],
             server_slot: Some(server_slot),
             time_control,
+            clock,
             supervision,
             calls: [Call::EMPTY; MAX_CALLS],
             high_water: [0; 2],
@@ -265,6 +275,8 @@ impl Broker {
             // is not a const operation, and zero is not a valid route identity
             // so an unfolded broker cannot emit an edge record by accident.
             route: 0,
+            route_index: 0,
+            answer_reported: false,
         }
     }
 
@@ -304,7 +316,7 @@ impl Broker {
     }
 
     pub fn run(&mut self) {
-        self.verify_graph();
+        self.route_index = self.verify_graph();
         // The route identity is the graph's own name for this edge, folded from
         // the same three inputs the generation folds. Recorded once here so the
         // trace names the admitted edge and not a local label.
@@ -460,8 +472,9 @@ impl Broker {
     /// Called once from `run`, so the read is not in the pump loop. The
     /// interface is folded into the route identity rather than compared as a
     /// name: a `parameters` route carrying a different contract resolves to no
-    /// index at all instead of matching by string.
-    fn verify_graph(&self) {
+    /// index at all instead of matching by string. Returns that index, which is
+    /// how the root names the route when this broker reports it answered.
+    fn verify_graph(&self) -> usize {
         let mut graph_rows = slime_components::fabric_self_view::EMPTY_ROWS;
         let Ok(row_count) = slime_components::fabric_self_view::rows(&mut graph_rows) else {
             fail(b"call graph read did not complete");
@@ -497,6 +510,7 @@ impl Broker {
         if declared(b"fabric-call-server", DIRECTION_SERVER) != 1 {
             fail(b"call server graph declaration");
         }
+        route_index
     }
 
     fn pump_client(&mut self, client: usize) -> bool {
@@ -1215,6 +1229,7 @@ impl Broker {
                     slime_rt::debug_write(b"[fabric] call cancelled\n");
                 } else {
                     self.deliver_inline_reply(index, outward);
+                    self.report_answer();
                     if status == STATUS_REJECTED {
                         slime_rt::debug_write(b"[fabric] server rejection routed\n");
                     } else {
@@ -1266,11 +1281,26 @@ impl Broker {
                 let buffer_slot =
                     relay_shared_payload(self.buffer_factory_slot, loan_slot, &descriptor);
                 self.deliver_shared_reply(index, outward, buffer_slot);
+                self.report_answer();
             }
             _ => release_caps(caps),
         }
     }
 
+    /// Tell the root the server answered a call on this route, for a health
+    /// probe that waits on it. The broker reports it because it carried the
+    /// reply; the server saying so would be the candidate vouching for itself.
+    ///
+    /// The root refuses a broker that is not the graph's declared holder, such
+    /// as a route worker, and a route no health record names. Neither refusal
+    /// is this broker's concern, so it is not read.
+    fn report_answer(&mut self) {
+        if !self.answer_reported {
+            self.answer_reported = true;
+            let _ = slime_rt::health_route_answered(self.route_index);
+        }
+    }
+
     /// Offer an inline reply to its client, and keep the record until the
     /// client acknowledges it.
     ///
@@ -1471,6 +1501,14 @@ impl Broker {
         {
             fail(b"invalid call time");
         }
+        // The record is only the wake-up: the instant is the granted clock's,
+        // advanced by the same driver before it sent the record. A record
+        // naming any other instant did not come from the clock's holder.
+        match slime_rt::clock_now(self.clock) {
+            Ok(now_ns) if now_ns == value.now_ns => {}
+            Ok(_) => fail(b"call time disagrees with its clock"),
+            Err(_) => fail(b"call clock read"),
+        }
         // The advance closes the previous instant, so it is recorded before any
         // deadline it triggers: the trace's tie order puts time last at the
         // instant it ends, and the expiries below belong to the new one.
@@ -25,6 +25,11 @@ const PAGE: u64 = 4096;
 const BASE: u64 = 0x7100_0000;
 const CLIENT_PHASE_SLOT: u32 = 1;
 
+/// Validation-only: the server takes its role and leaves before answering
+/// anything, so a generation that probes the route sees a live server whose
+/// route never answered. Compile-time, like the stream and proxy early exits.
+const SERVER_EARLY_EXIT: bool = option_env!("SLIME_FABRIC_CALL_SERVER_EARLY_EXIT").is_some();
+
 pub fn run_client_b() {
     let route = request_role(DIRECTION_CLIENT);
     let session = client_session(1);
@@ -123,6 +128,10 @@ pub fn run_client_b() {
 
 pub fn run_server() {
     let route = request_role(DIRECTION_SERVER);
+    if SERVER_EARLY_EXIT {
+        slime_rt::debug_write(b"[fabric-call-server] injected early server exit\n");
+        return;
+    }
     let mut executed_non_idempotent = false;
     loop {
         let mut bytes = [0u8; MAX_MSG];
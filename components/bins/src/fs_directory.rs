@@ -0,0 +1,1417 @@
+//! fs/v2 directories over the content-addressed object store (P5.4.3).
+//!
+//! A directory is a head object — its entry count and the hashes of its
+//! pages, in order — and at most `MAX_PAGES` pages of at most `PAGE_ENTRIES`
+//! entries each, sorted by name and packed full. Everything here works one page
+//! at a time on the caller's stack: the filesystem service runs on a bump heap
+//! that gives back only its newest allocation, so a request may not allocate,
+//! and a full directory decoded whole would not fit the stack it runs on either.
+//!
+//! A retained v1 snapshot is read wherever a directory hash is followed, as a
+//! directory of one page. Nothing here writes one: rewriting a directory always
+//! produces a v2 head and pages, so a v1 tree turns into a v2 tree one written
+//! path at a time.
+//!
+//! A byte range of a file is read and written here too, through a caller's
+//! staging buffer rather than the stack: a range edit holds the whole file, and
+//! stores the result as a new object before binding it like any other write.
+//! A file past `MAX_FILE_BYTES` is a chunked store object instead, whose entry
+//! names the manifest: a range of it is read a chunk at a time, and an edit
+//! streams the new object chunk by chunk out of the old one and the range, so
+//! neither ever holds more than one chunk of it.
+
+use boot_contracts::store_disk::{
+    MANIFEST_CHUNK_COUNT_OFFSET, MANIFEST_FORMAT_VERSION, MANIFEST_FORMAT_VERSION_OFFSET,
+    MANIFEST_HEADER, MANIFEST_MAGIC, MANIFEST_OBJ_TYPE_OFFSET, MANIFEST_OBJECT_TYPE,
+    MANIFEST_RESERVED_OFFSET, MANIFEST_TOTAL_LEN_OFFSET, MAX_OBJECT_PAYLOAD,
+};
+use slime_proto::{
+    fs::{
+        DIRECTORY_HEADER, DIRECTORY_MAGIC, DIRECTORY_VERSION, ENTRY_BYTES, MAX_CHUNKED_FILE_BYTES,
+        MAX_DEPTH, MAX_ENTRIES, MAX_FILE_BYTES, MAX_FILE_CHUNKS, MAX_NAME_BYTES, MAX_PAGES,
+        OFF_DIRECTORY_COUNT, OFF_DIRECTORY_PAGE_COUNT, OFF_DIRECTORY_RESERVED,
+        OFF_DIRECTORY_VERSION, OFF_ENTRY_HASH, OFF_ENTRY_KIND, OFF_ENTRY_NAME, OFF_ENTRY_NAME_LEN,
+        OFF_ENTRY_OBJECT_TYPE, OFF_ENTRY_PAYLOAD_LEN, OFF_ENTRY_RESERVED0, OFF_ENTRY_RESERVED1,
+        OFF_PAGE_COUNT, OFF_PAGE_VERSION, OFF_V1_SNAPSHOT_COUNT, OFF_V1_SNAPSHOT_ENTRY_HASH,
+        OFF_V1_SNAPSHOT_ENTRY_KIND, OFF_V1_SNAPSHOT_ENTRY_NAME, OFF_V1_SNAPSHOT_ENTRY_NAME_LEN,
+        OFF_V1_SNAPSHOT_ENTRY_OBJECT_TYPE, OFF_V1_SNAPSHOT_ENTRY_PAYLOAD_LEN,
+        OFF_V1_SNAPSHOT_ENTRY_RESERVED1, PAGE_BYTES, PAGE_ENTRIES, PAGE_HASH_BYTES, PAGE_HEADER,
+        PAGE_MAGIC, PAGE_OBJECT_TYPE, SNAPSHOT_OBJECT_TYPE, V1_MAX_ENTRIES, V1_MAX_NAME_BYTES,
+        V1_SNAPSHOT_BYTES, V1_SNAPSHOT_ENTRY_BYTES, V1_SNAPSHOT_HEADER, V1_SNAPSHOT_VERSION,
+    },
+    valid_fs_name,
+};
+
+pub const KIND_FILE: u8 = 1;
+pub const KIND_DIRECTORY: u8 = 2;
+
+/// The largest directory object either version writes: a v1 snapshot is a
+/// fixed 1040 bytes, a v2 head at most `DIRECTORY_BYTES`.
+const HEAD_BUFFER: usize = if V1_SNAPSHOT_BYTES > DIRECTORY_HEADER + MAX_PAGES * PAGE_HASH_BYTES {
+    V1_SNAPSHOT_BYTES
+} else {
+    DIRECTORY_HEADER + MAX_PAGES * PAGE_HASH_BYTES
+};
+
+/// The largest manifest a chunked file has: one hash per chunk.
+const MANIFEST_BYTES: usize = MANIFEST_HEADER + MAX_FILE_CHUNKS * 32;
+
+/// What a range operation's staging holds: a chunk, or a whole file, at the
+/// front, and a chunked file's manifest behind it.
+pub const STAGING_BYTES: usize = MAX_FILE_BYTES + MANIFEST_BYTES;
+
+// A chunked file's chunks are the store's: one record each, at most a record.
+const _: () = assert!(MAX_FILE_BYTES == MAX_OBJECT_PAYLOAD);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DirectoryError {
+    /// A name is not a single bounded segment, an entry on the path is a
+    /// file where a directory is needed (or the reverse, on write), or a byte
+    /// range falls outside what its file holds.
+    Malformed,
+    /// The store could not produce an object the tree names.
+    Store,
+    NotFound,
+    /// The directory already holds `MAX_ENTRIES` entries.
+    Full,
+    /// The store refused a new page or head.
+    Persist,
+    /// A directory object failed to decode, its pages disagree with it, or a
+    /// file's object disagrees with its entry.
+    Corrupt,
+}
+
+impl DirectoryError {
+    /// The fs reply status this error is reported as.
+    pub const fn status(self) -> i32 {
+        match self {
+            Self::Malformed => -1,
+            Self::Store => -2,
+            Self::NotFound => -3,
+            Self::Full => -4,
+            Self::Persist => -5,
+            Self::Corrupt => -7,
+        }
+    }
+}
+
+/// Where directory objects are read from and written to.
+pub trait Objects {
+    /// Copy the object named `hash` into `out`, returning its type and length.
+    fn get(&mut self, hash: &[u8; 32], out: &mut [u8]) -> Result<(u32, usize), DirectoryError>;
+    /// Store `payload` as an object of `object_type`, returning its hash.
+    fn put(&mut self, object_type: u32, payload: &[u8]) -> Result<[u8; 32], DirectoryError>;
+    /// Store `len` bytes as a chunked object of `object_type`, returning its
+    /// manifest's hash. `source` fills each `MAX_FILE_BYTES` chunk, in order,
+    /// from the offset it is given, and may read stored objects through the
+    /// [`Fetch`] it is handed; it may be asked for a chunk more than once. An
+    /// error from `source` is returned as it is.
+    fn put_chunked(
+        &mut self,
+        object_type: u32,
+        len: usize,
+        source: &mut ChunkSource<'_>,
+    ) -> Result<[u8; 32], DirectoryError>;
+}
+
+/// What fills a chunked object's chunks: given the store to read from, the
+/// chunk's offset, and the chunk's bytes to fill.
+pub type ChunkSource<'a> =
+    dyn FnMut(&mut dyn Fetch, usize, &mut [u8]) -> Result<(), DirectoryError> + 'a;
+
+/// Reads from the store while a chunked object is being written to it.
+pub trait Fetch {
+    /// As [`Objects::get`].
+    fn get(&mut self, hash: &[u8; 32], out: &mut [u8]) -> Result<(u32, usize), DirectoryError>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub kind: u8,
+    name_len: u8,
+    name: [u8; MAX_NAME_BYTES],
+    pub object_type: u32,
+    pub payload_len: u32,
+    pub hash: [u8; 32],
+}
+
+impl Entry {
+    const EMPTY: Self = Self {
+        kind: 0,
+        name_len: 0,
+        name: [0; MAX_NAME_BYTES],
+        object_type: 0,
+        payload_len: 0,
+        hash: [0; 32],
+    };
+
+    pub fn new(
+        kind: u8,
+        name: &[u8],
+        object_type: u32,
+        payload_len: u32,
+        hash: [u8; 32],
+    ) -> Result<Self, DirectoryError> {
+        if !matches!(kind, KIND_FILE | KIND_DIRECTORY) || !valid_fs_name(name) {
+            return Err(DirectoryError::Malformed);
+        }
+        let mut entry = Self {
+            kind,
+            name_len: name.len() as u8,
+            object_type,
+            payload_len,
+            hash,
+            ..Self::EMPTY
+        };
+        entry.name[..name.len()].copy_from_slice(name);
+        Ok(entry)
+    }
+
+    pub fn name(&self) -> &[u8] {
+        &self.name[..self.name_len as usize]
+    }
+
+    fn decode(
+        kind: u8,
+        name: &[u8],
+        padding: &[u8],
+        object_type: &[u8],
+        payload_len: &[u8],
+        hash: &[u8],
+    ) -> Result<Self, DirectoryError> {
+        if padding.iter().any(|byte| *byte != 0) {
+            return Err(DirectoryError::Corrupt);
+        }
+        Self::new(
+            kind,
+            name,
+            u32::from_le_bytes(
+                object_type
+                    .try_into()
+                    .map_err(|_| DirectoryError::Corrupt)?,
+            ),
+            u32::from_le_bytes(
+                payload_len
+                    .try_into()
+                    .map_err(|_| DirectoryError::Corrupt)?,
+            ),
+            hash.try_into().map_err(|_| DirectoryError::Corrupt)?,
+        )
+        .map_err(|_| DirectoryError::Corrupt)
+    }
+}
+
+/// One page of entries, decoded.
+pub struct Page {
+    count: usize,
+    entries: [Entry; PAGE_ENTRIES],
+}
+
+impl Page {
+    pub const EMPTY: Self = Self {
+        count: 0,
+        entries: [Entry::EMPTY; PAGE_ENTRIES],
+    };
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries[..self.count]
+    }
+
+    fn decode_v2(&mut self, bytes: &[u8], expected: usize) -> Result<(), DirectoryError> {
+        if bytes.len() < PAGE_HEADER
+            || bytes[..8] != PAGE_MAGIC
+            || read_u32(bytes, OFF_PAGE_VERSION) != DIRECTORY_VERSION
+            || read_u32(bytes, OFF_PAGE_COUNT) as usize != expected
+            || bytes.len() != PAGE_HEADER + expected * ENTRY_BYTES
+        {
+            return Err(DirectoryError::Corrupt);
+        }
+        self.count = 0;
+        for index in 0..expected {
+            let entry = &bytes[PAGE_HEADER + index * ENTRY_BYTES..][..ENTRY_BYTES];
+            let name_len = entry[OFF_ENTRY_NAME_LEN] as usize;
+            if name_len > MAX_NAME_BYTES
+                || entry[OFF_ENTRY_RESERVED0..OFF_ENTRY_OBJECT_TYPE]
+                    .iter()
+                    .chain(&entry[OFF_ENTRY_RESERVED1..OFF_ENTRY_HASH])
+                    .any(|byte| *byte != 0)
+            {
+                return Err(DirectoryError::Corrupt);
+            }
+            self.push_decoded(Entry::decode(
+                entry[OFF_ENTRY_KIND],
+                &entry[OFF_ENTRY_NAME..OFF_ENTRY_NAME + name_len],
+                &entry[OFF_ENTRY_NAME + name_len..],
+                &entry[OFF_ENTRY_OBJECT_TYPE..OFF_ENTRY_PAYLOAD_LEN],
+                &entry[OFF_ENTRY_PAYLOAD_LEN..OFF_ENTRY_RESERVED1],
+                &entry[OFF_ENTRY_HASH..OFF_ENTRY_NAME],
+            )?)?;
+        }
+        Ok(())
+    }
+
+    fn decode_v1(&mut self, bytes: &[u8]) -> Result<(), DirectoryError> {
+        let count = read_u32(bytes, OFF_V1_SNAPSHOT_COUNT) as usize;
+        if bytes.len() != V1_SNAPSHOT_BYTES || count > V1_MAX_ENTRIES {
+            return Err(DirectoryError::Corrupt);
+        }
+        self.count = 0;
+        for index in 0..count {
+            let entry = &bytes[V1_SNAPSHOT_HEADER + index * V1_SNAPSHOT_ENTRY_BYTES..]
+                [..V1_SNAPSHOT_ENTRY_BYTES];
+            let name_len = entry[OFF_V1_SNAPSHOT_ENTRY_NAME_LEN] as usize;
+            if name_len > V1_MAX_NAME_BYTES {
+                return Err(DirectoryError::Corrupt);
+            }
+            self.push_decoded(Entry::decode(
+                entry[OFF_V1_SNAPSHOT_ENTRY_KIND],
+                &entry[OFF_V1_SNAPSHOT_ENTRY_NAME..OFF_V1_SNAPSHOT_ENTRY_NAME + name_len],
+                &entry[OFF_V1_SNAPSHOT_ENTRY_NAME + name_len..OFF_V1_SNAPSHOT_ENTRY_OBJECT_TYPE],
+                &entry[OFF_V1_SNAPSHOT_ENTRY_OBJECT_TYPE..OFF_V1_SNAPSHOT_ENTRY_PAYLOAD_LEN],
+                &entry[OFF_V1_SNAPSHOT_ENTRY_PAYLOAD_LEN..OFF_V1_SNAPSHOT_ENTRY_HASH],
+                &entry[OFF_V1_SNAPSHOT_ENTRY_HASH..OFF_V1_SNAPSHOT_ENTRY_RESERVED1],
+            )?)?;
+        }
+        if bytes[V1_SNAPSHOT_HEADER + count * V1_SNAPSHOT_ENTRY_BYTES..]
+            .iter()
+            .any(|byte| *byte != 0)
+        {
+            return Err(DirectoryError::Corrupt);
+        }
+        Ok(())
+    }
+
+    /// Append a decoded entry, which must sort strictly after the last.
+    fn push_decoded(&mut self, entry: Entry) -> Result<(), DirectoryError> {
+        if self
+            .entries()
+            .last()
+            .is_some_and(|last| last.name() >= entry.name())
+        {
+            return Err(DirectoryError::Corrupt);
+        }
+        self.entries[self.count] = entry;
+        self.count += 1;
+        Ok(())
+    }
+
+    fn encode(&self, out: &mut [u8; PAGE_BYTES]) -> usize {
+        let len = PAGE_HEADER + self.count * ENTRY_BYTES;
+        out[..len].fill(0);
+        out[..8].copy_from_slice(&PAGE_MAGIC);
+        out[OFF_PAGE_VERSION..OFF_PAGE_COUNT].copy_from_slice(&DIRECTORY_VERSION.to_le_bytes());
+        out[OFF_PAGE_COUNT..PAGE_HEADER].copy_from_slice(&(self.count as u32).to_le_bytes());
+        for (index, entry) in self.entries().iter().enumerate() {
+            let bytes = &mut out[PAGE_HEADER + index * ENTRY_BYTES..][..ENTRY_BYTES];
+            bytes[OFF_ENTRY_KIND] = entry.kind;
+            bytes[OFF_ENTRY_NAME_LEN] = entry.name_len;
+            bytes[OFF_ENTRY_OBJECT_TYPE..OFF_ENTRY_PAYLOAD_LEN]
+                .copy_from_slice(&entry.object_type.to_le_bytes());
+            bytes[OFF_ENTRY_PAYLOAD_LEN..OFF_ENTRY_RESERVED1]
+                .copy_from_slice(&entry.payload_len.to_le_bytes());
+            bytes[OFF_ENTRY_HASH..OFF_ENTRY_NAME].copy_from_slice(&entry.hash);
+            bytes[OFF_ENTRY_NAME..].copy_from_slice(&entry.name);
+        }
+        len
+    }
+}
+
+/// A directory's head: how many entries it holds and which pages hold them.
+#[derive(Clone, Copy)]
+pub struct Head {
+    hash: [u8; 32],
+    legacy: bool,
+    count: usize,
+    page_count: usize,
+    pages: [[u8; 32]; MAX_PAGES],
+}
+
+impl Head {
+    /// A directory that does not exist yet, as a write creating it sees it.
+    const EMPTY: Self = Self {
+        hash: [0; 32],
+        legacy: false,
+        count: 0,
+        page_count: 0,
+        pages: [[0; 32]; MAX_PAGES],
+    };
+
+    pub fn load(objects: &mut impl Objects, hash: &[u8; 32]) -> Result<Self, DirectoryError> {
+        let mut bytes = [0u8; HEAD_BUFFER];
+        let (object_type, len) = objects.get(hash, &mut bytes)?;
+        Self::decode(hash, object_type, &bytes[..len])
+    }
+
+    fn decode(hash: &[u8; 32], object_type: u32, bytes: &[u8]) -> Result<Self, DirectoryError> {
+        let len = bytes.len();
+        if object_type != SNAPSHOT_OBJECT_TYPE
+            || len < DIRECTORY_HEADER.min(V1_SNAPSHOT_HEADER)
+            || bytes[..8] != DIRECTORY_MAGIC
+        {
+            return Err(DirectoryError::Corrupt);
+        }
+        let mut head = Self {
+            hash: *hash,
+            ..Self::EMPTY
+        };
+        match read_u32(bytes, OFF_DIRECTORY_VERSION) {
+            V1_SNAPSHOT_VERSION => {
+                // The entries are checked when the page is read; only the
+                // count is needed to shape the head.
+                head.legacy = true;
+                head.count = read_u32(bytes, OFF_V1_SNAPSHOT_COUNT) as usize;
+                if len != V1_SNAPSHOT_BYTES || head.count > V1_MAX_ENTRIES {
+                    return Err(DirectoryError::Corrupt);
+                }
+                head.page_count = usize::from(head.count > 0);
+            }
+            DIRECTORY_VERSION => {
+                if len < DIRECTORY_HEADER {
+                    return Err(DirectoryError::Corrupt);
+                }
+                head.count = read_u32(bytes, OFF_DIRECTORY_COUNT) as usize;
+                head.page_count = read_u32(bytes, OFF_DIRECTORY_PAGE_COUNT) as usize;
+                if head.count > MAX_ENTRIES
+                    || head.page_count != head.count.div_ceil(PAGE_ENTRIES)
+                    || len != DIRECTORY_HEADER + head.page_count * PAGE_HASH_BYTES
+                    || bytes[OFF_DIRECTORY_RESERVED..DIRECTORY_HEADER]
+                        .iter()
+                        .any(|byte| *byte != 0)
+                {
+                    return Err(DirectoryError::Corrupt);
+                }
+                for (index, page) in head.pages[..head.page_count].iter_mut().enumerate() {
+                    page.copy_from_slice(
+                        &bytes[DIRECTORY_HEADER + index * PAGE_HASH_BYTES..][..PAGE_HASH_BYTES],
+                    );
+                }
+            }
+            _ => return Err(DirectoryError::Corrupt),
+        }
+        Ok(head)
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Read page `index` into `out`, checking it holds exactly the entries the
+    /// head says it does.
+    pub fn page(
+        &self,
+        objects: &mut impl Objects,
+        index: usize,
+        out: &mut Page,
+    ) -> Result<(), DirectoryError> {
+        if index >= self.page_count {
+            return Err(DirectoryError::NotFound);
+        }
+        if self.legacy {
+            let mut bytes = [0u8; V1_SNAPSHOT_BYTES];
+            let (_, len) = objects.get(&self.hash, &mut bytes)?;
+            return out.decode_v1(&bytes[..len]);
+        }
+        let expected = if index + 1 < self.page_count {
+            PAGE_ENTRIES
+        } else {
+            self.count - index * PAGE_ENTRIES
+        };
+        let mut bytes = [0u8; PAGE_BYTES];
+        match objects.get(&self.pages[index], &mut bytes)? {
+            (PAGE_OBJECT_TYPE, len) => out.decode_v2(&bytes[..len], expected),
+            _ => Err(DirectoryError::Corrupt),
+        }
+    }
+
+    /// Read every page, checking the directory decodes whole and stays sorted
+    /// across page boundaries.
+    pub fn verify(&self, objects: &mut impl Objects) -> Result<(), DirectoryError> {
+        let mut page = Page::EMPTY;
+        let mut last = Entry::EMPTY;
+        for index in 0..self.page_count {
+            self.page(objects, index, &mut page)?;
+            follows(&last, &page)?;
+            last = page.entries[page.count - 1];
+        }
+        Ok(())
+    }
+
+    /// The entry named `name`, if this directory holds one.
+    pub fn lookup(
+        &self,
+        objects: &mut impl Objects,
+        name: &[u8],
+    ) -> Result<Option<Entry>, DirectoryError> {
+        let mut page = Page::EMPTY;
+        let mut last = Entry::EMPTY;
+        for index in 0..self.page_count {
+            self.page(objects, index, &mut page)?;
+            follows(&last, &page)?;
+            last = page.entries[page.count - 1];
+            if name > last.name() {
+                continue;
+            }
+            return Ok(page
+                .entries()
+                .iter()
+                .find(|entry| entry.name() == name)
+                .copied());
+        }
+        Ok(None)
+    }
+}
+
+/// A directory as written: its head's hash and length, and its entry count.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Written {
+    pub hash: [u8; 32],
+    pub len: u32,
+    pub count: usize,
+}
+
+/// A path split into its segments, each a valid name, at most `MAX_DEPTH`.
+pub struct Path<'a> {
+    bytes: &'a [u8],
+    bounds: [(usize, usize); MAX_DEPTH],
+    depth: usize,
+}
+
+impl<'a> Path<'a> {
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, DirectoryError> {
+        let mut path = Self {
+            bytes,
+            bounds: [(0, 0); MAX_DEPTH],
+            depth: 0,
+        };
+        if bytes.is_empty() {
+            return Ok(path);
+        }
+        let mut start = 0;
+        for segment in bytes.split(|byte| *byte == b'/') {
+            if path.depth == MAX_DEPTH || !valid_fs_name(segment) {
+                return Err(DirectoryError::Malformed);
+            }
+            path.bounds[path.depth] = (start, start + segment.len());
+            path.depth += 1;
+            start += segment.len() + 1;
+        }
+        Ok(path)
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn segment(&self, index: usize) -> &'a [u8] {
+        let (start, end) = self.bounds[index];
+        &self.bytes[start..end]
+    }
+}
+
+/// Report every object a directory object names: a head's pages, and the
+/// entries of a page or a v1 snapshot. Anything else, or a directory object
+/// that does not decode, names nothing.
+///
+/// For a walk over the store that reads each object once, such as a scrub's
+/// attribution, rather than a lookup through [`Objects`].
+pub fn references(object_type: u32, payload: &[u8], mut named: impl FnMut([u8; 32])) {
+    let mut page = Page::EMPTY;
+    match object_type {
+        SNAPSHOT_OBJECT_TYPE => match Head::decode(&[0; 32], object_type, payload) {
+            Ok(head) if head.legacy => {
+                if page.decode_v1(payload).is_ok() {
+                    page.entries().iter().for_each(|entry| named(entry.hash));
+                }
+            }
+            Ok(head) => head.pages[..head.page_count]
+                .iter()
+                .for_each(|hash| named(*hash)),
+            Err(_) => {}
+        },
+        PAGE_OBJECT_TYPE => {
+            let expected = read_u32(payload, OFF_PAGE_COUNT) as usize;
+            if expected <= PAGE_ENTRIES && page.decode_v2(payload, expected).is_ok() {
+                page.entries().iter().for_each(|entry| named(entry.hash));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The directory `path` names under `root`. Every segment must name a
+/// directory; the empty path is `root` itself.
+pub fn resolve_directory(
+    objects: &mut impl Objects,
+    root: &[u8; 32],
+    path: &Path<'_>,
+    depth: usize,
+) -> Result<Head, DirectoryError> {
+    let mut head = Head::load(objects, root)?;
+    for index in 0..depth {
+        match head.lookup(objects, path.segment(index))? {
+            Some(entry) if entry.kind == KIND_DIRECTORY => {
+                head = Head::load(objects, &entry.hash)?;
+            }
+            _ => return Err(DirectoryError::NotFound),
+        }
+    }
+    Ok(head)
+}
+
+/// The file `path` names under `root`.
+pub fn resolve_file(
+    objects: &mut impl Objects,
+    root: &[u8; 32],
+    path: &Path<'_>,
+) -> Result<Entry, DirectoryError> {
+    let Some(leaf) = path.depth().checked_sub(1) else {
+        return Err(DirectoryError::NotFound);
+    };
+    let parent = resolve_directory(objects, root, path, leaf)?;
+    match parent.lookup(objects, path.segment(leaf))? {
+        Some(entry) if entry.kind == KIND_FILE => Ok(entry),
+        _ => Err(DirectoryError::NotFound),
+    }
+}
+
+/// Bind the last segment of `path` to `file`, creating missing parent
+/// directories, and return the new root and the new entry count of the
+/// directory holding the file.
+///
+/// Only the directories along the path are rewritten, deepest first, each
+/// reloaded by the hash its parent recorded so no more than one directory is
+/// ever held in memory. The old root stays intact; committing the new one is
+/// the caller's compare-and-swap.
+pub fn write_file(
+    objects: &mut impl Objects,
+    root: &[u8; 32],
+    path: &Path<'_>,
+    object_type: u32,
+    payload_len: u32,
+    hash: [u8; 32],
+) -> Result<([u8; 32], usize), DirectoryError> {
+    let Some(leaf) = path.depth().checked_sub(1) else {
+        return Err(DirectoryError::Malformed);
+    };
+    let mut chain = [None; MAX_DEPTH];
+    chain[0] = Some(*root);
+    for index in 1..=leaf {
+        chain[index] = match chain[index - 1] {
+            Some(parent) => {
+                match Head::load(objects, &parent)?.lookup(objects, path.segment(index - 1))? {
+                    Some(entry) if entry.kind == KIND_DIRECTORY => Some(entry.hash),
+                    Some(_) => return Err(DirectoryError::Malformed),
+                    None => None,
+                }
+            }
+            None => None,
+        };
+    }
+    let file = Entry::new(
+        KIND_FILE,
+        path.segment(leaf),
+        object_type,
+        payload_len,
+        hash,
+    )?;
+    let written = upsert(objects, chain[leaf], &file)?;
+    let count = written.count;
+    let mut child = written;
+    for index in (0..leaf).rev() {
+        let directory = Entry::new(
+            KIND_DIRECTORY,
+            path.segment(index),
+            SNAPSHOT_OBJECT_TYPE,
+            child.len,
+            child.hash,
+        )?;
+        child = upsert(objects, chain[index], &directory)?;
+    }
+    Ok((child.hash, count))
+}
+
+/// Copy up to `out.len()` bytes of the file `path` names, from `offset`, into
+/// `out`. Returns the file's entry and how many bytes were copied: fewer than
+/// asked at the end of the file, none at exactly its end.
+///
+/// `staging` holds the whole file while the range is cut out of it, or, for a
+/// chunked file, its manifest and one chunk at a time.
+pub fn read_range(
+    objects: &mut impl Objects,
+    root: &[u8; 32],
+    path: &Path<'_>,
+    offset: u64,
+    out: &mut [u8],
+    staging: &mut [u8; STAGING_BYTES],
+) -> Result<(Entry, usize), DirectoryError> {
+    let file = resolve_file(objects, root, path)?;
+    let stored = open_file(objects, &file, staging)?;
+    let len = file.payload_len as usize;
+    let start = usize::try_from(offset)
+        .ok()
+        .filter(|start| *start <= len)
+        .ok_or(DirectoryError::Malformed)?;
+    let count = out.len().min(len - start);
+    let (front, manifest) = staging.split_at_mut(MAX_FILE_BYTES);
+    if stored == Stored::Whole {
+        out[..count].copy_from_slice(&front[start..start + count]);
+        return Ok((file, count));
+    }
+    let mut copied = 0;
+    while copied < count {
+        let at = start + copied;
+        let index = at / MAX_FILE_BYTES;
+        let chunk_start = index * MAX_FILE_BYTES;
+        let chunk = &mut front[..(len - chunk_start).min(MAX_FILE_BYTES)];
+        let (_, chunk_len) = objects.get(&chunk_hash(manifest, index), chunk)?;
+        if chunk_len != chunk.len() {
+            return Err(DirectoryError::Corrupt);
+        }
+        let take = (chunk_len - (at - chunk_start)).min(count - copied);
+        out[copied..copied + take].copy_from_slice(&chunk[at - chunk_start..][..take]);
+        copied += take;
+    }
+    Ok((file, count))
+}
+
+/// Overwrite `bytes` into the file `path` names at `offset`, extending it when
+/// the range runs past its end, and return the new root, the entry count of
+/// the directory holding the file, and the file's new entry.
+///
+/// The edited file is stored as a new object of the old one's type, and then
+/// bound exactly as [`write_file`] binds one, so the old root and the old file
+/// both stay intact until the caller commits. A range may start at the file's
+/// end but not past it: a file has no holes.
+///
+/// A file that fits one record is edited whole in `staging` and stays whole.
+/// One that would outgrow it, or already has, is stored chunked: each chunk of
+/// the new object is the old file's bytes at that offset with the range laid
+/// over them, so only chunks the range touches are new records and the rest
+/// are the old file's own.
+pub fn write_range(
+    objects: &mut impl Objects,
+    root: &[u8; 32],
+    path: &Path<'_>,
+    offset: u64,
+    bytes: &[u8],
+    staging: &mut [u8; STAGING_BYTES],
+) -> Result<([u8; 32], usize, Entry), DirectoryError> {
+    let file = resolve_file(objects, root, path)?;
+    let stored = open_file(objects, &file, staging)?;
+    let old_len = file.payload_len as usize;
+    let start = usize::try_from(offset)
+        .ok()
+        .filter(|start| *start <= old_len)
+        .ok_or(DirectoryError::Malformed)?;
+    let end = start
+        .checked_add(bytes.len())
+        .filter(|end| *end <= MAX_CHUNKED_FILE_BYTES)
+        .ok_or(DirectoryError::Malformed)?;
+    let len = old_len.max(end);
+    let (front, manifest) = staging.split_at_mut(MAX_FILE_BYTES);
+    let hash = if stored == Stored::Whole && len <= MAX_FILE_BYTES {
+        front[start..end].copy_from_slice(bytes);
+        objects
+            .put(file.object_type, &front[..len])
+            .map_err(|_| DirectoryError::Persist)?
+    } else {
+        objects.put_chunked(file.object_type, len, &mut |fetch, at, out| {
+            let old = old_len.saturating_sub(at).min(out.len());
+            match stored {
+                _ if old == 0 => {}
+                // A whole file is the first chunk's old bytes, and it is
+                // already in staging.
+                Stored::Whole => out[..old].copy_from_slice(&front[at..at + old]),
+                Stored::Chunked => {
+                    let hash = chunk_hash(manifest, at / MAX_FILE_BYTES);
+                    if fetch.get(&hash, &mut out[..old])?.1 != old {
+                        return Err(DirectoryError::Corrupt);
+                    }
+                }
+            }
+            let (from, to) = (start.max(at), end.min(at + out.len()));
+            if from < to {
+                out[from - at..to - at].copy_from_slice(&bytes[from - start..to - start]);
+            }
+            Ok(())
+        })?
+    };
+    let (root, count) = write_file(objects, root, path, file.object_type, len as u32, hash)?;
+    let edited = Entry {
+        payload_len: len as u32,
+        hash,
+        ..file
+    };
+    Ok((root, count, edited))
+}
+
+/// How a file's object holds its bytes.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Stored {
+    /// One record, now read whole into the front of staging.
+    Whole,
+    /// A chunked object, whose manifest is now behind the front of staging.
+    Chunked,
+}
+
+/// Read the object `file` names into `staging` and check it against the entry.
+///
+/// The object is either the file, of the entry's type and length, or the
+/// manifest of a chunked object of them: its header must describe exactly the
+/// chunks the entry's length implies. The chunks themselves are checked only
+/// as they are read, each against the hash the manifest lists and the length
+/// its place in the file gives it.
+fn open_file(
+    objects: &mut impl Objects,
+    file: &Entry,
+    staging: &mut [u8; STAGING_BYTES],
+) -> Result<Stored, DirectoryError> {
+    let len = file.payload_len as usize;
+    if len > MAX_CHUNKED_FILE_BYTES {
+        return Err(DirectoryError::Malformed);
+    }
+    let (front, manifest) = staging.split_at_mut(MAX_FILE_BYTES);
+    let (object_type, stored_len) = objects.get(&file.hash, front)?;
+    if object_type == file.object_type && stored_len == len {
+        return Ok(Stored::Whole);
+    }
+    let chunks = len.div_ceil(MAX_FILE_BYTES);
+    if object_type != MANIFEST_OBJECT_TYPE || stored_len != MANIFEST_HEADER + chunks * 32 {
+        return Err(DirectoryError::Corrupt);
+    }
+    let header = &front[..MANIFEST_HEADER];
+    if header[..8] != MANIFEST_MAGIC
+        || read_u32(header, MANIFEST_FORMAT_VERSION_OFFSET) != MANIFEST_FORMAT_VERSION
+        || read_u32(header, MANIFEST_CHUNK_COUNT_OFFSET) as usize != chunks
+        || read_u32(header, MANIFEST_OBJ_TYPE_OFFSET) != file.object_type
+        || read_u32(header, MANIFEST_RESERVED_OFFSET) != 0
+        || header[MANIFEST_TOTAL_LEN_OFFSET..MANIFEST_TOTAL_LEN_OFFSET + 8]
+            != (len as u64).to_le_bytes()
+    {
+        return Err(DirectoryError::Corrupt);
+    }
+    manifest[..stored_len].copy_from_slice(&front[..stored_len]);
+    Ok(Stored::Chunked)
+}
+
+/// The hash a chunked file's manifest lists for its `index`th chunk.
+fn chunk_hash(manifest: &[u8], index: usize) -> [u8; 32] {
+    manifest[MANIFEST_HEADER + index * 32..][..32]
+        .try_into()
+        .expect("chunk hash field")
+}
+
+/// Rewrite `directory` (or an empty one) with `entry` inserted in name order,
+/// or replacing the entry of the same name and kind.
+fn upsert(
+    objects: &mut impl Objects,
+    directory: Option<[u8; 32]>,
+    entry: &Entry,
+) -> Result<Written, DirectoryError> {
+    let head = match directory {
+        Some(hash) => Head::load(objects, &hash)?,
+        None => Head::EMPTY,
+    };
+    let mut writer = Writer::new();
+    let mut pending = Some(entry);
+    let mut page = Page::EMPTY;
+    for index in 0..head.page_count {
+        head.page(objects, index, &mut page)?;
+        for existing in page.entries() {
+            match pending {
+                Some(new) if new.name() == existing.name() => {
+                    if new.kind != existing.kind {
+                        return Err(DirectoryError::Malformed);
+                    }
+                    writer.push(objects, new)?;
+                    pending = None;
+                }
+                Some(new) if new.name() < existing.name() => {
+                    writer.push(objects, new)?;
+                    writer.push(objects, existing)?;
+                    pending = None;
+                }
+                _ => writer.push(objects, existing)?,
+            }
+        }
+    }
+    if let Some(new) = pending {
+        writer.push(objects, new)?;
+    }
+    writer.finish(objects)
+}
+
+/// Streams sorted entries into full pages and, at the end, a head.
+struct Writer {
+    page: Page,
+    head: Head,
+    last: Entry,
+}
+
+impl Writer {
+    fn new() -> Self {
+        Self {
+            page: Page::EMPTY,
+            head: Head::EMPTY,
+            last: Entry::EMPTY,
+        }
+    }
+
+    fn push(&mut self, objects: &mut impl Objects, entry: &Entry) -> Result<(), DirectoryError> {
+        if self.head.count == MAX_ENTRIES {
+            return Err(DirectoryError::Full);
+        }
+        // Sorted input is what makes the output canonical; input that is not
+        // sorted came from a directory whose pages disagree with each other.
+        if self.head.count > 0 && self.last.name() >= entry.name() {
+            return Err(DirectoryError::Corrupt);
+        }
+        if self.page.count == PAGE_ENTRIES {
+            self.flush(objects)?;
+        }
+        self.page.entries[self.page.count] = *entry;
+        self.page.count += 1;
+        self.head.count += 1;
+        self.last = *entry;
+        Ok(())
+    }
+
+    fn flush(&mut self, objects: &mut impl Objects) -> Result<(), DirectoryError> {
+        if self.page.count == 0 {
+            return Ok(());
+        }
+        let mut bytes = [0u8; PAGE_BYTES];
+        let len = self.page.encode(&mut bytes);
+        self.head.pages[self.head.page_count] = objects
+            .put(PAGE_OBJECT_TYPE, &bytes[..len])
+            .map_err(|_| DirectoryError::Persist)?;
+        self.head.page_count += 1;
+        self.page.count = 0;
+        Ok(())
+    }
+
+    fn finish(mut self, objects: &mut impl Objects) -> Result<Written, DirectoryError> {
+        self.flush(objects)?;
+        let mut bytes = [0u8; DIRECTORY_HEADER + MAX_PAGES * PAGE_HASH_BYTES];
+        let len = DIRECTORY_HEADER + self.head.page_count * PAGE_HASH_BYTES;
+        bytes[..8].copy_from_slice(&DIRECTORY_MAGIC);
+        bytes[OFF_DIRECTORY_VERSION..OFF_DIRECTORY_COUNT]
+            .copy_from_slice(&DIRECTORY_VERSION.to_le_bytes());
+        bytes[OFF_DIRECTORY_COUNT..OFF_DIRECTORY_PAGE_COUNT]
+            .copy_from_slice(&(self.head.count as u32).to_le_bytes());
+        bytes[OFF_DIRECTORY_PAGE_COUNT..OFF_DIRECTORY_RESERVED]
+            .copy_from_slice(&(self.head.page_count as u32).to_le_bytes());
+        for (index, page) in self.head.pages[..self.head.page_count].iter().enumerate() {
+            bytes[DIRECTORY_HEADER + index * PAGE_HASH_BYTES..][..PAGE_HASH_BYTES]
+                .copy_from_slice(page);
+        }
+        let hash = objects
+            .put(SNAPSHOT_OBJECT_TYPE, &bytes[..len])
+            .map_err(|_| DirectoryError::Persist)?;
+        Ok(Written {
+            hash,
+            len: len as u32,
+            count: self.head.count,
+        })
+    }
+}
+
+/// A page's first entry must sort after the previous page's last.
+fn follows(last: &Entry, page: &Page) -> Result<(), DirectoryError> {
+    match page.entries().first() {
+        Some(first) if last.name() < first.name() => Ok(()),
+        _ => Err(DirectoryError::Corrupt),
+    }
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    bytes
+        .get(offset..offset + 4)
+        .and_then(|field| field.try_into().ok())
+        .map_or(u32::MAX, u32::from_le_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use boot_contracts::sha256;
+    use boot_contracts::store_disk::{CHUNK_OBJECT_TYPE, MANIFEST_ROOT_HASH_OFFSET};
+    use std::collections::HashMap;
+
+    #[derive(Default)]
+    struct Memory {
+        objects: HashMap<[u8; 32], (u32, Vec<u8>)>,
+    }
+
+    struct Reader<'a>(&'a HashMap<[u8; 32], (u32, Vec<u8>)>);
+
+    impl Fetch for Reader<'_> {
+        fn get(&mut self, hash: &[u8; 32], out: &mut [u8]) -> Result<(u32, usize), DirectoryError> {
+            let (object_type, bytes) = self.0.get(hash).ok_or(DirectoryError::Store)?;
+            out.get_mut(..bytes.len())
+                .ok_or(DirectoryError::Store)?
+                .copy_from_slice(bytes);
+            Ok((*object_type, bytes.len()))
+        }
+    }
+
+    impl Objects for Memory {
+        fn get(&mut self, hash: &[u8; 32], out: &mut [u8]) -> Result<(u32, usize), DirectoryError> {
+            let (object_type, bytes) = self.objects.get(hash).ok_or(DirectoryError::Store)?;
+            out.get_mut(..bytes.len())
+                .ok_or(DirectoryError::Store)?
+                .copy_from_slice(bytes);
+            Ok((*object_type, bytes.len()))
+        }
+
+        fn put(&mut self, object_type: u32, payload: &[u8]) -> Result<[u8; 32], DirectoryError> {
+            let hash = sha256::digest(payload);
+            self.objects.insert(hash, (object_type, payload.to_vec()));
+            Ok(hash)
+        }
+
+        /// As the store does it: a chunk whose bytes are already held under
+        /// any type is that record, not a new one.
+        fn put_chunked(
+            &mut self,
+            object_type: u32,
+            len: usize,
+            source: &mut ChunkSource<'_>,
+        ) -> Result<[u8; 32], DirectoryError> {
+            let mut content = vec![0u8; len];
+            for (index, chunk) in content.chunks_mut(MAX_FILE_BYTES).enumerate() {
+                source(&mut Reader(&self.objects), index * MAX_FILE_BYTES, chunk)?;
+            }
+            let mut manifest = vec![0u8; MANIFEST_HEADER];
+            manifest[..8].copy_from_slice(&MANIFEST_MAGIC);
+            manifest[MANIFEST_FORMAT_VERSION_OFFSET..][..4]
+                .copy_from_slice(&MANIFEST_FORMAT_VERSION.to_le_bytes());
+            manifest[MANIFEST_CHUNK_COUNT_OFFSET..][..4]
+                .copy_from_slice(&(len.div_ceil(MAX_FILE_BYTES) as u32).to_le_bytes());
+            manifest[MANIFEST_OBJ_TYPE_OFFSET..][..4].copy_from_slice(&object_type.to_le_bytes());
+            manifest[MANIFEST_TOTAL_LEN_OFFSET..][..8].copy_from_slice(&(len as u64).to_le_bytes());
+            manifest[MANIFEST_ROOT_HASH_OFFSET..][..32].copy_from_slice(&sha256::digest(&content));
+            for chunk in content.chunks(MAX_FILE_BYTES) {
+                let hash = sha256::digest(chunk);
+                self.objects
+                    .entry(hash)
+                    .or_insert((CHUNK_OBJECT_TYPE, chunk.to_vec()));
+                manifest.extend_from_slice(&hash);
+            }
+            self.put(MANIFEST_OBJECT_TYPE, &manifest)
+        }
+    }
+
+    fn empty_root(memory: &mut Memory) -> [u8; 32] {
+        Writer::new().finish(memory).unwrap().hash
+    }
+
+    fn v1_snapshot(entries: &[(u8, &[u8], [u8; 32])]) -> Vec<u8> {
+        let mut bytes = vec![0u8; V1_SNAPSHOT_BYTES];
+        bytes[..8].copy_from_slice(&DIRECTORY_MAGIC);
+        bytes[8..12].copy_from_slice(&V1_SNAPSHOT_VERSION.to_le_bytes());
+        bytes[OFF_V1_SNAPSHOT_COUNT..OFF_V1_SNAPSHOT_COUNT + 4]
+            .copy_from_slice(&(entries.len() as u32).to_le_bytes());
+        for (index, (kind, name, hash)) in entries.iter().enumerate() {
+            let entry = &mut bytes[V1_SNAPSHOT_HEADER + index * V1_SNAPSHOT_ENTRY_BYTES..];
+            entry[OFF_V1_SNAPSHOT_ENTRY_KIND] = *kind;
+            entry[OFF_V1_SNAPSHOT_ENTRY_NAME_LEN] = name.len() as u8;
+            entry[OFF_V1_SNAPSHOT_ENTRY_NAME..OFF_V1_SNAPSHOT_ENTRY_NAME + name.len()]
+                .copy_from_slice(name);
+            entry[OFF_V1_SNAPSHOT_ENTRY_OBJECT_TYPE..OFF_V1_SNAPSHOT_ENTRY_PAYLOAD_LEN]
+                .copy_from_slice(&7u32.to_le_bytes());
+            entry[OFF_V1_SNAPSHOT_ENTRY_HASH..OFF_V1_SNAPSHOT_ENTRY_RESERVED1]
+                .copy_from_slice(hash);
+        }
+        bytes
+    }
+
+    #[test]
+    fn a_directory_past_one_page_splits_and_every_name_resolves() {
+        let mut memory = Memory::default();
+        let mut root = empty_root(&mut memory);
+        let total = PAGE_ENTRIES * 2 + 3;
+        // Written in reverse so every insert lands before existing entries and
+        // shifts the packing of every page after it.
+        for index in (0..total).rev() {
+            let name = format!("many/file-{index:03}");
+            let path = Path::parse(name.as_bytes()).unwrap();
+            let (next, count) =
+                write_file(&mut memory, &root, &path, 7, 4, [index as u8; 32]).unwrap();
+            assert_eq!(count, total - index);
+            root = next;
+        }
+        let many =
+            resolve_directory(&mut memory, &root, &Path::parse(b"many").unwrap(), 1).unwrap();
+        assert_eq!(many.count(), total);
+        assert_eq!(many.page_count, 3);
+        many.verify(&mut memory).unwrap();
+        for index in [0, PAGE_ENTRIES - 1, PAGE_ENTRIES, total - 1] {
+            let name = format!("many/file-{index:03}");
+            let entry = resolve_file(&mut memory, &root, &Path::parse(name.as_bytes()).unwrap());
+            assert_eq!(entry.unwrap().hash, [index as u8; 32]);
+        }
+        assert_eq!(
+            resolve_file(&mut memory, &root, &Path::parse(b"many/file-999").unwrap()),
+            Err(DirectoryError::NotFound)
+        );
+    }
+
+    #[test]
+    fn deep_paths_and_long_names_create_their_parents() {
+        let mut memory = Memory::default();
+        let root = empty_root(&mut memory);
+        let long = [b'n'; MAX_NAME_BYTES];
+        let mut path = b"a/b/c/d/e/f/g/".to_vec();
+        path.extend_from_slice(&long);
+        let parsed = Path::parse(&path).unwrap();
+        assert_eq!(parsed.depth(), MAX_DEPTH);
+        let (root, count) = write_file(&mut memory, &root, &parsed, 7, 4, [9; 32]).unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(
+            resolve_file(&mut memory, &root, &parsed).unwrap().name(),
+            long
+        );
+        let g = resolve_directory(&mut memory, &root, &parsed, MAX_DEPTH - 1).unwrap();
+        assert_eq!(g.count(), 1);
+
+        path.push(b'n');
+        assert_eq!(Path::parse(&path).err(), Some(DirectoryError::Malformed));
+        assert_eq!(
+            Path::parse(b"a/b/c/d/e/f/g/h/i").err(),
+            Some(DirectoryError::Malformed)
+        );
+        for invalid in [b"a//b".as_slice(), b"/a", b"a/..", b"a/b c"] {
+            assert!(Path::parse(invalid).is_err());
+        }
+    }
+
+    #[test]
+    fn a_v1_snapshot_still_reads_and_the_first_write_replaces_it_with_v2() {
+        let mut memory = Memory::default();
+        let docs = v1_snapshot(&[(KIND_FILE, b"note", [1; 32])]);
+        let docs_hash = memory.put(SNAPSHOT_OBJECT_TYPE, &docs).unwrap();
+        let root = v1_snapshot(&[
+            (KIND_DIRECTORY, b"docs", docs_hash),
+            (KIND_FILE, b"note", [2; 32]),
+        ]);
+        let root = memory.put(SNAPSHOT_OBJECT_TYPE, &root).unwrap();
+
+        let note = resolve_file(&mut memory, &root, &Path::parse(b"docs/note").unwrap()).unwrap();
+        assert_eq!(note.hash, [1; 32]);
+        assert!(Head::load(&mut memory, &root).unwrap().legacy);
+
+        let path = Path::parse(b"docs/a-name-longer-than-v1-allowed.txt").unwrap();
+        let (root, count) = write_file(&mut memory, &root, &path, 7, 4, [3; 32]).unwrap();
+        assert_eq!(count, 2);
+        let head = Head::load(&mut memory, &root).unwrap();
+        assert!(!head.legacy);
+        assert_eq!(head.count(), 2);
+        for (name, hash) in [(b"docs/note".as_slice(), [1; 32]), (b"note", [2; 32])] {
+            let entry = resolve_file(&mut memory, &root, &Path::parse(name).unwrap()).unwrap();
+            assert_eq!(entry.hash, hash);
+        }
+    }
+
+    #[test]
+    fn kind_conflicts_and_the_entry_bound_are_refused() {
+        let mut memory = Memory::default();
+        let root = empty_root(&mut memory);
+        let (root, _) = write_file(
+            &mut memory,
+            &root,
+            &Path::parse(b"docs/note").unwrap(),
+            7,
+            4,
+            [1; 32],
+        )
+        .unwrap();
+        assert_eq!(
+            write_file(
+                &mut memory,
+                &root,
+                &Path::parse(b"docs").unwrap(),
+                7,
+                4,
+                [1; 32]
+            ),
+            Err(DirectoryError::Malformed)
+        );
+        assert_eq!(
+            write_file(
+                &mut memory,
+                &root,
+                &Path::parse(b"docs/note/x").unwrap(),
+                7,
+                4,
+                [1; 32]
+            ),
+            Err(DirectoryError::Malformed)
+        );
+        assert_eq!(
+            resolve_directory(&mut memory, &root, &Path::parse(b"docs/note").unwrap(), 2).err(),
+            Some(DirectoryError::NotFound)
+        );
+
+        let mut full = Writer::new();
+        for index in 0..MAX_ENTRIES {
+            let name = format!("f{index:04}");
+            let entry = Entry::new(KIND_FILE, name.as_bytes(), 7, 4, [0; 32]).unwrap();
+            full.push(&mut memory, &entry).unwrap();
+        }
+        let full = full.finish(&mut memory).unwrap();
+        assert_eq!(full.count, MAX_ENTRIES);
+        let extra = Entry::new(KIND_FILE, b"g", 7, 4, [0; 32]).unwrap();
+        assert_eq!(
+            upsert(&mut memory, Some(full.hash), &extra),
+            Err(DirectoryError::Full)
+        );
+        let replace = Entry::new(KIND_FILE, b"f0000", 7, 4, [5; 32]).unwrap();
+        assert_eq!(
+            upsert(&mut memory, Some(full.hash), &replace)
+                .unwrap()
+                .count,
+            MAX_ENTRIES
+        );
+    }
+
+    #[test]
+    fn damaged_heads_and_pages_fail_closed() {
+        let mut memory = Memory::default();
+        let root = empty_root(&mut memory);
+        let (root, _) = write_file(
+            &mut memory,
+            &root,
+            &Path::parse(b"note").unwrap(),
+            7,
+            4,
+            [1; 32],
+        )
+        .unwrap();
+        let (object_type, head) = memory.objects[&root].clone();
+        assert_eq!(object_type, SNAPSHOT_OBJECT_TYPE);
+        let page = Head::load(&mut memory, &root).unwrap().pages[0];
+
+        let mut reserved = head.clone();
+        reserved[OFF_DIRECTORY_RESERVED] = 1;
+        let mut miscounted = head.clone();
+        miscounted[OFF_DIRECTORY_COUNT] = PAGE_ENTRIES as u8 + 1;
+        let mut unknown = head.clone();
+        unknown[OFF_DIRECTORY_VERSION] = 3;
+        for damaged in [reserved, miscounted, unknown] {
+            let hash = memory.put(SNAPSHOT_OBJECT_TYPE, &damaged).unwrap();
+            assert_eq!(
+                Head::load(&mut memory, &hash).err(),
+                Some(DirectoryError::Corrupt)
+            );
+        }
+        let retyped = memory.put(PAGE_OBJECT_TYPE, &head).unwrap();
+        assert_eq!(
+            Head::load(&mut memory, &retyped).err(),
+            Some(DirectoryError::Corrupt)
+        );
+
+        let mut bad_name = memory.objects[&page].1.clone();
+        bad_name[PAGE_HEADER + OFF_ENTRY_NAME] = b'/';
+        let bad_page = memory.put(PAGE_OBJECT_TYPE, &bad_name).unwrap();
+        let mut patched = head.clone();
+        patched[DIRECTORY_HEADER..DIRECTORY_HEADER + 32].copy_from_slice(&bad_page);
+        let patched = memory.put(SNAPSHOT_OBJECT_TYPE, &patched).unwrap();
+        let patched = Head::load(&mut memory, &patched).unwrap();
+        assert_eq!(patched.verify(&mut memory), Err(DirectoryError::Corrupt));
+        assert_eq!(
+            patched.lookup(&mut memory, b"note"),
+            Err(DirectoryError::Corrupt)
+        );
+    }
+
+    #[test]
+    fn a_range_write_edits_in_place_extends_at_the_end_and_keeps_the_old_file() {
+        let mut memory = Memory::default();
+        let root = empty_root(&mut memory);
+        let original = memory.put(7, b"hello, world").unwrap();
+        let path = Path::parse(b"docs/note").unwrap();
+        let (root, _) = write_file(&mut memory, &root, &path, 7, 12, original).unwrap();
+        let mut staging = [0u8; STAGING_BYTES];
+
+        let (edited_root, count, edited) =
+            write_range(&mut memory, &root, &path, 7, b"slime", &mut staging).unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(edited.payload_len, 12);
+        assert_eq!(memory.objects[&edited.hash], (7, b"hello, slime".to_vec()));
+        assert_eq!(resolve_file(&mut memory, &edited_root, &path), Ok(edited));
+        // The old root still names the old bytes.
+        assert_eq!(
+            resolve_file(&mut memory, &root, &path).unwrap().hash,
+            original
+        );
+
+        let (appended_root, _, appended) =
+            write_range(&mut memory, &edited_root, &path, 12, b" os", &mut staging).unwrap();
+        assert_eq!(appended.payload_len, 15);
+        let mut out = [0u8; 8];
+        let (file, len) = read_range(
+            &mut memory,
+            &appended_root,
+            &path,
+            7,
+            &mut out,
+            &mut staging,
+        )
+        .unwrap();
+        assert_eq!((file, &out[..len]), (appended, b"slime os".as_slice()));
+        let (_, len) = read_range(
+            &mut memory,
+            &appended_root,
+            &path,
+            15,
+            &mut out,
+            &mut staging,
+        )
+        .unwrap();
+        assert_eq!(len, 0);
+    }
+
+    #[test]
+    fn ranges_past_the_end_of_a_file_or_its_bound_are_refused() {
+        let mut memory = Memory::default();
+        let root = empty_root(&mut memory);
+        let content = memory.put(7, b"four").unwrap();
+        let path = Path::parse(b"note").unwrap();
+        let (root, _) = write_file(&mut memory, &root, &path, 7, 4, content).unwrap();
+        let mut staging = [0u8; STAGING_BYTES];
+        let mut out = [0u8; 4];
+
+        assert_eq!(
+            read_range(&mut memory, &root, &path, 5, &mut out, &mut staging).err(),
+            Some(DirectoryError::Malformed)
+        );
+        assert_eq!(
+            write_range(&mut memory, &root, &path, 5, b"x", &mut staging).err(),
+            Some(DirectoryError::Malformed)
+        );
+        let too_long = vec![0u8; MAX_CHUNKED_FILE_BYTES];
+        assert_eq!(
+            write_range(&mut memory, &root, &path, 4, &too_long, &mut staging).err(),
+            Some(DirectoryError::Malformed)
+        );
+        assert_eq!(
+            write_range(
+                &mut memory,
+                &root,
+                &Path::parse(b"missing").unwrap(),
+                0,
+                b"x",
+                &mut staging
+            )
+            .err(),
+            Some(DirectoryError::NotFound)
+        );
+
+        // An entry whose length disagrees with its object is damage, not a
+        // short read.
+        let (lying, _) = write_file(&mut memory, &root, &path, 7, 3, content).unwrap();
+        assert_eq!(
+            read_range(&mut memory, &lying, &path, 0, &mut out, &mut staging).err(),
+            Some(DirectoryError::Corrupt)
+        );
+    }
+
+    #[test]
+    fn a_file_grown_past_one_record_is_chunked_and_edited_a_chunk_at_a_time() {
+        let mut memory = Memory::default();
+        let root = empty_root(&mut memory);
+        let whole: Vec<u8> = (0..MAX_FILE_BYTES)
+            .map(|index| (index % 251) as u8)
+            .collect();
+        let original = memory.put(7, &whole).unwrap();
+        let path = Path::parse(b"big").unwrap();
+        let (root, _) = write_file(
+            &mut memory,
+            &root,
+            &path,
+            7,
+            MAX_FILE_BYTES as u32,
+            original,
+        )
+        .unwrap();
+        let mut staging = [0u8; STAGING_BYTES];
+        let mut out = [0u8; 8];
+        let end = MAX_FILE_BYTES as u64;
+
+        let (grown_root, _, grown) =
+            write_range(&mut memory, &root, &path, end - 2, b"slime", &mut staging).unwrap();
+        assert_eq!(grown.object_type, 7);
+        assert_eq!(grown.payload_len as usize, MAX_FILE_BYTES + 3);
+        assert_eq!(memory.objects[&grown.hash].0, MANIFEST_OBJECT_TYPE);
+        let (_, len) = read_range(
+            &mut memory,
+            &grown_root,
+            &path,
+            end - 4,
+            &mut out,
+            &mut staging,
+        )
+        .unwrap();
+        assert_eq!(
+            &out[..len],
+            [&whole[MAX_FILE_BYTES - 4..][..2], b"slime"].concat()
+        );
+
+        // An edit inside the second chunk reuses the first as it stands.
+        let first = chunk_hash(&memory.objects[&grown.hash].1, 0);
+        let (edited_root, _, edited) =
+            write_range(&mut memory, &grown_root, &path, end + 1, b"L", &mut staging).unwrap();
+        assert_eq!(edited.payload_len, grown.payload_len);
+        let manifest = memory.objects[&edited.hash].1.clone();
+        assert_eq!(chunk_hash(&manifest, 0), first);
+        assert_eq!(memory.objects[&chunk_hash(&manifest, 1)].1, b"iLe");
+        let (_, len) = read_range(
+            &mut memory,
+            &edited_root,
+            &path,
+            end - 2,
+            &mut out,
+            &mut staging,
+        )
+        .unwrap();
+        assert_eq!(&out[..len], b"sliLe");
+
+        // The old roots still name the old files.
+        let (file, len) =
+            read_range(&mut memory, &root, &path, end - 2, &mut out, &mut staging).unwrap();
+        assert_eq!(
+            (file.hash, &out[..len]),
+            (original, &whole[MAX_FILE_BYTES - 2..])
+        );
+        let (_, len) =
+            read_range(&mut memory, &grown_root, &path, end, &mut out, &mut staging).unwrap();
+        assert_eq!(&out[..len], b"ime");
+
+        // A manifest bound under another type, or another length, is damage.
+        let (relabeled, _) = write_file(
+            &mut memory,
+            &root,
+            &path,
+            8,
+            edited.payload_len,
+            edited.hash,
+        )
+        .unwrap();
+        let (shortened, _) = write_file(
+            &mut memory,
+            &root,
+            &path,
+            7,
+            edited.payload_len - 1,
+            edited.hash,
+        )
+        .unwrap();
+        for damaged in [relabeled, shortened] {
+            assert_eq!(
+                read_range(&mut memory, &damaged, &path, 0, &mut out, &mut staging).err(),
+                Some(DirectoryError::Corrupt)
+            );
+        }
+    }
+}
@@ -0,0 +1,444 @@
+//! Generation bisect over a release chain (M6.5).
+//!
+//! A session is two bounds and, once both are known, the chain between them:
+//! every generation from the known-good bound to the known-bad one, found by
+//! walking each release's `parent` back from the bad end. The search keeps the
+//! index of the newest generation known good and of the oldest known bad, and
+//! the generation midway between them is the one to boot next. When the two
+//! are adjacent the bad one is the first bad generation.
+//!
+//! Nothing here touches a device. The generation-management service persists
+//! each session it returns as a store record and stages `candidate()`; a
+//! session decoded from that record continues exactly where the encoded one
+//! stopped, which is how a search survives the reboots it is made of. Every
+//! change advances `serial`, so no two sessions encode to the same bytes and a
+//! content-addressed store never folds a new one into an old record.
+
+use slime_proto::generation::{
+    BISECT_SESSION_MAGIC, FORMAT_VERSION, MAX_BISECT_GENERATIONS, OFF_SESSION_RESERVED,
+    SESSION_HEADER_LEN, WireBisectSession,
+};
+
+/// The largest session record: the header and a full chain.
+pub const SESSION_BYTES: usize = SESSION_HEADER_LEN + MAX_BISECT_GENERATIONS * 32;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Good,
+    Bad,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BisectError {
+    /// A bound named no generation or the other bound's, or a verdict named a
+    /// generation other than the one being tested.
+    WrongGeneration,
+    /// The walk needed a release no record carries.
+    UnknownRelease,
+    /// The walk from the bad bound reached a release with no parent, or ran
+    /// past `MAX_BISECT_GENERATIONS`, without meeting the good bound.
+    NotAnAncestor,
+    /// The first bad generation is known; only a reset starts another search.
+    Finished,
+    /// A session record failed to decode.
+    Corrupt,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Session {
+    serial: u64,
+    good: [u8; 32],
+    bad: [u8; 32],
+    count: usize,
+    low: usize,
+    high: usize,
+    verdicts: u32,
+    chain: [[u8; 32]; MAX_BISECT_GENERATIONS],
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Session {
+    /// No bounds and no chain: what a store without a session record holds.
+    pub const fn new() -> Self {
+        Self {
+            serial: 0,
+            good: [0; 32],
+            bad: [0; 32],
+            count: 0,
+            low: 0,
+            high: 0,
+            verdicts: 0,
+            chain: [[0; 32]; MAX_BISECT_GENERATIONS],
+        }
+    }
+
+    pub const fn serial(&self) -> u64 {
+        self.serial
+    }
+
+    /// Verdicts recorded on staged generations; the two bounds are not counted.
+    pub const fn verdicts(&self) -> u32 {
+        self.verdicts
+    }
+
+    /// Whether neither bound has been named.
+    pub fn is_empty(&self) -> bool {
+        self.good == [0; 32] && self.bad == [0; 32]
+    }
+
+    /// The generation to boot and judge next, while the search is running.
+    pub fn candidate(&self) -> Option<[u8; 32]> {
+        (self.high - self.low > 1).then(|| self.chain[self.low + (self.high - self.low) / 2])
+    }
+
+    /// The answer, once the newest good and oldest bad generations are adjacent.
+    pub fn first_bad(&self) -> Option<[u8; 32]> {
+        (self.count != 0 && self.high - self.low == 1).then_some(self.chain[self.high])
+    }
+
+    /// Verdicts still needed in the worst case.
+    pub fn remaining(&self) -> u32 {
+        (self.high - self.low).next_power_of_two().trailing_zeros()
+    }
+
+    /// Record that `generation` is good or bad.
+    ///
+    /// Before the search starts this names a bound; the second bound starts it,
+    /// walking `parent_of` from the bad bound until the good one. After that the
+    /// only generation that can be judged is `candidate()`. A refusal leaves
+    /// this session as it was, so the caller persists only what is returned.
+    pub fn mark(
+        &self,
+        generation: [u8; 32],
+        verdict: Verdict,
+        parent_of: impl FnMut(&[u8; 32]) -> Result<Option<[u8; 32]>, BisectError>,
+    ) -> Result<Self, BisectError> {
+        let mut next = *self;
+        next.serial = self.serial.checked_add(1).ok_or(BisectError::Corrupt)?;
+        if self.count != 0 {
+            let Some(candidate) = self.candidate() else {
+                return Err(BisectError::Finished);
+            };
+            if generation != candidate {
+                return Err(BisectError::WrongGeneration);
+            }
+            let middle = self.low + (self.high - self.low) / 2;
+            match verdict {
+                Verdict::Good => next.low = middle,
+                Verdict::Bad => next.high = middle,
+            }
+            next.verdicts += 1;
+            return Ok(next);
+        }
+
+        if generation == [0; 32] {
+            return Err(BisectError::WrongGeneration);
+        }
+        let (bound, other) = match verdict {
+            Verdict::Good => (&mut next.good, self.bad),
+            Verdict::Bad => (&mut next.bad, self.good),
+        };
+        if generation == other {
+            return Err(BisectError::WrongGeneration);
+        }
+        *bound = generation;
+        if next.good != [0; 32] && next.bad != [0; 32] {
+            next.walk(parent_of)?;
+        }
+        Ok(next)
+    }
+
+    /// A session with no bounds, still ordered after this one.
+    pub fn reset(&self) -> Self {
+        Self {
+            serial: self.serial.saturating_add(1),
+            ..Self::new()
+        }
+    }
+
+    /// Fill the chain newest first, then turn it around.
+    fn walk(
+        &mut self,
+        mut parent_of: impl FnMut(&[u8; 32]) -> Result<Option<[u8; 32]>, BisectError>,
+    ) -> Result<(), BisectError> {
+        self.chain[0] = self.bad;
+        let mut count = 1;
+        while self.chain[count - 1] != self.good {
+            if count == MAX_BISECT_GENERATIONS {
+                return Err(BisectError::NotAnAncestor);
+            }
+            let Some(parent) = parent_of(&self.chain[count - 1])? else {
+                return Err(BisectError::NotAnAncestor);
+            };
+            self.chain[count] = parent;
+            count += 1;
+        }
+        self.chain[..count].reverse();
+        self.count = count;
+        self.low = 0;
+        self.high = count - 1;
+        Ok(())
+    }
+
+    /// The session record, in `out`; returns its length.
+    pub fn encode(&self, out: &mut [u8; SESSION_BYTES]) -> usize {
+        let header = WireBisectSession {
+            magic: BISECT_SESSION_MAGIC,
+            format_version: FORMAT_VERSION,
+            header_size: SESSION_HEADER_LEN as u32,
+            serial: self.serial,
+            count: self.count as u32,
+            low: self.low as u32,
+            high: self.high as u32,
+            verdicts: self.verdicts,
+            good: self.good,
+            bad: self.bad,
+            reserved: [0; SESSION_HEADER_LEN - OFF_SESSION_RESERVED],
+        };
+        out[..SESSION_HEADER_LEN].copy_from_slice(&header.encode());
+        for (slot, generation) in out[SESSION_HEADER_LEN..]
+            .chunks_exact_mut(32)
+            .zip(&self.chain[..self.count])
+        {
+            slot.copy_from_slice(generation);
+        }
+        SESSION_HEADER_LEN + self.count * 32
+    }
+
+    /// A session record, refused unless it is one `encode` could have written.
+    pub fn decode(bytes: &[u8]) -> Result<Self, BisectError> {
+        let header = WireBisectSession::decode(bytes).ok_or(BisectError::Corrupt)?;
+        let count = header.count as usize;
+        let (low, high) = (header.low as usize, header.high as usize);
+        if header.magic != BISECT_SESSION_MAGIC
+            || header.format_version != FORMAT_VERSION
+            || header.header_size as usize != SESSION_HEADER_LEN
+            || header.reserved.iter().any(|byte| *byte != 0)
+            || count > MAX_BISECT_GENERATIONS
+            || bytes.len() != SESSION_HEADER_LEN + count * 32
+        {
+            return Err(BisectError::Corrupt);
+        }
+        let mut session = Self {
+            serial: header.serial,
+            good: header.good,
+            bad: header.bad,
+            count,
+            low,
+            high,
+            verdicts: header.verdicts,
+            ..Self::new()
+        };
+        for (generation, slot) in session
+            .chain
+            .iter_mut()
+            .zip(bytes[SESSION_HEADER_LEN..].chunks_exact(32))
+        {
+            generation.copy_from_slice(slot);
+        }
+        let consistent = if count == 0 {
+            low == 0
+                && high == 0
+                && header.verdicts == 0
+                && (header.good == [0; 32] || header.good != header.bad)
+        } else {
+            count >= 2
+                && low < high
+                && high < count
+                && session.chain[0] == header.good
+                && session.chain[count - 1] == header.bad
+                && header.good != [0; 32]
+                && header.good != header.bad
+        };
+        if !consistent {
+            return Err(BisectError::Corrupt);
+        }
+        Ok(session)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generation(index: usize) -> [u8; 32] {
+        [0x40 + index as u8; 32]
+    }
+
+    /// A linear history: generation `i`'s parent is `i - 1`, and the first has
+    /// none.
+    fn history(length: usize) -> impl Fn(&[u8; 32]) -> Result<Option<[u8; 32]>, BisectError> {
+        move |child| {
+            let index = (0..length)
+                .find(|index| generation(*index) == *child)
+                .ok_or(BisectError::UnknownRelease)?;
+            Ok(index.checked_sub(1).map(generation))
+        }
+    }
+
+    fn started(length: usize, good: usize, bad: usize) -> Session {
+        Session::new()
+            .mark(generation(good), Verdict::Good, history(length))
+            .and_then(|session| session.mark(generation(bad), Verdict::Bad, history(length)))
+            .expect("the bounds start a search")
+    }
+
+    /// Round-trips the session through its record at every step, the way the
+    /// service reopens it after each reboot.
+    fn reopened(session: &Session) -> Session {
+        let mut bytes = [0u8; SESSION_BYTES];
+        let len = session.encode(&mut bytes);
+        Session::decode(&bytes[..len]).expect("a session record decodes")
+    }
+
+    /// For every span up to a full chain and every first-bad position in it, a
+    /// search driven by the truth finds that generation within the promised
+    /// number of verdicts.
+    #[test]
+    fn every_first_bad_generation_is_found_within_the_logarithmic_bound() {
+        for length in 2..=MAX_BISECT_GENERATIONS {
+            for first_bad in 1..length {
+                let mut session = reopened(&started(length, 0, length - 1));
+                let budget = session.remaining();
+                while let Some(candidate) = session.candidate() {
+                    let index = (0..length).find(|index| generation(*index) == candidate);
+                    let verdict = if index.expect("a chain member") >= first_bad {
+                        Verdict::Bad
+                    } else {
+                        Verdict::Good
+                    };
+                    let before = session.serial();
+                    session = reopened(
+                        &session
+                            .mark(candidate, verdict, history(length))
+                            .expect("the candidate is judged"),
+                    );
+                    assert!(session.serial() > before);
+                }
+                assert_eq!(session.first_bad(), Some(generation(first_bad)));
+                assert!(session.verdicts() <= budget);
+                assert_eq!(session.remaining(), 0);
+            }
+        }
+    }
+
+    /// Bounds may be named in either order, and bounds that meet the walk in
+    /// the middle of a longer history bisect only the generations between them.
+    #[test]
+    fn the_chain_spans_only_the_generations_between_the_bounds() {
+        let session = Session::new()
+            .mark(generation(9), Verdict::Bad, history(12))
+            .and_then(|session| session.mark(generation(4), Verdict::Good, history(12)))
+            .expect("bad then good starts a search");
+        assert_eq!(session.candidate(), Some(generation(6)));
+        assert_eq!(session.remaining(), 3);
+
+        let adjacent = started(12, 4, 5);
+        assert_eq!(adjacent.candidate(), None);
+        assert_eq!(adjacent.first_bad(), Some(generation(5)));
+    }
+
+    #[test]
+    fn bounds_that_are_not_a_history_are_refused() {
+        let empty = Session::new();
+        assert_eq!(
+            empty.mark([0; 32], Verdict::Good, history(4)).err(),
+            Some(BisectError::WrongGeneration)
+        );
+        let good = empty
+            .mark(generation(1), Verdict::Good, history(4))
+            .expect("one bound");
+        assert_eq!(
+            good.mark(generation(1), Verdict::Bad, history(4)).err(),
+            Some(BisectError::WrongGeneration)
+        );
+        // The good bound is newer than the bad one, so the walk runs off the
+        // first release without meeting it.
+        let reversed = Session::new()
+            .mark(generation(3), Verdict::Good, history(4))
+            .expect("one bound");
+        assert_eq!(
+            reversed.mark(generation(1), Verdict::Bad, history(4)).err(),
+            Some(BisectError::NotAnAncestor)
+        );
+        assert_eq!(
+            good.mark([0x99; 32], Verdict::Bad, history(4)).err(),
+            Some(BisectError::UnknownRelease)
+        );
+        let long = MAX_BISECT_GENERATIONS + 1;
+        let far = Session::new()
+            .mark(generation(0), Verdict::Good, history(long))
+            .expect("one bound");
+        assert_eq!(
+            far.mark(generation(long - 1), Verdict::Bad, history(long))
+                .err(),
+            Some(BisectError::NotAnAncestor)
+        );
+    }
+
+    /// Only the staged generation is judged, a finished search takes no more
+    /// verdicts, and a reset empties it without reusing a serial.
+    #[test]
+    fn verdicts_name_the_candidate_and_end_with_the_search() {
+        let session = started(4, 0, 3);
+        assert_eq!(session.candidate(), Some(generation(1)));
+        assert_eq!(
+            session.mark(generation(2), Verdict::Good, history(4)).err(),
+            Some(BisectError::WrongGeneration)
+        );
+        let finished = session
+            .mark(generation(1), Verdict::Bad, history(4))
+            .expect("the candidate is judged");
+        assert_eq!(finished.first_bad(), Some(generation(1)));
+        assert_eq!(
+            finished.mark(generation(1), Verdict::Bad, history(4)).err(),
+            Some(BisectError::Finished)
+        );
+        let reset = finished.reset();
+        assert!(reset.is_empty());
+        assert_eq!(reset.first_bad(), None);
+        assert!(reset.serial() > finished.serial());
+        assert_eq!(reopened(&reset), reset);
+    }
+
+    #[test]
+    fn damaged_session_records_are_refused() {
+        let session = started(6, 0, 5);
+        let mut bytes = [0u8; SESSION_BYTES];
+        let len = session.encode(&mut bytes);
+        assert_eq!(Session::decode(&bytes[..len]), Ok(session));
+        assert_eq!(
+            Session::decode(&bytes[..len - 1]),
+            Err(BisectError::Corrupt)
+        );
+
+        let damaged = |edit: &dyn Fn(&mut [u8])| {
+            let mut copy = bytes;
+            edit(&mut copy[..len]);
+            Session::decode(&copy[..len])
+        };
+        assert_eq!(damaged(&|bytes| bytes[0] ^= 1), Err(BisectError::Corrupt));
+        assert_eq!(
+            damaged(&|bytes| bytes[OFF_SESSION_RESERVED] = 1),
+            Err(BisectError::Corrupt)
+        );
+        // The chain's ends are the bounds, and its last generation is bad.
+        assert_eq!(
+            damaged(&|bytes| bytes[SESSION_HEADER_LEN] ^= 1),
+            Err(BisectError::Corrupt)
+        );
+        assert_eq!(
+            damaged(&|bytes| bytes[slime_proto::generation::OFF_SESSION_HIGH] = 6),
+            Err(BisectError::Corrupt)
+        );
+        assert_eq!(
+            damaged(&|bytes| bytes[slime_proto::generation::OFF_SESSION_LOW] = 5),
+            Err(BisectError::Corrupt)
+        );
+    }
+}
@@ -0,0 +1,106 @@
+//! Which generations a store compaction keeps (M5.6, M6.5).
+//!
+//! The generation manager's store holds a generation record and a release
+//! record for every generation it has imported or staged, and BootState names
+//! only the two it can select. A rollback or a bisect walks back along the
+//! releases' `parent` links from those two, and a generation imported onto
+//! the known-good one waits in the store for the STAGE that names it, so
+//! neither may be reclaimed just because BootState does not name it.
+//!
+//! A generation is retained when it is one a selectable generation descends
+//! from, or one that descends from a selectable generation. What is left is a
+//! branch forked off an ancestor that the chain has since moved past: nothing
+//! can select it, and bisect only searches between the known-good generation
+//! and something after it.
+//!
+//! Nothing here touches a device: the manager reads each release once into a
+//! table of links, and asks about every generation against that table.
+
+/// One release's link: the generation it releases, and the parent it names.
+pub type Link = ([u8; 32], Option<[u8; 32]>);
+
+/// Whether `generation` is an ancestor of, a descendant of, or one of the
+/// `selectable` generations, along the parent links in `links`.
+pub fn retained(links: &[Link], selectable: &[[u8; 32]], generation: &[u8; 32]) -> bool {
+    if selectable
+        .iter()
+        .any(|selectable| descends(links, selectable, generation))
+    {
+        return true;
+    }
+    selectable
+        .iter()
+        .any(|selectable| descends(links, generation, selectable))
+}
+
+/// Whether walking parents back from `child` meets `ancestor`, counting
+/// `child` itself. The walk takes at most one step per link, so a table with a
+/// cycle ends it rather than looping.
+fn descends(links: &[Link], child: &[u8; 32], ancestor: &[u8; 32]) -> bool {
+    let mut current = *child;
+    for _ in 0..=links.len() {
+        if current == *ancestor {
+            return true;
+        }
+        let Some(parent) = links
+            .iter()
+            .find(|(generation, _)| *generation == current)
+            .and_then(|(_, parent)| *parent)
+        else {
+            return false;
+        };
+        current = parent;
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(byte: u8) -> [u8; 32] {
+        [byte; 32]
+    }
+
+    /// 1 <- 2 <- 3 (known good) <- 4 (pending) <- 5 (imported), and a branch
+    /// 2 <- 6 forked before the known-good generation.
+    fn lineage() -> [Link; 6] {
+        [
+            (id(1), None),
+            (id(2), Some(id(1))),
+            (id(3), Some(id(2))),
+            (id(4), Some(id(3))),
+            (id(5), Some(id(4))),
+            (id(6), Some(id(2))),
+        ]
+    }
+
+    #[test]
+    fn the_rollback_chain_behind_a_selectable_generation_is_retained() {
+        let links = lineage();
+        for generation in [id(1), id(2), id(3), id(4)] {
+            assert!(retained(&links, &[id(3), id(4)], &generation));
+        }
+    }
+
+    #[test]
+    fn a_generation_imported_onto_a_selectable_one_is_retained() {
+        let links = lineage();
+        assert!(retained(&links, &[id(3)], &id(4)));
+        assert!(retained(&links, &[id(3)], &id(5)));
+    }
+
+    #[test]
+    fn a_branch_forked_before_the_known_good_generation_is_not() {
+        let links = lineage();
+        assert!(!retained(&links, &[id(3), id(4)], &id(6)));
+        assert!(!retained(&links, &[id(3)], &id(9)));
+    }
+
+    #[test]
+    fn a_cycle_in_the_links_ends_the_walk() {
+        let links = [(id(1), Some(id(2))), (id(2), Some(id(1)))];
+        assert!(!retained(&links, &[id(3)], &id(1)));
+        assert!(retained(&links, &[id(1)], &id(2)));
+    }
+}
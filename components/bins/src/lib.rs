@@ -1,5 +1,10 @@
 #![cfg_attr(not(test), no_std)]
 
+// Only with the store, which brings an allocator with it; see `store` in
+// Cargo.toml.
+#[cfg(feature = "store")]
+extern crate alloc;
+
 pub mod dango_runtime;
 
 #[cfg(feature = "component-runtime")]
@@ -9,7 +14,12 @@ pub mod fabric_matrix;
 pub mod fabric_self_view;
 #[cfg(feature = "component-runtime")]
 pub mod fabric_visibility;
+pub mod fs_directory;
+pub mod generation_bisect;
 #[cfg(feature = "component-runtime")]
 pub mod generation_composition;
+pub mod generation_retention;
 #[cfg(feature = "component-runtime")]
 pub mod shared_buffer_probe;
+#[cfg(feature = "store")]
+pub mod store_owners;
@@ -49,7 +49,7 @@ use slime_rt::{ERR_SUCCESS, ERR_WOULDBLOCK, MAX_CAPS_PER_MSG, MAX_MSG};
 use super::trace_log::{self, Trace};
 use super::{FABRIC_TRACE_DEPTH, control_clients, fail, release_received};
 // B59: the capability-rights vocabulary is generated from
-// `contracts/generation/v5/schema.zt`; these were local copies of the same
+// `contracts/generation/v6/schema.zt`; these were local copies of the same
 // bit numbering.
 use boot_contracts::generation::{RIGHT_RECV, RIGHT_SEND};
 
@@ -189,6 +189,9 @@ pub struct Broker {
     /// active after the request is fenced.
     server_request: Option<u64>,
     time_control: u32,
+    /// The plane's simulated clock, granted read-only. A time record only
+    /// wakes the broker; the instant it moves to is this clock's reading.
+    clock: u32,
     supervision: [u32; CLIENTS + 1],
     clients: [Option<u32>; CLIENTS],
     server_slot: Option<u32>,
@@ -238,6 +241,7 @@ impl Broker {
         clients: [u32; CLIENTS],
         server_slot: u32,
         time_control: u32,
+        clock: u32,
         replacement_control: u32,
         replacement_start: Option<u32>,
         backup_route_slot: u32,
@@ -250,6 +254,7 @@ impl Broker {
             replacement_supervision,
             server_request: None,
             time_control,
+            clock,
             supervision,
             clients: [Some(clients[0]), Some(clients[1])],
             replacement_control_closed: false,
@@ -1232,6 +1237,14 @@ impl Broker {
         {
             fail(b"invalid operation time");
         }
+        // The record is only the wake-up: the instant is the granted clock's,
+        // advanced by the same driver before it sent the record. A record
+        // naming any other instant did not come from the clock's holder.
+        match slime_rt::clock_now(self.clock) {
+            Ok(now_ns) if now_ns == value.now_ns => {}
+            Ok(_) => fail(b"operation time disagrees with its clock"),
+            Err(_) => fail(b"operation clock read"),
+        }
         // Recorded before the expiries it triggers: the advance closes the old
         // instant, and every deadline below belongs to the new one.
         let _ = self.trace.advance(value.now_ns);
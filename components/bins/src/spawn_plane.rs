@@ -173,7 +173,8 @@ fn launch_context() -> [u8; slime_proto::spawn::REQUEST_LEN] {
         arguments: [0u8; 8],
         environment: [0u8; 8],
         grant_rights: 0,
-        reserved: [0u8; 6],
+        scheduling_class: 0,
+        reserved: [0u8; 5],
     }
     .encode()
 }
@@ -0,0 +1,282 @@
+//! Which generation or snapshot a damaged store record costs (M5.9).
+//!
+//! `ObjectStore::attribute` names, for each record a scrub found damaged,
+//! every root whose closure reaches it. This is what the recovery probe hands
+//! it: the roots a store holds, each with the owner it stands for, and the
+//! edges out of every record type that names others.
+//!
+//! A generation owns the records tagged with its identity — its generation
+//! record, its release, and the state snapshot taken before it was staged —
+//! and whatever those reach. A directory snapshot owns its tree: each
+//! directory head is a snapshot of the subtree below it, so a damaged file is
+//! charged to every directory on its path.
+
+use alloc::vec::Vec;
+
+use boot_contracts::object_store::{BlockIo, ObjectStore, manifest_references};
+use boot_contracts::state_policy::StateMap;
+use slime_proto::fs::SNAPSHOT_OBJECT_TYPE;
+use slime_proto::generation::{
+    GENERATION_OBJECT_TYPE, RELEASE_OBJECT_TYPE, STATE_MAP_OBJECT_TYPE, STATE_SNAPSHOT_OBJECT_TYPE,
+};
+
+use crate::fs_directory;
+
+/// What a root stands for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Owner {
+    /// A generation, by its identity.
+    Generation([u8; 32]),
+    /// A directory snapshot, by the hash of its head.
+    Snapshot([u8; 32]),
+}
+
+/// One root to attribute damage to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Root {
+    pub hash: [u8; 32],
+    pub owner: Owner,
+}
+
+/// Every root the store holds. A record whose header no longer decodes is
+/// skipped: it cannot say which generation it was written for, and scrub
+/// reports it on its own.
+pub fn roots(io: &mut impl BlockIo, store: &ObjectStore) -> Vec<Root> {
+    let mut roots = Vec::new();
+    for entry in store.entries() {
+        let owner = match entry.obj_type {
+            GENERATION_OBJECT_TYPE | RELEASE_OBJECT_TYPE | STATE_SNAPSHOT_OBJECT_TYPE => {
+                match store.record_metadata(io, entry) {
+                    Ok(metadata) if metadata.generation != [0; 32] => {
+                        Owner::Generation(metadata.generation)
+                    }
+                    _ => continue,
+                }
+            }
+            SNAPSHOT_OBJECT_TYPE => Owner::Snapshot(entry.hash),
+            _ => continue,
+        };
+        if !roots.iter().any(|root: &Root| root.hash == entry.hash) {
+            roots.push(Root {
+                hash: entry.hash,
+                owner,
+            });
+        }
+    }
+    roots
+}
+
+/// What a record names. Shaped as `ObjectStore::attribute`'s `references`
+/// callback.
+pub fn references(obj_type: u32, payload: &[u8], out: &mut Vec<[u8; 32]>) {
+    let map = match obj_type {
+        STATE_MAP_OBJECT_TYPE => payload,
+        STATE_SNAPSHOT_OBJECT_TYPE => payload.get(32..).unwrap_or_default(),
+        _ => {
+            manifest_references(obj_type, payload, out);
+            fs_directory::references(obj_type, payload, |hash| out.push(hash));
+            return;
+        }
+    };
+    if let Ok(map) = StateMap::decode(map) {
+        out.extend(map.entries().map(|entry| entry.object_identity));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use boot_contracts::gpt::{Partition, SLIME_STORE_TYPE_GUID};
+    use boot_contracts::object_store::{IoError, ScrubCursor, ScrubProgress, ScrubReport};
+    use boot_contracts::state_policy::{self, MAP_ENTRY_BYTES};
+    use boot_contracts::store_disk::{RECORD_HEADER, SECTOR_BYTES, TYPE_METADATA_BYTES};
+    use slime_proto::fs::{
+        DIRECTORY_HEADER, DIRECTORY_MAGIC, DIRECTORY_VERSION, OFF_DIRECTORY_VERSION,
+    };
+
+    use crate::fs_directory::{DirectoryError, Objects, Path};
+
+    const SECTORS: u64 = 4096;
+
+    struct Disk(Vec<[u8; SECTOR_BYTES]>);
+
+    impl BlockIo for Disk {
+        fn read_sector(&mut self, lba: u64, out: &mut [u8; SECTOR_BYTES]) -> Result<(), IoError> {
+            *out = *self.0.get(lba as usize).ok_or(IoError::Device)?;
+            Ok(())
+        }
+
+        fn write_sector(&mut self, lba: u64, data: &[u8; SECTOR_BYTES]) -> Result<(), IoError> {
+            *self.0.get_mut(lba as usize).ok_or(IoError::Device)? = *data;
+            Ok(())
+        }
+
+        fn flush(&mut self) -> Result<(), IoError> {
+            Ok(())
+        }
+    }
+
+    struct Store<'a>(&'a mut ObjectStore, &'a mut Disk);
+
+    impl Objects for Store<'_> {
+        fn get(&mut self, hash: &[u8; 32], out: &mut [u8]) -> Result<(u32, usize), DirectoryError> {
+            self.0
+                .get(self.1, hash, out)
+                .map_err(|_| DirectoryError::Store)
+        }
+
+        fn put(&mut self, object_type: u32, payload: &[u8]) -> Result<[u8; 32], DirectoryError> {
+            self.0
+                .put_or_reuse(self.1, object_type, payload)
+                .map(|(hash, _)| hash)
+                .map_err(|_| DirectoryError::Persist)
+        }
+
+        fn put_chunked(
+            &mut self,
+            _: u32,
+            _: usize,
+            _: &mut fs_directory::ChunkSource<'_>,
+        ) -> Result<[u8; 32], DirectoryError> {
+            Err(DirectoryError::Persist)
+        }
+    }
+
+    fn partition() -> Partition {
+        Partition {
+            first_lba: 0,
+            last_lba: SECTORS - 1,
+            type_guid: SLIME_STORE_TYPE_GUID,
+        }
+    }
+
+    fn tagged(
+        store: &mut ObjectStore,
+        disk: &mut Disk,
+        obj_type: u32,
+        payload: &[u8],
+        generation: &[u8; 32],
+    ) -> [u8; 32] {
+        store
+            .put_with_metadata(
+                disk,
+                obj_type,
+                payload,
+                generation,
+                &[0; TYPE_METADATA_BYTES],
+            )
+            .expect("put")
+    }
+
+    fn damage(store: &ObjectStore, disk: &mut Disk, hash: &[u8; 32]) {
+        let lba = store.records(hash).next().expect("indexed").lba;
+        disk.0[lba as usize][RECORD_HEADER] ^= 0xff;
+    }
+
+    /// A state object reached through a generation's snapshot is charged to
+    /// that generation, a file to every directory above it, and a record
+    /// nothing names to no one.
+    #[test]
+    fn damage_is_charged_to_the_generations_and_snapshots_that_reach_it() {
+        let mut disk = Disk(vec![[0; SECTOR_BYTES]; SECTORS as usize]);
+        ObjectStore::format(&mut disk, &partition(), None).expect("format");
+        let mut store = ObjectStore::open(&mut disk, &partition()).expect("open");
+        let (old, new) = ([0x0A; 32], [0x0B; 32]);
+
+        let state = store.put(&mut disk, 1, b"dango history").expect("put");
+        let mut map = [0u8; MAP_ENTRY_BYTES];
+        map[..32].copy_from_slice(&[0x51; 32]);
+        map[32..64].copy_from_slice(&state);
+        map[64..68].copy_from_slice(&1u32.to_le_bytes());
+        let map = StateMap::decode(&map).expect("map");
+        let mut snapshot = [0u8; 32 + MAP_ENTRY_BYTES];
+        state_policy::encode_snapshot(&new, &map, &mut snapshot).expect("snapshot");
+        tagged(
+            &mut store,
+            &mut disk,
+            GENERATION_OBJECT_TYPE,
+            b"old generation",
+            &old,
+        );
+        let new_generation = tagged(
+            &mut store,
+            &mut disk,
+            GENERATION_OBJECT_TYPE,
+            b"new generation",
+            &new,
+        );
+        tagged(
+            &mut store,
+            &mut disk,
+            STATE_SNAPSHOT_OBJECT_TYPE,
+            &snapshot,
+            &new,
+        );
+
+        let mut head = [0u8; DIRECTORY_HEADER];
+        head[..8].copy_from_slice(&DIRECTORY_MAGIC);
+        head[OFF_DIRECTORY_VERSION..OFF_DIRECTORY_VERSION + 4]
+            .copy_from_slice(&DIRECTORY_VERSION.to_le_bytes());
+        let empty = store
+            .put(&mut disk, SNAPSHOT_OBJECT_TYPE, &head)
+            .expect("put");
+        let file = store.put(&mut disk, 1, b"notes").expect("put");
+        let (root, _) = fs_directory::write_file(
+            &mut Store(&mut store, &mut disk),
+            &empty,
+            &Path::parse(b"docs/notes").expect("path"),
+            1,
+            5,
+            file,
+        )
+        .expect("write");
+        let orphan = store.put(&mut disk, 1, b"orphan").expect("put");
+        for hash in [state, file, orphan, new_generation] {
+            damage(&store, &mut disk, &hash);
+        }
+
+        let mut cursor = ScrubCursor::new();
+        let mut report = ScrubReport::default();
+        while store
+            .scrub_step(&mut disk, &mut cursor, 64, &mut report)
+            .expect("scrub")
+            == ScrubProgress::Partial
+        {}
+        let roots = roots(&mut disk, &store);
+        let hashes: Vec<[u8; 32]> = roots.iter().map(|root| root.hash).collect();
+        store
+            .attribute(&mut disk, &mut report, &hashes, references)
+            .expect("attribute");
+        let owners = |hash: &[u8; 32]| -> Vec<Owner> {
+            let damage = report
+                .damaged
+                .iter()
+                .find(|damage| damage.hash == *hash)
+                .expect("reported");
+            damage
+                .referenced_by
+                .iter()
+                .map(|by| roots.iter().find(|root| root.hash == *by).unwrap().owner)
+                .collect()
+        };
+
+        assert_eq!(report.damaged.len(), 4);
+        assert_eq!(owners(&state), [Owner::Generation(new)]);
+        assert_eq!(owners(&new_generation), [Owner::Generation(new)]);
+        assert_eq!(owners(&orphan), []);
+        let docs = Store(&mut store, &mut disk)
+            .resolve(&root, b"docs")
+            .expect("docs");
+        let charged = owners(&file);
+        assert_eq!(charged.len(), 2);
+        assert!(charged.contains(&Owner::Snapshot(root)));
+        assert!(charged.contains(&Owner::Snapshot(docs)));
+    }
+
+    impl Store<'_> {
+        fn resolve(&mut self, root: &[u8; 32], name: &[u8]) -> Option<[u8; 32]> {
+            let head = fs_directory::Head::load(self, root).ok()?;
+            head.lookup(self, name).ok()?.map(|entry| entry.hash)
+        }
+    }
+}
@@ -33,7 +33,7 @@ use super::{
     FIRST_CONTROL_SLOT, ROUTE_NAMES, control_clients, fail, release_received, supervision_slot_for,
 };
 // B59: the capability-rights vocabulary is generated from
-// `contracts/generation/v5/schema.zt`; these were local copies of the same
+// `contracts/generation/v6/schema.zt`; these were local copies of the same
 // bit numbering.
 use boot_contracts::generation::{RIGHT_RECV, RIGHT_SEND};
 
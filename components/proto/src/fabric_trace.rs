@@ -35,8 +35,9 @@ pub const RESOURCE_EVENT: u32 = 11;
 pub const RESOURCE_LOAN: u32 = 12;
 pub const RESOURCE_MAPPING: u32 = 13;
 pub const RESOURCE_CAPABILITY_SLOTS: u32 = 14;
-pub const RESOURCE_COMPLETE: u32 = 15;
-pub const MAX_RESOURCE_COUNTER: u32 = 15;
+pub const RESOURCE_PRIVATE_PAGES: u32 = 15;
+pub const RESOURCE_COMPLETE: u32 = 16;
+pub const MAX_RESOURCE_COUNTER: u32 = 16;
 
 /// What a `visibility` or `interposition` record's `event` names. Both
 /// families are graph-shaped -- an edge, no outcome, and an event saying
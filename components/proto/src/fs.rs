@@ -1,34 +1,59 @@
-// @generated by contracts/fs/v1/gen_rust.zt; do not edit.
-// Source contract: contracts/fs/v1/schema.zt
+// @generated by contracts/fs/v2/gen_rust.zt; do not edit.
+// Source contract: contracts/fs/v2/schema.zt
 
-pub const FORMAT_VERSION: u32 = 1;
+pub const FORMAT_VERSION: u32 = 2;
 pub const FS_MAGIC: u32 = 1397117766;
 pub const REQUEST_LEN: usize = 64;
 pub const REPLY_LEN: usize = 64;
-pub const MAX_NAME_BYTES: usize = 16;
-pub const MAX_ENTRIES: usize = 16;
-pub const MAX_DEPTH: usize = 4;
-pub const MAX_PATH_BYTES: usize = 48;
+pub const MAX_NAME_BYTES: usize = 64;
+pub const MAX_DEPTH: usize = 8;
+pub const MAX_PATH_BYTES: usize = 128;
+pub const PAGE_ENTRIES: usize = 32;
+pub const MAX_PAGES: usize = 16;
+pub const MAX_ENTRIES: usize = PAGE_ENTRIES * MAX_PAGES;
 pub const OP_LIST: u8 = 1;
 pub const OP_READ: u8 = 2;
 pub const OP_WRITE: u8 = 3;
 pub const OP_DERIVE: u8 = 4;
+pub const OP_SNAPSHOT: u8 = 5;
+pub const OP_RESTORE: u8 = 6;
+pub const OP_READ_RANGE: u8 = 7;
+pub const OP_WRITE_RANGE: u8 = 8;
+pub const MAX_RANGE_BYTES: usize = 16384;
+pub const MAX_FILE_BYTES: usize = 32768;
+pub const MAX_FILE_CHUNKS: usize = 8;
+pub const MAX_CHUNKED_FILE_BYTES: usize = MAX_FILE_CHUNKS * MAX_FILE_BYTES;
 
-pub const SNAPSHOT_MAGIC: [u8; 8] = *b"SLIMEDIR";
-pub const SNAPSHOT_VERSION: u32 = 1;
-pub const SNAPSHOT_HEADER: usize = 16;
-pub const SNAPSHOT_ENTRY_BYTES: usize = 64;
-pub const SNAPSHOT_BYTES: usize = SNAPSHOT_HEADER + MAX_ENTRIES * SNAPSHOT_ENTRY_BYTES;
+pub const DIRECTORY_MAGIC: [u8; 8] = *b"SLIMEDIR";
+pub const DIRECTORY_VERSION: u32 = 2;
+pub const DIRECTORY_HEADER: usize = 32;
+pub const PAGE_HASH_BYTES: usize = 32;
+pub const DIRECTORY_BYTES: usize = DIRECTORY_HEADER + MAX_PAGES * PAGE_HASH_BYTES;
+pub const PAGE_MAGIC: [u8; 8] = *b"SLIMEPAG";
+pub const PAGE_HEADER: usize = 16;
+pub const ENTRY_BYTES: usize = 112;
+pub const PAGE_BYTES: usize = PAGE_HEADER + PAGE_ENTRIES * ENTRY_BYTES;
 pub const SNAPSHOT_OBJECT_TYPE: u32 = 1146243411;
+pub const PAGE_OBJECT_TYPE: u32 = 1347570003;
+
+// Retained v1 directory snapshot. Read wherever a directory hash is followed,
+// never written: a v1 tree keeps resolving, and the first v2 write through a
+// directory replaces it with a v2 head and pages.
+pub const V1_SNAPSHOT_VERSION: u32 = 1;
+pub const V1_MAX_NAME_BYTES: usize = 16;
+pub const V1_MAX_ENTRIES: usize = 16;
+pub const V1_SNAPSHOT_HEADER: usize = 16;
+pub const V1_SNAPSHOT_ENTRY_BYTES: usize = 64;
+pub const V1_SNAPSHOT_BYTES: usize = V1_SNAPSHOT_HEADER + V1_MAX_ENTRIES * V1_SNAPSHOT_ENTRY_BYTES;
 
 pub const OFF_REQUEST_MAGIC: usize = 0;
 pub const OFF_REQUEST_VERSION: usize = 4;
 pub const OFF_REQUEST_OP: usize = 8;
 pub const OFF_REQUEST_FLAGS: usize = 9;
-pub const OFF_REQUEST_NAME_LEN: usize = 10;
-pub const OFF_REQUEST_RESERVED0: usize = 11;
-pub const OFF_REQUEST_NAME: usize = 12;
-pub const OFF_REQUEST_PAYLOAD_LEN: usize = 28;
+pub const OFF_REQUEST_RESERVED0: usize = 10;
+pub const OFF_REQUEST_PAYLOAD_LEN: usize = 12;
+pub const OFF_REQUEST_OFFSET: usize = 16;
+pub const OFF_REQUEST_RESERVED1: usize = 24;
 pub const OFF_REQUEST_HASH0: usize = 32;
 pub const OFF_REQUEST_HASH1: usize = 40;
 pub const OFF_REQUEST_HASH2: usize = 48;
@@ -46,18 +71,37 @@ pub const OFF_REPLY_HASH2: usize = 40;
 pub const OFF_REPLY_HASH3: usize = 48;
 pub const OFF_REPLY_RESERVED: usize = 56;
 
-pub const OFF_SNAPSHOT_MAGIC: usize = 0;
-pub const OFF_SNAPSHOT_VERSION: usize = 8;
-pub const OFF_SNAPSHOT_COUNT: usize = 12;
+pub const OFF_DIRECTORY_MAGIC: usize = 0;
+pub const OFF_DIRECTORY_VERSION: usize = 8;
+pub const OFF_DIRECTORY_COUNT: usize = 12;
+pub const OFF_DIRECTORY_PAGE_COUNT: usize = 16;
+pub const OFF_DIRECTORY_RESERVED: usize = 20;
+
+pub const OFF_PAGE_MAGIC: usize = 0;
+pub const OFF_PAGE_VERSION: usize = 8;
+pub const OFF_PAGE_COUNT: usize = 12;
+
+pub const OFF_ENTRY_KIND: usize = 0;
+pub const OFF_ENTRY_NAME_LEN: usize = 1;
+pub const OFF_ENTRY_RESERVED0: usize = 2;
+pub const OFF_ENTRY_OBJECT_TYPE: usize = 4;
+pub const OFF_ENTRY_PAYLOAD_LEN: usize = 8;
+pub const OFF_ENTRY_RESERVED1: usize = 12;
+pub const OFF_ENTRY_HASH: usize = 16;
+pub const OFF_ENTRY_NAME: usize = 48;
+
+pub const OFF_V1_SNAPSHOT_MAGIC: usize = 0;
+pub const OFF_V1_SNAPSHOT_VERSION: usize = 8;
+pub const OFF_V1_SNAPSHOT_COUNT: usize = 12;
 
-pub const OFF_SNAPSHOT_ENTRY_KIND: usize = 0;
-pub const OFF_SNAPSHOT_ENTRY_NAME_LEN: usize = 1;
-pub const OFF_SNAPSHOT_ENTRY_RESERVED0: usize = 2;
-pub const OFF_SNAPSHOT_ENTRY_NAME: usize = 4;
-pub const OFF_SNAPSHOT_ENTRY_OBJECT_TYPE: usize = 20;
-pub const OFF_SNAPSHOT_ENTRY_PAYLOAD_LEN: usize = 24;
-pub const OFF_SNAPSHOT_ENTRY_HASH: usize = 28;
-pub const OFF_SNAPSHOT_ENTRY_RESERVED1: usize = 60;
+pub const OFF_V1_SNAPSHOT_ENTRY_KIND: usize = 0;
+pub const OFF_V1_SNAPSHOT_ENTRY_NAME_LEN: usize = 1;
+pub const OFF_V1_SNAPSHOT_ENTRY_RESERVED0: usize = 2;
+pub const OFF_V1_SNAPSHOT_ENTRY_NAME: usize = 4;
+pub const OFF_V1_SNAPSHOT_ENTRY_OBJECT_TYPE: usize = 20;
+pub const OFF_V1_SNAPSHOT_ENTRY_PAYLOAD_LEN: usize = 24;
+pub const OFF_V1_SNAPSHOT_ENTRY_HASH: usize = 28;
+pub const OFF_V1_SNAPSHOT_ENTRY_RESERVED1: usize = 60;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct WireFsRequest {
@@ -65,10 +109,10 @@ pub struct WireFsRequest {
     pub version: u32,
     pub op: u8,
     pub flags: u8,
-    pub name_len: u8,
-    pub reserved0: u8,
-    pub name: [u8; 16],
+    pub reserved0: u16,
     pub payload_len: u32,
+    pub offset: u64,
+    pub reserved1: [u8; 8],
     pub hash0: u64,
     pub hash1: u64,
     pub hash2: u64,
@@ -93,16 +137,24 @@ impl WireFsRequest {
             ),
             op: buf[OFF_REQUEST_OP],
             flags: buf[OFF_REQUEST_FLAGS],
-            name_len: buf[OFF_REQUEST_NAME_LEN],
-            reserved0: buf[OFF_REQUEST_RESERVED0],
-            name: buf[OFF_REQUEST_NAME..OFF_REQUEST_NAME + 16]
-                .try_into()
-                .expect("generated fs layout"),
+            reserved0: u16::from_le_bytes(
+                buf[OFF_REQUEST_RESERVED0..OFF_REQUEST_RESERVED0 + 2]
+                    .try_into()
+                    .expect("generated fs layout"),
+            ),
             payload_len: u32::from_le_bytes(
                 buf[OFF_REQUEST_PAYLOAD_LEN..OFF_REQUEST_PAYLOAD_LEN + 4]
                     .try_into()
                     .expect("generated fs layout"),
             ),
+            offset: u64::from_le_bytes(
+                buf[OFF_REQUEST_OFFSET..OFF_REQUEST_OFFSET + 8]
+                    .try_into()
+                    .expect("generated fs layout"),
+            ),
+            reserved1: buf[OFF_REQUEST_RESERVED1..OFF_REQUEST_RESERVED1 + 8]
+                .try_into()
+                .expect("generated fs layout"),
             hash0: u64::from_le_bytes(
                 buf[OFF_REQUEST_HASH0..OFF_REQUEST_HASH0 + 8]
                     .try_into()
@@ -133,11 +185,12 @@ impl WireFsRequest {
             .copy_from_slice(&self.version.to_le_bytes());
         buf[OFF_REQUEST_OP] = self.op;
         buf[OFF_REQUEST_FLAGS] = self.flags;
-        buf[OFF_REQUEST_NAME_LEN] = self.name_len;
-        buf[OFF_REQUEST_RESERVED0] = self.reserved0;
-        buf[OFF_REQUEST_NAME..OFF_REQUEST_NAME + 16].copy_from_slice(&self.name);
+        buf[OFF_REQUEST_RESERVED0..OFF_REQUEST_RESERVED0 + 2]
+            .copy_from_slice(&self.reserved0.to_le_bytes());
         buf[OFF_REQUEST_PAYLOAD_LEN..OFF_REQUEST_PAYLOAD_LEN + 4]
             .copy_from_slice(&self.payload_len.to_le_bytes());
+        buf[OFF_REQUEST_OFFSET..OFF_REQUEST_OFFSET + 8].copy_from_slice(&self.offset.to_le_bytes());
+        buf[OFF_REQUEST_RESERVED1..OFF_REQUEST_RESERVED1 + 8].copy_from_slice(&self.reserved1);
         buf[OFF_REQUEST_HASH0..OFF_REQUEST_HASH0 + 8].copy_from_slice(&self.hash0.to_le_bytes());
         buf[OFF_REQUEST_HASH1..OFF_REQUEST_HASH1 + 8].copy_from_slice(&self.hash1.to_le_bytes());
         buf[OFF_REQUEST_HASH2..OFF_REQUEST_HASH2 + 8].copy_from_slice(&self.hash2.to_le_bytes());
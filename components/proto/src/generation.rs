@@ -11,6 +11,14 @@ pub const OP_INSPECT: u8 = 2;
 pub const OP_STAGE: u8 = 3;
 pub const OP_SELECT: u8 = 4;
 pub const OP_ROLLBACK: u8 = 5;
+pub const OP_BISECT_GOOD: u8 = 6;
+pub const OP_BISECT_BAD: u8 = 7;
+pub const OP_BISECT_STATUS: u8 = 8;
+pub const OP_BISECT_RESET: u8 = 9;
+pub const OP_DIFF: u8 = 10;
+pub const OP_ROTATE_TRUST: u8 = 11;
+pub const OP_EXPORT: u8 = 12;
+pub const OP_IMPORT: u8 = 13;
 
 pub const OFF_REQUEST_MAGIC: usize = 0;
 pub const OFF_REQUEST_VERSION: usize = 4;
@@ -26,6 +34,7 @@ pub const REPLY_FLAG_KNOWN_GOOD: u32 = 1;
 pub const REPLY_FLAG_PENDING: u32 = 2;
 pub const REPLY_FLAG_RUNNING: u32 = 4;
 pub const REPLY_FLAG_STAGED: u32 = 8;
+pub const REPLY_FLAG_BISECT_DONE: u32 = 16;
 pub const OFF_REPLY_MAGIC: usize = 0;
 pub const OFF_REPLY_VERSION: usize = 4;
 pub const OFF_REPLY_STATUS: usize = 8;
@@ -39,6 +48,42 @@ pub const OFF_REPLY_GENERATION1: usize = 40;
 pub const OFF_REPLY_GENERATION2: usize = 48;
 pub const OFF_REPLY_GENERATION3: usize = 56;
 
+pub const RELEASE_OBJECT_TYPE: u32 = 1196249676;
+pub const BISECT_SESSION_OBJECT_TYPE: u32 = 1196245587;
+pub const GENERATION_OBJECT_TYPE: u32 = 1196246862;
+pub const ROTATION_OBJECT_TYPE: u32 = 1196249684;
+pub const TRUST_ROOT_OBJECT_TYPE: u32 = 1196250194;
+pub const STATE_MAP_OBJECT_TYPE: u32 = 1196249933;
+pub const STATE_SNAPSHOT_OBJECT_TYPE: u32 = 1196249939;
+pub const BISECT_SESSION_MAGIC: [u8; 8] = *b"SLIMEBI\0";
+pub const SESSION_HEADER_LEN: usize = 128;
+pub const MAX_BISECT_GENERATIONS: usize = 32;
+pub const OFF_SESSION_MAGIC: usize = 0;
+pub const OFF_SESSION_FORMAT_VERSION: usize = 8;
+pub const OFF_SESSION_HEADER_SIZE: usize = 12;
+pub const OFF_SESSION_SERIAL: usize = 16;
+pub const OFF_SESSION_COUNT: usize = 24;
+pub const OFF_SESSION_LOW: usize = 28;
+pub const OFF_SESSION_HIGH: usize = 32;
+pub const OFF_SESSION_VERDICTS: usize = 36;
+pub const OFF_SESSION_GOOD: usize = 40;
+pub const OFF_SESSION_BAD: usize = 72;
+pub const OFF_SESSION_RESERVED: usize = 104;
+pub const MIGRATION_REQUEST_LEN: usize = 64;
+pub const MIGRATION_REPLY_LEN: usize = 64;
+pub const OFF_MIGRATION_REQUEST_MAGIC: usize = 0;
+pub const OFF_MIGRATION_REQUEST_VERSION: usize = 4;
+pub const OFF_MIGRATION_REQUEST_FROM_VERSION: usize = 8;
+pub const OFF_MIGRATION_REQUEST_TO_VERSION: usize = 12;
+pub const OFF_MIGRATION_REQUEST_LENGTH: usize = 16;
+pub const OFF_MIGRATION_REQUEST_RESERVED: usize = 20;
+pub const OFF_MIGRATION_REQUEST_BINDING: usize = 32;
+pub const OFF_MIGRATION_REPLY_MAGIC: usize = 0;
+pub const OFF_MIGRATION_REPLY_VERSION: usize = 4;
+pub const OFF_MIGRATION_REPLY_STATUS: usize = 8;
+pub const OFF_MIGRATION_REPLY_LENGTH: usize = 12;
+pub const OFF_MIGRATION_REPLY_RESERVED: usize = 16;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct WireGenerationRequest {
     pub magic: u32,
@@ -225,3 +270,222 @@ impl WireGenerationReply {
         buf
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WireBisectSession {
+    pub magic: [u8; 8],
+    pub format_version: u32,
+    pub header_size: u32,
+    pub serial: u64,
+    pub count: u32,
+    pub low: u32,
+    pub high: u32,
+    pub verdicts: u32,
+    pub good: [u8; 32],
+    pub bad: [u8; 32],
+    pub reserved: [u8; 24],
+}
+
+impl WireBisectSession {
+    pub fn decode(buf: &[u8]) -> Option<Self> {
+        if buf.len() < SESSION_HEADER_LEN {
+            return None;
+        }
+        Some(Self {
+            magic: buf[OFF_SESSION_MAGIC..OFF_SESSION_MAGIC + 8]
+                .try_into()
+                .expect("generated generation-management layout"),
+            format_version: u32::from_le_bytes(
+                buf[OFF_SESSION_FORMAT_VERSION..OFF_SESSION_FORMAT_VERSION + 4]
+                    .try_into()
+                    .expect("generated generation-management layout"),
+            ),
+            header_size: u32::from_le_bytes(
+                buf[OFF_SESSION_HEADER_SIZE..OFF_SESSION_HEADER_SIZE + 4]
+                    .try_into()
+                    .expect("generated generation-management layout"),
+            ),
+            serial: u64::from_le_bytes(
+                buf[OFF_SESSION_SERIAL..OFF_SESSION_SERIAL + 8]
+                    .try_into()
+                    .expect("generated generation-management layout"),
+            ),
+            count: u32::from_le_bytes(
+                buf[OFF_SESSION_COUNT..OFF_SESSION_COUNT + 4]
+                    .try_into()
+                    .expect("generated generation-management layout"),
+            ),
+            low: u32::from_le_bytes(
+                buf[OFF_SESSION_LOW..OFF_SESSION_LOW + 4]
+                    .try_into()
+                    .expect("generated generation-management layout"),
+            ),
+            high: u32::from_le_bytes(
+                buf[OFF_SESSION_HIGH..OFF_SESSION_HIGH + 4]
+                    .try_into()
+                    .expect("generated generation-management layout"),
+            ),
+            verdicts: u32::from_le_bytes(
+                buf[OFF_SESSION_VERDICTS..OFF_SESSION_VERDICTS + 4]
+                    .try_into()
+                    .expect("generated generation-management layout"),
+            ),
+            good: buf[OFF_SESSION_GOOD..OFF_SESSION_GOOD + 32]
+                .try_into()
+                .expect("generated generation-management layout"),
+            bad: buf[OFF_SESSION_BAD..OFF_SESSION_BAD + 32]
+                .try_into()
+                .expect("generated generation-management layout"),
+            reserved: buf[OFF_SESSION_RESERVED..OFF_SESSION_RESERVED + 24]
+                .try_into()
+                .expect("generated generation-management layout"),
+        })
+    }
+
+    pub fn encode(self) -> [u8; SESSION_HEADER_LEN] {
+        let mut buf = [0u8; SESSION_HEADER_LEN];
+        buf[OFF_SESSION_MAGIC..OFF_SESSION_MAGIC + 8].copy_from_slice(&self.magic);
+        buf[OFF_SESSION_FORMAT_VERSION..OFF_SESSION_FORMAT_VERSION + 4]
+            .copy_from_slice(&self.format_version.to_le_bytes());
+        buf[OFF_SESSION_HEADER_SIZE..OFF_SESSION_HEADER_SIZE + 4]
+            .copy_from_slice(&self.header_size.to_le_bytes());
+        buf[OFF_SESSION_SERIAL..OFF_SESSION_SERIAL + 8].copy_from_slice(&self.serial.to_le_bytes());
+        buf[OFF_SESSION_COUNT..OFF_SESSION_COUNT + 4].copy_from_slice(&self.count.to_le_bytes());
+        buf[OFF_SESSION_LOW..OFF_SESSION_LOW + 4].copy_from_slice(&self.low.to_le_bytes());
+        buf[OFF_SESSION_HIGH..OFF_SESSION_HIGH + 4].copy_from_slice(&self.high.to_le_bytes());
+        buf[OFF_SESSION_VERDICTS..OFF_SESSION_VERDICTS + 4]
+            .copy_from_slice(&self.verdicts.to_le_bytes());
+        buf[OFF_SESSION_GOOD..OFF_SESSION_GOOD + 32].copy_from_slice(&self.good);
+        buf[OFF_SESSION_BAD..OFF_SESSION_BAD + 32].copy_from_slice(&self.bad);
+        buf[OFF_SESSION_RESERVED..OFF_SESSION_RESERVED + 24].copy_from_slice(&self.reserved);
+        buf
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WireMigrationRequest {
+    pub magic: u32,
+    pub version: u32,
+    pub from_version: u32,
+    pub to_version: u32,
+    pub length: u32,
+    pub reserved: [u8; 12],
+    pub binding: [u8; 32],
+}
+
+impl WireMigrationRequest {
+    pub fn decode(buf: &[u8]) -> Option<Self> {
+        if buf.len() < MIGRATION_REQUEST_LEN {
+            return None;
+        }
+        Some(Self {
+            magic: u32::from_le_bytes(
+                buf[OFF_MIGRATION_REQUEST_MAGIC..OFF_MIGRATION_REQUEST_MAGIC + 4]
+                    .try_into()
+                    .expect("generated generation-management layout"),
+            ),
+            version: u32::from_le_bytes(
+                buf[OFF_MIGRATION_REQUEST_VERSION..OFF_MIGRATION_REQUEST_VERSION + 4]
+                    .try_into()
+                    .expect("generated generation-management layout"),
+            ),
+            from_version: u32::from_le_bytes(
+                buf[OFF_MIGRATION_REQUEST_FROM_VERSION..OFF_MIGRATION_REQUEST_FROM_VERSION + 4]
+                    .try_into()
+                    .expect("generated generation-management layout"),
+            ),
+            to_version: u32::from_le_bytes(
+                buf[OFF_MIGRATION_REQUEST_TO_VERSION..OFF_MIGRATION_REQUEST_TO_VERSION + 4]
+                    .try_into()
+                    .expect("generated generation-management layout"),
+            ),
+            length: u32::from_le_bytes(
+                buf[OFF_MIGRATION_REQUEST_LENGTH..OFF_MIGRATION_REQUEST_LENGTH + 4]
+                    .try_into()
+                    .expect("generated generation-management layout"),
+            ),
+            reserved: buf[OFF_MIGRATION_REQUEST_RESERVED..OFF_MIGRATION_REQUEST_RESERVED + 12]
+                .try_into()
+                .expect("generated generation-management layout"),
+            binding: buf[OFF_MIGRATION_REQUEST_BINDING..OFF_MIGRATION_REQUEST_BINDING + 32]
+                .try_into()
+                .expect("generated generation-management layout"),
+        })
+    }
+
+    pub fn encode(self) -> [u8; MIGRATION_REQUEST_LEN] {
+        let mut buf = [0u8; MIGRATION_REQUEST_LEN];
+        buf[OFF_MIGRATION_REQUEST_MAGIC..OFF_MIGRATION_REQUEST_MAGIC + 4]
+            .copy_from_slice(&self.magic.to_le_bytes());
+        buf[OFF_MIGRATION_REQUEST_VERSION..OFF_MIGRATION_REQUEST_VERSION + 4]
+            .copy_from_slice(&self.version.to_le_bytes());
+        buf[OFF_MIGRATION_REQUEST_FROM_VERSION..OFF_MIGRATION_REQUEST_FROM_VERSION + 4]
+            .copy_from_slice(&self.from_version.to_le_bytes());
+        buf[OFF_MIGRATION_REQUEST_TO_VERSION..OFF_MIGRATION_REQUEST_TO_VERSION + 4]
+            .copy_from_slice(&self.to_version.to_le_bytes());
+        buf[OFF_MIGRATION_REQUEST_LENGTH..OFF_MIGRATION_REQUEST_LENGTH + 4]
+            .copy_from_slice(&self.length.to_le_bytes());
+        buf[OFF_MIGRATION_REQUEST_RESERVED..OFF_MIGRATION_REQUEST_RESERVED + 12]
+            .copy_from_slice(&self.reserved);
+        buf[OFF_MIGRATION_REQUEST_BINDING..OFF_MIGRATION_REQUEST_BINDING + 32]
+            .copy_from_slice(&self.binding);
+        buf
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WireMigrationReply {
+    pub magic: u32,
+    pub version: u32,
+    pub status: i32,
+    pub length: u32,
+    pub reserved: [u8; 48],
+}
+
+impl WireMigrationReply {
+    pub fn decode(buf: &[u8]) -> Option<Self> {
+        if buf.len() < MIGRATION_REPLY_LEN {
+            return None;
+        }
+        Some(Self {
+            magic: u32::from_le_bytes(
+                buf[OFF_MIGRATION_REPLY_MAGIC..OFF_MIGRATION_REPLY_MAGIC + 4]
+                    .try_into()
+                    .expect("generated generation-management layout"),
+            ),
+            version: u32::from_le_bytes(
+                buf[OFF_MIGRATION_REPLY_VERSION..OFF_MIGRATION_REPLY_VERSION + 4]
+                    .try_into()
+                    .expect("generated generation-management layout"),
+            ),
+            status: i32::from_le_bytes(
+                buf[OFF_MIGRATION_REPLY_STATUS..OFF_MIGRATION_REPLY_STATUS + 4]
+                    .try_into()
+                    .expect("generated generation-management layout"),
+            ),
+            length: u32::from_le_bytes(
+                buf[OFF_MIGRATION_REPLY_LENGTH..OFF_MIGRATION_REPLY_LENGTH + 4]
+                    .try_into()
+                    .expect("generated generation-management layout"),
+            ),
+            reserved: buf[OFF_MIGRATION_REPLY_RESERVED..OFF_MIGRATION_REPLY_RESERVED + 48]
+                .try_into()
+                .expect("generated generation-management layout"),
+        })
+    }
+
+    pub fn encode(self) -> [u8; MIGRATION_REPLY_LEN] {
+        let mut buf = [0u8; MIGRATION_REPLY_LEN];
+        buf[OFF_MIGRATION_REPLY_MAGIC..OFF_MIGRATION_REPLY_MAGIC + 4]
+            .copy_from_slice(&self.magic.to_le_bytes());
+        buf[OFF_MIGRATION_REPLY_VERSION..OFF_MIGRATION_REPLY_VERSION + 4]
+            .copy_from_slice(&self.version.to_le_bytes());
+        buf[OFF_MIGRATION_REPLY_STATUS..OFF_MIGRATION_REPLY_STATUS + 4]
+            .copy_from_slice(&self.status.to_le_bytes());
+        buf[OFF_MIGRATION_REPLY_LENGTH..OFF_MIGRATION_REPLY_LENGTH + 4]
+            .copy_from_slice(&self.length.to_le_bytes());
+        buf[OFF_MIGRATION_REPLY_RESERVED..OFF_MIGRATION_REPLY_RESERVED + 48]
+            .copy_from_slice(&self.reserved);
+        buf
+    }
+}
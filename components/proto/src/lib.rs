@@ -24,26 +24,50 @@ pub mod syscall_abi;
 pub mod trace_sink;
 
 pub fn valid_fs_request(request: &fs::WireFsRequest) -> bool {
-    let name_len = request.name_len as usize;
+    // fs/v2 carries no name: the target path is the scope of the Directory
+    // capability the request travels with, and the root validated it there.
     let base_valid = request.magic == fs::FS_MAGIC
         && request.version == fs::FORMAT_VERSION
         && matches!(
             request.op,
-            fs::OP_LIST | fs::OP_READ | fs::OP_WRITE | fs::OP_DERIVE
+            fs::OP_LIST
+                | fs::OP_READ
+                | fs::OP_WRITE
+                | fs::OP_DERIVE
+                | fs::OP_SNAPSHOT
+                | fs::OP_RESTORE
+                | fs::OP_READ_RANGE
+                | fs::OP_WRITE_RANGE
         )
         && request.flags == 0
         && request.reserved0 == 0
-        && name_len <= fs::MAX_NAME_BYTES
-        && request.name[name_len..].iter().all(|byte| *byte == 0)
-        && valid_name(&request.name[..name_len], request.op == fs::OP_LIST);
+        && request.reserved1.iter().all(|byte| *byte == 0);
     if !base_valid {
         return false;
     }
     let zero_hash =
         request.hash0 == 0 && request.hash1 == 0 && request.hash2 == 0 && request.hash3 == 0;
+    // A range is non-empty, within one transfer, and inside what a chunked
+    // file may hold. Only the two range operations carry an offset at all.
+    let range_valid = request.payload_len > 0
+        && request.payload_len as usize <= fs::MAX_RANGE_BYTES
+        && request
+            .offset
+            .checked_add(u64::from(request.payload_len))
+            .is_some_and(|end| end <= fs::MAX_CHUNKED_FILE_BYTES as u64);
     match request.op {
-        fs::OP_LIST | fs::OP_READ | fs::OP_DERIVE => request.payload_len == 0 && zero_hash,
-        fs::OP_WRITE => request.payload_len <= 32 * 1024 && !zero_hash,
+        fs::OP_LIST | fs::OP_READ | fs::OP_DERIVE | fs::OP_SNAPSHOT => {
+            request.payload_len == 0 && request.offset == 0 && zero_hash
+        }
+        fs::OP_WRITE => {
+            request.payload_len as usize <= fs::MAX_FILE_BYTES && request.offset == 0 && !zero_hash
+        }
+        fs::OP_RESTORE => request.payload_len == 0 && request.offset == 0 && !zero_hash,
+        // A range read names no content; a range write names the SHA-256 of
+        // the bytes its loan carries, so the service can tell the loan it was
+        // handed is the one the request was written for.
+        fs::OP_READ_RANGE => range_valid && zero_hash,
+        fs::OP_WRITE_RANGE => range_valid && !zero_hash,
         _ => false,
     }
 }
@@ -54,11 +78,14 @@ pub fn valid_fs_reply(reply: &fs::WireFsReply) -> bool {
         && reply.entry_count as usize <= fs::MAX_ENTRIES
         && reply.reserved == 0
 }
-fn valid_name(name: &[u8], allow_empty: bool) -> bool {
-    if name.is_empty() {
-        return allow_empty;
-    }
-    name != b"."
+
+/// One fs/v2 directory entry name: a single path segment of at most
+/// `fs::MAX_NAME_BYTES`, drawn from the same alphabet the root admits in a
+/// directory scope.
+pub fn valid_fs_name(name: &[u8]) -> bool {
+    !name.is_empty()
+        && name.len() <= fs::MAX_NAME_BYTES
+        && name != b"."
         && name != b".."
         && name
             .iter()
@@ -117,6 +144,7 @@ pub fn valid_spawn_request(request: &spawn::WireSpawnRequest) -> bool {
             && request.environment.iter().all(|byte| *byte == 0)
             && u64::from_le_bytes(request.arguments) != 0
             && request.grant_rights == 0
+            && request.scheduling_class == 0
             && request.reserved.iter().all(|byte| *byte == 0);
     }
     if request.flags == spawn::REQUEST_FLAG_SHUTDOWN {
@@ -128,6 +156,7 @@ pub fn valid_spawn_request(request: &spawn::WireSpawnRequest) -> bool {
             && request.arguments.iter().all(|byte| *byte == 0)
             && request.environment.iter().all(|byte| *byte == 0)
             && request.grant_rights == 0
+            && request.scheduling_class == 0
             && request.reserved.iter().all(|byte| *byte == 0);
     }
     request.flags == 0
@@ -137,6 +166,10 @@ pub fn valid_spawn_request(request: &spawn::WireSpawnRequest) -> bool {
         && request.environment_count as usize <= spawn::MAX_ENVIRONMENT
         && packed_fields_valid(&request.arguments, request.argument_count as usize)
         && packed_fields_valid(&request.environment, request.environment_count as usize)
+        && matches!(
+            u64::from(request.scheduling_class),
+            syscall_abi::SCHEDULING_CLASS_DECLARED | syscall_abi::SCHEDULING_CLASS_BACKGROUND
+        )
 }
 
 fn packed_fields_valid<const N: usize>(bytes: &[u8; N], count: usize) -> bool {
@@ -32,7 +32,8 @@ pub const OFF_REQUEST_COMMAND: usize = 18;
 pub const OFF_REQUEST_ARGUMENTS: usize = 34;
 pub const OFF_REQUEST_ENVIRONMENT: usize = 42;
 pub const OFF_REQUEST_GRANT_RIGHTS: usize = 50;
-pub const OFF_REQUEST_RESERVED: usize = 54;
+pub const OFF_REQUEST_SCHEDULING_CLASS: usize = 54;
+pub const OFF_REQUEST_RESERVED: usize = 55;
 
 pub const OFF_REPLY_MAGIC: usize = 0;
 pub const OFF_REPLY_VERSION: usize = 4;
@@ -55,7 +56,8 @@ pub struct WireSpawnRequest {
     pub arguments: [u8; 8],
     pub environment: [u8; 8],
     pub grant_rights: u32,
-    pub reserved: [u8; 6],
+    pub scheduling_class: u8,
+    pub reserved: [u8; 5],
 }
 
 impl WireSpawnRequest {
@@ -102,7 +104,8 @@ impl WireSpawnRequest {
                     .try_into()
                     .expect("generated spawn layout"),
             ),
-            reserved: buf[OFF_REQUEST_RESERVED..OFF_REQUEST_RESERVED + 6]
+            scheduling_class: buf[OFF_REQUEST_SCHEDULING_CLASS],
+            reserved: buf[OFF_REQUEST_RESERVED..OFF_REQUEST_RESERVED + 5]
                 .try_into()
                 .expect("generated spawn layout"),
         })
@@ -126,7 +129,8 @@ impl WireSpawnRequest {
             .copy_from_slice(&self.environment);
         buf[OFF_REQUEST_GRANT_RIGHTS..OFF_REQUEST_GRANT_RIGHTS + 4]
             .copy_from_slice(&self.grant_rights.to_le_bytes());
-        buf[OFF_REQUEST_RESERVED..OFF_REQUEST_RESERVED + 6].copy_from_slice(&self.reserved);
+        buf[OFF_REQUEST_SCHEDULING_CLASS] = self.scheduling_class;
+        buf[OFF_REQUEST_RESERVED..OFF_REQUEST_RESERVED + 5].copy_from_slice(&self.reserved);
         buf
     }
 }
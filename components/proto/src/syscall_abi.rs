@@ -16,6 +16,7 @@ pub mod fixture_labels {
 pub mod lifecycle_labels {
     pub const EXIT: u64 = 3;
     pub const UNHEALTHY: u64 = 9;
+    pub const ROUTE_ANSWERED: u64 = 42;
 }
 
 pub mod spawn_labels {
@@ -25,6 +26,9 @@ pub mod spawn_labels {
 pub mod supervision_labels {
     pub const STATUS: u64 = 12;
     pub const DERIVE: u64 = 32;
+    pub const SET_PRIORITY: u64 = 45;
+    pub const SET_CLASS: u64 = 46;
+    pub const KILL: u64 = 50;
 }
 
 pub mod capability_table_labels {
@@ -38,6 +42,7 @@ pub mod capability_table_labels {
 
 pub mod directory_labels {
     pub const DERIVE: u64 = 15;
+    pub const SNAPSHOT: u64 = 41;
 }
 
 pub mod shared_buffer_labels {
@@ -60,6 +65,17 @@ pub mod capability_transfer_labels {
     pub const EXPORT_FINALIZE: u64 = 36;
 }
 
+pub mod memory_labels {
+    pub const HEAP_GROW: u64 = 43;
+    pub const OCCUPANCY: u64 = 44;
+}
+
+pub mod clock_labels {
+    pub const NOW: u64 = 47;
+    pub const WAIT: u64 = 48;
+    pub const ADVANCE: u64 = 49;
+}
+
 pub const ERR_SUCCESS: i64 = 0;
 pub const ERR_BAD_CAP: i64 = -1;
 pub const ERR_PEER_DEAD: i64 = -2;
@@ -76,3 +92,10 @@ pub const MAX_CAPS_PER_MSG: usize = 1;
 pub const GRANT_RECORD_BYTES: usize = 16;
 pub const GRANT_SLOT_OFFSET: usize = 0;
 pub const GRANT_RIGHTS_OFFSET: usize = 8;
+
+/// Scheduling classes, as a spawn requests one in the high half of its
+/// primary operand and `SET_CLASS` names one. `DECLARED` runs every thread
+/// at the priority its generation declares; `BACKGROUND` runs them all at
+/// the lowest.
+pub const SCHEDULING_CLASS_DECLARED: u64 = 0;
+pub const SCHEDULING_CLASS_BACKGROUND: u64 = 1;
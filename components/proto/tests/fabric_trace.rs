@@ -372,6 +372,7 @@ fn a_resource_record_must_name_which_count_it_carries() {
         fabric_trace::RESOURCE_LOAN,
         fabric_trace::RESOURCE_MAPPING,
         fabric_trace::RESOURCE_CAPABILITY_SLOTS,
+        fabric_trace::RESOURCE_PRIVATE_PAGES,
         fabric_trace::RESOURCE_COMPLETE,
     ] {
         record.event = counter;
@@ -1,20 +1,18 @@
 use slime_proto::{
     fs::{self, WireFsReply, WireFsRequest},
-    valid_fs_reply, valid_fs_request,
+    valid_fs_name, valid_fs_reply, valid_fs_request,
 };
 
-fn request(op: u8, name: &[u8]) -> WireFsRequest {
-    let mut encoded_name = [0u8; fs::MAX_NAME_BYTES];
-    encoded_name[..name.len()].copy_from_slice(name);
+fn request(op: u8) -> WireFsRequest {
     WireFsRequest {
         magic: fs::FS_MAGIC,
         version: fs::FORMAT_VERSION,
         op,
         flags: 0,
-        name_len: name.len() as u8,
         reserved0: 0,
-        name: encoded_name,
         payload_len: 0,
+        offset: 0,
+        reserved1: [0; 8],
         hash0: 0,
         hash1: 0,
         hash2: 0,
@@ -24,35 +22,36 @@ fn request(op: u8, name: &[u8]) -> WireFsRequest {
 
 #[test]
 fn request_round_trips_byte_identically() {
-    for request in [request(fs::OP_LIST, b""), request(fs::OP_READ, b"note")] {
+    for request in [request(fs::OP_LIST), request(fs::OP_READ)] {
         assert!(valid_fs_request(&request));
         let encoded = request.encode();
         assert_eq!(WireFsRequest::decode(&encoded), Some(request));
         assert_eq!(WireFsRequest::decode(&encoded).unwrap().encode(), encoded);
     }
-    let mut write = request(fs::OP_WRITE, b"new.txt");
+    let mut write = request(fs::OP_WRITE);
     write.payload_len = 16;
     write.hash0 = 1;
     assert!(valid_fs_request(&write));
     assert_eq!(WireFsRequest::decode(&write.encode()), Some(write));
 }
+
 #[test]
-fn malformed_names_versions_and_bounds_fail_closed() {
+fn malformed_versions_reserved_bytes_and_bounds_fail_closed() {
     assert!(WireFsRequest::decode(&[0; fs::REQUEST_LEN - 1]).is_none());
-    for invalid in [b".".as_slice(), b"..", b"a/b", b"bad name"] {
-        assert!(!valid_fs_request(&request(fs::OP_READ, invalid)));
-    }
-    let mut unknown = request(fs::OP_READ, b"note");
-    unknown.version += 1;
+    let mut unknown = request(fs::OP_READ);
+    unknown.version = 1;
     assert!(!valid_fs_request(&unknown));
-    let mut trailing = request(fs::OP_READ, b"note");
-    trailing.name[5] = b'x';
-    assert!(!valid_fs_request(&trailing));
-    assert!(!valid_fs_request(&request(fs::OP_READ, b"")));
-    let mut list_with_hash = request(fs::OP_LIST, b"");
+    let mut flagged = request(fs::OP_READ);
+    flagged.flags = 1;
+    assert!(!valid_fs_request(&flagged));
+    // v1 carried the name in these bytes; v2 must not read a stale one.
+    let mut named = request(fs::OP_READ);
+    named.reserved1[0] = b'n';
+    assert!(!valid_fs_request(&named));
+    let mut list_with_hash = request(fs::OP_LIST);
     list_with_hash.hash0 = 1;
     assert!(!valid_fs_request(&list_with_hash));
-    let mut empty_write = request(fs::OP_WRITE, b"new.txt");
+    let mut empty_write = request(fs::OP_WRITE);
     empty_write.payload_len = 16;
     assert!(!valid_fs_request(&empty_write));
     let mut oversized_write = empty_write;
@@ -61,6 +60,18 @@ fn malformed_names_versions_and_bounds_fail_closed() {
     assert!(!valid_fs_request(&oversized_write));
 }
 
+#[test]
+fn names_are_single_bounded_segments() {
+    assert!(valid_fs_name(b"note"));
+    assert!(valid_fs_name(&[b'a'; fs::MAX_NAME_BYTES]));
+    assert!(!valid_fs_name(&[b'a'; fs::MAX_NAME_BYTES + 1]));
+    for invalid in [b"".as_slice(), b".", b"..", b"a/b", b"bad name"] {
+        assert!(!valid_fs_name(invalid));
+    }
+    assert!(fs::MAX_ENTRIES > fs::V1_MAX_ENTRIES);
+    assert!(fs::MAX_NAME_BYTES > fs::V1_MAX_NAME_BYTES);
+}
+
 #[test]
 fn replies_round_trip_and_enforce_entry_bound() {
     let reply = WireFsReply {
@@ -85,3 +96,59 @@ fn replies_round_trip_and_enforce_entry_bound() {
         ..reply
     }));
 }
+
+#[test]
+fn snapshot_is_hashless_and_restore_names_a_root() {
+    let snapshot = request(fs::OP_SNAPSHOT);
+    assert!(valid_fs_request(&snapshot));
+    let mut hashed_snapshot = snapshot;
+    hashed_snapshot.hash0 = 1;
+    assert!(!valid_fs_request(&hashed_snapshot));
+
+    let mut restore = request(fs::OP_RESTORE);
+    assert!(!valid_fs_request(&restore));
+    restore.hash2 = 9;
+    assert!(valid_fs_request(&restore));
+    assert_eq!(WireFsRequest::decode(&restore.encode()), Some(restore));
+    let mut restore_with_payload = restore;
+    restore_with_payload.payload_len = 1;
+    assert!(!valid_fs_request(&restore_with_payload));
+    assert!(!valid_fs_request(&request(fs::OP_WRITE_RANGE + 1)));
+}
+
+#[test]
+fn ranges_are_bounded_and_only_range_operations_carry_an_offset() {
+    let mut read = request(fs::OP_READ_RANGE);
+    assert!(!valid_fs_request(&read));
+    read.offset = 100;
+    read.payload_len = 28;
+    assert!(valid_fs_request(&read));
+    assert_eq!(WireFsRequest::decode(&read.encode()), Some(read));
+    let mut hashed_read = read;
+    hashed_read.hash1 = 1;
+    assert!(!valid_fs_request(&hashed_read));
+
+    let mut write = read;
+    write.op = fs::OP_WRITE_RANGE;
+    assert!(!valid_fs_request(&write));
+    write.hash0 = 1;
+    assert!(valid_fs_request(&write));
+
+    let mut at_limit = write;
+    at_limit.payload_len = fs::MAX_RANGE_BYTES as u32;
+    at_limit.offset = (fs::MAX_CHUNKED_FILE_BYTES - fs::MAX_RANGE_BYTES) as u64;
+    assert!(valid_fs_request(&at_limit));
+    let mut past_file = at_limit;
+    past_file.offset += 1;
+    assert!(!valid_fs_request(&past_file));
+    let mut past_range = write;
+    past_range.payload_len = fs::MAX_RANGE_BYTES as u32 + 1;
+    assert!(!valid_fs_request(&past_range));
+    let mut wrapping = write;
+    wrapping.offset = u64::MAX;
+    assert!(!valid_fs_request(&wrapping));
+
+    let mut offset_read = request(fs::OP_READ);
+    offset_read.offset = 1;
+    assert!(!valid_fs_request(&offset_read));
+}
@@ -4,7 +4,7 @@ use slime_proto::{
 };
 
 // B59: rights bit numbering is generated from
-// `contracts/generation/v5/schema.zt`. The powerbox protocol carries a 32-bit
+// `contracts/generation/v6/schema.zt`. The powerbox protocol carries a 32-bit
 // rights field, so the generated `u64` constant is narrowed here rather than
 // re-spelled as a separate `u32` literal.
 const RIGHT_DIRECTORY_READ: u32 = boot_contracts::generation::RIGHT_DIRECTORY_READ as u32;
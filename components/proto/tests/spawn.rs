@@ -1,7 +1,7 @@
 use slime_proto::{
     spawn::{
-        CAPABILITY_ROLE_STDOUT, FORMAT_VERSION, REPLY_LEN, REQUEST_LEN, SPAWN_MAGIC,
-        WireSpawnReply, WireSpawnRequest,
+        CAPABILITY_ROLE_STDOUT, FORMAT_VERSION, REPLY_LEN, REQUEST_FLAG_SHUTDOWN, REQUEST_LEN,
+        SPAWN_MAGIC, WireSpawnReply, WireSpawnRequest,
     },
     valid_spawn_reply, valid_spawn_request,
 };
@@ -21,7 +21,8 @@ fn request_round_trips_byte_identically() {
         arguments: [3, b'-', b'v', b'v', 0, 0, 0, 0],
         environment: [6, b'K', b'=', b'V', b'A', b'L', b'1', 0],
         grant_rights: 1,
-        reserved: [0; 6],
+        scheduling_class: 1,
+        reserved: [0; 5],
     };
     let encoded = request.encode();
     assert_eq!(
@@ -67,12 +68,26 @@ fn request_validation_rejects_unknown_versions_and_bounds() {
         arguments: [0; 8],
         environment: [0; 8],
         grant_rights: 0,
-        reserved: [0; 6],
+        scheduling_class: 0,
+        reserved: [0; 5],
     };
     assert!(!valid_spawn_request(&WireSpawnRequest {
         version: FORMAT_VERSION + 1,
         ..base
     }));
+    // A class the root does not know, and a class on a request that spawns
+    // nothing.
+    assert!(!valid_spawn_request(&WireSpawnRequest {
+        scheduling_class: 2,
+        ..base
+    }));
+    assert!(!valid_spawn_request(&WireSpawnRequest {
+        flags: REQUEST_FLAG_SHUTDOWN,
+        command_len: 0,
+        command: [0; 16],
+        scheduling_class: 1,
+        ..base
+    }));
     assert!(!valid_spawn_request(&WireSpawnRequest {
         command_len: 17,
         ..base
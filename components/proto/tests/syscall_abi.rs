@@ -15,13 +15,14 @@
 use slime_proto::syscall_abi::{
     ERR_BAD_CAP, ERR_INVALID_ARG, ERR_OUT_OF_MEMORY, ERR_PEER_DEAD, ERR_SUCCESS, ERR_WOULDBLOCK,
     FORMAT_VERSION, GRANT_RECORD_BYTES, GRANT_RIGHTS_OFFSET, GRANT_SLOT_OFFSET, MAX_CAPS_PER_MSG,
-    MAX_MSG, capability_table_labels, capability_transfer_labels, directory_labels, fixture_labels,
-    lifecycle_labels, shared_buffer_labels, spawn_labels, supervision_labels,
+    MAX_MSG, SCHEDULING_CLASS_BACKGROUND, SCHEDULING_CLASS_DECLARED, capability_table_labels,
+    capability_transfer_labels, clock_labels, directory_labels, fixture_labels, lifecycle_labels, memory_labels,
+    shared_buffer_labels, spawn_labels, supervision_labels,
 };
 
 #[test]
 fn operation_labels_are_frozen() {
-    let labels: [(&str, u64); 27] = [
+    let labels: [(&str, u64); 37] = [
         ("lifecycle::EXIT", lifecycle_labels::EXIT),
         ("lifecycle::UNHEALTHY", lifecycle_labels::UNHEALTHY),
         ("spawn::SPAWN", spawn_labels::SPAWN),
@@ -81,10 +82,23 @@ fn operation_labels_are_frozen() {
             "capabilityTable::BOOT_ACTION",
             capability_table_labels::BOOT_ACTION,
         ),
+        ("directory::SNAPSHOT", directory_labels::SNAPSHOT),
+        (
+            "lifecycle::ROUTE_ANSWERED",
+            lifecycle_labels::ROUTE_ANSWERED,
+        ),
+        ("memory::HEAP_GROW", memory_labels::HEAP_GROW),
+        ("memory::OCCUPANCY", memory_labels::OCCUPANCY),
+        ("supervision::SET_PRIORITY", supervision_labels::SET_PRIORITY),
+        ("supervision::SET_CLASS", supervision_labels::SET_CLASS),
+        ("clock::NOW", clock_labels::NOW),
+        ("clock::WAIT", clock_labels::WAIT),
+        ("clock::ADVANCE", clock_labels::ADVANCE),
+        ("supervision::KILL", supervision_labels::KILL),
     ];
-    let expected: [u64; 27] = [
+    let expected: [u64; 37] = [
         3, 9, 4, 5, 12, 32, 13, 31, 15, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 33, 34, 35, 36, 37,
-        38, 39, 40,
+        38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50,
     ];
     for ((name, actual), want) in labels.iter().zip(expected) {
         assert_eq!(*actual, want, "operation {name} was renumbered");
@@ -124,6 +138,16 @@ fn operation_labels_are_distinct() {
         capability_table_labels::GRAPH_READ,
         capability_table_labels::GRAPH_ROUTE_INDEX,
         capability_table_labels::BOOT_ACTION,
+        directory_labels::SNAPSHOT,
+        lifecycle_labels::ROUTE_ANSWERED,
+        memory_labels::HEAP_GROW,
+        memory_labels::OCCUPANCY,
+        supervision_labels::SET_PRIORITY,
+        supervision_labels::SET_CLASS,
+        clock_labels::NOW,
+        clock_labels::WAIT,
+        clock_labels::ADVANCE,
+        supervision_labels::KILL,
     ];
     labels.sort_unstable();
     for pair in labels.windows(2) {
@@ -167,6 +191,15 @@ fn spawn_grant_record_layout_is_frozen() {
     assert_eq!(GRANT_SLOT_OFFSET + 8, GRANT_RIGHTS_OFFSET);
 }
 
+/// A spawn's class travels in its primary operand's high half, so zero must be
+/// the declared class: every image built before classes existed sends zero
+/// there, and must keep starting children where the generation put them.
+#[test]
+fn scheduling_classes_are_frozen() {
+    assert_eq!(SCHEDULING_CLASS_DECLARED, 0);
+    assert_eq!(SCHEDULING_CLASS_BACKGROUND, 1);
+}
+
 #[test]
 fn message_bounds_are_frozen() {
     assert_eq!(FORMAT_VERSION, 1);
@@ -0,0 +1,338 @@
+//! A free-list allocator over the root-served heap window.
+//!
+//! [`crate::BumpHeap`] is a `.bss` array sized for the worst case at build
+//! time and never reuses what it hands out, which is right for the store
+//! plane's open-index-answer-exit shape and wrong for anything that runs
+//! long or allocates in proportion to its input. This one starts empty and
+//! asks the root for pages as it needs them ([`crate::heap_grow`]), up to the
+//! page quota the generation declares for the instance, and a freed block is
+//! reused by the next allocation that fits.
+//!
+//! First fit over one address-ordered free list, coalescing on free. Nothing
+//! cleverer, because the bound that matters is the quota rather than the
+//! fragmentation: a component that needs a size-class allocator needs a larger
+//! `heapPages` first. Pages are never handed back — the window only grows, and
+//! the root reclaims all of it with the task.
+//!
+//! Behind the `growable-heap` feature, registered once for every component in
+//! the build for the reason `heap` is (see `heap.rs`). The two are exclusive:
+//! one build has one `#[global_allocator]`.
+
+use core::alloc::{GlobalAlloc, Layout};
+use core::cell::UnsafeCell;
+use core::mem::size_of;
+use core::ptr;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::runtime::MAX_THREADS;
+use crate::syscall::{HeapRefusal, heap_grow, thread_lock_signal, thread_lock_wait};
+
+#[cfg(feature = "heap")]
+compile_error!("`heap` and `growable-heap` each register a global allocator; enable one");
+
+const PAGE_BYTES: usize = 4096;
+
+/// A free block's header, written into the block itself.
+#[repr(C)]
+struct FreeBlock {
+    size: usize,
+    next: *mut FreeBlock,
+}
+
+/// The allocation granule. Every block starts on one and is a whole number of
+/// them, so any fragment left over from a split can hold its own header.
+const UNIT: usize = size_of::<FreeBlock>();
+
+/// Free memory as an address-ordered singly linked list of [`FreeBlock`]s.
+struct FreeList {
+    head: *mut FreeBlock,
+}
+
+impl FreeList {
+    const fn new() -> Self {
+        Self {
+            head: ptr::null_mut(),
+        }
+    }
+
+    /// Carve `size` bytes aligned to `align` out of the first block that fits,
+    /// or null. Both are whole units; what is left before and after the carved
+    /// range stays on the list.
+    ///
+    /// # Safety
+    ///
+    /// Every block on the list must be memory this list owns.
+    unsafe fn take(&mut self, size: usize, align: usize) -> *mut u8 {
+        let mut link: *mut *mut FreeBlock = &mut self.head;
+        // SAFETY: `link` is the head or a `next` field of a listed block, and
+        // every listed block is owned, unit-aligned memory per the contract.
+        unsafe {
+            while !(*link).is_null() {
+                let block = *link;
+                let start = block as usize;
+                let end = start + (*block).size;
+                let carved = start
+                    .checked_next_multiple_of(align)
+                    .and_then(|aligned| Some((aligned, aligned.checked_add(size)?)));
+                if let Some((aligned, carved_end)) = carved
+                    && carved_end <= end
+                {
+                    let next = (*block).next;
+                    let tail = if carved_end < end {
+                        let tail = carved_end as *mut FreeBlock;
+                        tail.write(FreeBlock {
+                            size: end - carved_end,
+                            next,
+                        });
+                        tail
+                    } else {
+                        next
+                    };
+                    if aligned > start {
+                        (*block).size = aligned - start;
+                        (*block).next = tail;
+                    } else {
+                        *link = tail;
+                    }
+                    return aligned as *mut u8;
+                }
+                link = &raw mut (*block).next;
+            }
+        }
+        ptr::null_mut()
+    }
+
+    /// Put `size` bytes at `block` back on the list, merged with whichever
+    /// neighbours it touches.
+    ///
+    /// # Safety
+    ///
+    /// The range must be unit-aligned, a whole number of units, owned by no
+    /// one else, and overlap nothing already listed.
+    unsafe fn give(&mut self, block: *mut u8, size: usize) {
+        let start = block as usize;
+        let block = block.cast::<FreeBlock>();
+        // SAFETY: the range is owned and header-sized per the contract, and
+        // every block walked is a listed, owned block.
+        unsafe {
+            let mut previous: *mut FreeBlock = ptr::null_mut();
+            let mut next = self.head;
+            while !next.is_null() && (next as usize) < start {
+                previous = next;
+                next = (*next).next;
+            }
+            block.write(FreeBlock { size, next });
+            if !next.is_null() && start + size == next as usize {
+                (*block).size += (*next).size;
+                (*block).next = (*next).next;
+            }
+            if previous.is_null() {
+                self.head = block;
+            } else if previous as usize + (*previous).size == start {
+                (*previous).size += (*block).size;
+                (*previous).next = (*block).next;
+            } else {
+                (*previous).next = block;
+            }
+        }
+    }
+}
+
+struct State {
+    free: FreeList,
+    /// Pages of the window backed so far; the next growth lands right after.
+    pages: usize,
+    /// The root's answer to the most recent growth it refused.
+    refused: Option<HeapRefusal>,
+}
+
+/// A `GlobalAlloc` that grows through the root's `HEAP_GROW` operation.
+///
+/// Out of quota returns null, which the `alloc` crate turns into
+/// `handle_alloc_error` and the panic handler into a nonzero exit, as for
+/// [`crate::BumpHeap`]. A component that would rather degrade allocates
+/// fallibly (`try_reserve`, `Box::try_new`) and reads [`heap_refusal`] for the
+/// budget it ran into.
+pub struct GrowableHeap {
+    /// Threads holding or waiting for `state`: zero when it is free.
+    contenders: AtomicUsize,
+    state: UnsafeCell<State>,
+}
+
+// A component may run worker threads (B47), so `state` is only touched by the
+// thread that took it in `with`. A thread that finds it taken blocks on the
+// task's thread-lock Notification rather than spinning, so a holder at a lower
+// priority than the waiter still runs, finishes, and signals it.
+unsafe impl Sync for GrowableHeap {}
+
+// A Notification wakes one waiter per signal and remembers a signal sent
+// before the wait, which makes the counting lock in `with` exact for one
+// waiter. With two threads there is never a second.
+const _: () = assert!(
+    MAX_THREADS <= 2,
+    "the heap lock wakes one waiter per release"
+);
+
+impl Default for GrowableHeap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GrowableHeap {
+    pub const fn new() -> Self {
+        Self {
+            contenders: AtomicUsize::new(0),
+            state: UnsafeCell::new(State {
+                free: FreeList::new(),
+                pages: 0,
+                refused: None,
+            }),
+        }
+    }
+
+    /// Pages the root has backed so far, so a component can report its own
+    /// footprint against the quota it declares.
+    pub fn pages(&self) -> usize {
+        self.with(|state| state.pages)
+    }
+
+    /// The root's most recent refusal to grow this heap, if any.
+    pub fn refusal(&self) -> Option<HeapRefusal> {
+        self.with(|state| state.refused)
+    }
+
+    /// Run `operation` with `state` to itself.
+    ///
+    /// Uncontended, taking and releasing are one atomic each and no syscall.
+    /// A thread that arrives while its sibling holds the lock counts itself in
+    /// and blocks; the holder's release sees the count and signals it through.
+    fn with<R>(&self, operation: impl FnOnce(&mut State) -> R) -> R {
+        if self.contenders.fetch_add(1, Ordering::Acquire) != 0 {
+            thread_lock_wait();
+            // The signal that woke this thread followed the holder's release,
+            // so this read observes it and everything the holder wrote.
+            self.contenders.load(Ordering::Acquire);
+        }
+        // SAFETY: this thread took the lock, so this is the only reference to
+        // `state`.
+        let result = operation(unsafe { &mut *self.state.get() });
+        if self.contenders.fetch_sub(1, Ordering::Release) != 1 {
+            thread_lock_signal();
+        }
+        result
+    }
+}
+
+/// `layout` as the whole-unit size and alignment a block is carved at.
+fn block_shape(layout: Layout) -> Option<(usize, usize)> {
+    let size = layout.size().max(1).checked_next_multiple_of(UNIT)?;
+    Some((size, layout.align().max(UNIT)))
+}
+
+unsafe impl GlobalAlloc for GrowableHeap {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let Some((size, align)) = block_shape(layout) else {
+            return ptr::null_mut();
+        };
+        self.with(|state| {
+            // SAFETY: the list holds only window pages this heap was given.
+            let block = unsafe { state.free.take(size, align) };
+            if !block.is_null() {
+                return block;
+            }
+            // Enough for the block at its worst alignment, whether or not the
+            // free block at the top of the window would have helped.
+            let Some(pages) = size
+                .checked_add(align - UNIT)
+                .map(|bytes| bytes.div_ceil(PAGE_BYTES))
+            else {
+                return ptr::null_mut();
+            };
+            let window = match heap_grow(pages) {
+                Ok(window) => window,
+                Err(refusal) => {
+                    state.refused = Some(refusal);
+                    return ptr::null_mut();
+                }
+            };
+            let grown = window.base + state.pages * PAGE_BYTES;
+            let bytes = (window.pages - state.pages) * PAGE_BYTES;
+            state.pages = window.pages;
+            // SAFETY: the root just backed these pages for this task alone,
+            // above everything the list already holds.
+            unsafe {
+                state.free.give(grown as *mut u8, bytes);
+                state.free.take(size, align)
+            }
+        })
+    }
+
+    unsafe fn dealloc(&self, block: *mut u8, layout: Layout) {
+        let Some((size, _)) = block_shape(layout) else {
+            return;
+        };
+        // SAFETY: `block` came from `alloc` with this layout, so it is a
+        // carved, unit-aligned range of exactly `size` bytes.
+        self.with(|state| unsafe { state.free.give(block, size) });
+    }
+}
+
+#[global_allocator]
+static HEAP: GrowableHeap = GrowableHeap::new();
+
+/// Heap pages the root has backed for this component so far.
+pub fn heap_pages() -> usize {
+    HEAP.pages()
+}
+
+/// Why the root last declined to grow this component's heap: the pages it
+/// held and the budget it was held to.
+pub fn heap_refusal() -> Option<HeapRefusal> {
+    HEAP.refusal()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FreeList, UNIT};
+
+    #[repr(align(4096))]
+    struct Arena([u8; 4096]);
+
+    #[test]
+    fn a_freed_block_is_reused_and_neighbours_coalesce() {
+        let mut arena = Arena([0; 4096]);
+        let base = arena.0.as_mut_ptr();
+        let mut list = FreeList::new();
+        unsafe {
+            list.give(base, 4096);
+            let first = list.take(UNIT, UNIT);
+            let second = list.take(2 * UNIT, UNIT);
+            assert_eq!(first, base);
+            assert_eq!(second, base.add(UNIT));
+
+            list.give(first, UNIT);
+            assert_eq!(list.take(UNIT, UNIT), first, "first fit reuses the hole");
+
+            list.give(first, UNIT);
+            list.give(second, 2 * UNIT);
+            assert_eq!((*list.head).size, 4096, "three neighbours merge into one");
+            assert!((*list.head).next.is_null());
+        }
+    }
+
+    #[test]
+    fn an_aligned_take_keeps_both_fragments() {
+        let mut arena = Arena([0; 4096]);
+        let base = arena.0.as_mut_ptr();
+        let mut list = FreeList::new();
+        unsafe {
+            list.give(base.add(UNIT), 4096 - UNIT);
+            let aligned = list.take(UNIT, 256);
+            assert_eq!(aligned, base.add(256));
+            assert_eq!((*list.head).size, 256 - UNIT);
+            assert_eq!((*(*list.head).next).size, 4096 - 256 - UNIT);
+            assert!(list.take(4096, UNIT).is_null());
+        }
+    }
+}
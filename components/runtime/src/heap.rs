@@ -6,10 +6,14 @@
 //! `boot_contracts::object_store` builds its object index the same way — and
 //! those two are the reason this exists.
 //!
-//! Bump, and no free. That is not a shortcut, it is the allocation shape:
-//! a store component opens a partition, indexes it, answers a bounded number
-//! of requests, and exits. Nothing outlives the component, so returning memory
-//! to a free list would only add a failure mode.
+//! Bump, and no free list. That is not a shortcut, it is the allocation
+//! shape: a store component opens a partition, indexes it, answers a bounded
+//! number of requests, and exits. Nothing outlives the component, so returning
+//! memory to a free list would only add a failure mode. The one thing given
+//! back is the most recent allocation, when it is freed before anything newer:
+//! a request's payload buffers are allocated and dropped in turn, and without
+//! that a filesystem service streaming a chunked file would spend its whole
+//! heap on one request.
 //!
 //! The whole module is behind the `heap` feature, which the store-plane build
 //! turns on. That granularity is forced rather than chosen: `extern crate
@@ -95,9 +99,18 @@ unsafe impl<const N: usize> GlobalAlloc for BumpHeap<N> {
         }
     }
 
-    /// A no-op, deliberately. See the module comment: nothing here outlives the
-    /// component, and the allocation pattern is open-index-answer-exit.
-    unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {}
+    /// Give back `ptr` only if it is the most recent allocation still held,
+    /// by moving the bump pointer back to its start. Anything else is kept:
+    /// see the module comment, nothing here outlives the component.
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let start = ptr as usize - self.memory.get() as usize;
+        let _ = self.next.compare_exchange(
+            start + layout.size(),
+            start,
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+        );
+    }
 }
 
 /// The heap bound shared by every component in a `heap`-enabled build.
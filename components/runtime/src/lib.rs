@@ -1,5 +1,7 @@
 #![no_std]
 
+#[cfg(feature = "growable-heap")]
+mod growable_heap;
 #[cfg(feature = "heap")]
 mod heap;
 mod sha256;
@@ -7,21 +9,29 @@ mod syscall;
 
 mod runtime;
 
+#[cfg(feature = "growable-heap")]
+pub use growable_heap::{GrowableHeap, heap_pages, heap_refusal};
 #[cfg(feature = "heap")]
 pub use heap::{BumpHeap, HEAP_BYTES, heap_used};
 pub use sha256::sha256;
 pub use syscall::{
-    BufferLoan, BufferOccupancy, CapabilityDisposition, DIRECTORY_ROOT_BYTES, ERR_BAD_CAP,
-    ERR_INVALID_ARG, ERR_OUT_OF_MEMORY, ERR_PEER_DEAD, ERR_SUCCESS, ERR_WOULDBLOCK, InputEvent,
-    InputKey, MAX_CAPS_PER_MSG, MAX_DIRECTORY_PATH, MAX_MSG, Rights, SharedBuffer, SlotOccupancy,
-    SpawnGrant, Termination, block_transact, block_transact_sector, block_transact_write,
-    boot_action, call, cap_drop, capability_delegate, capability_import, capability_slot_occupancy,
-    debug_write, directory_commit, directory_derive, directory_inspect, exit, graph_read,
-    graph_route_index, input_read, notification_poll, notification_signal, notification_wait, recv,
-    recv_blocking, reply, resolve_binding, send, shared_buffer_create, shared_buffer_loan,
-    shared_buffer_loan_map, shared_buffer_map, shared_buffer_occupancy, shared_buffer_release,
-    shared_buffer_return, shared_buffer_revoke, shared_buffer_seal, shared_buffer_unmap, spawn,
-    supervision_derive, supervision_status, try_send, unhealthy, yield_now,
+    BufferLoan, BufferOccupancy, CapabilityDisposition, ChildPlacement, DIRECTORY_ROOT_BYTES,
+    Delegation, ERR_BAD_CAP, ERR_INVALID_ARG, ERR_OUT_OF_MEMORY, ERR_PEER_DEAD, ERR_SUCCESS,
+    ERR_WOULDBLOCK, HeapRefusal, HeapWindow, InputEvent, InputKey, MAX_CAPS_PER_MSG,
+    MAX_DIRECTORY_PATH, MAX_MSG, MemoryOccupancy, Rights, SchedulingClass, SharedBuffer,
+    SlotOccupancy, SpawnGrant, Termination, block_transact, block_transact_sector,
+    block_transact_write, boot_action, call, cap_drop, capability_delegate,
+    capability_delegate_all, capability_import, capability_import_alongside,
+    capability_import_kind, capability_slot_occupancy, clock_advance, clock_now, clock_sleep_until,
+    clock_wait, debug_write, directory_commit, directory_derive, directory_inspect,
+    directory_restore, directory_snapshot, exit, graph_read, graph_route_index,
+    health_route_answered, heap_grow, input_read, memory_occupancy, notification_poll,
+    notification_signal, notification_wait, recv, recv_blocking, reply, resolve_binding, send,
+    shared_buffer_create, shared_buffer_loan, shared_buffer_loan_map, shared_buffer_map,
+    shared_buffer_occupancy, shared_buffer_release, shared_buffer_return, shared_buffer_revoke,
+    shared_buffer_seal, shared_buffer_unmap, spawn, spawn_in_class, supervision_derive,
+    supervision_kill, supervision_set_class, supervision_set_priority, supervision_status,
+    try_send, unhealthy, yield_now,
 };
 
 /// The CSpace slot holding this component's root service endpoint — its only
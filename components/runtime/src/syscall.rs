@@ -21,6 +21,18 @@ pub(crate) fn bind_startup_window(base: usize) -> i64 {
 pub(crate) fn early_debug_write(bytes: &[u8]) {
     sel4_transport::early_debug_write(bytes)
 }
+
+/// Block on the task's thread-lock Notification until a sibling signals it.
+#[cfg(feature = "growable-heap")]
+pub(crate) fn thread_lock_wait() {
+    sel4_transport::thread_lock_wait()
+}
+
+/// Wake the sibling blocked in [`thread_lock_wait`].
+#[cfg(feature = "growable-heap")]
+pub(crate) fn thread_lock_signal() {
+    sel4_transport::thread_lock_signal()
+}
 // B59: the operation labels, status codes, and message bounds are generated
 // from `contracts/syscall-abi/v1/schema.zt`. `slime-root` consumes the same
 // module, so a renumbering cannot desync the two crates -- which it has done
@@ -30,7 +42,8 @@ pub use slime_proto::syscall_abi::{
     MAX_CAPS_PER_MSG, MAX_MSG,
 };
 use slime_proto::syscall_abi::{
-    capability_table_labels, capability_transfer_labels, directory_labels, lifecycle_labels,
+    SCHEDULING_CLASS_BACKGROUND, SCHEDULING_CLASS_DECLARED, capability_table_labels,
+    capability_transfer_labels, clock_labels, directory_labels, lifecycle_labels, memory_labels,
     shared_buffer_labels, spawn_labels, supervision_labels,
 };
 
@@ -46,7 +59,7 @@ pub enum CapabilityDisposition {
 pub(crate) const MIN_TRANSFER_WINDOW: usize = 4096;
 
 /// A capability rights bitset. The vocabulary and bit numbering are generated
-/// from `contracts/generation/v5/schema.zt`; see `boot_contracts::generation`
+/// from `contracts/generation/v6/schema.zt`; see `boot_contracts::generation`
 /// for the named `RIGHT_*` constants (B57).
 pub type Rights = u64;
 
@@ -68,6 +81,32 @@ pub struct Spawned {
     pub supervision_slot: u32,
 }
 
+/// The scheduling class a child runs in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SchedulingClass {
+    /// The priorities its generation declares, or the one its supervisor set.
+    Declared,
+    /// Every thread at the lowest priority: runs only when nothing else can.
+    Background,
+}
+
+impl SchedulingClass {
+    const fn code(self) -> u64 {
+        match self {
+            Self::Declared => SCHEDULING_CLASS_DECLARED,
+            Self::Background => SCHEDULING_CLASS_BACKGROUND,
+        }
+    }
+}
+
+/// Where a child runs after a placement: its main thread's priority, and its
+/// class. In the background the priority is the one it returns to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChildPlacement {
+    pub priority: u32,
+    pub class: SchedulingClass,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Termination {
     Exit(i64),
@@ -194,7 +233,20 @@ pub fn exit(status: i64) -> ! {
 /// narrow copy; the source capability remains in the spawner. Success returns
 /// only an opaque supervision capability slot; task identity stays root-local.
 pub fn spawn(executable_slot: u32, grants: &[SpawnGrant]) -> Result<Spawned, i64> {
-    let (result, supervision_slot) = transport::spawn(executable_slot, grants);
+    spawn_in_class(executable_slot, grants, SchedulingClass::Declared)
+}
+
+/// [`spawn`], asking for the child to start in `class`.
+///
+/// Needs no scheduling authority, because a spawn can only lower: asking for
+/// the background gives time away, and asking for the declared class does not
+/// lift a placement a supervisor recorded for an earlier run of the child.
+pub fn spawn_in_class(
+    executable_slot: u32,
+    grants: &[SpawnGrant],
+    class: SchedulingClass,
+) -> Result<Spawned, i64> {
+    let (result, supervision_slot) = transport::spawn(executable_slot, grants, class.code());
     if result < 0 {
         Err(result)
     } else {
@@ -226,6 +278,28 @@ pub fn capability_delegate(
     )
 }
 
+/// One logical capability of several that cross in a single message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Delegation {
+    pub slot: u32,
+    pub disposition: CapabilityDisposition,
+    pub kind: u32,
+    pub rights: Rights,
+}
+
+/// [`capability_delegate`] for several logical capabilities and one
+/// descriptor: every export is finalized before the single send, so the
+/// receiver holds them all the moment it has the message, and nothing arrives
+/// as a second message another peer's could be taken for. Native Endpoints
+/// are refused here; they cross as kernel capabilities, one per delegate.
+pub fn capability_delegate_all(
+    endpoint_slot: u32,
+    delegations: &[Delegation],
+    descriptor: &[u8; 64],
+) -> i64 {
+    transport::capability_delegate_all(endpoint_slot, delegations, descriptor)
+}
+
 /// Claim the oldest root-recorded export addressed to this component.
 ///
 /// The counterpart to [`capability_delegate`] for every object kind that is
@@ -233,7 +307,19 @@ pub fn capability_delegate(
 /// message, so the descriptor arrives alone and this takes up the authority
 /// behind it. Returns the slot the capability landed in.
 pub fn capability_import() -> Result<u32, i64> {
-    transport::capability_import()
+    transport::capability_import(0, false)
+}
+
+/// [`capability_import`], taking only an export of `kind`.
+pub fn capability_import_kind(kind: u32) -> Result<u32, i64> {
+    transport::capability_import(kind, false)
+}
+
+/// [`capability_import_kind`], from the peer the previous import came from:
+/// the second capability of a [`capability_delegate_all`], never another
+/// peer's that happened to be older.
+pub fn capability_import_alongside(kind: u32) -> Result<u32, i64> {
+    transport::capability_import(kind, true)
 }
 
 /// A shared buffer allocated through a `SharedBufferFactory` capability: the
@@ -452,6 +538,52 @@ pub fn supervision_derive(slot: u32) -> Result<u32, i64> {
     }
 }
 
+/// Move the child `slot` supervises to main-thread priority `priority`.
+///
+/// Requires the `SchedulingControl` capability in `control_slot`. A priority
+/// that would run any of the child's threads above this component's declared
+/// `maxControlledPriority` is refused with `ERR_INVALID_ARG`. The placement
+/// holds for every later run of the child, so a restart does not undo it.
+pub fn supervision_set_priority(
+    slot: u32,
+    control_slot: u32,
+    priority: u32,
+) -> Result<ChildPlacement, i64> {
+    placement_of(transport::supervision_set_priority(
+        slot,
+        control_slot,
+        priority,
+    ))
+}
+
+/// Move the child `slot` supervises to `class`, under the same authority and
+/// bound as [`supervision_set_priority`].
+pub fn supervision_set_class(
+    slot: u32,
+    control_slot: u32,
+    class: SchedulingClass,
+) -> Result<ChildPlacement, i64> {
+    placement_of(transport::supervision_set_class(
+        slot,
+        control_slot,
+        class.code(),
+    ))
+}
+
+fn placement_of((result, class): (i64, u64)) -> Result<ChildPlacement, i64> {
+    if result < 0 {
+        return Err(result);
+    }
+    Ok(ChildPlacement {
+        priority: result as u32,
+        class: if class == SCHEDULING_CLASS_BACKGROUND {
+            SchedulingClass::Background
+        } else {
+            SchedulingClass::Declared
+        },
+    })
+}
+
 pub fn supervision_status(slot: u32) -> Result<Option<Termination>, i64> {
     let (kind, detail) = transport::supervision_status(slot);
     match kind {
@@ -465,12 +597,21 @@ pub fn supervision_status(slot: u32) -> Result<Option<Termination>, i64> {
     }
 }
 
+/// Stop the child `slot` supervises, wherever it is. The handle stays, and
+/// [`supervision_status`] then answers [`Termination::Timeout`]: a supervisor
+/// kills a child because it stopped waiting for it.
+pub fn supervision_kill(slot: u32) -> i64 {
+    transport::supervision_kill(slot)
+}
+
 /// Releases the capability in `slot`, revoking this task's ownership of it.
 pub fn cap_drop(slot: u32) -> i64 {
     transport::cap_drop(slot)
 }
 
-pub const MAX_DIRECTORY_PATH: usize = 48;
+/// The longest directory scope the root interns, and so the longest path an
+/// fs/v2 request can name: the root's own bound, not a runtime choice.
+pub const MAX_DIRECTORY_PATH: usize = 128;
 
 /// Returns the current immutable root and this capability's enforced scope.
 /// A namespace root identity: a SHA-256 over the directory object it names.
@@ -502,6 +643,18 @@ pub fn directory_derive(slot: u32, relative: &[u8], rights: u32) -> Result<u32,
     }
 }
 
+/// Pins `expected`, the capability's current root, as a read-only snapshot
+/// capability that keeps its scope. A root that moved since the caller read it
+/// returns `ERR_WOULDBLOCK`, so the pin is always the root the caller saw.
+pub fn directory_snapshot(slot: u32, expected: &[u8; 32], rights: u32) -> Result<u32, i64> {
+    let result = transport::directory_snapshot(slot, expected, rights);
+    if result < 0 {
+        Err(result)
+    } else {
+        Ok(result as u32)
+    }
+}
+
 /// Which of this component's own capability slots holds the binding `name`.
 ///
 /// CP2's replacement for compiling slot numbers in from a `build.rs`-generated
@@ -582,7 +735,18 @@ pub fn boot_action() -> Result<u32, i64> {
 /// Atomically swaps a directory namespace root after the new snapshot object
 /// has been committed. A stale expected root returns `ERR_WOULDBLOCK`.
 pub fn directory_commit(slot: u32, expected: &[u8; 32], new: &[u8; 32]) -> i64 {
-    transport::directory_commit(slot, expected, new)
+    transport::directory_commit(slot, expected, new, None)
+}
+
+/// [`directory_commit`] back to a pinned snapshot's root, proven by the
+/// unscoped snapshot capability at `snapshot_slot` that pins exactly `new`.
+pub fn directory_restore(
+    slot: u32,
+    snapshot_slot: u32,
+    expected: &[u8; 32],
+    new: &[u8; 32],
+) -> i64 {
+    transport::directory_commit(slot, expected, new, Some(snapshot_slot))
 }
 
 /// Reads one decoded keyboard event through an explicit input capability.
@@ -660,3 +824,154 @@ pub fn block_transact_write(
 pub fn unhealthy() -> ! {
     transport::unhealthy()
 }
+
+/// This component's heap window: where it starts and how many pages of it
+/// the root has backed so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeapWindow {
+    pub base: usize,
+    pub pages: usize,
+}
+
+/// Why a heap growth mapped nothing: the status the root answered, and the
+/// window as it stood when it refused.
+///
+/// `status` is `ERR_OUT_OF_MEMORY` both for a growth past `budget` and for a
+/// root that ran out of frames first; `pages + requested > budget` tells the
+/// two apart. A refusal is a reply like any other — the caller keeps running
+/// and decides what an allocation failure means for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeapRefusal {
+    pub status: i64,
+    /// Pages of the window backed when the root refused.
+    pub pages: usize,
+    /// The instance's declared `heapPages`: zero for one that declares none.
+    pub budget: usize,
+}
+
+/// Back `pages` more pages at the top of this component's heap window.
+///
+/// The pages are read-write, never executable, and arrive zeroed. Charged to
+/// the page budget the generation declares for this instance; a growth past it
+/// maps nothing and is refused with the budget attached, and an instance that
+/// declares no budget has none. `pages == 0` maps nothing and answers the
+/// window as it stands. Teardown reclaims every page with the task, so there
+/// is no release.
+pub fn heap_grow(pages: usize) -> Result<HeapWindow, HeapRefusal> {
+    let (base, packed) = transport::heap_grow(pages);
+    if base < 0 {
+        // Shifts mirror `pack_heap_refusal` in `slime-root/src/main.rs`.
+        let field = |shift: u32| ((packed >> shift) & 0xffff) as usize;
+        Err(HeapRefusal {
+            status: base,
+            pages: field(0),
+            budget: field(16),
+        })
+    } else {
+        Ok(HeapWindow {
+            base: base as usize,
+            pages: packed as usize,
+        })
+    }
+}
+
+/// This component's private memory, in pages: everything the root mapped for
+/// it alone, as against the shared buffers [`shared_buffer_occupancy`] counts.
+///
+/// `private` is the image, each thread's stack and IPC buffer, and the heap
+/// window's backed pages; `private_peak` is the root's high-water mark over
+/// it. `heap` and `heap_budget` are the growable half and the instance's
+/// declared `heapPages`, the only part of the total a running component can
+/// move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryOccupancy {
+    pub private: u32,
+    pub private_peak: u32,
+    pub heap: u32,
+    pub heap_budget: u32,
+}
+
+/// Read-only: this component's private-memory occupancy.
+///
+/// Self-scoped exactly as [`capability_slot_occupancy`] is. Unlike that query
+/// it does carry a budget, because `heapPages` is declared per instance: the
+/// caller is learning its own bound, not a graph-wide one.
+pub fn memory_occupancy() -> Result<MemoryOccupancy, i64> {
+    let (result, packed) = transport::memory_occupancy();
+    if result < 0 {
+        return Err(result);
+    }
+    // Shifts mirror `pack_memory_occupancy` in `slime-root/src/main.rs`.
+    let field = |shift: u32| ((packed >> shift) & 0xffff) as u32;
+    Ok(MemoryOccupancy {
+        private: field(0),
+        private_peak: field(16),
+        heap: field(32),
+        heap_budget: field(48),
+    })
+}
+
+/// Read the clock in `slot`, in nanoseconds.
+///
+/// The slot must hold a clock capability the generation granted; the root
+/// answers a time request through nothing else. Which epoch the reading counts
+/// from is the clock's kind: the counter's zero for a monotonic clock, the Unix
+/// epoch for a wall clock, and whatever its driver advanced it to for a
+/// simulated one. A wall clock the root could not anchor is `ERR_INVALID_ARG`.
+pub fn clock_now(slot: u32) -> Result<u64, i64> {
+    match transport::clock_now(slot) {
+        (ERR_SUCCESS, now_ns) => Ok(now_ns),
+        (error, _) => Err(error),
+    }
+}
+
+/// Ask whether the clock in `slot` has reached `deadline_ns`.
+///
+/// `Ok(Some(now))` once it has. `Ok(None)` records a wait and means ask again
+/// later; a newer deadline on the same clock replaces it. Wall clocks take no
+/// waits.
+pub fn clock_wait(slot: u32, deadline_ns: u64) -> Result<Option<u64>, i64> {
+    match transport::clock_wait(slot, deadline_ns) {
+        (ERR_SUCCESS, now_ns) => Ok(Some(now_ns)),
+        (ERR_WOULDBLOCK, _) => Ok(None),
+        (error, _) => Err(error),
+    }
+}
+
+/// Yield until the clock in `slot` reaches `deadline_ns`, and answer its
+/// reading then. A simulated clock returns only once something advances it.
+pub fn clock_sleep_until(slot: u32, deadline_ns: u64) -> Result<u64, i64> {
+    loop {
+        if let Some(now_ns) = clock_wait(slot, deadline_ns)? {
+            return Ok(now_ns);
+        }
+        yield_now();
+    }
+}
+
+/// Move the simulated clock in `slot` forward to `to_ns`, answering how many
+/// recorded waits that released.
+///
+/// Needs `clockAdvance`, which only a `simulatedClock` grant may carry. A
+/// reading behind the clock's own is `ERR_INVALID_ARG` and moves nothing.
+pub fn clock_advance(slot: u32, to_ns: u64) -> Result<usize, i64> {
+    let (result, _) = transport::clock_advance(slot, to_ns);
+    if result < 0 {
+        return Err(result);
+    }
+    Ok(result as usize)
+}
+
+/// Report that this broker carried a server's reply on the call route at
+/// `route_index`.
+///
+/// Credits the `HEALTH_PROBE_ROUTE` probe of every health record naming the
+/// route. Only the graph's declared fabric holder may report (`ERR_BAD_CAP`
+/// otherwise); a route no record names is `ERR_INVALID_ARG` and credits
+/// nothing. The index is the one [`graph_route_index`] resolves.
+pub fn health_route_answered(route_index: usize) -> Result<(), i64> {
+    match transport::health_route_answered(route_index) {
+        ERR_SUCCESS => Ok(()),
+        error => Err(error),
+    }
+}
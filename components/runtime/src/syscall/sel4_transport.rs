@@ -23,10 +23,10 @@ use super::wire::{
     frame_len, pack_bytes, slot_pair, slot_with_flag,
 };
 use super::{
-    CapabilityDisposition, ERR_INVALID_ARG, ERR_SUCCESS, ERR_WOULDBLOCK, MAX_CAPS_PER_MSG,
-    MAX_DIRECTORY_PATH, MAX_MSG, MIN_TRANSFER_WINDOW, SpawnGrant, capability_table_labels,
-    capability_transfer_labels, directory_labels, lifecycle_labels, shared_buffer_labels,
-    spawn_labels, supervision_labels,
+    CapabilityDisposition, Delegation, ERR_INVALID_ARG, ERR_SUCCESS, ERR_WOULDBLOCK,
+    MAX_CAPS_PER_MSG, MAX_DIRECTORY_PATH, MAX_MSG, MIN_TRANSFER_WINDOW, SpawnGrant,
+    capability_table_labels, capability_transfer_labels, clock_labels, directory_labels,
+    lifecycle_labels, memory_labels, shared_buffer_labels, spawn_labels, supervision_labels,
 };
 /// Bytes of a spawn grant record in the transfer window: slot word, then rights
 /// word. Generated from `contracts/syscall-abi/v1`; the root decodes the same
@@ -69,16 +69,27 @@ fn root_service() -> cap::Endpoint {
 /// Fixed child-CNode regions shared with `slime-root`'s native-capability ABI.
 const NATIVE_ENDPOINT_BASE: u32 = 33;
 const NATIVE_TRANSFER_ENDPOINT_BASE: u32 = 5;
+/// Each thread's Reply object under the MCS kernel profile (B48), one slot per
+/// thread directly below the console. `slime-root`'s
+/// `task::CHILD_SLOT_REPLY_BASE`; the product kernel leaves them empty, but
+/// they are outside the transfer region on both.
+const NATIVE_REPLY_BASE: u32 = 30;
 /// Marks a received Endpoint handle. The decoded slot is accepted only inside
 /// the dedicated transfer region, so callers cannot turn an arbitrary CPtr
 /// into endpoint authority by setting the tag.
 const TRANSFERRED_ENDPOINT_HANDLE_TAG: u32 = 1 << 31;
 const TRANSFERRED_ENDPOINT_HANDLE_BASE: u32 = NATIVE_TRANSFER_ENDPOINT_BASE;
-const TRANSFERRED_ENDPOINT_HANDLE_LIMIT: u32 = NATIVE_ENDPOINT_BASE;
+const TRANSFERRED_ENDPOINT_HANDLE_LIMIT: u32 = NATIVE_REPLY_BASE;
 const NATIVE_NOTIFICATION_BASE: u32 = 64;
 const NATIVE_TOKEN_BASE: u32 = 95;
 const NATIVE_REGION_SLOTS: u32 = 31;
 const NATIVE_RECEIVE_SLOT: u32 = 127;
+/// The task's thread-lock Notification, `slime-root`'s
+/// `task::CHILD_SLOT_THREAD_LOCK`: installed for every task on both kernels and
+/// named by no plan, so it is reached only through [`thread_lock_wait`] and
+/// [`thread_lock_signal`].
+#[cfg(feature = "growable-heap")]
+const THREAD_LOCK_SLOT: sel4::CPtrBits = 126;
 const CHILD_CNODE_SLOT: u32 = 4;
 const CHILD_CNODE_SIZE_BITS: usize = 7;
 fn native_endpoint(slot: u32) -> Result<cap::Endpoint, i64> {
@@ -168,6 +179,9 @@ pub fn call_endpoint(slot: u32, payload: &[u8], reply: &mut [u8; MAX_MSG]) -> i6
 /// last receive took a message from. That is one outstanding request per
 /// thread, which is the discipline these single-threaded components already
 /// have. It cannot block -- the caller is already waiting in `seL4_Call`.
+///
+/// Under MCS the authority is the Reply object the root installed for the
+/// main thread, which the receive named. Same discipline, made explicit.
 pub fn reply_to_caller(payload: &[u8]) -> i64 {
     if payload.len() > MAX_MSG {
         return ERR_INVALID_ARG;
@@ -178,11 +192,39 @@ pub fn reply_to_caller(payload: &[u8]) -> i64 {
             .label(payload.len() as Word)
             .length(payload.len().div_ceil(core::mem::size_of::<Word>()))
             .build();
-        sel4::reply(ipc_buffer, info);
+        send_reply(ipc_buffer, info)?;
         Ok(ERR_SUCCESS)
     })
 }
 
+/// Reply through this thread's implicit reply capability.
+#[sel4::sel4_cfg(not(KERNEL_MCS))]
+fn send_reply(ipc_buffer: &mut sel4::IpcBuffer, info: MessageInfo) -> Result<(), i64> {
+    sel4::reply(ipc_buffer, info);
+    Ok(())
+}
+
+/// Reply through this thread's Reply object.
+#[sel4::sel4_cfg(KERNEL_MCS)]
+fn send_reply(ipc_buffer: &mut sel4::IpcBuffer, info: MessageInfo) -> Result<(), i64> {
+    reply_authority()?.with(ipc_buffer).send(info);
+    Ok(())
+}
+
+/// What a receive on this thread names to hold its caller's reply.
+#[sel4::sel4_cfg(not(KERNEL_MCS))]
+fn reply_authority() -> Result<(), i64> {
+    Ok(())
+}
+
+/// This thread's own Reply object. A Reply is bound by the receive that
+/// names it, so two threads sharing one would lose each other's callers.
+#[sel4::sel4_cfg(KERNEL_MCS)]
+fn reply_authority() -> Result<cap::Reply, i64> {
+    let slot = NATIVE_REPLY_BASE + crate::runtime::thread_index() as u32;
+    Ok(cap::Reply::from_bits(slot as sel4::CPtrBits))
+}
+
 /// Best-effort send: deliver only if a receiver is already blocked on the
 /// endpoint, otherwise discard. See [`crate::syscall::try_send`].
 pub fn try_send(slot: u32, payload: &[u8], caps: &[u32]) -> i64 {
@@ -236,6 +278,10 @@ fn receive_native(
         Ok(endpoint) => endpoint,
         Err(error) => return error,
     };
+    let reply = match reply_authority() {
+        Ok(reply) => reply,
+        Err(error) => return error,
+    };
     if RECEIVE_SLOT_LIVE
         .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
         .is_err()
@@ -246,9 +292,9 @@ fn receive_native(
         clear_unnamed_slots(cap_out.as_mut_slice(), 0);
         ipc_buffer.set_recv_slot(&native_receive_slot());
         let (info, _) = if blocking {
-            endpoint.with(&mut *ipc_buffer).recv(())
+            endpoint.with(&mut *ipc_buffer).recv(reply)
         } else {
-            endpoint.with(&mut *ipc_buffer).nb_recv(())
+            endpoint.with(&mut *ipc_buffer).nb_recv(reply)
         };
         // An empty `nb_recv` is identified by carrying no words and no
         // capabilities. The label is *not* part of the test: seL4 leaves MR0
@@ -277,13 +323,17 @@ fn receive_native(
         // single-entry guard on the one receive slot, and a path that returns
         // while it is still set makes every later receive on this thread answer
         // `ERR_WOULDBLOCK` forever.
-        let destination_slot = (NATIVE_TRANSFER_ENDPOINT_BASE..NATIVE_ENDPOINT_BASE).find(|slot| {
-            let probe = cap::CNode::from_bits(CHILD_CNODE_SLOT as u64)
-                .absolute_cptr_from_bits_with_depth(*slot as sel4::CPtrBits, CHILD_CNODE_SIZE_BITS)
-                .with(&mut *ipc_buffer);
-            let source = native_receive_slot();
-            probe.move_(&source).is_ok()
-        });
+        let destination_slot =
+            (TRANSFERRED_ENDPOINT_HANDLE_BASE..TRANSFERRED_ENDPOINT_HANDLE_LIMIT).find(|slot| {
+                let probe = cap::CNode::from_bits(CHILD_CNODE_SLOT as u64)
+                    .absolute_cptr_from_bits_with_depth(
+                        *slot as sel4::CPtrBits,
+                        CHILD_CNODE_SIZE_BITS,
+                    )
+                    .with(&mut *ipc_buffer);
+                let source = native_receive_slot();
+                probe.move_(&source).is_ok()
+            });
         let Some(destination_slot) = destination_slot else {
             RECEIVE_SLOT_LIVE.store(false, Ordering::Release);
             return Err(super::ERR_BAD_CAP);
@@ -664,6 +714,31 @@ pub fn notification_poll(slot: u32) -> Result<Option<u64>, i64> {
     Ok((badge != 0).then_some(badge))
 }
 
+/// Block until a sibling thread releases the lock this one is waiting for.
+#[cfg(feature = "growable-heap")]
+pub fn thread_lock_wait() {
+    let notification = cap::Notification::from_bits(THREAD_LOCK_SLOT);
+    if uses_ambient_buffer() {
+        notification.wait();
+    } else {
+        // SAFETY: this thread's own buffer, borrowed for one invocation.
+        notification.with(unsafe { thread_context() }).wait();
+    }
+}
+
+/// Wake the sibling thread blocked in [`thread_lock_wait`], or let its next
+/// wait return at once if it has not blocked yet.
+#[cfg(feature = "growable-heap")]
+pub fn thread_lock_signal() {
+    let notification = cap::Notification::from_bits(THREAD_LOCK_SLOT);
+    if uses_ambient_buffer() {
+        notification.signal();
+    } else {
+        // SAFETY: this thread's own buffer, borrowed for one invocation.
+        notification.with(unsafe { thread_context() }).signal();
+    }
+}
+
 fn native_notification(slot: u32) -> Result<cap::Notification, i64> {
     let absolute = NATIVE_NOTIFICATION_BASE
         .checked_add(slot)
@@ -684,7 +759,7 @@ pub fn exit(status: i64) -> ! {
 /// spawn's grant array is checked against server-side.
 const MAX_SPAWN_GRANTS: usize = 64;
 
-pub fn spawn(executable_slot: u32, grants: &[SpawnGrant]) -> (i64, u64) {
+pub fn spawn(executable_slot: u32, grants: &[SpawnGrant], class: u64) -> (i64, u64) {
     let mut encoded = [0u8; MAX_SPAWN_GRANTS * GRANT_RECORD_BYTES];
     let Some(frame) = encoded.get_mut(..grants.len() * GRANT_RECORD_BYTES) else {
         return (ERR_INVALID_ARG, 0);
@@ -700,7 +775,9 @@ pub fn spawn(executable_slot: u32, grants: &[SpawnGrant]) -> (i64, u64) {
         Ok(transfer) => transfer,
         Err(error) => return (error, 0),
     };
-    let (operands, used) = payload_operands(executable_slot as u64, transfer, bytes);
+    // The requested class rides in the high half of the slot word.
+    let (operands, used) =
+        payload_operands(u64::from(executable_slot) | (class << 32), transfer, bytes);
     pair_of(spawn_labels::SPAWN, &operands[..used])
 }
 
@@ -816,6 +893,70 @@ pub fn capability_delegate(
     )
 }
 
+/// Export every delegation, finalize them all, then send `descriptor` once.
+///
+/// An export that fails cancels the ones before it, which are not yet
+/// finalized, so a refused batch leaves the receiver nothing to claim.
+/// Finalizing one's own export cannot fail, so no half-finalized batch exists.
+pub fn capability_delegate_all(
+    endpoint_slot: u32,
+    delegations: &[Delegation],
+    descriptor: &[u8; 64],
+) -> i64 {
+    let endpoint = match native_endpoint(endpoint_slot) {
+        Ok(endpoint) => endpoint,
+        Err(error) => return error,
+    };
+    if delegations.is_empty()
+        || delegations.len() > MAX_CAPS_PER_MSG
+        || delegations
+            .iter()
+            .any(|delegation| delegation.rights == 0 || delegation.kind == 1)
+    {
+        return ERR_INVALID_ARG;
+    }
+    let transfer = match stage(descriptor.as_slice(), &[]) {
+        Ok(transfer) => transfer,
+        Err(error) => return error,
+    };
+    let mut exported = [0u32; MAX_CAPS_PER_MSG];
+    for (index, delegation) in delegations.iter().enumerate() {
+        let disposition_word = match delegation.disposition {
+            CapabilityDisposition::Move => 0u64,
+            CapabilityDisposition::Retain => 1u64,
+        };
+        let (result, _) = pair_of(
+            capability_transfer_labels::EXPORT,
+            &[
+                slot_pair(endpoint_slot, delegation.slot) as Word,
+                (u64::from(delegation.kind) | (disposition_word << 32)) as Word,
+                transfer as Word,
+                delegation.rights as Word,
+            ],
+        );
+        match u32::try_from(result) {
+            Ok(id) if result > 0 => exported[index] = id,
+            _ => {
+                for id in &exported[..index] {
+                    let _ = result_of(capability_transfer_labels::EXPORT_CANCEL, &[*id as Word]);
+                }
+                return if result < 0 { result } else { ERR_INVALID_ARG };
+            }
+        }
+    }
+    for id in &exported[..delegations.len()] {
+        let finalized = result_of(capability_transfer_labels::EXPORT_FINALIZE, &[*id as Word]);
+        if finalized != ERR_SUCCESS {
+            return finalized;
+        }
+    }
+    with_thread_buffer(|ipc_buffer| {
+        stage_native_message(ipc_buffer, descriptor.as_slice(), &[])?;
+        send_staged_native(endpoint, ipc_buffer, descriptor.len(), 0);
+        Ok(ERR_SUCCESS)
+    })
+}
+
 /// Claim the oldest root-side export addressed to this component, installing
 /// it into a free capability slot and returning that slot.
 ///
@@ -823,8 +964,12 @@ pub fn capability_delegate(
 /// capability in the message. Every other kind is a root-owned logical
 /// capability with no kernel object the peer could hold, so the descriptor
 /// arrives alone and this is how the authority behind it is taken up.
-pub fn capability_import() -> Result<u32, i64> {
-    let slot = result_of(capability_transfer_labels::IMPORT, &[0]);
+///
+/// `kind` narrows the claim to one object kind (zero for any), and `alongside`
+/// to the sender the previous import came from.
+pub fn capability_import(kind: u32, alongside: bool) -> Result<u32, i64> {
+    let filter = u64::from(kind) | (u64::from(alongside) << 32);
+    let slot = result_of(capability_transfer_labels::IMPORT, &[0, filter as Word]);
     if slot < 0 {
         return Err(slot);
     }
@@ -926,6 +1071,24 @@ pub fn supervision_derive(slot: u32) -> (i64, u64) {
     pair_of(supervision_labels::DERIVE, &[slot as Word])
 }
 
+pub fn supervision_kill(slot: u32) -> i64 {
+    result_of(supervision_labels::KILL, &[slot as Word])
+}
+
+pub fn supervision_set_priority(slot: u32, control_slot: u32, priority: u32) -> (i64, u64) {
+    pair_of(
+        supervision_labels::SET_PRIORITY,
+        &[slot as Word, control_slot as Word, priority as Word],
+    )
+}
+
+pub fn supervision_set_class(slot: u32, control_slot: u32, class: u64) -> (i64, u64) {
+    pair_of(
+        supervision_labels::SET_CLASS,
+        &[slot as Word, control_slot as Word, class],
+    )
+}
+
 /// Query this component's own live child-CSpace slot occupancy (C8.13.3).
 ///
 /// Same shape as `shared_buffer_occupancy` above and for the same reason: the
@@ -1008,6 +1171,15 @@ pub fn directory_derive(slot: u32, relative: &[u8], rights: u32) -> i64 {
     result_of(directory_labels::DERIVE, &operands[..used])
 }
 
+pub fn directory_snapshot(slot: u32, expected: &[u8; DIRECTORY_ROOT_BYTES], rights: u32) -> i64 {
+    let transfer = match stage(expected, &[]) {
+        Ok(transfer) => transfer,
+        Err(error) => return error,
+    };
+    let (operands, used) = payload_operands(slot_pair(slot, rights), transfer, expected);
+    result_of(directory_labels::SNAPSHOT, &operands[..used])
+}
+
 /// CP2: ask the root which of this component's own slots holds `name`.
 ///
 /// The name travels through the transfer window like every other variable-length
@@ -1080,7 +1252,12 @@ pub fn boot_action() -> i64 {
     result_of(capability_table_labels::BOOT_ACTION, &[0])
 }
 
-pub fn directory_commit(slot: u32, expected: &[u8; 32], new: &[u8; 32]) -> i64 {
+pub fn directory_commit(
+    slot: u32,
+    expected: &[u8; 32],
+    new: &[u8; 32],
+    snapshot_slot: Option<u32>,
+) -> i64 {
     let mut frame = [0u8; 64];
     frame[..32].copy_from_slice(expected);
     frame[32..].copy_from_slice(new);
@@ -1091,7 +1268,11 @@ pub fn directory_commit(slot: u32, expected: &[u8; 32], new: &[u8; 32]) -> i64 {
     match outcome(&call_on(
         console_service(),
         CONSOLE_LABEL_DIRECTORY_COMMIT,
-        &[slot as Word, transfer as Word],
+        &[
+            slot as Word,
+            transfer as Word,
+            snapshot_slot.map_or(0, |snapshot| Word::from(snapshot) + 1),
+        ],
     )) {
         Ok((result, _)) => result,
         Err(error) => error,
@@ -1246,6 +1427,30 @@ pub fn block_transact_write(
     }
 }
 
+pub fn health_route_answered(route_index: usize) -> i64 {
+    result_of(lifecycle_labels::ROUTE_ANSWERED, &[route_index as Word])
+}
+
+pub fn heap_grow(pages: usize) -> (i64, u64) {
+    pair_of(memory_labels::HEAP_GROW, &[pages as Word])
+}
+
+pub fn memory_occupancy() -> (i64, u64) {
+    pair_of(memory_labels::OCCUPANCY, &[0])
+}
+
+pub fn clock_now(slot: u32) -> (i64, u64) {
+    pair_of(clock_labels::NOW, &[slot as Word])
+}
+
+pub fn clock_wait(slot: u32, deadline_ns: u64) -> (i64, u64) {
+    pair_of(clock_labels::WAIT, &[slot as Word, deadline_ns as Word])
+}
+
+pub fn clock_advance(slot: u32, to_ns: u64) -> (i64, u64) {
+    pair_of(clock_labels::ADVANCE, &[slot as Word, to_ns as Word])
+}
+
 pub fn unhealthy() -> ! {
     let _ = call(lifecycle_labels::UNHEALTHY, &[]);
     // Exit after recording the unhealthy transition so this diverging API
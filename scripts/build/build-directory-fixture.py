@@ -15,22 +15,26 @@ from harness import load_script
 
 store = load_script("build_store_fixture", "build/build-store-fixture.py")
 
+# The fixture tree is written in the retained v1 snapshot layout on purpose:
+# the filesystem service writes only fs/v2 directories, so booting from a v1
+# root is what keeps its v1 read path exercised. The first write through a
+# directory replaces it with a v2 head and pages.
 from fs_contracts import (
-    SNAPSHOT_BYTES,
-    SNAPSHOT_COUNT_OFFSET,
-    SNAPSHOT_ENTRY_BYTES as ENTRY_BYTES,
-    SNAPSHOT_ENTRY_HASH_END,
-    SNAPSHOT_ENTRY_HASH_OFFSET,
-    SNAPSHOT_ENTRY_KIND_OFFSET,
-    SNAPSHOT_ENTRY_NAME_LEN_OFFSET,
-    SNAPSHOT_ENTRY_NAME_OFFSET,
-    SNAPSHOT_ENTRY_OBJECT_TYPE_OFFSET,
-    SNAPSHOT_ENTRY_PAYLOAD_LEN_OFFSET,
-    SNAPSHOT_HEADER,
-    SNAPSHOT_MAGIC,
     SNAPSHOT_OBJECT_TYPE as SNAPSHOT_TYPE,
-    SNAPSHOT_VERSION,
-    SNAPSHOT_VERSION_OFFSET,
+    V1_SNAPSHOT_BYTES as SNAPSHOT_BYTES,
+    V1_SNAPSHOT_COUNT_OFFSET as SNAPSHOT_COUNT_OFFSET,
+    V1_SNAPSHOT_ENTRY_BYTES as ENTRY_BYTES,
+    V1_SNAPSHOT_ENTRY_HASH_END as SNAPSHOT_ENTRY_HASH_END,
+    V1_SNAPSHOT_ENTRY_HASH_OFFSET as SNAPSHOT_ENTRY_HASH_OFFSET,
+    V1_SNAPSHOT_ENTRY_KIND_OFFSET as SNAPSHOT_ENTRY_KIND_OFFSET,
+    V1_SNAPSHOT_ENTRY_NAME_LEN_OFFSET as SNAPSHOT_ENTRY_NAME_LEN_OFFSET,
+    V1_SNAPSHOT_ENTRY_NAME_OFFSET as SNAPSHOT_ENTRY_NAME_OFFSET,
+    V1_SNAPSHOT_ENTRY_OBJECT_TYPE_OFFSET as SNAPSHOT_ENTRY_OBJECT_TYPE_OFFSET,
+    V1_SNAPSHOT_ENTRY_PAYLOAD_LEN_OFFSET as SNAPSHOT_ENTRY_PAYLOAD_LEN_OFFSET,
+    V1_SNAPSHOT_HEADER as SNAPSHOT_HEADER,
+    V1_SNAPSHOT_VERSION as SNAPSHOT_VERSION,
+    V1_SNAPSHOT_VERSION_OFFSET as SNAPSHOT_VERSION_OFFSET,
+    DIRECTORY_MAGIC as SNAPSHOT_MAGIC,
 )
 
 PAYLOAD_TYPE = 7
@@ -71,21 +75,16 @@ def build() -> tuple[bytearray, bytes]:
     )
     root_hash = hashlib.sha256(root).digest()
 
-    records = [
-        store.record(PAYLOAD_TYPE, PAYLOAD),
-        store.record(SNAPSHOT_TYPE, docs),
-        store.record(SNAPSHOT_TYPE, root),
-    ]
+    objects = [(PAYLOAD_TYPE, PAYLOAD), (SNAPSHOT_TYPE, docs), (SNAPSHOT_TYPE, root)]
+    entries = []
     cursor = store.RECORD_AREA_START
-    for record in records:
+    for obj_type, payload in objects:
+        record = store.record(obj_type, payload, 2)
         store.place(image, store.STORE_FIRST + cursor, record)
+        entries.append((obj_type, payload, cursor))
         cursor += len(record) // store.SECTOR
-    store.place(image, store.STORE_FIRST, store.superblock(2, cursor, len(records)))
-    store.place(
-        image,
-        store.STORE_FIRST + 1,
-        store.superblock(1, store.RECORD_AREA_START, 0),
-    )
+    store.commit(image, 0, 2, cursor, entries)
+    store.commit(image, 1, 1, store.RECORD_AREA_START, [])
     return image, root_hash
 
 
@@ -28,6 +28,8 @@ from boot_contracts import (
     BOOTSTATE_PENDING_END,
     BOOTSTATE_PENDING_OFFSET,
     BOOTSTATE_REMAINING_ATTEMPTS_OFFSET,
+    BOOTSTATE_ROLLBACK_STATE_ROOT_END,
+    BOOTSTATE_ROLLBACK_STATE_ROOT_OFFSET,
     BOOTSTATE_SLOT_BYTES,
     BOOTSTATE_STATE_ROOT_END,
     BOOTSTATE_STATE_ROOT_OFFSET,
@@ -35,6 +37,7 @@ from boot_contracts import (
     BOOTSTORE_CAPACITY,
     BOOTSTORE_DIRECTORY_OFFSET,
     BOOTSTORE_ENTRY,
+    BOOTSTORE_EVIDENCE_OFFSET,
     BOOTSTORE_GENERATIONS_OFFSET,
     BOOTSTORE_RELEASES_OFFSET,
     BOOTSTORE_HEADER,
@@ -108,6 +111,9 @@ from boot_contracts import (
     GENERATION_GRANT,
     GENERATION_HEADER,
     GENERATION_HEALTH,
+    GENERATION_HEALTH_PROBE_NO_FAULT,
+    GENERATION_HEALTH_PROBE_ROUTE,
+    GENERATION_HEALTH_PROBE_RUNNING,
     GENERATION_MAGIC,
     GENERATION_OBJECT,
     GENERATION_RIGHT_ALL,
@@ -169,9 +175,15 @@ from boot_contracts import (
     NORMALIZED_SCHEMAS_HEADER_BYTES,
     NORMALIZED_SCHEMAS_MAGIC,
     NORMALIZED_SCHEMAS_VERSION,
+    SHADOW_EVIDENCE,
+    SHADOW_EVIDENCE_CHECKSUM_END,
+    SHADOW_EVIDENCE_CHECKSUM_OFFSET,
+    SHADOW_EVIDENCE_MAGIC,
+    SHADOW_EVIDENCE_VERSION,
     bootstate_checksum,
     bootstore_checksum,
     generation_identity,
+    shadow_evidence_checksum,
     sha256,
 )
 from boot_layout import build_boot_layout, layout_from_manifest
@@ -254,6 +266,14 @@ SEL4_MANIFESTS = {
     / "v1"
     / "fixtures"
     / "sel4-call.zti",
+    # The call plane with its server required and probed: running, a fault
+    # window, and the `parameters` route answered through the broker.
+    "sel4-health": ROOT
+    / "contracts"
+    / "generation"
+    / "v1"
+    / "fixtures"
+    / "sel4-health.zti",
     "sel4-qos": ROOT
     / "contracts"
     / "generation"
@@ -370,7 +390,25 @@ SEL4_MANIFESTS = {
     / "v1"
     / "fixtures"
     / "sel4-transfer.zti",
+    # B48's MCS half: the only manifest declaring reservations, so the only
+    # one that builds solely for the MCS kernel profile.
+    "sel4-isolation": ROOT
+    / "contracts"
+    / "generation"
+    / "v1"
+    / "fixtures"
+    / "sel4-isolation.zti",
+    "sel4-clock": ROOT
+    / "contracts"
+    / "generation"
+    / "v1"
+    / "fixtures"
+    / "sel4-clock.zti",
 }
+# Manifests that declare `budgetUs`/`periodUs` and so build only with
+# `SLIME_SEL4_KERNEL_MCS=1`. A check that builds every seL4 manifest selects the
+# profile from this rather than learning it from a refusal.
+SEL4_MCS_MANIFESTS = frozenset({"sel4-isolation"})
 COMPONENTS_TARGET_DIR = Path(
     os.environ.get("CARGO_TARGET_DIR") or ROOT / "target" / "components"
 )
@@ -378,7 +416,7 @@ PAGE_SIZE = 4096
 KIND = {"kernel": 1, "bootstrap": 2, "component": 3, "resource": 4}
 ROLE = {"init": 1, "service": 2, "driver": 3, "application": 4}
 # Rights numbering is generated-contract truth. Both the manifest-spelling table
-# and `RIGHT_ALL` come from `contracts/generation/v5/schema.zt` via
+# and `RIGHT_ALL` come from `contracts/generation/v6/schema.zt` via
 # `boot_contracts`, so the builder, the root, and the oracle cannot disagree
 # about which bit a right is or which bits exist. `RIGHT_ALL` is the union of
 # the named bits rather than a bit-width mask, which is what closes B57's hole
@@ -397,6 +435,10 @@ CAPABILITY_KIND = {
     "supervision": 7,
     "sharedBuffer": 8,
     "loan": 9,
+    "schedulingControl": 10,
+    "monotonicClock": 11,
+    "wallClock": 12,
+    "simulatedClock": 13,
 }
 
 
@@ -497,6 +539,10 @@ def validate_capability_rights(name: str, kind: str, rights: int) -> None:
             RIGHT["bufferWrite"] | RIGHT["bufferMap"] | RIGHT["bufferLoan"] | RIGHT_TRANSFER
         ),
         "loan": RIGHT["bufferWrite"] | RIGHT["bufferMap"] | RIGHT_TRANSFER,
+        "schedulingControl": RIGHT["schedule"],
+        "monotonicClock": RIGHT["clockRead"],
+        "wallClock": RIGHT["clockRead"],
+        "simulatedClock": RIGHT["clockRead"] | RIGHT["clockAdvance"],
     }
     required = {
         "endpoint": RIGHT["send"] | RIGHT["recv"],
@@ -513,6 +559,10 @@ def validate_capability_rights(name: str, kind: str, rights: int) -> None:
         "supervision": RIGHT["supervise"],
         "sharedBuffer": RIGHT["bufferWrite"] | RIGHT["bufferMap"] | RIGHT["bufferLoan"],
         "loan": RIGHT["bufferMap"],
+        "schedulingControl": RIGHT["schedule"],
+        "monotonicClock": RIGHT["clockRead"],
+        "wallClock": RIGHT["clockRead"],
+        "simulatedClock": RIGHT["clockRead"],
     }
     mask = masks.get(kind)
     if mask is None:
@@ -525,7 +575,12 @@ def validate_capability_rights(name: str, kind: str, rights: int) -> None:
         fail(f"{name}: executable capability requires exec and spawn")
     if kind == "input" and rights != RIGHT["inputRead"]:
         fail(f"{name}: input capability has an exact inputRead right")
+    if kind == "schedulingControl" and rights != RIGHT["schedule"]:
+        fail(f"{name}: schedulingControl capability has an exact schedule right")
 MAX_SPAWN_BUDGET = 32
+# The root's heap window: one task arena records at most this many growth
+# frames beside the image's (`slime-root/src/child_vspace.rs`).
+MAX_HEAP_PAGES = 256
 POLICY = {
     "immutable": 1,
     "ephemeral": 2,
@@ -2182,6 +2237,15 @@ def cargo_target_directory_name(target_profile: TargetProfile) -> str:
     return target_profile.cargo_target
 
 
+def kernel_mcs() -> bool:
+    """Whether this generation is built for the alternate MCS kernel profile.
+
+    Set by `build-sel4.py --mcs`. It is the only thing that admits a budget
+    and a period, because only that kernel enforces them.
+    """
+    return os.environ.get("SLIME_SEL4_KERNEL_MCS") == "1"
+
+
 def sel4_component_environment(environment: dict[str, str]) -> dict[str, str]:
     """Add what a `slime-components` build for the seL4 profile needs.
 
@@ -2199,7 +2263,9 @@ def sel4_component_environment(environment: dict[str, str]) -> dict[str, str]:
 
     pins = tomllib.loads(pins_path.read_text(encoding="utf-8"))
     environment["RUSTUP_TOOLCHAIN"] = pins["rust_sel4"]["toolchain"]
-    prefix = ROOT / "build" / "sel4-prefix"
+    # The MCS profile installs its own prefix (`build-sel4.py --mcs`), and a
+    # component built against the other kernel's bindings would not run.
+    prefix = ROOT / "build" / ("sel4-mcs-prefix" if kernel_mcs() else "sel4-prefix")
     if not (prefix / "libsel4" / "include" / "kernel" / "gen_config.json").is_file():
         fail(
             f"no installed seL4 prefix at {prefix.relative_to(ROOT)}; "
@@ -2250,6 +2316,10 @@ def build_rust_components(
         environment["SLIME_FABRIC_STREAM_EARLY_EXIT"] = "1"
     else:
         environment.pop("SLIME_FABRIC_STREAM_EARLY_EXIT", None)
+    if environment.get("SLIME_FABRIC_CALL_SERVER_EARLY_EXIT") == "1":
+        environment["SLIME_FABRIC_CALL_SERVER_EARLY_EXIT"] = "1"
+    else:
+        environment.pop("SLIME_FABRIC_CALL_SERVER_EARLY_EXIT", None)
     if recovery:
         environment["SLIME_RECOVERY_IMAGE"] = "1"
     if environment.get("SLIME_GENERATION_CMD_CHECK") == "1" and candidate_identity is not None:
@@ -2267,6 +2337,8 @@ def build_rust_components(
         target_name = f"generation-{generation_number}-transfer-activate"
     else:
         target_name = f"generation-{generation_number}"
+    if kernel_mcs():
+        target_name = f"{target_name}-mcs"
     target_dir = component_target_dir(COMPONENTS_TARGET_DIR, target_profile, target_name)
     environment["CARGO_TARGET_DIR"] = str(target_dir)
     command = [
@@ -2302,6 +2374,11 @@ def build_rust_components(
             )
         ):
             features.append("store")
+        # The isolation plane's control loop allocates from two threads at
+        # different priorities, which is what the growable heap's blocking
+        # lock is for. It never shares a build with the store plane.
+        elif components is not None and "isolation-control-loop" in components:
+            features.append("growable-heap")
         command += ["--no-default-features", "--features", ",".join(features)]
         # Build exactly the binaries this generation declares, rather than every
         # binary in the crate. The fabric components are compiled against a
@@ -2507,6 +2584,12 @@ def validate_acyclic(instances: list[dict]) -> None:
 
 
 PLAN_NONE = 0xFFFFFFFF
+# A thread's CPU reservation bounds, matching `slime-root`'s
+# `schedule::MIN_BUDGET_US` and `schedule::MAX_PERIOD_US`. The floor is well
+# above the kernel's own minimum. A budget that cannot cover one IPC round
+# trip is refused here, not discovered as a thread that never finishes one.
+MIN_BUDGET_US = 100
+MAX_PERIOD_US = 10_000_000
 # One below the root task's own priority, matching `slime-root`'s
 # `task::CHILD_PRIORITY`. A child at or above the root cannot be preempted by
 # the service loop, so this is a ceiling as well as a default.
@@ -2546,6 +2629,10 @@ SERVICE_BY_CAPABILITY_KIND = {
     "input": SERVICE_INPUT,
     "block": SERVICE_BLOCK,
     "supervision": SERVICE_SUPERVISION,
+    "schedulingControl": SERVICE_SUPERVISION,
+    "monotonicClock": SERVICE_LIFECYCLE,
+    "wallClock": SERVICE_LIFECYCLE,
+    "simulatedClock": SERVICE_LIFECYCLE,
 }
 KERNEL_OBJECT_CNODE = 1
 KERNEL_OBJECT_VSPACE = 2
@@ -2716,9 +2803,8 @@ def build_sel4_plan(
         #
         # Bounded here as well as in the root, because a manifest is the wrong
         # place to learn that a number was silently clamped. `budget_us` and
-        # `period_us` stay zero until MCS is admitted -- seL4 without it has no
-        # notion of either, and writing a figure the kernel cannot enforce
-        # would make the record say more than the system does.
+        # `period_us` are written only for the MCS kernel profile; see
+        # `budgetUs` below.
         priority = instance.get("priority", DEFAULT_CHILD_PRIORITY)
         if not isinstance(priority, int) or isinstance(priority, bool):
             fail(f"instance {name}: invalid priority")
@@ -2727,6 +2813,21 @@ def build_sel4_plan(
                 f"instance {name}: priority {priority} outside 0..={DEFAULT_CHILD_PRIORITY}; "
                 "a child at or above the root's priority can stall the service loop"
             )
+        # The ceiling this instance's main thread may assign to another
+        # thread: the `maxControlledPriority` a `schedulingControl` capability
+        # is exercised under. Absent, it is the instance's own priority, so
+        # holding the capability lets a supervisor demote a child and raise it
+        # no higher than itself. Never below `priority` -- seL4 refuses a
+        # thread whose priority exceeds its own controlled ceiling -- and
+        # bounded by the root's default for the reason `priority` is.
+        max_controlled_priority = instance.get("maxControlledPriority", priority)
+        if not isinstance(max_controlled_priority, int) or isinstance(max_controlled_priority, bool):
+            fail(f"instance {name}: invalid maxControlledPriority")
+        if not priority <= max_controlled_priority <= DEFAULT_CHILD_PRIORITY:
+            fail(
+                f"instance {name}: maxControlledPriority {max_controlled_priority} outside "
+                f"{priority}..={DEFAULT_CHILD_PRIORITY}"
+            )
         # A worker's priority is its own to declare, defaulting to its main
         # thread's (B48). Declaring it *below* the main thread is the case that
         # matters: it lets one component hold a busy thread without stalling
@@ -2740,15 +2841,37 @@ def build_sel4_plan(
                 f"instance {name}: workerPriority {worker_priority} outside "
                 f"0..={DEFAULT_CHILD_PRIORITY}"
             )
+        # B48's MCS half: at most `budgetUs` of CPU in every `periodUs`, for
+        # each of the instance's threads on its own scheduling context. Both or
+        # neither. Refused outright for the product kernel, which has no notion
+        # of either, because writing a figure the kernel cannot enforce would
+        # make the record say more than the system does.
+        budget_us = instance.get("budgetUs", 0)
+        period_us = instance.get("periodUs", 0)
+        for field, value in (("budgetUs", budget_us), ("periodUs", period_us)):
+            if not isinstance(value, int) or isinstance(value, bool) or value < 0:
+                fail(f"instance {name}: invalid {field}")
+        if (budget_us or period_us) and not kernel_mcs():
+            fail(
+                f"instance {name}: budgetUs and periodUs need the MCS kernel profile "
+                "(build-sel4.py --mcs)"
+            )
+        if (budget_us == 0) != (period_us == 0):
+            fail(f"instance {name}: budgetUs and periodUs must be declared together")
+        if budget_us and not MIN_BUDGET_US <= budget_us <= period_us <= MAX_PERIOD_US:
+            fail(
+                f"instance {name}: budgetUs {budget_us} and periodUs {period_us} outside "
+                f"{MIN_BUDGET_US} <= budget <= period <= {MAX_PERIOD_US}"
+            )
         schedule_records.extend(
             GENERATION_SCHEDULE.pack(
                 string_offset(f"{name}:schedule"),
                 thread,
                 PLAN_NONE,
                 priority,
-                priority,
-                0,
-                0,
+                max_controlled_priority,
+                budget_us,
+                period_us,
                 0,
             )
         )
@@ -2814,6 +2937,12 @@ def build_sel4_plan(
             None,
         )
         image_frame_count = image_pages.get(executable_object, 0)
+        # The heap the instance may grow into, as the quota's dynamic reserve:
+        # frames the root retypes from the task's arena on request rather than
+        # at construction, so they are reserved here and counted nowhere else.
+        heap_pages = instance.get("heapPages", 0)
+        if not isinstance(heap_pages, int) or isinstance(heap_pages, bool) or not 0 <= heap_pages <= MAX_HEAP_PAGES:
+            fail(f"instance {name}: invalid heapPages")
         process_objects = {
             "cnode": 1,
             "vspace": 1,
@@ -2850,7 +2979,7 @@ def build_sel4_plan(
                 # CSpace object above was given.
                 1 << cnode_size_bits,
                 0,
-                0,
+                heap_pages * PAGE_SIZE,
                 0,
             )
         )
@@ -2911,8 +3040,8 @@ def build_sel4_plan(
                     PLAN_NONE,
                     worker_priority,
                     worker_priority,
-                    0,
-                    0,
+                    budget_us,
+                    period_us,
                     0,
                 )
             )
@@ -3140,6 +3269,50 @@ def layout_executables(manifest: dict) -> set[str]:
     return names
 
 
+def health_record(manifest: dict, instance: dict, index: int) -> bytes:
+    """Encode one required instance's v6 health record.
+
+    Running is always probed: it is what v5 meant by required, so a manifest
+    that declares nothing more promotes exactly when it used to. The route is
+    named here and identified the way the fabric graph identifies it, so the
+    root can match the record against the graph it carries without knowing
+    route names.
+    """
+    name = instance["name"]
+    probes = GENERATION_HEALTH_PROBE_RUNNING
+    window = instance.get("healthFaultWindowTicks", 0)
+    if isinstance(window, bool) or not isinstance(window, int) or not 0 <= window <= 0xFFFFFFFF:
+        fail(f"instance {name}: invalid healthFaultWindowTicks")
+    if "healthFaultWindowTicks" in instance:
+        if window == 0:
+            fail(f"instance {name}: healthFaultWindowTicks must be positive")
+        probes |= GENERATION_HEALTH_PROBE_NO_FAULT
+    route = bytes(32)
+    if "healthRoute" in instance:
+        route = health_route_identity(manifest, name, instance["healthRoute"])
+        probes |= GENERATION_HEALTH_PROBE_ROUTE
+    return GENERATION_HEALTH.pack(index, probes, window, 0, route)
+
+
+def health_route_identity(manifest: dict, instance: str, route_name: str) -> bytes:
+    graph = manifest.get("fabricGraph") or {}
+    route = next((r for r in graph.get("routes", []) if r["name"] == route_name), None)
+    if route is None:
+        fail(f"instance {instance}: healthRoute {route_name} is not a fabric route")
+    if not any(member["component"] == instance for member in route["participants"]):
+        fail(f"instance {instance}: healthRoute {route_name} does not name it")
+    by_name = {
+        interface.name: interface
+        for interface in validate_interface_schemas(manifest["interfaceSchemas"])
+    }
+    interface = by_name[route["interface"]]
+    if interface.kind != "call":
+        fail(f"instance {instance}: healthRoute {route_name} is not a call route")
+    return fabric_route_identity(
+        route_name, interface.identity, FABRIC_CONTRACT_KIND[interface.kind]
+    )
+
+
 def build_generation(manifest: dict, payloads: dict[str, bytes], parent: bytes | None, number: int, profile: TargetProfile) -> bytes:
     if "boot-layout" in {object_["id"] for object_ in manifest["objects"]}:
         payloads = dict(payloads)
@@ -3456,8 +3629,26 @@ def build_generation(manifest: dict, payloads: dict[str, bytes], parent: bytes |
         owner = instance_index.get(state["owner"])
         if owner is None or state["schemaVersion"] <= 0 or state["policy"] not in POLICY:
             fail(f"invalid state {state['name']}")
-        state_records += GENERATION_STATE.pack(string_offset(state["name"]), owner, state["schemaVersion"], POLICY[state["policy"]])
-    for name in required: health_records += GENERATION_HEALTH.pack(instance_index[name])
+        # No migration is zero in both fields; one names its source version and
+        # the executable that translates from it, and never the version it is.
+        migrate_from, migrator = state.get("migrateFrom", 0), 0
+        if ("migrateFrom" in state) != ("migrator" in state):
+            fail(f"invalid state migration {state['name']}")
+        if "migrator" in state:
+            migrator = executable_index.get(state["migrator"])
+            if migrator is None or migrate_from <= 0 or migrate_from == state["schemaVersion"]:
+                fail(f"invalid state migration {state['name']}")
+        state_records += GENERATION_STATE.pack(
+            string_offset(state["name"]), owner, state["schemaVersion"], POLICY[state["policy"]], migrate_from, migrator
+        )
+    for name in required:
+        index = instance_index[name]
+        health_records += health_record(manifest, instances[index], index)
+    for instance in instances:
+        if instance["health"] != "required" and (
+            "healthFaultWindowTicks" in instance or "healthRoute" in instance
+        ):
+            fail(f"instance {instance['name']}: health probes on an optional instance")
 
     object_offset = GENERATION_HEADER.size
     executable_offset = object_offset + len(object_records)
@@ -3530,11 +3721,49 @@ def encode_bootstate(
     struct.pack_into("<II", slot, BOOTSTATE_REMAINING_ATTEMPTS_OFFSET, remaining_attempts, 0)
     slot[BOOTSTATE_GENERATION_ROOT_OFFSET:BOOTSTATE_GENERATION_ROOT_END] = generation_root
     slot[BOOTSTATE_STATE_ROOT_OFFSET:BOOTSTATE_STATE_ROOT_END] = state_root or sha256(b"")
+    if pending is not None:
+        # Nothing is migrated at build time, so a rollback restores the state
+        # the pending generation was staged over.
+        slot[BOOTSTATE_ROLLBACK_STATE_ROOT_OFFSET:BOOTSTATE_ROLLBACK_STATE_ROOT_END] = (
+            state_root or sha256(b"")
+        )
     struct.pack_into("<Q", slot, BOOTSTATE_ACCEPTED_RELEASE_SEQUENCE_OFFSET, accepted_release_sequence)
     slot[BOOTSTATE_CHECKSUM_OFFSET:BOOTSTATE_CHECKSUM_END] = bootstate_checksum(slot)
     return bytes(slot)
 
 
+# `ShadowOutcome::Requested` in `boot-contracts/src/bootstate.rs`.
+SHADOW_OUTCOME_REQUESTED = 0
+
+
+def encode_shadow_request(candidate: bytes, running: bytes, release_sequence: int) -> bytes:
+    """The evidence sector asking the next boot to shadow `candidate`.
+
+    Every count is zero: the root fills them in when it overwrites the request
+    with its verdict.
+    """
+    record = bytearray(
+        SHADOW_EVIDENCE.pack(
+            SHADOW_EVIDENCE_MAGIC,
+            SHADOW_EVIDENCE_VERSION,
+            SHADOW_EVIDENCE.size,
+            SHADOW_OUTCOME_REQUESTED,
+            0,
+            candidate,
+            running,
+            release_sequence,
+            0, 0, 0, 0, 0, 0,
+            0,
+            0,
+            bytes(32),
+        )
+    )
+    record[SHADOW_EVIDENCE_CHECKSUM_OFFSET:SHADOW_EVIDENCE_CHECKSUM_END] = (
+        shadow_evidence_checksum(record)
+    )
+    return bytes(record)
+
+
 # Must equal `slime-root/src/boot_selector.rs`'s `SELECTOR_GENERATION_BYTES`.
 # Lowered from 8 MiB with that constant: the selector's buffer is `.bss`, so
 # every page of it costs a root CSlot before the root runs, and 8 MiB spent
@@ -3597,6 +3826,14 @@ def build_bootstore(generations: list[bytes]) -> bytes:
         accepted_release_sequence=accepted_sequence,
         remaining_attempts=remaining_attempts,
     )
+    # Outside the sealed range, so the request and the verdict that replaces it
+    # never disturb the directory checksum.
+    if os.environ.get("SLIME_SHADOW_REQUEST") == "1":
+        if pending is None:
+            fail("SLIME_SHADOW_REQUEST needs a pending generation")
+        image[BOOTSTORE_EVIDENCE_OFFSET : BOOTSTORE_EVIDENCE_OFFSET + SHADOW_EVIDENCE.size] = (
+            encode_shadow_request(pending, known_good, release_sequences[-1])
+        )
     directory = bytearray()
     release_cursor = BOOTSTORE_RELEASES_OFFSET
     generation_cursor = BOOTSTORE_GENERATIONS_OFFSET
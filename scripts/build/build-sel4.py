@@ -95,6 +95,19 @@ BOOT_SELECTION_IMAGE = BUILD_ROOT / "slime-sel4-boot-selection.elf"
 BOOT_SELECTION_MANIFEST = BUILD_ROOT / "slime-sel4-boot-selection.identity.json"
 DEMO_IMAGE = BUILD_ROOT / "slime-sel4-demo.elf"
 DEMO_MANIFEST = BUILD_ROOT / "slime-sel4-demo.identity.json"
+# Only ever built with `--mcs`, so only ever written under its `-mcs` name.
+ISOLATION_IMAGE = BUILD_ROOT / "slime-sel4-isolation.elf"
+ISOLATION_MANIFEST = BUILD_ROOT / "slime-sel4-isolation.identity.json"
+CLOCK_IMAGE = BUILD_ROOT / "slime-sel4-clock.elf"
+CLOCK_MANIFEST = BUILD_ROOT / "slime-sel4-clock.identity.json"
+
+# The alternate MCS kernel profile (B48), selected by `--mcs`. Its kernel,
+# prefix, cargo targets, artifacts, and images all sit beside the product's
+# rather than over them. `sel4-sys` generates its bindings from the installed
+# prefix, so a root task built against one kernel does not run on the other.
+# Sharing directories would also let an MCS build silently replace the artifact
+# a product gate boots.
+MCS_CONFIG = ROOT / "sel4" / "config" / "qemu-arm-virt-mcs.cmake"
 
 # Which generation the root task embeds. That is the only difference between the
 # images this script builds; see `build_application`.
@@ -150,6 +163,8 @@ INPUT_VARIANT = "input"
 POWERBOX_VARIANT = "powerbox"
 TRANSFER_VARIANT = "transfer"
 BOOT_SELECTION_VARIANT = "boot-selection"
+ISOLATION_VARIANT = "isolation"
+CLOCK_VARIANT = "clock"
 VARIANT_MANIFESTS = {
     GRAPH_VARIANT: "sel4",
     DEMO_VARIANT: "sel4-demo",
@@ -184,6 +199,8 @@ VARIANT_MANIFESTS = {
     POWERBOX_VARIANT: "sel4-powerbox",
     TRANSFER_VARIANT: "sel4-transfer",
     BOOT_SELECTION_VARIANT: "sel4",
+    ISOLATION_VARIANT: "sel4-isolation",
+    CLOCK_VARIANT: "sel4-clock",
 }
 # B62: what distinguishes a variant that shares another's manifest.
 #
@@ -247,6 +264,8 @@ VARIANT_TARGET_DIRS = {
     POWERBOX_VARIANT: "root-powerbox",
     TRANSFER_VARIANT: "root-transfer",
     BOOT_SELECTION_VARIANT: "root-boot-selection",
+    ISOLATION_VARIANT: "root-isolation",
+    CLOCK_VARIANT: "root-clock",
 }
 VARIANT_IMAGES = {
     FIXTURE_VARIANT: (IMAGE, MANIFEST),
@@ -286,6 +305,8 @@ VARIANT_IMAGES = {
     POWERBOX_VARIANT: (POWERBOX_IMAGE, POWERBOX_MANIFEST),
     TRANSFER_VARIANT: (TRANSFER_IMAGE, TRANSFER_MANIFEST),
     BOOT_SELECTION_VARIANT: (BOOT_SELECTION_IMAGE, BOOT_SELECTION_MANIFEST),
+    ISOLATION_VARIANT: (ISOLATION_IMAGE, ISOLATION_MANIFEST),
+    CLOCK_VARIANT: (CLOCK_IMAGE, CLOCK_MANIFEST),
 }
 
 CHILD_MANIFEST = ROOT / "slime-root" / "child" / "Cargo.toml"
@@ -296,6 +317,28 @@ def fail(message: str) -> NoReturn:
     raise SystemExit(f"seL4 image build: {message}")
 
 
+def select_mcs_profile() -> None:
+    """Point every kernel-dependent path at the MCS profile's own tree.
+
+    Also tells `build-generation.py` that the selected kernel enforces
+    budgets and periods, which is the only thing that lets it write them into
+    the generation (see `SLIME_SEL4_KERNEL_MCS` there).
+    """
+    global SEL4_CONFIG, SEL4_BUILD, SEL4_PREFIX, CARGO_BUILD, ARTIFACTS
+    SEL4_CONFIG = MCS_CONFIG
+    SEL4_BUILD = BUILD_ROOT / "sel4-mcs-qemu"
+    SEL4_PREFIX = BUILD_ROOT / "sel4-mcs-prefix"
+    CARGO_BUILD = BUILD_ROOT / "sel4-mcs-cargo"
+    ARTIFACTS = BUILD_ROOT / "sel4-mcs-artifacts"
+    os.environ["SLIME_SEL4_KERNEL_MCS"] = "1"
+
+
+def mcs_path(path: Path) -> Path:
+    """`slime-sel4-x.identity.json` as `slime-sel4-x-mcs.identity.json`."""
+    stem, _, extensions = path.name.partition(".")
+    return path.with_name(f"{stem}-mcs.{extensions}")
+
+
 def load_pins() -> dict[str, object]:
     if not PINS_PATH.is_file():
         fail(f"missing pin manifest: {PINS_PATH.relative_to(ROOT)}")
@@ -428,6 +471,17 @@ def boot_bundle_identity() -> str:
     return digest.hexdigest()
 
 
+def plane_rotation_identity() -> str:
+    """Store identity of the trust-root rotation the generation plane's fixture
+    seeds, which its client names in ROTATE_TRUST. Signatures are deterministic
+    Ed25519, so the fixture's bytes, and therefore this hash, are fixed."""
+    sys.path.insert(0, str(ROOT / "scripts" / "lib"))
+    from harness import load_script
+
+    fixture = load_script("slime_build_store_fixture", "build/build-store-fixture.py")
+    return hashlib.sha256(fixture.plane_rotation()).hexdigest()
+
+
 def git_commit(path: Path) -> str:
     commit = run_output(["git", "rev-parse", "HEAD"], cwd=path, description="read submodule pin")
     if re.fullmatch(r"[0-9a-f]{40}", commit) is None:
@@ -881,6 +935,9 @@ def build_application(
             # absent.
             generation_environment = generation_environment or dict(os.environ)
             generation_environment["SLIME_FABRIC_PROXY_EARLY_EXIT"] = "1"
+        if variant == GENERATION_VARIANT:
+            generation_environment = generation_environment or dict(os.environ)
+            generation_environment["SLIME_GENERATION_ROTATION"] = plane_rotation_identity()
         if variant in STREAM_DEATH_VARIANTS:
             # C8.5/C8.14: the stream family's own peer death, scripted for the
             # same reason the interposition hop's is. A publisher cannot both
@@ -1099,6 +1156,9 @@ def write_manifest(
     }
     if variant == BOOT_SELECTION_VARIANT:
         manifest["boot_bundle_identity"] = boot_bundle_identity()
+    if SEL4_CONFIG == MCS_CONFIG:
+        # Absent from product manifests, so their bytes are unchanged.
+        manifest["kernel_profile"] = "mcs"
     encoded = json.dumps(manifest, indent=2, sort_keys=True) + "\n"
     try:
         manifest_path.write_text(encoded, encoding="utf-8")
@@ -1118,6 +1178,14 @@ def main() -> None:
         action="store_true",
         help="build the immutable disk-backed generation selector as the sole loader app",
     )
+    parser.add_argument(
+        "--mcs",
+        action="store_true",
+        help=(
+            "build against the alternate MCS kernel profile (B48) so declared "
+            "budgets and periods are enforced, writing separate -mcs images"
+        ),
+    )
     parser.add_argument(
         "--component-graph",
         action="store_true",
@@ -1364,6 +1432,22 @@ def main() -> None:
             "capability, writing a separate image"
         ),
     )
+    parser.add_argument(
+        "--isolation-plane",
+        action="store_true",
+        help=(
+            "embed the MCS isolation generation (B48): a saturating component "
+            "held to its reservation beside a reserved control loop; needs --mcs"
+        ),
+    )
+    parser.add_argument(
+        "--clock-plane",
+        action="store_true",
+        help=(
+            "embed the clock generation: one probe granted a monotonic, a wall, "
+            "and a simulated clock, writing a separate image"
+        ),
+    )
     arguments = parser.parse_args()
     selected = [
         variant
@@ -1401,12 +1485,18 @@ def main() -> None:
             (POWERBOX_VARIANT, arguments.powerbox_plane),
             (TRANSFER_VARIANT, arguments.transfer_plane),
             (BOOT_SELECTION_VARIANT, arguments.boot_selection),
+            (ISOLATION_VARIANT, arguments.isolation_plane),
+            (CLOCK_VARIANT, arguments.clock_plane),
         )
         if chosen
     ]
     if len(selected) > 1:
         fail("each --*-plane flag selects a different generation; pass one")
     variant = selected[0] if selected else FIXTURE_VARIANT
+    if variant == ISOLATION_VARIANT and not arguments.mcs:
+        fail("--isolation-plane declares reservations, which only --mcs enforces")
+    if arguments.mcs:
+        select_mcs_profile()
 
     if Path.cwd().resolve() != ROOT:
         fail(f"run from repository root: {ROOT}")
@@ -1418,17 +1508,24 @@ def main() -> None:
         )
     BUILD_ROOT.mkdir(parents=True, exist_ok=True)
     configure_and_install_sel4()
-    run(
-        [
-            sys.executable,
-            str(ROOT / "scripts" / "check" / "check-sel4-pins.py"),
-            "--prefix",
-        ],
-        description="verify installed seL4 prefix",
-    )
+    if arguments.mcs:
+        # `[observed_prefix]` describes the product kernel. No MCS prefix has
+        # been observed, so there is nothing to compare this one against.
+        print("seL4 image build: MCS profile; installed prefix is not pinned")
+    else:
+        run(
+            [
+                sys.executable,
+                str(ROOT / "scripts" / "check" / "check-sel4-pins.py"),
+                "--prefix",
+            ],
+            description="verify installed seL4 prefix",
+        )
     child_elf, root_elf = build_application(pins, variant=variant)
     loader, payload_tool = build_loader(pins)
     image, manifest_path = VARIANT_IMAGES[variant]
+    if arguments.mcs:
+        image, manifest_path = mcs_path(image), mcs_path(manifest_path)
     package_image(payload_tool, loader, root_elf, image)
     write_manifest(
         pins,
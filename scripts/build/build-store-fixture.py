@@ -4,8 +4,8 @@
 Every variant shares one layout: a 2048-sector raw image with a protective
 MBR, primary and backup GPT copies, and a single Slime OS object-store
 partition (type GUID "SLIMEOSSTOREGPT!") at LBA 40..2014. The store carries
-genesis superblock slot B, committed slot A with one seeded object, and the
-seeded record. Fault variants corrupt exactly one structure so the guest must
+genesis superblock slot B, committed slot A with one seeded object, the index
+each slot seals, and the seeded record. Fault variants corrupt exactly one structure so the guest must
 recover or reject per the documented rules.
 """
 
@@ -42,27 +42,76 @@ SEEDED_TYPE = 1
 SEEDED_PAYLOAD_LEN = 512
 SEEDED_RECORD_SECTORS = 2
 from boot_contracts import (
+    SHADOW_EVIDENCE,
+    SHADOW_EVIDENCE_CHECKSUM_END,
+    SHADOW_EVIDENCE_CHECKSUM_OFFSET,
+    SHADOW_EVIDENCE_MAGIC,
+    SHADOW_EVIDENCE_VERSION,
+    BOOTSTATE_CHECKSUM_END,
+    BOOTSTATE_CHECKSUM_OFFSET,
+    BOOTSTATE_MAGIC,
+    BOOTSTATE_SLOT,
+    BOOTSTATE_SLOT_BYTES,
+    BOOTSTATE_VERSION,
+    STATE_JOURNAL_SECTORS,
     STORE_FORMAT_VERSION as FORMAT_VERSION,
+    STORE_INDEX,
+    STORE_INDEX_A_LBA,
+    STORE_INDEX_B_LBA,
+    STORE_INDEX_ENTRY,
+    STORE_INDEX_MAGIC as INDEX_MAGIC,
+    STORE_INDEX_SECTORS,
+    STORE_MAX_OBJECT_PAYLOAD as MAX_OBJECT_PAYLOAD,
     STORE_RECORD,
     STORE_RECORD_AREA_START as RECORD_AREA_START,
     STORE_RECORD_CONTENT_HASH_OFFSET,
+    STORE_RECORD_CREATION_SEQUENCE_OFFSET,
     STORE_RECORD_FORMAT_VERSION_OFFSET,
+    STORE_RECORD_GENERATION_OFFSET,
+    STORE_RECORD_HEADER_CRC32_OFFSET,
     STORE_RECORD_HEADER_SIZE_OFFSET,
     STORE_RECORD_MAGIC as RECORD_MAGIC,
     STORE_RECORD_OBJ_TYPE_OFFSET,
     STORE_RECORD_PAYLOAD_LEN_OFFSET,
+    STORE_SUPERBLOCK,
     STORE_SUPERBLOCK_APPEND_LBA_OFFSET,
     STORE_SUPERBLOCK_CRC32_OFFSET,
     STORE_SUPERBLOCK_FORMAT_VERSION_OFFSET,
     STORE_SUPERBLOCK_HEADER_SIZE_OFFSET,
+    STORE_SUPERBLOCK_INDEX_CRC32_OFFSET,
     STORE_SUPERBLOCK_MAGIC as SUPERBLOCK_MAGIC,
     STORE_SUPERBLOCK_OBJECT_COUNT_OFFSET,
     STORE_SUPERBLOCK_PARTITION_SECTORS_OFFSET,
     STORE_SUPERBLOCK_RECORD_AREA_START_OFFSET,
     STORE_SUPERBLOCK_SEQUENCE_OFFSET,
+    BOOTSTORE_CAPACITY,
+    BOOTSTORE_DIRECTORY_OFFSET,
+    BOOTSTORE_ENTRY,
+    BOOTSTORE_EVIDENCE_OFFSET,
+    BOOTSTORE_GENERATIONS_OFFSET,
+    BOOTSTORE_HEADER,
+    BOOTSTORE_HEADER_CHECKSUM_OFFSET,
+    BOOTSTORE_MAGIC,
+    BOOTSTORE_RELEASES_OFFSET,
+    BOOTSTORE_VERSION,
+    bootstate_checksum,
+    bootstore_checksum,
+    shadow_evidence_checksum,
 )
 from recovery_index import binding_identity, build_recovery_index, sha256
+from release_trust import KEY_PATHS, build_release, build_rotation, initial_public_keys, trust_root_record
 from boot_contracts import (
+    GENERATION_HEADER_GENERATION_NUMBER_OFFSET,
+    GENERATION_HEADER_IDENTITY_END,
+    GENERATION_HEADER_IDENTITY_OFFSET,
+    GENERATION_HEADER_OBJECT_COUNT_OFFSET,
+    GENERATION_HEADER_OBJECT_OFFSET_OFFSET,
+    GENERATION_HEADER_PARENT_END,
+    GENERATION_HEADER_PARENT_OFFSET,
+    GENERATION_HEADER_PAYLOAD_OFFSET_OFFSET,
+    GENERATION_HEADER_TOTAL_LEN_OFFSET,
+    GENERATION_OBJECT,
+    generation_identity,
     RELEASE_BYTES,
     TRANSFER_HEADER,
     TRANSFER_HEADER_BYTES,
@@ -97,6 +146,10 @@ VARIANTS = [
     # partition for its BootState slots and nothing else.
     "transfer",
     "boot-selection",
+    # M6.5's generation plane: the happy store plus a release chain, committed
+    # as a second root, for the management service's bisect to walk, and the
+    # known-good BootState over seeded state for its stages to inherit.
+    "generation",
 ]
 
 # Fixture-only regions are fixed by the probes. They are not object-store
@@ -109,11 +162,20 @@ RECOVERY_INDEX_LBA = 1026
 RECOVERY_INDEX_SECTORS = 4
 TRANSFER_MANIFEST_LBA = 1030
 TRANSFER_MANIFEST_SECTORS = 16
+# The generation manager's state journal. No fixture writes it, but the
+# manager writes it on the same partition, so it is reserved like the rest.
+STATE_JOURNAL_LBA = 1046
+# The generation manager's shadow-evidence sector, after the journal, where it
+# keeps a shadow request when no boot disk is granted to it. The generation
+# plane grants one, so no fixture writes it.
+SHADOW_EVIDENCE_LBA = STATE_JOURNAL_LBA + STATE_JOURNAL_SECTORS
 FIXTURE_REGIONS = (
     (STATE_SLOT_A, STATE_SLOT_A + 1, "BootState A"),
     (STATE_SLOT_B, STATE_SLOT_B + 1, "BootState B"),
     (RECOVERY_INDEX_LBA, RECOVERY_INDEX_LBA + RECOVERY_INDEX_SECTORS, "recovery index"),
     (TRANSFER_MANIFEST_LBA, TRANSFER_MANIFEST_LBA + TRANSFER_MANIFEST_SECTORS, "transfer manifest"),
+    (STATE_JOURNAL_LBA, STATE_JOURNAL_LBA + STATE_JOURNAL_SECTORS, "state journal"),
+    (SHADOW_EVIDENCE_LBA, SHADOW_EVIDENCE_LBA + 1, "shadow evidence"),
 )
 for index, (start, end, name) in enumerate(FIXTURE_REGIONS):
     if start < RECORD_AREA_START or end > PARTITION_SECTORS or start >= end:
@@ -130,6 +192,13 @@ RECOVERY_GENERATION_ROOT = bytes([0x66]) * 32
 RECOVERY_RELEASE_SEQUENCE = 3
 RECOVERY_BINDING = "recovered-state"
 RECOVERY_SCHEMA_VERSION = 1
+# The trust root the recovery target was accepted under: the compiled-in keys
+# rotated once, stored as `TRUST_ROOT_OBJECT_TYPE` from
+# contracts/generation-management/v1/schema.zt. The index names it, so a
+# reconstructed root keeps the rotation instead of reverting to version 1.
+TRUST_ROOT_OBJECT_TYPE = 1196250194
+RECOVERY_TRUST_ROOT_VERSION = 2
+RECOVERY_TRUST_ROOT_THRESHOLD = 2
 
 # M6.7: what the source transfer manifest carries.
 TRANSFER_GENERATION = bytes([0x77]) * 32
@@ -139,6 +208,107 @@ TRANSFER_OBJECT_TYPE = 1
 TRANSFER_PAYLOAD = b"Slime OS M6.7 transferred object\n"
 TRANSFER_STATE_BINDING = "transferred-state"
 
+# The generation plane's release chain, oldest first: the manager's known-good
+# and candidate generations, then seven generations past the candidate for a
+# bisect to search. Each names the one before it as its parent. All are real
+# generations derived from the plane's own (`plane_generations`), with signed
+# releases the manager verifies before it stages one, a bisect midpoint
+# included. The record tags are `RELEASE_OBJECT_TYPE` and
+# `GENERATION_OBJECT_TYPE` from contracts/generation-management/v1/schema.zt.
+RELEASE_OBJECT_TYPE = 1196249676
+GENERATION_OBJECT_TYPE = 1196246862
+BISECT_CHAIN_LENGTH = 7
+# The known-good state the generation plane's manager starts from, one object
+# under each binding sel4-generation.zti declares: a snapshot-before-upgrade
+# binding a rollback hands back, and a discard-on-rollback one it drops. The
+# map record is `STATE_MAP_OBJECT_TYPE` from the same schema, and the boot
+# disk's BootState names its root, so the manager stages over real state
+# rather than the empty map its own genesis would write.
+STATE_MAP_OBJECT_TYPE = 1196249933
+PLANE_STATE = (
+    ("generation-client-settings", b"generation-plane settings, schema 1\n"),
+    ("generation-client-scratch", b"generation-plane scratch, schema 1\n"),
+)
+PLANE_STATE_SCHEMA_VERSION = 1
+# The trust-root rotation the plane's client asks for once its bisect is done:
+# the compiled-in keys re-signed as version 2, stored as `ROTATION_OBJECT_TYPE`
+# from the same schema. The manager refuses a rotation that would strand the
+# root's generation, so the candidate the client promotes is re-released under
+# the replacement beside it.
+ROTATION_OBJECT_TYPE = 1196249684
+PLANE_ROTATED_VERSION = 2
+# `KIND_RESOURCE` in `boot-contracts/src/generation.rs`.
+GENERATION_KIND_RESOURCE = 4
+# The manager compacts once its records pass half the room below the BootState
+# slots; a fixture already past that would have the plane compacting first.
+COMPACTION_THRESHOLD = STATE_SLOT_A // 2
+
+
+# `ShadowOutcome::Healthy` in `boot-contracts/src/bootstate.rs`.
+SHADOW_OUTCOME_HEALTHY = 1
+
+
+def shadow_verdict(candidate: bytes, running: bytes, release_sequence: int) -> bytes:
+    """A healthy shadow verdict on `candidate`: one required instance,
+    exercised and completed, none failed — the least `promotes` accepts."""
+    record = bytearray(
+        SHADOW_EVIDENCE.pack(
+            SHADOW_EVIDENCE_MAGIC,
+            SHADOW_EVIDENCE_VERSION,
+            SHADOW_EVIDENCE.size,
+            SHADOW_OUTCOME_HEALTHY,
+            0,
+            candidate,
+            running,
+            release_sequence,
+            1, 1, 0, 1, 0, 0,
+            0,
+            0,
+            bytes(32),
+        )
+    )
+    record[SHADOW_EVIDENCE_CHECKSUM_OFFSET:SHADOW_EVIDENCE_CHECKSUM_END] = (
+        shadow_evidence_checksum(record)
+    )
+    return bytes(record)
+
+
+def state_map(entries: list[tuple[bytes, bytes, int]]) -> bytes:
+    """A state map: (binding, object, schema version) entries in ascending
+    binding order, as `boot-contracts::state_policy` decodes one."""
+    return b"".join(
+        binding + identity + struct.pack("<I", schema_version)
+        for binding, identity, schema_version in sorted(entries)
+    )
+
+
+def known_good_bootstate(known_good: bytes, generation_root: bytes, state_root: bytes) -> bytes:
+    """The BootState the manager's genesis would write, at sequence 1 on the
+    known-good's release, except that it names a boot store's
+    `generation_root` and `state_root`."""
+    slot = bytearray(
+        BOOTSTATE_SLOT.pack(
+            BOOTSTATE_MAGIC,
+            BOOTSTATE_VERSION,
+            BOOTSTATE_SLOT_BYTES,
+            0,
+            1,
+            known_good,
+            bytes(32),
+            0,
+            0,
+            generation_root,
+            state_root,
+            1,
+            bytes(32),
+            bytes(32),
+            0,
+            bytes(32),
+        )
+    )
+    slot[BOOTSTATE_CHECKSUM_OFFSET:BOOTSTATE_CHECKSUM_END] = bootstate_checksum(slot)
+    return bytes(slot)
+
 
 def seeded_payload() -> bytes:
     data = bytearray(SEEDED_PAYLOAD_LEN)
@@ -178,22 +348,45 @@ def gpt_entries() -> bytes:
     return bytes(table)
 
 
-def superblock(sequence: int, append_lba: int, object_count: int) -> bytes:
+def superblock(sequence: int, append_lba: int, object_count: int, index_crc32: int) -> bytes:
     sector = bytearray(SECTOR)
     struct.pack_into("<8s", sector, 0, SUPERBLOCK_MAGIC)
     struct.pack_into("<I", sector, STORE_SUPERBLOCK_FORMAT_VERSION_OFFSET, FORMAT_VERSION)
-    struct.pack_into("<I", sector, STORE_SUPERBLOCK_HEADER_SIZE_OFFSET, HEADER_SIZE)
+    struct.pack_into("<I", sector, STORE_SUPERBLOCK_HEADER_SIZE_OFFSET, STORE_SUPERBLOCK.size)
     struct.pack_into("<Q", sector, STORE_SUPERBLOCK_SEQUENCE_OFFSET, sequence)
     struct.pack_into("<Q", sector, STORE_SUPERBLOCK_APPEND_LBA_OFFSET, append_lba)
     struct.pack_into("<I", sector, STORE_SUPERBLOCK_OBJECT_COUNT_OFFSET, object_count)
     struct.pack_into("<Q", sector, STORE_SUPERBLOCK_RECORD_AREA_START_OFFSET, RECORD_AREA_START)
     struct.pack_into("<Q", sector, STORE_SUPERBLOCK_PARTITION_SECTORS_OFFSET, PARTITION_SECTORS)
+    struct.pack_into("<I", sector, STORE_SUPERBLOCK_INDEX_CRC32_OFFSET, index_crc32)
     crc = zlib.crc32(bytes(sector[:STORE_SUPERBLOCK_CRC32_OFFSET]))
     struct.pack_into("<I", sector, STORE_SUPERBLOCK_CRC32_OFFSET, crc)
     return bytes(sector)
 
 
-def record(obj_type: int, payload: bytes) -> bytes:
+def index(sequence: int, entries: list[tuple[int, bytes, int]]) -> bytes:
+    """The index a commit seals: one (type, payload, lba) per record, in order."""
+    body = bytearray(
+        STORE_INDEX.pack(
+            INDEX_MAGIC, FORMAT_VERSION, STORE_INDEX.size, sequence, len(entries), STORE_INDEX_ENTRY.size, bytes(32)
+        )
+    )
+    for obj_type, payload, lba in entries:
+        body += STORE_INDEX_ENTRY.pack(hashlib.sha256(payload).digest(), obj_type, len(payload), lba)
+    if len(body) > STORE_INDEX_SECTORS * SECTOR:
+        raise SystemExit("store index exceeds its reserved sectors")
+    return bytes(body)
+
+
+def commit(image: bytearray, slot: int, sequence: int, append_lba: int, entries: list[tuple[int, bytes, int]]) -> None:
+    """Write one superblock slot and the index it seals."""
+    body = index(sequence, entries)
+    index_lba = (STORE_INDEX_A_LBA, STORE_INDEX_B_LBA)[slot]
+    place(image, STORE_FIRST + index_lba, body + bytes(-len(body) % SECTOR))
+    place(image, STORE_FIRST + slot, superblock(sequence, append_lba, len(entries), zlib.crc32(body)))
+
+
+def record(obj_type: int, payload: bytes, creation_sequence: int, generation: bytes = bytes(32)) -> bytes:
     digest = hashlib.sha256(payload).digest()
     header = bytearray(HEADER_SIZE)
     struct.pack_into("<8s", header, 0, RECORD_MAGIC)
@@ -202,6 +395,10 @@ def record(obj_type: int, payload: bytes) -> bytes:
     struct.pack_into("<I", header, STORE_RECORD_OBJ_TYPE_OFFSET, obj_type)
     struct.pack_into("<Q", header, STORE_RECORD_PAYLOAD_LEN_OFFSET, len(payload))
     struct.pack_into("<32s", header, STORE_RECORD_CONTENT_HASH_OFFSET, digest)
+    struct.pack_into("<Q", header, STORE_RECORD_CREATION_SEQUENCE_OFFSET, creation_sequence)
+    struct.pack_into("<32s", header, STORE_RECORD_GENERATION_OFFSET, generation)
+    crc = zlib.crc32(bytes(header[:STORE_RECORD_HEADER_CRC32_OFFSET]))
+    struct.pack_into("<I", header, STORE_RECORD_HEADER_CRC32_OFFSET, crc)
     data = bytes(header) + payload
     data += bytes(-len(data) % SECTOR)
     return data
@@ -211,8 +408,15 @@ def place(image: bytearray, lba: int, data: bytes) -> None:
     image[lba * SECTOR : lba * SECTOR + len(data)] = data
 
 
+def recovery_trust_root() -> bytes:
+    return trust_root_record(
+        RECOVERY_TRUST_ROOT_VERSION, RECOVERY_TRUST_ROOT_THRESHOLD, initial_public_keys()
+    )
+
+
 def recovery_index(state_object: bytes) -> bytes:
-    """A recovery index naming the store's seeded state object."""
+    """A recovery index naming the store's seeded state object and the stored
+    rotated trust root."""
     return build_recovery_index(
         RECOVERY_TARGET,
         RECOVERY_GENERATION_ROOT,
@@ -221,20 +425,26 @@ def recovery_index(state_object: bytes) -> bytes:
         [(RECOVERY_BINDING, state_object, RECOVERY_SCHEMA_VERSION)],
         STORE_FIRST + RECORD_AREA_START,
         STORE_LAST,
+        (sha256(recovery_trust_root()), RECOVERY_TRUST_ROOT_VERSION),
     )
 
 
 def transfer_manifest() -> bytes:
-    """A one-object, one-state transfer manifest.
+    """A two-object, one-state transfer manifest.
 
     Encoded here rather than by `build-transfer.py` because that script builds
     from a *pair of real generations*, and this fixture needs only a
     well-formed record for the seL4 plane to verify: the properties under test
     are the self-excluding digest, the object closure's content hashes, and the
     travel flags, none of which need a real generation behind them.
+
+    The transfer is negotiated against the `happy` receiver, whose store holds
+    the seeded object and nothing else. That object travels without its
+    payload, and the receiver must find it in its own store.
     """
     payload = TRANSFER_PAYLOAD
     digest = sha256(payload)
+    held = seeded_payload()
     state_root = sha256(b"transferred-state-root")
     # `<32s32sIII4x`: binding, state root, schema version, policy, flags.
     states = TRANSFER_STATE.pack(
@@ -247,7 +457,7 @@ def transfer_manifest() -> bytes:
     release = bytes(RELEASE_BYTES)
     metadata = b"sel4-transfer-fixture"
     object_offset = TRANSFER_HEADER_BYTES
-    state_offset = object_offset + TRANSFER_OBJECT.size
+    state_offset = object_offset + 2 * TRANSFER_OBJECT.size
     release_offset = state_offset + len(states)
     metadata_offset = release_offset + len(release)
     payload_offset = metadata_offset + len(metadata)
@@ -257,7 +467,7 @@ def transfer_manifest() -> bytes:
     # payload flag is set.
     objects = TRANSFER_OBJECT.pack(
         digest, len(payload), payload_offset, TRANSFER_OBJECT_TYPE, OBJECT_FLAG_PAYLOAD
-    )
+    ) + TRANSFER_OBJECT.pack(sha256(held), len(held), 0, SEEDED_TYPE, 0)
     # Field order is the generated layout's, read from
     # `TRANSFER_HEADER_*_OFFSET` rather than guessed: magic, version, header
     # size, required flags, generation, parent, source state root, authority
@@ -276,7 +486,7 @@ def transfer_manifest() -> bytes:
         TRANSFER_RELEASE_SEQUENCE,
         0,
         0,
-        1,
+        2,
         1,
         object_offset,
         state_offset,
@@ -298,6 +508,152 @@ def transfer_manifest() -> bytes:
     body[TRANSFER_HEADER_HASH_OFFSET:TRANSFER_HEADER_HASH_END] = hasher.digest()
     return bytes(body)
 
+def plane_generations(built: bytes, count: int) -> list[bytes]:
+    """`count` generations from the plane's own, each the previous one's child.
+
+    Identities are content hashes, so a manager that verifies what it stages
+    needs the bytes behind them. Each is `built` with every executable payload
+    cut to a placeholder, which keeps a generation inside one store record; the
+    manager decodes a generation and never runs one. The first is the
+    known-good and the second the candidate; each after the first names the
+    one before as its parent, one generation number on, and declares the same
+    authority, so its release needs no widening approval.
+    """
+    object_count = struct.unpack_from("<I", built, GENERATION_HEADER_OBJECT_COUNT_OFFSET)[0]
+    object_offset = struct.unpack_from("<Q", built, GENERATION_HEADER_OBJECT_OFFSET_OFFSET)[0]
+    payload_offset = struct.unpack_from("<Q", built, GENERATION_HEADER_PAYLOAD_OFFSET_OFFSET)[0]
+    body = bytearray(built[:payload_offset])
+    payloads = bytearray()
+    for index in range(object_count):
+        entry = object_offset + index * GENERATION_OBJECT.size
+        id_offset, kind, start, length, _ = GENERATION_OBJECT.unpack_from(built, entry)
+        payload = built[start : start + length]
+        if kind != GENERATION_KIND_RESOURCE:
+            payload = b"generation-plane placeholder %d\n" % index
+        GENERATION_OBJECT.pack_into(
+            body, entry, id_offset, kind, payload_offset + len(payloads), len(payload), sha256(payload)
+        )
+        payloads += payload
+    body += payloads
+    struct.pack_into("<Q", body, GENERATION_HEADER_TOTAL_LEN_OFFSET, len(body))
+
+    def derive(number: int, parent: bytes) -> bytes:
+        generation = bytearray(body)
+        struct.pack_into("<Q", generation, GENERATION_HEADER_GENERATION_NUMBER_OFFSET, number)
+        generation[GENERATION_HEADER_PARENT_OFFSET:GENERATION_HEADER_PARENT_END] = parent
+        generation[GENERATION_HEADER_IDENTITY_OFFSET:GENERATION_HEADER_IDENTITY_END] = generation_identity(
+            generation
+        )
+        return bytes(generation)
+
+    generations = [derive(1, bytes(32))]
+    for number in range(2, count + 1):
+        parent = generations[-1][GENERATION_HEADER_IDENTITY_OFFSET:GENERATION_HEADER_IDENTITY_END]
+        generations.append(derive(number, parent))
+    return generations
+
+
+def generation_identity_of(body: bytes) -> bytes:
+    return body[GENERATION_HEADER_IDENTITY_OFFSET:GENERATION_HEADER_IDENTITY_END]
+
+
+def plane_chain(generation: bytes) -> tuple[list[bytes], list[tuple[bytes, bytes]]]:
+    """The generation plane's chain and one signed release per generation,
+    as (identity, release) pairs in chain order; the known-good's is at
+    sequence 1."""
+    chain = plane_generations(generation, 2 + BISECT_CHAIN_LENGTH)
+    releases = [
+        (generation_identity_of(body), build_release(body, sequence))
+        for sequence, body in enumerate(chain, start=1)
+    ]
+    return chain, releases
+
+
+def plane_rotation() -> bytes:
+    """The signed rotation the generation plane's client names; its SHA-256
+    is the identity build-sel4.py compiles into the client."""
+    return build_rotation(KEY_PATHS, KEY_PATHS, PLANE_ROTATED_VERSION - 1, PLANE_ROTATED_VERSION)
+
+
+def plane_state_map() -> bytes:
+    return state_map(
+        [
+            (binding_identity(name), sha256(payload), PLANE_STATE_SCHEMA_VERSION)
+            for name, payload in PLANE_STATE
+        ]
+    )
+
+
+def plane_boot_store(generation: bytes) -> bytes:
+    """The generation plane's boot store: the chain and its releases in a
+    directory a boot-selector root validates, BootState slot A on the
+    known-good over the plane's state, and the candidate's healthy shadow
+    verdict in the evidence sector, where a root's shadow run leaves one.
+
+    The manager keeps BootState here, not on its store, so what it stages is
+    what a root selects. No root shadows in this plane, since its generations
+    are placeholders, so the verdict is seeded for SELECT to promote on.
+
+    The store's release region holds eight releases, one short of the chain.
+    The chain's last is the bisect's bad bound, which no search stages, so it
+    is the one left out: every generation the manager can stage is listed."""
+    chain, releases = plane_chain(generation)
+    image = bytearray(BOOTSTORE_CAPACITY)
+    entries = sorted(
+        (identity, body, release)
+        for body, (identity, release) in zip(chain[:-1], releases[:-1])
+    )
+    directory = bytearray()
+    release_cursor = BOOTSTORE_RELEASES_OFFSET
+    generation_cursor = BOOTSTORE_GENERATIONS_OFFSET
+    for identity, body, release in entries:
+        generation_cursor = -(-generation_cursor // 4096) * 4096
+        directory += BOOTSTORE_ENTRY.pack(
+            identity, generation_cursor, len(body), release_cursor, len(release)
+        )
+        image[release_cursor : release_cursor + len(release)] = release
+        image[generation_cursor : generation_cursor + len(body)] = body
+        release_cursor += RELEASE_BYTES
+        generation_cursor += len(body)
+    if release_cursor > BOOTSTORE_GENERATIONS_OFFSET or generation_cursor > BOOTSTORE_CAPACITY:
+        raise SystemExit("the plane's chain exceeds its boot store")
+    header = BOOTSTORE_HEADER.pack(
+        BOOTSTORE_MAGIC,
+        BOOTSTORE_VERSION,
+        BOOTSTORE_HEADER.size,
+        0,
+        len(entries),
+        0,
+        len(directory),
+        BOOTSTORE_CAPACITY,
+        bytes(32),
+    )
+    place_bytes(image, BOOTSTORE_DIRECTORY_OFFSET, header + directory)
+    place_bytes(
+        image,
+        BOOTSTORE_DIRECTORY_OFFSET + BOOTSTORE_HEADER_CHECKSUM_OFFSET,
+        bootstore_checksum(image),
+    )
+    generation_root = sha256(b"".join(identity for identity, _, _ in entries))
+    place_bytes(
+        image,
+        0,
+        known_good_bootstate(releases[0][0], generation_root, sha256(plane_state_map())),
+    )
+    # The candidate's verdict at the release it would be accepted at, one past
+    # the known-good's.
+    place_bytes(
+        image,
+        BOOTSTORE_EVIDENCE_OFFSET,
+        shadow_verdict(releases[1][0], releases[0][0], 2),
+    )
+    return bytes(image)
+
+
+def place_bytes(image: bytearray, offset: int, data: bytes) -> None:
+    image[offset : offset + len(data)] = data
+
+
 def boot_selection_image(bootstore: bytes) -> bytearray:
     """Build a GPT disk whose store partition is exactly one boot-store image."""
     partition_sectors = len(bootstore) // SECTOR
@@ -339,7 +695,7 @@ def boot_selection_image(bootstore: bytes) -> bytearray:
     return image
 
 
-def build(variant: str) -> bytearray:
+def build(variant: str, generation: bytes | None = None) -> bytearray:
     image = bytearray(CAPACITY * SECTOR)
 
     # Protective MBR: one 0xEE entry spanning the disk plus the signature.
@@ -366,12 +722,12 @@ def build(variant: str) -> bytearray:
     # append pointer at the first free record sector, before every fixture-only
     # region asserted above.
     seeded = seeded_payload()
-    place(image, STORE_FIRST + 0, superblock(2, SEEDED_APPEND_LBA, 1))
-    place(image, STORE_FIRST + 1, superblock(1, RECORD_AREA_START, 0))
-    place(image, STORE_FIRST + RECORD_AREA_START, record(SEEDED_TYPE, seeded))
+    commit(image, 0, 2, SEEDED_APPEND_LBA, [(SEEDED_TYPE, seeded, RECORD_AREA_START)])
+    commit(image, 1, 1, RECORD_AREA_START, [])
+    place(image, STORE_FIRST + RECORD_AREA_START, record(SEEDED_TYPE, seeded, 2))
 
     if variant == "superblock-newest-damaged":
-        damaged = bytearray(superblock(2, SEEDED_APPEND_LBA, 1))
+        damaged = bytearray(image[STORE_FIRST * SECTOR : (STORE_FIRST + 1) * SECTOR])
         damaged[60] ^= 0xFF
         place(image, STORE_FIRST + 0, bytes(damaged))
     elif variant == "superblock-both-damaged":
@@ -384,8 +740,8 @@ def build(variant: str) -> bytearray:
         # truncated garbage. The committed append_lba still excludes it.
         garbage = bytearray(SECTOR)
         struct.pack_into("<8s", garbage, 0, RECORD_MAGIC)
-        struct.pack_into("<I", garbage, 8, FORMAT_VERSION)
-        struct.pack_into("<I", garbage, 24, 0xFFFF_FFFF)
+        struct.pack_into("<I", garbage, STORE_RECORD_FORMAT_VERSION_OFFSET, FORMAT_VERSION)
+        struct.pack_into("<I", garbage, STORE_RECORD_PAYLOAD_LEN_OFFSET, 0xFFFF_FFFF)
         place(image, STORE_FIRST + SEEDED_APPEND_LBA, bytes(garbage))
 
     if variant == "recovery":
@@ -397,12 +753,67 @@ def build(variant: str) -> bytearray:
             corrupt[8:12] = struct.pack("<I", 1)
             corrupt[64:96] = bytes([0xEE]) * 32
             place(image, STORE_FIRST + slot, bytes(corrupt))
+        # The rotated trust root, committed beside the seeded object in the
+        # other superblock slot so the store's newest root holds both.
+        trust_root = record(TRUST_ROOT_OBJECT_TYPE, recovery_trust_root(), 3)
+        place(image, STORE_FIRST + SEEDED_APPEND_LBA, trust_root)
+        commit(
+            image,
+            1,
+            3,
+            SEEDED_APPEND_LBA + len(trust_root) // SECTOR,
+            [
+                (SEEDED_TYPE, seeded, RECORD_AREA_START),
+                (TRUST_ROOT_OBJECT_TYPE, recovery_trust_root(), SEEDED_APPEND_LBA),
+            ],
+        )
         index = recovery_index(sha256(seeded))
         if len(index) > RECOVERY_INDEX_SECTORS * SECTOR:
             raise SystemExit("recovery index exceeds its reserved sectors")
         padded = index + bytes(RECOVERY_INDEX_SECTORS * SECTOR - len(index))
         place(image, STORE_FIRST + RECOVERY_INDEX_LBA, padded)
 
+    if variant == "generation":
+        # A second commit, into the genesis slot: the seeded object, the
+        # chain's generations, and then one release per generation, each
+        # record's header naming the generation it is for so a reader can find
+        # it by identity. The known-good's release is the one at sequence 1,
+        # which is how the manager finds the generation it starts from. The
+        # known-good state and its map come first.
+        if generation is None:
+            raise SystemExit("the generation variant requires --generation")
+        chain, releases = plane_chain(generation)
+        entries = [(SEEDED_TYPE, seeded, RECORD_AREA_START)]
+        cursor = SEEDED_APPEND_LBA
+        records = [(SEEDED_TYPE, payload, bytes(32)) for _, payload in PLANE_STATE]
+        records.append((STATE_MAP_OBJECT_TYPE, plane_state_map(), bytes(32)))
+        for body in chain:
+            if len(body) > MAX_OBJECT_PAYLOAD:
+                raise SystemExit("a plane generation does not fit one store record")
+            records.append((GENERATION_OBJECT_TYPE, body, generation_identity_of(body)))
+        records += [(RELEASE_OBJECT_TYPE, payload, identity) for identity, payload in releases]
+        # After every version-1 release, so each lookup that takes a
+        # generation's first release still finds the one it stages by.
+        candidate = chain[1]
+        records.append(
+            (
+                RELEASE_OBJECT_TYPE,
+                build_release(candidate, 2, trust_root_version=PLANE_ROTATED_VERSION),
+                generation_identity_of(candidate),
+            )
+        )
+        records.append((ROTATION_OBJECT_TYPE, plane_rotation(), bytes(32)))
+        for obj_type, payload, identity in records:
+            data = record(obj_type, payload, 3, identity)
+            place(image, STORE_FIRST + cursor, data)
+            entries.append((obj_type, payload, cursor))
+            cursor += len(data) // SECTOR
+        if cursor > min(start for start, _, _ in FIXTURE_REGIONS):
+            raise SystemExit("generation and release records overlap fixture regions")
+        if cursor > COMPACTION_THRESHOLD:
+            raise SystemExit("generation and release records start the plane past its compaction threshold")
+        commit(image, 1, 3, cursor, entries)
+
     if variant == "transfer":
         manifest = transfer_manifest()
         if len(manifest) > TRANSFER_MANIFEST_SECTORS * SECTOR:
@@ -418,7 +829,13 @@ def main() -> None:
     parser.add_argument("image", type=Path)
     parser.add_argument("variant", choices=VARIANTS)
     parser.add_argument("--boot-store", type=Path)
+    parser.add_argument("--generation", type=Path, help="the plane generation the generation variant derives from")
+    parser.add_argument("--boot-disk", type=Path, help="where the generation variant writes its boot disk")
     arguments = parser.parse_args()
+    if arguments.generation is not None and arguments.variant != "generation":
+        raise SystemExit("--generation is only valid with generation")
+    if (arguments.boot_disk is not None) != (arguments.variant == "generation"):
+        raise SystemExit("--boot-disk is required with, and only valid with, generation")
     if arguments.variant == "boot-selection":
         if arguments.boot_store is None:
             raise SystemExit("boot-selection requires --boot-store")
@@ -426,8 +843,16 @@ def main() -> None:
     else:
         if arguments.boot_store is not None:
             raise SystemExit("--boot-store is only valid with boot-selection")
-        image = build(arguments.variant)
+        image = build(
+            arguments.variant,
+            arguments.generation.read_bytes() if arguments.generation is not None else None,
+        )
     arguments.image.write_bytes(image)
+    if arguments.boot_disk is not None:
+        # One sector past the boot store, where the manager writes the root a
+        # rotation installs for a boot-selector root to read back.
+        bootstore = plane_boot_store(arguments.generation.read_bytes()) + bytes(SECTOR)
+        arguments.boot_disk.write_bytes(boot_selection_image(bootstore))
     print(
         f"Built {arguments.image} variant={arguments.variant} "
         f"({len(image)} bytes, seeded sha256:{hashlib.sha256(seeded_payload()).hexdigest()})"
@@ -23,7 +23,13 @@ from boot_contracts import (
     TRANSFER_HEADER_OBJECT_OFFSET_OFFSET,
     TRANSFER_HEADER_RELEASE_SEQUENCE_OFFSET,
     TRANSFER_HEADER_TOTAL_LEN_OFFSET,
+    TRANSFER_HOLDINGS,
+    TRANSFER_HOLDINGS_HASH_END,
+    TRANSFER_HOLDINGS_HASH_OFFSET,
+    TRANSFER_HOLDINGS_HEADER_BYTES,
+    TRANSFER_HOLDINGS_MAGIC,
     TRANSFER_MAGIC,
+    TRANSFER_MAX_HELD_DIGESTS,
     TRANSFER_VERSION,
 )
 from boot_contracts import (
@@ -103,7 +109,7 @@ def parse_generation(data: bytes) -> dict:
         )
     state_rows = []
     for index in range(states):
-        name_offset, _, schema_version, policy = CHECK.GENERATION_STATE.unpack_from(
+        name_offset, _, schema_version, policy, _, _ = CHECK.GENERATION_STATE.unpack_from(
             data, state_offset + index * CHECK.GENERATION_STATE.size
         )
         state_rows.append(
@@ -124,7 +130,47 @@ def parse_generation(data: bytes) -> dict:
     return checked
 
 
-def build_bundle(receiver: bytes, source: bytes, release: bytes, state_root: bytes) -> bytes:
+def decode_holdings(data: bytes) -> set[bytes]:
+    """The receiver's answer: the digests its store already holds.
+
+    Refused on the same terms as `transfer::Holdings::decode`. The receiver
+    still verifies every omitted object against its store, so a wrong answer
+    costs a failed import, never a wrong one.
+    """
+    if len(data) < TRANSFER_HOLDINGS_HEADER_BYTES:
+        raise SystemExit("holdings record is truncated")
+    magic, version, header_size, flags, count, reserved, digest = TRANSFER_HOLDINGS.unpack_from(data)
+    if magic != TRANSFER_HOLDINGS_MAGIC or version != VERSION or header_size != TRANSFER_HOLDINGS_HEADER_BYTES:
+        raise SystemExit("holdings record has an unsupported header")
+    if flags or reserved:
+        raise SystemExit("holdings record sets reserved bits")
+    if count > TRANSFER_MAX_HELD_DIGESTS or len(data) != TRANSFER_HOLDINGS_HEADER_BYTES + 32 * count:
+        raise SystemExit("holdings record is out of bounds")
+    sealed = data[:TRANSFER_HOLDINGS_HASH_OFFSET] + bytes(32) + data[TRANSFER_HOLDINGS_HASH_END:]
+    if hashlib.sha256(sealed).digest() != digest:
+        raise SystemExit("holdings record fails its digest")
+    digests = [
+        data[offset : offset + 32]
+        for offset in range(TRANSFER_HOLDINGS_HEADER_BYTES, len(data), 32)
+    ]
+    if any(previous >= following for previous, following in zip(digests, digests[1:], strict=False)):
+        raise SystemExit("holdings digests are not strictly ascending")
+    return set(digests)
+
+
+def build_bundle(
+    receiver: bytes,
+    source: bytes,
+    release: bytes,
+    state_root: bytes,
+    holdings: set[bytes] | None = None,
+) -> bytes:
+    """A transfer manifest carrying the source generation.
+
+    With `holdings` the transfer is negotiated: an object whose digest the
+    receiver answered for travels without its payload. Without it, the
+    receiver generation's own objects are taken as held.
+    """
     receiver_generation = parse_generation(receiver)
     source_generation = parse_generation(source)
     if source_generation["parent"] != receiver_generation["identity"]:
@@ -136,7 +182,11 @@ def build_bundle(receiver: bytes, source: bytes, release: bytes, state_root: byt
     receiver_objects = {obj["digest"]: obj for obj in receiver_generation["objects"]}
     for obj in source_generation["objects"]:
         payload = None
-        if receiver_objects.get(obj["digest"], {}).get("length") != obj["length"]:
+        if holdings is not None:
+            held = obj["digest"] in holdings
+        else:
+            held = receiver_objects.get(obj["digest"], {}).get("length") == obj["length"]
+        if not held:
             payload = source[obj["payload_offset"] : obj["payload_offset"] + obj["length"]]
         objects.append((obj, payload))
 
@@ -221,6 +271,11 @@ def main() -> None:
     parser.add_argument("source", type=Path)
     parser.add_argument("release", type=Path)
     parser.add_argument("--state-root", required=True)
+    parser.add_argument(
+        "--holdings",
+        type=Path,
+        help="the receiver's holdings record; objects it names travel without payload",
+    )
     parser.add_argument("output", type=Path)
     args = parser.parse_args()
     state_root = bytes.fromhex(args.state_root)
@@ -232,6 +287,7 @@ def main() -> None:
             args.source.read_bytes(),
             args.release.read_bytes(),
             state_root,
+            decode_holdings(args.holdings.read_bytes()) if args.holdings else None,
         )
     )
 if __name__ == "__main__":
@@ -0,0 +1,68 @@
+#!/usr/bin/env python3
+"""Enforce that only the root's product timer adapter names the architected timer in source."""
+
+from __future__ import annotations
+
+import re
+from pathlib import Path
+
+ROOT = Path(__file__).resolve().parents[2]
+# The product `PlatformTimer` backing. The MCS backing
+# (`slime-root/src/timeout_timer.rs`) counts time by a scheduling context's
+# replenishments and must not read the counter, because its kernel no longer
+# exports it. Everything else is meant to observe time through a
+# generation-declared clock capability served by `slime-root/src/clock.rs`.
+EXPECTED_READERS = {
+    "slime-root/src/platform_timer.rs",
+}
+# Any generic-timer system register, by name or by its `S3_<op1>_C14_<CRm>_<op2>`
+# encoding: the counters, their frequency, and the timer controls.
+# The MCS kernel exports neither, so there a component that named one would
+# fault. The product and Raspberry Pi kernels export both, because the
+# non-MCS kernel gives the root no other time source; on them this remains a
+# source-level rule: a component that named one would see time no grant gave
+# it.
+TIMER_REGISTER = re.compile(
+    r"\bcnt[a-z_]*_el[0-3]\b|\bs3_[0-7]_c14_c(?:1[0-5]|[0-9])_[0-7]\b",
+    re.IGNORECASE,
+)
+# Rust, and assembly in either preprocessed or plain form.
+SUFFIXES = {".rs", ".S", ".s"}
+SKIPPED = {"target", ".git"}
+# The top-level build output and pinned upstream checkouts, whose kernel
+# programs the timer by design and is not a component.
+SKIPPED_ROOTS = {"build", "deps"}
+
+
+def fail(message: str) -> None:
+    raise SystemExit(f"clock authority check: {message}")
+
+
+def sources() -> list[Path]:
+    return sorted(
+        path
+        for path in ROOT.rglob("*")
+        if path.suffix in SUFFIXES
+        and path.is_file()
+        and not SKIPPED.intersection(path.relative_to(ROOT).parts)
+        and path.relative_to(ROOT).parts[0] not in SKIPPED_ROOTS
+    )
+
+
+def main() -> None:
+    readers = {
+        path.relative_to(ROOT).as_posix()
+        for path in sources()
+        if TIMER_REGISTER.search(path.read_text(encoding="utf-8"))
+    }
+    added = sorted(readers - EXPECTED_READERS)
+    if added:
+        fail(f"timer registers read outside the root's product timer adapter: {added}")
+    missing = sorted(EXPECTED_READERS - readers)
+    if missing:
+        fail(f"expected timer readers no longer read the timer; update the allowlist: {missing}")
+    print(f"clock authority check: only {len(readers)} root timer adapter names the architected timer")
+
+
+if __name__ == "__main__":
+    main()
@@ -22,6 +22,7 @@ COMPONENT_BINDING_GENERATOR = ROOT / "scripts" / "generate" / "generate-componen
 STORE_CONTRACT = ROOT / "contracts" / "store" / "v1"
 STORE_BINDING_GENERATOR = ROOT / "scripts" / "generate" / "generate-store-bindings.py"
 FS_CONTRACT = ROOT / "contracts" / "fs" / "v1"
+FS_V2_CONTRACT = ROOT / "contracts" / "fs" / "v2"
 FS_BINDING_GENERATOR = ROOT / "scripts" / "generate" / "generate-fs-bindings.py"
 GENERATION_MANAGEMENT_CONTRACT = ROOT / "contracts" / "generation-management" / "v1"
 GENERATION_MANAGEMENT_BINDING_GENERATOR = ROOT / "scripts" / "generate" / "generate-generation-management-bindings.py"
@@ -29,13 +30,15 @@ POWERBOX_CONTRACT = ROOT / "contracts" / "powerbox" / "v1"
 POWERBOX_BINDING_GENERATOR = ROOT / "scripts" / "generate" / "generate-powerbox-bindings.py"
 BOOT_BINDING_GENERATOR = ROOT / "scripts" / "generate" / "generate-boot-bindings.py"
 # Retained wire-format history. Nothing generates from these — `boot_gen` wires
-# only v5 — but the format's history is part of the contract (see
-# `check-generation-v5.py`), so they are type-checked rather than left to rot.
+# only v6 — but the format's history is part of the contract (see
+# `check-generation-v6.py`), so they are type-checked rather than left to rot.
 # B64: v4 was the one retained version no gate touched, so it could have stopped
-# parsing without anything noticing.
+# parsing without anything noticing. v5 joined them when v6 took over
+# generation.
 GENERATION_V2_CONTRACT = ROOT / "contracts" / "generation" / "v2"
 GENERATION_V3_CONTRACT = ROOT / "contracts" / "generation" / "v3"
 GENERATION_V4_CONTRACT = ROOT / "contracts" / "generation" / "v4"
+GENERATION_V5_CONTRACT = ROOT / "contracts" / "generation" / "v5"
 KERNEL_IMAGE_CONTRACT = ROOT / "contracts" / "kernel-image" / "v1"
 KERNEL_IMAGE_V2_CONTRACT = ROOT / "contracts" / "kernel-image" / "v2"
 TARGET_PROFILE_CONTRACT = ROOT / "contracts" / "target-profile" / "v1"
@@ -154,6 +157,8 @@ subprocess.run(
 
 run("check", str(FS_CONTRACT / "schema.zt"))
 run("check", str(FS_CONTRACT / "gen_rust.zt"))
+run("check", str(FS_V2_CONTRACT / "schema.zt"))
+run("check", str(FS_V2_CONTRACT / "gen_rust.zt"))
 subprocess.run(
     [sys.executable, str(FS_BINDING_GENERATOR), "--check"],
     cwd=ROOT,
@@ -285,6 +290,7 @@ for contract in (
     GENERATION_V2_CONTRACT,
     GENERATION_V3_CONTRACT,
     GENERATION_V4_CONTRACT,
+    GENERATION_V5_CONTRACT,
     KERNEL_IMAGE_CONTRACT,
     KERNEL_IMAGE_V2_CONTRACT,
     COMPONENT_V2_CONTRACT,
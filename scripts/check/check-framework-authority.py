@@ -45,7 +45,8 @@ def main() -> None:
     actual: dict[str, str] = {}
     for path in sorted(FIXTURES.glob("sel4*.zti")):
         holders = writers(path)
-        if len(holders) > 1:
+        # One holder may be granted several devices — a mirror's two disks.
+        if len(set(holders)) > 1:
             fail(f"{path.relative_to(ROOT)} grants blockWrite to multiple holders: {holders}")
         if holders:
             actual[path.name] = holders[0]
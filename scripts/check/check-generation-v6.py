@@ -1,11 +1,12 @@
 #!/usr/bin/env python3
-"""Every generation this repository builds is v5, and nothing still writes v4.
+"""Every generation this repository builds is v6, and nothing still writes v5.
 
-B50's exit condition asks that "every fixture uses v5". The wire format cut
-over at `8745d18`, and the v4 binding generator is still on disk under
-`contracts/generation/v4/` because the format's history is part of the
-contract. What must not survive is a *producer*: a manifest the builder still
-encodes as v4, or a second `GENERATION_VERSION` that some path selects.
+B50's exit condition asked that "every fixture uses v5"; v6's declared health
+probes replaced it the same way, and this check moved with the format. The v4
+and v5 binding generators are still on disk under `contracts/generation/`
+because the format's history is part of the contract. What must not survive is
+a *producer*: a manifest the builder still encodes as v5, or a second
+`GENERATION_VERSION` that some path selects.
 
 Checked by building, not by reading. A manifest can declare whatever it likes
 in `formatVersion` -- that field is the *manifest* schema's version, not the
@@ -26,12 +27,12 @@ ROOT = Path(__file__).resolve().parents[2]
 sys.path.insert(0, str(ROOT / "scripts" / "lib"))
 sys.path.insert(0, str(ROOT / "scripts" / "build"))
 
-EXPECTED_MAGIC = b"SLIMEG5\0"
-EXPECTED_VERSION = 5
+EXPECTED_MAGIC = b"SLIMEG6\0"
+EXPECTED_VERSION = 6
 
 
 def fail(message: str) -> None:
-    print(f"generation v5 check: {message}", file=sys.stderr)
+    print(f"generation v6 check: {message}", file=sys.stderr)
     raise SystemExit(1)
 
 
@@ -39,11 +40,11 @@ def load_builder():
     import importlib.util
 
     path = ROOT / "scripts" / "build" / "build-generation.py"
-    spec = importlib.util.spec_from_file_location("build_generation_v5", path)
+    spec = importlib.util.spec_from_file_location("build_generation_v6", path)
     if spec is None or spec.loader is None:
         fail(f"cannot load {path.relative_to(ROOT)}")
     module = importlib.util.module_from_spec(spec)
-    sys.modules["build_generation_v5"] = module
+    sys.modules["build_generation_v6"] = module
     spec.loader.exec_module(module)
     return module
 
@@ -51,7 +52,7 @@ def load_builder():
 def check_single_version(builder) -> None:
     """One version constant, and it is the one the decoder expects.
 
-    A second constant is how a v4 path survives a cutover: the format stays
+    A second constant is how a v5 path survives a cutover: the format stays
     described in one place while some manifest quietly selects the other.
     """
     if builder.GENERATION_VERSION != EXPECTED_VERSION:
@@ -66,12 +67,18 @@ def check_single_version(builder) -> None:
         fail(f"builder writes magic {magic!r}, expected {EXPECTED_MAGIC!r}")
 
 
-def built_header(manifest: str) -> tuple[bytes, int]:
-    """Build one manifest and return the magic and version it encodes."""
+def built_header(manifest: str, mcs: bool) -> tuple[bytes, int]:
+    """Build one manifest and return the magic and version it encodes.
+
+    `mcs` builds for the MCS kernel profile, the only one that admits a
+    manifest declaring reservations.
+    """
     with tempfile.TemporaryDirectory() as directory:
         environment = os.environ.copy()
         environment["SLIME_TARGET_PROFILE"] = "aarch64-sel4-qemu-virt"
         environment["SLIME_SEL4_MANIFEST"] = manifest
+        if mcs:
+            environment["SLIME_SEL4_KERNEL_MCS"] = "1"
         result = subprocess.run(
             [
                 sys.executable,
@@ -103,7 +110,7 @@ def main() -> None:
     if not manifests:
         fail("the builder declares no seL4 manifests")
     for manifest in manifests:
-        magic, version = built_header(manifest)
+        magic, version = built_header(manifest, manifest in builder.SEL4_MCS_MANIFESTS)
         if magic != EXPECTED_MAGIC or version != EXPECTED_VERSION:
             fail(
                 f"{manifest} encodes magic {magic!r} version {version}, "
@@ -111,7 +118,7 @@ def main() -> None:
             )
     label = EXPECTED_MAGIC.rstrip(b"\0").decode()
     print(
-        f"generation v5 check: all {len(manifests)} seL4 manifests encode "
+        f"generation v6 check: all {len(manifests)} seL4 manifests encode "
         f"{label} version {EXPECTED_VERSION}",
         flush=True,
     )
@@ -132,7 +132,7 @@ def check_component_image(blob: bytes, profile, name: str) -> None:
 
 # Rights numbering is generated-contract truth: these alias the
 # `GENERATION_RIGHT_*` constants `boot_contracts` emits from
-# `contracts/generation/v5/schema.zt`. `RIGHT_ALL` is the union of the named
+# `contracts/generation/v6/schema.zt`. `RIGHT_ALL` is the union of the named
 # bits, not a bit-width mask, so an undefined position such as bit 17 is
 # rejected here rather than admitted (B57).
 RIGHT_TRANSFER = GENERATION_RIGHT_TRANSFER
@@ -148,6 +148,10 @@ CAPABILITY_INPUT = 6
 CAPABILITY_SUPERVISION = 7
 CAPABILITY_SHARED_BUFFER = 8
 CAPABILITY_LOAN = 9
+CAPABILITY_SCHEDULING_CONTROL = 10
+CAPABILITY_MONOTONIC_CLOCK = 11
+CAPABILITY_WALL_CLOCK = 12
+CAPABILITY_SIMULATED_CLOCK = 13
 
 
 def capability_rights_valid(kind: int, rights: int) -> bool:
@@ -161,6 +165,10 @@ def capability_rights_valid(kind: int, rights: int) -> bool:
         CAPABILITY_SUPERVISION: (1 << 18) | RIGHT_TRANSFER,
         CAPABILITY_SHARED_BUFFER: (1 << 8) | (1 << 9) | (1 << 25) | RIGHT_TRANSFER,
         CAPABILITY_LOAN: (1 << 8) | (1 << 9) | RIGHT_TRANSFER,
+        CAPABILITY_SCHEDULING_CONTROL: 1 << 26,
+        CAPABILITY_MONOTONIC_CLOCK: 1 << 27,
+        CAPABILITY_WALL_CLOCK: 1 << 27,
+        CAPABILITY_SIMULATED_CLOCK: (1 << 27) | (1 << 28),
     }.get(kind)
     required = {
         CAPABILITY_ENDPOINT: 0b11,
@@ -172,6 +180,10 @@ def capability_rights_valid(kind: int, rights: int) -> bool:
         CAPABILITY_SUPERVISION: 1 << 18,
         CAPABILITY_SHARED_BUFFER: (1 << 8) | (1 << 9) | (1 << 25),
         CAPABILITY_LOAN: 1 << 9,
+        CAPABILITY_SCHEDULING_CONTROL: 1 << 26,
+        CAPABILITY_MONOTONIC_CLOCK: 1 << 27,
+        CAPABILITY_WALL_CLOCK: 1 << 27,
+        CAPABILITY_SIMULATED_CLOCK: 1 << 27,
     }.get(kind, 0)
     return (
         allowed is not None
@@ -180,6 +192,7 @@ def capability_rights_valid(kind: int, rights: int) -> bool:
         and bool(rights & required)
         and (kind != CAPABILITY_EXECUTABLE or rights & (RIGHT_EXEC | RIGHT_SPAWN) == RIGHT_EXEC | RIGHT_SPAWN)
         and (kind != CAPABILITY_INPUT or rights == 1 << 23)
+        and (kind != CAPABILITY_SCHEDULING_CONTROL or rights == 1 << 26)
     )
 MAX_SPAWN_BUDGET = 32
 PLAN_NONE = 0xFFFFFFFF
@@ -202,6 +215,10 @@ SERVICE_BY_CAPABILITY_KIND = {
     CAPABILITY_INPUT: SERVICE_INPUT,
     CAPABILITY_BLOCK: SERVICE_BLOCK,
     CAPABILITY_SUPERVISION: SERVICE_SUPERVISION,
+    CAPABILITY_SCHEDULING_CONTROL: SERVICE_SUPERVISION,
+    CAPABILITY_MONOTONIC_CLOCK: SERVICE_LIFECYCLE,
+    CAPABILITY_WALL_CLOCK: SERVICE_LIFECYCLE,
+    CAPABILITY_SIMULATED_CLOCK: SERVICE_LIFECYCLE,
 }
 SHARED_BUFFER_BUDGET_MAGIC = b"SLIMESB\0"
 SHARED_BUFFER_BUDGET_HEADER = struct.Struct("<8sIIQII")
@@ -406,12 +423,27 @@ def check_generation(data: bytes, expected_identity: bytes | None = None) -> dic
         previous_grant = key
     previous_state = ""
     for index in range(states):
-        name_offset, owner, schema_version, policy = GENERATION_STATE.unpack_from(data, state_offset + index * GENERATION_STATE.size)
+        name_offset, owner, schema_version, policy, migrate_from, migrator = GENERATION_STATE.unpack_from(
+            data, state_offset + index * GENERATION_STATE.size
+        )
         name = read_string(data, strings_offset, strings_len, name_offset)
         require(name > previous_state and owner < instances and schema_version > 0 and policy in (1, 2, 3, 4, 5), "BadState")
+        require(
+            migrator == 0 if migrate_from == 0 else migrate_from != schema_version and migrator < executables,
+            "BadState",
+        )
         previous_state = name
     require(boot_attempts > 0, "BadHealthPolicy")
-    health_rows = [GENERATION_HEALTH.unpack_from(data, health_offset + index * GENERATION_HEALTH.size)[0] for index in range(health)]
+    health_rows = []
+    for index in range(health):
+        instance, probes, window, reserved, route = GENERATION_HEALTH.unpack_from(
+            data, health_offset + index * GENERATION_HEALTH.size
+        )
+        require(reserved == 0 and probes != 0, "BadHealth")
+        require(probes & ~GENERATION_HEALTH_PROBE_ALL == 0, "BadHealth")
+        require(bool(probes & GENERATION_HEALTH_PROBE_NO_FAULT) == (window != 0), "BadHealth")
+        require(bool(probes & GENERATION_HEALTH_PROBE_ROUTE) == (route != bytes(32)), "BadHealth")
+        health_rows.append(instance)
     require(all(instance < instances for instance in health_rows) and health_rows == sorted(set(health_rows)), "BadHealthInstance")
     require(set(health_rows) == {index for index, row in enumerate(instance_rows) if row[9]}, "BadHealthPolicy")
     require(processes == instances == threads == schedules == fault_policies == resource_quotas, "BadPlanShape")
@@ -564,6 +596,7 @@ def check_generation(data: bytes, expected_identity: bytes | None = None) -> dic
     for index in range(resource_quotas):
         name_offset, owner_process, cnode_count, tcb_count, endpoint_count, notification_count, frame_count, page_table_count, mapping_count, irq_count, cslot_count, untyped_bytes, dynamic_reserve_bytes, flags = GENERATION_RESOURCE_QUOTA.unpack_from(data, resource_quota_offset + index * GENERATION_RESOURCE_QUOTA.size)
         require(owner_process < processes and cnode_count > 0 and tcb_count > 0 and cslot_count > 0 and flags == 0, "BadResourceQuota")
+        require(dynamic_reserve_bytes % 4096 == 0, "BadResourceQuota")
         require(process_rows[owner_process]["quota"] == index, "BadResourceQuota")
     previous_minted = ""
     seen_minted_slots: set[tuple[int, int]] = set()
@@ -598,7 +631,7 @@ def decode_bootstate(slot: bytes) -> dict:
     require(len(slot) == BOOTSTATE_SLOT_BYTES and slot[BOOTSTATE_MAGIC_OFFSET:BOOTSTATE_MAGIC_END] == BOOTSTATE_MAGIC, "BadBootStateMagic")
     version, header, flags, sequence = __import__("struct").unpack_from("<IIQQ", slot, BOOTSTATE_FORMAT_VERSION_OFFSET)
     require(version == BOOTSTATE_VERSION and header == BOOTSTATE_SLOT_BYTES and flags == 0, "BadBootStateVersion")
-    require(sequence != 2**64 - 1 and not any(slot[BOOTSTATE_RESERVED_OFFSET:BOOTSTATE_RESERVED_END]) and not any(slot[BOOTSTATE_CHECKSUM_END:]), "BadBootStateReserved")
+    require(sequence != 2**64 - 1 and not any(slot[BOOTSTATE_RESERVED_OFFSET:BOOTSTATE_RESERVED_END]) and not any(slot[BOOTSTATE_TRUST_ROOT_OFFSET:BOOTSTATE_TRUST_ROOT_VERSION_END]) and not any(slot[BOOTSTATE_ROLLBACK_STATE_ROOT_END:]), "BadBootStateReserved")
     require(slot[BOOTSTATE_CHECKSUM_OFFSET:BOOTSTATE_CHECKSUM_END] == bootstate_checksum(slot), "BadBootStateChecksum")
     known_good = slot[BOOTSTATE_KNOWN_GOOD_OFFSET:BOOTSTATE_KNOWN_GOOD_END]
     pending = slot[BOOTSTATE_PENDING_OFFSET:BOOTSTATE_PENDING_END]
@@ -608,17 +641,26 @@ def decode_bootstate(slot: bytes) -> dict:
     accepted_release_sequence = int.from_bytes(slot[BOOTSTATE_ACCEPTED_RELEASE_SEQUENCE_OFFSET:BOOTSTATE_ACCEPTED_RELEASE_SEQUENCE_END], "little")
     require(known_good != bytes(32) and generation_root != bytes(32), "BadBootStateRoot")
     require((pending == bytes(32) and attempts == 0) or pending != bytes(32), "BadPendingAttempts")
+    rollback_state_root = slot[BOOTSTATE_ROLLBACK_STATE_ROOT_OFFSET:BOOTSTATE_ROLLBACK_STATE_ROOT_END]
+    require((pending == bytes(32)) == (rollback_state_root == bytes(32)), "BadRollbackStateRoot")
     return {"sequence": sequence, "known_good": known_good, "pending": None if pending == bytes(32) else pending, "remaining_attempts": attempts, "generation_root": generation_root, "state_root": state_root, "accepted_release_sequence": accepted_release_sequence}
 
 
 def check_release(data: bytes, generation: bytes, accepted_sequence: int | None = None) -> int:
     require(len(data) == RELEASE_BYTES and data[:8] == RELEASE_MAGIC, "BadReleaseMagic")
     version, header, flags = struct.unpack_from("<IIQ", data, RELEASE_HEADER_FORMAT_VERSION_OFFSET)
-    require(version == RELEASE_VERSION and header == RELEASE_HEADER_BYTES and flags == 0, "BadReleaseVersion")
+    require(
+        version == RELEASE_VERSION
+        and header == RELEASE_HEADER_BYTES
+        and flags & ~RELEASE_FLAG_AUTHORITY_WIDENING == 0,
+        "BadReleaseVersion",
+    )
     sequence, target_len, trust_version = struct.unpack_from("<QII", data, RELEASE_HEADER_RELEASE_SEQUENCE_OFFSET)
     signature_count = struct.unpack_from("<I", data, RELEASE_HEADER_SIGNATURE_COUNT_OFFSET)[0]
     require(1 <= target_len <= MAX_TARGET_BYTES and trust_version == 1, "BadReleaseBounds")
     require(2 <= signature_count <= MAX_RELEASE_SIGNATURES and not any(data[RELEASE_HEADER_RESERVED_OFFSET:RELEASE_HEADER_RESERVED_END]), "BadReleaseSignatures")
+    approved = data[RELEASE_HEADER_APPROVED_WIDENINGS_OFFSET:RELEASE_HEADER_APPROVED_WIDENINGS_END]
+    require(flags & RELEASE_FLAG_AUTHORITY_WIDENING != 0 or approved == bytes(32), "UnflaggedWideningDigest")
     generation_info = check_generation(generation)
     require(data[RELEASE_HEADER_GENERATION_IDENTITY_OFFSET:RELEASE_HEADER_GENERATION_IDENTITY_END] == generation_info["identity"], "WrongReleaseGeneration")
     parent = generation_info["parent"] or bytes(32)
@@ -672,6 +714,11 @@ def check_bootstore(data: bytes) -> dict:
     require(magic == BOOTSTORE_MAGIC and version == BOOTSTORE_VERSION and header_size == BOOTSTORE_HEADER.size, "BadBootStoreVersion")
     require(flags == 0 and reserved == 0 and 1 <= count <= 64 and directory_len == count * BOOTSTORE_ENTRY.size and capacity == len(data), "BadBootStoreHeader")
     require(checksum == bootstore_checksum(data), "BadBootStoreChecksum")
+    # The verdict sector is written by a booted root, never by the builder: a
+    # store that ships with one would promote or roll back a generation no
+    # probe ever ran against.
+    verdict = data[BOOTSTORE_VERDICT_OFFSET:BOOTSTORE_SEALED_OFFSET]
+    require(verdict == bytes(len(verdict)), "PrebuiltHealthVerdict")
     slots = []
     for label, offset in (("A", 0), ("B", BOOTSTATE_SLOT_BYTES)):
         try:
@@ -222,47 +222,13 @@ def ssh_rotation_payload(payload: bytes) -> bytes:
     )
 
 
-def build_rotation(
-    current_keys: tuple[Path, ...],
-    replacement_keys: tuple[Path, ...],
-    previous_version: int,
-    replacement_version: int,
-) -> bytes:
-    rotation = bytearray(CONTRACTS.ROTATION_BYTES)
-    rotation[:8] = CONTRACTS.ROTATION_MAGIC
-    struct.pack_into("<IIQ", rotation, 8, CONTRACTS.ROTATION_VERSION, CONTRACTS.ROTATION_HEADER_BYTES, 0)
-    struct.pack_into(
-        "<IIIIII",
-        rotation,
-        24,
-        previous_version,
-        replacement_version,
-        2,
-        len(replacement_keys),
-        len(current_keys),
-        len(replacement_keys),
-    )
-    replacement_public = tuple(TRUST.ssh_public_key(path) for path in replacement_keys)
-    for index, public in enumerate(replacement_public):
-        offset = CONTRACTS.ROTATION_HEADER_BYTES + index * 32
-        rotation[offset : offset + 32] = public
-    previous_offset = CONTRACTS.ROTATION_HEADER_BYTES + CONTRACTS.MAX_TRUST_KEYS * 32
-    replacement_offset = previous_offset + TRUST.MAX_RELEASE_SIGNATURES * TRUST.RELEASE_SIGNATURE_BYTES
-    payload = bytes(rotation[:previous_offset])
-    for base, paths in ((previous_offset, current_keys), (replacement_offset, replacement_keys)):
-        entries = sorted((TRUST.sha256(TRUST.ssh_public_key(path)), TRUST.ssh_signature(path, payload)) for path in paths)
-        for index, (key_id, signature) in enumerate(entries):
-            offset = base + index * TRUST.RELEASE_SIGNATURE_BYTES
-            rotation[offset : offset + 32] = key_id
-            rotation[offset + 32 : offset + TRUST.RELEASE_SIGNATURE_BYTES] = signature
-    return bytes(rotation)
-
-
 def verify_rotation(rotation: bytes, *, current_version: int = 1) -> None:
     if len(rotation) != CONTRACTS.ROTATION_BYTES or rotation[:8] != CONTRACTS.ROTATION_MAGIC:
         raise CHECK.CheckError("BadRotation")
     version, header, flags = struct.unpack_from("<IIQ", rotation, 8)
-    previous, replacement, threshold, key_count, previous_count, replacement_count = struct.unpack_from("<IIIIII", rotation, 24)
+    previous, replacement, threshold, key_count, previous_count, replacement_count, revoked_count = struct.unpack_from(
+        "<IIIIIII", rotation, 24
+    )
     if (
         version != CONTRACTS.ROTATION_VERSION
         or header != CONTRACTS.ROTATION_HEADER_BYTES
@@ -276,7 +242,8 @@ def verify_rotation(rotation: bytes, *, current_version: int = 1) -> None:
         or replacement_count < threshold
         or previous_count > TRUST.MAX_RELEASE_SIGNATURES
         or replacement_count > TRUST.MAX_RELEASE_SIGNATURES
-        or any(rotation[48:CONTRACTS.ROTATION_HEADER_BYTES])
+        or revoked_count > CONTRACTS.MAX_REVOKED_KEYS
+        or any(rotation[CONTRACTS.ROTATION_HEADER_REVOKED_COUNT_END : CONTRACTS.ROTATION_HEADER_BYTES])
     ):
         raise CHECK.CheckError("BadRotation")
     replacement_keys = []
@@ -287,21 +254,39 @@ def verify_rotation(rotation: bytes, *, current_version: int = 1) -> None:
         raise CHECK.CheckError("BadRotation")
     if any(rotation[CONTRACTS.ROTATION_HEADER_BYTES + key_count * 32 : CONTRACTS.ROTATION_HEADER_BYTES + CONTRACTS.MAX_TRUST_KEYS * 32]):
         raise CHECK.CheckError("BadRotation")
+    revoked_offset = CONTRACTS.ROTATION_HEADER_BYTES + CONTRACTS.MAX_TRUST_KEYS * 32
+    revoked = [rotation[revoked_offset + index * 32 : revoked_offset + index * 32 + 32] for index in range(revoked_count)]
+    if (
+        any(not any(key_id) for key_id in revoked)
+        or revoked != sorted(set(revoked))
+        or any(rotation[revoked_offset + revoked_count * 32 : revoked_offset + CONTRACTS.MAX_REVOKED_KEYS * 32])
+    ):
+        raise CHECK.CheckError("BadRotation")
     current_by_id = {TRUST.sha256(key): key for key in TRUST.initial_public_keys()}
     replacement_by_id = {TRUST.sha256(key): key for key in replacement_keys}
-    previous_offset = CONTRACTS.ROTATION_HEADER_BYTES + CONTRACTS.MAX_TRUST_KEYS * 32
+    if sum(key_id not in revoked for key_id in replacement_by_id) < threshold:
+        raise CHECK.CheckError("BadRotation")
+    previous_offset = revoked_offset + CONTRACTS.MAX_REVOKED_KEYS * 32
     replacement_offset = previous_offset + TRUST.MAX_RELEASE_SIGNATURES * TRUST.RELEASE_SIGNATURE_BYTES
     payload = rotation[:previous_offset]
-    for name, base, count, keys in (
-        ("previous", previous_offset, previous_count, current_by_id),
-        ("replacement", replacement_offset, replacement_count, replacement_by_id),
+    # The initial root revokes nothing; a revoked replacement key's signature
+    # is skipped, as `release.rs` skips it, and does not count.
+    for name, base, count, keys, skipped, required in (
+        ("previous", previous_offset, previous_count, current_by_id, (), 2),
+        ("replacement", replacement_offset, replacement_count, replacement_by_id, revoked, threshold),
     ):
         previous_key_id = bytes(32)
+        live = 0
         for index in range(count):
             offset = base + index * TRUST.RELEASE_SIGNATURE_BYTES
             key_id = rotation[offset : offset + 32]
             signature = rotation[offset + 32 : offset + TRUST.RELEASE_SIGNATURE_BYTES]
-            if key_id <= previous_key_id or key_id not in keys:
+            if key_id <= previous_key_id:
+                raise CHECK.CheckError(f"BadRotation{name}")
+            previous_key_id = key_id
+            if key_id in skipped:
+                continue
+            if key_id not in keys:
                 raise CHECK.CheckError(f"BadRotation{name}")
             process = subprocess.run(
                 [
@@ -316,7 +301,9 @@ def verify_rotation(rotation: bytes, *, current_version: int = 1) -> None:
             )
             if process.returncode != 0:
                 raise CHECK.CheckError(f"BadRotation{name}")
-            previous_key_id = key_id
+            live += 1
+        if live < required:
+            raise CHECK.CheckError(f"BadRotation{name}")
     if any(rotation[replacement_offset + replacement_count * TRUST.RELEASE_SIGNATURE_BYTES :]):
         raise CHECK.CheckError("BadRotation")
 
@@ -359,20 +346,20 @@ def expect_rust_rotation_refused(name: str, rotation: bytes) -> None:
 
 
 def test_rotation() -> None:
-    valid = build_rotation(TRUST.KEY_PATHS[:2], TRUST.KEY_PATHS, 1, 2)
+    valid = TRUST.build_rotation(TRUST.KEY_PATHS[:2], TRUST.KEY_PATHS, 1, 2)
     if rust_rotation(valid, "valid").returncode != 0:
         raise CHECK.CheckError("apply_rotation refused a well-formed rotation")
     verify_rotation(valid)
 
-    skipped = build_rotation(TRUST.KEY_PATHS[:2], TRUST.KEY_PATHS, 1, 3)
+    skipped = TRUST.build_rotation(TRUST.KEY_PATHS[:2], TRUST.KEY_PATHS, 1, 3)
     expect_error("rotation version skip", "BadRotation", lambda: verify_rotation(skipped))
     expect_rust_rotation_refused("version-skip", skipped)
 
-    no_previous_continuity = build_rotation(TRUST.KEY_PATHS[:1], TRUST.KEY_PATHS, 1, 2)
+    no_previous_continuity = TRUST.build_rotation(TRUST.KEY_PATHS[:1], TRUST.KEY_PATHS, 1, 2)
     expect_error("rotation previous continuity", "BadRotation", lambda: verify_rotation(no_previous_continuity))
     expect_rust_rotation_refused("previous-continuity", no_previous_continuity)
 
-    no_replacement_continuity = build_rotation(TRUST.KEY_PATHS[:2], TRUST.KEY_PATHS[:1], 1, 2)
+    no_replacement_continuity = TRUST.build_rotation(TRUST.KEY_PATHS[:2], TRUST.KEY_PATHS[:1], 1, 2)
     expect_error("rotation replacement continuity", "BadRotation", lambda: verify_rotation(no_replacement_continuity))
     expect_rust_rotation_refused("replacement-continuity", no_replacement_continuity)
 
@@ -386,9 +373,22 @@ def test_rotation() -> None:
     # counts instead, so neither reaches this branch — deleting
     # `previous_version != current.version` from `release.rs` left the whole gate
     # green until this fixture existed.
-    stale_previous = build_rotation(TRUST.KEY_PATHS[:2], TRUST.KEY_PATHS, 2, 2)
+    stale_previous = TRUST.build_rotation(TRUST.KEY_PATHS[:2], TRUST.KEY_PATHS, 2, 2)
     expect_rust_rotation_refused("stale-previous", stale_previous)
 
+    # Revoking one of three replacement keys leaves two live signers for a
+    # threshold of two, so the rotation stands; revoking two leaves one, and
+    # the replacement root could verify nothing.
+    key_ids = sorted(TRUST.sha256(TRUST.ssh_public_key(path)) for path in TRUST.KEY_PATHS)
+    revoking = TRUST.build_rotation(TRUST.KEY_PATHS[:2], TRUST.KEY_PATHS, 1, 2, revoked=(key_ids[0],))
+    if rust_rotation(revoking, "revoking").returncode != 0:
+        raise CHECK.CheckError("apply_rotation refused a rotation revoking one key")
+    verify_rotation(revoking)
+
+    over_revoked = TRUST.build_rotation(TRUST.KEY_PATHS[:2], TRUST.KEY_PATHS, 1, 2, revoked=tuple(key_ids[:2]))
+    expect_error("rotation revoking past the threshold", "BadRotation", lambda: verify_rotation(over_revoked))
+    expect_rust_rotation_refused("over-revoked", over_revoked)
+
 
 def main() -> None:
     generation_path, image_path = build("authorized")
@@ -402,7 +402,7 @@ def main() -> None:
 
     print(
         "release trust check: signed release, pending/stale/exhausted/promotion/directory/conflict "
-        "stores, threshold refusals, replay refusal, and trust-root rotation passed"
+        "stores, threshold refusals, replay refusal, and trust-root rotation and revocation passed"
     )
 
 
@@ -1,6 +1,10 @@
 #!/usr/bin/env python3
 """B35 persistent disk-backed selection across fresh QEMU boots."""
 from __future__ import annotations
+import sys as _sys
+from pathlib import Path as _Path
+
+_sys.path.insert(0, str(_Path(__file__).resolve().parents[1] / "lib"))
 
 import importlib.util
 import json
@@ -15,6 +19,15 @@ import tomllib
 from pathlib import Path
 from typing import NoReturn
 
+from boot_contracts import (
+    BOOTSTORE_EVIDENCE_OFFSET,
+    SHADOW_EVIDENCE,
+    SHADOW_EVIDENCE_CHECKSUM_END,
+    SHADOW_EVIDENCE_CHECKSUM_OFFSET,
+    SHADOW_EVIDENCE_MAGIC,
+    shadow_evidence_checksum,
+)
+
 ROOT = Path(__file__).resolve().parents[2]
 BUILD = ROOT / "scripts/build/build-sel4.py"
 GENERATOR = ROOT / "scripts/build/build-generation.py"
@@ -81,7 +94,9 @@ def restamp_wire_version(source: Path, destination: Path, magic: bytes, version:
     return destination
 
 
-def make_store(paths: list[Path], bundle: str, attempts: int) -> bytes:
+def make_store(
+    paths: list[Path], bundle: str, attempts: int, *, shadow: bool = False
+) -> bytes:
     spec = importlib.util.spec_from_file_location("slime_build_generation", GENERATOR)
     if spec is None or spec.loader is None:
         fail("cannot import generation builder")
@@ -97,6 +112,8 @@ def make_store(paths: list[Path], bundle: str, attempts: int) -> bytes:
             SLIME_PENDING_RELEASE_SEQUENCE="2",
             SLIME_BOOT_BUNDLE_IDENTITY=bundle,
         )
+        if shadow:
+            os.environ["SLIME_SHADOW_REQUEST"] = "1"
         return module.build_bootstore([path.read_bytes() for path in paths])
     finally:
         os.environ.clear()
@@ -316,6 +333,65 @@ def only_slots(before: bytes, after: bytes) -> None:
         fail("BootState did not change")
 
 
+def only_evidence(before: bytes, after: bytes) -> None:
+    """A shadow boot writes its verdict and nothing else: BootState, the
+    directory, and every generation are byte-identical afterwards."""
+    start = STORE_FIRST * SECTOR + BOOTSTORE_EVIDENCE_OFFSET
+    end = start + SECTOR
+    if len(before) != len(after) or before[:start] != after[:start] or before[end:] != after[end:]:
+        fail("bytes outside the shadow evidence sector changed")
+
+
+# `ShadowOutcome` codes in `boot-contracts/src/bootstate.rs`.
+SHADOW_OUTCOMES = {0: "requested", 1: "healthy", 2: "unhealthy", 3: "timed-out", 4: "refused"}
+
+
+def shadow_verdict(disk: bytes) -> tuple[str, int, int, int]:
+    """The recorded outcome with its exercised, failed and unverified counts."""
+    start = STORE_FIRST * SECTOR + BOOTSTORE_EVIDENCE_OFFSET
+    record = disk[start : start + SECTOR]
+    fields = SHADOW_EVIDENCE.unpack(record)
+    if fields[0] != SHADOW_EVIDENCE_MAGIC:
+        fail("shadow evidence sector lost its magic")
+    if record[SHADOW_EVIDENCE_CHECKSUM_OFFSET:SHADOW_EVIDENCE_CHECKSUM_END] != (
+        shadow_evidence_checksum(record)
+    ):
+        fail("shadow evidence checksum does not match its record")
+    outcome = SHADOW_OUTCOMES.get(fields[3])
+    if outcome is None:
+        fail(f"shadow evidence carries unknown outcome {fields[3]}")
+    return outcome, fields[9], fields[12], fields[4]
+
+
+def expect_shadow(disk: Path, store: bytes, verdict: str) -> None:
+    """Boot once under a shadow request and check the verdict on both sides.
+
+    The running generation must be the known-good one with its attempts
+    untouched — a shadow boot spends none — and the transcript's verdict must
+    be the one the evidence sector now holds.
+    """
+    make_disk(disk, store)
+    before = disk.read_bytes()
+    transcript = boot(disk, "SLIME_SHADOW verdict")
+    expect(transcript, 1, 0, 2)
+    if re.search(rf"SLIME_SHADOW verdict={verdict} .* recorded=1", transcript) is None:
+        fail(f"missing marker SLIME_SHADOW verdict={verdict} ... recorded=1")
+    after = disk.read_bytes()
+    only_evidence(before, after)
+    recorded, exercised, failed, unverified = shadow_verdict(after)
+    if recorded != verdict:
+        fail(f"shadow evidence records {recorded}, transcript reported {verdict}")
+    if exercised == 0:
+        fail("shadow verdict rests on no exercised instance")
+    if (failed == 0) != (verdict == "healthy"):
+        fail(f"shadow {verdict} verdict disagrees with failed={failed}")
+    # sel4.zti declares no probe past `RUNNING`, so a shadow of it leaves
+    # nothing unverified; a nonzero count would refuse the promotion the
+    # healthy verdict exists to allow.
+    if unverified != 0:
+        fail(f"shadow verdict leaves {unverified} declared probes unverified")
+
+
 def main() -> None:
     if Path.cwd().resolve() != ROOT:
         fail("run from repository root")
@@ -387,6 +463,10 @@ def main() -> None:
         before = promotion.read_bytes()
         candidate = boot(promotion, "SLIME_BOOT promoted")
         expect(candidate, 2, 1, 1)
+        # Promotion rests on a recorded verdict, consumed with the commit: the
+        # verdict sector is zero again afterwards, which `only_slots` checks.
+        if re.search(r"SLIME_BOOT verdict=passed .* failed=0 .* recorded=1", candidate) is None:
+            fail("missing marker SLIME_BOOT verdict=passed ... recorded=1 before promotion")
         after = promotion.read_bytes()
         only_slots(before, after)
         confirmed = boot(promotion, "SLIME_BOOT selected")
@@ -394,6 +474,20 @@ def main() -> None:
         if promotion.read_bytes() != after:
             fail("promoted boot mutated disk")
 
+        # Shadow boot: the same healthy and failing candidates, this time run
+        # beside the known-good graph rather than in its place. Each boot must
+        # leave BootState alone and replace the request with its verdict.
+        expect_shadow(
+            work / "shadow.img",
+            make_store([known_good, healthy], bundle, 2, shadow=True),
+            "healthy",
+        )
+        expect_shadow(
+            work / "shadow-failing.img",
+            make_store([known_good, failing], bundle, 2, shadow=True),
+            "unhealthy",
+        )
+
         # Structural proof: selector builds intentionally ignore the ambient
         # compile-time generation path, and packaging supplies only one app.
         build_source = (ROOT / "scripts/build/build-sel4.py").read_text(encoding="utf-8")
@@ -407,8 +501,9 @@ def main() -> None:
     print(
         "seL4 boot selection check: attempts persisted across fresh QEMU processes, "
         "exhaustion rolled back, a pending generation in a superseded wire format "
-        "was refused without consuming the known-good root, health promoted, and "
-        "only BootState sectors changed"
+        "was refused without consuming the known-good root, a passed health verdict "
+        "promoted, only BootState sectors changed, and shadow boots recorded healthy and "
+        "unhealthy verdicts without spending an attempt"
     )
 
 
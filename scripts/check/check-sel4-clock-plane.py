@@ -0,0 +1,252 @@
+#!/usr/bin/env python3
+
+"""Clock plane gate: the root's three clock operations answered on a boot.
+
+`CLOCK NOW`, `CLOCK WAIT` and `CLOCK ADVANCE` (labels 47-49) were otherwise
+exercised only by `slime-root/src/clock.rs`'s host tests. `clock-probe` holds one
+grant of each clock kind and walks them in order: the refusals first, so a root
+that ignored the capability cannot pass the later arms by accident; then a
+monotonic wait, the wall clock's date, and a simulated wait released by the
+probe's own advance.
+
+The root's `SLIME_CLOCK wall anchored` line is the PL031 read behind the wall
+clock. An unanchored wall clock is a failure marker rather than a missing one,
+so the transcript names the reason.
+"""
+
+from __future__ import annotations
+
+import argparse
+import re
+import shutil
+import subprocess
+import sys
+import threading
+import tomllib
+from pathlib import Path
+from typing import NoReturn
+
+sys.path.insert(0, str(Path(__file__).resolve().parents[1] / "lib"))
+
+from harness import profile_text, profile_integer  # noqa: E402
+
+ROOT = Path(__file__).resolve().parents[2]
+PINS_PATH = ROOT / "sel4" / "pins.toml"
+BUILD_SCRIPT = ROOT / "scripts" / "build" / "build-sel4.py"
+IMAGE = ROOT / "build" / "slime-sel4-clock.elf"
+FIXTURE = ROOT / "contracts" / "generation" / "v1" / "fixtures" / "sel4-clock.zti"
+BOOT_TIMEOUT_SECONDS = 180
+
+REQUIRED_MARKERS: tuple[tuple[str, str], ...] = (
+    (
+        "the root anchored the wall clock from the PL031",
+        r"SLIME_CLOCK wall anchored unix_s=\d+",
+    ),
+    (
+        "the probe started",
+        r"\[clock-probe\] running",
+    ),
+    (
+        "an ungranted slot and a monotonic advance were refused",
+        r"\[clock-probe\] ungranted slot and advance refused",
+    ),
+    (
+        "a monotonic wait was reached at its deadline",
+        r"\[clock-probe\] monotonic wait reached",
+    ),
+    (
+        "the wall clock read a date after 2020 and refused a wait",
+        r"\[clock-probe\] wall clock anchored and not waitable",
+    ),
+    (
+        "the simulated clock stood at zero with the wait pending",
+        r"\[clock-probe\] simulated wait pending at zero",
+    ),
+    (
+        "the probe's advance released the pending wait",
+        r"\[clock-probe\] simulated advance released=1",
+    ),
+    (
+        "the wait was reached at the advanced time and the clock kept it",
+        r"\[clock-probe\] simulated wait reached now_ns=1000",
+    ),
+    (
+        "the probe finished",
+        r"\[clock-probe\] clock plane complete",
+    ),
+)
+
+TERMINAL_MARKER = r"\[clock-probe\] clock plane complete"
+
+FAILURE_MARKERS: tuple[str, ...] = (
+    r"SLIME_ROOT FATAL",
+    r"SLIME_ROOT FAIL",
+    r"SLIME_GRAPH FAIL",
+    r"\[init\] unknown boot action",
+    r"SLIME_CLOCK wall unanchored.*",
+    r"SLIME_CLOCK wall rtc unmap failed.*",
+    r"\[clock-probe\] fail: .*",
+    r"Caught cap fault",
+    r"Caught vm fault",
+    r"Caught user exception",
+    r"panicked at ",
+    r"aborted at ",
+    r"\(aborted\)",
+)
+
+
+def fail(message: str) -> NoReturn:
+    raise SystemExit(f"seL4 clock plane check: {message}")
+
+
+def load_pins() -> dict[str, object]:
+    if not PINS_PATH.is_file():
+        fail(f"missing pin manifest: {PINS_PATH.relative_to(ROOT)}")
+    try:
+        pins = tomllib.loads(PINS_PATH.read_text(encoding="utf-8"))
+    except (OSError, tomllib.TOMLDecodeError) as error:
+        fail(f"cannot parse {PINS_PATH.relative_to(ROOT)}: {error}")
+    if pins.get("schema") != 1:
+        fail("unsupported sel4/pins.toml schema (expected 1)")
+    if not isinstance(pins.get("qemu_arm_virt"), dict):
+        fail("sel4/pins.toml is missing [qemu_arm_virt]")
+    return pins
+
+
+def build_image() -> None:
+    command = [sys.executable, str(BUILD_SCRIPT), "--clock-plane"]
+    print(f"[build] {' '.join(command)}", flush=True)
+    try:
+        process = subprocess.run(command, cwd=ROOT, check=False)
+    except OSError as error:
+        fail(f"cannot run the seL4 image build: {error}")
+    if process.returncode != 0:
+        fail(f"seL4 image build failed with exit status {process.returncode}")
+
+
+def boot(profile: dict[str, object]) -> str:
+    qemu = shutil.which("qemu-system-aarch64")
+    if qemu is None:
+        fail("qemu-system-aarch64 is not on PATH")
+    command = [
+        qemu,
+        "-machine",
+        profile_text(profile, "machine", fail),
+        "-cpu",
+        profile_text(profile, "cpu", fail),
+        "-smp",
+        str(profile_integer(profile, "cpus", fail)),
+        "-m",
+        f"size={profile_integer(profile, 'memory_mib', fail)}M",
+        "-nographic",
+        "-serial",
+        "mon:stdio",
+        "-kernel",
+        str(IMAGE),
+    ]
+    print(f"[boot] {' '.join(command)}", flush=True)
+    failures = re.compile("|".join(FAILURE_MARKERS))
+    terminal = re.compile(TERMINAL_MARKER)
+    lines: list[str] = []
+    reached = False
+    try:
+        process = subprocess.Popen(
+            command,
+            cwd=ROOT,
+            stdin=subprocess.DEVNULL,
+            stdout=subprocess.PIPE,
+            stderr=subprocess.STDOUT,
+            text=True,
+            bufsize=1,
+        )
+    except OSError as error:
+        fail(f"cannot run QEMU: {error}")
+    watchdog = threading.Timer(BOOT_TIMEOUT_SECONDS, process.kill)
+    watchdog.start()
+    try:
+        assert process.stdout is not None
+        for line in process.stdout:
+            lines.append(line.rstrip("\r\n"))
+            if failures.search(line):
+                break
+            if terminal.search(line) is not None:
+                reached = True
+                break
+    finally:
+        watchdog.cancel()
+        process.terminate()
+        try:
+            process.wait(timeout=10)
+        except subprocess.TimeoutExpired:
+            process.kill()
+            process.wait()
+    transcript = "\n".join(lines)
+    if not reached:
+        report_transcript(transcript)
+        fail(f"boot exceeded {BOOT_TIMEOUT_SECONDS}s without completing the plane")
+    return transcript
+
+
+def report_transcript(transcript: str) -> None:
+    tail = transcript.splitlines()[-40:]
+    if tail:
+        sys.stdout.write("--- serial transcript (tail) ---\n")
+        sys.stdout.write("\n".join(tail) + "\n")
+        sys.stdout.write("--- end transcript ---\n")
+        sys.stdout.flush()
+
+
+def check_transcript(transcript: str) -> None:
+    for pattern in FAILURE_MARKERS:
+        match = re.search(pattern, transcript)
+        if match is not None:
+            report_transcript(transcript)
+            fail(f"failure marker in serial transcript: {match.group(0)!r}")
+    position = 0
+    for label, pattern in REQUIRED_MARKERS:
+        match = re.compile(pattern).search(transcript, position)
+        if match is None:
+            report_transcript(transcript)
+            if re.search(pattern, transcript) is not None:
+                fail(f"marker out of order: {label} ({pattern})")
+            fail(f"missing marker: {label} ({pattern})")
+        position = match.end()
+    print(
+        f"transcript: {len(REQUIRED_MARKERS)} markers observed; each clock "
+        "kind answered through its own grant",
+        flush=True,
+    )
+
+
+def main() -> None:
+    parser = argparse.ArgumentParser(
+        description="Boot the seL4 clock-plane image and assert the root's clock operations"
+    )
+    parser.add_argument(
+        "--no-build",
+        action="store_true",
+        help="boot the already-built image instead of rebuilding it first",
+    )
+    arguments = parser.parse_args()
+
+    if Path.cwd().resolve() != ROOT:
+        fail(f"run from repository root: {ROOT}")
+    if not FIXTURE.is_file():
+        fail(f"missing generation fixture {FIXTURE.relative_to(ROOT)}")
+    pins = load_pins()
+    if not arguments.no_build:
+        build_image()
+    if not IMAGE.is_file():
+        fail(f"missing packaged image {IMAGE.relative_to(ROOT)}")
+    profile = pins["qemu_arm_virt"]
+    assert isinstance(profile, dict)
+    check_transcript(boot(profile))
+    print(
+        "seL4 clock plane check: the wall clock was anchored, and the "
+        "monotonic, wall and simulated clocks each answered, waited and "
+        "refused as their grants allow"
+    )
+
+
+if __name__ == "__main__":
+    main()
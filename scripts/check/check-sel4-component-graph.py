@@ -120,6 +120,8 @@ COMPATIBILITY_SOURCE_FILES = (
     ROOT / "contracts" / "generation" / "v1" / "schema.zt",
     ROOT / "contracts" / "generation" / "v5" / "schema.zt",
     ROOT / "contracts" / "generation" / "v5" / "gen_rust.zt",
+    ROOT / "contracts" / "generation" / "v6" / "schema.zt",
+    ROOT / "contracts" / "generation" / "v6" / "gen_rust.zt",
     ROOT / "boot-contracts" / "src" / "generation.rs",
 )
 
@@ -116,6 +116,16 @@ REQUIRED_MARKERS: tuple[tuple[str, str], ...] = (
         "a commit through one view is visible through another",
         r"\[sel4-directory-probe\] the namespace is shared across views",
     ),
+    (
+        # A pinned root holds still while the namespace moves on, keeps its
+        # scope, and refuses a commit.
+        "a snapshot pinned the root read-only",
+        r"\[sel4-directory-probe\] snapshot pinned the root",
+    ),
+    (
+        "a restore committed the pinned root back",
+        r"\[sel4-directory-probe\] restore committed the pinned root",
+    ),
     (
         "the probe ran every arm and exited cleanly",
         r"\[sel4-directory-probe\] directory plane complete",
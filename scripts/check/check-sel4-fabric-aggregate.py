@@ -206,6 +206,9 @@ POLL_SAMPLED_COUNTERS: frozenset[int] = frozenset(
 # asserts only `baseline == peak` and `peak != 0`), so exempting it would have
 # been the one place this relaxation silently stopped catching a regression.
 # `resourceSinkDropped` is the sink's own loss count, which must not move at all.
+# `resourcePrivatePages` is compared too: the root's page ledger is credited
+# only at construction and heap growth, both fixed by the generation, so two
+# boots of one image must report the same pages.
 COMPARED_COUNTERS: frozenset[int] = frozenset(
     set(range(1, FABRIC_TRACE_MAX_RESOURCE_COUNTER + 1)) - POLL_SAMPLED_COUNTERS
 )
@@ -278,9 +281,12 @@ if POLL_SAMPLED_COUNTERS | COMPARED_COUNTERS != set(
 # peer-death record appeared, so both planes intermittently emitted 140 and
 # intermittently 139. Splitting the constant states the difference these two
 # images are supposed to have; it does not paper over a variable one.
+#
+# 139/140 -> 141/142: the stream broker's `resourcePrivatePages` peak and
+# baseline, on both planes alike.
 EXPECTED_TRACE_RECORDS: dict[str, int] = {
-    "normal concurrent schedule": 139,
-    "fault schedule over the same graph": 140,
+    "normal concurrent schedule": 141,
+    "fault schedule over the same graph": 142,
 }
 
 # The keys above are `PLANES` labels. Checked here rather than left to the
@@ -76,6 +76,7 @@ from fabric_trace_contract import (  # noqa: E402
     FABRIC_TRACE_RESOURCE_LOAN,
     FABRIC_TRACE_RESOURCE_MAPPING,
     FABRIC_TRACE_RESOURCE_OPERATIONS,
+    FABRIC_TRACE_RESOURCE_PRIVATE_PAGES,
     FABRIC_TRACE_RESOURCE_QUEUE,
     FABRIC_TRACE_RESOURCE_RETAINED,
     FABRIC_TRACE_RESOURCE_RETRIES,
@@ -244,6 +245,10 @@ EXPECTED_RESOURCES: dict[str, tuple[tuple[int, str, int], ...]] = {
         # this fixture's own declared `capabilitySlots`, which the tightened
         # ceilings leave unchanged at 48.
         (FABRIC_TRACE_RESOURCE_CAPABILITY_SLOTS, "capability-slots", 2),
+        # The broker's private memory in pages, from the root's own ledger
+        # through the self-scoped `memory OCCUPANCY` query. Pages leave only
+        # with the task, so it takes `mapping`'s constant shape.
+        (FABRIC_TRACE_RESOURCE_PRIVATE_PAGES, "private-pages", 2),
     ),
     "call": (
         (FABRIC_TRACE_RESOURCE_CALLS, "calls", 2),
@@ -757,6 +762,25 @@ def check_resources(transcript: str) -> None:
                         f"the {family} holder's {name!r} baseline {observed[1]} exceeded "
                         f"its own peak {observed[0]}"
                     )
+            elif expected_count == 2 and name == "private-pages":
+                # Constant, as `mapping` is, and for a stronger reason: the root
+                # has no operation that returns a private page, so the post-drain
+                # count equalling the peak is the only coherent answer, and a
+                # lower one would mean the ledger lost a page. Nonzero because
+                # every task holds at least its image, stack and IPC buffer.
+                if observed[0] == 0:
+                    report_transcript(transcript)
+                    fail(
+                        f"the {family} holder reported no private pages; every task holds "
+                        "its image, so the memory query or the ledger regressed"
+                    )
+                if observed[1] != observed[0]:
+                    report_transcript(transcript)
+                    fail(
+                        f"the {family} holder's {name!r} baseline {observed[1]} differs from "
+                        f"its peak {observed[0]}; no private page is released while its "
+                        "holder lives"
+                    )
             elif expected_count == 2 and observed[1] != 0:
                 report_transcript(transcript)
                 fail(
@@ -16,11 +16,18 @@ changed underneath is that the object bytes come from a userspace
 The client hands the service its *own* directory view with every request, so
 the service acts with the client's authority rather than its own. That transfer
 is the reason `contracts/capability-transfer` gained a directory object kind.
+
+The store is mirrored across two disks, so this is also the two-disk gate for
+`object_store::Mirror`. The secondary is damaged before boot — its active
+superblock slot rolled back to an older root, and a byte of a record flipped —
+and the service must reconcile the one at open and repair the other in its
+scrub, leaving the two disks byte-identical after every write the arms make.
 """
 
 from __future__ import annotations
 
 import argparse
+import hashlib
 import re
 import shutil
 import subprocess
@@ -33,7 +40,7 @@ from typing import NoReturn
 
 sys.path.insert(0, str(Path(__file__).resolve().parents[1] / "lib"))
 
-from harness import profile_text, profile_integer  # noqa: E402
+from harness import load_script, profile_text, profile_integer  # noqa: E402
 
 ROOT = Path(__file__).resolve().parents[2]
 PINS_PATH = ROOT / "sel4" / "pins.toml"
@@ -48,6 +55,14 @@ REQUIRED_MARKERS: tuple[tuple[str, str], ...] = (
         "init spawned the service",
         r"\[init\] filesystem service spawned",
     ),
+    (
+        # Both disks were mirrored, and the one record damaged on the
+        # secondary was rewritten from the primary. The stale slot does not
+        # show here: open reconciles it before the scrub, and the comparison
+        # of the two disks after the boot is what proves it.
+        "the service mirrored the store and repaired the damaged copy",
+        r"\[filesystem\] mirrored copies=2 records=\d+ repaired=1 damaged=0",
+    ),
     (
         # The service opened the object store over its block capability, and
         # says so on a declared edge to init as well as on serial. The client is
@@ -74,6 +89,27 @@ REQUIRED_MARKERS: tuple[tuple[str, str], ...] = (
         "a root transition committed and is visible",
         r"\[directory-probe\] root transition committed",
     ),
+    (
+        # fs/v2's depth and name bounds: a file six segments down, under
+        # parents the write itself created, with a name v1 could not hold. The
+        # path crossed as the scope of the request's capability, not inline.
+        "a deep path with a long name was written and read back",
+        r"\[directory-probe\] deep path written",
+    ),
+    (
+        # One directory holding more entries than a whole v1 snapshot, split
+        # across fs/v2 pages and listed back with the full count.
+        "a directory past the v1 entry bound was listed",
+        r"\[directory-probe\] large directory listed",
+    ),
+    (
+        # A range larger than a message written into the middle of a file and
+        # past its end, then read back: both directions crossed as loans of
+        # sealed shared buffers, and a loan whose bytes disagreed with the
+        # request's hash was refused first.
+        "a byte range round-tripped through buffer loans",
+        r"\[directory-probe\] byte range round-tripped",
+    ),
     (
         # A subdirectory capability, minted by the service and transferred to
         # the client — narrower in scope and in rights.
@@ -89,6 +125,19 @@ REQUIRED_MARKERS: tuple[tuple[str, str], ...] = (
         "the derived scope's boundary is enforced",
         r"\[directory-probe\] scoped boundary enforced",
     ),
+    (
+        # A snapshot pinned the live root as a read-only view: a later write
+        # did not appear through it, and neither a commit nor a write could
+        # cross it.
+        "a snapshot pinned the root read-only",
+        r"\[directory-probe\] snapshot pinned",
+    ),
+    (
+        # Restore committed the pinned root back as the live one through the
+        # namespace's atomic commit, dropping the later write.
+        "a restore rolled the root back to the snapshot",
+        r"\[directory-probe\] snapshot restored",
+    ),
     (
         "a malformed request was rejected",
         r"\[directory-probe\] malformed rejected",
@@ -163,7 +212,21 @@ def build_fixture(disk: Path) -> None:
         fail(f"directory fixture build failed: {process.stderr.decode()}")
 
 
-def boot(profile: dict[str, object], disk: Path) -> str:
+def damage_secondary(secondary: Path) -> None:
+    """One leg's faults: slot A, the fixture's active root, overwritten with
+    slot B's older one, which still opens on its own; and a payload byte of the
+    first record flipped, which only a scrub of that copy can find."""
+    store = load_script("build_store_fixture", "build/build-store-fixture.py")
+    image = bytearray(secondary.read_bytes())
+    slot_a = store.STORE_FIRST * store.SECTOR
+    slot_b = slot_a + store.SECTOR
+    image[slot_a:slot_b] = image[slot_b : slot_b + store.SECTOR]
+    record = (store.STORE_FIRST + store.RECORD_AREA_START) * store.SECTOR
+    image[record + store.HEADER_SIZE] ^= 0x01
+    secondary.write_bytes(image)
+
+
+def boot(profile: dict[str, object], primary: Path, secondary: Path) -> str:
     qemu = shutil.which("qemu-system-aarch64")
     if qemu is None:
         fail("qemu-system-aarch64 is not on PATH")
@@ -183,9 +246,15 @@ def boot(profile: dict[str, object], disk: Path) -> str:
         "-kernel",
         str(IMAGE),
         "-drive",
-        f"if=none,id=slimedisk,format=raw,file={disk}",
+        f"if=none,id=slimedisk,format=raw,file={primary}",
         "-device",
         "virtio-blk-device,drive=slimedisk",
+        # The mirror's second disk, attached second so the root numbers it
+        # device 1 — the service's second block grant.
+        "-drive",
+        f"if=none,id=mirrordisk,format=raw,file={secondary}",
+        "-device",
+        "virtio-blk-device,drive=mirrordisk",
     ]
     print(f"[boot] {' '.join(command)}", flush=True)
     failures = re.compile("|".join(FAILURE_MARKERS))
@@ -290,7 +359,7 @@ def check_transcript(transcript: str) -> None:
         )
     print(
         f"transcript: {len(REQUIRED_MARKERS)} markers observed; the oracle's own "
-        f"directory-probe drove read, interrupted-write, write, derive, and "
+        f"directory-probe drove read, interrupted-write, write, derive, snapshot, restore, and "
         f"boundary arms through the seL4 filesystem service, handing its view "
         f"across {len(exports)} times",
         flush=True,
@@ -322,9 +391,12 @@ def main() -> None:
 
     with tempfile.TemporaryDirectory() as directory:
         disk = Path(directory) / "filesystem-plane.img"
+        mirror = Path(directory) / "filesystem-plane-mirror.img"
         build_fixture(disk)
+        shutil.copyfile(disk, mirror)
+        damage_secondary(mirror)
         before = disk.read_bytes()
-        transcript = boot(profile, disk)
+        transcript = boot(profile, disk, mirror)
         check_transcript(transcript)
         # The store is append-only: a committed snapshot adds records and moves
         # a superblock, and never rewrites what was already sealed. The GPT is
@@ -334,12 +406,22 @@ def main() -> None:
             fail("the filesystem service modified the GPT or protective MBR")
         if after == before:
             fail("no snapshot was committed, so the write arms did nothing")
+        # Every write went to both disks, and both faults were repaired from
+        # the primary, so nothing is left to tell the copies apart.
+        mirrored = mirror.read_bytes()
+        if mirrored != after:
+            fail(
+                "the mirror's copies diverged: primary "
+                f"sha256:{hashlib.sha256(after).hexdigest()}, secondary "
+                f"sha256:{hashlib.sha256(mirrored).hexdigest()}"
+            )
 
     print(
         "seL4 filesystem plane check: the oracle's own directory-probe resolved "
         "names, committed a root transition, and derived a narrowed "
         "subdirectory through a seL4 filesystem service backed by a userspace "
-        "object store — unmodified, because M6.3's service half is policy"
+        "object store mirrored across two disks — unmodified, because M6.3's "
+        "service half is policy"
     )
 
 
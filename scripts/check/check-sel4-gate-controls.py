@@ -152,14 +152,16 @@ GATES: tuple[tuple[str, str, int], ...] = (
     ("sel4_storage_plane", "check/check-sel4-storage-plane.py", 9),
     ("sel4_store_plane", "check/check-sel4-store-plane.py", 14),
     ("sel4_rollback_plane", "check/check-sel4-rollback-plane.py", 16),
-    ("sel4_recovery_plane", "check/check-sel4-recovery-plane.py", 11),
-    ("sel4_generation_plane", "check/check-sel4-generation-plane.py", 18),
-    ("sel4_directory_plane", "check/check-sel4-directory-plane.py", 16),
-    ("sel4_filesystem_plane", "check/check-sel4-filesystem-plane.py", 11),
+    ("sel4_recovery_plane", "check/check-sel4-recovery-plane.py", 13),
+    ("sel4_generation_plane", "check/check-sel4-generation-plane.py", 35),
+    ("sel4_directory_plane", "check/check-sel4-directory-plane.py", 18),
+    ("sel4_filesystem_plane", "check/check-sel4-filesystem-plane.py", 17),
     ("sel4_input_plane", "check/check-sel4-input-plane.py", 7),
+    ("sel4_isolation_plane", "check/check-sel4-isolation-plane.py", 10),
+    ("sel4_clock_plane", "check/check-sel4-clock-plane.py", 9),
     ("sel4_powerbox_plane", "check/check-sel4-powerbox-plane.py", 11),
     ("sel4_dango_plane", "check/check-sel4-dango-plane.py", 13),
-    ("sel4_transfer_plane", "check/check-sel4-transfer-plane.py", 11),
+    ("sel4_transfer_plane", "check/check-sel4-transfer-plane.py", 13),
 )
 
 
@@ -8,17 +8,41 @@ holds one RPC endpoint and nothing else. That split IS the milestone: M6.5
 requires `BOOT_UPDATE` scoped by manifest to the management service, so a
 component that wants to inspect, stage, select, or roll back must ask.
 
-The client walks all five operations and their refusals, then tries a direct
-`BlockTransact` and is refused — not by a rights check, but because no slot it
-holds names a device. The gate additionally compares the disk image around the
-refused-stage arm: "fail before BootState changes" is a claim about bytes, and
-a component reporting a refusal it did not honour would pass the marker.
+The client walks all five operations and their refusals, bisects the fixture's
+release chain to its first bad generation, then tries a direct `BlockTransact`
+and is refused — not by a rights check, but because no slot it holds names a
+device. The gate additionally checks the disk image: "fail before BootState
+changes" is a claim about bytes, and a component reporting a refusal it did not
+honour would pass the marker. The bisect's verdicts are read back off the disk
+too, since surviving a reboot is a property of what was written. So is the
+state: the generations declare a snapshot-before-upgrade and a
+discard-on-rollback binding over state the fixture seeds, and the gate reads
+back the root the rollback and promotion left and the journal that names each
+step.
+
+The manager keeps BootState on a second disk, a boot store of the chain, in
+the sectors a boot-selector root selects from and leaves shadow evidence in,
+so the state it stages is the state a root would boot and shadow.
+
+The client exports the candidate's child to a third, blank disk and imports it
+back. The gate reads the archive off that disk: a transfer partition holding
+a sealed manifest that carries every object it names, the state map BootState
+names among them, and each object that map names after it.
+
+Last, the client rotates the trust root with the rotation record the fixture
+holds. The gate reads the installed root back from the store and from the
+sector after the boot store, where a boot-selector root reads it, and lays
+both files stage 0 reads, `boot-store.bin` and `trust-root.bin`, out under
+`build/sel4-generation-stage0/boot`, holding them to stage 0's own checks.
 """
 
 from __future__ import annotations
 
 import argparse
 import re
+import struct
+import zlib
+from collections import Counter
 import shutil
 import subprocess
 import sys
@@ -30,6 +54,45 @@ from typing import NoReturn
 
 sys.path.insert(0, str(Path(__file__).resolve().parents[1] / "lib"))
 
+from boot_contracts import (  # noqa: E402
+    BOOTSTATE_CHECKSUM_END,
+    BOOTSTATE_CHECKSUM_OFFSET,
+    BOOTSTATE_MAGIC,
+    BOOTSTATE_SLOT,
+    STATE_JOURNAL,
+    STATE_JOURNAL_CHECKSUM_END,
+    STATE_JOURNAL_CHECKSUM_OFFSET,
+    STATE_JOURNAL_ENTRY_BYTES,
+    STATE_JOURNAL_MAGIC,
+    STATE_JOURNAL_SECTORS,
+    STORE_INDEX,
+    TRANSFER_HEADER,
+    TRANSFER_HEADER_HASH_END,
+    TRANSFER_HEADER_HASH_OFFSET,
+    TRANSFER_MAGIC,
+    TRANSFER_OBJECT,
+    TRANSFER_OBJECT_FLAG_PAYLOAD,
+    TRANSFER_VERSION,
+    STORE_INDEX_A_LBA,
+    STORE_INDEX_B_LBA,
+    STORE_INDEX_ENTRY,
+    STORE_RECORD,
+    STORE_RECORD_AREA_START,
+    STORE_SUPERBLOCK,
+    STORE_SUPERBLOCK_CRC32_OFFSET,
+    STORE_SUPERBLOCK_MAGIC,
+    BOOTSTATE_TRUST_ROOT_OFFSET,
+    BOOTSTATE_TRUST_ROOT_VERSION_END,
+    BOOTSTORE_CAPACITY,
+    BOOTSTORE_EVIDENCE_OFFSET,
+    BOOTSTORE_VERDICT_OFFSET,
+    TRUST_ROOT_HEADER,
+    TRUST_ROOT_MAGIC,
+    TRUST_ROOT_RECORD_VERSION,
+    bootstate_checksum,
+)
+from recovery_index import binding_identity, sha256  # noqa: E402
+from release_trust import initial_public_keys, trust_root_record  # noqa: E402
 from harness import profile_text, profile_integer  # noqa: E402
 
 ROOT = Path(__file__).resolve().parents[2]
@@ -37,6 +100,9 @@ PINS_PATH = ROOT / "sel4" / "pins.toml"
 BUILD_SCRIPT = ROOT / "scripts" / "build" / "build-sel4.py"
 FIXTURE_SCRIPT = ROOT / "scripts" / "build" / "build-store-fixture.py"
 IMAGE = ROOT / "build" / "slime-sel4-generation.elf"
+# What the image was built from, and what the fixture derives the manager's
+# known-good and candidate generations from.
+GENERATION = ROOT / "build" / "sel4-generation-generation" / "generation.bin"
 FIXTURE = ROOT / "contracts" / "generation" / "v1" / "fixtures" / "sel4-generation.zti"
 BOOT_TIMEOUT_SECONDS = 240
 
@@ -110,6 +176,86 @@ REQUIRED_MARKERS: tuple[tuple[str, str], ...] = (
         "the client observed the promotion",
         r"\[sel4-generation-client\] promoted the candidate",
     ),
+    (
+        # Held and signed, but its release is one the promotion moved past:
+        # verified before BootState changes, and refused.
+        "staging the superseded generation was refused",
+        r"\[sel4-generation-client\] superseded stage refused",
+    ),
+    (
+        # The bisect bounds are the promoted candidate and the chain's tip; a
+        # bound no release carries has no chain to walk.
+        "a bisect bound without a release was refused",
+        r"\[sel4-generation-client\] bisect bound without a release refused",
+    ),
+    (
+        # The first midpoint of eight generations, staged like any candidate.
+        "the bisect staged its first midpoint",
+        r"\[sel4-generation-manager\] bisect-stage seq=6 pending=1 attempts=2 release=2",
+    ),
+    (
+        "the client observed the midpoint",
+        r"\[sel4-generation-client\] bisect staged the midpoint",
+    ),
+    (
+        "a verdict on a generation that was not staged was refused",
+        r"\[sel4-generation-client\] verdict on an unstaged generation refused",
+    ),
+    (
+        # Three verdicts, two more stages, and the last midpoint rolled back:
+        # the search ends on the root it started from.
+        "the bisect finished and unstaged its last midpoint",
+        r"\[sel4-generation-manager\] bisect-done seq=9 pending=0 attempts=0 release=2",
+    ),
+    (
+        "the client observed the first bad generation",
+        r"\[sel4-generation-client\] bisect named the first bad generation",
+    ),
+    (
+        "the bisect session was reset",
+        r"\[sel4-generation-client\] bisect session reset",
+    ),
+    (
+        "an export of a generation the store does not hold was refused",
+        r"\[sel4-generation-client\] unknown export refused",
+    ),
+    (
+        # Both read BootState and neither moves it.
+        "the manager exported the candidate's child",
+        r"\[sel4-generation-manager\] export seq=9 pending=0 attempts=0 release=2",
+    ),
+    (
+        "the client observed the export",
+        r"\[sel4-generation-client\] exported the archive",
+    ),
+    (
+        "the manager imported the archive",
+        r"\[sel4-generation-manager\] import seq=9 pending=0 attempts=0 release=2",
+    ),
+    (
+        "the client observed the import",
+        r"\[sel4-generation-client\] imported the archive",
+    ),
+    (
+        "a rotation the store does not hold was refused",
+        r"\[sel4-generation-client\] unknown rotation refused",
+    ),
+    (
+        # Stored, written where a root reads it, then named: the reset left
+        # BootState at the bisect's last commit.
+        "the manager rotated the trust root",
+        r"\[sel4-generation-manager\] rotate seq=10 pending=0 attempts=0 release=2",
+    ),
+    (
+        "the client observed the rotation",
+        r"\[sel4-generation-client\] rotated the trust root",
+    ),
+    (
+        # The rotation names the version it replaces, which is no longer the
+        # installed one.
+        "replaying the rotation was refused",
+        r"\[sel4-generation-client\] replayed rotation refused",
+    ),
     (
         # The authority claim. The client knows the on-disk format perfectly
         # well and still cannot write it, because it holds no device.
@@ -134,6 +280,70 @@ REQUIRED_MARKERS: tuple[tuple[str, str], ...] = (
 
 TERMINAL_MARKER = r"\[init\] generation plane complete"
 
+# The manager's state journal on the store disk, partition-relative:
+# `STATE_JOURNAL` in sel4-generation-manager.rs.
+STATE_JOURNAL_LBA = 1046
+# The boot disk's BootState slots, shadow evidence and health verdict,
+# partition-relative: `BOOT_STORE_SECTORS` in sel4-generation-manager.rs.
+BOOT_SLOT_A_LBA = 0
+BOOT_SLOT_B_LBA = 1
+BOOT_EVIDENCE_LBA = BOOTSTORE_EVIDENCE_OFFSET // 512
+BOOT_VERDICT_LBA = BOOTSTORE_VERDICT_OFFSET // 512
+
+# Kept in step with contracts/generation-management/v1/schema.zt and the
+# client's tester.
+BISECT_SESSION_OBJECT_TYPE = 1196245587
+BISECT_SESSION_MAGIC = b"SLIMEBI\0"
+SESSION_HEADER_LEN = 128
+# The generation the client's tester calls the first bad, by its place in a
+# session's chain: the good bound, then the seven generations past it.
+BISECT_CHAIN_COUNT = 8
+FIRST_BAD_INDEX = 5
+STATE_MAP_OBJECT_TYPE = 1196249933
+STATE_SNAPSHOT_OBJECT_TYPE = 1196249939
+TRUST_ROOT_OBJECT_TYPE = 1196250194
+# The root the fixture's rotation installs: the same keys and threshold at
+# the next version (`PLANE_ROTATED_VERSION` in build-store-fixture.py).
+ROTATED_VERSION = 2
+ROTATED_THRESHOLD = 2
+# The sector after the boot store, partition-relative: `TRUST_ROOT_SECTOR` in
+# slime-root/src/boot_selector.rs.
+BOOT_TRUST_ROOT_LBA = BOOTSTORE_CAPACITY // 512
+# Where stage 0 reads its boot store and rotated root: `BOOT_STORE_PATH` and
+# `TRUST_ROOT_PATH` in stage0/src/main.rs.
+STAGE0_BOOT = ROOT / "build" / "sel4-generation-stage0" / "boot"
+# The blank disk EXPORT writes and IMPORT reads, and the partition type
+# `archive::write_archive` formats it with: `SLIME_TRANSFER_TYPE_GUID` in
+# boot-contracts/src/gpt.rs.
+ARCHIVE_DISK_BYTES = 1 << 20
+TRANSFER_TYPE_GUID = b"SLIMEOSTRANSFER!"
+
+# The two bindings sel4-generation.zti declares and the fixture seeds, and
+# `JournalAction` codes from boot-contracts/src/bootstate.rs.
+SETTINGS = binding_identity("generation-client-settings")
+SCRATCH = binding_identity("generation-client-scratch")
+JOURNAL_SNAPSHOT = 1
+JOURNAL_DISCARD = 3
+JOURNAL_RELEASE = 4
+# Each binding the candidate's transitions touched, with the BootState
+# sequence that committed it: staging snapshots the settings, the rollback
+# drops the scratch state, the second staging snapshots the settings it left,
+# and the promotion lets that snapshot go. Each of the bisect's three
+# midpoints is a generation declaring the same bindings, so staging it
+# snapshots the settings too; the rollback that ends the search puts back the
+# very object each midpoint kept, so it journals nothing.
+EXPECTED_JOURNAL = [
+    (JOURNAL_SNAPSHOT, 2, SETTINGS),
+    (JOURNAL_DISCARD, 3, SCRATCH),
+    (JOURNAL_SNAPSHOT, 4, SETTINGS),
+    (JOURNAL_RELEASE, 5, SETTINGS),
+    (JOURNAL_SNAPSHOT, 6, SETTINGS),
+    (JOURNAL_SNAPSHOT, 7, SETTINGS),
+    (JOURNAL_SNAPSHOT, 8, SETTINGS),
+]
+# One snapshot record per stage: the candidate's two and the three midpoints'.
+EXPECTED_SNAPSHOTS = 5
+
 FAILURE_MARKERS: tuple[str, ...] = (
     r"SLIME_ROOT FATAL",
     r"SLIME_ROOT FAIL",
@@ -142,6 +352,9 @@ FAILURE_MARKERS: tuple[str, ...] = (
     r"\[init\] generation plane fail: .*",
     r"\[sel4-generation-manager\] fail: .*",
     r"\[sel4-generation-client\] fail: .*",
+    # Every midpoint of the fixture's chain is signed, so a bisect that refuses
+    # one to staging has refused a release it should have verified.
+    r"\[sel4-generation-manager\] bisect-unverified",
     r"SLIME_ROOT block bring-up failed",
     r"Caught cap fault",
     r"Caught vm fault",
@@ -180,10 +393,22 @@ def build_image() -> None:
         fail(f"seL4 image build failed with exit status {process.returncode}")
 
 
-def build_fixture(disk: Path) -> None:
-    """The store fixture, reused: the manager needs a validated GPT partition,
-    and the BootState slots live above the object store's record area in it."""
-    command = [sys.executable, str(FIXTURE_SCRIPT), str(disk), "happy"]
+def build_fixture(disk: Path, boot_disk: Path) -> None:
+    """The store fixture with a release chain, and a boot disk over the same
+    chain: the manager needs a validated GPT partition on each, the bisect
+    walks the releases the store holds, a STAGE verifies the candidate's
+    generation and signed release against them, and BootState lives in the
+    boot store's slots, where a root would select from it."""
+    command = [
+        sys.executable,
+        str(FIXTURE_SCRIPT),
+        str(disk),
+        "generation",
+        "--generation",
+        str(GENERATION),
+        "--boot-disk",
+        str(boot_disk),
+    ]
     try:
         process = subprocess.run(command, cwd=ROOT, check=False, capture_output=True)
     except OSError as error:
@@ -192,7 +417,7 @@ def build_fixture(disk: Path) -> None:
         fail(f"store fixture build failed: {process.stderr.decode()}")
 
 
-def boot(profile: dict[str, object], disk: Path) -> str:
+def boot(profile: dict[str, object], disk: Path, archive_disk: Path, boot_disk: Path) -> str:
     qemu = shutil.which("qemu-system-aarch64")
     if qemu is None:
         fail("qemu-system-aarch64 is not on PATH")
@@ -215,6 +440,18 @@ def boot(profile: dict[str, object], disk: Path) -> str:
         f"if=none,id=slimedisk,format=raw,file={disk}",
         "-device",
         "virtio-blk-device,drive=slimedisk",
+        # The archive and then the boot disk, each attached after the last so
+        # QEMU gives it a lower transport: the root sorts highest-address-first,
+        # so the store is device 0, the archive 1 and the boot disk 2, as the
+        # manager's bindings, sorted by slot, expect.
+        "-drive",
+        f"if=none,id=archivedisk,format=raw,file={archive_disk}",
+        "-device",
+        "virtio-blk-device,drive=archivedisk",
+        "-drive",
+        f"if=none,id=bootdisk,format=raw,file={boot_disk}",
+        "-device",
+        "virtio-blk-device,drive=bootdisk",
     ]
     print(f"[boot] {' '.join(command)}", flush=True)
     failures = re.compile("|".join(FAILURE_MARKERS))
@@ -321,13 +558,35 @@ def check_transcript(transcript: str) -> None:
         (match.group("op"), int(match.group("seq")))
         for match in re.finditer(
             r"\[sel4-generation-manager\] "
-            r"(?P<op>stage|select|rollback|inspect-unknown|stage-refused|"
-            r"select-refused|rollback-nothing) seq=(?P<seq>\d+)",
+            r"(?P<op>stage|select|rollback|inspect-unknown|stage-refused|stage-unverified|"
+            r"select-refused|select-unproven|rollback-nothing|bisect-stage|bisect-done|"
+            r"bisect-refused|bisect-unverified|bisect-bound|bisect-status|bisect-none|"
+            r"export|export-unknown|import|rotate|rotate-unknown|rotate-refused) "
+            r"seq=(?P<seq>\d+)",
             transcript,
         )
     ]
-    refusal_names = {"inspect-unknown", "stage-refused", "select-refused", "rollback-nothing"}
-    commit_names = {"stage", "select", "rollback"}
+    # Bounds and status reads are not refusals, but they must not move the
+    # root either.
+    refusal_names = {
+        "inspect-unknown",
+        "stage-refused",
+        "stage-unverified",
+        "select-refused",
+        "select-unproven",
+        "rollback-nothing",
+        "bisect-refused",
+        "bisect-unverified",
+        "bisect-bound",
+        "bisect-status",
+        "bisect-none",
+        "export",
+        "export-unknown",
+        "import",
+        "rotate-unknown",
+        "rotate-refused",
+    }
+    commit_names = {"stage", "select", "rollback", "bisect-stage", "bisect-done", "rotate"}
     refusals: list[tuple[str, int]] = []
     commits: list[int] = []
     # The fixture's admitted BootState starts at sequence 1; the first successful
@@ -344,42 +603,414 @@ def check_transcript(transcript: str) -> None:
                     f"{operation} mutated BootState sequence from {committed_sequence} "
                     f"to {sequence}"
                 )
-    expected_refusals = {"inspect-unknown", "stage-refused", "select-refused", "rollback-nothing"}
-    if {name for name, _ in refusals} != expected_refusals or len(refusals) != 4:
-        fail(f"refusal evidence was {refusals}, expected one of each {sorted(expected_refusals)}")
+    # One of each of the original four, a stage that did not verify, three
+    # bisect refusals: a bound without a release, a verdict on an unstaged
+    # generation, and a verdict after the search ended, an export of a
+    # generation the store does not hold, and a rotation the store does not
+    # hold and its replay.
+    expected_refusals = {
+        "inspect-unknown": 1,
+        "stage-refused": 1,
+        "stage-unverified": 1,
+        "select-refused": 1,
+        "rollback-nothing": 1,
+        "bisect-refused": 3,
+        "export-unknown": 1,
+        "rotate-unknown": 1,
+        "rotate-refused": 1,
+    }
+    observed_refusals = Counter(name for name, _ in refusals if name in expected_refusals)
+    if observed_refusals != Counter(expected_refusals):
+        fail(f"refusal evidence was {refusals}, expected {expected_refusals}")
     if commits != sorted(set(commits)):
         fail(f"committed sequences are not strictly increasing: {commits}")
     print(
         f"transcript: {len(REQUIRED_MARKERS)} markers observed; the client drove "
-        f"five operations through the service, {len(refusals)} refusals left the "
-        f"root untouched, {len(commits)} commits advanced it strictly, and a "
-        "direct device request was refused",
+        "five operations, a bisect, an export and import and a rotation through the "
+        f"service, {len(refusals)} refusals and reads left the root untouched, {len(commits)} commits "
+        "advanced it strictly, and a direct device request was refused",
         flush=True,
     )
 
 
-def check_only_state_slots(disk: Path, before: bytes, partition_first_lba: int) -> None:
-    """The manager wrote BootState and nothing else.
+def committed_index(
+    image: bytes, partition_first_lba: int
+) -> tuple[int, list[tuple[int, int, int]]]:
+    """The newest valid superblock's append LBA and its index entries.
+
+    Only the fields this check relies on are read; the guest's own store
+    checks are what prove the format, so a damaged slot here is a failure
+    rather than something to recover from.
+    """
+    best: tuple[int, int, list[tuple[int, int, int]]] | None = None
+    for slot, index_lba in enumerate((STORE_INDEX_A_LBA, STORE_INDEX_B_LBA)):
+        offset = (partition_first_lba + slot) * 512
+        sector = image[offset : offset + 512]
+        fields = STORE_SUPERBLOCK.unpack_from(sector)
+        magic, sequence, append_lba, object_count = fields[0], fields[3], fields[4], fields[5]
+        crc = struct.unpack_from("<I", sector, STORE_SUPERBLOCK_CRC32_OFFSET)[0]
+        sealed = zlib.crc32(sector[:STORE_SUPERBLOCK_CRC32_OFFSET])
+        if magic != STORE_SUPERBLOCK_MAGIC or sealed != crc:
+            continue
+        base = (partition_first_lba + index_lba) * 512
+        entries = []
+        for number in range(object_count):
+            at = base + STORE_INDEX.size + number * STORE_INDEX_ENTRY.size
+            _, obj_type, payload_len, lba = STORE_INDEX_ENTRY.unpack_from(image, at)
+            entries.append((obj_type, payload_len, lba))
+        if best is None or sequence > best[0]:
+            best = (sequence, append_lba, entries)
+    if best is None:
+        fail("no valid object-store superblock after the run")
+    return best[1], best[2]
+
+
+def read_session(
+    image: bytes, partition_first_lba: int, payload_len: int, lba: int
+) -> tuple[int, int, int, int, list[bytes]]:
+    """One bisect-session record: its serial, count, bounds, and chain."""
+    start = (partition_first_lba + lba) * 512 + STORE_RECORD.size
+    payload = image[start : start + payload_len]
+    if len(payload) < SESSION_HEADER_LEN or payload[:8] != BISECT_SESSION_MAGIC:
+        fail(f"bisect-session record at store LBA {lba} is not a session")
+    serial, count, low, high = struct.unpack_from("<QIII", payload, 16)
+    if payload_len != SESSION_HEADER_LEN + count * 32:
+        fail(
+            f"bisect-session record at store LBA {lba} has {payload_len} bytes "
+            f"for {count} generations"
+        )
+    chain = [
+        payload[SESSION_HEADER_LEN + 32 * n : SESSION_HEADER_LEN + 32 * (n + 1)]
+        for n in range(count)
+    ]
+    return serial, count, low, high, chain
+
+
+def selected_bootstate(image: bytes, partition_first_lba: int) -> tuple[int, bytes, bytes]:
+    """The sequence, state root and trust-root reference of the BootState slot
+    a boot would select."""
+    best: tuple[int, bytes, bytes] | None = None
+    for slot in (BOOT_SLOT_A_LBA, BOOT_SLOT_B_LBA):
+        sector = image[(partition_first_lba + slot) * 512 : (partition_first_lba + slot + 1) * 512]
+        fields = BOOTSTATE_SLOT.unpack(sector)
+        if fields[0] != BOOTSTATE_MAGIC:
+            continue
+        if bootstate_checksum(sector) != sector[BOOTSTATE_CHECKSUM_OFFSET:BOOTSTATE_CHECKSUM_END]:
+            fail(f"BootState slot at LBA {slot} does not checksum")
+        sequence, pending, state_root, rollback_root = fields[4], fields[6], fields[10], fields[15]
+        if (pending == bytes(32)) != (rollback_root == bytes(32)):
+            fail(f"BootState slot at LBA {slot} has a pending generation without a rollback root")
+        if best is None or sequence > best[0]:
+            trust_root = sector[BOOTSTATE_TRUST_ROOT_OFFSET:BOOTSTATE_TRUST_ROOT_VERSION_END]
+            best = (sequence, state_root, trust_root)
+    if best is None:
+        fail("no valid BootState slot after the run")
+    return best
+
+
+def state_entries(image: bytes, partition_first_lba: int, root: bytes) -> list[bytes]:
+    """The 68-byte entries of the committed state map whose root is `root`."""
+    _, entries = committed_index(image, partition_first_lba)
+    for obj_type, payload_len, lba in entries:
+        if obj_type != STATE_MAP_OBJECT_TYPE:
+            continue
+        start = (partition_first_lba + lba) * 512 + STORE_RECORD.size
+        payload = image[start : start + payload_len]
+        if sha256(payload) == root:
+            return [payload[at : at + 68] for at in range(0, len(payload), 68)]
+    fail("the fixture's BootState names a state map the store does not hold")
+
+
+def journal_entries(image: bytes, partition_first_lba: int) -> list[tuple[int, int, bytes]]:
+    """The journal ring's valid entries, oldest first, as (action, BootState
+    sequence, binding)."""
+    entries = []
+    for sector in range(STATE_JOURNAL_SECTORS):
+        start = (partition_first_lba + STATE_JOURNAL_LBA + sector) * 512
+        entry = image[start : start + STATE_JOURNAL_ENTRY_BYTES]
+        if entry[:8] != STATE_JOURNAL_MAGIC:
+            continue
+        sealed = sha256(
+            entry[:STATE_JOURNAL_CHECKSUM_OFFSET]
+            + bytes(STATE_JOURNAL_CHECKSUM_END - STATE_JOURNAL_CHECKSUM_OFFSET)
+            + entry[STATE_JOURNAL_CHECKSUM_END:]
+        )
+        if sealed != entry[STATE_JOURNAL_CHECKSUM_OFFSET:STATE_JOURNAL_CHECKSUM_END]:
+            fail(f"state journal sector {sector} does not checksum")
+        fields = STATE_JOURNAL.unpack(entry)
+        action, sequence, boot_sequence, binding = fields[3], fields[5], fields[6], fields[10]
+        entries.append((sequence, action, boot_sequence, binding))
+    return [(action, boot_sequence, binding) for _, action, boot_sequence, binding in sorted(entries)]
+
+
+def check_disk_writes(
+    disk: Path, before: bytes, boot_disk: Path, boot_before: bytes, partition_first_lba: int
+) -> bytes:
+    """The manager wrote BootState, bisect sessions, the state its stages
+    kept, the shadow evidence it promoted on, and the trust root it rotated
+    to, and nothing else.
 
     It holds `blockRead | blockWrite` over the whole device, so "it only touched
-    its own two sectors" is a property of the component rather than of the
-    capability — which is exactly why it is worth checking from outside.
+    its own sectors" is a property of the component rather than of the
+    capability — which is exactly why it is worth checking from outside. The
+    store may grow only by state and session records, and change past them
+    only in the journal; the sessions on disk must carry the verdicts, because
+    they are what a reboot mid-bisect would resume from. The boot disk may
+    change only in the sectors a root selects from: BootState, the shadow
+    evidence, the health verdict and the trust root. The fixture's shadow
+    verdict on the candidate must have been consumed by the promotion it
+    allowed.
+
+    The import re-admits a generation the store already holds, so it reuses
+    every record it puts and grows the store by nothing.
+
+    The rotation must have stored exactly one trust-root record, the root the
+    fixture's rotation yields, written the same bytes after the boot store,
+    and left the selected BootState naming it.
+
+    Returns the trust-root record, for the stage-0 tree.
+
+    The fixture's known-good BootState names state under both policies a
+    rollback acts on. After the candidate's rollback and promotion the root
+    must name the settings alone, the scratch state dropped by the rollback and
+    never brought back, and the journal must say so transition by transition.
     """
     after = disk.read_bytes()
-    slot_a = (partition_first_lba + 1024) * 512
-    slot_b = slot_a + 512
+    boot_after = boot_disk.read_bytes()
+    journal_end = (partition_first_lba + STATE_JOURNAL_LBA + STATE_JOURNAL_SECTORS) * 512
+    old_append, old_entries = committed_index(before, partition_first_lba)
+    new_append, new_entries = committed_index(after, partition_first_lba)
+    record_area = (partition_first_lba + STORE_RECORD_AREA_START) * 512
     for name, start, end in (
         ("the GPT and protective MBR", 0, partition_first_lba * 512),
-        ("the object store region", partition_first_lba * 512, slot_a),
-        ("the disk beyond the BootState slots", slot_b + 512, len(after)),
+        ("the committed store records", record_area, (partition_first_lba + old_append) * 512),
+        (
+            "the store disk between its records and the journal",
+            (partition_first_lba + new_append) * 512,
+            (partition_first_lba + STATE_JOURNAL_LBA) * 512,
+        ),
+        ("the store disk beyond the journal", journal_end, len(after)),
     ):
         if after[start:end] != before[start:end]:
             fail(f"the generation service modified {name}")
-    if after[slot_a : slot_b + 512] == before[slot_a : slot_b + 512]:
+    slot_a = (partition_first_lba + BOOT_SLOT_A_LBA) * 512
+    slot_b = (partition_first_lba + BOOT_SLOT_B_LBA) * 512
+    evidence = (partition_first_lba + BOOT_EVIDENCE_LBA) * 512
+    verdict = (partition_first_lba + BOOT_VERDICT_LBA) * 512
+    if len(boot_after) != len(boot_before):
+        fail("the boot disk changed size")
+    trust_root = (partition_first_lba + BOOT_TRUST_ROOT_LBA) * 512
+    written = {slot_a, slot_b, evidence, verdict, trust_root}
+    for offset in range(0, len(boot_after), 512):
+        if offset not in written and boot_after[offset : offset + 512] != boot_before[offset : offset + 512]:
+            fail(f"the generation service modified boot disk sector {offset // 512}")
+    if new_entries[: len(old_entries)] != old_entries:
+        fail("the store's committed objects changed under the generation service")
+    appended = new_entries[len(old_entries) :]
+    grown = {
+        STATE_MAP_OBJECT_TYPE,
+        STATE_SNAPSHOT_OBJECT_TYPE,
+        BISECT_SESSION_OBJECT_TYPE,
+        TRUST_ROOT_OBJECT_TYPE,
+    }
+    if any(obj_type not in grown for obj_type, _, _ in appended):
+        fail(
+            f"the store grew by {appended}, expected only state, bisect-session "
+            "and trust-root records"
+        )
+    roots = [entry for entry in appended if entry[0] == TRUST_ROOT_OBJECT_TYPE]
+    if len(roots) != 1:
+        fail(f"{len(roots)} trust-root records were stored, expected the one rotation")
+    _, length, lba = roots[0]
+    start = (partition_first_lba + lba) * 512 + STORE_RECORD.size
+    record = after[start : start + length]
+    if record != trust_root_record(ROTATED_VERSION, ROTATED_THRESHOLD, initial_public_keys()):
+        fail("the stored trust root is not the one the fixture's rotation yields")
+    snapshots = [entry for entry in appended if entry[0] == STATE_SNAPSHOT_OBJECT_TYPE]
+    if len(snapshots) != EXPECTED_SNAPSHOTS:
+        fail(
+            f"{len(snapshots)} state snapshots were stored, expected {EXPECTED_SNAPSHOTS}: "
+            "one per stage of the candidate and of each midpoint"
+        )
+    appended = [entry for entry in appended if entry[0] == BISECT_SESSION_OBJECT_TYPE]
+    if not appended:
+        fail("the store grew by no bisect-session records")
+    if boot_after[slot_a : slot_b + 512] == boot_before[slot_a : slot_b + 512]:
         fail("no BootState slot changed, so nothing was actually committed")
+    if boot_before[evidence : evidence + 512] == bytes(512):
+        fail("the fixture carries no shadow verdict for the promotion to rest on")
+    if boot_after[evidence : evidence + 512] != bytes(512):
+        fail("the shadow verdict the promotion rested on was not consumed")
+
+    sessions = [
+        read_session(after, partition_first_lba, length, lba) for _, length, lba in appended
+    ]
+    serials = [serial for serial, *_ in sessions]
+    if serials != sorted(set(serials)):
+        fail(f"bisect-session serials are not strictly increasing: {serials}")
+    finished = [session for session in sessions if session[1] and session[3] - session[2] == 1]
+    if (
+        not finished
+        or finished[-1][1] != BISECT_CHAIN_COUNT
+        or finished[-1][3] != FIRST_BAD_INDEX
+    ):
+        fail("no persisted bisect session names the first bad generation")
+    if sessions[-1][1] != 0:
+        fail("the newest bisect session is not the reset one")
+
+    _, known_good_root, unrotated = selected_bootstate(boot_before, partition_first_lba)
+    sequence, state_root, reference = selected_bootstate(boot_after, partition_first_lba)
+    if unrotated != bytes(36):
+        fail("the fixture's BootState already names a trust root")
+    if reference != sha256(record) + struct.pack("<I", ROTATED_VERSION):
+        fail(f"BootState sequence {sequence} does not name the rotated trust root")
+    if boot_after[trust_root : trust_root + 512] != record:
+        fail("the boot store's trust-root sector does not hold the rotated root")
+    inherited = state_entries(before, partition_first_lba, known_good_root)
+    if sorted(entry[:32] for entry in inherited) != sorted((SETTINGS, SCRATCH)):
+        fail("the fixture's known-good state does not bind both declared policies")
+    settings = [entry for entry in inherited if entry[:32] == SETTINGS]
+    if len(settings) != 1 or sha256(settings[0]) != state_root:
+        fail(f"BootState sequence {sequence} does not name the settings alone")
+    journal = journal_entries(after, partition_first_lba)
+    if journal != EXPECTED_JOURNAL:
+        fail(f"the state journal records {journal}, expected {EXPECTED_JOURNAL}")
+    print(
+        f"image: the service wrote its BootState slots and {len(sessions)} bisect "
+        "sessions, the finished one naming the first bad generation, journalled "
+        f"{len(EXPECTED_JOURNAL)} state transitions ending on the settings alone "
+        "after the rollback dropped the scratch state, consumed the shadow "
+        f"verdict it promoted on, rotated to trust root version {ROTATED_VERSION} "
+        "in the store and after the boot store, and left every other sector "
+        "byte-identical",
+        flush=True,
+    )
+    return record
+
+
+def check_archive(archive_disk: Path, boot_disk: Path, partition_first_lba: int) -> None:
+    """The export wrote a transfer partition holding one sealed manifest that
+    carries every object it names, and the state closure BootState names: the
+    map whose digest is the header's source state root, then each object that
+    map names, in map order, as `TransferManifest::state_closure` reads it.
+
+    Export and import both left BootState's state root where the bisect left
+    it, and the rotation after them does not move it, so the root BootState
+    names after the run is the one the archive must carry."""
+    image = archive_disk.read_bytes()
+    if len(image) != ARCHIVE_DISK_BYTES:
+        fail("the archive disk changed size")
+    # The GPT header's entry array LBA, then the one partition's type GUID and
+    # first LBA.
+    if image[512:520] != b"EFI PART":
+        fail("the archive disk carries no GPT")
+    entries = struct.unpack_from("<Q", image, 512 + 72)[0] * 512
+    if image[entries : entries + 16] != TRANSFER_TYPE_GUID:
+        fail("the archive disk's partition is not a transfer partition")
+    start = struct.unpack_from("<Q", image, entries + 32)[0] * 512
+    header = TRANSFER_HEADER.unpack_from(image, start)
+    magic, version, source_state_root = header[0], header[1], header[6]
+    object_count, object_offset, total_len = header[11], header[13], header[19]
+    if magic != TRANSFER_MAGIC or version != TRANSFER_VERSION:
+        fail("the archive partition does not start with a transfer manifest")
+    manifest = image[start : start + total_len]
+    sealed = sha256(
+        manifest[:TRANSFER_HEADER_HASH_OFFSET]
+        + bytes(TRANSFER_HEADER_HASH_END - TRANSFER_HEADER_HASH_OFFSET)
+        + manifest[TRANSFER_HEADER_HASH_END:]
+    )
+    seal = manifest[TRANSFER_HEADER_HASH_OFFSET:TRANSFER_HEADER_HASH_END]
+    if len(manifest) != total_len or sealed != seal:
+        fail("the archived manifest does not check against its seal")
+    objects = []
+    for number in range(object_count):
+        digest, length, payload_offset, _, flags = TRANSFER_OBJECT.unpack_from(
+            manifest, object_offset + number * TRANSFER_OBJECT.size
+        )
+        payload = manifest[payload_offset : payload_offset + length]
+        if flags != TRANSFER_OBJECT_FLAG_PAYLOAD or sha256(payload) != digest:
+            fail(f"archived object {number} is not carried whole")
+        objects.append((digest, payload))
+    _, state_root, _ = selected_bootstate(boot_disk.read_bytes(), partition_first_lba)
+    if source_state_root != state_root:
+        fail("the archive names a state root BootState does not")
+    digests = [digest for digest, _ in objects]
+    if state_root not in digests:
+        fail("the archive does not carry the state map it names")
+    first = digests.index(state_root)
+    map_payload = objects[first][1]
+    named = [map_payload[at : at + 32] for at in range(32, len(map_payload), 68)]
+    if not named or digests[first + 1 :] != named:
+        fail(
+            f"the archive carries {digests[first + 1 :]} after its state map, "
+            f"expected the objects it names, {named}"
+        )
+    print(
+        f"archive: a sealed transfer manifest of {object_count} objects, every one "
+        f"carried, the last {len(named) + 1} the state map BootState names and "
+        "the objects it names",
+        flush=True,
+    )
+
+
+def stage0_trust_root(bootstore: bytes, record: bytes | None) -> int | None:
+    """The trust-root version stage 0 would verify releases against, or None
+    where it fails the boot: `stage0::trust_root` over the BootState a boot
+    store selects."""
+    best: tuple[int, bytes] | None = None
+    for slot in (BOOT_SLOT_A_LBA, BOOT_SLOT_B_LBA):
+        sector = bootstore[slot * 512 : (slot + 1) * 512]
+        fields = BOOTSTATE_SLOT.unpack(sector)
+        if fields[0] != BOOTSTATE_MAGIC:
+            continue
+        if bootstate_checksum(sector) != sector[BOOTSTATE_CHECKSUM_OFFSET:BOOTSTATE_CHECKSUM_END]:
+            continue
+        if best is None or fields[4] > best[0]:
+            best = (fields[4], sector[BOOTSTATE_TRUST_ROOT_OFFSET:BOOTSTATE_TRUST_ROOT_VERSION_END])
+    if best is None:
+        return None
+    reference = best[1]
+    if reference == bytes(36):
+        return 1
+    if record is None or sha256(record) != reference[:32]:
+        return None
+    magic, format_version, header_size, _, version, *_ = TRUST_ROOT_HEADER.unpack_from(record)
+    if (
+        magic != TRUST_ROOT_MAGIC
+        or format_version != TRUST_ROOT_RECORD_VERSION
+        or header_size != TRUST_ROOT_HEADER.size
+        or version != struct.unpack("<I", reference[32:])[0]
+    ):
+        return None
+    return version
+
+
+def write_stage0_tree(boot_disk: Path, record: bytes, partition_first_lba: int) -> None:
+    """Lay out the files stage 0 reads after the plane's rotation, and hold
+    them to its checks: the rotated root is the one it verifies against, and
+    without the record, or with one naming another version, it fails the
+    boot rather than falling back to the compiled-in root."""
+    start = partition_first_lba * 512
+    bootstore = boot_disk.read_bytes()[start : start + BOOTSTORE_CAPACITY]
+    STAGE0_BOOT.mkdir(parents=True, exist_ok=True)
+    (STAGE0_BOOT / "boot-store.bin").write_bytes(bootstore)
+    (STAGE0_BOOT / "trust-root.bin").write_bytes(record)
+    laid_out = stage0_trust_root(
+        (STAGE0_BOOT / "boot-store.bin").read_bytes(),
+        (STAGE0_BOOT / "trust-root.bin").read_bytes(),
+    )
+    if laid_out != ROTATED_VERSION:
+        fail("stage 0 would not verify against the rotated trust root")
+    if stage0_trust_root(bootstore, None) is not None:
+        fail("stage 0 would boot a rotated BootState without its trust-root record")
+    other = trust_root_record(ROTATED_VERSION + 1, ROTATED_THRESHOLD, initial_public_keys())
+    if stage0_trust_root(bootstore, other) is not None:
+        fail("stage 0 would boot against a trust root BootState does not name")
     print(
-        "image: the service wrote its two BootState slots and left every other "
-        "sector byte-identical",
+        f"stage 0: wrote boot-store.bin and trust-root.bin under "
+        f"{STAGE0_BOOT.relative_to(ROOT)}; they select trust root version "
+        f"{ROTATED_VERSION}, and stage 0 refuses the boot without the record "
+        "or with another",
         flush=True,
     )
 
@@ -404,23 +1035,32 @@ def main() -> None:
         build_image()
     if not IMAGE.is_file():
         fail(f"missing packaged image {IMAGE.relative_to(ROOT)}")
+    if not GENERATION.is_file():
+        fail(f"missing built generation {GENERATION.relative_to(ROOT)}")
     profile = pins["qemu_arm_virt"]
     assert isinstance(profile, dict)
 
     with tempfile.TemporaryDirectory() as directory:
         disk = Path(directory) / "generation-plane.img"
-        build_fixture(disk)
+        boot_disk = Path(directory) / "generation-plane-boot.img"
+        archive_disk = Path(directory) / "generation-plane-archive.img"
+        build_fixture(disk, boot_disk)
+        archive_disk.write_bytes(bytes(ARCHIVE_DISK_BYTES))
         before = disk.read_bytes()
-        transcript = boot(profile, disk)
+        boot_before = boot_disk.read_bytes()
+        transcript = boot(profile, disk, archive_disk, boot_disk)
         check_transcript(transcript)
-        check_only_state_slots(disk, before, 40)
+        record = check_disk_writes(disk, before, boot_disk, boot_before, 40)
+        check_archive(archive_disk, boot_disk, 40)
+        write_stage0_tree(boot_disk, record, 40)
 
     print(
         "seL4 generation plane check: an unprivileged client drove list, "
-        "inspect, stage, select, and rollback through a management service "
-        "holding the only block capability, every refusal left the root "
-        "untouched, and the client's own direct device request was refused "
-        "because no slot it holds names a device"
+        "inspect, stage, select, rollback, a bisect, an export and import and a "
+        "trust-root rotation through a management service holding the only block "
+        "capability, the archive carries its state, every refusal left "
+        "the root untouched, stage 0's files name the rotated root, and the client's "
+        "own direct device request was refused because no slot it holds names a device"
     )
 
 
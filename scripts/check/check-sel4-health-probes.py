@@ -0,0 +1,174 @@
+#!/usr/bin/env python3
+"""Declared health probes decide a candidate's verdict on a real boot.
+
+`sel4-health.zti` is the call plane with its server required and probed:
+running, a fault window of `healthFaultWindowTicks`, and the `parameters`
+route named by `healthRoute`. The route probe passes only when the fabric
+broker reports that it carried a reply from that server -- the server has no
+way to report it for itself.
+
+Two candidates, each pending against the `sel4` generation as known-good:
+
+* the plane as declared, which must record `verdict=passed` with every probe
+  passed and promote;
+* the same plane with the server built to take its role and leave before
+  answering (`SLIME_FABRIC_CALL_SERVER_EARLY_EXIT`), which must record
+  `verdict=failed` on the route probe while running and the fault window
+  pass, and roll back on the next boot with an attempt still unspent.
+"""
+from __future__ import annotations
+
+import sys as _sys
+from pathlib import Path as _Path
+
+_sys.path.insert(0, str(_Path(__file__).resolve().parents[1] / "lib"))
+
+import json
+import os
+import re
+import subprocess
+import sys
+import tempfile
+from pathlib import Path
+from typing import NoReturn
+
+from harness import ROOT, load_script
+
+SELECTION = load_script("check_sel4_boot_selection", "check/check-sel4-boot-selection.py")
+GENERATOR = ROOT / "scripts/build/build-generation.py"
+BUILD = ROOT / "scripts/build/build-sel4.py"
+IDENTITY = ROOT / "build/slime-sel4-boot-selection.identity.json"
+MANIFEST = "sel4-health"
+
+# `HEALTH_PROBE_*` in `contracts/generation/v6/schema.zt`.
+PROBE_ROUTE = 4
+# `init` and `fabric-service` declare running; the server declares all three.
+DECLARED_PROBES = 5
+
+
+def fail(message: str) -> NoReturn:
+    raise SystemExit(f"seL4 health probe check: {message}")
+
+
+def build_candidate(output: Path, bundle: str, *, early_exit: bool = False) -> Path:
+    """Build `sel4-health` at its own generation number.
+
+    The number is left as declared: the call plane's boot layout is keyed by
+    generation 18, so renumbering the candidate would boot the base table.
+    """
+    environment = dict(os.environ)
+    environment.update(
+        SLIME_TARGET_PROFILE="aarch64-sel4-qemu-virt",
+        SLIME_SEL4_MANIFEST=MANIFEST,
+        SLIME_BOOT_BUNDLE_IDENTITY=bundle,
+    )
+    environment.pop("SLIME_GENERATION_NUMBER", None)
+    if early_exit:
+        environment["SLIME_FABRIC_CALL_SERVER_EARLY_EXIT"] = "1"
+    else:
+        environment.pop("SLIME_FABRIC_CALL_SERVER_EARLY_EXIT", None)
+    process = subprocess.run(
+        [sys.executable, str(GENERATOR), str(output)], cwd=ROOT, env=environment, check=False
+    )
+    if process.returncode:
+        fail(f"generation builder failed ({process.returncode}) for {MANIFEST}")
+    generation = output / "generation.bin"
+    if not generation.is_file():
+        fail(f"generation builder omitted {generation}")
+    return generation
+
+
+def declared_number() -> int:
+    builder = load_script("slime_build_generation_health", "build/build-generation.py")
+    saved = dict(os.environ)
+    try:
+        os.environ["SLIME_SEL4_MANIFEST"] = MANIFEST
+        manifest = builder.load_manifest()
+    finally:
+        os.environ.clear()
+        os.environ.update(saved)
+    server = next(
+        instance for instance in manifest["instances"] if instance["name"] == "fabric-call-server"
+    )
+    if server.get("healthRoute") != "parameters" or not server.get("healthFaultWindowTicks"):
+        fail("sel4-health.zti no longer probes the server's route and fault window")
+    return int(manifest["generation"])
+
+
+def verdict(transcript: str) -> dict[str, str]:
+    match = re.search(r"SLIME_BOOT verdict=(\S+)((?: \w+=\S+)*)", transcript)
+    if match is None:
+        fail("missing marker SLIME_BOOT verdict=")
+    fields = dict(field.split("=", 1) for field in match.group(2).split())
+    fields["verdict"] = match.group(1)
+    return fields
+
+
+def expect_passed(disk: Path, store: bytes, number: int) -> None:
+    SELECTION.make_disk(disk, store)
+    before = disk.read_bytes()
+    transcript = SELECTION.boot(disk, "SLIME_BOOT promoted")
+    SELECTION.expect(transcript, number, 1, 1)
+    fields = verdict(transcript)
+    if fields["verdict"] != "passed":
+        fail(f"the declared plane recorded verdict={fields['verdict']}")
+    if fields["probes"] != str(DECLARED_PROBES) or fields["passed"] != str(DECLARED_PROBES):
+        fail(f"expected {DECLARED_PROBES} probes all passed, saw {fields}")
+    if fields["failed"] != "0" or fields["recorded"] != "1":
+        fail(f"a passed verdict must be recorded with nothing failed, saw {fields}")
+    after = disk.read_bytes()
+    SELECTION.only_slots(before, after)
+    confirmed = SELECTION.boot(disk, "SLIME_BOOT selected")
+    SELECTION.expect(confirmed, number, 0, 0)
+
+
+def expect_failed(disk: Path, store: bytes, number: int) -> None:
+    SELECTION.make_disk(disk, store)
+    before = disk.read_bytes()
+    transcript = SELECTION.boot(disk, "SLIME_BOOT verdict=")
+    SELECTION.expect(transcript, number, 1, 1)
+    if "[fabric-call-server] injected early server exit" not in transcript:
+        fail("the failing candidate's server did not take the injected exit")
+    fields = verdict(transcript)
+    if fields["verdict"] != "failed":
+        fail(f"a server that never answered recorded verdict={fields['verdict']}")
+    if fields["failed_probe"] != str(PROBE_ROUTE):
+        fail(f"expected the route probe to fail first, saw failed_probe={fields['failed_probe']}")
+    if fields["recorded"] != "1":
+        fail(f"the failed verdict was not recorded, saw {fields}")
+    if "SLIME_BOOT promoted" in transcript:
+        fail("a failed verdict promoted its candidate")
+    after = disk.read_bytes()
+    SELECTION.only_slots(before, after)
+    # One attempt is still declared; the recorded verdict, not exhaustion, is
+    # what sends the next boot back to known-good.
+    rolled_back = SELECTION.boot(disk, "SLIME_BOOT selected")
+    SELECTION.expect(rolled_back, 1, 0, 0)
+
+
+def main() -> None:
+    if Path.cwd().resolve() != ROOT:
+        fail("run from repository root")
+    number = declared_number()
+    SELECTION.run([sys.executable, str(BUILD), "--boot-selection"])
+    bundle = str(json.loads(IDENTITY.read_text(encoding="utf-8"))["boot_bundle_identity"])
+    with tempfile.TemporaryDirectory(prefix="slime-health-") as temporary:
+        work = Path(temporary)
+        known_good = SELECTION.build_generation(work / "known-good", 1, bundle)
+        answering = build_candidate(work / "answering", bundle)
+        silent = build_candidate(work / "silent", bundle, early_exit=True)
+        expect_passed(
+            work / "passed.img", SELECTION.make_store([known_good, answering], bundle, 2), number
+        )
+        expect_failed(
+            work / "failed.img", SELECTION.make_store([known_good, silent], bundle, 2), number
+        )
+    print(
+        "seL4 health probe check: a server whose route the broker answered passed "
+        "every declared probe and promoted, and a server that left unanswered failed "
+        "the route probe and rolled back"
+    )
+
+
+if __name__ == "__main__":
+    main()
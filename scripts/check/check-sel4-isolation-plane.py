@@ -0,0 +1,278 @@
+#!/usr/bin/env python3
+
+"""B48 gate: CPU isolation under the MCS kernel profile.
+
+The plane pairs a saturating component with a reserved control loop. The
+saturator spins without yielding at a priority *above* the loop, held to 2 ms in
+every 10 ms. Under priority alone nothing would preempt it for the loop, so the
+loop finishing its 64 ticks while the saturator is still spinning shows the
+kernel enforced the budget. The saturator's `saturation ended` line is therefore
+a failure marker: the transcript is cut at the loop's completion, and that line
+can appear before the cut only if the loop got CPU no sooner than the saturator
+ran out of work.
+
+Each tick is also a native send to the control loop's worker thread, so the
+worker's receive runs through its own Reply object. Before each thread had one,
+that receive was refused with `ERR_BAD_CAP`.
+
+Both threads allocate from the growable heap, with the worker at a lower
+priority than the main thread. When the main thread finds the heap's lock held
+by the worker, it has to block on the task's thread-lock Notification, because
+spinning would take the CPU the worker needs to release the lock. The heap
+marker confirms the allocations stayed inside the fixture's `heapPages`.
+
+The image is built only for the MCS profile: the fixture declares reservations,
+which the builder refuses for the product kernel.
+"""
+
+from __future__ import annotations
+
+import argparse
+import re
+import shutil
+import subprocess
+import sys
+import threading
+import tomllib
+from pathlib import Path
+from typing import NoReturn
+
+sys.path.insert(0, str(Path(__file__).resolve().parents[1] / "lib"))
+
+from harness import profile_text, profile_integer  # noqa: E402
+
+ROOT = Path(__file__).resolve().parents[2]
+PINS_PATH = ROOT / "sel4" / "pins.toml"
+BUILD_SCRIPT = ROOT / "scripts" / "build" / "build-sel4.py"
+IMAGE = ROOT / "build" / "slime-sel4-isolation-mcs.elf"
+FIXTURE = ROOT / "contracts" / "generation" / "v1" / "fixtures" / "sel4-isolation.zti"
+BOOT_TIMEOUT_SECONDS = 180
+
+REQUIRED_MARKERS: tuple[tuple[str, str], ...] = (
+    (
+        "the root booted on the MCS kernel",
+        r"SLIME_ROOT kernel profile=mcs",
+    ),
+    (
+        "the saturator's reservation reached its thread",
+        r"SLIME_GRAPH reservation instance=isolation-saturator thread=0 "
+        r"budget_us=2000 period_us=10000",
+    ),
+    (
+        "the control loop's main thread has its own reservation",
+        r"SLIME_GRAPH reservation instance=isolation-control-loop thread=0 "
+        r"budget_us=5000 period_us=10000",
+    ),
+    (
+        "so does its worker",
+        r"SLIME_GRAPH reservation instance=isolation-control-loop thread=1 "
+        r"budget_us=5000 period_us=10000",
+    ),
+    (
+        # Init is above both components, so it runs and exits first.
+        "init launched the plane",
+        r"\[init\] isolation plane launched",
+    ),
+    (
+        # Above the loop, so it is running before the loop is first scheduled.
+        "the saturator started spinning",
+        r"\[isolation-saturator\] saturating",
+    ),
+    (
+        "the control loop was scheduled below a spinning saturator",
+        r"\[isolation-control-loop\] control loop running",
+    ),
+    (
+        "the worker received every tick through its own Reply object",
+        r"\[isolation-control-loop\] worker received ticks=64",
+    ),
+    (
+        "both threads allocated inside the declared heap pages",
+        r"\[isolation-control-loop\] heap shared by both threads pages=[1-4]",
+    ),
+    (
+        "the control loop finished while the saturator was still spinning",
+        r"\[isolation-control-loop\] control loop complete ticks=64",
+    ),
+)
+
+TERMINAL_MARKER = r"\[isolation-control-loop\] control loop complete ticks=64"
+
+FAILURE_MARKERS: tuple[str, ...] = (
+    r"SLIME_ROOT FATAL",
+    r"SLIME_ROOT FAIL",
+    r"SLIME_GRAPH FAIL",
+    r"\[init\] unknown boot action",
+    # Before the terminal marker, this is the budget not being enforced.
+    r"\[isolation-saturator\] saturation ended",
+    r"\[isolation-control-loop\] fail: .*",
+    r"Caught cap fault",
+    r"Caught vm fault",
+    r"Caught user exception",
+    r"panicked at ",
+    r"aborted at ",
+    r"\(aborted\)",
+)
+
+
+def fail(message: str) -> NoReturn:
+    raise SystemExit(f"seL4 isolation plane check: {message}")
+
+
+def load_pins() -> dict[str, object]:
+    if not PINS_PATH.is_file():
+        fail(f"missing pin manifest: {PINS_PATH.relative_to(ROOT)}")
+    try:
+        pins = tomllib.loads(PINS_PATH.read_text(encoding="utf-8"))
+    except (OSError, tomllib.TOMLDecodeError) as error:
+        fail(f"cannot parse {PINS_PATH.relative_to(ROOT)}: {error}")
+    if pins.get("schema") != 1:
+        fail("unsupported sel4/pins.toml schema (expected 1)")
+    # The MCS profile boots on the product's QEMU machine pins and carries none
+    # of its own.
+    if not isinstance(pins.get("qemu_arm_virt"), dict):
+        fail("sel4/pins.toml is missing [qemu_arm_virt]")
+    return pins
+
+
+def build_image() -> None:
+    command = [sys.executable, str(BUILD_SCRIPT), "--mcs", "--isolation-plane"]
+    print(f"[build] {' '.join(command)}", flush=True)
+    try:
+        process = subprocess.run(command, cwd=ROOT, check=False)
+    except OSError as error:
+        fail(f"cannot run the seL4 image build: {error}")
+    if process.returncode != 0:
+        fail(f"seL4 image build failed with exit status {process.returncode}")
+
+
+def boot(profile: dict[str, object]) -> str:
+    qemu = shutil.which("qemu-system-aarch64")
+    if qemu is None:
+        fail("qemu-system-aarch64 is not on PATH")
+    command = [
+        qemu,
+        "-machine",
+        profile_text(profile, "machine", fail),
+        "-cpu",
+        profile_text(profile, "cpu", fail),
+        "-smp",
+        str(profile_integer(profile, "cpus", fail)),
+        "-m",
+        f"size={profile_integer(profile, 'memory_mib', fail)}M",
+        "-nographic",
+        "-serial",
+        "mon:stdio",
+        "-kernel",
+        str(IMAGE),
+    ]
+    print(f"[boot] {' '.join(command)}", flush=True)
+    failures = re.compile("|".join(FAILURE_MARKERS))
+    terminal = re.compile(TERMINAL_MARKER)
+    lines: list[str] = []
+    reached = False
+    try:
+        process = subprocess.Popen(
+            command,
+            cwd=ROOT,
+            stdin=subprocess.DEVNULL,
+            stdout=subprocess.PIPE,
+            stderr=subprocess.STDOUT,
+            text=True,
+            bufsize=1,
+        )
+    except OSError as error:
+        fail(f"cannot run QEMU: {error}")
+    watchdog = threading.Timer(BOOT_TIMEOUT_SECONDS, process.kill)
+    watchdog.start()
+    try:
+        assert process.stdout is not None
+        # Stop at the loop's completion. The saturator goes on spinning and
+        # eventually reports its end, which is legitimate after this point and
+        # the failure this plane exists to catch before it.
+        for line in process.stdout:
+            lines.append(line.rstrip("\r\n"))
+            if failures.search(line):
+                break
+            if terminal.search(line) is not None:
+                reached = True
+                break
+    finally:
+        watchdog.cancel()
+        process.terminate()
+        try:
+            process.wait(timeout=10)
+        except subprocess.TimeoutExpired:
+            process.kill()
+            process.wait()
+    transcript = "\n".join(lines)
+    if not reached:
+        report_transcript(transcript)
+        fail(f"boot exceeded {BOOT_TIMEOUT_SECONDS}s without completing the plane")
+    return transcript
+
+
+def report_transcript(transcript: str) -> None:
+    tail = transcript.splitlines()[-40:]
+    if tail:
+        sys.stdout.write("--- serial transcript (tail) ---\n")
+        sys.stdout.write("\n".join(tail) + "\n")
+        sys.stdout.write("--- end transcript ---\n")
+        sys.stdout.flush()
+
+
+def check_transcript(transcript: str) -> None:
+    for pattern in FAILURE_MARKERS:
+        match = re.search(pattern, transcript)
+        if match is not None:
+            report_transcript(transcript)
+            fail(f"failure marker in serial transcript: {match.group(0)!r}")
+    position = 0
+    for label, pattern in REQUIRED_MARKERS:
+        match = re.compile(pattern).search(transcript, position)
+        if match is None:
+            report_transcript(transcript)
+            if re.search(pattern, transcript) is not None:
+                fail(f"marker out of order: {label} ({pattern})")
+            fail(f"missing marker: {label} ({pattern})")
+        position = match.end()
+    print(
+        f"transcript: {len(REQUIRED_MARKERS)} markers observed; the control "
+        "loop finished below a saturator held to its reservation, its "
+        "worker received every tick, and both threads shared the heap",
+        flush=True,
+    )
+
+
+def main() -> None:
+    parser = argparse.ArgumentParser(
+        description="Boot the seL4 MCS isolation-plane image and assert CPU isolation"
+    )
+    parser.add_argument(
+        "--no-build",
+        action="store_true",
+        help="boot the already-built image instead of rebuilding it first",
+    )
+    arguments = parser.parse_args()
+
+    if Path.cwd().resolve() != ROOT:
+        fail(f"run from repository root: {ROOT}")
+    if not FIXTURE.is_file():
+        fail(f"missing generation fixture {FIXTURE.relative_to(ROOT)}")
+    pins = load_pins()
+    if not arguments.no_build:
+        build_image()
+    if not IMAGE.is_file():
+        fail(f"missing packaged image {IMAGE.relative_to(ROOT)}")
+    profile = pins["qemu_arm_virt"]
+    assert isinstance(profile, dict)
+    check_transcript(boot(profile))
+    print(
+        "seL4 isolation plane check: a control loop below a saturating "
+        "component finished its ticks while the saturator was still spinning, "
+        "and its worker thread received through its own Reply object"
+    )
+
+
+if __name__ == "__main__":
+    main()
@@ -19,6 +19,7 @@ from harness import sha256_file  # noqa: E402
 ROOT = Path(__file__).resolve().parents[2]
 PINS_PATH = ROOT / "sel4" / "pins.toml"
 QEMU_CONFIG_PATH = ROOT / "sel4" / "config" / "qemu-arm-virt.cmake"
+QEMU_MCS_CONFIG_PATH = ROOT / "sel4" / "config" / "qemu-arm-virt-mcs.cmake"
 RPI5_CONFIG_PATH = ROOT / "sel4" / "config" / "bcm2712-rpi5.cmake"
 SEL4_PATH = ROOT / "deps" / "sel4"
 RUST_SEL4_PATH = ROOT / "deps" / "rust-sel4"
@@ -183,32 +184,34 @@ def parse_cmake_cache(path: Path) -> dict[str, str]:
     return values
 
 
-def expected_cmake_values(profile: dict[str, object]) -> dict[str, str]:
+def expected_cmake_values(
+    profile: dict[str, object], section: str = "qemu_arm_virt"
+) -> dict[str, str]:
     return {
-        "KernelPlatform": text(profile, "platform", "qemu_arm_virt"),
-        "KernelSel4Arch": text(profile, "sel4_arch", "qemu_arm_virt"),
+        "KernelPlatform": text(profile, "platform", section),
+        "KernelSel4Arch": text(profile, "sel4_arch", section),
         "KernelArmHypervisorSupport": "ON"
-        if boolean(profile, "hypervisor", "qemu_arm_virt")
+        if boolean(profile, "hypervisor", section)
         else "OFF",
-        "KernelIsMCS": "ON" if boolean(profile, "mcs", "qemu_arm_virt") else "OFF",
-        "KernelMaxNumNodes": str(integer(profile, "nodes", "qemu_arm_virt")),
+        "KernelIsMCS": "ON" if boolean(profile, "mcs", section) else "OFF",
+        "KernelMaxNumNodes": str(integer(profile, "nodes", section)),
         "KernelVerificationBuild": "ON"
-        if boolean(profile, "verification_build", "qemu_arm_virt")
+        if boolean(profile, "verification_build", section)
         else "OFF",
         "KernelDebugBuild": "ON"
-        if boolean(profile, "debug_build", "qemu_arm_virt")
+        if boolean(profile, "debug_build", section)
         else "OFF",
-        "KernelPrinting": "ON" if boolean(profile, "printing", "qemu_arm_virt") else "OFF",
+        "KernelPrinting": "ON" if boolean(profile, "printing", section) else "OFF",
         # `slime-root`'s timer phase reads/writes the EL1 physical
         # counter/timer registers directly from EL0; see
         # `slime-root/src/platform_timer.rs` for why this is the one
         # architected-timer PPI available to it under
         # `KernelArmHypervisorSupport ON`.
         "KernelArmExportPCNTUser": "ON"
-        if boolean(profile, "export_pcnt_user", "qemu_arm_virt")
+        if boolean(profile, "export_pcnt_user", section)
         else "OFF",
         "KernelArmExportPTMRUser": "ON"
-        if boolean(profile, "export_ptmr_user", "qemu_arm_virt")
+        if boolean(profile, "export_ptmr_user", section)
         else "OFF",
     }
 
@@ -351,6 +354,46 @@ def check_profile(pins: dict[str, object]) -> None:
     if integer(profile, "memory_mib", "qemu_arm_virt") != 2048:
         fail("qemu-arm-virt QEMU memory must be 2048 MiB")
 
+    # The MCS profile is the product profile with the scheduler changed, and
+    # nothing else. Comparing it key by key against the product's expected
+    # values keeps the two from drifting apart, because a difference between
+    # the two images must come from the scheduler alone.
+    mcs_profile = table(pins, "qemu_arm_virt_mcs")
+    mcs_expected = expected_cmake_values(mcs_profile, "qemu_arm_virt_mcs")
+    mcs_actual = parse_cmake_cache(QEMU_MCS_CONFIG_PATH)
+    if mcs_actual != mcs_expected:
+        details = [
+            f"{key}: expected {mcs_expected.get(key)!r}, got {mcs_actual.get(key)!r}"
+            for key in sorted(set(mcs_expected) | set(mcs_actual))
+            if mcs_expected.get(key) != mcs_actual.get(key)
+        ]
+        fail("qemu-arm-virt MCS CMake config disagrees with pins.toml:\n" + "\n".join(details))
+    if mcs_expected["KernelIsMCS"] != "ON":
+        fail("qemu-arm-virt MCS profile must enable MCS")
+    # Under MCS the root takes deadlines from a scheduling context's period and
+    # counts time by another's replenishments, so it touches neither the
+    # physical timer nor the counter. Both exports must stay off: with the
+    # counter exported, any component could read time without a clock grant.
+    if mcs_expected["KernelArmExportPTMRUser"] != "OFF":
+        fail("qemu-arm-virt MCS profile must not export the physical timer")
+    if mcs_expected["KernelArmExportPCNTUser"] != "OFF":
+        fail("qemu-arm-virt MCS profile must not export the physical counter")
+    scheduler_keys = {
+        "KernelIsMCS",
+        "KernelArmExportPTMRUser",
+        "KernelArmExportPCNTUser",
+    }
+    drift = sorted(
+        key
+        for key in set(expected) | set(mcs_expected)
+        if key not in scheduler_keys and expected.get(key) != mcs_expected.get(key)
+    )
+    if drift:
+        fail(
+            "qemu-arm-virt MCS profile differs from the product beyond the scheduler: "
+            + ", ".join(drift)
+        )
+
     rpi5 = parse_cmake_cache(RPI5_CONFIG_PATH)
     include = "${CMAKE_CURRENT_LIST_DIR}/../../deps/sel4/configs/AARCH64_bcm2712_verified.cmake"
     # The inherited verified profile supplies platform/architecture and turns
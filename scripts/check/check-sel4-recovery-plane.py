@@ -9,7 +9,9 @@ both halves.
 
 The rebuild: both slots are corrupt so selection refuses; a signed recovery
 index decodes; every state object it names is retrieved from the object store
-with its payload re-hashed; the reconstructed root is written to both slots at
+with its payload re-hashed; so is the rotated trust root it names, which the
+reconstructed root must carry rather than revert to the compiled-in keys; the
+reconstructed root is written to both slots at
 sequences 1 and 2, each flushed; and the result is re-selected off the device
 and must be the index's target. Running it twice must converge.
 
@@ -35,6 +37,12 @@ from typing import NoReturn
 
 sys.path.insert(0, str(Path(__file__).resolve().parents[1] / "lib"))
 
+from boot_contracts import (  # noqa: E402
+    BOOTSTATE_TRUST_ROOT_OFFSET,
+    BOOTSTATE_TRUST_ROOT_VERSION_END,
+    RECOVERY_INDEX_TRUST_ROOT_OFFSET,
+    RECOVERY_INDEX_TRUST_ROOT_VERSION_END,
+)
 from harness import profile_text, profile_integer  # noqa: E402
 
 ROOT = Path(__file__).resolve().parents[2]
@@ -56,6 +64,9 @@ RECONSTRUCTED_SEQUENCE = 2
 RECONSTRUCTED_RELEASE = 3
 # The fixture's state closure: the store's seeded object.
 CLOSURE_OBJECTS = 1
+# `RECOVERY_TRUST_ROOT_VERSION` in build-store-fixture.py: the compiled-in root
+# rotated once.
+TRUST_ROOT_VERSION = 2
 
 REQUIRED_MARKERS: tuple[tuple[str, str], ...] = (
     (
@@ -80,6 +91,12 @@ REQUIRED_MARKERS: tuple[tuple[str, str], ...] = (
         rf"\[sel4-recovery-probe\] index states={CLOSURE_OBJECTS} "
         rf"release={RECONSTRUCTED_RELEASE}",
     ),
+    (
+        # The whole store walked in bounded steps, not one pass; a damaged
+        # record would be listed with its closure references before this.
+        "an incremental scrub found no damaged record",
+        r"\[sel4-recovery-probe\] scrub records=\d+ damaged=0",
+    ),
     (
         # Every named object retrieved and re-hashed. A closure with a missing
         # or corrupted object fails here, before anything is written.
@@ -87,6 +104,13 @@ REQUIRED_MARKERS: tuple[tuple[str, str], ...] = (
         rf"\[sel4-recovery-probe\] closure verified objects={CLOSURE_OBJECTS} "
         rf"of={CLOSURE_OBJECTS}",
     ),
+    (
+        # Retrieved and decoded at the recorded version before any write; a
+        # reconstruction that dropped it would hand stage 0 the retired keys.
+        "the rotated trust root the index names was restored",
+        rf"\[sel4-recovery-probe\] trust root restored version={TRUST_ROOT_VERSION} "
+        rf"release={RECONSTRUCTED_RELEASE}",
+    ),
     (
         # Re-selected off the device rather than assumed from what was written.
         "the reconstructed root is what a fresh boot would select",
@@ -128,6 +152,7 @@ FAILURE_MARKERS: tuple[str, ...] = (
     r"SLIME_GRAPH wedged waiter",
     r"\[init\] recovery plane fail: .*",
     r"\[sel4-recovery-probe\] fail: .*",
+    r"\[sel4-recovery-probe\] scrub damaged .*",
     r"SLIME_ROOT block bring-up failed",
     r"Caught cap fault",
     r"Caught vm fault",
@@ -304,7 +329,8 @@ def check_transcript(transcript: str) -> None:
 
 
 def check_reconstructed(disk: Path, partition_first_lba: int) -> None:
-    """Both slots carry a BootState record in the image after the boot."""
+    """Both slots carry a BootState record in the image after the boot, each
+    naming the trust root the recovery index records."""
     image = disk.read_bytes()
     magic = b"SLIMEBS\0"
     present = sum(
@@ -317,9 +343,22 @@ def check_reconstructed(disk: Path, partition_first_lba: int) -> None:
     )
     if present != 2:
         fail(f"{present} of 2 slots carry a record after reconstruction")
+    index = (partition_first_lba + 1026) * 512
+    expected = image[
+        index + RECOVERY_INDEX_TRUST_ROOT_OFFSET : index + RECOVERY_INDEX_TRUST_ROOT_VERSION_END
+    ]
+    if expected[32:] != TRUST_ROOT_VERSION.to_bytes(4, "little") or not any(expected[:32]):
+        fail("the fixture's recovery index names no rotated trust root")
+    for offset in (1024, 1025):
+        slot = (partition_first_lba + offset) * 512
+        carried = image[
+            slot + BOOTSTATE_TRUST_ROOT_OFFSET : slot + BOOTSTATE_TRUST_ROOT_VERSION_END
+        ]
+        if carried != expected:
+            fail(f"the slot at {offset} does not carry the index's trust root")
     print(
-        "image: both BootState slots carry a record, so reconstruction left "
-        "redundancy rather than a single root",
+        "image: both BootState slots carry a record naming the rotated trust "
+        "root, so reconstruction left redundancy rather than a single root",
         flush=True,
     )
 
@@ -402,8 +441,8 @@ def main() -> None:
         "seL4 recovery plane check: a component refused two corrupt BootState "
         "slots, decoded a signed recovery index, verified its whole state "
         "closure against the content-addressed store, reconstructed a bootable "
-        "root into both slots idempotently, and left an attached disk that no "
-        "capability names byte-identical"
+        "root under the rotated trust root into both slots idempotently, and "
+        "left an attached disk that no capability names byte-identical"
     )
 
 
@@ -39,6 +39,7 @@ from typing import NoReturn
 
 sys.path.insert(0, str(Path(__file__).resolve().parents[1] / "lib"))
 
+from boot_contracts import STORE_INDEX_A_LBA, STORE_INDEX_SECTORS  # noqa: E402
 from harness import profile_text, profile_integer  # noqa: E402
 
 ROOT = Path(__file__).resolve().parents[2]
@@ -420,9 +421,16 @@ def check_seeded_untouched(disk: Path, before: bytes) -> None:
             "the commit wrote superblock slots "
             f"{changed or ['none']}, expected exactly the older slot B"
         )
+    # Each commit writes the index beside the slot it claims. Slot A's index
+    # is part of the root that must survive, so it does not move either.
+    index_a = slot_a + STORE_INDEX_A_LBA * 512
+    index_a_end = index_a + STORE_INDEX_SECTORS * 512
+    if after[index_a:index_a_end] != before[index_a:index_a_end]:
+        fail("the commit rewrote slot A's index, which the preserved root seals")
     print(
         "image: the GPT is unchanged and the commit wrote exactly the older "
-        "superblock slot, so the previously committed root survived",
+        "superblock slot and its index, so the previously committed root "
+        "survived",
         flush=True,
     )
 
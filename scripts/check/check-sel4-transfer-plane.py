@@ -17,8 +17,11 @@ The arms:
 * the manifest decodes, which validates bounds, ordering, and a self-excluding
   SHA-256 over the whole record;
 * a tampered byte fails that digest specifically, not some field it landed in;
-* the object closure re-hashes to the identities the manifest declares, before
-  any BootState write — so an incomplete transfer costs the receiver nothing;
+* the receiver answers with the digests its store holds, and the manifest
+  omits the payload of exactly those objects;
+* the object closure re-hashes to the identities the manifest declares, with
+  omitted objects read back from the receiver's store, before any BootState
+  write — so an incomplete transfer costs the receiver nothing;
 * state travels only where the source declared it may;
 * the generation stages **pending**, leaving the known-good root intact, and
   only health confirmation promotes it.
@@ -78,9 +81,15 @@ REQUIRED_MARKERS: tuple[tuple[str, str], ...] = (
         "the receiver starts from a known-good root",
         r"\[sel4-transfer-probe\] receiver holds a known-good root",
     ),
+    (
+        # The receiver's store holds the fixture's seeded object and nothing
+        # else, so its answer names one digest.
+        "the receiver answered with the objects it holds",
+        r"\[sel4-transfer-probe\] holdings answered objects=1 bytes=96",
+    ),
     (
         "the manifest decoded with its declared closure",
-        r"\[sel4-transfer-probe\] manifest objects=1 states=1",
+        r"\[sel4-transfer-probe\] manifest objects=2 states=1",
     ),
     (
         # On the digest specifically. A flip in the metadata is covered by no
@@ -88,11 +97,17 @@ REQUIRED_MARKERS: tuple[tuple[str, str], ...] = (
         "a tampered manifest was refused on its digest",
         r"\[sel4-transfer-probe\] tampered manifest refused",
     ),
+    (
+        # An omitted payload the receiver cannot produce fails the closure.
+        "a closure missing a held object was refused",
+        r"\[sel4-transfer-probe\] missing held object refused",
+    ),
     (
         # Before any BootState write, so an incomplete transfer consumes no
-        # attempt and leaves the receiver as it was.
+        # attempt and leaves the receiver as it was. The held object is read
+        # back from the receiver's store, not taken on the answer's word.
         "every object in the closure re-hashed to its declared identity",
-        r"\[sel4-transfer-probe\] closure verified objects=1 of=1",
+        r"\[sel4-transfer-probe\] closure verified carried=1 held=1",
     ),
     (
         # `ephemeral` state does not travel; `immutable` travels read-only.
@@ -348,7 +363,8 @@ def check_transcript(transcript: str) -> None:
         f"transcript: {len(REQUIRED_MARKERS)} markers observed; both devices came "
         "up from one shared granule and each answered under its own index, the "
         "read-only source refused a write, a "
-        "tampered manifest failed its digest, and the transferred generation "
+        "tampered manifest failed its digest, the receiver's held object "
+        "travelled without its payload, and the transferred generation "
         "staged pending before health confirmation promoted it",
         flush=True,
     )
@@ -19,7 +19,7 @@ from harness import ROOT
 OUTPUT = ROOT / "scripts" / "lib" / "boot_contracts.py"
 RUST_OUTPUT_DIR = ROOT / "boot-contracts" / "src" / "generated"
 GENERATORS = (
-    (ROOT / "contracts" / "generation" / "v5" / "schema.zt", "generation.py", "generation.rs"),
+    (ROOT / "contracts" / "generation" / "v6" / "schema.zt", "generation.py", "generation.rs"),
     (ROOT / "contracts" / "kernel-image" / "v2" / "schema.zt", "kernel_image.py", "kernel_image.rs"),
     (
         ROOT / "contracts" / "target-profile" / "v1" / "schema.zt",
@@ -40,7 +40,7 @@ GENERATORS = (
     (ROOT / "contracts" / "release" / "v1" / "schema.zt", "release.py", "release.rs"),
     (ROOT / "contracts" / "recovery" / "v1" / "schema.zt", "recovery.py", "recovery.rs"),
     (ROOT / "contracts" / "transfer" / "v1" / "schema.zt", "transfer.py", "transfer.rs"),
-    (ROOT / "contracts" / "store" / "disk" / "v1" / "schema.zt", "store_disk.py", "store_disk.rs"),
+    (ROOT / "contracts" / "store" / "disk" / "v2" / "schema.zt", "store_disk.py", "store_disk.rs"),
     (ROOT / "contracts" / "handoff" / "v1" / "schema.zt", "handoff.py", "handoff.rs"),
     (
         ROOT / "contracts" / "shared-buffer-budget" / "v1" / "schema.zt",
@@ -95,7 +95,23 @@ def bootstate_checksum(slot: bytes) -> bytes:
 def bootstore_checksum(data: bytes) -> bytes:
     offset = BOOTSTORE_DIRECTORY_OFFSET + BOOTSTORE_HEADER_CHECKSUM_OFFSET
     end = BOOTSTORE_DIRECTORY_OFFSET + BOOTSTORE_HEADER_CHECKSUM_END
-    return sha256(data[BOOTSTATE_SLOT_BYTES * BOOTSTATE_SLOT_COUNT : offset] + bytes(end - offset) + data[end:])
+    return sha256(data[BOOTSTORE_SEALED_OFFSET:offset] + bytes(end - offset) + data[end:])
+
+
+def shadow_evidence_checksum(record: bytes) -> bytes:
+    return sha256(
+        record[:SHADOW_EVIDENCE_CHECKSUM_OFFSET]
+        + bytes(SHADOW_EVIDENCE_CHECKSUM_END - SHADOW_EVIDENCE_CHECKSUM_OFFSET)
+        + record[SHADOW_EVIDENCE_CHECKSUM_END:]
+    )
+
+
+def health_verdict_checksum(record: bytes) -> bytes:
+    return sha256(
+        record[:HEALTH_VERDICT_CHECKSUM_OFFSET]
+        + bytes(HEALTH_VERDICT_CHECKSUM_END - HEALTH_VERDICT_CHECKSUM_OFFSET)
+        + record[HEALTH_VERDICT_CHECKSUM_END:]
+    )
 """
 
 
@@ -16,7 +16,7 @@ from zutai_cli import STDLIB, binary
 
 from harness import ROOT
 
-GENERATOR = ROOT / "contracts" / "fs" / "v1" / "schema.zt"
+GENERATOR = ROOT / "contracts" / "fs" / "v2" / "schema.zt"
 OUTPUT = ROOT / "components" / "proto" / "src" / "fs.rs"
 PYTHON_OUTPUT = ROOT / "scripts" / "lib" / "fs_contracts.py"
 INVALID_SCHEMA = "INVALID_FS_SCHEMA"
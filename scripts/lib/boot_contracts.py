@@ -5,8 +5,8 @@ import hashlib
 import struct
 import typing
 
-GENERATION_MAGIC = b"SLIMEG5\0"
-GENERATION_VERSION = 5
+GENERATION_MAGIC = b"SLIMEG6\0"
+GENERATION_VERSION = 6
 GENERATION_HEADER = struct.Struct("<8sIIQ32sQ32sIIIIIIIIIIIIIIIIIIIIIIIIIIQQQQQQQQQQQQQQQQQQQQQQQQQ112x")
 GENERATION_HEADER_MAGIC_OFFSET = 0
 GENERATION_HEADER_MAGIC_END = 8
@@ -195,7 +195,7 @@ GENERATION_GRANT_FLAGS_END = 28
 GENERATION_GRANT_CAPABILITY_KIND_OFFSET = 28
 GENERATION_GRANT_CAPABILITY_KIND_END = 32
 
-GENERATION_STATE = struct.Struct("<IIII8x")
+GENERATION_STATE = struct.Struct("<IIIIII")
 GENERATION_STATE_NAME_OFFSET_OFFSET = 0
 GENERATION_STATE_NAME_OFFSET_END = 4
 GENERATION_STATE_OWNER_OFFSET = 4
@@ -204,10 +204,22 @@ GENERATION_STATE_SCHEMA_VERSION_OFFSET = 8
 GENERATION_STATE_SCHEMA_VERSION_END = 12
 GENERATION_STATE_POLICY_OFFSET = 12
 GENERATION_STATE_POLICY_END = 16
+GENERATION_STATE_MIGRATE_FROM_OFFSET = 16
+GENERATION_STATE_MIGRATE_FROM_END = 20
+GENERATION_STATE_MIGRATOR_OFFSET = 20
+GENERATION_STATE_MIGRATOR_END = 24
 
-GENERATION_HEALTH = struct.Struct("<I")
+GENERATION_HEALTH = struct.Struct("<IIII32s")
 GENERATION_HEALTH_INSTANCE_OFFSET = 0
 GENERATION_HEALTH_INSTANCE_END = 4
+GENERATION_HEALTH_PROBES_OFFSET = 4
+GENERATION_HEALTH_PROBES_END = 8
+GENERATION_HEALTH_FAULT_WINDOW_TICKS_OFFSET = 8
+GENERATION_HEALTH_FAULT_WINDOW_TICKS_END = 12
+GENERATION_HEALTH_RESERVED_OFFSET = 12
+GENERATION_HEALTH_RESERVED_END = 16
+GENERATION_HEALTH_ROUTE_OFFSET = 16
+GENERATION_HEALTH_ROUTE_END = 48
 
 GENERATION_PROCESS = struct.Struct("<IIIIIII4x")
 GENERATION_PROCESS_NAME_OFFSET_OFFSET = 0
@@ -465,6 +477,10 @@ GENERATION_CAPABILITY_INPUT = 6
 GENERATION_CAPABILITY_SUPERVISION = 7
 GENERATION_CAPABILITY_SHARED_BUFFER = 8
 GENERATION_CAPABILITY_LOAN = 9
+GENERATION_CAPABILITY_SCHEDULING_CONTROL = 10
+GENERATION_CAPABILITY_MONOTONIC_CLOCK = 11
+GENERATION_CAPABILITY_WALL_CLOCK = 12
+GENERATION_CAPABILITY_SIMULATED_CLOCK = 13
 GENERATION_SERVICE_LIFECYCLE = 1
 GENERATION_SERVICE_SPAWN = 2
 GENERATION_SERVICE_SUPERVISION = 3
@@ -499,7 +515,10 @@ GENERATION_RIGHT_DIRECTORY_DERIVE = 4194304
 GENERATION_RIGHT_INPUT_READ = 8388608
 GENERATION_RIGHT_BUFFER_CREATE = 16777216
 GENERATION_RIGHT_BUFFER_LOAN = 33554432
-GENERATION_RIGHT_ALL = 66977791
+GENERATION_RIGHT_SCHEDULE = 67108864
+GENERATION_RIGHT_CLOCK_READ = 134217728
+GENERATION_RIGHT_CLOCK_ADVANCE = 268435456
+GENERATION_RIGHT_ALL = 536739839
 GENERATION_RIGHT_BY_MANIFEST_NAME = {
     "send": 1,
     "recv": 2,
@@ -525,6 +544,18 @@ GENERATION_RIGHT_BY_MANIFEST_NAME = {
     "inputRead": 8388608,
     "bufferCreate": 16777216,
     "bufferLoan": 33554432,
+    "schedule": 67108864,
+    "clockRead": 134217728,
+    "clockAdvance": 268435456,
+}
+GENERATION_HEALTH_PROBE_RUNNING = 1
+GENERATION_HEALTH_PROBE_NO_FAULT = 2
+GENERATION_HEALTH_PROBE_ROUTE = 4
+GENERATION_HEALTH_PROBE_ALL = 7
+GENERATION_HEALTH_PROBE_BY_MANIFEST_NAME = {
+    "running": 1,
+    "noFault": 2,
+    "route": 4,
 }
 
 KERNEL_MAGIC = b"SLIMEKR2"
@@ -778,7 +809,7 @@ BOOTSTATE_VERSION = 2
 BOOTSTATE_SLOT_BYTES = 512
 BOOTSTATE_SLOT_COUNT = 2
 BOOTSTATE_REQUIRED_FLAGS = 0
-BOOTSTATE_SLOT = struct.Struct("<8sIIQQ32s32sII32s32sQ32s304x")
+BOOTSTATE_SLOT = struct.Struct("<8sIIQQ32s32sII32s32sQ32s32sI32s236x")
 BOOTSTATE_MAGIC_OFFSET = 0
 BOOTSTATE_MAGIC_END = 8
 BOOTSTATE_FORMAT_VERSION_OFFSET = 8
@@ -805,6 +836,12 @@ BOOTSTATE_ACCEPTED_RELEASE_SEQUENCE_OFFSET = 168
 BOOTSTATE_ACCEPTED_RELEASE_SEQUENCE_END = 176
 BOOTSTATE_CHECKSUM_OFFSET = 176
 BOOTSTATE_CHECKSUM_END = 208
+BOOTSTATE_TRUST_ROOT_OFFSET = 208
+BOOTSTATE_TRUST_ROOT_END = 240
+BOOTSTATE_TRUST_ROOT_VERSION_OFFSET = 240
+BOOTSTATE_TRUST_ROOT_VERSION_END = 244
+BOOTSTATE_ROLLBACK_STATE_ROOT_OFFSET = 244
+BOOTSTATE_ROLLBACK_STATE_ROOT_END = 276
 
 BOOTSTORE_HEADER = struct.Struct("<8sIIQIIQQ32s16x")
 BOOTSTORE_HEADER_MAGIC_OFFSET = 0
@@ -838,32 +875,143 @@ BOOTSTORE_ENTRY_RELEASE_OFFSET_END = 56
 BOOTSTORE_ENTRY_RELEASE_LEN_OFFSET = 56
 BOOTSTORE_ENTRY_RELEASE_LEN_END = 64
 
+SHADOW_EVIDENCE = struct.Struct("<8sIIII32s32sQIIIIIIQQ32s344x")
+SHADOW_EVIDENCE_MAGIC_OFFSET = 0
+SHADOW_EVIDENCE_MAGIC_END = 8
+SHADOW_EVIDENCE_FORMAT_VERSION_OFFSET = 8
+SHADOW_EVIDENCE_FORMAT_VERSION_END = 12
+SHADOW_EVIDENCE_HEADER_SIZE_OFFSET = 12
+SHADOW_EVIDENCE_HEADER_SIZE_END = 16
+SHADOW_EVIDENCE_OUTCOME_OFFSET = 16
+SHADOW_EVIDENCE_OUTCOME_END = 20
+SHADOW_EVIDENCE_UNVERIFIED_OFFSET = 20
+SHADOW_EVIDENCE_UNVERIFIED_END = 24
+SHADOW_EVIDENCE_CANDIDATE_OFFSET = 24
+SHADOW_EVIDENCE_CANDIDATE_END = 56
+SHADOW_EVIDENCE_RUNNING_OFFSET = 56
+SHADOW_EVIDENCE_RUNNING_END = 88
+SHADOW_EVIDENCE_RELEASE_SEQUENCE_OFFSET = 88
+SHADOW_EVIDENCE_RELEASE_SEQUENCE_END = 96
+SHADOW_EVIDENCE_REQUIRED_OFFSET = 96
+SHADOW_EVIDENCE_REQUIRED_END = 100
+SHADOW_EVIDENCE_EXERCISED_OFFSET = 100
+SHADOW_EVIDENCE_EXERCISED_END = 104
+SHADOW_EVIDENCE_PARKED_OFFSET = 104
+SHADOW_EVIDENCE_PARKED_END = 108
+SHADOW_EVIDENCE_COMPLETED_OFFSET = 108
+SHADOW_EVIDENCE_COMPLETED_END = 112
+SHADOW_EVIDENCE_FAILED_OFFSET = 112
+SHADOW_EVIDENCE_FAILED_END = 116
+SHADOW_EVIDENCE_WITHHELD_OFFSET = 116
+SHADOW_EVIDENCE_WITHHELD_END = 120
+SHADOW_EVIDENCE_BUDGET_BYTES_OFFSET = 120
+SHADOW_EVIDENCE_BUDGET_BYTES_END = 128
+SHADOW_EVIDENCE_USED_BYTES_OFFSET = 128
+SHADOW_EVIDENCE_USED_BYTES_END = 136
+SHADOW_EVIDENCE_CHECKSUM_OFFSET = 136
+SHADOW_EVIDENCE_CHECKSUM_END = 168
+
+HEALTH_VERDICT = struct.Struct("<8sIIII32sQQIIII32s392x")
+HEALTH_VERDICT_MAGIC_OFFSET = 0
+HEALTH_VERDICT_MAGIC_END = 8
+HEALTH_VERDICT_FORMAT_VERSION_OFFSET = 8
+HEALTH_VERDICT_FORMAT_VERSION_END = 12
+HEALTH_VERDICT_HEADER_SIZE_OFFSET = 12
+HEALTH_VERDICT_HEADER_SIZE_END = 16
+HEALTH_VERDICT_OUTCOME_OFFSET = 16
+HEALTH_VERDICT_OUTCOME_END = 20
+HEALTH_VERDICT_PROBE_COUNT_OFFSET = 20
+HEALTH_VERDICT_PROBE_COUNT_END = 24
+HEALTH_VERDICT_GENERATION_OFFSET = 24
+HEALTH_VERDICT_GENERATION_END = 56
+HEALTH_VERDICT_RELEASE_SEQUENCE_OFFSET = 56
+HEALTH_VERDICT_RELEASE_SEQUENCE_END = 64
+HEALTH_VERDICT_TICKS_OFFSET = 64
+HEALTH_VERDICT_TICKS_END = 72
+HEALTH_VERDICT_PASSED_OFFSET = 72
+HEALTH_VERDICT_PASSED_END = 76
+HEALTH_VERDICT_FAILED_OFFSET = 76
+HEALTH_VERDICT_FAILED_END = 80
+HEALTH_VERDICT_FAILED_INSTANCE_OFFSET = 80
+HEALTH_VERDICT_FAILED_INSTANCE_END = 84
+HEALTH_VERDICT_FAILED_PROBE_OFFSET = 84
+HEALTH_VERDICT_FAILED_PROBE_END = 88
+HEALTH_VERDICT_CHECKSUM_OFFSET = 88
+HEALTH_VERDICT_CHECKSUM_END = 120
+
+STATE_JOURNAL = struct.Struct("<8sIIIIQQII32s32s32s32s32s304x")
+STATE_JOURNAL_MAGIC_OFFSET = 0
+STATE_JOURNAL_MAGIC_END = 8
+STATE_JOURNAL_FORMAT_VERSION_OFFSET = 8
+STATE_JOURNAL_FORMAT_VERSION_END = 12
+STATE_JOURNAL_HEADER_SIZE_OFFSET = 12
+STATE_JOURNAL_HEADER_SIZE_END = 16
+STATE_JOURNAL_ACTION_OFFSET = 16
+STATE_JOURNAL_ACTION_END = 20
+STATE_JOURNAL_POLICY_OFFSET = 20
+STATE_JOURNAL_POLICY_END = 24
+STATE_JOURNAL_SEQUENCE_OFFSET = 24
+STATE_JOURNAL_SEQUENCE_END = 32
+STATE_JOURNAL_BOOT_SEQUENCE_OFFSET = 32
+STATE_JOURNAL_BOOT_SEQUENCE_END = 40
+STATE_JOURNAL_SCHEMA_VERSION_OFFSET = 40
+STATE_JOURNAL_SCHEMA_VERSION_END = 44
+STATE_JOURNAL_RESERVED_OFFSET = 44
+STATE_JOURNAL_RESERVED_END = 48
+STATE_JOURNAL_GENERATION_OFFSET = 48
+STATE_JOURNAL_GENERATION_END = 80
+STATE_JOURNAL_BINDING_OFFSET = 80
+STATE_JOURNAL_BINDING_END = 112
+STATE_JOURNAL_OBJECT_OFFSET = 112
+STATE_JOURNAL_OBJECT_END = 144
+STATE_JOURNAL_STATE_ROOT_OFFSET = 144
+STATE_JOURNAL_STATE_ROOT_END = 176
+STATE_JOURNAL_CHECKSUM_OFFSET = 176
+STATE_JOURNAL_CHECKSUM_END = 208
+
 BOOTSTATE_RELEASE_SEQUENCE_OFFSET = BOOTSTATE_ACCEPTED_RELEASE_SEQUENCE_OFFSET
 BOOTSTORE_MAGIC = b"SLIMEBT\0"
-BOOTSTORE_VERSION = 1
+BOOTSTORE_VERSION = 3
 BOOTSTORE_CAPACITY = 33554432
 BOOTSTORE_DIRECTORY_OFFSET = 4096
 BOOTSTORE_RELEASES_OFFSET = 8192
 BOOTSTORE_GENERATIONS_OFFSET = 16384
+BOOTSTORE_EVIDENCE_OFFSET = 1024
+BOOTSTORE_VERDICT_OFFSET = 1536
+BOOTSTORE_SEALED_OFFSET = 2048
+SHADOW_EVIDENCE_MAGIC = b"SLIMESE\0"
+SHADOW_EVIDENCE_VERSION = 1
+HEALTH_VERDICT_MAGIC = b"SLIMEHV\0"
+HEALTH_VERDICT_VERSION = 1
+STATE_JOURNAL_MAGIC = b"SLIMESJ\0"
+STATE_JOURNAL_VERSION = 1
+STATE_JOURNAL_ENTRY_BYTES = 512
+STATE_JOURNAL_SECTORS = 64
 
 BOOTSTATE_TRACE_VERSION = 1
 BOOTSTATE_TRACE_MAX_LINE = 640
 BOOTSTATE_TRACE_PREFIX = "[bootstate-trace]"
 
 RELEASE_MAGIC = b"SLIMERL\0"
-RELEASE_VERSION = 2
-RELEASE_BYTES = 512
-RELEASE_HEADER_BYTES = 208
+RELEASE_VERSION = 3
+RELEASE_BYTES = 1024
+RELEASE_HEADER_BYTES = 240
 RELEASE_SIGNATURE_BYTES = 96
 MAX_RELEASE_SIGNATURES = 3
 MAX_TARGET_BYTES = 32
+RELEASE_FLAG_AUTHORITY_WIDENING = 1
 ROTATION_MAGIC = b"SLIMERT\0"
-ROTATION_VERSION = 1
+ROTATION_VERSION = 2
 ROTATION_BYTES = 1024
 ROTATION_HEADER_BYTES = 64
 MAX_TRUST_KEYS = 4
+MAX_REVOKED_KEYS = 4
+TRUST_ROOT_MAGIC = b"SLIMETRT"
+TRUST_ROOT_RECORD_VERSION = 1
+TRUST_ROOT_RECORD_BYTES = 512
+TRUST_ROOT_HEADER_BYTES = 48
 SIGN_NAMESPACE = "slime-release"
-RELEASE_HEADER = struct.Struct("<8sIIQ32s32sQII32s32s32sI4s")
+RELEASE_HEADER = struct.Struct("<8sIIQ32s32sQII32s32s32s32sI4s")
 RELEASE_HEADER_MAGIC_OFFSET = 0
 RELEASE_HEADER_MAGIC_END = 8
 RELEASE_HEADER_FORMAT_VERSION_OFFSET = 8
@@ -888,10 +1036,12 @@ RELEASE_HEADER_BOOT_BUNDLE_IDENTITY_OFFSET = 136
 RELEASE_HEADER_BOOT_BUNDLE_IDENTITY_END = 168
 RELEASE_HEADER_AUTHORITY_MANIFEST_OFFSET = 168
 RELEASE_HEADER_AUTHORITY_MANIFEST_END = 200
-RELEASE_HEADER_SIGNATURE_COUNT_OFFSET = 200
-RELEASE_HEADER_SIGNATURE_COUNT_END = 204
-RELEASE_HEADER_RESERVED_OFFSET = 204
-RELEASE_HEADER_RESERVED_END = 208
+RELEASE_HEADER_APPROVED_WIDENINGS_OFFSET = 200
+RELEASE_HEADER_APPROVED_WIDENINGS_END = 232
+RELEASE_HEADER_SIGNATURE_COUNT_OFFSET = 232
+RELEASE_HEADER_SIGNATURE_COUNT_END = 236
+RELEASE_HEADER_RESERVED_OFFSET = 236
+RELEASE_HEADER_RESERVED_END = 240
 
 RELEASE_SIGNATURE = struct.Struct("<32s64s")
 RELEASE_SIGNATURE_KEY_ID_OFFSET = 0
@@ -899,7 +1049,7 @@ RELEASE_SIGNATURE_KEY_ID_END = 32
 RELEASE_SIGNATURE_SIGNATURE_OFFSET = 32
 RELEASE_SIGNATURE_SIGNATURE_END = 96
 
-ROTATION_HEADER = struct.Struct("<8sIIQIIIIII16x")
+ROTATION_HEADER = struct.Struct("<8sIIQIIIIIII12x")
 ROTATION_HEADER_MAGIC_OFFSET = 0
 ROTATION_HEADER_MAGIC_END = 8
 ROTATION_HEADER_FORMAT_VERSION_OFFSET = 8
@@ -920,13 +1070,33 @@ ROTATION_HEADER_PREVIOUS_SIGNATURE_COUNT_OFFSET = 40
 ROTATION_HEADER_PREVIOUS_SIGNATURE_COUNT_END = 44
 ROTATION_HEADER_REPLACEMENT_SIGNATURE_COUNT_OFFSET = 44
 ROTATION_HEADER_REPLACEMENT_SIGNATURE_COUNT_END = 48
+ROTATION_HEADER_REVOKED_COUNT_OFFSET = 48
+ROTATION_HEADER_REVOKED_COUNT_END = 52
+
+TRUST_ROOT_HEADER = struct.Struct("<8sIIQIIII8x")
+TRUST_ROOT_HEADER_MAGIC_OFFSET = 0
+TRUST_ROOT_HEADER_MAGIC_END = 8
+TRUST_ROOT_HEADER_FORMAT_VERSION_OFFSET = 8
+TRUST_ROOT_HEADER_FORMAT_VERSION_END = 12
+TRUST_ROOT_HEADER_HEADER_SIZE_OFFSET = 12
+TRUST_ROOT_HEADER_HEADER_SIZE_END = 16
+TRUST_ROOT_HEADER_REQUIRED_FLAGS_OFFSET = 16
+TRUST_ROOT_HEADER_REQUIRED_FLAGS_END = 24
+TRUST_ROOT_HEADER_VERSION_OFFSET = 24
+TRUST_ROOT_HEADER_VERSION_END = 28
+TRUST_ROOT_HEADER_THRESHOLD_OFFSET = 28
+TRUST_ROOT_HEADER_THRESHOLD_END = 32
+TRUST_ROOT_HEADER_KEY_COUNT_OFFSET = 32
+TRUST_ROOT_HEADER_KEY_COUNT_END = 36
+TRUST_ROOT_HEADER_REVOKED_COUNT_OFFSET = 36
+TRUST_ROOT_HEADER_REVOKED_COUNT_END = 40
 
 RECOVERY_INDEX_MAGIC = b"SLIMERC\0"
-RECOVERY_INDEX_VERSION = 1
-RECOVERY_INDEX_HEADER_BYTES = 160
+RECOVERY_INDEX_VERSION = 2
+RECOVERY_INDEX_HEADER_BYTES = 200
 RECOVERY_STATE_ENTRY_BYTES = 72
 MAX_RECOVERY_STATE_OBJECTS = 32
-RECOVERY_INDEX_HEADER = struct.Struct("<8sIIQ32s32s32sQIIIQQ4s")
+RECOVERY_INDEX_HEADER = struct.Struct("<8sIIQ32s32s32sQIIIQQ32sI8s")
 RECOVERY_INDEX_MAGIC_OFFSET = 0
 RECOVERY_INDEX_MAGIC_END = 8
 RECOVERY_INDEX_FORMAT_VERSION_OFFSET = 8
@@ -953,8 +1123,12 @@ RECOVERY_INDEX_STATE_FIRST_LBA_OFFSET = 140
 RECOVERY_INDEX_STATE_FIRST_LBA_END = 148
 RECOVERY_INDEX_STATE_LAST_LBA_OFFSET = 148
 RECOVERY_INDEX_STATE_LAST_LBA_END = 156
-RECOVERY_INDEX_RESERVED_OFFSET = 156
-RECOVERY_INDEX_RESERVED_END = 160
+RECOVERY_INDEX_TRUST_ROOT_OFFSET = 156
+RECOVERY_INDEX_TRUST_ROOT_END = 188
+RECOVERY_INDEX_TRUST_ROOT_VERSION_OFFSET = 188
+RECOVERY_INDEX_TRUST_ROOT_VERSION_END = 192
+RECOVERY_INDEX_RESERVED_OFFSET = 192
+RECOVERY_INDEX_RESERVED_END = 200
 
 RECOVERY_STATE_ENTRY = struct.Struct("<32s32sI4s")
 RECOVERY_STATE_BINDING_IDENTITY_OFFSET = 0
@@ -975,6 +1149,9 @@ TRANSFER_MAX_BYTES = 33554432
 TRANSFER_OBJECT_FLAG_PAYLOAD = 1
 TRANSFER_STATE_FLAG_TRAVEL = 1
 TRANSFER_STATE_FLAG_READ_ONLY = 2
+TRANSFER_HOLDINGS_MAGIC = b"SLIMEHLD"
+TRANSFER_HOLDINGS_HEADER_BYTES = 64
+TRANSFER_MAX_HELD_DIGESTS = 64
 TRANSFER_HEADER = struct.Struct("<8sIIQ32s32s32s32sQQQIIQQQQQQQQ32s40x")
 TRANSFER_HEADER_MAGIC_OFFSET = 0
 TRANSFER_HEADER_MAGIC_END = 8
@@ -1045,15 +1222,46 @@ TRANSFER_STATE_POLICY_END = 72
 TRANSFER_STATE_FLAGS_OFFSET = 72
 TRANSFER_STATE_FLAGS_END = 76
 
+TRANSFER_HOLDINGS = struct.Struct("<8sIIQII32s")
+TRANSFER_HOLDINGS_MAGIC_OFFSET = 0
+TRANSFER_HOLDINGS_MAGIC_END = 8
+TRANSFER_HOLDINGS_FORMAT_VERSION_OFFSET = 8
+TRANSFER_HOLDINGS_FORMAT_VERSION_END = 12
+TRANSFER_HOLDINGS_HEADER_SIZE_OFFSET = 12
+TRANSFER_HOLDINGS_HEADER_SIZE_END = 16
+TRANSFER_HOLDINGS_REQUIRED_FLAGS_OFFSET = 16
+TRANSFER_HOLDINGS_REQUIRED_FLAGS_END = 24
+TRANSFER_HOLDINGS_COUNT_OFFSET = 24
+TRANSFER_HOLDINGS_COUNT_END = 28
+TRANSFER_HOLDINGS_RESERVED_OFFSET = 28
+TRANSFER_HOLDINGS_RESERVED_END = 32
+TRANSFER_HOLDINGS_HASH_OFFSET = 32
+TRANSFER_HOLDINGS_HASH_END = 64
+
 STORE_SUPERBLOCK_MAGIC = b"SLIMESB\0"
 STORE_RECORD_MAGIC = b"SLIMEOR\0"
-STORE_FORMAT_VERSION = 1
-STORE_RECORD_AREA_START = 2
+STORE_INDEX_MAGIC = b"SLIMEIX\0"
+STORE_MANIFEST_MAGIC = b"SLIMEMF\0"
+STORE_FORMAT_VERSION = 2
+STORE_RECORD_AREA_START = 18
 STORE_SLOT_A_LBA = 0
 STORE_SLOT_B_LBA = 1
+STORE_INDEX_A_LBA = 2
+STORE_INDEX_B_LBA = 10
+STORE_INDEX_SECTORS = 8
+STORE_V1_FORMAT_VERSION = 1
+STORE_V1_RECORD_HEADER = 64
+STORE_V1_RECORD_AREA_START = 2
 STORE_MAX_OBJECTS = 64
 STORE_MAX_OBJECT_PAYLOAD = 32768
 STORE_SECTOR_BYTES = 512
+STORE_TYPE_METADATA_BYTES = 20
+STORE_MANIFEST_OBJECT_TYPE = 1280265542
+STORE_CHUNK_OBJECT_TYPE = 1280262984
+STORE_MANIFEST_FORMAT_VERSION = 1
+STORE_SUPERBLOCK_FLAG_ENCRYPTED = 1
+STORE_RECORD_FLAG_ENCRYPTED = 1
+STORE_AUTH_TAG_BYTES = 16
 STORE_SUPERBLOCK = struct.Struct("<8sIIQQIIQQII")
 STORE_SUPERBLOCK_MAGIC_OFFSET = 0
 STORE_SUPERBLOCK_MAGIC_END = 8
@@ -1073,12 +1281,12 @@ STORE_SUPERBLOCK_RECORD_AREA_START_OFFSET = 40
 STORE_SUPERBLOCK_RECORD_AREA_START_END = 48
 STORE_SUPERBLOCK_PARTITION_SECTORS_OFFSET = 48
 STORE_SUPERBLOCK_PARTITION_SECTORS_END = 56
-STORE_SUPERBLOCK_RESERVED_OFFSET = 56
-STORE_SUPERBLOCK_RESERVED_END = 60
+STORE_SUPERBLOCK_INDEX_CRC32_OFFSET = 56
+STORE_SUPERBLOCK_INDEX_CRC32_END = 60
 STORE_SUPERBLOCK_CRC32_OFFSET = 60
 STORE_SUPERBLOCK_CRC32_END = 64
 
-STORE_RECORD = struct.Struct("<8sIIIIQ32s")
+STORE_RECORD = struct.Struct("<8sIIIIQ32sQ32s20sI")
 STORE_RECORD_MAGIC_OFFSET = 0
 STORE_RECORD_MAGIC_END = 8
 STORE_RECORD_FORMAT_VERSION_OFFSET = 8
@@ -1093,6 +1301,56 @@ STORE_RECORD_PAYLOAD_LEN_OFFSET = 24
 STORE_RECORD_PAYLOAD_LEN_END = 32
 STORE_RECORD_CONTENT_HASH_OFFSET = 32
 STORE_RECORD_CONTENT_HASH_END = 64
+STORE_RECORD_CREATION_SEQUENCE_OFFSET = 64
+STORE_RECORD_CREATION_SEQUENCE_END = 72
+STORE_RECORD_GENERATION_OFFSET = 72
+STORE_RECORD_GENERATION_END = 104
+STORE_RECORD_TYPE_METADATA_OFFSET = 104
+STORE_RECORD_TYPE_METADATA_END = 124
+STORE_RECORD_HEADER_CRC32_OFFSET = 124
+STORE_RECORD_HEADER_CRC32_END = 128
+
+STORE_INDEX = struct.Struct("<8sIIQII32s")
+STORE_INDEX_MAGIC_OFFSET = 0
+STORE_INDEX_MAGIC_END = 8
+STORE_INDEX_FORMAT_VERSION_OFFSET = 8
+STORE_INDEX_FORMAT_VERSION_END = 12
+STORE_INDEX_HEADER_SIZE_OFFSET = 12
+STORE_INDEX_HEADER_SIZE_END = 16
+STORE_INDEX_SEQUENCE_OFFSET = 16
+STORE_INDEX_SEQUENCE_END = 24
+STORE_INDEX_ENTRY_COUNT_OFFSET = 24
+STORE_INDEX_ENTRY_COUNT_END = 28
+STORE_INDEX_ENTRY_SIZE_OFFSET = 28
+STORE_INDEX_ENTRY_SIZE_END = 32
+STORE_INDEX_RESERVED_OFFSET = 32
+STORE_INDEX_RESERVED_END = 64
+
+STORE_INDEX_ENTRY = struct.Struct("<32sIIQ")
+STORE_INDEX_ENTRY_CONTENT_HASH_OFFSET = 0
+STORE_INDEX_ENTRY_CONTENT_HASH_END = 32
+STORE_INDEX_ENTRY_OBJ_TYPE_OFFSET = 32
+STORE_INDEX_ENTRY_OBJ_TYPE_END = 36
+STORE_INDEX_ENTRY_PAYLOAD_LEN_OFFSET = 36
+STORE_INDEX_ENTRY_PAYLOAD_LEN_END = 40
+STORE_INDEX_ENTRY_LBA_OFFSET = 40
+STORE_INDEX_ENTRY_LBA_END = 48
+
+STORE_MANIFEST = struct.Struct("<8sIIIIQ32s")
+STORE_MANIFEST_MAGIC_OFFSET = 0
+STORE_MANIFEST_MAGIC_END = 8
+STORE_MANIFEST_FORMAT_VERSION_OFFSET = 8
+STORE_MANIFEST_FORMAT_VERSION_END = 12
+STORE_MANIFEST_CHUNK_COUNT_OFFSET = 12
+STORE_MANIFEST_CHUNK_COUNT_END = 16
+STORE_MANIFEST_OBJ_TYPE_OFFSET = 16
+STORE_MANIFEST_OBJ_TYPE_END = 20
+STORE_MANIFEST_RESERVED_OFFSET = 20
+STORE_MANIFEST_RESERVED_END = 24
+STORE_MANIFEST_TOTAL_LEN_OFFSET = 24
+STORE_MANIFEST_TOTAL_LEN_END = 32
+STORE_MANIFEST_ROOT_HASH_OFFSET = 32
+STORE_MANIFEST_ROOT_HASH_END = 64
 
 HANDOFF_MAGIC = b"SLIMEHND"
 HANDOFF_VERSION = 1
@@ -1481,4 +1739,20 @@ def bootstate_checksum(slot: bytes) -> bytes:
 def bootstore_checksum(data: bytes) -> bytes:
     offset = BOOTSTORE_DIRECTORY_OFFSET + BOOTSTORE_HEADER_CHECKSUM_OFFSET
     end = BOOTSTORE_DIRECTORY_OFFSET + BOOTSTORE_HEADER_CHECKSUM_END
-    return sha256(data[BOOTSTATE_SLOT_BYTES * BOOTSTATE_SLOT_COUNT : offset] + bytes(end - offset) + data[end:])
+    return sha256(data[BOOTSTORE_SEALED_OFFSET:offset] + bytes(end - offset) + data[end:])
+
+
+def shadow_evidence_checksum(record: bytes) -> bytes:
+    return sha256(
+        record[:SHADOW_EVIDENCE_CHECKSUM_OFFSET]
+        + bytes(SHADOW_EVIDENCE_CHECKSUM_END - SHADOW_EVIDENCE_CHECKSUM_OFFSET)
+        + record[SHADOW_EVIDENCE_CHECKSUM_END:]
+    )
+
+
+def health_verdict_checksum(record: bytes) -> bytes:
+    return sha256(
+        record[:HEALTH_VERDICT_CHECKSUM_OFFSET]
+        + bytes(HEALTH_VERDICT_CHECKSUM_END - HEALTH_VERDICT_CHECKSUM_OFFSET)
+        + record[HEALTH_VERDICT_CHECKSUM_END:]
+    )
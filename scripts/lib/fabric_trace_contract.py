@@ -26,5 +26,6 @@ FABRIC_TRACE_RESOURCE_EVENT = 11
 FABRIC_TRACE_RESOURCE_LOAN = 12
 FABRIC_TRACE_RESOURCE_MAPPING = 13
 FABRIC_TRACE_RESOURCE_CAPABILITY_SLOTS = 14
-FABRIC_TRACE_RESOURCE_COMPLETE = 15
-FABRIC_TRACE_MAX_RESOURCE_COUNTER = 15
+FABRIC_TRACE_RESOURCE_PRIVATE_PAGES = 15
+FABRIC_TRACE_RESOURCE_COMPLETE = 16
+FABRIC_TRACE_MAX_RESOURCE_COUNTER = 16
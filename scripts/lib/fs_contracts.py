@@ -1,33 +1,76 @@
-# @generated by contracts/fs/v1/gen_rust.zt; do not edit.
-# Source contract: contracts/fs/v1/schema.zt
+# @generated by contracts/fs/v2/gen_rust.zt; do not edit.
+# Source contract: contracts/fs/v2/schema.zt
 
-FS_MAX_NAME_BYTES = 16
-FS_MAX_ENTRIES = 16
-SNAPSHOT_MAGIC = b"SLIMEDIR"
-SNAPSHOT_VERSION = 1
-SNAPSHOT_HEADER = 16
-SNAPSHOT_ENTRY_BYTES = 64
-SNAPSHOT_BYTES = SNAPSHOT_HEADER + FS_MAX_ENTRIES * SNAPSHOT_ENTRY_BYTES
+FS_MAX_NAME_BYTES = 64
+FS_PAGE_ENTRIES = 32
+FS_MAX_PAGES = 16
+FS_MAX_ENTRIES = FS_PAGE_ENTRIES * FS_MAX_PAGES
+DIRECTORY_MAGIC = b"SLIMEDIR"
+DIRECTORY_VERSION = 2
+DIRECTORY_HEADER = 32
+PAGE_HASH_BYTES = 32
+PAGE_MAGIC = b"SLIMEPAG"
+PAGE_HEADER = 16
+ENTRY_BYTES = 112
 SNAPSHOT_OBJECT_TYPE = 1146243411
-SNAPSHOT_MAGIC_OFFSET = 0
-SNAPSHOT_MAGIC_END = 8
-SNAPSHOT_VERSION_OFFSET = 8
-SNAPSHOT_VERSION_END = 12
-SNAPSHOT_COUNT_OFFSET = 12
-SNAPSHOT_COUNT_END = 16
-SNAPSHOT_ENTRY_KIND_OFFSET = 0
-SNAPSHOT_ENTRY_KIND_END = 1
-SNAPSHOT_ENTRY_NAME_LEN_OFFSET = 1
-SNAPSHOT_ENTRY_NAME_LEN_END = 2
-SNAPSHOT_ENTRY_RESERVED0_OFFSET = 2
-SNAPSHOT_ENTRY_RESERVED0_END = 4
-SNAPSHOT_ENTRY_NAME_OFFSET = 4
-SNAPSHOT_ENTRY_NAME_END = 20
-SNAPSHOT_ENTRY_OBJECT_TYPE_OFFSET = 20
-SNAPSHOT_ENTRY_OBJECT_TYPE_END = 24
-SNAPSHOT_ENTRY_PAYLOAD_LEN_OFFSET = 24
-SNAPSHOT_ENTRY_PAYLOAD_LEN_END = 28
-SNAPSHOT_ENTRY_HASH_OFFSET = 28
-SNAPSHOT_ENTRY_HASH_END = 60
-SNAPSHOT_ENTRY_RESERVED1_OFFSET = 60
-SNAPSHOT_ENTRY_RESERVED1_END = 64
+PAGE_OBJECT_TYPE = 1347570003
+V1_SNAPSHOT_VERSION = 1
+V1_MAX_NAME_BYTES = 16
+V1_MAX_ENTRIES = 16
+V1_SNAPSHOT_HEADER = 16
+V1_SNAPSHOT_ENTRY_BYTES = 64
+V1_SNAPSHOT_BYTES = V1_SNAPSHOT_HEADER + V1_MAX_ENTRIES * V1_SNAPSHOT_ENTRY_BYTES
+DIRECTORY_MAGIC_OFFSET = 0
+DIRECTORY_MAGIC_END = 8
+DIRECTORY_VERSION_OFFSET = 8
+DIRECTORY_VERSION_END = 12
+DIRECTORY_COUNT_OFFSET = 12
+DIRECTORY_COUNT_END = 16
+DIRECTORY_PAGE_COUNT_OFFSET = 16
+DIRECTORY_PAGE_COUNT_END = 20
+DIRECTORY_RESERVED_OFFSET = 20
+DIRECTORY_RESERVED_END = 32
+PAGE_MAGIC_OFFSET = 0
+PAGE_MAGIC_END = 8
+PAGE_VERSION_OFFSET = 8
+PAGE_VERSION_END = 12
+PAGE_COUNT_OFFSET = 12
+PAGE_COUNT_END = 16
+ENTRY_KIND_OFFSET = 0
+ENTRY_KIND_END = 1
+ENTRY_NAME_LEN_OFFSET = 1
+ENTRY_NAME_LEN_END = 2
+ENTRY_RESERVED0_OFFSET = 2
+ENTRY_RESERVED0_END = 4
+ENTRY_OBJECT_TYPE_OFFSET = 4
+ENTRY_OBJECT_TYPE_END = 8
+ENTRY_PAYLOAD_LEN_OFFSET = 8
+ENTRY_PAYLOAD_LEN_END = 12
+ENTRY_RESERVED1_OFFSET = 12
+ENTRY_RESERVED1_END = 16
+ENTRY_HASH_OFFSET = 16
+ENTRY_HASH_END = 48
+ENTRY_NAME_OFFSET = 48
+ENTRY_NAME_END = 112
+V1_SNAPSHOT_MAGIC_OFFSET = 0
+V1_SNAPSHOT_MAGIC_END = 8
+V1_SNAPSHOT_VERSION_OFFSET = 8
+V1_SNAPSHOT_VERSION_END = 12
+V1_SNAPSHOT_COUNT_OFFSET = 12
+V1_SNAPSHOT_COUNT_END = 16
+V1_SNAPSHOT_ENTRY_KIND_OFFSET = 0
+V1_SNAPSHOT_ENTRY_KIND_END = 1
+V1_SNAPSHOT_ENTRY_NAME_LEN_OFFSET = 1
+V1_SNAPSHOT_ENTRY_NAME_LEN_END = 2
+V1_SNAPSHOT_ENTRY_RESERVED0_OFFSET = 2
+V1_SNAPSHOT_ENTRY_RESERVED0_END = 4
+V1_SNAPSHOT_ENTRY_NAME_OFFSET = 4
+V1_SNAPSHOT_ENTRY_NAME_END = 20
+V1_SNAPSHOT_ENTRY_OBJECT_TYPE_OFFSET = 20
+V1_SNAPSHOT_ENTRY_OBJECT_TYPE_END = 24
+V1_SNAPSHOT_ENTRY_PAYLOAD_LEN_OFFSET = 24
+V1_SNAPSHOT_ENTRY_PAYLOAD_LEN_END = 28
+V1_SNAPSHOT_ENTRY_HASH_OFFSET = 28
+V1_SNAPSHOT_ENTRY_HASH_END = 60
+V1_SNAPSHOT_ENTRY_RESERVED1_OFFSET = 60
+V1_SNAPSHOT_ENTRY_RESERVED1_END = 64
@@ -39,11 +39,19 @@ def build_recovery_index(
     state_entries: list[tuple[str, bytes, int]],
     state_first_lba: int,
     state_last_lba: int,
+    trust_root: tuple[bytes, int] | None = None,
 ) -> bytes:
     if len(target_generation) != 32 or target_generation == bytes(32):
         raise ValueError("target generation must be a nonzero 32-byte identity")
     if len(generation_root) != 32 or generation_root == bytes(32):
         raise ValueError("generation root must be a nonzero 32-byte identity")
+    # The compiled-in root is version 1 and is named by no record, so only a
+    # rotated root is carried, and it is carried whole.
+    trust_object, trust_version = trust_root if trust_root is not None else (bytes(32), 0)
+    if trust_root is not None and (
+        len(trust_object) != 32 or trust_object == bytes(32) or trust_version <= 1
+    ):
+        raise ValueError("trust root must name a rotated record and its version")
     if len(state_entries) > MAX_RECOVERY_STATE_OBJECTS:
         raise ValueError("recovery state closure exceeds bound")
     entries = sorted(
@@ -83,7 +91,9 @@ def build_recovery_index(
         RECOVERY_INDEX_HEADER.size + len(encoded),
         state_first_lba,
         state_last_lba,
-        bytes(4),
+        trust_object,
+        trust_version,
+        bytes(8),
     )
     return header + encoded
 
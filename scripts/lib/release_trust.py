@@ -10,8 +10,11 @@ from boot_contracts import (
     MAX_RELEASE_SIGNATURES,
     MAX_TARGET_BYTES,
     RELEASE_BYTES,
+    RELEASE_FLAG_AUTHORITY_WIDENING,
     RELEASE_HEADER_AUTHORITY_MANIFEST_END,
     RELEASE_HEADER_AUTHORITY_MANIFEST_OFFSET,
+    RELEASE_HEADER_APPROVED_WIDENINGS_END,
+    RELEASE_HEADER_APPROVED_WIDENINGS_OFFSET,
     RELEASE_HEADER_BYTES,
     RELEASE_HEADER_GENERATION_IDENTITY_END,
     RELEASE_HEADER_GENERATION_IDENTITY_OFFSET,
@@ -40,6 +43,17 @@ from boot_contracts import (
     GENERATION_HEADER_INSTANCE_OFFSET_OFFSET,
     GENERATION_HEADER_STRING_OFFSET_OFFSET,
     GENERATION_INSTANCE,
+    MAX_REVOKED_KEYS,
+    MAX_TRUST_KEYS,
+    ROTATION_BYTES,
+    ROTATION_HEADER_BYTES,
+    ROTATION_MAGIC,
+    ROTATION_VERSION,
+    TRUST_ROOT_HEADER,
+    TRUST_ROOT_HEADER_BYTES,
+    TRUST_ROOT_MAGIC,
+    TRUST_ROOT_RECORD_BYTES,
+    TRUST_ROOT_RECORD_VERSION,
 )
 
 from harness import ROOT
@@ -186,6 +200,8 @@ def build_release(
     sequence: int,
     key_paths: tuple[Path, ...] = KEY_PATHS,
     boot_bundle_identity: bytes | None = None,
+    approved_widenings: bytes | None = None,
+    trust_root_version: int = 1,
 ) -> bytes:
     identity, parent, target, authority = generation_release_fields(generation)
     if boot_bundle_identity is None:
@@ -195,15 +211,24 @@ def build_release(
     target_bytes = target.encode()
     if not 1 <= len(target_bytes) <= MAX_TARGET_BYTES or len(key_paths) > MAX_RELEASE_SIGNATURES:
         raise ValueError("release bound exceeded")
+    if approved_widenings is not None and (len(approved_widenings) != 32 or approved_widenings == bytes(32)):
+        raise ValueError("approved widenings must be a nonzero SHA-256 digest")
     release = bytearray(RELEASE_BYTES)
     release[:8] = RELEASE_MAGIC
-    struct.pack_into("<IIQ", release, 8, RELEASE_VERSION, RELEASE_HEADER_BYTES, 0)
+    # An approval is `generation_diff::widenings_digest` from the baseline the
+    # release may widen: it admits that widening list and no other.
+    flags = 0 if approved_widenings is None else RELEASE_FLAG_AUTHORITY_WIDENING
+    struct.pack_into("<IIQ", release, 8, RELEASE_VERSION, RELEASE_HEADER_BYTES, flags)
     release[RELEASE_HEADER_GENERATION_IDENTITY_OFFSET:RELEASE_HEADER_GENERATION_IDENTITY_END] = identity
     release[RELEASE_HEADER_PARENT_IDENTITY_OFFSET:RELEASE_HEADER_PARENT_IDENTITY_END] = parent
-    struct.pack_into("<QII", release, RELEASE_HEADER_RELEASE_SEQUENCE_OFFSET, sequence, len(target_bytes), 1)
+    struct.pack_into(
+        "<QII", release, RELEASE_HEADER_RELEASE_SEQUENCE_OFFSET, sequence, len(target_bytes), trust_root_version
+    )
     release[RELEASE_HEADER_TARGET_OFFSET : RELEASE_HEADER_TARGET_OFFSET + len(target_bytes)] = target_bytes
     release[RELEASE_HEADER_BOOT_BUNDLE_IDENTITY_OFFSET:RELEASE_HEADER_BOOT_BUNDLE_IDENTITY_END] = boot_bundle_identity
     release[RELEASE_HEADER_AUTHORITY_MANIFEST_OFFSET:RELEASE_HEADER_AUTHORITY_MANIFEST_END] = authority
+    if approved_widenings is not None:
+        release[RELEASE_HEADER_APPROVED_WIDENINGS_OFFSET:RELEASE_HEADER_APPROVED_WIDENINGS_END] = approved_widenings
     struct.pack_into("<I", release, RELEASE_HEADER_SIGNATURE_COUNT_OFFSET, len(key_paths))
     payload = bytes(release[:RELEASE_HEADER_BYTES])
     entries = sorted((sha256(ssh_public_key(path)), ssh_signature(path, payload)) for path in key_paths)
@@ -216,3 +241,78 @@ def build_release(
 
 def initial_public_keys() -> tuple[bytes, ...]:
     return tuple(ssh_public_key(path) for path in KEY_PATHS)
+
+
+def trust_root_record(
+    version: int, threshold: int, public_keys: tuple[bytes, ...], revoked: tuple[bytes, ...] = ()
+) -> bytes:
+    """The stored trust-root record, matching `release::TrustRoot::encode`.
+
+    Its SHA-256 is the object a BootState's trust-root reference names.
+    """
+    if not 0 < threshold <= len(public_keys) <= MAX_TRUST_KEYS or len(revoked) > MAX_REVOKED_KEYS:
+        raise ValueError("trust root out of bounds")
+    record = bytearray(TRUST_ROOT_RECORD_BYTES)
+    TRUST_ROOT_HEADER.pack_into(
+        record,
+        0,
+        TRUST_ROOT_MAGIC,
+        TRUST_ROOT_RECORD_VERSION,
+        TRUST_ROOT_HEADER_BYTES,
+        0,
+        version,
+        threshold,
+        len(public_keys),
+        len(revoked),
+    )
+    for index, key in enumerate(public_keys):
+        record[TRUST_ROOT_HEADER_BYTES + index * 32 : TRUST_ROOT_HEADER_BYTES + index * 32 + 32] = key
+    revoked_offset = TRUST_ROOT_HEADER_BYTES + MAX_TRUST_KEYS * 32
+    for index, key_id in enumerate(sorted(revoked)):
+        record[revoked_offset + index * 32 : revoked_offset + index * 32 + 32] = key_id
+    return bytes(record)
+
+
+def build_rotation(
+    current_keys: tuple[Path, ...],
+    replacement_keys: tuple[Path, ...],
+    previous_version: int,
+    replacement_version: int,
+    revoked: tuple[bytes, ...] = (),
+) -> bytes:
+    """A signed rotation record, as `release::apply_rotation` reads it: the
+    replacement keys at threshold 2, signed by `current_keys` and by
+    `replacement_keys` over the same payload."""
+    rotation = bytearray(ROTATION_BYTES)
+    rotation[:8] = ROTATION_MAGIC
+    struct.pack_into("<IIQ", rotation, 8, ROTATION_VERSION, ROTATION_HEADER_BYTES, 0)
+    struct.pack_into(
+        "<IIIIIII",
+        rotation,
+        24,
+        previous_version,
+        replacement_version,
+        2,
+        len(replacement_keys),
+        len(current_keys),
+        len(replacement_keys),
+        len(revoked),
+    )
+    replacement_public = tuple(ssh_public_key(path) for path in replacement_keys)
+    for index, public in enumerate(replacement_public):
+        offset = ROTATION_HEADER_BYTES + index * 32
+        rotation[offset : offset + 32] = public
+    revoked_offset = ROTATION_HEADER_BYTES + MAX_TRUST_KEYS * 32
+    for index, key_id in enumerate(sorted(revoked)):
+        offset = revoked_offset + index * 32
+        rotation[offset : offset + 32] = key_id
+    previous_offset = revoked_offset + MAX_REVOKED_KEYS * 32
+    replacement_offset = previous_offset + MAX_RELEASE_SIGNATURES * RELEASE_SIGNATURE_BYTES
+    payload = bytes(rotation[:previous_offset])
+    for base, paths in ((previous_offset, current_keys), (replacement_offset, replacement_keys)):
+        entries = sorted((sha256(ssh_public_key(path)), ssh_signature(path, payload)) for path in paths)
+        for index, (key_id, signature) in enumerate(entries):
+            offset = base + index * RELEASE_SIGNATURE_BYTES
+            rotation[offset : offset + 32] = key_id
+            rotation[offset + 32 : offset + RELEASE_SIGNATURE_BYTES] = signature
+    return bytes(rotation)
@@ -344,6 +344,10 @@ def _validate(spec: dict, components: dict[str, dict], contract: ModuleType) ->
             _fail(f"state: {binding['name']}: owner {binding['owner']!r} is not admitted")
         if binding["policy"] not in _builder.POLICY:
             _fail(f"state: {binding['name']}: unknown policy {binding['policy']!r}")
+        if ("migrateFrom" in binding) != ("migrator" in binding):
+            _fail(f"state: {binding['name']}: migrateFrom and migrator go together")
+        if "migrator" in binding and binding["migrator"] not in admitted:
+            _fail(f"state: {binding['name']}: migrator {binding['migrator']!r} is not admitted")
     # The builder refuses duplicate state names (`unique_sorted`), so a spec that
     # produced them could never encode; refusing here names the spec instead of
     # the manifest it would have written.
@@ -353,6 +357,10 @@ def _validate(spec: dict, components: dict[str, dict], contract: ModuleType) ->
     for binding in spec["state"]:
         if binding["schemaVersion"] <= 0:
             _fail(f"state: {binding['name']}: schemaVersion must be positive")
+        if "migrateFrom" in binding and (
+            binding["migrateFrom"] <= 0 or binding["migrateFrom"] == binding["schemaVersion"]
+        ):
+            _fail(f"state: {binding['name']}: migrateFrom must be positive and differ from schemaVersion")
     command_components = [entry["component"] for entry in spec["commandBindings"]]
     if len(set(command_components)) != len(command_components):
         _fail("commandBindings: duplicate component")
@@ -674,7 +682,17 @@ def derive_manifest(system: CompiledSystem) -> dict:
             "name": name,
             "owner": placement.get("owner", component["owner"]),
         }
-        for field in ("priority", "extraThreads", "workerPriority"):
+        for field in (
+            "priority",
+            "maxControlledPriority",
+            "extraThreads",
+            "workerPriority",
+            "budgetUs",
+            "periodUs",
+            "healthFaultWindowTicks",
+            "healthRoute",
+            "heapPages",
+        ):
             if field in placement:
                 instance[field] = placement[field]
         instances.append(instance)
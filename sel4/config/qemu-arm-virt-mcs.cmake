@@ -0,0 +1,28 @@
+set(KernelPlatform "qemu-arm-virt" CACHE STRING "")
+set(KernelSel4Arch "aarch64" CACHE STRING "")
+set(KernelArmHypervisorSupport ON CACHE BOOL "")
+# The alternate profile B48 deferred: the product configuration with MCS on.
+#
+# `qemu-arm-virt.cmake` records why the product kernel stays non-MCS, and that
+# reason still holds. The functional-correctness proofs do not cover MCS on
+# AArch64, so this profile is an unverified kernel. It builds separately
+# (`build-sel4.py --mcs`, under `build/sel4-mcs-*`) and is not a product image.
+# It exists so a generation's `budgetUs`/`periodUs` can be exercised and CPU
+# isolation between components demonstrated under QEMU, ahead of the
+# assurance decision rather than in place of it.
+#
+# Every other key matches the product profile, so a difference in behaviour
+# between the two images comes from the scheduler alone. `check-sel4-pins.py`
+# holds the two files to that.
+set(KernelIsMCS ON CACHE BOOL "")
+set(KernelMaxNumNodes 1 CACHE STRING "")
+set(KernelVerificationBuild OFF CACHE BOOL "")
+set(KernelDebugBuild ON CACHE BOOL "")
+set(KernelPrinting ON CACHE BOOL "")
+# Neither architected-timer export stays. Under MCS, `slime-root` takes its
+# deadlines from a scheduling context's period and counts time by another's
+# replenishments (`slime-root/src/timeout_timer.rs`), so it reads no `CNT*`
+# register. With the counter unexported, no component can observe time except
+# through a clock grant.
+set(KernelArmExportPCNTUser OFF CACHE BOOL "")
+set(KernelArmExportPTMRUser OFF CACHE BOOL "")
@@ -34,5 +34,12 @@ set(KernelPrinting ON CACHE BOOL "")
 # grant PL0 access to exactly the physical counter/frequency and physical
 # timer control/compare registers that scheme reads and writes; the virtual
 # counter/timer equivalents stay off because nothing here uses them.
+#
+# The exports are not the root's alone: any component can read the counter
+# too, so on this kernel a clock grant is a source-level rule
+# (`check-clock-authority.py`). The non-MCS kernel has no timer a user thread
+# can wait on, so turning them off leaves the root without time. The MCS
+# profile counts time by scheduling-context replenishments instead and turns
+# both off.
 set(KernelArmExportPCNTUser ON CACHE BOOL "")
 set(KernelArmExportPTMRUser ON CACHE BOOL "")
@@ -10,22 +10,30 @@ use boot_contracts::bootstate::{
     BOOTSTORE_CAPACITY, BOOTSTORE_DIRECTORY_OFFSET, BOOTSTORE_ENTRY_GENERATION_LEN_OFFSET,
     BOOTSTORE_ENTRY_GENERATION_OFFSET_OFFSET, BOOTSTORE_ENTRY_LEN, BOOTSTORE_ENTRY_PADDING_OFFSET,
     BOOTSTORE_ENTRY_RELEASE_LEN_OFFSET, BOOTSTORE_ENTRY_RELEASE_OFFSET_OFFSET,
-    BOOTSTORE_GENERATIONS_OFFSET, BOOTSTORE_HEADER_CAPACITY_OFFSET, BOOTSTORE_HEADER_CHECKSUM_END,
-    BOOTSTORE_HEADER_CHECKSUM_OFFSET, BOOTSTORE_HEADER_DIRECTORY_LEN_OFFSET,
-    BOOTSTORE_HEADER_ENTRY_COUNT_OFFSET, BOOTSTORE_HEADER_FORMAT_VERSION_OFFSET,
-    BOOTSTORE_HEADER_HEADER_SIZE_OFFSET, BOOTSTORE_HEADER_LEN,
-    BOOTSTORE_HEADER_REQUIRED_FLAGS_OFFSET, BOOTSTORE_HEADER_RESERVED_OFFSET, BOOTSTORE_MAGIC,
-    BOOTSTORE_RELEASES_OFFSET, BOOTSTORE_VERSION, BootState, SLOT_BYTES, SelectedBootState, Slot,
-    select_bootstate,
+    BOOTSTORE_EVIDENCE_OFFSET, BOOTSTORE_GENERATIONS_OFFSET, BOOTSTORE_HEADER_CAPACITY_OFFSET,
+    BOOTSTORE_HEADER_CHECKSUM_END, BOOTSTORE_HEADER_CHECKSUM_OFFSET,
+    BOOTSTORE_HEADER_DIRECTORY_LEN_OFFSET, BOOTSTORE_HEADER_ENTRY_COUNT_OFFSET,
+    BOOTSTORE_HEADER_FORMAT_VERSION_OFFSET, BOOTSTORE_HEADER_HEADER_SIZE_OFFSET,
+    BOOTSTORE_HEADER_LEN, BOOTSTORE_HEADER_REQUIRED_FLAGS_OFFSET, BOOTSTORE_HEADER_RESERVED_OFFSET,
+    BOOTSTORE_MAGIC, BOOTSTORE_RELEASES_OFFSET, BOOTSTORE_SEALED_OFFSET, BOOTSTORE_VERDICT_OFFSET,
+    BOOTSTORE_VERSION, BootState, HEALTH_VERDICT_BYTES, HealthVerdict, SHADOW_EVIDENCE_BYTES,
+    SLOT_BYTES, SelectedBootState, ShadowEvidence, Slot, select_bootstate,
 };
 use boot_contracts::generation::{Generation, generation_identity};
 use boot_contracts::gpt::{self, GptError, Partition};
-use boot_contracts::release::{INITIAL_TRUST_ROOT, RELEASE_BYTES, Release};
+use boot_contracts::release::{
+    INITIAL_TRUST_ROOT, RELEASE_BYTES, Release, TRUST_ROOT_RECORD_BYTES, TrustRoot,
+};
 use boot_contracts::sha256::Sha256;
 
 const STATE_SLOT_A: u64 = 0;
 const STATE_SLOT_B: u64 = 1;
 const MAX_DIRECTORY_ENTRIES: usize = 64;
+/// The rotated trust root's record, read only when BootState names one: the
+/// sector straight after the boot store, in the same partition. Every sector
+/// below the sealed offset is spoken for, and the record needs no seal of its
+/// own — BootState carries its hash and version.
+const TRUST_ROOT_SECTOR: u64 = (BOOTSTORE_CAPACITY / SECTOR_BYTES) as u64;
 // The size of this buffer is a root CSlot budget decision, not a statement about
 // the store: store v1 places generations after 16 KiB and declares no ceiling on
 // how large one may be, so nothing but this constant bounds what the selector
@@ -69,8 +77,11 @@ pub enum SelectorError {
     MissingGeneration,
     Generation,
     Release,
+    TrustRoot,
     WrongBootBundle,
     Commit,
+    Evidence,
+    Verdict,
 }
 
 #[derive(Clone, Copy)]
@@ -84,6 +95,10 @@ struct DirectoryEntry {
 pub struct SelectedGeneration {
     pub generation: Generation<'static>,
     pub runtime: BootRuntime,
+    /// The pending generation, when the evidence sector asks for it to be
+    /// shadowed rather than booted. `Err` when it was asked for and could not
+    /// be loaded; the root records that as a refusal and boots on.
+    pub shadow: Option<Result<Generation<'static>, SelectorError>>,
 }
 
 /// Durable selection context retained while the selected graph runs.
@@ -93,6 +108,7 @@ pub struct BootRuntime {
     running_identity: [u8; 32],
     release_sequence: u64,
     running_pending: bool,
+    shadow_request: Option<ShadowEvidence>,
 }
 
 impl BootRuntime {
@@ -105,13 +121,84 @@ impl BootRuntime {
     pub const fn remaining_attempts(&self) -> u32 {
         self.selected.state.remaining_attempts
     }
+    /// The sequence of the release the running generation was verified under.
+    pub const fn release_sequence(&self) -> u64 {
+        self.release_sequence
+    }
+    /// The request this boot is shadowing, if it is shadowing one.
+    pub const fn shadow_request(&self) -> Option<ShadowEvidence> {
+        self.shadow_request
+    }
+
+    /// Replace the shadow request with its verdict.
+    ///
+    /// The evidence sector is the only one a shadow boot writes, and it
+    /// writes it once: BootState is untouched, so the pending generation keeps
+    /// every attempt it had, and the request is consumed by being overwritten.
+    /// Flushed and read back before success, as a BootState commit is.
+    pub fn record_shadow(
+        &mut self,
+        device: &mut VirtioBlock,
+        evidence: ShadowEvidence,
+    ) -> Result<(), SelectorError> {
+        let Some(request) = self.shadow_request else {
+            return Err(SelectorError::Evidence);
+        };
+        if evidence.candidate != request.candidate {
+            return Err(SelectorError::Evidence);
+        }
+        let bytes = evidence.encode().map_err(|_| SelectorError::Evidence)?;
+        let lba = self.partition_first_lba + (BOOTSTORE_EVIDENCE_OFFSET / SECTOR_BYTES) as u64;
+        device
+            .write_sector(lba, &bytes)
+            .map_err(|_| SelectorError::Commit)?;
+        device.flush().map_err(|_| SelectorError::Commit)?;
+        if read_evidence(device, self.partition_first_lba)? != Some(evidence) {
+            return Err(SelectorError::Commit);
+        }
+        self.shadow_request = None;
+        Ok(())
+    }
+
+    /// Record the supervision table's verdict on the running candidate.
+    ///
+    /// Written only while the candidate is the running generation and only for
+    /// it, flushed and read back before success. The verdict is what a later
+    /// boot and the generation manager decide from: a pass is followed here by
+    /// [`Self::confirm`], a failure is left for the next selection to roll
+    /// back before it spends another attempt.
+    pub fn record_verdict(
+        &mut self,
+        device: &mut VirtioBlock,
+        verdict: HealthVerdict,
+    ) -> Result<(), SelectorError> {
+        if !self.running_pending
+            || verdict.generation != self.running_identity
+            || verdict.release_sequence != self.release_sequence
+        {
+            return Err(SelectorError::Verdict);
+        }
+        let bytes = verdict.encode().map_err(|_| SelectorError::Verdict)?;
+        write_verdict(device, self.partition_first_lba, &bytes)?;
+        if read_verdict(device, self.partition_first_lba)? != Some(verdict) {
+            return Err(SelectorError::Commit);
+        }
+        Ok(())
+    }
 
     /// Health confirmation is the only transition that promotes a pending
-    /// generation. The commit is older-slot-first and flushed before success.
+    /// generation. The commit is older-slot-first and flushed before success;
+    /// the verdict that allowed it is consumed afterwards, so a later pending
+    /// generation is judged by its own boot.
     pub fn confirm(&mut self, device: &mut VirtioBlock) -> Result<(), SelectorError> {
         if !self.running_pending {
             return Err(SelectorError::BootState);
         }
+        if !read_verdict(device, self.partition_first_lba)?
+            .is_some_and(|verdict| verdict.promotes(self.running_identity, self.release_sequence))
+        {
+            return Err(SelectorError::Verdict);
+        }
         let promoted = self
             .selected
             .state
@@ -124,7 +211,7 @@ impl BootRuntime {
             promoted,
         )?;
         self.running_pending = false;
-        Ok(())
+        write_verdict(device, self.partition_first_lba, &[0; HEALTH_VERDICT_BYTES])
     }
 
     /// An explicit unhealthy report never repairs the attempt consumed before
@@ -149,12 +236,36 @@ pub fn select(
     if selected.state.generation_root != generation_root {
         return Err(SelectorError::BootState);
     }
+    // A failed verdict for the pending generation rolls it back before an
+    // attempt is spent on it, and is consumed with it. Stage-0 makes the same
+    // decision from the same sector; whichever reads it first commits it.
+    if let Some(pending) = selected.state.pending
+        && read_verdict(device, partition.first_lba)?
+            .is_some_and(|verdict| verdict.rejects(pending))
+    {
+        let rolled_back = selected
+            .state
+            .rollback_pending()
+            .map_err(|_| SelectorError::BootState)?;
+        selected = commit_state(device, partition.first_lba, selected.slot, rolled_back)?;
+        write_verdict(device, partition.first_lba, &[0; HEALTH_VERDICT_BYTES])?;
+    }
 
     let selection_state = selected.state;
     let pending_exhausted =
         selection_state.pending.is_some() && selection_state.remaining_attempts == 0;
-    let running_pending =
-        selection_state.pending.is_some() && selection_state.remaining_attempts > 0;
+    // A shadow request for the live candidate boots known-good instead and
+    // spends no attempt: the candidate runs beside it, not in its place. A
+    // request naming anything else is stale and ignored.
+    let shadow_request = read_evidence(device, partition.first_lba)?.filter(|request| {
+        selection_state.remaining_attempts > 0
+            && selection_state
+                .pending
+                .is_some_and(|pending| request.requests(pending))
+    });
+    let running_pending = selection_state.pending.is_some()
+        && selection_state.remaining_attempts > 0
+        && shadow_request.is_none();
     let running_identity = if running_pending {
         selection_state.pending.ok_or(SelectorError::BootState)?
     } else {
@@ -178,19 +289,20 @@ pub fn select(
         .find(|entry| entry.identity == running_identity)
         .copied()
         .ok_or(SelectorError::MissingGeneration)?;
-    let generation_bytes = read_generation(device, &partition, entry)?;
+    let generation_bytes = read_generation(device, &partition, entry, 0)?;
     if generation_identity(generation_bytes) != entry.identity {
         return Err(SelectorError::Generation);
     }
     let generation = Generation::decode(generation_bytes).map_err(|_| SelectorError::Generation)?;
-    if !generation.is_v5() {
+    if !generation.is_v6() {
         return Err(SelectorError::Generation);
     }
 
+    let root = read_trust_root(device, &partition, &selection_state)?;
     let release_bytes = read_release(device, &partition, entry)?;
     let release = Release::decode(&release_bytes).map_err(|_| SelectorError::Release)?;
     release
-        .verify_generation(&generation, &INITIAL_TRUST_ROOT)
+        .verify_generation(&generation, &root)
         .map_err(|_| SelectorError::Release)?;
     release
         .verify_boot_bundle(expected_boot_bundle)
@@ -210,6 +322,19 @@ pub fn select(
         selected = commit_state(device, partition.first_lba, selected.slot, rolled_back)?;
     }
 
+    let shadow = shadow_request.map(|request| {
+        load_shadow(
+            device,
+            &partition,
+            &entries,
+            &root,
+            request,
+            generation_bytes.len().next_multiple_of(8),
+            selection_state.accepted_release_sequence,
+            expected_boot_bundle,
+        )
+    });
+
     Ok(SelectedGeneration {
         generation,
         runtime: BootRuntime {
@@ -218,17 +343,139 @@ pub fn select(
             running_identity,
             release_sequence: release.sequence,
             running_pending,
+            shadow_request,
         },
+        shadow,
     })
 }
 
+/// Load and verify the generation a shadow request names.
+///
+/// The same checks the candidate would face as the running generation — its
+/// identity, its wire version, its release against the trust root and this
+/// boot bundle, and a sequence above the accepted one — plus one of its own:
+/// the release must be the one the request named, so evidence cannot be
+/// gathered for one release and read as evidence for another.
+fn load_shadow(
+    device: &mut VirtioBlock,
+    partition: &Partition,
+    entries: &[Option<DirectoryEntry>; MAX_DIRECTORY_ENTRIES],
+    root: &TrustRoot,
+    request: ShadowEvidence,
+    start: usize,
+    accepted_release_sequence: u64,
+    expected_boot_bundle: &[u8; 32],
+) -> Result<Generation<'static>, SelectorError> {
+    let entry = entries
+        .iter()
+        .flatten()
+        .find(|entry| entry.identity == request.candidate)
+        .copied()
+        .ok_or(SelectorError::MissingGeneration)?;
+    let bytes = read_generation(device, partition, entry, start)?;
+    if generation_identity(bytes) != entry.identity {
+        return Err(SelectorError::Generation);
+    }
+    let generation = Generation::decode(bytes).map_err(|_| SelectorError::Generation)?;
+    if !generation.is_v6() {
+        return Err(SelectorError::Generation);
+    }
+    let release_bytes = read_release(device, partition, entry)?;
+    let release = Release::decode(&release_bytes).map_err(|_| SelectorError::Release)?;
+    release
+        .verify_generation(&generation, root)
+        .map_err(|_| SelectorError::Release)?;
+    release
+        .verify_boot_bundle(expected_boot_bundle)
+        .map_err(|_| SelectorError::WrongBootBundle)?;
+    if release.sequence <= accepted_release_sequence || release.sequence != request.release_sequence
+    {
+        return Err(SelectorError::Release);
+    }
+    Ok(generation)
+}
+
+/// The trust root releases are verified against: the compiled-in one while
+/// BootState names none, otherwise the record in `TRUST_ROOT_SECTOR`, which
+/// must hash to the named object and carry the named version. Stage 0 holds
+/// its copy to the same checks.
+///
+/// A named root that cannot be read back fails selection rather than falling
+/// back to the compiled-in one, which would re-accept the keys a rotation
+/// retired.
+fn read_trust_root(
+    device: &mut VirtioBlock,
+    partition: &Partition,
+    state: &BootState,
+) -> Result<TrustRoot, SelectorError> {
+    let Some(reference) = state.trust_root else {
+        return Ok(INITIAL_TRUST_ROOT);
+    };
+    let mut record = [0u8; TRUST_ROOT_RECORD_BYTES];
+    read_partition_sector(device, partition, TRUST_ROOT_SECTOR, &mut record)
+        .map_err(|_| SelectorError::TrustRoot)?;
+    let mut hash = Sha256::new();
+    hash.update(&record);
+    if hash.finalize() != reference.object {
+        return Err(SelectorError::TrustRoot);
+    }
+    let root = TrustRoot::decode(&record).map_err(|_| SelectorError::TrustRoot)?;
+    if root.version != reference.version {
+        return Err(SelectorError::TrustRoot);
+    }
+    Ok(root)
+}
+
+/// The evidence sector's record, or `None` for a sector that holds none.
+fn read_evidence(
+    device: &mut VirtioBlock,
+    first_lba: u64,
+) -> Result<Option<ShadowEvidence>, SelectorError> {
+    let mut bytes = [0u8; SHADOW_EVIDENCE_BYTES];
+    device
+        .read_sector(
+            first_lba + (BOOTSTORE_EVIDENCE_OFFSET / SECTOR_BYTES) as u64,
+            &mut bytes,
+        )
+        .map_err(|_| SelectorError::Device)?;
+    Ok(ShadowEvidence::decode(&bytes).ok())
+}
+
+fn read_verdict(
+    device: &mut VirtioBlock,
+    first_lba: u64,
+) -> Result<Option<HealthVerdict>, SelectorError> {
+    let mut bytes = [0u8; HEALTH_VERDICT_BYTES];
+    device
+        .read_sector(
+            first_lba + (BOOTSTORE_VERDICT_OFFSET / SECTOR_BYTES) as u64,
+            &mut bytes,
+        )
+        .map_err(|_| SelectorError::Device)?;
+    Ok(HealthVerdict::decode(&bytes).ok())
+}
+
+fn write_verdict(
+    device: &mut VirtioBlock,
+    first_lba: u64,
+    bytes: &[u8; HEALTH_VERDICT_BYTES],
+) -> Result<(), SelectorError> {
+    device
+        .write_sector(
+            first_lba + (BOOTSTORE_VERDICT_OFFSET / SECTOR_BYTES) as u64,
+            bytes,
+        )
+        .map_err(|_| SelectorError::Commit)?;
+    device.flush().map_err(|_| SelectorError::Commit)
+}
+
 fn locate_partition(device: &mut VirtioBlock) -> Result<Partition, SelectorError> {
     let capacity = device.capacity_sectors();
     let mut reader = |lba: u64, out: &mut [u8; SECTOR_BYTES]| -> Result<(), GptError> {
         device.read_sector(lba, out).map_err(|_| GptError::Device)
     };
-    let selected =
-        gpt::validate_store_partition(&mut reader, capacity).map_err(|_| SelectorError::Gpt)?;
+    let selected = gpt::validate_store_partition(&mut reader, capacity, None)
+        .map_err(|_| SelectorError::Gpt)?;
     let sectors = selected
         .partition
         .last_lba
@@ -324,7 +571,7 @@ fn verify_directory_checksum(
     expected: &[u8],
 ) -> Result<(), SelectorError> {
     let mut hasher = Sha256::new();
-    let first = (SLOT_BYTES * 2 / SECTOR_BYTES) as u64;
+    let first = (BOOTSTORE_SEALED_OFFSET / SECTOR_BYTES) as u64;
     let sectors = (BOOTSTORE_CAPACITY / SECTOR_BYTES) as u64;
     let checksum_start = BOOTSTORE_DIRECTORY_OFFSET + BOOTSTORE_HEADER_CHECKSUM_OFFSET;
     let checksum_end = BOOTSTORE_DIRECTORY_OFFSET + BOOTSTORE_HEADER_CHECKSUM_END;
@@ -395,21 +642,38 @@ fn commit_state(
     Ok(live)
 }
 
+/// Read `entry`'s generation into the buffer at `start`.
+///
+/// The running generation goes at zero and a shadow candidate straight after
+/// it, 8-aligned, so one buffer — one root CSlot budget — holds both. Two that
+/// do not fit together are refused here rather than the shadow evicting
+/// anything.
 fn read_generation(
     device: &mut VirtioBlock,
     partition: &Partition,
     entry: DirectoryEntry,
+    start: usize,
 ) -> Result<&'static [u8], SelectorError> {
-    // SAFETY: the root is single-threaded, selection runs once before graph
-    // launch, and the returned bytes remain immutable for the rest of boot.
-    let buffer = unsafe { &mut *core::ptr::addr_of_mut!(GENERATION_BUFFER) };
-    read_partition_bytes(
-        device,
-        partition,
-        entry.generation_offset,
-        &mut buffer.0[..entry.generation_len],
-    )?;
-    Ok(&buffer.0[..entry.generation_len])
+    if start
+        .checked_add(entry.generation_len)
+        .is_none_or(|end| end > SELECTOR_GENERATION_BYTES)
+    {
+        return Err(SelectorError::Generation);
+    }
+    // SAFETY: the root is single-threaded and selection runs once before graph
+    // launch. Each call fills a range no earlier call returned — the shadow's
+    // starts past the running generation's end — and every returned range
+    // stays immutable for the rest of boot.
+    let region = unsafe {
+        core::slice::from_raw_parts_mut(
+            core::ptr::addr_of_mut!(GENERATION_BUFFER)
+                .cast::<u8>()
+                .add(start),
+            entry.generation_len,
+        )
+    };
+    read_partition_bytes(device, partition, entry.generation_offset, region)?;
+    Ok(region)
 }
 
 fn read_release(
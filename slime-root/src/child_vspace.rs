@@ -24,10 +24,19 @@ pub const GRANULE_SIZE: usize = sel4::FrameObjectType::GRANULE.bytes();
 /// silently truncating.
 pub const MAX_CHILD_IMAGE_PAGES: usize = 512;
 
-/// Highest child virtual address this root task will map. AArch64 user VAs are
+/// Highest child virtual address an image may span. AArch64 user VAs are
 /// 48-bit; the bound keeps footprint arithmetic inside a single `usize`.
 const CHILD_ADDRESS_CEILING: usize = 1usize << 40;
 
+/// Where a child's heap window starts: at the image ceiling, so no accepted
+/// footprint or thread page can overlap it and nothing a component maps at
+/// its own chosen base sits this high.
+pub const HEAP_WINDOW_BASE: usize = CHILD_ADDRESS_CEILING;
+
+/// Pages one heap window may grow to. Each is a frame the task's arena
+/// records beside the image's, so the bound is what that record holds.
+pub const MAX_HEAP_PAGES: usize = 256;
+
 const FLAG_READ: u8 = 1 << 0;
 const FLAG_WRITE: u8 = 1 << 1;
 const FLAG_EXEC: u8 = 1 << 2;
@@ -63,6 +72,11 @@ pub enum VSpaceError {
         requested: usize,
         limit: usize,
     },
+    /// A heap quota past [`MAX_HEAP_PAGES`], or a growth past the quota.
+    HeapQuota {
+        requested: usize,
+        limit: usize,
+    },
     Image(ImageError),
     Alloc(AllocError),
     /// Assigning an ASID to the child VSpace failed.
@@ -173,24 +187,24 @@ impl<'a> ChildImage<'a> {
         self.footprint.len() / GRANULE_SIZE
     }
 
-    /// Exact kernel-memory plan for the VSpace portion of this image.
-    pub fn vspace_arena_plan(&self, threads: usize) -> Result<ArenaPlan, ImageError> {
+    /// Exact kernel-memory plan for the VSpace portion of this image, with
+    /// the tables and every frame of a heap window of `heap_pages` reserved
+    /// up front: growth retypes from the same arena, which cannot be resized
+    /// once the task exists.
+    pub fn vspace_arena_plan(
+        &self,
+        threads: usize,
+        heap_pages: usize,
+    ) -> Result<ArenaPlan, VSpaceError> {
         let mapped = thread_mapped_span(&self.footprint, threads)?;
         let mut plan = ArenaPlan::new();
         plan.add(sel4::cap_type::VSpace::object_blueprint())
             .ok_or(ImageError::FootprintOutOfRange)?;
-        for level in 1..sel4::vspace_levels::NUM_LEVELS {
-            let span_bytes = 1usize << sel4::vspace_levels::span_bits(level);
-            let coarse = coarsen(&mapped, span_bytes);
-            let Some(ty) = sel4::TranslationTableObjectType::from_level(level) else {
-                continue;
-            };
-            for _ in 0..(coarse.len() / span_bytes) {
-                plan.add(ty.blueprint())
-                    .ok_or(ImageError::FootprintOutOfRange)?;
-            }
+        plan_intermediate_tables(&mut plan, &mapped)?;
+        if heap_pages != 0 {
+            plan_intermediate_tables(&mut plan, &heap_window(heap_pages)?)?;
         }
-        for _ in 0..(self.image_pages() + 2 * threads) {
+        for _ in 0..(self.image_pages() + 2 * threads + heap_pages) {
             plan.add(sel4::cap_type::Granule::object_blueprint())
                 .ok_or(ImageError::FootprintOutOfRange)?;
         }
@@ -278,6 +292,10 @@ pub struct ChildVSpace {
     pub threads: usize,
     pub frames_mapped: usize,
     pub tables_mapped: usize,
+    /// Heap pages the plan lets this child grow by, and how many it has:
+    /// the window's next page is `HEAP_WINDOW_BASE + heap_mapped` granules.
+    pub heap_pages: usize,
+    pub heap_mapped: usize,
 }
 
 impl ChildVSpace {
@@ -366,6 +384,7 @@ pub fn create_child_vspace(
     scratch: &ScratchPage,
     asid_pool: sel4::cap::AsidPool,
     threads: usize,
+    heap_pages: usize,
 ) -> Result<ChildVSpace, VSpaceError> {
     admit_thread_count(threads)?;
     let footprint = image.footprint();
@@ -381,7 +400,13 @@ pub fn create_child_vspace(
     // The arena planner uses this exact helper too: mapping a wider range than
     // it plans would make construction depend on power-of-two arena slack.
     let mapped = thread_mapped_span(&footprint, threads).map_err(VSpaceError::Image)?;
-    let tables_mapped = map_intermediate_tables(allocator, arena, vspace, &mapped)?;
+    let mut tables_mapped = map_intermediate_tables(allocator, arena, vspace, &mapped)?;
+    // The heap window's tables exist from the start, so a growth maps frames
+    // and nothing else: it cannot fail for want of a table halfway through.
+    if heap_pages != 0 {
+        tables_mapped +=
+            map_intermediate_tables(allocator, arena, vspace, &heap_window(heap_pages)?)?;
+    }
 
     let mut pages = [EMPTY_PAGE; MAX_CHILD_IMAGE_PAGES];
     let page_count = image.image_pages();
@@ -430,9 +455,78 @@ pub fn create_child_vspace(
         threads,
         frames_mapped: page_count + 2 * threads,
         tables_mapped,
+        heap_pages,
+        heap_mapped: 0,
     })
 }
 
+/// Map `pages` more frames at the top of `child`'s heap window, read-write and
+/// never executable, and answer how many the window then holds.
+///
+/// Retyped from the task's own arena, which construction sized for the whole
+/// quota, so the arena's revoke at teardown reclaims them with the rest. A
+/// growth past the quota maps nothing; one the kernel refuses partway keeps
+/// the frames already mapped, counted, since they are the child's either way.
+pub fn grow_heap(
+    allocator: &mut ObjectAllocator,
+    arena: TaskArenaId,
+    child: &mut ChildVSpace,
+    pages: usize,
+) -> Result<usize, VSpaceError> {
+    let requested = child
+        .heap_mapped
+        .checked_add(pages)
+        .filter(|&requested| requested <= child.heap_pages)
+        .ok_or(VSpaceError::HeapQuota {
+            requested: child.heap_mapped.saturating_add(pages),
+            limit: child.heap_pages,
+        })?;
+    while child.heap_mapped < requested {
+        let vaddr = HEAP_WINDOW_BASE + child.heap_mapped * GRANULE_SIZE;
+        allocator
+            .allocate_fixed_in::<sel4::cap_type::Granule>(arena)?
+            .cap()
+            .frame_map(
+                child.vspace,
+                vaddr,
+                sel4::CapRights::read_write(),
+                sel4::VmAttributes::DEFAULT | sel4::VmAttributes::EXECUTE_NEVER,
+            )
+            .map_err(|error| VSpaceError::FrameMap { vaddr, error })?;
+        child.heap_mapped += 1;
+        child.frames_mapped += 1;
+    }
+    Ok(child.heap_mapped)
+}
+
+/// The window a heap of `pages` spans, refused past [`MAX_HEAP_PAGES`].
+fn heap_window(pages: usize) -> Result<Range<usize>, VSpaceError> {
+    if pages > MAX_HEAP_PAGES {
+        return Err(VSpaceError::HeapQuota {
+            requested: pages,
+            limit: MAX_HEAP_PAGES,
+        });
+    }
+    Ok(HEAP_WINDOW_BASE..HEAP_WINDOW_BASE + pages * GRANULE_SIZE)
+}
+
+/// Add the intermediate tables covering `range` to `plan`: what
+/// [`map_intermediate_tables`] allocates for the same range.
+fn plan_intermediate_tables(plan: &mut ArenaPlan, range: &Range<usize>) -> Result<(), ImageError> {
+    for level in 1..sel4::vspace_levels::NUM_LEVELS {
+        let span_bytes = 1usize << sel4::vspace_levels::span_bits(level);
+        let coarse = coarsen(range, span_bytes);
+        let Some(ty) = sel4::TranslationTableObjectType::from_level(level) else {
+            continue;
+        };
+        for _ in 0..(coarse.len() / span_bytes) {
+            plan.add(ty.blueprint())
+                .ok_or(ImageError::FootprintOutOfRange)?;
+        }
+    }
+    Ok(())
+}
+
 /// Maps thread `index`'s IPC buffer and transfer window.
 ///
 /// The pairs sit above the image in thread order — buffer at `base`, window one
@@ -780,9 +874,9 @@ mod tests {
     use sel4::CapTypeForObjectOfFixedSize;
 
     use super::{
-        CHILD_ADDRESS_CEILING, FLAG_EXEC, FLAG_READ, FLAG_WRITE, GRANULE_SIZE, ImageError,
-        MAX_CHILD_IMAGE_PAGES, coarsen, reject_writable_executable, round_down, thread_mapped_span,
-        validate_footprint_span,
+        CHILD_ADDRESS_CEILING, FLAG_EXEC, FLAG_READ, FLAG_WRITE, GRANULE_SIZE, HEAP_WINDOW_BASE,
+        ImageError, MAX_CHILD_IMAGE_PAGES, MAX_HEAP_PAGES, VSpaceError, coarsen, heap_window,
+        reject_writable_executable, round_down, thread_mapped_span, validate_footprint_span,
     };
 
     #[test]
@@ -864,4 +958,21 @@ mod tests {
         );
         assert_eq!(plan.required_size_bits(), Some(first));
     }
+
+    #[test]
+    fn heap_window_sits_above_every_image_and_is_bounded() {
+        let highest_image =
+            thread_mapped_span(&(0x1000..CHILD_ADDRESS_CEILING - 2 * GRANULE_SIZE), 1).unwrap();
+        let window = heap_window(MAX_HEAP_PAGES).unwrap();
+        assert!(highest_image.end <= window.start);
+        assert_eq!(window.start, HEAP_WINDOW_BASE);
+        assert_eq!(window.len(), MAX_HEAP_PAGES * GRANULE_SIZE);
+        assert_eq!(
+            heap_window(MAX_HEAP_PAGES + 1),
+            Err(VSpaceError::HeapQuota {
+                requested: MAX_HEAP_PAGES + 1,
+                limit: MAX_HEAP_PAGES,
+            })
+        );
+    }
 }
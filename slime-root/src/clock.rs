@@ -0,0 +1,477 @@
+//! Time as granted authority.
+//!
+//! The root answers a component's time only through a clock capability its
+//! generation declares: `monotonicClock`, `wallClock`, or `simulatedClock`, one
+//! kind per grant. This module owns the one [`PlatformTimer`] the root holds
+//! once the boot proof is done with it.
+//!
+//! How far that holds depends on the kernel. The MCS profile exports no
+//! architected-timer register to EL0: the root counts time by a scheduling
+//! context's replenishments (`timeout_timer.rs`), so a component without a
+//! grant has no way to observe time at all. The non-MCS kernels (the verified
+//! product profile and the Raspberry Pi) give user mode no time source but the
+//! architected registers, so they export the counter
+//! (`KernelArmExportPCNTUser`) and the physical timer for the root's
+//! `platform_timer.rs`, and any component can execute the same read. There
+//! `check-clock-authority.py`, which keeps every other source in the tree from
+//! naming the counter, is the whole of the rule, and a replay or determinism
+//! claim rests on it as much as on the generation.
+//!
+//! The three kinds are distinct because they answer different questions:
+//!
+//! - **Monotonic** is the counter the timer is programmed against, scaled to
+//!   nanoseconds. It never goes backwards and says nothing about the date.
+//! - **Wall** is the monotonic reading offset by a Unix-epoch anchor the root
+//!   reads once at startup from the platform's real-time clock. Without one
+//!   there is no wall clock to grant, and a read is refused rather than
+//!   answered with a reading starting at 1970. It takes no waits: a clock that
+//!   can be re-anchored is not one a deadline can be measured on.
+//! - **Simulated** starts at zero and moves only when a holder of
+//!   `clockAdvance` moves it, so a component reading it sees exactly the time
+//!   its driver chose. Each `simulatedClock` grant is its own clock: copies
+//!   narrowed from one grant share it, and two scenarios driven from two
+//!   grants cannot move each other's time.
+//!
+//! Waits are recorded in a [`TimerScheduler`] per waitable clock, one per
+//! task, and delivered by polling: `WAIT` answers `WouldBlock` until the
+//! deadline has passed, as `INPUT READ` does until an event arrives. The
+//! monotonic queue arms no hardware, because the dispatch loop blocks on its
+//! endpoint rather than on the timer notification; a simulated clock's queue
+//! is what its `ADVANCE` counts releases from.
+
+use crate::event::{MonotonicInstant, TaskEpoch};
+use crate::timer::{PlatformTimer, TimerError, TimerScheduler};
+
+const NANOS_PER_SECOND: u128 = 1_000_000_000;
+
+/// Simulated clocks the root keeps at once. A simulated clock takes a slot
+/// the first time it is waited on or advanced; one only ever read is at zero
+/// and needs none.
+pub const MAX_SIMULATED_CLOCKS: usize = 4;
+
+/// Which clock a capability names. The discriminants are the generation's
+/// `CAPABILITY_*` numbers, so the manifest's kind and this one cannot drift.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum ClockKind {
+    Monotonic = boot_contracts::generation::CAPABILITY_MONOTONIC_CLOCK,
+    Wall = boot_contracts::generation::CAPABILITY_WALL_CLOCK,
+    Simulated = boot_contracts::generation::CAPABILITY_SIMULATED_CLOCK,
+}
+
+/// One clock a capability names. A simulated clock is named by the grant that
+/// declared it, so every copy derived from that grant reads the same time.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Clock {
+    Monotonic,
+    Wall,
+    Simulated { grant: usize },
+}
+
+impl Clock {
+    pub const fn kind(self) -> ClockKind {
+        match self {
+            Self::Monotonic => ClockKind::Monotonic,
+            Self::Wall => ClockKind::Wall,
+            Self::Simulated { .. } => ClockKind::Simulated,
+        }
+    }
+}
+
+/// The wall clock's anchor: the Unix time in nanoseconds at one counter
+/// reading.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct WallEpoch {
+    pub unix_ns: u64,
+    pub counter: MonotonicInstant,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ClockError {
+    /// The platform counter could not be read.
+    Platform,
+    /// A wall clock was asked for and the root found no real-time clock to
+    /// anchor one.
+    Unanchored,
+    /// A wait on the wall clock.
+    NotWaitable,
+    /// An advance to a reading behind the simulated clock's own.
+    Backwards { now_ns: u64, requested_ns: u64 },
+    /// Every simulated clock slot already belongs to another grant.
+    SimulatedExhausted { grant: usize },
+    /// An advance named a clock that is not simulated.
+    NotSimulated,
+    /// The wait queue refused the wait.
+    Timer(TimerError),
+}
+
+/// The answer to a wait: the clock's reading, and whether the deadline was
+/// reached by it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WaitOutcome {
+    Reached { now_ns: u64 },
+    Pending { now_ns: u64 },
+}
+
+/// One grant's simulated time and the waits recorded against it.
+struct SimulatedClock<const CAPACITY: usize> {
+    grant: usize,
+    now_ns: u64,
+    waits: TimerScheduler<CAPACITY>,
+}
+
+/// The root's clocks. `CAPACITY` bounds each waitable clock's queue; one wait
+/// per task means the task table's bound is enough.
+pub struct Clocks<P: PlatformTimer, const CAPACITY: usize> {
+    platform: P,
+    frequency_hz: u64,
+    wall: Option<WallEpoch>,
+    monotonic_waits: TimerScheduler<CAPACITY>,
+    simulated: [Option<SimulatedClock<CAPACITY>>; MAX_SIMULATED_CLOCKS],
+}
+
+impl<P: PlatformTimer, const CAPACITY: usize> Clocks<P, CAPACITY> {
+    /// Take ownership of the root's timer. `frequency_hz` is the counter's
+    /// rate; a zero rate would make every reading infinite, so it is clamped
+    /// to one.
+    pub const fn new(platform: P, frequency_hz: u64, wall: Option<WallEpoch>) -> Self {
+        Self {
+            platform,
+            frequency_hz: if frequency_hz == 0 { 1 } else { frequency_hz },
+            wall,
+            monotonic_waits: TimerScheduler::new(),
+            simulated: [const { None }; MAX_SIMULATED_CLOCKS],
+        }
+    }
+
+    /// Whether a wall clock can be answered.
+    pub const fn wall_anchored(&self) -> bool {
+        self.wall.is_some()
+    }
+
+    /// Waits recorded on `clock`'s queue.
+    pub fn pending(&self, clock: Clock) -> usize {
+        match clock {
+            Clock::Monotonic => self.monotonic_waits.len(),
+            Clock::Wall => 0,
+            Clock::Simulated { grant } => self
+                .simulated(grant)
+                .map_or(0, |simulated| simulated.waits.len()),
+        }
+    }
+
+    /// `clock`'s current reading, in nanoseconds.
+    pub fn now(&mut self, clock: Clock) -> Result<u64, ClockError> {
+        match clock {
+            Clock::Monotonic => {
+                let ticks = self.counter()?.0;
+                Ok(self.ticks_to_ns(ticks))
+            }
+            Clock::Wall => {
+                let epoch = self.wall.ok_or(ClockError::Unanchored)?;
+                let elapsed = self.counter()?.0.saturating_sub(epoch.counter.0);
+                Ok(epoch.unix_ns.saturating_add(self.ticks_to_ns(elapsed)))
+            }
+            Clock::Simulated { grant } => Ok(self
+                .simulated(grant)
+                .map_or(0, |simulated| simulated.now_ns)),
+        }
+    }
+
+    /// Answer whether `deadline_ns` has passed on `clock`, recording a wait for
+    /// `owner` if not. A task holds at most one wait per clock, so a new one
+    /// replaces its last, and a reached deadline clears it.
+    ///
+    /// A full queue records nothing and still answers `Pending`: delivery is
+    /// by polling, so the caller's next ask sees the deadline pass either way,
+    /// and only an `ADVANCE` count misses the wait.
+    pub fn wait(
+        &mut self,
+        clock: Clock,
+        owner: TaskEpoch,
+        deadline_ns: u64,
+    ) -> Result<WaitOutcome, ClockError> {
+        let (now, deadline, now_ns, waits) = match clock {
+            Clock::Wall => return Err(ClockError::NotWaitable),
+            Clock::Monotonic => {
+                let now = self.counter()?;
+                let deadline = MonotonicInstant(self.ns_to_ticks(deadline_ns));
+                let now_ns = self.ticks_to_ns(now.0);
+                (now, deadline, now_ns, &mut self.monotonic_waits)
+            }
+            Clock::Simulated { grant } => {
+                let simulated = self.simulated_mut(grant)?;
+                (
+                    MonotonicInstant(simulated.now_ns),
+                    MonotonicInstant(deadline_ns),
+                    simulated.now_ns,
+                    &mut simulated.waits,
+                )
+            }
+        };
+        // Due waits leave the queue before the caller's own is touched, so a
+        // wait that expired unobserved never holds a slot.
+        waits
+            .on_timer_expiry(now, |_| true)
+            .map_err(ClockError::Timer)?;
+        waits.cancel_task(owner, now).map_err(ClockError::Timer)?;
+        if now_ns >= deadline_ns {
+            return Ok(WaitOutcome::Reached { now_ns });
+        }
+        match waits.schedule_at(owner, now, deadline) {
+            Ok(_) | Err(TimerError::CapacityExhausted { .. }) => {
+                Ok(WaitOutcome::Pending { now_ns })
+            }
+            Err(error) => Err(ClockError::Timer(error)),
+        }
+    }
+
+    /// Move a simulated clock forward to `to_ns`, answering how many waits
+    /// recorded on it were released. Moving to the current reading releases
+    /// nothing new and is not an error.
+    pub fn advance(&mut self, clock: Clock, to_ns: u64) -> Result<usize, ClockError> {
+        let Clock::Simulated { grant } = clock else {
+            return Err(ClockError::NotSimulated);
+        };
+        let simulated = self.simulated_mut(grant)?;
+        if to_ns < simulated.now_ns {
+            return Err(ClockError::Backwards {
+                now_ns: simulated.now_ns,
+                requested_ns: to_ns,
+            });
+        }
+        let released = simulated
+            .waits
+            .on_timer_expiry(MonotonicInstant(to_ns), |_| true)
+            .map_err(ClockError::Timer)?;
+        simulated.now_ns = to_ns;
+        Ok(released.events.len())
+    }
+
+    /// Drop every wait `owner` left, called when its task dies so a reused
+    /// index inherits none.
+    pub fn forget(&mut self, owner: TaskEpoch) -> Result<(), ClockError> {
+        let now = self.counter()?;
+        self.monotonic_waits
+            .cancel_task(owner, now)
+            .map_err(ClockError::Timer)?;
+        for simulated in self.simulated.iter_mut().flatten() {
+            simulated
+                .waits
+                .cancel_task(owner, MonotonicInstant(simulated.now_ns))
+                .map_err(ClockError::Timer)?;
+        }
+        Ok(())
+    }
+
+    fn simulated(&self, grant: usize) -> Option<&SimulatedClock<CAPACITY>> {
+        self.simulated
+            .iter()
+            .flatten()
+            .find(|simulated| simulated.grant == grant)
+    }
+
+    /// `grant`'s simulated clock, taking a free slot for it at zero if it has
+    /// none yet.
+    fn simulated_mut(&mut self, grant: usize) -> Result<&mut SimulatedClock<CAPACITY>, ClockError> {
+        let index = match self.simulated.iter().position(|slot| {
+            slot.as_ref()
+                .is_some_and(|simulated| simulated.grant == grant)
+        }) {
+            Some(index) => index,
+            None => {
+                let index = self
+                    .simulated
+                    .iter()
+                    .position(Option::is_none)
+                    .ok_or(ClockError::SimulatedExhausted { grant })?;
+                self.simulated[index] = Some(SimulatedClock {
+                    grant,
+                    now_ns: 0,
+                    waits: TimerScheduler::new(),
+                });
+                index
+            }
+        };
+        self.simulated[index]
+            .as_mut()
+            .ok_or(ClockError::SimulatedExhausted { grant })
+    }
+
+    fn counter(&mut self) -> Result<MonotonicInstant, ClockError> {
+        self.platform
+            .monotonic_now()
+            .map_err(|_| ClockError::Platform)
+    }
+
+    fn ticks_to_ns(&self, ticks: u64) -> u64 {
+        let ns = ticks as u128 * NANOS_PER_SECOND / self.frequency_hz as u128;
+        u64::try_from(ns).unwrap_or(u64::MAX)
+    }
+
+    /// Rounded up, so a deadline is never reported reached a tick early.
+    fn ns_to_ticks(&self, ns: u64) -> u64 {
+        let ticks = (ns as u128 * self.frequency_hz as u128).div_ceil(NANOS_PER_SECOND);
+        u64::try_from(ticks).unwrap_or(u64::MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: TaskEpoch = TaskEpoch::new(1, 0);
+    const B: TaskEpoch = TaskEpoch::new(2, 0);
+    /// 62.5 MHz, QEMU virt's counter rate: 16 ns a tick.
+    const FREQUENCY_HZ: u64 = 62_500_000;
+
+    struct FakeCounter(u64);
+
+    impl PlatformTimer for FakeCounter {
+        type Error = ();
+
+        fn monotonic_now(&mut self) -> Result<MonotonicInstant, Self::Error> {
+            Ok(MonotonicInstant(self.0))
+        }
+
+        fn program_deadline(&mut self, _: MonotonicInstant) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn disarm_timer(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn acknowledge_timer_irq(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    fn clocks(ticks: u64, wall: Option<WallEpoch>) -> Clocks<FakeCounter, 4> {
+        Clocks::new(FakeCounter(ticks), FREQUENCY_HZ, wall)
+    }
+
+    const SIMULATED: Clock = Clock::Simulated { grant: 7 };
+
+    #[test]
+    fn the_kinds_read_their_own_time() {
+        let epoch = WallEpoch {
+            unix_ns: 1_700_000_000_000_000_000,
+            counter: MonotonicInstant(1_000),
+        };
+        let mut clocks = clocks(1_000 + FREQUENCY_HZ, Some(epoch));
+        assert_eq!(
+            clocks.now(Clock::Monotonic),
+            Ok((1_000 + FREQUENCY_HZ) * 16)
+        );
+        assert_eq!(clocks.now(Clock::Wall), Ok(epoch.unix_ns + 1_000_000_000));
+        assert_eq!(clocks.now(SIMULATED), Ok(0));
+    }
+
+    #[test]
+    fn an_unanchored_wall_clock_is_refused_and_no_clock_waits_on_it() {
+        let mut clocks = clocks(0, None);
+        assert_eq!(clocks.now(Clock::Wall), Err(ClockError::Unanchored));
+        assert_eq!(clocks.wait(Clock::Wall, A, 0), Err(ClockError::NotWaitable));
+        assert_eq!(
+            clocks.advance(Clock::Monotonic, 1),
+            Err(ClockError::NotSimulated)
+        );
+    }
+
+    #[test]
+    fn a_monotonic_wait_is_pending_until_its_deadline_and_replaced_by_the_next() {
+        let mut clocks = clocks(100, None);
+        assert_eq!(
+            clocks.wait(Clock::Monotonic, A, 3_200),
+            Ok(WaitOutcome::Pending { now_ns: 1_600 })
+        );
+        assert_eq!(
+            clocks.wait(Clock::Monotonic, A, 4_800),
+            Ok(WaitOutcome::Pending { now_ns: 1_600 })
+        );
+        assert_eq!(clocks.pending(Clock::Monotonic), 1);
+        clocks.platform.0 = 300;
+        assert_eq!(
+            clocks.wait(Clock::Monotonic, A, 4_800),
+            Ok(WaitOutcome::Reached { now_ns: 4_800 })
+        );
+        assert_eq!(clocks.pending(Clock::Monotonic), 0);
+    }
+
+    #[test]
+    fn only_an_advance_moves_simulated_time_and_never_backwards() {
+        let mut clocks = clocks(1_000_000, None);
+        assert_eq!(
+            clocks.wait(SIMULATED, A, 500),
+            Ok(WaitOutcome::Pending { now_ns: 0 })
+        );
+        assert_eq!(
+            clocks.wait(SIMULATED, B, 900),
+            Ok(WaitOutcome::Pending { now_ns: 0 })
+        );
+        assert_eq!(clocks.advance(SIMULATED, 500), Ok(1));
+        assert_eq!(clocks.now(SIMULATED), Ok(500));
+        assert_eq!(
+            clocks.wait(SIMULATED, A, 500),
+            Ok(WaitOutcome::Reached { now_ns: 500 })
+        );
+        assert_eq!(
+            clocks.advance(SIMULATED, 499),
+            Err(ClockError::Backwards {
+                now_ns: 500,
+                requested_ns: 499
+            })
+        );
+        assert_eq!(clocks.advance(SIMULATED, 500), Ok(0));
+        assert_eq!(clocks.pending(SIMULATED), 1);
+    }
+
+    #[test]
+    fn each_simulated_grant_is_its_own_clock() {
+        let mut clocks = clocks(0, None);
+        let other = Clock::Simulated { grant: 8 };
+        clocks.wait(other, A, 100).unwrap();
+        assert_eq!(clocks.advance(SIMULATED, 4_000), Ok(0));
+        assert_eq!(clocks.now(other), Ok(0));
+        assert_eq!(clocks.pending(other), 1);
+        // Behind the first clock, and still forward on its own.
+        assert_eq!(clocks.advance(other, 1_000), Ok(1));
+        assert_eq!(clocks.now(SIMULATED), Ok(4_000));
+
+        for grant in 9..11 {
+            clocks.advance(Clock::Simulated { grant }, 1).unwrap();
+        }
+        assert_eq!(
+            clocks.advance(Clock::Simulated { grant: 11 }, 1),
+            Err(ClockError::SimulatedExhausted { grant: 11 })
+        );
+        // A clock that is only read takes no slot.
+        assert_eq!(clocks.now(Clock::Simulated { grant: 11 }), Ok(0));
+    }
+
+    #[test]
+    fn a_full_queue_still_answers_pending() {
+        let mut clocks = clocks(0, None);
+        for task in 0..5 {
+            assert_eq!(
+                clocks.wait(SIMULATED, TaskEpoch::new(task, 0), 10),
+                Ok(WaitOutcome::Pending { now_ns: 0 })
+            );
+        }
+        assert_eq!(clocks.pending(SIMULATED), 4);
+        assert_eq!(clocks.advance(SIMULATED, 10), Ok(4));
+    }
+
+    #[test]
+    fn a_dead_task_leaves_no_wait_behind() {
+        let mut clocks = clocks(0, None);
+        clocks.wait(Clock::Monotonic, A, 1_000).unwrap();
+        clocks.wait(SIMULATED, A, 1_000).unwrap();
+        clocks.wait(SIMULATED, B, 1_000).unwrap();
+        clocks.forget(A).unwrap();
+        assert_eq!(clocks.pending(Clock::Monotonic), 0);
+        assert_eq!(clocks.pending(SIMULATED), 1);
+        assert_eq!(clocks.advance(SIMULATED, 1_000), Ok(1));
+    }
+}
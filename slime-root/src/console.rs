@@ -37,7 +37,7 @@ use crate::ipc::{self, IpcError, Response};
 use crate::task::{MAX_TASKS, TaskId, TaskTable};
 use crate::transfer_window::{self, Window, WindowTable, descriptor_thread};
 // B59: the capability-rights vocabulary is generated from
-// `contracts/generation/v5/schema.zt`; these were local copies of the same
+// `contracts/generation/v6/schema.zt`; these were local copies of the same
 // bit numbering.
 use boot_contracts::generation::{RIGHT_BLOCK_READ, RIGHT_BLOCK_WRITE};
 
@@ -99,9 +99,14 @@ pub struct ConsoleContext {
     /// variant launches no components, so it keeps its own direct access and
     /// never constructs this thread.
     pub devices: *mut crate::device::BlockDevices,
-    /// The namespace roots (B45). Owned here because inspect and commit are
-    /// the only writers and both live here.
-    pub namespaces: *mut crate::directory::Namespaces,
+    /// The namespace roots (B45). Committed only here, because commit is the
+    /// only writer; the main dispatcher reads a root to check a snapshot's
+    /// expected one, which is why each root is published rather than plain.
+    pub namespaces: *const crate::directory::Namespaces,
+    /// The pinned snapshot roots, read-only here so inspect can answer a
+    /// snapshot. Pinning and release stay on the owning dispatcher for the
+    /// same reason derivation does: both follow its capability tables.
+    pub snapshots: *const crate::directory::SnapshotTable,
     /// The interned scopes, read-only here. Directory derivation remains on the
     /// owning dispatcher because it mutates both this service state and one
     /// task's authority table.
@@ -173,7 +178,8 @@ pub unsafe fn serve(context: &ConsoleContext) -> ! {
     let tasks = unsafe { &*context.tasks };
     let input = unsafe { &mut *context.input };
     let devices = unsafe { &mut *context.devices };
-    let namespaces = unsafe { &mut *context.namespaces };
+    let namespaces = unsafe { &*context.namespaces };
+    let snapshots = unsafe { &*context.snapshots };
     let scopes = unsafe { &*context.scopes };
 
     // Both endpoints are bound to one notification so a single blocking wait
@@ -211,6 +217,7 @@ pub unsafe fn serve(context: &ConsoleContext) -> ! {
                 pending = Some(crate::directory::serve_directory_inspect(
                     tasks,
                     namespaces,
+                    snapshots,
                     scopes,
                     window,
                     &context.scratch,
@@ -223,6 +230,7 @@ pub unsafe fn serve(context: &ConsoleContext) -> ! {
                 pending = Some(crate::directory::serve_directory_commit(
                     tasks,
                     namespaces,
+                    snapshots,
                     scopes,
                     window,
                     &context.scratch,
@@ -418,11 +418,12 @@ impl DmaPage {
 
 /// Block devices this cutover brings up, in stable physical-address order.
 ///
-/// Two, because M6.7 transfers a generation from a source device to a receiver
-/// and needs both at once. The bound is a table size rather than a policy: a
+/// Three: M6.7 transfers a generation from a source device to a receiver and
+/// needs both at once, and the generation plane's manager holds its store, its
+/// boot store and an export archive together. The bound is a table size rather than a policy: a
 /// generation grants authority over a device by index, and an index the boot
 /// did not fill is authority the root cannot back.
-pub const MAX_BLOCK_DEVICES: usize = 2;
+pub const MAX_BLOCK_DEVICES: usize = 3;
 
 /// The brought-up devices.
 pub struct BlockDevices {
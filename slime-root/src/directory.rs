@@ -1,6 +1,7 @@
 //! Directory namespace state and interned scopes (B45).
 //!
-//! Namespace roots and scope interning are owned here. Callers resolve a
+//! Namespace roots, pinned snapshot roots, and scope interning are owned here.
+//! Callers resolve a
 //! task's typed directory authority through [`crate::task::TaskTable`]; this
 //! module keeps no second authority database.
 
@@ -10,8 +11,9 @@ use crate::ipc::{IpcError, Response};
 use crate::task::{TaskId, TaskTable};
 use crate::transfer_window;
 use boot_contracts::generation::RIGHT_TRANSFER;
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering, fence};
 // B59: the capability-rights vocabulary is generated from
-// `contracts/generation/v5/schema.zt`; these were local copies of the same
+// `contracts/generation/v6/schema.zt`; these were local copies of the same
 // bit numbering.
 use boot_contracts::generation::{
     RIGHT_DIRECTORY_DERIVE, RIGHT_DIRECTORY_LIST, RIGHT_DIRECTORY_READ, RIGHT_DIRECTORY_WRITE,
@@ -27,7 +29,10 @@ pub struct ScopeId(u16);
 
 pub const MAX_DIRECTORY_PATH: usize = 128;
 pub const MAX_DIRECTORY_DEPTH: usize = 8;
-pub const MAX_SCOPES: usize = 64;
+/// Interned scopes are never released, and under fs/v2 every request names its
+/// target by deriving a scope for it, so this bounds the distinct paths a boot
+/// can touch rather than the views alive at once.
+pub const MAX_SCOPES: usize = 256;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct DirectoryScope {
@@ -178,8 +183,80 @@ pub const DIRECTORY_FIXTURE_ROOT: [u8; 32] = [
     0xf1, 0x6d, 0x89, 0xa8, 0x77, 0x0a, 0xa1, 0x08, 0x05, 0x51, 0xbd, 0x84, 0xf6, 0x6b, 0x0f, 0xf2,
 ];
 
+/// A 32-byte root one dispatcher writes and the other reads (M6.3).
+///
+/// The two root dispatchers run on separate threads, so a root cannot be a
+/// plain array one of them mutates through a shared pointer. This is a
+/// sequence lock: the single writer makes the sequence odd, stores the words,
+/// and makes it even again; a reader copies the words and keeps the copy only
+/// if it saw the same even sequence on both sides. Both threads run at one
+/// priority, so a reader that catches a store half-done spins for at most the
+/// writer's remaining timeslice.
+pub struct PublishedRoot {
+    sequence: AtomicU32,
+    words: [AtomicU64; 4],
+}
+
+impl PublishedRoot {
+    pub const fn new(root: [u8; 32]) -> Self {
+        const fn word(root: &[u8; 32], index: usize) -> AtomicU64 {
+            let mut bytes = [0u8; 8];
+            let mut byte = 0;
+            while byte < 8 {
+                bytes[byte] = root[index * 8 + byte];
+                byte += 1;
+            }
+            AtomicU64::new(u64::from_le_bytes(bytes))
+        }
+        Self {
+            sequence: AtomicU32::new(0),
+            words: [
+                word(&root, 0),
+                word(&root, 1),
+                word(&root, 2),
+                word(&root, 3),
+            ],
+        }
+    }
+
+    pub fn load(&self) -> [u8; 32] {
+        loop {
+            let before = self.sequence.load(Ordering::Acquire);
+            if before & 1 != 0 {
+                core::hint::spin_loop();
+                continue;
+            }
+            let mut root = [0u8; 32];
+            for (chunk, word) in root.chunks_exact_mut(8).zip(&self.words) {
+                chunk.copy_from_slice(&word.load(Ordering::Relaxed).to_le_bytes());
+            }
+            fence(Ordering::Acquire);
+            if self.sequence.load(Ordering::Relaxed) == before {
+                return root;
+            }
+        }
+    }
+
+    /// Publish `root`. Every root has exactly one thread that stores it.
+    fn store(&self, root: [u8; 32]) {
+        let sequence = self.sequence.load(Ordering::Relaxed);
+        self.sequence
+            .store(sequence.wrapping_add(1), Ordering::Relaxed);
+        fence(Ordering::Release);
+        for (chunk, word) in root.chunks_exact(8).zip(&self.words) {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(chunk);
+            word.store(u64::from_le_bytes(bytes), Ordering::Relaxed);
+        }
+        self.sequence
+            .store(sequence.wrapping_add(2), Ordering::Release);
+    }
+}
+
+/// The live namespace roots. Committed only by the console dispatcher and read
+/// by both, so each root is published rather than shared as plain bytes.
 pub struct Namespaces {
-    roots: [[u8; 32]; MAX_NAMESPACES],
+    roots: [PublishedRoot; MAX_NAMESPACES],
 }
 
 /// Namespaces this cutover supports. One, and the resource carries an index so
@@ -195,12 +272,12 @@ impl Default for Namespaces {
 impl Namespaces {
     pub const fn new() -> Self {
         Self {
-            roots: [DIRECTORY_FIXTURE_ROOT; MAX_NAMESPACES],
+            roots: [const { PublishedRoot::new(DIRECTORY_FIXTURE_ROOT) }; MAX_NAMESPACES],
         }
     }
 
     fn root(&self, namespace: u32) -> Option<[u8; 32]> {
-        self.roots.get(namespace as usize).copied()
+        self.roots.get(namespace as usize).map(PublishedRoot::load)
     }
 
     /// Replace a namespace root, but only if it still holds `expected`.
@@ -210,16 +287,133 @@ impl Namespaces {
     /// stale parent and installing it would silently discard the other's work.
     /// A failed compare is `false`, not an error: the caller re-reads and
     /// retries, which is the ordinary path rather than a fault.
-    fn commit(&mut self, namespace: u32, expected: [u8; 32], new: [u8; 32]) -> Option<bool> {
-        let slot = self.roots.get_mut(namespace as usize)?;
-        if *slot != expected {
+    ///
+    /// Takes `&self` because the root is published, but the compare and the
+    /// store are only atomic together on the one thread that commits: the
+    /// console dispatcher.
+    fn commit(&self, namespace: u32, expected: [u8; 32], new: [u8; 32]) -> Option<bool> {
+        let slot = self.roots.get(namespace as usize)?;
+        if slot.load() != expected {
             return Some(false);
         }
-        *slot = new;
+        slot.store(new);
         Some(true)
     }
 }
 
+/// Namespace identities at and above this name a pinned snapshot, not a live
+/// namespace. The bit keeps the two spaces disjoint without widening the
+/// capability: a snapshot is an ordinary Directory capability whose namespace
+/// happens to index [`SnapshotTable`], so every path that resolves a directory
+/// capability already handles it.
+pub const SNAPSHOT_NAMESPACE_BASE: u32 = 1 << 31;
+
+/// Distinct roots pinned at once. Interned, so repeated snapshots of an
+/// unchanged root cost nothing, and released once no capability names them.
+pub const MAX_SNAPSHOTS: usize = 32;
+
+/// Pinned roots (M6.3).
+///
+/// Pinned and released only by the root dispatcher, which serves `DIRECTORY
+/// SNAPSHOT` beside derive and sees every capability drop, and read by the
+/// console dispatcher to inspect a snapshot. Each root is published, so the
+/// console never copies one half-written; and an entry is only written while
+/// no capability names it, so the console can only be asked about it once
+/// the reply delivering that capability has ordered the write.
+pub struct SnapshotTable {
+    roots: [PublishedRoot; MAX_SNAPSHOTS],
+    held: [AtomicBool; MAX_SNAPSHOTS],
+}
+
+impl SnapshotTable {
+    pub const fn new() -> Self {
+        Self {
+            roots: [const { PublishedRoot::new([0; 32]) }; MAX_SNAPSHOTS],
+            held: [const { AtomicBool::new(false) }; MAX_SNAPSHOTS],
+        }
+    }
+
+    fn index(namespace: u32) -> Option<usize> {
+        let index = namespace.checked_sub(SNAPSHOT_NAMESPACE_BASE)? as usize;
+        (index < MAX_SNAPSHOTS).then_some(index)
+    }
+
+    fn root(&self, namespace: u32) -> Option<[u8; 32]> {
+        let index = Self::index(namespace)?;
+        self.held[index]
+            .load(Ordering::Acquire)
+            .then(|| self.roots[index].load())
+    }
+
+    /// How many roots are pinned now.
+    pub fn len(&self) -> usize {
+        self.held
+            .iter()
+            .filter(|held| held.load(Ordering::Relaxed))
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The namespace identity pinning `root`, interning it on first use.
+    fn pin(&self, root: [u8; 32]) -> Option<u32> {
+        let pinned = |index: &usize| self.held[*index].load(Ordering::Relaxed);
+        let index = match (0..MAX_SNAPSHOTS)
+            .filter(pinned)
+            .find(|index| self.roots[*index].load() == root)
+        {
+            Some(index) => index,
+            None => {
+                let free = (0..MAX_SNAPSHOTS).find(|index| !pinned(index))?;
+                self.roots[free].store(root);
+                self.held[free].store(true, Ordering::Release);
+                free
+            }
+        };
+        Some(SNAPSHOT_NAMESPACE_BASE + index as u32)
+    }
+
+    /// Unpin every root no capability names any more, answering how many.
+    ///
+    /// `named` is the caller's census of its capability tables: a pin is
+    /// released only when it answers `false`, so a snapshot stays resolvable
+    /// for exactly as long as something can still inspect it. Run on the
+    /// pinning dispatcher, which is the only writer of the tables it asks.
+    pub fn release_unnamed(&self, named: impl Fn(u32) -> bool) -> usize {
+        let mut released = 0;
+        for (index, held) in self.held.iter().enumerate() {
+            let namespace = SNAPSHOT_NAMESPACE_BASE + index as u32;
+            if held.load(Ordering::Relaxed) && !named(namespace) {
+                held.store(false, Ordering::Release);
+                released += 1;
+            }
+        }
+        released
+    }
+}
+
+impl Default for SnapshotTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The root a directory capability's namespace currently names: the live root
+/// for a namespace, the pinned one for a snapshot.
+fn current_root(
+    namespaces: &Namespaces,
+    snapshots: &SnapshotTable,
+    namespace: u32,
+) -> Option<[u8; 32]> {
+    if namespace >= SNAPSHOT_NAMESPACE_BASE {
+        snapshots.root(namespace)
+    } else {
+        namespaces.root(namespace)
+    }
+}
+
 /// Answer `DirectoryInspect`: the namespace root this capability sees, and the
 /// scope it sees it through.
 ///
@@ -229,10 +423,13 @@ impl Namespaces {
 /// rather than discovering it at commit time.
 ///
 /// The reply is the 32-byte root followed by the scope path, written through
-/// the caller's transfer window because a scope can exceed a message.
+/// the caller's transfer window because a scope can exceed a message. A
+/// snapshot answers its pinned root, which never moves.
+#[allow(clippy::too_many_arguments)]
 pub fn serve_directory_inspect<const TASKS: usize>(
     tasks: &TaskTable<TASKS>,
     namespaces: &Namespaces,
+    snapshots: &SnapshotTable,
     scopes: &ScopeTable,
     window: Option<transfer_window::Window>,
     scratch: &ScratchPage,
@@ -253,7 +450,7 @@ pub fn serve_directory_inspect<const TASKS: usize>(
     };
     let namespace = capability.namespace;
     let scope = capability.scope;
-    let Some(root) = namespaces.root(namespace) else {
+    let Some(root) = current_root(namespaces, snapshots, namespace) else {
         return Response::error(IpcError::BadCapability);
     };
     let path = scopes.path(scope);
@@ -353,6 +550,94 @@ pub fn serve_directory_derive<const TASKS: usize>(
     Response::success(free as i64, 0)
 }
 
+/// Answer `DirectorySnapshot`: pin the root a capability sees as a read-only
+/// capability over the same scope (M6.3).
+///
+/// The caller stages the root it read through inspect, and the pin is refused
+/// with `WouldBlock` unless that is still the root — so a snapshot always names
+/// a tree the caller actually saw, never one another writer committed a moment
+/// later. The namespace root is read here while the console dispatcher may be
+/// committing it, through the published copy, so the compare is against a
+/// root that really was live rather than half of two.
+///
+/// The rights narrow exactly as a derive's do, and `directoryWrite` is refused
+/// outright: nothing can be committed through a pinned root, and the commit
+/// handler's namespace lookup refuses a snapshot identity anyway. Resolved on
+/// `directoryRead`, because a pin is a read of the root, not a delegation.
+pub fn serve_directory_snapshot<const TASKS: usize>(
+    tasks: &mut TaskTable<TASKS>,
+    namespaces: &Namespaces,
+    snapshots: &SnapshotTable,
+    window: Option<transfer_window::Window>,
+    scratch: &ScratchPage,
+    id: TaskId,
+    words: &[sel4::Word],
+) -> Response {
+    let slot = words[0] as u32;
+    let rights = words[0] >> 32;
+    if rights == 0
+        || rights & !(RIGHTS_DIRECTORY_ALL | RIGHT_TRANSFER) != 0
+        || rights & RIGHT_DIRECTORY_WRITE != 0
+    {
+        return Response::error(IpcError::InvalidOperation);
+    }
+    let Some(transfer) = words.get(1).copied() else {
+        return Response::error(IpcError::InvalidLength);
+    };
+    let frame = match transfer_window::read_staged_array(window, transfer, words, scratch) {
+        Ok(frame) => frame,
+        Err(error) => return Response::error(error),
+    };
+    let staged = frame.bytes();
+    if staged.len() != 32 {
+        return Response::error(IpcError::InvalidLength);
+    }
+    let mut expected = [0u8; 32];
+    expected.copy_from_slice(staged);
+    let Some(table) = tasks.authority_mut(id) else {
+        return Response::error(IpcError::BadCapability);
+    };
+    let Ok(source) = table.resolve_directory(slot, RIGHT_DIRECTORY_READ) else {
+        return Response::error(IpcError::BadCapability);
+    };
+    if rights & !source.rights.bits() != 0 {
+        return Response::error(IpcError::BadCapability);
+    }
+    let Some(current) = current_root(namespaces, snapshots, source.namespace) else {
+        return Response::error(IpcError::BadCapability);
+    };
+    if current != expected {
+        sel4::debug_println!(
+            "SLIME_GRAPH directory snapshot stale task={} namespace={}",
+            id.0,
+            source.namespace,
+        );
+        return Response::error(IpcError::WouldBlock);
+    }
+    let Some(pinned) = snapshots.pin(expected) else {
+        return Response::error(IpcError::DestinationSlotsExhausted);
+    };
+    let Some(capability) = CapabilityEntry::directory(pinned, source.scope, rights) else {
+        return Response::error(IpcError::BadCapability);
+    };
+    let Some(free) = table.free_slot_from(1) else {
+        return Response::error(IpcError::DestinationSlotsExhausted);
+    };
+    if table.install(free, capability).is_err() {
+        return Response::error(IpcError::DestinationSlotsExhausted);
+    }
+    sel4::debug_println!(
+        "SLIME_GRAPH directory snapshot task={} from={slot} to={free} namespace={} pinned={pinned:#x} root={:02x}{:02x}{:02x}{:02x} rights={rights:#x}",
+        id.0,
+        source.namespace,
+        expected[0],
+        expected[1],
+        expected[2],
+        expected[3],
+    );
+    Response::success(free as i64, 0)
+}
+
 /// Answer `DirectoryCommit`: replace the namespace root, atomically.
 ///
 /// Two gates the oracle also applies, and each rules out a different attack:
@@ -365,9 +650,20 @@ pub fn serve_directory_derive<const TASKS: usize>(
 /// The staged payload is two 32-byte identities: the root the caller believes
 /// is live, and the one it built. A mismatch answers `WouldBlock`, which is the
 /// retry signal rather than a failure.
+///
+/// Restoring a snapshot is this same commit with the pinned root as the new
+/// one, and it is proven rather than claimed: `words[2]` is one more than the
+/// caller's slot holding the snapshot capability (zero for an ordinary
+/// commit), which must be an unscoped readable pin of exactly that root. A
+/// root that merely hashes to a snapshot object is not one the caller was
+/// ever shown, so without the pin a writer could install any tree it can
+/// name. The snapshot capability itself never reaches the swap, since it
+/// carries no write right and names no live namespace.
+#[allow(clippy::too_many_arguments)]
 pub fn serve_directory_commit<const TASKS: usize>(
     tasks: &TaskTable<TASKS>,
-    namespaces: &mut Namespaces,
+    namespaces: &Namespaces,
+    snapshots: &SnapshotTable,
     scopes: &ScopeTable,
     window: Option<transfer_window::Window>,
     scratch: &ScratchPage,
@@ -408,6 +704,21 @@ pub fn serve_directory_commit<const TASKS: usize>(
         );
         return Response::error(IpcError::BadCapability);
     }
+    if let Some(proof) = words.get(2).copied().filter(|proof| *proof != 0) {
+        let snapshot = (proof - 1) as u32;
+        let pinned = table
+            .resolve_directory(snapshot, RIGHT_DIRECTORY_READ)
+            .ok()
+            .filter(|pin| pin.namespace >= SNAPSHOT_NAMESPACE_BASE && scopes.is_root(pin.scope))
+            .and_then(|pin| snapshots.root(pin.namespace));
+        if pinned != Some(new) {
+            sel4::debug_println!(
+                "SLIME_GRAPH directory restore refused task={} slot={slot} namespace={namespace} snapshot={snapshot} reason=unpinned",
+                id.0,
+            );
+            return Response::error(IpcError::BadCapability);
+        }
+    }
     match namespaces.commit(namespace, expected, new) {
         Some(true) => {
             sel4::debug_println!(
@@ -449,3 +760,95 @@ impl core::fmt::Display for DisplayPath<'_> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshots_intern_roots_outside_the_namespace_space() {
+        let snapshots = SnapshotTable::new();
+        let first = snapshots.pin([1; 32]).unwrap();
+        assert!(first >= SNAPSHOT_NAMESPACE_BASE);
+        assert_eq!(snapshots.pin([1; 32]), Some(first));
+        let second = snapshots.pin([2; 32]).unwrap();
+        assert_ne!(first, second);
+        assert_eq!(snapshots.root(first), Some([1; 32]));
+        assert_eq!(snapshots.root(second), Some([2; 32]));
+        assert_eq!(snapshots.root(second + 1), None);
+        assert_eq!(snapshots.root(0), None);
+    }
+
+    #[test]
+    fn a_full_snapshot_table_refuses_only_new_roots() {
+        let snapshots = SnapshotTable::new();
+        for index in 0..MAX_SNAPSHOTS {
+            assert!(snapshots.pin([index as u8; 32]).is_some());
+        }
+        assert_eq!(snapshots.pin([0xff; 32]), None);
+        assert_eq!(
+            snapshots.pin([0; 32]),
+            Some(SNAPSHOT_NAMESPACE_BASE),
+            "an already-pinned root still resolves"
+        );
+    }
+
+    #[test]
+    fn an_unnamed_pin_is_released_and_its_entry_reused() {
+        let snapshots = SnapshotTable::new();
+        for index in 0..MAX_SNAPSHOTS {
+            assert!(snapshots.pin([index as u8; 32]).is_some());
+        }
+        let kept = SNAPSHOT_NAMESPACE_BASE + 1;
+        assert_eq!(
+            snapshots.release_unnamed(|namespace| namespace == kept),
+            MAX_SNAPSHOTS - 1
+        );
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots.root(SNAPSHOT_NAMESPACE_BASE), None);
+        assert_eq!(snapshots.root(kept), Some([1; 32]));
+        assert_eq!(snapshots.pin([0xff; 32]), Some(SNAPSHOT_NAMESPACE_BASE));
+        assert_eq!(snapshots.pin([1; 32]), Some(kept), "a named pin is kept");
+    }
+
+    #[test]
+    fn a_published_root_reads_back_what_was_stored() {
+        let root = PublishedRoot::new(DIRECTORY_FIXTURE_ROOT);
+        assert_eq!(root.load(), DIRECTORY_FIXTURE_ROOT);
+        let mut next = [0u8; 32];
+        next.iter_mut()
+            .enumerate()
+            .for_each(|(index, byte)| *byte = index as u8);
+        root.store(next);
+        assert_eq!(root.load(), next);
+    }
+
+    #[test]
+    fn a_snapshot_resolves_its_pin_and_cannot_be_committed() {
+        let namespaces = Namespaces::new();
+        let snapshots = SnapshotTable::new();
+        let pinned = snapshots.pin(DIRECTORY_FIXTURE_ROOT).unwrap();
+        assert_eq!(
+            namespaces.commit(0, DIRECTORY_FIXTURE_ROOT, [7; 32]),
+            Some(true)
+        );
+        assert_eq!(current_root(&namespaces, &snapshots, 0), Some([7; 32]));
+        assert_eq!(
+            current_root(&namespaces, &snapshots, pinned),
+            Some(DIRECTORY_FIXTURE_ROOT)
+        );
+        assert_eq!(
+            namespaces.commit(pinned, DIRECTORY_FIXTURE_ROOT, [8; 32]),
+            None
+        );
+        // Restore is an ordinary commit back to the pinned root.
+        assert_eq!(
+            namespaces.commit(0, [7; 32], DIRECTORY_FIXTURE_ROOT),
+            Some(true)
+        );
+        assert_eq!(
+            current_root(&namespaces, &snapshots, 0),
+            Some(DIRECTORY_FIXTURE_ROOT)
+        );
+    }
+}
@@ -115,7 +115,7 @@ pub struct FaultArrival {
 /// [`SupervisionTable::fault`]; a faulted thread is left suspended by the
 /// kernel, so no reply is owed.
 pub fn receive_fault(endpoint: sel4::cap::Endpoint) -> FaultArrival {
-    let (info, badge) = endpoint.recv(());
+    let (info, badge) = endpoint.recv(crate::ipc::service_reply());
     FaultArrival {
         badge,
         record: decode_fault(&info),
@@ -8,12 +8,12 @@
 use boot_contracts::component_image::{self, ComponentTargetError};
 use boot_contracts::fabric_graph::{self, FabricGraph, MAX_INTERPOSITION_HOPS};
 use boot_contracts::generation::{
-    DecodeError, Generation, Instance, InstanceBinding, KIND_BOOTSTRAP, KIND_COMPONENT,
-    KIND_RESOURCE, RIGHT_TRANSFER, ResourceQuota, Rights,
+    DecodeError, Generation, HealthProbe, Instance, InstanceBinding, KIND_BOOTSTRAP,
+    KIND_COMPONENT, KIND_RESOURCE, RIGHT_TRANSFER, ResourceQuota, Rights,
 };
 use boot_contracts::target_profile::TargetProfile;
 // B59: the capability-rights vocabulary is generated from
-// `contracts/generation/v5/schema.zt`; these were local copies of the same
+// `contracts/generation/v6/schema.zt`; these were local copies of the same
 // bit numbering.
 use boot_contracts::generation::{RIGHT_RECV, RIGHT_SEND};
 
@@ -71,6 +71,15 @@ pub enum GenerationError {
     /// to a component that will never exist, so the promise cannot be kept by
     /// any mechanism.
     UndeclaredFabricParticipant,
+    /// A required instance's health record names a route the instance could
+    /// never answer: absent from the graph, not a call route, or one the
+    /// instance does not participate in.
+    ///
+    /// Refused at admission because the alternative is a probe that can only
+    /// fail, which would roll back every boot of an otherwise sound generation.
+    UnservedHealthRoute {
+        instance: usize,
+    },
 }
 
 impl From<DecodeError> for GenerationError {
@@ -375,6 +384,55 @@ fn participants_are_declared(
     Ok(())
 }
 
+/// The index of the call route `identity` names, if `name` participates in it.
+///
+/// What a `HEALTH_PROBE_ROUTE` record must satisfy to be admitted, and the
+/// index a `ROUTE_ANSWERED` report is later matched against. Only call routes
+/// qualify: a call is the one contract whose answer the server produces, so it
+/// is the one an instance can be said to have served.
+pub fn health_route_index(
+    graph: &FabricGraph<'_>,
+    identity: &[u8; 32],
+    name: &str,
+) -> Option<usize> {
+    let index = (0..graph.route_count()).find(|index| {
+        graph
+            .route(*index)
+            .is_some_and(|route| route.route_identity == *identity)
+    })?;
+    if graph.route(index)?.contract_kind != fabric_graph::CONTRACT_KIND_CALL {
+        return None;
+    }
+    let component = fabric_graph::component_identity(name);
+    (0..graph.participant_count())
+        .filter_map(|slot| graph.participant(slot))
+        .any(|entry| entry.route_index as usize == index && entry.component_identity == component)
+        .then_some(index)
+}
+
+/// The fabric route index `probe`'s route resolves to in `generation`'s graph,
+/// or `None` when the record declares no route.
+///
+/// Admission calls this to refuse an unservable route; the service loop calls
+/// it again to seed its health table with the index `ROUTE_ANSWERED` reports
+/// are matched against.
+pub fn health_probe_route(
+    generation: &Generation<'_>,
+    probe: &HealthProbe,
+) -> Result<Option<usize>, GenerationError> {
+    let instance = generation.instance(probe.instance)?;
+    let Some(route) = probe.route else {
+        return Ok(None);
+    };
+    fabric_graph_object(generation)
+        .and_then(Result::ok)
+        .and_then(|graph| health_route_index(&graph, &route, instance.name))
+        .map(Some)
+        .ok_or(GenerationError::UnservedHealthRoute {
+            instance: probe.instance,
+        })
+}
+
 /// The component names the graph's interposition hops resolve to, in chain order.
 ///
 /// B70. The two fabric brokers each carried an `assert_declared_chain` that
@@ -504,7 +562,7 @@ impl Admission {
         generation: &Generation<'_>,
         profile: &TargetProfile,
     ) -> Result<Self, GenerationError> {
-        if !generation.is_v5() {
+        if !generation.is_v6() {
             return Err(GenerationError::UnsupportedGenerationVersion);
         }
         let executable_len = generation.executable_count();
@@ -583,7 +641,7 @@ impl Admission {
             generation.grant(index)?;
         }
         for index in 0..generation.health_count() {
-            generation.health_instance(index)?;
+            health_probe_route(generation, &generation.health_probe(index)?)?;
         }
 
         Ok(Self {
@@ -751,6 +809,14 @@ fn admit_resource_quota(quota: &ResourceQuota<'_>) -> Result<(), GenerationError
             quota.endpoint_count,
             2 + crate::task::CHILD_NATIVE_REGION_SLOTS as u32,
         ),
+        // Heap pages, counted from the reserve. Bounded by the window the
+        // child VSpace reserves, whose frames the task's arena records beside
+        // the image's; a plan past it would build a task that cannot hold them.
+        (
+            "heap",
+            u32::try_from(quota.dynamic_reserve_bytes / 4096).unwrap_or(u32::MAX),
+            crate::child_vspace::MAX_HEAP_PAGES as u32,
+        ),
     ] {
         if declared > limit {
             return Err(GenerationError::QuotaExceedsCeiling {
@@ -768,13 +834,14 @@ fn admit_resource_quota(quota: &ResourceQuota<'_>) -> Result<(), GenerationError
 mod tests {
     use super::{
         Authority, FabricGraph, GenerationError, PayloadFormat, RIGHT_RECV, RIGHT_SEND,
-        fabric_graph_is_satisfiable, participants_are_declared, resolve_interposition_hops,
+        fabric_graph_is_satisfiable, health_route_index, participants_are_declared,
+        resolve_interposition_hops,
     };
     use boot_contracts::component_image::wire;
     use boot_contracts::generation::{RIGHT_TRANSFER, ResourceQuota};
 
     use super::admit_resource_quota;
-    use crate::child_vspace::{MAX_CHILD_IMAGE_PAGES, MAX_CHILD_THREADS};
+    use crate::child_vspace::{MAX_CHILD_IMAGE_PAGES, MAX_CHILD_THREADS, MAX_HEAP_PAGES};
 
     /// The quota a single-threaded process declares: one CNode, one VSpace,
     /// one TCB, one IPC-buffer frame, two endpoints, and a full CNode of
@@ -821,7 +888,7 @@ mod tests {
         // that admitted one over would let a graph activate and then fail to
         // place a capability, with children already running -- which is the
         // failure admission exists to prevent.
-        let cases: [(&str, fn(&mut ResourceQuota<'_>), u32, u32); 6] = [
+        let cases: [(&str, fn(&mut ResourceQuota<'_>), u32, u32); 7] = [
             ("cnode", |q| q.cnode_count = 2, 2, 1),
             ("tcb", |q| q.tcb_count = 3, 3, 2),
             ("endpoint", |q| q.endpoint_count = 34, 34, 33),
@@ -833,6 +900,12 @@ mod tests {
             ),
             ("vspace", |q| q.page_table_count = 2, 2, 1),
             ("cslot", |q| q.cslot_count = 129, 129, 128),
+            (
+                "heap",
+                |q| q.dynamic_reserve_bytes = (MAX_HEAP_PAGES as u64 + 1) * 4096,
+                MAX_HEAP_PAGES as u32 + 1,
+                MAX_HEAP_PAGES as u32,
+            ),
         ];
         for (class, mutate, declared, limit) in cases {
             let mut quota = single_threaded_quota();
@@ -1209,6 +1282,28 @@ mod tests {
         assert_eq!(participants_are_declared(&extra, &graph), Ok(()));
     }
 
+    /// A health route is admitted only where its instance could answer it.
+    ///
+    /// The fixture's one route is a stream, so every arm here is a refusal.
+    /// Accepting needs a call route with a client and a server, which no
+    /// hand-built graph in this module carries.
+    #[test]
+    fn a_health_route_must_be_a_call_route_its_instance_serves() {
+        use boot_contracts::fabric_graph::{
+            CONTRACT_KIND_STREAM, RELIABILITY_RELIABLE, route_identity,
+        };
+        let bytes = qos_graph(RELIABILITY_RELIABLE as u8, RELIABILITY_RELIABLE as u8);
+        let graph = FabricGraph::decode(&bytes).expect("well-formed graph");
+        let telemetry = route_identity("telemetry", &[0x11; 32], CONTRACT_KIND_STREAM);
+
+        // A participant on the route, but a stream has no answer to observe.
+        assert_eq!(health_route_index(&graph, &telemetry, QOS_PUBLISHER), None);
+        // A route the graph does not carry.
+        assert_eq!(health_route_index(&graph, &[0x5a; 32], QOS_PUBLISHER), None);
+        // A component that is on no route at all.
+        assert_eq!(health_route_index(&graph, &telemetry, "console"), None);
+    }
+
     /// C8.1 (P5.4.10): two distinct interfaces may not share one
     /// generation-local type tag.
     ///
@@ -4,18 +4,20 @@
 //! generation-global task-id-to-authority index: a caller is first resolved in
 //! [`crate::task::TaskTable`], then its own table is consulted.
 
+use crate::clock::{Clock, ClockKind};
 use crate::directory::ScopeId;
 use crate::ipc::IpcError;
 use crate::shared_buffer::{BufferHandle, LoanHandle};
 use crate::task::TaskId;
 // B59: the capability-rights vocabulary is generated from
-// `contracts/generation/v5/schema.zt`; these were local copies of the same
+// `contracts/generation/v6/schema.zt`; these were local copies of the same
 // bit numbering.
 use boot_contracts::generation::{
     RIGHT_BLOCK_READ, RIGHT_BLOCK_WRITE, RIGHT_BUFFER_CREATE, RIGHT_BUFFER_LOAN, RIGHT_BUFFER_MAP,
-    RIGHT_BUFFER_WRITE, RIGHT_DIRECTORY_DERIVE, RIGHT_DIRECTORY_LIST, RIGHT_DIRECTORY_READ,
-    RIGHT_DIRECTORY_WRITE, RIGHT_EXEC, RIGHT_INPUT_READ, RIGHT_RECV, RIGHT_SEND, RIGHT_SPAWN,
-    RIGHT_SUPERVISE, RIGHT_TRANSFER,
+    RIGHT_BUFFER_WRITE, RIGHT_CLOCK_ADVANCE, RIGHT_CLOCK_READ, RIGHT_DIRECTORY_DERIVE,
+    RIGHT_DIRECTORY_LIST, RIGHT_DIRECTORY_READ, RIGHT_DIRECTORY_WRITE, RIGHT_EXEC,
+    RIGHT_INPUT_READ, RIGHT_RECV, RIGHT_SCHEDULE, RIGHT_SEND, RIGHT_SPAWN, RIGHT_SUPERVISE,
+    RIGHT_TRANSFER,
 };
 
 /// Logical capability slots one task may hold.
@@ -69,6 +71,8 @@ rights_type!(
         | RIGHT_TRANSFER
 );
 rights_type!(InputRights, RIGHT_INPUT_READ);
+rights_type!(SchedulingControlRights, RIGHT_SCHEDULE);
+rights_type!(ClockRights, RIGHT_CLOCK_READ | RIGHT_CLOCK_ADVANCE);
 rights_type!(NativeEndpointRights, RIGHT_SEND | RIGHT_RECV);
 rights_type!(
     LoanRights,
@@ -128,6 +132,28 @@ pub struct InputCapability {
     pub rights: InputRights,
 }
 
+/// Authority to place a supervised child's threads in the scheduler.
+///
+/// Carries no ceiling of its own: the bound is the holder's declared
+/// `max_controlled_priority`, read from the generation when it is exercised,
+/// so the capability cannot carry one instance's ceiling to another. It is
+/// never transferable for the same reason.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SchedulingControlCapability {
+    pub rights: SchedulingControlRights,
+}
+
+/// Authority to read one clock, and for a simulated one, to move it.
+///
+/// Never transferable: which clocks a component may observe is a property of
+/// its generation, and a clock that travelled would make that property
+/// whatever its holders decided.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ClockCapability {
+    pub clock: Clock,
+    pub rights: ClockRights,
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct NativeEndpointCapability {
     pub rights: NativeEndpointRights,
@@ -157,6 +183,8 @@ pub enum CapabilityEntry {
     NativeEndpoint(NativeEndpointCapability),
     SharedBuffer(SharedBufferCapability),
     Loan(LoanCapability),
+    SchedulingControl(SchedulingControlCapability),
+    Clock(ClockCapability),
 }
 
 impl CapabilityEntry {
@@ -209,6 +237,27 @@ impl CapabilityEntry {
         }
     }
 
+    pub const fn scheduling_control(rights: u64) -> Option<Self> {
+        match SchedulingControlRights::from_bits(rights) {
+            Some(rights) => Some(Self::SchedulingControl(SchedulingControlCapability {
+                rights,
+            })),
+            None => None,
+        }
+    }
+
+    /// Only a simulated clock can be advanced, so `clockAdvance` on any other
+    /// kind is refused here as admission refuses it in the manifest.
+    pub const fn clock(clock: Clock, rights: u64) -> Option<Self> {
+        if rights & RIGHT_CLOCK_ADVANCE != 0 && !matches!(clock, Clock::Simulated { .. }) {
+            return None;
+        }
+        match ClockRights::from_bits(rights) {
+            Some(rights) => Some(Self::Clock(ClockCapability { clock, rights })),
+            None => None,
+        }
+    }
+
     pub const fn native_endpoint(rights: u64) -> Option<Self> {
         match NativeEndpointRights::from_bits(rights) {
             Some(rights) => Some(Self::NativeEndpoint(NativeEndpointCapability { rights })),
@@ -244,6 +293,12 @@ impl CapabilityEntry {
             Self::NativeEndpoint(_) => "endpoint",
             Self::SharedBuffer(_) => "shared-buffer",
             Self::Loan(_) => "loan",
+            Self::SchedulingControl(_) => "scheduling-control",
+            Self::Clock(cap) => match cap.clock.kind() {
+                ClockKind::Monotonic => "monotonic-clock",
+                ClockKind::Wall => "wall-clock",
+                ClockKind::Simulated => "simulated-clock",
+            },
         }
     }
 
@@ -258,6 +313,8 @@ impl CapabilityEntry {
             Self::NativeEndpoint(cap) => cap.rights.bits(),
             Self::SharedBuffer(cap) => cap.rights.bits(),
             Self::Loan(cap) => cap.rights.bits(),
+            Self::SchedulingControl(cap) => cap.rights.bits(),
+            Self::Clock(cap) => cap.rights.bits(),
         }
     }
 
@@ -272,6 +329,8 @@ impl CapabilityEntry {
             Self::NativeEndpoint(cap) => cap.rights.allows(required),
             Self::Loan(cap) => cap.rights.allows(required),
             Self::SharedBuffer(cap) => cap.rights.allows(required),
+            Self::SchedulingControl(cap) => cap.rights.allows(required),
+            Self::Clock(cap) => cap.rights.allows(required),
         }
     }
 
@@ -340,6 +399,20 @@ impl CapabilityEntry {
                 }
                 None => None,
             },
+            Self::SchedulingControl(mut cap) => match cap.rights.narrow(requested) {
+                Some(rights) => {
+                    cap.rights = rights;
+                    Some(Self::SchedulingControl(cap))
+                }
+                None => None,
+            },
+            Self::Clock(mut cap) => match cap.rights.narrow(requested) {
+                Some(rights) => {
+                    cap.rights = rights;
+                    Some(Self::Clock(cap))
+                }
+                None => None,
+            },
         }
     }
 
@@ -475,6 +548,25 @@ impl AuthorityTable {
             _ => Err(IpcError::InvalidOperation),
         }
     }
+
+    pub fn resolve_scheduling_control(
+        &self,
+        slot: u32,
+    ) -> Result<SchedulingControlCapability, IpcError> {
+        match self.get(slot) {
+            Some(CapabilityEntry::SchedulingControl(cap)) if cap.rights.allows(RIGHT_SCHEDULE) => {
+                Ok(cap)
+            }
+            _ => Err(IpcError::InvalidOperation),
+        }
+    }
+
+    pub fn resolve_clock(&self, slot: u32, required: u64) -> Result<ClockCapability, IpcError> {
+        match self.get(slot) {
+            Some(CapabilityEntry::Clock(cap)) if cap.rights.allows(required) => Ok(cap),
+            _ => Err(IpcError::InvalidOperation),
+        }
+    }
 }
 
 impl Default for AuthorityTable {
@@ -487,7 +579,9 @@ impl Default for AuthorityTable {
 mod tests {
     use super::{
         AuthorityTable, CapabilityEntry, MAX_TASK_CAPS, RIGHT_BLOCK_READ, RIGHT_BLOCK_WRITE,
+        RIGHT_CLOCK_ADVANCE, RIGHT_CLOCK_READ,
     };
+    use crate::clock::Clock;
     use crate::ipc::IpcError;
 
     #[test]
@@ -510,6 +604,31 @@ mod tests {
         assert_eq!(table.resolve_input(4), Err(IpcError::InvalidOperation));
     }
 
+    #[test]
+    fn only_a_simulated_clock_carries_advance() {
+        let both = RIGHT_CLOCK_READ | RIGHT_CLOCK_ADVANCE;
+        assert!(CapabilityEntry::clock(Clock::Monotonic, both).is_none());
+        assert!(CapabilityEntry::clock(Clock::Wall, both).is_none());
+        let simulated = Clock::Simulated { grant: 0 };
+        let mut table = AuthorityTable::new();
+        table
+            .install(
+                2,
+                CapabilityEntry::clock(simulated, RIGHT_CLOCK_READ).unwrap(),
+            )
+            .unwrap();
+        table
+            .install(3, CapabilityEntry::clock(simulated, both).unwrap())
+            .unwrap();
+        assert!(table.resolve_clock(2, RIGHT_CLOCK_READ).is_ok());
+        assert_eq!(
+            table.resolve_clock(2, RIGHT_CLOCK_ADVANCE),
+            Err(IpcError::InvalidOperation)
+        );
+        assert!(table.resolve_clock(3, RIGHT_CLOCK_ADVANCE).is_ok());
+        assert!(!table.get(3).unwrap().is_transferable());
+    }
+
     #[test]
     fn allocation_stays_bounded_and_non_overwriting() {
         let mut table = AuthorityTable::new();
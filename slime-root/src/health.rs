@@ -0,0 +1,417 @@
+//! Health probes: what a generation declared healthy means, and what the boot
+//! observed.
+//!
+//! Before generation v6 the only question asked of a pending generation was
+//! whether every required instance was running or had exited cleanly, and the
+//! answer lived in the service loop as a count. A generation now declares its
+//! probes per required instance — running, running for a window of ticks
+//! without faulting, and answering a named call route — and this table folds
+//! the loop's observations into one [`HealthVerdict`] the root persists.
+//!
+//! Pure bookkeeping: `main.rs` reports starts, exits, faults, route answers,
+//! and ticks, and asks for the verdict each iteration. A route answer is the
+//! route's broker's report that it carried a reply from the server, never the
+//! server's own word about itself. A verdict is final once
+//! reached; the first failed probe decides a failure, and a pass needs every
+//! declared probe.
+
+use boot_contracts::bootstate::{HealthOutcome, HealthVerdict};
+use boot_contracts::generation::{
+    HEALTH_PROBE_NO_FAULT, HEALTH_PROBE_ROUTE, HEALTH_PROBE_RUNNING, HealthProbe,
+    MAX_HEALTH_INSTANCES,
+};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HealthError {
+    /// More records than a generation may declare.
+    TooManyProbes,
+    /// A second record for one instance. The decoder admits a sorted, unique
+    /// table, so reaching this means the table was fed twice.
+    DuplicateInstance(usize),
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    probe: HealthProbe,
+    /// The fabric route index the `ROUTE` probe resolved to at admission.
+    route_index: Option<usize>,
+    /// The tick the instance was first seen live.
+    started_at: Option<u64>,
+    /// Probe bits decided so far, each either passed or failed.
+    passed: u32,
+    failed: u32,
+}
+
+impl Entry {
+    fn decided(&self) -> u32 {
+        self.passed | self.failed
+    }
+
+    fn pass(&mut self, probe: u32) {
+        if self.probe.declares(probe) && self.decided() & probe == 0 {
+            self.passed |= probe;
+        }
+    }
+
+    fn fail(&mut self, probe: u32) {
+        if self.probe.declares(probe) && self.decided() & probe == 0 {
+            self.failed |= probe;
+        }
+    }
+}
+
+/// The declared probes of one boot's generation and what each has observed.
+pub struct HealthTable {
+    entries: [Option<Entry>; MAX_HEALTH_INSTANCES],
+    len: usize,
+    ticks: u64,
+    /// The first failure, in the order it was observed.
+    first_failure: Option<(usize, u32)>,
+}
+
+impl HealthTable {
+    pub const fn new() -> Self {
+        Self {
+            entries: [None; MAX_HEALTH_INSTANCES],
+            len: 0,
+            ticks: 0,
+            first_failure: None,
+        }
+    }
+
+    /// Declare one instance's probes. `route_index` is what admission resolved
+    /// the record's route to, and must be present exactly when it declares one.
+    pub fn declare(
+        &mut self,
+        probe: HealthProbe,
+        route_index: Option<usize>,
+    ) -> Result<(), HealthError> {
+        if self.entry(probe.instance).is_some() {
+            return Err(HealthError::DuplicateInstance(probe.instance));
+        }
+        let slot = self
+            .entries
+            .get_mut(self.len)
+            .ok_or(HealthError::TooManyProbes)?;
+        *slot = Some(Entry {
+            probe,
+            route_index,
+            started_at: None,
+            passed: 0,
+            failed: 0,
+        });
+        self.len += 1;
+        Ok(())
+    }
+
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub const fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    /// Whether `instance` declared any probe.
+    pub fn declares(&self, instance: usize) -> bool {
+        self.entry(instance).is_some()
+    }
+
+    /// `instance` has a task. Passes `RUNNING` and starts its fault window.
+    pub fn started(&mut self, instance: usize) {
+        let ticks = self.ticks;
+        if let Some(entry) = self.entry_mut(instance) {
+            entry.started_at.get_or_insert(ticks);
+            entry.pass(HEALTH_PROBE_RUNNING);
+        }
+    }
+
+    /// `instance` exited. A clean exit completes the instance: it ran, and it
+    /// can no longer fault. Any exit before answering its route fails `ROUTE`,
+    /// since nothing will answer it now.
+    pub fn exited(&mut self, instance: usize, status: i64) {
+        let Some(entry) = self.entry_mut(instance) else {
+            return;
+        };
+        if status == 0 {
+            entry.pass(HEALTH_PROBE_RUNNING);
+            entry.pass(HEALTH_PROBE_NO_FAULT);
+            entry.fail(HEALTH_PROBE_ROUTE);
+        } else {
+            entry.fail(HEALTH_PROBE_RUNNING);
+            entry.fail(HEALTH_PROBE_NO_FAULT);
+            entry.fail(HEALTH_PROBE_ROUTE);
+        }
+        self.note_failure(instance);
+    }
+
+    /// `instance` faulted. Every probe it has not already passed fails.
+    pub fn faulted(&mut self, instance: usize) {
+        let Some(entry) = self.entry_mut(instance) else {
+            return;
+        };
+        entry.fail(HEALTH_PROBE_RUNNING);
+        entry.fail(HEALTH_PROBE_NO_FAULT);
+        entry.fail(HEALTH_PROBE_ROUTE);
+        self.note_failure(instance);
+    }
+
+    /// A reply was carried on `route_index`. Passes `ROUTE` for every record
+    /// that declared that route, and returns whether any did; a route no
+    /// record declares changes nothing.
+    pub fn route_answered(&mut self, route_index: usize) -> bool {
+        let mut credited = false;
+        for entry in self.entries.iter_mut().flatten() {
+            if entry.route_index == Some(route_index) {
+                entry.pass(HEALTH_PROBE_ROUTE);
+                credited = true;
+            }
+        }
+        credited
+    }
+
+    /// One service tick. Passes every `NO_FAULT` window that has now elapsed.
+    pub fn tick(&mut self) {
+        self.ticks += 1;
+        let ticks = self.ticks;
+        for entry in self.entries.iter_mut().flatten() {
+            if let Some(started) = entry.started_at
+                && ticks - started >= u64::from(entry.probe.fault_window_ticks)
+            {
+                entry.pass(HEALTH_PROBE_NO_FAULT);
+            }
+        }
+    }
+
+    /// The boot is over without every probe decided: whatever is still open
+    /// failed, because it never got the chance to pass.
+    pub fn expire(&mut self) {
+        for index in 0..self.len {
+            let Some(entry) = self.entries[index].as_mut() else {
+                continue;
+            };
+            let open = entry.probe.probes & !entry.decided();
+            if open != 0 {
+                entry.failed |= open;
+                let instance = entry.probe.instance;
+                self.note_failure(instance);
+            }
+        }
+    }
+
+    /// The verdict, once one is reached: `Failed` as soon as any probe fails,
+    /// `Passed` once every declared probe has passed, `None` while any is
+    /// still open. A table with no probes reaches no verdict.
+    pub fn verdict(&self, generation: [u8; 32], release_sequence: u64) -> Option<HealthVerdict> {
+        let mut probe_count = 0;
+        let mut passed = 0;
+        let mut failed = 0;
+        for entry in self.entries.iter().flatten() {
+            probe_count += entry.probe.count();
+            passed += entry.passed.count_ones();
+            failed += entry.failed.count_ones();
+        }
+        let outcome = match self.first_failure {
+            Some(_) => HealthOutcome::Failed,
+            None if probe_count != 0 && passed == probe_count => HealthOutcome::Passed,
+            None => return None,
+        };
+        Some(HealthVerdict {
+            outcome,
+            generation,
+            release_sequence,
+            probe_count,
+            ticks: self.ticks,
+            passed,
+            failed,
+            failed_instance: self.first_failure.map(|(instance, _)| instance as u32),
+            failed_probe: self.first_failure.map_or(0, |(_, probe)| probe),
+        })
+    }
+
+    fn note_failure(&mut self, instance: usize) {
+        if self.first_failure.is_some() {
+            return;
+        }
+        if let Some(entry) = self.entry(instance)
+            && entry.failed != 0
+        {
+            // The lowest failed bit: `RUNNING` before `NO_FAULT` before
+            // `ROUTE`, the order a reader would check them in.
+            let probe = entry.failed & entry.failed.wrapping_neg();
+            self.first_failure = Some((instance, probe));
+        }
+    }
+
+    fn entry(&self, instance: usize) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .flatten()
+            .find(|entry| entry.probe.instance == instance)
+    }
+
+    fn entry_mut(&mut self, instance: usize) -> Option<&mut Entry> {
+        self.entries
+            .iter_mut()
+            .flatten()
+            .find(|entry| entry.probe.instance == instance)
+    }
+}
+
+impl Default for HealthTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HealthError, HealthTable};
+    use boot_contracts::bootstate::HealthOutcome;
+    use boot_contracts::generation::{
+        HEALTH_PROBE_NO_FAULT, HEALTH_PROBE_ROUTE, HEALTH_PROBE_RUNNING, HealthProbe,
+    };
+
+    const GENERATION: [u8; 32] = [0x42; 32];
+
+    fn running(instance: usize) -> HealthProbe {
+        HealthProbe {
+            instance,
+            probes: HEALTH_PROBE_RUNNING,
+            fault_window_ticks: 0,
+            route: None,
+        }
+    }
+
+    #[test]
+    fn running_alone_passes_when_every_instance_is_live_or_completed() {
+        // The v5 rule, which a v6 generation declaring nothing more keeps.
+        let mut table = HealthTable::new();
+        table.declare(running(0), None).unwrap();
+        table.declare(running(3), None).unwrap();
+        table.started(0);
+        assert_eq!(table.verdict(GENERATION, 2), None);
+        table.exited(3, 0);
+        let verdict = table.verdict(GENERATION, 2).expect("every probe passed");
+        assert_eq!(verdict.outcome, HealthOutcome::Passed);
+        assert!(verdict.promotes(GENERATION, 2));
+        assert_eq!((verdict.probe_count, verdict.passed), (2, 2));
+    }
+
+    #[test]
+    fn a_fault_inside_the_window_fails_and_names_the_probe() {
+        let mut table = HealthTable::new();
+        let probe = HealthProbe {
+            probes: HEALTH_PROBE_RUNNING | HEALTH_PROBE_NO_FAULT,
+            fault_window_ticks: 3,
+            ..running(1)
+        };
+        table.declare(probe, None).unwrap();
+        table.started(1);
+        table.tick();
+        table.tick();
+        assert_eq!(table.verdict(GENERATION, 2), None);
+        table.faulted(1);
+        let verdict = table.verdict(GENERATION, 2).expect("a failure decides");
+        assert_eq!(verdict.outcome, HealthOutcome::Failed);
+        assert_eq!(verdict.failed_instance, Some(1));
+        assert_eq!(verdict.failed_probe, HEALTH_PROBE_NO_FAULT);
+        // Running had already passed; the fault does not take it back.
+        assert_eq!((verdict.passed, verdict.failed), (1, 1));
+        assert!(verdict.rejects(GENERATION));
+    }
+
+    #[test]
+    fn the_window_passes_only_once_it_has_elapsed_since_start() {
+        let mut table = HealthTable::new();
+        let probe = HealthProbe {
+            probes: HEALTH_PROBE_RUNNING | HEALTH_PROBE_NO_FAULT,
+            fault_window_ticks: 2,
+            ..running(0)
+        };
+        table.declare(probe, None).unwrap();
+        // Ticks before the instance starts do not count toward its window.
+        table.tick();
+        table.tick();
+        table.started(0);
+        table.tick();
+        assert_eq!(table.verdict(GENERATION, 1), None);
+        table.tick();
+        let verdict = table.verdict(GENERATION, 1).unwrap();
+        assert_eq!(verdict.outcome, HealthOutcome::Passed);
+        assert_eq!(verdict.ticks, 4);
+    }
+
+    #[test]
+    fn only_the_declared_route_answers_the_route_probe() {
+        let mut table = HealthTable::new();
+        let probe = HealthProbe {
+            probes: HEALTH_PROBE_RUNNING | HEALTH_PROBE_ROUTE,
+            route: Some([0x77; 32]),
+            ..running(2)
+        };
+        table.declare(probe, Some(5)).unwrap();
+        table.declare(running(3), None).unwrap();
+        table.started(2);
+        table.started(3);
+        assert!(!table.route_answered(4));
+        assert_eq!(table.verdict(GENERATION, 1), None);
+        assert!(table.route_answered(5));
+        assert_eq!(
+            table.verdict(GENERATION, 1).unwrap().outcome,
+            HealthOutcome::Passed
+        );
+    }
+
+    #[test]
+    fn exiting_before_answering_and_expiring_both_fail_the_route() {
+        let probe = HealthProbe {
+            probes: HEALTH_PROBE_RUNNING | HEALTH_PROBE_ROUTE,
+            route: Some([0x77; 32]),
+            ..running(2)
+        };
+        let mut exited = HealthTable::new();
+        exited.declare(probe, Some(0)).unwrap();
+        exited.started(2);
+        exited.exited(2, 0);
+        let verdict = exited.verdict(GENERATION, 1).unwrap();
+        assert_eq!(verdict.failed_probe, HEALTH_PROBE_ROUTE);
+
+        let mut expired = HealthTable::new();
+        expired.declare(probe, Some(0)).unwrap();
+        expired.started(2);
+        assert_eq!(expired.verdict(GENERATION, 1), None);
+        expired.expire();
+        let verdict = expired.verdict(GENERATION, 1).unwrap();
+        assert_eq!(verdict.outcome, HealthOutcome::Failed);
+        assert_eq!(verdict.failed_probe, HEALTH_PROBE_ROUTE);
+    }
+
+    #[test]
+    fn the_first_failure_is_the_one_reported() {
+        let mut table = HealthTable::new();
+        table.declare(running(0), None).unwrap();
+        table.declare(running(1), None).unwrap();
+        table.exited(1, 3);
+        table.faulted(0);
+        let verdict = table.verdict(GENERATION, 1).unwrap();
+        assert_eq!(verdict.failed_instance, Some(1));
+        assert_eq!(verdict.failed_probe, HEALTH_PROBE_RUNNING);
+        assert_eq!(verdict.failed, 2);
+    }
+
+    #[test]
+    fn an_empty_table_reaches_no_verdict_and_duplicates_are_refused() {
+        let mut table = HealthTable::new();
+        assert_eq!(table.verdict(GENERATION, 1), None);
+        table.declare(running(0), None).unwrap();
+        assert_eq!(
+            table.declare(running(0), None),
+            Err(HealthError::DuplicateInstance(0))
+        );
+    }
+}
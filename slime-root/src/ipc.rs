@@ -42,11 +42,13 @@ pub const fn service_for_root_label(label: sel4::Word) -> Option<u32> {
         SERVICE_SPAWN, SERVICE_SUPERVISION,
     };
     use slime_proto::syscall_abi::{
-        capability_table_labels, capability_transfer_labels, directory_labels, lifecycle_labels,
-        shared_buffer_labels, spawn_labels, supervision_labels,
+        capability_table_labels, capability_transfer_labels, clock_labels, directory_labels,
+        lifecycle_labels, memory_labels, shared_buffer_labels, spawn_labels, supervision_labels,
     };
     match label {
-        lifecycle_labels::EXIT | lifecycle_labels::UNHEALTHY => Some(SERVICE_LIFECYCLE),
+        lifecycle_labels::EXIT | lifecycle_labels::UNHEALTHY | lifecycle_labels::ROUTE_ANSWERED => {
+            Some(SERVICE_LIFECYCLE)
+        }
         // B70's boot action. Lifecycle rather than the capability table, though
         // the label sits in that table's namespace, because the service is the
         // *authority gate* and this operation needs the one every instance
@@ -61,8 +63,24 @@ pub const fn service_for_root_label(label: sel4::Word) -> Option<u32> {
         // `declared_services` seeds it, and 0 of 182 lack it), which states the
         // unscoped policy the contract declares instead of approximating it.
         capability_table_labels::BOOT_ACTION => Some(SERVICE_LIFECYCLE),
+        // Heap growth is lifecycle-gated for the same reason: every instance
+        // may ask, and the generation's page quota -- zero unless declared --
+        // is what refuses, not a service the instance happens to lack.
+        memory_labels::HEAP_GROW | memory_labels::OCCUPANCY => Some(SERVICE_LIFECYCLE),
+        // Clocks likewise: the clock slot each operation names is the
+        // authority, and a caller without a clock grant is refused by it.
+        clock_labels::NOW | clock_labels::WAIT | clock_labels::ADVANCE => Some(SERVICE_LIFECYCLE),
         spawn_labels::SPAWN => Some(SERVICE_SPAWN),
-        supervision_labels::STATUS | supervision_labels::DERIVE => Some(SERVICE_SUPERVISION),
+        // Scheduling control names its child by supervision handle, so it is the
+        // supervision service's; the `SchedulingControl` slot beside it is what
+        // separates a supervisor that may place its child from one that may
+        // only watch it. A kill needs only the handle: a parent may always
+        // give up on a child it started.
+        supervision_labels::STATUS
+        | supervision_labels::DERIVE
+        | supervision_labels::SET_PRIORITY
+        | supervision_labels::SET_CLASS
+        | supervision_labels::KILL => Some(SERVICE_SUPERVISION),
         capability_table_labels::DROP
         | capability_table_labels::OCCUPANCY
         | capability_table_labels::RESOLVE_BINDING
@@ -82,7 +100,7 @@ pub const fn service_for_root_label(label: sel4::Word) -> Option<u32> {
         | shared_buffer_labels::RETURN
         | shared_buffer_labels::REVOKE
         | shared_buffer_labels::OCCUPANCY => Some(SERVICE_SHARED_BUFFER),
-        directory_labels::DERIVE => Some(SERVICE_DIRECTORY),
+        directory_labels::DERIVE | directory_labels::SNAPSHOT => Some(SERVICE_DIRECTORY),
         _ => None,
     }
 }
@@ -195,6 +213,15 @@ impl Response {
             aux: 0,
         }
     }
+
+    /// An error that carries evidence: the status, plus an auxiliary word
+    /// saying which bound refused the request.
+    pub const fn refusal(error: IpcError, aux: sel4::Word) -> Self {
+        Self {
+            result: error.slime_status(),
+            aux,
+        }
+    }
 }
 
 /// One decoded arrival on the root service endpoint.
@@ -243,9 +270,9 @@ pub enum ConsoleKind {
     /// the handlers, and a commit racing a lifecycle syscall on one queue
     /// makes each wait for the other for no reason.
     ///
-    /// Derive is *not* here: it is the only writer of the caller's capability
-    /// table, which the main dispatcher also writes, and two threads writing
-    /// one task's table is a data race.
+    /// Derive and snapshot are *not* here: each installs into the caller's
+    /// capability table, which the main dispatcher also writes, and two
+    /// threads writing one task's table is a data race.
     DirectoryInspect,
     DirectoryCommit,
     /// One sector-granular block-device request (B43). On this thread because
@@ -261,7 +288,7 @@ impl ConsoleKind {
     const DIRECTORY_INSPECT: sel4::Word = 3;
     const DIRECTORY_COMMIT: sel4::Word = 4;
 
-    const fn from_label(label: sel4::Word) -> Option<Self> {
+    pub const fn from_label(label: sel4::Word) -> Option<Self> {
         match label {
             Self::WRITE => Some(Self::Write),
             Self::INPUT_READ => Some(Self::InputRead),
@@ -273,6 +300,58 @@ impl ConsoleKind {
     }
 }
 
+/// What a receive names to hold its caller's reply capability.
+///
+/// The product kernel keeps one implicit reply slot per thread, so there is
+/// nothing to name. MCS makes it an object, and the root holds two: one for
+/// the service loop and one for the console thread. Each receives on its own,
+/// and a Reply object holds one pending caller at a time.
+#[sel4::sel4_cfg(not(KERNEL_MCS))]
+pub type ReplyAuthority = ();
+#[sel4::sel4_cfg(KERNEL_MCS)]
+pub type ReplyAuthority = sel4::cap::Reply;
+
+#[sel4::sel4_cfg(KERNEL_MCS)]
+static SERVICE_REPLY: core::sync::atomic::AtomicU64 = core::sync::atomic::AtomicU64::new(0);
+#[sel4::sel4_cfg(KERNEL_MCS)]
+static CONSOLE_REPLY: core::sync::atomic::AtomicU64 = core::sync::atomic::AtomicU64::new(0);
+
+/// Allocate the root's two Reply objects. Called once at boot, before either
+/// thread first receives.
+#[sel4::sel4_cfg(KERNEL_MCS)]
+pub fn install_replies(
+    allocator: &mut crate::object_allocator::ObjectAllocator,
+) -> Result<(), crate::object_allocator::AllocError> {
+    use core::sync::atomic::Ordering;
+    let service = allocator.allocate_fixed::<sel4::cap_type::Reply>()?.cap();
+    let console = allocator.allocate_fixed::<sel4::cap_type::Reply>()?.cap();
+    SERVICE_REPLY.store(service.bits() as u64, Ordering::Relaxed);
+    CONSOLE_REPLY.store(console.bits() as u64, Ordering::Relaxed);
+    Ok(())
+}
+
+/// The service loop's reply authority.
+#[sel4::sel4_cfg(not(KERNEL_MCS))]
+pub fn service_reply() -> ReplyAuthority {}
+
+#[sel4::sel4_cfg(KERNEL_MCS)]
+pub fn service_reply() -> ReplyAuthority {
+    sel4::cap::Reply::from_bits(
+        SERVICE_REPLY.load(core::sync::atomic::Ordering::Relaxed) as sel4::CPtrBits
+    )
+}
+
+/// The console thread's reply authority.
+#[sel4::sel4_cfg(not(KERNEL_MCS))]
+pub fn console_reply() -> ReplyAuthority {}
+
+#[sel4::sel4_cfg(KERNEL_MCS)]
+pub fn console_reply() -> ReplyAuthority {
+    sel4::cap::Reply::from_bits(
+        CONSOLE_REPLY.load(core::sync::atomic::Ordering::Relaxed) as sel4::CPtrBits
+    )
+}
+
 /// Receive one console message through an explicit IPC buffer.
 ///
 /// The `sel4` crate keeps one IPC-buffer slot per address space, and a receive
@@ -283,7 +362,7 @@ pub fn recv_console(
     endpoint: sel4::cap::Endpoint,
     buffer: &mut sel4::IpcBuffer,
 ) -> Result<ConsoleMessage, IpcError> {
-    let reception = endpoint.with(buffer).recv_with_mrs(());
+    let reception = endpoint.with(buffer).recv_with_mrs(console_reply());
     let len = reception.info.length();
     if len > FAST_MESSAGE_REGISTERS {
         return Err(IpcError::InvalidLength);
@@ -317,7 +396,9 @@ pub fn reply_recv_console(
     // rather than the fast ones, so the reply is staged there and the next
     // request is read back out of them.
     buffer.msg_regs_mut()[..words.len()].copy_from_slice(&words);
-    let (received, badge) = endpoint.with(&mut *buffer).reply_recv(info, ());
+    let (received, badge) = endpoint
+        .with(&mut *buffer)
+        .reply_recv(info, console_reply());
     let len = received.length();
     if len > FAST_MESSAGE_REGISTERS {
         return Err(IpcError::InvalidLength);
@@ -346,7 +427,7 @@ pub fn reply_recv_console(
 /// by whichever thread is parked in `seL4_Recv`. Naming the buffer on the
 /// capability sidesteps the slot entirely (B41).
 pub fn recv_request_with(endpoint: sel4::cap::Endpoint, buffer: &mut sel4::IpcBuffer) -> Reception {
-    let reception = endpoint.with(buffer).recv_with_mrs(());
+    let reception = endpoint.with(buffer).recv_with_mrs(service_reply());
     Reception {
         info: reception.info.clone(),
         badge: reception.badge,
@@ -355,7 +436,7 @@ pub fn recv_request_with(endpoint: sel4::cap::Endpoint, buffer: &mut sel4::IpcBu
 }
 
 pub fn recv_request(endpoint: sel4::cap::Endpoint) -> Reception {
-    let reception = endpoint.recv_with_mrs(());
+    let reception = endpoint.recv_with_mrs(service_reply());
     Reception {
         info: reception.info.clone(),
         badge: reception.badge,
@@ -394,6 +475,20 @@ pub fn reply(response: Response) {
     });
 }
 
+/// Reply to the most recent MCS request, through the service loop's Reply
+/// object. Same wire shape as the non-MCS reply.
+#[sel4::sel4_cfg(KERNEL_MCS)]
+pub fn reply(response: Response) {
+    let words = [response.result as sel4::Word, response.aux];
+    let info = sel4::MessageInfoBuilder::default()
+        .length(words.len())
+        .build();
+    sel4::with_ipc_buffer_mut(|ipc_buffer| {
+        ipc_buffer.msg_regs_mut()[..words.len()].copy_from_slice(&words);
+        service_reply().with(ipc_buffer).send(info);
+    });
+}
+
 /// Poll a notification used to multiplex endpoint, timer, IRQ, and lifecycle
 /// readiness. Badges are opaque routing tokens assigned by `slime-root`; no
 /// CSpace slot or physical identifier is exposed by this helper.
@@ -772,6 +867,10 @@ fn capability_kind_named(name: &str) -> Option<boot_contracts::generation::Capab
         "supervision" => CapabilityKind::Supervision,
         "sharedBuffer" => CapabilityKind::SharedBuffer,
         "loan" => CapabilityKind::Loan,
+        "schedulingControl" => CapabilityKind::SchedulingControl,
+        "monotonicClock" => CapabilityKind::MonotonicClock,
+        "wallClock" => CapabilityKind::WallClock,
+        "simulatedClock" => CapabilityKind::SimulatedClock,
         _ => return None,
     })
 }
@@ -1012,8 +1111,8 @@ mod tests {
         SERVICE_SPAWN, SERVICE_SUPERVISION,
     };
     use slime_proto::syscall_abi::{
-        capability_table_labels, capability_transfer_labels, directory_labels, lifecycle_labels,
-        shared_buffer_labels, spawn_labels, supervision_labels,
+        capability_table_labels, capability_transfer_labels, clock_labels, directory_labels,
+        lifecycle_labels, memory_labels, shared_buffer_labels, spawn_labels, supervision_labels,
     };
 
     /// Every declared operation routes to the mechanism that owns it. B61 moved
@@ -1025,9 +1124,16 @@ mod tests {
         for (label, service) in [
             (lifecycle_labels::EXIT, SERVICE_LIFECYCLE),
             (lifecycle_labels::UNHEALTHY, SERVICE_LIFECYCLE),
+            (lifecycle_labels::ROUTE_ANSWERED, SERVICE_LIFECYCLE),
+            (clock_labels::NOW, SERVICE_LIFECYCLE),
+            (clock_labels::WAIT, SERVICE_LIFECYCLE),
+            (clock_labels::ADVANCE, SERVICE_LIFECYCLE),
             (spawn_labels::SPAWN, SERVICE_SPAWN),
             (supervision_labels::STATUS, SERVICE_SUPERVISION),
             (supervision_labels::DERIVE, SERVICE_SUPERVISION),
+            (supervision_labels::SET_PRIORITY, SERVICE_SUPERVISION),
+            (supervision_labels::SET_CLASS, SERVICE_SUPERVISION),
+            (supervision_labels::KILL, SERVICE_SUPERVISION),
             (capability_table_labels::DROP, SERVICE_CAPABILITY_TRANSFER),
             (
                 capability_table_labels::OCCUPANCY,
@@ -1042,6 +1148,8 @@ mod tests {
             // pairing is the point of this assertion: the namespace a label
             // sits in and the authority it needs are separate facts.
             (capability_table_labels::BOOT_ACTION, SERVICE_LIFECYCLE),
+            (memory_labels::HEAP_GROW, SERVICE_LIFECYCLE),
+            (memory_labels::OCCUPANCY, SERVICE_LIFECYCLE),
             (
                 capability_transfer_labels::EXPORT,
                 SERVICE_CAPABILITY_TRANSFER,
@@ -1069,6 +1177,7 @@ mod tests {
             (shared_buffer_labels::REVOKE, SERVICE_SHARED_BUFFER),
             (shared_buffer_labels::OCCUPANCY, SERVICE_SHARED_BUFFER),
             (directory_labels::DERIVE, SERVICE_DIRECTORY),
+            (directory_labels::SNAPSHOT, SERVICE_DIRECTORY),
         ] {
             assert_eq!(
                 service_for_root_label(label),
@@ -1104,12 +1213,15 @@ mod tests {
             19,
             20,
             // 37 was here until CP2 assigned it to `RESOLVE_BINDING`, 38 until
-            // B70's `GRAPH_READ`, 39 until `GRAPH_ROUTE_INDEX`, and 40 until
-            // B70's `BOOT_ACTION`. Moving one out of this list is the whole
-            // change: a number this test asserts routes nowhere and a number the
-            // contract declares are the same fact stated twice, so assigning a
-            // label must fail here first — as it did for 38, 39 and 40.
-            41,
+            // B70's `GRAPH_READ`, 39 until `GRAPH_ROUTE_INDEX`, 40 until
+            // B70's `BOOT_ACTION`, 41 until `SNAPSHOT`, 43 until `HEAP_GROW`,
+            // 44 until `memory::OCCUPANCY`, 45 and 46 until the two
+            // scheduling-control operations, and 47 to 49 until the clocks.
+            // Moving one out of this list is the whole change: a number this
+            // test asserts routes nowhere and a number the contract declares
+            // are the same fact stated twice, so assigning a label must fail
+            // here first — as it did for 38, 39 and 40.
+            50,
             64,
             sel4::Word::MAX,
         ] {
@@ -1204,6 +1316,10 @@ mod tests {
             ("supervision", CapabilityKind::Supervision),
             ("sharedBuffer", CapabilityKind::SharedBuffer),
             ("loan", CapabilityKind::Loan),
+            ("schedulingControl", CapabilityKind::SchedulingControl),
+            ("monotonicClock", CapabilityKind::MonotonicClock),
+            ("wallClock", CapabilityKind::WallClock),
+            ("simulatedClock", CapabilityKind::SimulatedClock),
         ] {
             assert_eq!(capability_kind_named(spelling), Some(kind), "{spelling}");
         }
@@ -1216,7 +1332,7 @@ mod tests {
     /// generated constants.
     ///
     /// Both sides are rendered from `rightBits` in
-    /// `contracts/generation/v5/gen_rust.zt`; this asserts the pairing rather than
+    /// `contracts/generation/v6/gen_rust.zt`; this asserts the pairing rather than
     /// trusting that two emitters stayed in step.
     #[test]
     fn manifest_right_spellings_match_their_bits() {
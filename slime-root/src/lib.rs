@@ -2,7 +2,7 @@
 //!
 //! Every module here is bounded, and most are pure state machines over fixed
 //! arrays: task-owned typed authority, shared-buffer accounting, supervision
-//! records, and timer scheduling. `main.rs` is the
+//! records, timer scheduling, and the clocks served from it. `main.rs` is the
 //! seL4 root task that drives them — startup staging, the dispatch loop, and
 //! the seL4 object plumbing — and it is deliberately not part of this crate's
 //! testable surface.
@@ -30,6 +30,11 @@
 //! `fault`, `child_vspace`, `object_allocator`, `platform_timer`, and
 //! `buffer_adapter` — compile unchanged.
 //!
+//! The one selection is by kernel profile, not by host: `platform_timer` and
+//! `timeout_timer` are alternative timer backings, and the installed prefix's
+//! `KERNEL_MCS` picks one (B48). The tests build whichever that prefix picks,
+//! exactly as the image does.
+//!
 //! What the host cannot do is *invoke* seL4. No test here performs a syscall;
 //! they exercise the state machines, which is the whole of what they ever
 //! claimed to cover. The behavior that needs a running kernel stays the seL4
@@ -49,6 +54,7 @@ extern crate alloc;
 pub mod boot_selector;
 pub mod buffer_adapter;
 pub mod child_vspace;
+pub mod clock;
 pub mod console;
 pub mod cspace;
 pub mod device;
@@ -57,15 +63,28 @@ pub mod event;
 pub mod fault;
 pub mod generation;
 pub mod graph;
+pub mod health;
 pub mod ipc;
 pub mod launched;
+pub mod memory;
 pub mod notification;
 pub mod object_allocator;
 pub mod peer_endpoint;
-pub mod platform_timer;
+pub mod schedule;
+pub mod shadow;
 pub mod shared_buffer;
 pub mod supervision;
 pub mod task;
 pub mod timer;
 pub mod transfer_window;
 pub mod virtio_blk;
+
+// The timer backing this kernel profile provides. A bang macro rather than an
+// attribute on each `mod`: an attribute macro cannot take a file module.
+sel4::sel4_cfg_if! {
+    if #[sel4_cfg(KERNEL_MCS)] {
+        pub mod timeout_timer;
+    } else {
+        pub mod platform_timer;
+    }
+}
@@ -30,22 +30,32 @@
 // test result evidence about the root the seL4 image boots.
 #[cfg(slime_boot_selector)]
 use slime_root::boot_selector;
+#[cfg(slime_boot_selector)]
+use slime_root::shadow::{
+    self, MAX_SHADOW_TASKS, SHADOW_FIRST_TASK, ShadowBudget, ShadowRun, ShadowState,
+};
 use slime_root::{
-    buffer_adapter, child_vspace, console, cspace, device, directory, event, fault, generation,
-    graph, ipc, launched, notification, object_allocator, peer_endpoint, platform_timer,
-    shared_buffer, supervision, task, timer, transfer_window, virtio_blk,
+    buffer_adapter, child_vspace, clock, console, cspace, device, directory, event, fault,
+    generation, graph, health, ipc, launched, notification, object_allocator, peer_endpoint,
+    schedule, shared_buffer, supervision, task, timer, transfer_window, virtio_blk,
 };
 
 use core::ptr;
 
+#[cfg(slime_boot_selector)]
+use boot_contracts::bootstate::{HealthOutcome, ShadowOutcome};
 use boot_contracts::generation::{
     CapabilityKind, Generation, Grant, GrantEndpoint, Instance, InstanceHealth, InstanceOwner,
     KIND_RESOURCE, MintedBinding,
 };
+#[cfg(slime_boot_selector)]
+use boot_contracts::generation::{HEALTH_PROBE_NO_FAULT, HEALTH_PROBE_ROUTE};
 use boot_contracts::shared_buffer_budget::{self as budget_magic, SharedBufferBudget};
 use sel4_root_task::root_task;
 
-use boot_contracts::generation::{RIGHT_EXEC, RIGHT_RECV, RIGHT_SEND, RIGHT_TRANSFER};
+use boot_contracts::generation::{
+    RIGHT_CLOCK_ADVANCE, RIGHT_CLOCK_READ, RIGHT_EXEC, RIGHT_RECV, RIGHT_SEND, RIGHT_TRANSFER,
+};
 use buffer_adapter::BufferAdapter;
 use child_vspace::{ChildImage, GRANULE_SIZE, ScratchPage};
 use device::{BlockDevices, MAX_BLOCK_DEVICES};
@@ -55,11 +65,14 @@ use generation::{Admission, Authority, bound_authority};
 use ipc::{IpcError, Response, poll_notification};
 use launched::LaunchedInstances;
 use object_allocator::ObjectAllocator;
-use platform_timer::{PhysicalTimerAdapter, TIMER_IRQ};
 use shared_buffer::{
     BufferHandle, GenerationEpoch, HolderId, HolderQuota, MappingRights, PAGE_SIZE,
     SharedBufferAdapter, SharedBufferTable, VSpaceCap,
 };
+#[sel4::sel4_cfg(not(KERNEL_MCS))]
+use slime_root::platform_timer::{PhysicalTimerAdapter as TimerAdapter, TIMER_IRQ};
+#[sel4::sel4_cfg(KERNEL_MCS)]
+use slime_root::timeout_timer::TimeoutTimerAdapter as TimerAdapter;
 use task::{Arrival, CHILD_CNODE_SIZE_BITS, MAX_TASKS, Supervision, TaskId, TaskTable};
 use timer::{PlatformTimer, ServiceTimerError, TimerScheduler, apply_deadline_programming};
 use transfer_window::{WindowTable, descriptor_thread};
@@ -81,8 +94,9 @@ const MAX_WINDOW_ENTRIES: usize = MAX_TASKS * child_vspace::MAX_CHILD_THREADS;
 // - `shared_buffer_labels::OCCUPANCY` derives its holder from the badge for the
 //   same reason.
 use slime_proto::syscall_abi::{
-    capability_table_labels, capability_transfer_labels, directory_labels, fixture_labels,
-    lifecycle_labels, shared_buffer_labels, spawn_labels, supervision_labels,
+    capability_table_labels, capability_transfer_labels, clock_labels, directory_labels,
+    fixture_labels, lifecycle_labels, memory_labels, shared_buffer_labels, spawn_labels,
+    supervision_labels,
 };
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -216,6 +230,16 @@ const TIMER_PROOF_DEADLINE_DIVISOR: u64 = 100;
 /// of burning the full boot-check timeout.
 const TIMER_PROOF_BOUND_SECONDS: u64 = 3;
 
+/// The clocks the graph is served, over the timer the boot proof used. One
+/// wait per task per clock, so the task table bounds each queue.
+type RootClocks = clock::Clocks<TimerAdapter, MAX_TASKS>;
+
+/// qemu-arm-virt's PL031 real-time clock. Like [`VIRTIO_MMIO_BASE`], a
+/// fixture constant the platform's device tree is the authority for.
+const RTC_BASE: usize = 0x0901_0000;
+/// PL031 `RTCDR`: the current time in whole seconds since the Unix epoch.
+const RTC_DATA_OFFSET: usize = 0;
+
 #[repr(C, align(4096))]
 struct FreePage([u8; GRANULE_SIZE]);
 
@@ -245,12 +269,17 @@ static mut FOUNDATION_PAGES: [FreePage; 2] = [const { FreePage([0; GRANULE_SIZE]
 /// holds the device.
 static mut DEVICE_PAGE: FreePage = FreePage([0; GRANULE_SIZE]);
 
+/// A root-image page whose virtual address maps the real-time clock for the
+/// one read that anchors the wall clock. Separate from `DEVICE_PAGE`, which
+/// the block probe may already hold.
+static mut RTC_PAGE: FreePage = FreePage([0; GRANULE_SIZE]);
+
 /// Standing windows for each attached block device's register bank (P5.4.2b),
 /// one per device since P5.4.3.
 ///
 /// Separate from `DEVICE_PAGE`, which the probe reuses granule by granule as it
-/// scans: a live device's registers must stay mapped, and two live devices need
-/// two windows.
+/// scans: a live device's registers must stay mapped, and each live device
+/// needs its own window.
 ///
 /// Arrays rather than a second set of named statics: the bound is
 /// `MAX_BLOCK_DEVICES`, and duplicating three names per device would make the
@@ -449,6 +478,8 @@ fn main(bootinfo: &sel4::BootInfoPtr) -> ! {
         "SLIME_ROOT allocator slots={initial_slots} untypeds={initial_untypeds} bytes={initial_bytes}",
     );
 
+    install_mcs_authority(bootinfo, allocator);
+
     // ---- timer phase ----
     // Proves `TimerScheduler` (see `timer.rs`) is driven by a real seL4 IRQ
     // before any fixture task exists: acquire the one architected-timer PPI
@@ -457,14 +488,12 @@ fn main(bootinfo: &sel4::BootInfoPtr) -> ! {
     // confirm the monotonic counter it reads actually advanced. The wait is
     // bounded by wall-clock ticks read directly from hardware rather than by
     // IRQ delivery, so a broken wiring fails loudly instead of hanging boot.
-    let mut timer_adapter = match PhysicalTimerAdapter::acquire(allocator) {
-        Ok(adapter) => adapter,
-        Err(error) => fatal!("timer source unavailable: {error:?}"),
-    };
-    sel4::debug_println!(
-        "SLIME_TIMER acquired irq={TIMER_IRQ} freq_hz={}",
-        timer_adapter.frequency_hz(),
-    );
+    //
+    // Under the MCS profile the source is a scheduling context instead
+    // (`timeout_timer.rs`), and the same proof runs against it. There the
+    // counter is the clock thread's, so the bound is counted by the kernel's
+    // replenishments rather than read from hardware.
+    let mut timer_adapter = acquire_timer(bootinfo, allocator);
 
     let mut timer_scheduler = TimerScheduler::<1>::new();
     const TIMER_PROOF_OWNER: TaskEpoch = TaskEpoch::new(0, 0);
@@ -608,6 +637,7 @@ fn main(bootinfo: &sel4::BootInfoPtr) -> ! {
     let boot_selector::SelectedGeneration {
         generation,
         runtime: mut boot_runtime,
+        shadow,
     } = selected;
     #[cfg(not(slime_boot_selector))]
     let generation = match Generation::decode(GENERATION_BYTES) {
@@ -725,6 +755,13 @@ fn main(bootinfo: &sel4::BootInfoPtr) -> ! {
     // capability authorizes a userspace spawn.
     #[cfg(not(slime_root_fixture))]
     if admission.loadable > 0 {
+        // From here the root answers time only through a clock capability.
+        // The counter itself stays exported to EL0 for the adapters' sake;
+        // `check-clock-authority.py` is what keeps components from reading it
+        // around one.
+        let frequency_hz = timer_adapter.frequency_hz();
+        let wall = anchor_wall_clock(bootinfo, allocator, &mut timer_adapter);
+        let mut clocks = RootClocks::new(timer_adapter, frequency_hz, wall);
         launch_instance_graph(
             &generation,
             &admission,
@@ -734,8 +771,11 @@ fn main(bootinfo: &sel4::BootInfoPtr) -> ! {
             service_endpoint,
             console_endpoint,
             &mut block_devices,
+            &mut clocks,
             #[cfg(slime_boot_selector)]
             &mut boot_runtime,
+            #[cfg(slime_boot_selector)]
+            shadow,
         );
         loop {
             core::hint::spin_loop();
@@ -803,6 +843,10 @@ fn main(bootinfo: &sel4::BootInfoPtr) -> ! {
             1,
             // No workers, so no worker priorities.
             [task::CHILD_PRIORITY; child_vspace::MAX_CHILD_THREADS],
+            // No plan, so no reservation either.
+            [None; child_vspace::MAX_CHILD_THREADS],
+            // No plan, so no heap quota.
+            0,
         ) {
             Ok(id) => id,
             Err(error) => fatal!("child task construction failed: {error:?}"),
@@ -1119,6 +1163,12 @@ static mut PEER_ENDPOINTS: peer_endpoint::PeerEndpointTable =
     peer_endpoint::PeerEndpointTable::new();
 static mut NOTIFICATIONS: notification::NotificationTable = notification::NotificationTable::new();
 
+/// The shadow graph's tasks, numbered from `SHADOW_FIRST_TASK` so their badges
+/// can never name a running task. A static for the reason `ELF_SCRATCH` is
+/// one: a second task table is too large for the root's stack.
+#[cfg(slime_boot_selector)]
+static mut SHADOW_TASKS: TaskTable<MAX_SHADOW_TASKS> = TaskTable::starting_at(SHADOW_FIRST_TASK);
+
 const MAX_CAPABILITY_EXPORTS: usize = 64;
 #[derive(Clone, Copy)]
 struct CapabilityExport {
@@ -1133,6 +1183,9 @@ struct CapabilityExport {
 }
 struct CapabilityExports {
     entries: [Option<CapabilityExport>; MAX_CAPABILITY_EXPORTS],
+    /// Each receiver's most recent import and who sent it, so a second import
+    /// can insist on the same sender.
+    last_senders: [Option<(TaskId, TaskId)>; MAX_CAPABILITY_EXPORTS],
     next_id: u32,
     exported: usize,
     imported: usize,
@@ -1143,6 +1196,7 @@ impl CapabilityExports {
     const fn new() -> Self {
         Self {
             entries: [None; MAX_CAPABILITY_EXPORTS],
+            last_senders: [None; MAX_CAPABILITY_EXPORTS],
             next_id: 1,
             exported: 0,
             imported: 0,
@@ -1159,6 +1213,33 @@ impl CapabilityExports {
             .flatten()
             .find(|entry| entry.id == id)
     }
+    fn last_sender(&self, receiver: TaskId) -> Option<TaskId> {
+        self.last_senders
+            .iter()
+            .flatten()
+            .find(|(to, _)| *to == receiver)
+            .map(|(_, from)| *from)
+    }
+    fn note_import(&mut self, receiver: TaskId, sender: TaskId) {
+        let slot = match self
+            .last_senders
+            .iter()
+            .position(|entry| entry.is_some_and(|(to, _)| to == receiver))
+        {
+            Some(index) => Some(index),
+            None => self.last_senders.iter().position(Option::is_none),
+        };
+        if let Some(index) = slot {
+            self.last_senders[index] = Some((receiver, sender));
+        }
+    }
+    fn forget_receiver(&mut self, receiver: TaskId) {
+        for entry in &mut self.last_senders {
+            if entry.is_some_and(|(to, _)| to == receiver) {
+                *entry = None;
+            }
+        }
+    }
     fn remove(&mut self, id: u32) -> Option<CapabilityExport> {
         let slot = self
             .entries
@@ -1390,6 +1471,60 @@ fn probe_devices(bootinfo: &sel4::BootInfo, allocator: &mut ObjectAllocator) ->
     devices
 }
 
+/// Anchor the wall clock: read the PL031 real-time clock once and pair its
+/// seconds with a counter reading taken beside it.
+///
+/// The wall clock is then the monotonic one offset by that anchor. Any failure
+/// leaves it unanchored: a grant of it is still served, and every read answers
+/// that there is no wall time to give. The RTC is mapped through `RTC_PAGE`
+/// for this one read, unmapped after it, and never granted.
+fn anchor_wall_clock(
+    bootinfo: &sel4::BootInfo,
+    allocator: &mut ObjectAllocator,
+    timer: &mut TimerAdapter,
+) -> Option<clock::WallEpoch> {
+    if allocator.device_untyped_count() == 0 {
+        sel4::debug_println!("SLIME_CLOCK wall unanchored reason=no-device-memory");
+        return None;
+    }
+    // SAFETY: the root task is single-threaded and this is the only reference
+    // taken to `RTC_PAGE`. Its address is granule-aligned by the type's
+    // `repr(align(4096))`, and it is claimed exactly once.
+    let base = ptr::addr_of!(RTC_PAGE) as usize;
+    if let Err(error) = ScratchPage::claim(bootinfo, base) {
+        sel4::debug_println!("SLIME_CLOCK wall unanchored page={error:?}");
+        return None;
+    }
+    let region = match device::DeviceRegion::map(
+        allocator,
+        sel4::init_thread::slot::VSPACE.cap(),
+        base,
+        RTC_BASE,
+    ) {
+        Ok(region) => region,
+        Err(error) => {
+            sel4::debug_println!("SLIME_CLOCK wall unanchored map={error:?}");
+            return None;
+        }
+    };
+    let reading = (region.read32(RTC_DATA_OFFSET), timer.monotonic_now());
+    // Nothing reads the RTC again, so the mapping goes before the reading is
+    // judged. A failed unmap leaves the register reachable from the root's own
+    // VSpace only, which no grant exposes, so it is reported and not fatal.
+    if let Err(error) = region.unmap() {
+        sel4::debug_println!("SLIME_CLOCK wall rtc unmap failed {error:?}");
+    }
+    let (Some(seconds), Ok(counter)) = reading else {
+        sel4::debug_println!("SLIME_CLOCK wall unanchored reason=unreadable");
+        return None;
+    };
+    sel4::debug_println!("SLIME_CLOCK wall anchored unix_s={seconds}");
+    Some(clock::WallEpoch {
+        unix_ns: u64::from(seconds) * 1_000_000_000,
+        counter,
+    })
+}
+
 /// Bring up a second transport in a granule another driver already mapped
 /// (B29, P5.4.3).
 ///
@@ -1624,8 +1759,12 @@ const fn input_script(generation: u64) -> &'static [u8] {
 /// rights are valid for the declared kind; dispatch never guesses the object
 /// class from overlapping right bits.
 /// Construct one typed root-mediated capability from a declared kind.
+///
+/// `grant` is the declaring grant's index, which names a simulated clock: one
+/// clock per grant, shared by every copy narrowed from it.
 const fn declared_capability(
     kind: CapabilityKind,
+    grant: usize,
     device: u8,
     rights: u64,
 ) -> Option<graph::CapabilityEntry> {
@@ -1636,6 +1775,14 @@ const fn declared_capability(
         CapabilityKind::Input => graph::CapabilityEntry::input(rights),
         CapabilityKind::SharedBufferFactory => graph::CapabilityEntry::buffer_factory(rights),
         CapabilityKind::Block => graph::CapabilityEntry::block(device, rights),
+        CapabilityKind::SchedulingControl => graph::CapabilityEntry::scheduling_control(rights),
+        CapabilityKind::MonotonicClock => {
+            graph::CapabilityEntry::clock(clock::Clock::Monotonic, rights)
+        }
+        CapabilityKind::WallClock => graph::CapabilityEntry::clock(clock::Clock::Wall, rights),
+        CapabilityKind::SimulatedClock => {
+            graph::CapabilityEntry::clock(clock::Clock::Simulated { grant }, rights)
+        }
         CapabilityKind::Endpoint
         | CapabilityKind::Executable
         | CapabilityKind::Supervision
@@ -1687,10 +1834,115 @@ struct ConsoleTables<'a> {
     tasks: &'a TaskTable<MAX_TASKS>,
     script: &'static [u8],
     devices: &'a mut BlockDevices,
-    namespaces: &'a mut directory::Namespaces,
+    namespaces: &'a directory::Namespaces,
+    snapshots: &'a directory::SnapshotTable,
     scopes: &'a directory::ScopeTable,
 }
 
+/// Record the authority only the MCS kernel has, before anything needs it:
+/// the `SchedControl` capability every scheduling context is configured
+/// through, and the Reply objects the root's two receiving threads name.
+/// Nothing to do on the product kernel.
+#[sel4::sel4_cfg(KERNEL_MCS)]
+fn install_mcs_authority(bootinfo: &sel4::BootInfo, allocator: &mut ObjectAllocator) {
+    task::install_sched_control(bootinfo);
+    if let Err(error) = ipc::install_replies(allocator) {
+        fatal!("SLIME_ROOT reply objects unavailable: {error:?}")
+    }
+    sel4::debug_println!("SLIME_ROOT kernel profile=mcs");
+}
+
+#[sel4::sel4_cfg(not(KERNEL_MCS))]
+fn install_mcs_authority(_bootinfo: &sel4::BootInfo, _allocator: &mut ObjectAllocator) {}
+
+/// Acquire the timer source this kernel profile provides.
+///
+/// The product line is what the boot gate reads, so it is unchanged.
+#[sel4::sel4_cfg(not(KERNEL_MCS))]
+fn acquire_timer(_bootinfo: &sel4::BootInfo, allocator: &mut ObjectAllocator) -> TimerAdapter {
+    let adapter = match TimerAdapter::acquire(allocator) {
+        Ok(adapter) => adapter,
+        Err(error) => fatal!("timer source unavailable: {error:?}"),
+    };
+    sel4::debug_println!(
+        "SLIME_TIMER acquired irq={TIMER_IRQ} freq_hz={}",
+        adapter.frequency_hz(),
+    );
+    adapter
+}
+
+#[sel4::sel4_cfg(KERNEL_MCS)]
+fn acquire_timer(bootinfo: &sel4::BootInfo, allocator: &mut ObjectAllocator) -> TimerAdapter {
+    let adapter = match TimerAdapter::acquire(bootinfo, allocator) {
+        Ok(adapter) => adapter,
+        Err(error) => fatal!("timer source unavailable: {error:?}"),
+    };
+    sel4::debug_println!(
+        "SLIME_TIMER acquired sched_context freq_hz={}",
+        adapter.frequency_hz(),
+    );
+    adapter
+}
+
+/// Bind a helper thread of the root's own to the root's CSpace and VSpace at
+/// `priority`, with no fault handler: a fault in one is a root defect, and a
+/// null handler makes the kernel report it rather than deliver it somewhere
+/// that would swallow it.
+#[sel4::sel4_cfg(not(KERNEL_MCS))]
+fn configure_root_thread(
+    bootinfo: &sel4::BootInfo,
+    _allocator: &mut ObjectAllocator,
+    tcb: sel4::cap::Tcb,
+    ipc_buffer_addr: sel4::Word,
+    ipc_buffer: sel4::cap::Granule,
+    priority: sel4::Word,
+) -> Result<(), task::TaskError> {
+    tcb.tcb_configure(
+        sel4::CPtr::from_bits(0),
+        sel4::init_thread::slot::CNODE.cap(),
+        // The root CNode's own guard. A zero guard faults every lookup: a CPtr
+        // resolves to `WORD_SIZE` bits and the CNode holds fewer.
+        child_vspace::root_cspace_guard(bootinfo),
+        sel4::init_thread::slot::VSPACE.cap(),
+        ipc_buffer_addr,
+        ipc_buffer,
+    )
+    .map_err(task::TaskError::Configure)?;
+    tcb.tcb_set_sched_params(sel4::init_thread::slot::TCB.cap(), priority, priority)
+        .map_err(task::TaskError::SchedParams)
+}
+
+/// Under MCS the thread also needs a scheduling context to run at all. It gets
+/// a round-robin one, which is what the product kernel's timeslice gives it.
+#[sel4::sel4_cfg(KERNEL_MCS)]
+fn configure_root_thread(
+    bootinfo: &sel4::BootInfo,
+    allocator: &mut ObjectAllocator,
+    tcb: sel4::cap::Tcb,
+    ipc_buffer_addr: sel4::Word,
+    ipc_buffer: sel4::cap::Granule,
+    priority: sel4::Word,
+) -> Result<(), task::TaskError> {
+    let sched_context =
+        task::reserve_sched_context(allocator, None, schedule::Reservation::ROUND_ROBIN)?;
+    tcb.tcb_configure(
+        sel4::init_thread::slot::CNODE.cap(),
+        child_vspace::root_cspace_guard(bootinfo),
+        sel4::init_thread::slot::VSPACE.cap(),
+        ipc_buffer_addr,
+        ipc_buffer,
+    )
+    .map_err(task::TaskError::Configure)?;
+    tcb.tcb_set_sched_params(
+        sel4::init_thread::slot::TCB.cap(),
+        priority,
+        priority,
+        sched_context,
+        sel4::cap::Endpoint::from_bits(0),
+    )
+    .map_err(task::TaskError::SchedParams)
+}
+
 fn start_console_dispatcher(
     bootinfo: &sel4::BootInfo,
     allocator: &mut ObjectAllocator,
@@ -1703,6 +1955,7 @@ fn start_console_dispatcher(
         script,
         devices,
         namespaces,
+        snapshots,
         scopes,
     } = tables;
     let scratch_addr = ptr::addr_of!(CONSOLE_PAGE) as usize;
@@ -1735,7 +1988,8 @@ fn start_console_dispatcher(
             input,
             tasks: tasks as *const _,
             devices: ptr::addr_of_mut!(*devices),
-            namespaces: ptr::addr_of_mut!(*namespaces),
+            namespaces: namespaces as *const _,
+            snapshots: snapshots as *const _,
             scopes: scopes as *const _,
         });
         match slot.as_ref() {
@@ -1744,28 +1998,19 @@ fn start_console_dispatcher(
         }
     };
 
-    let configured = tcb.tcb_configure(
-        // No fault handler: a fault in this thread is a root defect, and a
-        // null handler makes the kernel report it rather than deliver it
-        // somewhere that would swallow it.
-        sel4::CPtr::from_bits(0),
-        sel4::init_thread::slot::CNODE.cap(),
-        // The root CNode's own guard. A zero guard faults every lookup: a CPtr
-        // resolves to `WORD_SIZE` bits and the CNode holds fewer.
-        child_vspace::root_cspace_guard(bootinfo),
-        sel4::init_thread::slot::VSPACE.cap(),
+    // The root's own priority: this thread answers on equal terms with the
+    // service loop rather than starving behind it.
+    let configured = configure_root_thread(
+        bootinfo,
+        allocator,
+        tcb,
         ipc_addr as sel4::Word,
         ipc_frame,
+        255,
     );
     if let Err(error) = configured {
         fatal!("console thread configure failed: {error:?}")
     }
-    // The root's own priority: this thread answers on equal terms with the
-    // service loop rather than starving behind it.
-    let scheduled = tcb.tcb_set_sched_params(sel4::init_thread::slot::TCB.cap(), 255, 255);
-    if let Err(error) = scheduled {
-        fatal!("console thread priority failed: {error:?}")
-    }
 
     let stack_top = ptr::addr_of!(CONSOLE_STACK) as usize + size_of::<console::ConsoleStack>();
     let mut registers = sel4::UserContext::default();
@@ -1793,6 +2038,448 @@ extern "C" fn console_entry(context: usize) -> ! {
     unsafe { console::serve(&*(context as *const console::ConsoleContext)) }
 }
 
+/// A pending generation's graph, running beside this one under a shadow
+/// request. Everything it was built from is its own: the candidate bytes sit
+/// in the selector's second buffer region and its tasks in `SHADOW_TASKS`, so
+/// dropping it touches nothing the running graph holds.
+#[cfg(slime_boot_selector)]
+struct Shadow {
+    candidate: Generation<'static>,
+    tasks: &'static mut TaskTable<MAX_SHADOW_TASKS>,
+    run: ShadowRun,
+    budget: ShadowBudget,
+}
+
+/// Build and start the candidate's root instances, or record why it could not
+/// be.
+///
+/// Runs after the running graph is activated, so the budget is carved from
+/// what that graph left. A candidate the selector refused, or one that fails
+/// the running root's own preflight, is recorded as refused without a task
+/// being built — it is the same answer a real boot of it would have reached,
+/// without spending the attempt.
+#[cfg(slime_boot_selector)]
+fn launch_shadow(
+    candidate: Result<Generation<'static>, boot_selector::SelectorError>,
+    allocator: &mut ObjectAllocator,
+    scratch: &ScratchPage,
+    service_endpoint: sel4::cap::Endpoint,
+    block_devices: &mut BlockDevices,
+    clocks: &mut RootClocks,
+    boot_runtime: &mut boot_selector::BootRuntime,
+) -> Option<Shadow> {
+    let budget = ShadowBudget::carve(allocator.untyped_bytes_remaining(), allocator.free_slots());
+    let candidate = match candidate {
+        Ok(candidate) => candidate,
+        Err(error) => {
+            sel4::debug_println!("SLIME_SHADOW refused stage=selection error={error:?}");
+            record_shadow_verdict(
+                ShadowOutcome::Refused,
+                0,
+                &ShadowRun::new(0, 0, 0, 0),
+                &budget,
+                block_devices,
+                boot_runtime,
+            );
+            return None;
+        }
+    };
+    // The window opens at launch, on the same counter the clocks answer from.
+    let Ok(started_ns) = clocks.now(clock::Clock::Monotonic) else {
+        sel4::debug_println!("SLIME_SHADOW refused stage=clock");
+        record_shadow_verdict(
+            ShadowOutcome::Refused,
+            0,
+            &ShadowRun::new(0, 0, 0, 0),
+            &budget,
+            block_devices,
+            boot_runtime,
+        );
+        return None;
+    };
+    let mut shadow = Shadow {
+        run: ShadowRun::new(
+            shadow_required(&candidate),
+            shadow_withheld(&candidate),
+            shadow_unverified(&candidate),
+            started_ns,
+        ),
+        candidate,
+        tasks: unsafe { &mut *ptr::addr_of_mut!(SHADOW_TASKS) },
+        budget,
+    };
+    let built = build_shadow(&mut shadow, allocator, scratch, service_endpoint).and_then(|()| {
+        shadow
+            .tasks
+            .activate_all()
+            .map(|_| ())
+            .map_err(|_| "activation")
+    });
+    if let Err(stage) = built {
+        sel4::debug_println!(
+            "SLIME_SHADOW refused stage={stage} number={}",
+            shadow.candidate.number
+        );
+        finish_shadow(
+            shadow,
+            ShadowOutcome::Refused,
+            allocator,
+            block_devices,
+            boot_runtime,
+        );
+        return None;
+    }
+    sel4::debug_println!(
+        "SLIME_SHADOW launched number={} instances={} required={} withheld={} bytes={} slots={} budget_bytes={} budget_slots={}",
+        shadow.candidate.number,
+        shadow.run.exercised(),
+        shadow.run.required(),
+        shadow.run.withheld(),
+        shadow.budget.used_bytes(),
+        shadow.budget.used_slots(),
+        shadow.budget.bytes(),
+        shadow.budget.slots(),
+    );
+    Some(shadow)
+}
+
+/// The candidate's autostart instances declared `Required`, counted the way
+/// `serve_instance_graph` counts the running graph's.
+#[cfg(slime_boot_selector)]
+fn shadow_required(candidate: &Generation<'_>) -> usize {
+    (0..candidate.instance_count())
+        .filter(|index| {
+            candidate.instance(*index).is_ok_and(|instance| {
+                instance.autostart && instance.health == InstanceHealth::Required
+            })
+        })
+        .count()
+}
+
+/// The candidate's declared `NO_FAULT` and `ROUTE` probes on autostart
+/// instances, the ones `health_table` would hold a real boot of it to. A
+/// shadow passes neither, so each is evidence it leaves missing. A record the
+/// table would refuse is counted whole: it is not the shadow's to vouch for.
+#[cfg(slime_boot_selector)]
+fn shadow_unverified(candidate: &Generation<'_>) -> usize {
+    (0..candidate.health_count())
+        .map(|index| match candidate.health_probe(index) {
+            Ok(probe)
+                if !candidate
+                    .instance(probe.instance)
+                    .is_ok_and(|instance| instance.autostart) =>
+            {
+                0
+            }
+            Ok(probe) => {
+                (probe.probes & (HEALTH_PROBE_NO_FAULT | HEALTH_PROBE_ROUTE)).count_ones() as usize
+            }
+            Err(_) => 1,
+        })
+        .sum()
+}
+
+/// Bindings of the candidate's root instances that a shadow build declines to
+/// install: every one that is not the service endpoint.
+#[cfg(slime_boot_selector)]
+fn shadow_withheld(candidate: &Generation<'_>) -> usize {
+    (0..candidate.instance_count())
+        .filter_map(|index| candidate.instance(index).ok())
+        .filter(|instance| instance.is_root_autostart())
+        .map(|instance| {
+            (0..instance.binding_count())
+                .filter_map(|binding| candidate.binding(instance, binding).ok())
+                .filter_map(|binding| candidate.grant(binding.grant).ok())
+                .filter(|grant| grant.capability_kind != CapabilityKind::Endpoint)
+                .count()
+        })
+        .sum()
+}
+
+/// Construct the candidate's root instances in the shadow table.
+///
+/// The same plan reads `launch_instance_graph` makes, answered with a stage
+/// name instead of a fatal: a candidate that cannot be built is evidence, not
+/// a reason to stop the running graph. The console endpoint handed to each
+/// child is the root's service endpoint, so its output is counted here rather
+/// than reaching the console thread's tables.
+#[cfg(slime_boot_selector)]
+fn build_shadow(
+    shadow: &mut Shadow,
+    allocator: &mut ObjectAllocator,
+    scratch: &ScratchPage,
+    service_endpoint: sel4::cap::Endpoint,
+) -> Result<(), &'static str> {
+    let candidate = &shadow.candidate;
+    let profile = boot_contracts::target_profile::TargetProfile::by_name(TARGET_PROFILE)
+        .map_err(|_| "profile")?;
+    let admission = Admission::admit(candidate, profile).map_err(|_| "admission")?;
+    generation::admit_total_slots(candidate, shadow.budget.slots()).map_err(|_| "slots")?;
+    let aligned = unsafe { &mut *ptr::addr_of_mut!(ELF_SCRATCH) };
+    for instance_index in 0..candidate.instance_count() {
+        let instance = candidate.instance(instance_index).map_err(|_| "instance")?;
+        if !instance.is_root_autostart() {
+            continue;
+        }
+        let executable = candidate
+            .executable(instance.executable)
+            .map_err(|_| "executable")?;
+        if !admission
+            .executable_plan(instance.executable)
+            .is_some_and(|plan| plan.format.is_loadable())
+        {
+            return Err("loadable");
+        }
+        let object = candidate.object(executable.object).map_err(|_| "object")?;
+        let elf = boot_contracts::component_image::admit_elf(object.bytes, profile)
+            .map_err(|_| "image")?;
+        let image =
+            ChildImage::parse(aligned.hold(elf).map_err(|_| "image")?).map_err(|_| "image")?;
+        let authority = bound_authority(candidate, instance).map_err(|_| "authority")?;
+        let Ok(Some(cspace_size_bits)) = candidate.instance_cspace_size_bits(instance_index) else {
+            return Err("cspace");
+        };
+        let priority = match candidate.instance_priority(instance_index) {
+            Ok(Some(priority)) => sel4::Word::from(priority),
+            Ok(None) => task::CHILD_PRIORITY,
+            Err(_) => return Err("schedule"),
+        };
+        let threads = match candidate.instance_threads(instance_index) {
+            Ok(Some(threads)) => threads,
+            Ok(None) => 1,
+            Err(_) => return Err("threads"),
+        };
+        let heap_pages = match candidate.instance_heap_pages(instance_index) {
+            Ok(pages) => pages.unwrap_or(0),
+            Err(_) => return Err("heap"),
+        };
+        let mut worker_priorities = [priority; child_vspace::MAX_CHILD_THREADS];
+        for (thread_index, slot) in worker_priorities
+            .iter_mut()
+            .enumerate()
+            .take(threads)
+            .skip(1)
+        {
+            *slot = match candidate.thread_priority(instance_index, thread_index) {
+                Ok(Some(priority)) => sel4::Word::from(priority),
+                Ok(None) => priority,
+                Err(_) => return Err("schedule"),
+            };
+        }
+        let reservations =
+            declared_reservations(candidate, instance_index).map_err(|_| "schedule")?;
+        let Ok(Some(boot_contracts::generation::ChildSlotPlan {
+            service: Some(service),
+            console: Some(console),
+            tcb: Some(tcb),
+            fault: Some(fault),
+        })) = candidate.instance_child_slots(instance_index)
+        else {
+            return Err("child-slots");
+        };
+        let child_slots = task::ChildSlots {
+            service: service as sel4::CPtrBits,
+            console: console as sel4::CPtrBits,
+            tcb: tcb as sel4::CPtrBits,
+            fault: fault as sel4::CPtrBits,
+        }
+        .validate()
+        .map_err(|_| "child-slots")?;
+        let (bytes_before, slots_before) =
+            (allocator.untyped_bytes_remaining(), allocator.free_slots());
+        let id = shadow
+            .tasks
+            .create(
+                allocator,
+                &image,
+                service_endpoint,
+                service_endpoint,
+                authority,
+                Supervision::SelfManaged,
+                sel4::init_thread::slot::VSPACE.cap(),
+                scratch,
+                sel4::init_thread::slot::ASID_POOL.cap(),
+                None,
+                Some(instance.executable),
+                Some(instance_index),
+                if instance_index == candidate.bootstrap() {
+                    candidate.boot_action.id()
+                } else {
+                    0
+                },
+                cspace_size_bits as usize,
+                child_slots,
+                priority,
+                threads,
+                worker_priorities,
+                reservations,
+                heap_pages,
+            )
+            .map_err(|_| "construction")?;
+        shadow
+            .run
+            .launched(
+                id,
+                instance_index,
+                instance.health == InstanceHealth::Required,
+            )
+            .map_err(|_| "instances")?;
+        shadow
+            .budget
+            .charge(
+                bytes_before.saturating_sub(allocator.untyped_bytes_remaining()),
+                slots_before.saturating_sub(allocator.free_slots()),
+            )
+            .map_err(|_| "budget")?;
+    }
+    Ok(())
+}
+
+/// Account for one arrival from a shadow task.
+///
+/// Only lifecycle reports are answered. `UNHEALTHY` gets its success reply so
+/// the runtime goes on to exit as it would under a real boot, and the exit
+/// that follows is never replied to, as for the running graph. Every other
+/// request parks its caller: the shadow has reached a real service call, which
+/// is all it is allowed to show. A console write is a one-way send and counts.
+#[cfg(slime_boot_selector)]
+fn serve_shadow_arrival(
+    shadow: &mut Shadow,
+    id: TaskId,
+    arrival: Arrival,
+    reception: &ipc::Reception,
+) {
+    let label = reception.info.label();
+    let state = if arrival == Arrival::Fault {
+        Some(ShadowState::Failed)
+    } else if label == lifecycle_labels::EXIT {
+        let status = reception
+            .request
+            .as_ref()
+            .map_or(1, |request| request.mrs[0] as i64);
+        Some(if status == 0 {
+            ShadowState::Completed
+        } else {
+            ShadowState::Failed
+        })
+    } else if label == lifecycle_labels::UNHEALTHY {
+        ipc::reply(Response::success(0, 0));
+        Some(ShadowState::Failed)
+    } else if ipc::ConsoleKind::from_label(label) == Some(ipc::ConsoleKind::Write) {
+        shadow.run.console_write();
+        None
+    } else {
+        Some(ShadowState::Parked)
+    };
+    if let Some(state) = state
+        && let Err(error) = shadow.run.observe(id, state)
+    {
+        sel4::debug_println!(
+            "SLIME_SHADOW arrival rejected task={} error={error:?}",
+            id.0
+        );
+    }
+}
+
+/// The monotonic reading a shadow's window is judged at. A counter that
+/// cannot be read closes the window rather than holding the shadow open.
+#[cfg(slime_boot_selector)]
+fn shadow_now(clocks: &mut RootClocks) -> u64 {
+    clocks.now(clock::Clock::Monotonic).unwrap_or(u64::MAX)
+}
+
+/// Tear the shadow down and record `outcome` in the evidence sector.
+#[cfg(slime_boot_selector)]
+fn finish_shadow(
+    shadow: Shadow,
+    outcome: ShadowOutcome,
+    allocator: &mut ObjectAllocator,
+    block_devices: &mut BlockDevices,
+    boot_runtime: &mut boot_selector::BootRuntime,
+) {
+    let Shadow {
+        candidate,
+        tasks,
+        run,
+        budget,
+    } = shadow;
+    // `reclaim` suspends before it revokes, so a task still running, parked,
+    // or blocked on a fault reply is stopped the same way.
+    while let Some(id) = tasks.tasks().next().map(|task| task.id) {
+        if let Err(error) = tasks.reclaim(allocator, id) {
+            sel4::debug_println!(
+                "SLIME_SHADOW reclaim incomplete task={} error={error:?}",
+                id.0
+            );
+            break;
+        }
+    }
+    record_shadow_verdict(
+        outcome,
+        candidate.number,
+        &run,
+        &budget,
+        block_devices,
+        boot_runtime,
+    );
+}
+
+/// Write the verdict over the request, then report it.
+///
+/// Reported after the write rather than before: the verdict line is a gate's
+/// terminal marker, and an observer that stops reading there must find the
+/// sector already flushed.
+#[cfg(slime_boot_selector)]
+fn record_shadow_verdict(
+    outcome: ShadowOutcome,
+    number: u64,
+    run: &ShadowRun,
+    budget: &ShadowBudget,
+    block_devices: &mut BlockDevices,
+    boot_runtime: &mut boot_selector::BootRuntime,
+) {
+    let recorded = match (boot_runtime.shadow_request(), block_devices.get_mut(0)) {
+        (Some(request), Some(device)) => {
+            let evidence = boot_contracts::bootstate::ShadowEvidence {
+                outcome,
+                unverified: run.unverified() as u32,
+                running: boot_runtime.running_identity(),
+                required: run.required() as u32,
+                exercised: run.exercised() as u32,
+                parked: run.count(ShadowState::Parked) as u32,
+                completed: run.count(ShadowState::Completed) as u32,
+                failed: run.count(ShadowState::Failed) as u32,
+                withheld: run.withheld() as u32,
+                budget_bytes: budget.bytes() as u64,
+                used_bytes: budget.used_bytes() as u64,
+                ..request
+            };
+            match boot_runtime.record_shadow(device, evidence) {
+                Ok(()) => true,
+                Err(error) => {
+                    sel4::debug_println!("SLIME_SHADOW evidence rejected error={error:?}");
+                    false
+                }
+            }
+        }
+        _ => false,
+    };
+    sel4::debug_println!(
+        "SLIME_SHADOW verdict={} number={number} exercised={} required={} parked={} completed={} failed={} withheld={} unverified={} writes={} bytes={} recorded={}",
+        outcome.label(),
+        run.exercised(),
+        run.required(),
+        run.count(ShadowState::Parked),
+        run.count(ShadowState::Completed),
+        run.count(ShadowState::Failed),
+        run.withheld(),
+        run.unverified(),
+        run.console_writes(),
+        budget.used_bytes(),
+        usize::from(recorded),
+    );
+}
+
 fn launch_instance_graph(
     generation: &Generation<'_>,
     admission: &Admission,
@@ -1802,7 +2489,13 @@ fn launch_instance_graph(
     service_endpoint: sel4::cap::Endpoint,
     console_endpoint: sel4::cap::Endpoint,
     block_devices: &mut BlockDevices,
+    clocks: &mut RootClocks,
     #[cfg(slime_boot_selector)] boot_runtime: &mut boot_selector::BootRuntime,
+    // The pending generation this boot shadows, if BootState carries a request
+    // for it; `Err` when the selector refused it.
+    #[cfg(slime_boot_selector)] shadow: Option<
+        Result<Generation<'static>, boot_selector::SelectorError>,
+    >,
 ) {
     let mut tasks = TaskTable::<MAX_TASKS>::new();
     let mut windows = WindowTable::<MAX_WINDOW_ENTRIES>::new();
@@ -1909,6 +2602,19 @@ fn launch_instance_graph(
             "SLIME_GRAPH threads instance={} count={declared_threads}",
             instance.name,
         );
+        // Pages this instance's heap may grow to. Recorded for the same reason
+        // as the thread count: a quota the transcript never states cannot be
+        // told apart from a window that was never reserved.
+        let declared_heap_pages = match generation.instance_heap_pages(instance_index) {
+            Ok(pages) => pages.unwrap_or(0),
+            Err(error) => fatal!("SLIME_GRAPH FAIL heap quota rejected: {error:?}"),
+        };
+        if declared_heap_pages != 0 {
+            sel4::debug_println!(
+                "SLIME_GRAPH heap instance={} pages={declared_heap_pages}",
+                instance.name,
+            );
+        }
         // Each worker's own declared priority (B48). Resolved here rather than
         // in `task::create` so the transcript records what the plan asked for,
         // the same way the main thread's priority is recorded above.
@@ -1930,6 +2636,29 @@ fn launch_instance_graph(
                 instance.name,
             );
         }
+        // And each thread's reservation, recorded for the same reason. Only
+        // an MCS generation declares one.
+        let declared_thread_reservations = match declared_reservations(generation, instance_index) {
+            Ok(reservations) => reservations,
+            Err(error) => fatal!(
+                "SLIME_GRAPH FAIL instance {} declares an unusable reservation: {error:?}",
+                instance.name
+            ),
+        };
+        for (thread_index, reservation) in declared_thread_reservations
+            .iter()
+            .enumerate()
+            .take(declared_threads)
+        {
+            if let Some(reservation) = reservation {
+                sel4::debug_println!(
+                    "SLIME_GRAPH reservation instance={} thread={thread_index} budget_us={} period_us={}",
+                    instance.name,
+                    reservation.budget_us,
+                    reservation.period_us,
+                );
+            }
+        }
         // The child's own TCB and fault endpoint go where the plan declared
         // them. A plan that omits either leaves the root nowhere to install
         // authority the child needs, so it is refused rather than defaulted.
@@ -1984,6 +2713,8 @@ fn launch_instance_graph(
             declared_priority,
             declared_threads,
             declared_worker_priorities,
+            declared_thread_reservations,
+            declared_heap_pages,
         ) {
             Ok(id) => id,
             Err(error) => fatal!(
@@ -2061,7 +2792,7 @@ fn launch_instance_graph(
                 if grant.capability_kind == CapabilityKind::Block {
                     block_index = block_index.saturating_add(1);
                 }
-                declared_capability(grant.capability_kind, device, grant.rights)
+                declared_capability(grant.capability_kind, binding.grant, device, grant.rights)
             };
             let Some(capability) = capability else {
                 fatal!(
@@ -2292,8 +3023,10 @@ fn launch_instance_graph(
         launched_instances.len(),
         budget.as_ref().map_or(0, SharedBufferBudget::holder_count),
     );
-    // The shared filesystem namespaces and their append-only interned scopes.
-    let mut namespaces = directory::Namespaces::new();
+    // The shared filesystem namespaces, their released-when-unnamed pinned
+    // snapshots, and their append-only interned scopes.
+    let namespaces = directory::Namespaces::new();
+    let snapshots = directory::SnapshotTable::new();
     let mut scopes = directory::ScopeTable::new();
 
     // B41: the console dispatcher starts before the service loop, so console
@@ -2308,11 +3041,28 @@ fn launch_instance_graph(
             tasks: &tasks,
             script: input_script(generation.number),
             devices: block_devices,
-            namespaces: &mut namespaces,
+            namespaces: &namespaces,
+            snapshots: &snapshots,
             scopes: &scopes,
         },
     );
 
+    // Started last, so the running graph has every resource it asked for
+    // before the shadow carves its half of what is left.
+    #[cfg(slime_boot_selector)]
+    let mut shadow = match shadow {
+        Some(candidate) => launch_shadow(
+            candidate,
+            allocator,
+            scratch,
+            service_endpoint,
+            block_devices,
+            clocks,
+            boot_runtime,
+        ),
+        None => None,
+    };
+
     serve_instance_graph(
         generation,
         &mut launched_instances,
@@ -2324,11 +3074,16 @@ fn launch_instance_graph(
         allocator,
         scratch,
         admission.fabric_capability_slots,
+        &namespaces,
+        &snapshots,
         &mut scopes,
+        clocks,
         #[cfg(slime_boot_selector)]
         block_devices,
         #[cfg(slime_boot_selector)]
         boot_runtime,
+        #[cfg(slime_boot_selector)]
+        &mut shadow,
     );
 }
 
@@ -2429,14 +3184,32 @@ fn serve_instance_graph(
     // The block devices, needed here only by the selector variant's promotion
     // path. Component block traffic reaches the console thread, which owns
     // the tables (B43), so the service loop no longer touches them.
-    // Interned directory scopes. Derive is the only writer and stays here,
-    // because it also writes the caller's capability table, which this loop
-    // writes on `cap_drop` and on a spawn's result (B45).
+    // The namespace roots, read here only to check a snapshot's expected root;
+    // the console dispatcher commits them, and each root is published so this
+    // read never sees one half-written.
+    namespaces: &directory::Namespaces,
+    // Pinned snapshot roots and interned directory scopes. Snapshot and derive
+    // are their only writers and stay here, because both also write the
+    // caller's capability table, which this loop writes on `cap_drop` and on a
+    // spawn's result (B45). A pin is released here too, once `cap_drop` or a
+    // full table finds nothing naming it.
+    snapshots: &directory::SnapshotTable,
     scopes: &mut directory::ScopeTable,
+    // Every clock a component can be granted. Only `clock_labels` operations
+    // reach it, each through the caller's own clock capability; the root reads
+    // it itself only to time a shadow's window.
+    clocks: &mut RootClocks,
     #[cfg(slime_boot_selector)] block_devices: &mut BlockDevices,
     #[cfg(slime_boot_selector)] boot_runtime: &mut boot_selector::BootRuntime,
+    // A running shadow boot. Its arrivals share this endpoint and are told
+    // apart by badge; it is concluded here, in the loop, once it has a
+    // verdict.
+    #[cfg(slime_boot_selector)] shadow: &mut Option<Shadow>,
 ) {
     let mut terminations = supervision::Terminations::new();
+    // Where each instance's threads sit, kept across its tasks so that a
+    // supervised restart comes back where its supervisor left it.
+    let mut placements = schedule::Placements::new();
     let mut healthy_emitted = false;
 
     sel4::debug_println!(
@@ -2461,9 +3234,27 @@ fn serve_instance_graph(
         })
         .count();
     let mut completed_required = [false; generation::MAX_ADMITTED_INSTANCES];
+    let mut health = health_table(generation);
+    #[cfg(slime_boot_selector)]
+    let mut health_concluded = false;
     for _ in 0..MAX_GRAPH_ITERATIONS {
         iterations += 1;
-        if live == 0 {
+        health.tick();
+        #[cfg(slime_boot_selector)]
+        if let Some(running) = shadow.as_mut() {
+            if let Some(outcome) = running.run.verdict(shadow_now(clocks))
+                && let Some(concluded) = shadow.take()
+            {
+                finish_shadow(concluded, outcome, allocator, block_devices, boot_runtime);
+            }
+        }
+        // A shadow still running keeps the loop alive past the running
+        // graph's last task: its arrivals are the only way it concludes.
+        #[cfg(slime_boot_selector)]
+        let quiescent = live == 0 && shadow.is_none();
+        #[cfg(not(slime_boot_selector))]
+        let quiescent = live == 0;
+        if quiescent {
             sel4::debug_println!(
                 "SLIME_ROOT allocator quiescent live_slots={} live_objects={} live_bytes={}",
                 allocator.live_slots(),
@@ -2492,6 +3283,18 @@ fn serve_instance_graph(
             ipc::reply(Response::error(IpcError::InvalidOperation));
             continue;
         };
+        #[cfg(slime_boot_selector)]
+        if shadow::is_shadow_task(id) {
+            if let Some(running) = shadow.as_mut() {
+                serve_shadow_arrival(running, id, arrival, &reception);
+                if let Some(outcome) = running.run.verdict(shadow_now(clocks))
+                    && let Some(concluded) = shadow.take()
+                {
+                    finish_shadow(concluded, outcome, allocator, block_devices, boot_runtime);
+                }
+            }
+            continue;
+        }
         if tasks.get(id).is_none() {
             sel4::debug_println!("SLIME_GRAPH unknown task badge={badge:#x} rejected");
             ipc::reply(Response::error(IpcError::InvalidOperation));
@@ -2502,6 +3305,15 @@ fn serve_instance_graph(
                 && let Ok(instance) = generation.instance(instance_index)
                 && instance.health == InstanceHealth::Required
             {
+                health.faulted(instance_index);
+                #[cfg(slime_boot_selector)]
+                conclude_health(
+                    &health,
+                    generation,
+                    &mut health_concluded,
+                    block_devices,
+                    boot_runtime,
+                );
                 fatal!("SLIME_GRAPH FAIL required instance {} fault", instance.name)
             }
             let reason = match fault::decode_fault(&info) {
@@ -2533,6 +3345,7 @@ fn serve_instance_graph(
             }
             reclaim_dead_task(buffers, allocator, id);
             windows.release(id);
+            forget_clock_waits(clocks, id);
             reclaim_task_objects(launched, tasks, allocator, &mut reclaimed_slots, id);
             live -= 1;
             continue;
@@ -2597,6 +3410,24 @@ fn serve_instance_graph(
                     &words,
                 ));
             }
+            // M6.3: pin the caller's current root as a read-only snapshot.
+            directory_labels::SNAPSHOT => {
+                // A full table may only be full of pins nothing names any
+                // more: a task reclaimed whole drops its snapshots without a
+                // `cap_drop` for each.
+                if snapshots.len() == directory::MAX_SNAPSHOTS {
+                    release_unnamed_snapshots(tasks, snapshots);
+                }
+                ipc::reply(directory::serve_directory_snapshot(
+                    tasks,
+                    namespaces,
+                    snapshots,
+                    windows.bound(id, descriptor_thread(words[1])),
+                    scratch,
+                    id,
+                    &words,
+                ));
+            }
             // P5.4.2c: sectors, mediated.
             //
             // The root owns the driver because it owns the device untyped and
@@ -2611,11 +3442,22 @@ fn serve_instance_graph(
             lifecycle_labels::EXIT => {
                 let status = words[0] as i64;
                 sel4::debug_println!("SLIME_GRAPH component exit task={} status={status}", id.0);
+                if let Some(instance_index) = tasks.get(id).and_then(|task| task.instance) {
+                    health.exited(instance_index, status);
+                }
                 if status != 0
                     && let Some(instance_index) = tasks.get(id).and_then(|task| task.instance)
                     && let Ok(instance) = generation.instance(instance_index)
                     && instance.health == InstanceHealth::Required
                 {
+                    #[cfg(slime_boot_selector)]
+                    conclude_health(
+                        &health,
+                        generation,
+                        &mut health_concluded,
+                        block_devices,
+                        boot_runtime,
+                    );
                     fatal!(
                         "SLIME_GRAPH FAIL required instance {} exit status={status}",
                         instance.name
@@ -2641,6 +3483,7 @@ fn serve_instance_graph(
                 }
                 reclaim_dead_task(buffers, allocator, id);
                 windows.release(id);
+                forget_clock_waits(clocks, id);
                 reclaim_task_objects(launched, tasks, allocator, &mut reclaimed_slots, id);
                 live -= 1;
             }
@@ -2670,6 +3513,7 @@ fn serve_instance_graph(
                     id,
                     &words,
                     &mut spawns,
+                    &mut placements,
                 );
                 if response.result >= 0 {
                     live += 1;
@@ -2701,6 +3545,12 @@ fn serve_instance_graph(
                     let capability = table.get(slot)?;
                     table.drop_slot(slot).then_some(capability)
                 });
+                // The last capability naming a snapshot releases its pin.
+                if let Some(graph::CapabilityEntry::Directory(view)) = dropped
+                    && view.namespace >= directory::SNAPSHOT_NAMESPACE_BASE
+                {
+                    release_unnamed_snapshots(tasks, snapshots);
+                }
                 ipc::reply(if dropped.is_some() {
                     drops += 1;
                     Response::success(0, 0)
@@ -2961,6 +3811,91 @@ fn serve_instance_graph(
             capability_table_labels::BOOT_ACTION => {
                 ipc::reply(Response::success(generation.boot_action.id() as i64, 0));
             }
+            // Grow the caller's own heap window. Self-scoped by badge: the
+            // window, its quota and the arena the frames come from are all the
+            // caller's task's, fixed when it was built, so the one operand is
+            // how many pages more. A growth past the quota maps nothing and is
+            // answered as exhaustion, the class every declared bound uses.
+            //
+            // The refusal is a structured reply, not a fault: the auxiliary
+            // word carries the pages the window held and the quota it was held
+            // to, so the component learns which bound it met and decides for
+            // itself what an allocation failure means. Nothing about the task
+            // changes -- it is not suspended, and the budget is not spent.
+            memory_labels::HEAP_GROW => {
+                let requested = words[0] as usize;
+                let response = match tasks.grow_heap(allocator, id, requested) {
+                    Ok(mapped) => {
+                        if requested != 0 {
+                            sel4::debug_println!(
+                                "SLIME_GRAPH heap grown task={} pages={mapped}",
+                                id.0
+                            );
+                        }
+                        Response::success(
+                            child_vspace::HEAP_WINDOW_BASE as i64,
+                            mapped as sel4::Word,
+                        )
+                    }
+                    Err(error) => match tasks.get(id) {
+                        Some(task) => {
+                            let (held, budget) = (task.vspace.heap_mapped, task.vspace.heap_pages);
+                            if matches!(
+                                error,
+                                task::TaskError::VSpace(
+                                    child_vspace::VSpaceError::HeapQuota { .. }
+                                )
+                            ) {
+                                sel4::debug_println!(
+                                    "SLIME_GRAPH heap refused task={} held={held} requested={requested} budget={budget}",
+                                    id.0
+                                );
+                            } else {
+                                sel4::debug_println!(
+                                    "SLIME_GRAPH heap growth refused task={} error={error:?}",
+                                    id.0
+                                );
+                            }
+                            Response::refusal(
+                                IpcError::DestinationSlotsExhausted,
+                                pack_heap_refusal(held as u32, budget as u32),
+                            )
+                        }
+                        None => Response::error(IpcError::BadCapability),
+                    },
+                };
+                ipc::reply(response);
+            }
+            // The caller's private memory, counted the way
+            // `capability_table_labels::OCCUPANCY` counts slots: self-scoped by
+            // badge, operand ignored, the peak the root's own rather than a
+            // reader's. Private pages are every frame mapped for this task
+            // alone; shared buffers are `shared_buffer_labels::OCCUPANCY`'s.
+            //
+            // Unlike the slot query this one ships its budget. `heapPages` is
+            // declared per instance, so the caller learns its own bound and no
+            // generation-wide fact.
+            memory_labels::OCCUPANCY => {
+                let response = match tasks.get(id) {
+                    Some(task) => Response::success(
+                        0,
+                        pack_memory_occupancy(
+                            task.memory.live(),
+                            task.memory.peak(),
+                            task.vspace.heap_mapped as u32,
+                            task.vspace.heap_pages as u32,
+                        ),
+                    ),
+                    None => Response::error(IpcError::BadCapability),
+                };
+                ipc::reply(response);
+            }
+            // Time, for a caller holding a clock capability and for no one
+            // else. The slot names the clock; the grant's kind says which one
+            // answers and its rights whether it may be moved.
+            clock_labels::NOW | clock_labels::WAIT | clock_labels::ADVANCE => {
+                ipc::reply(serve_clock(tasks, clocks, id, label, &words));
+            }
             capability_transfer_labels::EXPORT => {
                 ipc::reply(serve_capability_export(
                     generation, launched, allocator, tasks, id, &words,
@@ -2991,6 +3926,57 @@ fn serve_instance_graph(
             supervision_labels::DERIVE => {
                 ipc::reply(serve_supervision_derive(tasks, id, &words));
             }
+            // Move a supervised child within the caller's controlled ceiling:
+            // its main-thread priority, or its class. The generation declares
+            // both the child's priorities and the caller's ceiling; these only
+            // move the child inside what the two already allow, and the
+            // placement is kept for the instance, so a restart cannot shed it.
+            supervision_labels::SET_PRIORITY | supervision_labels::SET_CLASS => {
+                ipc::reply(serve_supervision_schedule(
+                    generation,
+                    tasks,
+                    &mut placements,
+                    id,
+                    label,
+                    &words,
+                ));
+            }
+            // Stop a supervised child its parent has given up on: a migrator
+            // that never answers, say. It is torn down as a faulted child is,
+            // and its termination recorded for the handle to collect.
+            supervision_labels::KILL => {
+                match killable_child(generation, tasks, &terminations, id, &words) {
+                    Ok(child) => {
+                        sel4::debug_println!(
+                            "SLIME_GRAPH supervision killed task={} child={}",
+                            id.0,
+                            child.0
+                        );
+                        record_termination(
+                            &mut terminations,
+                            tasks,
+                            child,
+                            supervision::Termination::Timeout,
+                        );
+                        if let Some(task) = tasks.get(child) {
+                            let _ = task.suspend();
+                        }
+                        reclaim_dead_task(buffers, allocator, child);
+                        windows.release(child);
+                        forget_clock_waits(clocks, child);
+                        reclaim_task_objects(
+                            launched,
+                            tasks,
+                            allocator,
+                            &mut reclaimed_slots,
+                            child,
+                        );
+                        live -= 1;
+                        ipc::reply(Response::success(0, 0));
+                    }
+                    Err(error) => ipc::reply(Response::error(error)),
+                }
+            }
             // Emit a component's diagnostic line as one uninterruptible unit
             // (B18).
             //
@@ -3188,6 +4174,21 @@ fn serve_instance_graph(
                 };
                 ipc::reply(response);
             }
+            // Only the graph's declared fabric holder reports a route answered:
+            // it brokers the route, so it saw the server's reply go by. The
+            // server's own report would be the candidate vouching for itself.
+            lifecycle_labels::ROUTE_ANSWERED => {
+                let broker = launched
+                    .instance_for_task(id)
+                    .is_some_and(|instance| ipc::is_declared_fabric_holder(generation, instance));
+                ipc::reply(if !broker {
+                    Response::error(IpcError::BadCapability)
+                } else if health.route_answered(words[0] as usize) {
+                    Response::success(0, 0)
+                } else {
+                    Response::error(IpcError::InvalidOperation)
+                });
+            }
             shared_buffer_labels::UNMAP
             | shared_buffer_labels::SEAL
             | shared_buffer_labels::RELEASE => {
@@ -3293,20 +4294,22 @@ fn serve_instance_graph(
                     .tasks()
                     .any(|task| task.instance == Some(instance_index))
                 {
+                    health.started(instance_index);
                     live_required += 1;
                 }
             }
+            // Before the healthy markers, as confirmation always was: a
+            // generation declaring only `RUNNING` reaches its verdict on the
+            // iteration the count below first balances.
+            #[cfg(slime_boot_selector)]
+            conclude_health(
+                &health,
+                generation,
+                &mut health_concluded,
+                block_devices,
+                boot_runtime,
+            );
             if live_required + completed == required && (!healthy_emitted || live_required == 0) {
-                #[cfg(slime_boot_selector)]
-                if !healthy_emitted && boot_runtime.running_pending() {
-                    let device = block_devices
-                        .get_mut(0)
-                        .unwrap_or_else(|| fatal!("boot promotion has no boot device"));
-                    match boot_runtime.confirm(device) {
-                        Ok(()) => sel4::debug_println!("SLIME_BOOT promoted"),
-                        Err(error) => fatal!("boot promotion rejected: {error:?}"),
-                    }
-                }
                 if completed == 0 {
                     let digest = generation.identity;
                     sel4::debug_println!(
@@ -3358,6 +4361,31 @@ fn serve_instance_graph(
             "SLIME_GRAPH exhausted live={live} iterations={iterations} certified=1"
         );
     }
+    // Whatever the boot did not observe it will not observe now: open probes
+    // fail, and a candidate that never reached a verdict is recorded as
+    // having failed rather than left to spend its next attempt unexplained.
+    health.expire();
+    #[cfg(slime_boot_selector)]
+    conclude_health(
+        &health,
+        generation,
+        &mut health_concluded,
+        block_devices,
+        boot_runtime,
+    );
+    // The loop ran out with the shadow still open, which only a running graph
+    // that exhausted its own bound can cause. The window did not elapse, but
+    // no further arrival will be served, so the run is over all the same.
+    #[cfg(slime_boot_selector)]
+    if let Some(concluded) = shadow.take() {
+        finish_shadow(
+            concluded,
+            ShadowOutcome::TimedOut,
+            allocator,
+            block_devices,
+            boot_runtime,
+        );
+    }
     sel4::debug_println!(
         "SLIME_GRAPH served live={live} unsupported={unsupported} buffers={buffers_served} windows={} tasks={}",
         windows.len(),
@@ -3427,6 +4455,110 @@ fn serve_instance_graph(
     }
 }
 
+/// Release every pinned snapshot no capability names any more (M6.3).
+///
+/// A snapshot is named by Directory capabilities in task tables and by ones in
+/// flight as exports, and by nothing else, so those two censuses decide it.
+/// Both are written only by this dispatcher, which is also the only pinner.
+fn release_unnamed_snapshots(tasks: &TaskTable<MAX_TASKS>, snapshots: &directory::SnapshotTable) {
+    let exports = unsafe { &*ptr::addr_of!(CAPABILITY_EXPORTS) };
+    let released = snapshots.release_unnamed(|namespace| {
+        let names = |capability: &graph::CapabilityEntry| match capability {
+            graph::CapabilityEntry::Directory(view) => view.namespace == namespace,
+            _ => false,
+        };
+        tasks.tasks().any(|task| {
+            task.capabilities
+                .slots()
+                .any(|(_, entry)| entry.is_some_and(names))
+        }) || exports
+            .entries
+            .iter()
+            .flatten()
+            .any(|export| names(&export.capability))
+    });
+    if released > 0 {
+        sel4::debug_println!(
+            "SLIME_GRAPH directory snapshots released={released} pinned={}",
+            snapshots.len(),
+        );
+    }
+}
+
+/// The health table for the graph about to be served: the declared probes of
+/// every autostart instance, with each route resolved to its graph index.
+///
+/// Autostart only, matching the required count the loop certifies against:
+/// an instance the root does not launch has nothing to observe until a
+/// component spawns it, and its `RUNNING` probe would otherwise fail every boot.
+fn health_table(generation: &Generation<'_>) -> health::HealthTable {
+    let mut table = health::HealthTable::new();
+    for index in 0..generation.health_count() {
+        let probe = generation
+            .health_probe(index)
+            .unwrap_or_else(|error| fatal!("SLIME_GRAPH FAIL health record rejected: {error:?}"));
+        if !generation
+            .instance(probe.instance)
+            .is_ok_and(|instance| instance.autostart)
+        {
+            continue;
+        }
+        let route = generation::health_probe_route(generation, &probe)
+            .unwrap_or_else(|error| fatal!("SLIME_GRAPH FAIL health route rejected: {error:?}"));
+        if let Err(error) = table.declare(probe, route) {
+            fatal!("SLIME_GRAPH FAIL health table rejected: {error:?}")
+        }
+    }
+    table
+}
+
+/// Persist the health table's verdict on a running candidate, and promote it
+/// on a pass.
+///
+/// Once per boot, and only once the table has reached a verdict: a failure is
+/// recorded and left for the next selection to roll back, a pass is recorded
+/// and then confirmed, so promotion always rests on a verdict the device holds.
+#[cfg(slime_boot_selector)]
+fn conclude_health(
+    health: &health::HealthTable,
+    generation: &Generation<'_>,
+    concluded: &mut bool,
+    block_devices: &mut BlockDevices,
+    boot_runtime: &mut boot_selector::BootRuntime,
+) {
+    if *concluded || !boot_runtime.running_pending() {
+        return;
+    }
+    let Some(verdict) = health.verdict(generation.identity, boot_runtime.release_sequence()) else {
+        return;
+    };
+    *concluded = true;
+    let device = block_devices
+        .get_mut(0)
+        .unwrap_or_else(|| fatal!("boot verdict has no boot device"));
+    let recorded = boot_runtime.record_verdict(device, verdict);
+    sel4::debug_println!(
+        "SLIME_BOOT verdict={} probes={} passed={} failed={} ticks={} failed_instance={} failed_probe={} recorded={}",
+        verdict.outcome.label(),
+        verdict.probe_count,
+        verdict.passed,
+        verdict.failed,
+        verdict.ticks,
+        verdict.failed_instance.map_or(-1, i64::from),
+        verdict.failed_probe,
+        usize::from(recorded.is_ok()),
+    );
+    if let Err(error) = recorded {
+        fatal!("boot verdict rejected: {error:?}")
+    }
+    if verdict.outcome == HealthOutcome::Passed {
+        match boot_runtime.confirm(device) {
+            Ok(()) => sel4::debug_println!("SLIME_BOOT promoted"),
+            Err(error) => fatal!("boot promotion rejected: {error:?}"),
+        }
+    }
+}
+
 /// Bytes one encoded spawn-grant record occupies in the caller's transfer
 /// window: a slot word, then a rights word.
 ///
@@ -3438,7 +4570,7 @@ use slime_proto::syscall_abi::{
     GRANT_RECORD_BYTES as SPAWN_GRANT_RECORD_BYTES, GRANT_RIGHTS_OFFSET, GRANT_SLOT_OFFSET,
 };
 // B59: the capability-rights vocabulary is generated from
-// `contracts/generation/v5/schema.zt`; these were local copies of the same
+// `contracts/generation/v6/schema.zt`; these were local copies of the same
 // bit numbering.
 use boot_contracts::generation::{
     RIGHT_BUFFER_CREATE, RIGHT_BUFFER_MAP, RIGHT_BUFFER_WRITE, RIGHT_SPAWN, RIGHT_SUPERVISE,
@@ -3766,6 +4898,7 @@ fn construct_child(
     console_endpoint: sel4::cap::Endpoint,
     parent: TaskId,
     plan: &SpawnPlan,
+    placement: schedule::Placement,
 ) -> Result<TaskId, IpcError> {
     let record = generation
         .executable(plan.executable)
@@ -3787,6 +4920,11 @@ fn construct_child(
     let elf = aligned.hold(elf).map_err(|_| IpcError::InvalidLength)?;
     let image = ChildImage::parse(elf).map_err(|_| IpcError::BadCapability)?;
     let authority = bound_authority(generation, instance).map_err(|_| IpcError::BadCapability)?;
+    // Where the generation put each thread, then where its placement puts
+    // them: a child whose supervisor moved an earlier task of the same
+    // instance starts where that one was left.
+    let declared = declared_priorities(generation, plan.instance)?;
+    let placed = placement.priorities(declared);
 
     let id = tasks
         .create(
@@ -3829,21 +4967,24 @@ fn construct_child(
             // As the boot path: a spawned child is a declared instance, so its
             // priority comes from the same plan, and is recorded for the same
             // reason -- a priority nothing reports is indistinguishable from
-            // the constant it replaced (B48).
+            // the constant it replaced (B48). A placement that moved it is
+            // recorded beside the declared figure rather than in its place.
             {
-                let priority = match generation.instance_priority(plan.instance) {
-                    Ok(Some(priority)) => sel4::Word::from(priority),
-                    Ok(None) => task::CHILD_PRIORITY,
-                    Err(_) => return Err(IpcError::BadCapability),
-                };
                 sel4::debug_println!(
-                    "SLIME_GRAPH schedule instance={} priority={priority} default={}",
-                    generation
-                        .instance(plan.instance)
-                        .map_or("<unknown>", |record| record.name),
+                    "SLIME_GRAPH schedule instance={} priority={} default={}",
+                    instance.name,
+                    declared[0],
                     task::CHILD_PRIORITY,
                 );
-                priority
+                if placement != schedule::Placement::DECLARED {
+                    sel4::debug_println!(
+                        "SLIME_GRAPH schedule spawned instance={} class={} priority={}",
+                        instance.name,
+                        placement.class().code(),
+                        placed[0],
+                    );
+                }
+                sel4::Word::from(placed[0])
             },
             // As the boot path: the thread count comes from the same plan, so
             // a spawned instance declaring a worker gets one (B47).
@@ -3852,23 +4993,17 @@ fn construct_child(
                 Ok(None) => 1,
                 Err(_) => return Err(IpcError::BadCapability),
             },
-            // As the boot path: each worker's own declared priority (B48).
-            {
-                let main_priority = match generation.instance_priority(plan.instance) {
-                    Ok(Some(priority)) => sel4::Word::from(priority),
-                    Ok(None) => task::CHILD_PRIORITY,
-                    Err(_) => return Err(IpcError::BadCapability),
-                };
-                let mut priorities = [main_priority; child_vspace::MAX_CHILD_THREADS];
-                for (thread_index, slot) in priorities.iter_mut().enumerate().skip(1) {
-                    match generation.thread_priority(plan.instance, thread_index) {
-                        Ok(Some(priority)) => *slot = sel4::Word::from(priority),
-                        Ok(None) => {}
-                        Err(_) => return Err(IpcError::BadCapability),
-                    }
-                }
-                priorities
-            },
+            // As the boot path: each worker's own declared priority (B48),
+            // as placed.
+            placed.map(sel4::Word::from),
+            // As the boot path: each thread's reservation. A placement moves
+            // priorities only, so the generation's figures stand.
+            declared_reservations(generation, plan.instance)?,
+            // As the boot path: the heap quota comes from the same plan.
+            generation
+                .instance_heap_pages(plan.instance)
+                .map_err(|_| IpcError::BadCapability)?
+                .unwrap_or(0),
         )
         .map_err(|_| IpcError::DestinationSlotsExhausted)?;
 
@@ -3956,7 +5091,8 @@ fn construct_child(
         if grant.capability_kind == CapabilityKind::Block {
             block_index = block_index.saturating_add(1);
         }
-        let Some(capability) = declared_capability(grant.capability_kind, device, grant.rights)
+        let Some(capability) =
+            declared_capability(grant.capability_kind, binding.grant, device, grant.rights)
         else {
             continue;
         };
@@ -4030,6 +5166,54 @@ fn spawner_budget(
         .map_or(0, |record| usize::from(record.spawn_budget))
 }
 
+/// What the generation declares for each of `instance`'s threads, main first.
+///
+/// The main thread defaults to [`task::CHILD_PRIORITY`] and a worker to its
+/// main thread, exactly as construction always read them.
+fn declared_priorities(
+    generation: &Generation<'_>,
+    instance: usize,
+) -> Result<[u32; child_vspace::MAX_CHILD_THREADS], IpcError> {
+    let main = match generation.instance_priority(instance) {
+        Ok(Some(priority)) => priority,
+        Ok(None) => task::CHILD_PRIORITY as u32,
+        Err(_) => return Err(IpcError::BadCapability),
+    };
+    let mut priorities = [main; child_vspace::MAX_CHILD_THREADS];
+    for (thread_index, slot) in priorities.iter_mut().enumerate().skip(1) {
+        match generation.thread_priority(instance, thread_index) {
+            Ok(Some(priority)) => *slot = priority,
+            Ok(None) => {}
+            Err(_) => return Err(IpcError::BadCapability),
+        }
+    }
+    Ok(priorities)
+}
+
+/// The CPU reservation the generation declares for each of `instance`'s
+/// threads, main first (B48's MCS half).
+///
+/// A thread declaring none reads as `None`. Figures the root could not run are
+/// refused here, before anything is allocated. Whether the kernel can run one
+/// at all is `task::admit_reservation`'s to decide.
+fn declared_reservations(
+    generation: &Generation<'_>,
+    instance: usize,
+) -> Result<[Option<schedule::Reservation>; child_vspace::MAX_CHILD_THREADS], IpcError> {
+    let mut reservations = [None; child_vspace::MAX_CHILD_THREADS];
+    for (thread_index, slot) in reservations.iter_mut().enumerate() {
+        match generation.thread_schedule(instance, thread_index) {
+            Ok(Some(declared)) => {
+                *slot = schedule::Reservation::admit(declared.budget_us, declared.period_us)
+                    .map_err(|_| IpcError::BadCapability)?;
+            }
+            Ok(None) => {}
+            Err(_) => return Err(IpcError::BadCapability),
+        }
+    }
+    Ok(reservations)
+}
+
 /// Serve one `spawn`: validate, construct, activate, and hand the parent a
 /// supervision handle.
 #[allow(clippy::too_many_arguments)]
@@ -4046,8 +5230,15 @@ fn serve_spawn(
     id: TaskId,
     words: &[sel4::Word; ipc::FAST_MESSAGE_REGISTERS],
     spawns: &mut usize,
+    placements: &mut schedule::Placements,
 ) -> Response {
     let executable_slot = words[0] as u32;
+    // The class the caller asks the child to run in, in the high half of the
+    // slot word. Zero is the declared class, so a caller that predates the
+    // field asks for what it always got.
+    let Some(class) = schedule::SchedulingClass::decode(words[0] >> 32) else {
+        return Response::error(IpcError::InvalidOperation);
+    };
     // The wide reader (B15), because a grant array is not a message: at
     // `SPAWN_GRANT_RECORD_BYTES` each, the message bound admitted four records
     // where the oracle admits sixty-four. It refuses a descriptor naming any
@@ -4130,6 +5321,7 @@ fn serve_spawn(
         id.0,
         plan.count,
     );
+    let placement = placements.get(plan.instance).at_spawn(class);
 
     let child = match construct_child(
         generation,
@@ -4142,6 +5334,7 @@ fn serve_spawn(
         console_endpoint,
         id,
         &plan,
+        placement,
     ) {
         Ok(child) => child,
         Err(error) => {
@@ -4274,6 +5467,9 @@ fn serve_spawn(
             matches!(capability, graph::CapabilityEntry::BufferFactory(_))
         })
         .count();
+    // Kept only once the child runs: a spawn refused above leaves whatever an
+    // earlier run of the instance was placed at.
+    placements.record(plan.instance, placement);
     *spawns += 1;
     sel4::debug_println!(
         "SLIME_GRAPH spawned task={} child={} component={name} grants={} endpoints={copied} notifications={notification_copied} handle={handle} supervision_grants={supervision_grants} buffer_factory_grants={buffer_factory_grants}",
@@ -4351,6 +5547,184 @@ fn serve_supervision_derive(
     Response::success(0, sel4::Word::from(derived))
 }
 
+/// The live child a `KILL` may stop: one the caller supervises through the
+/// handle in `MR0`. A child that has already ended has nothing left to stop,
+/// and a required instance's end is the boot's to judge, not its parent's.
+///
+/// The termination record is asked first: it outlives the child while the
+/// handle does, so an index a later task reuses is never mistaken for it.
+fn killable_child(
+    generation: &Generation<'_>,
+    tasks: &TaskTable<MAX_TASKS>,
+    terminations: &supervision::Terminations,
+    id: TaskId,
+    words: &[sel4::Word; ipc::FAST_MESSAGE_REGISTERS],
+) -> Result<TaskId, IpcError> {
+    let child = tasks
+        .authority(id)
+        .ok_or(IpcError::InvalidOperation)
+        .and_then(|table| table.resolve_supervision(words[0] as u32, RIGHT_SUPERVISE))
+        .map_err(|_| IpcError::BadCapability)?;
+    if terminations.get(child.task).is_some() {
+        return Err(IpcError::BadCapability);
+    }
+    let task = tasks.get(child.task).ok_or(IpcError::BadCapability)?;
+    if let Some(instance) = task.instance
+        && generation
+            .instance(instance)
+            .is_ok_and(|instance| instance.health == InstanceHealth::Required)
+    {
+        return Err(IpcError::InvalidOperation);
+    }
+    Ok(child.task)
+}
+
+/// Answer one clock operation through the caller's own clock capability.
+///
+/// A slot holding no clock, or one without the right the operation needs, is
+/// refused as any other missing capability is, so a clockless component
+/// learns nothing about time from the refusal either.
+fn serve_clock(
+    tasks: &TaskTable<MAX_TASKS>,
+    clocks: &mut RootClocks,
+    id: TaskId,
+    label: sel4::Word,
+    words: &[sel4::Word; ipc::FAST_MESSAGE_REGISTERS],
+) -> Response {
+    let required = if label == clock_labels::ADVANCE {
+        RIGHT_CLOCK_ADVANCE
+    } else {
+        RIGHT_CLOCK_READ
+    };
+    let Some(capability) = tasks
+        .authority(id)
+        .and_then(|table| table.resolve_clock(words[0] as u32, required).ok())
+    else {
+        return Response::error(IpcError::BadCapability);
+    };
+    // Task indices are reused, and a dead task's waits are forgotten before
+    // its index is, so the index alone names the incarnation.
+    let owner = TaskEpoch::new(id.0, 0);
+    let answered = match label {
+        clock_labels::NOW => clocks
+            .now(capability.clock)
+            .map(|now| Response::success(0, now)),
+        clock_labels::WAIT => clocks
+            .wait(capability.clock, owner, words[1])
+            .map(|outcome| match outcome {
+                clock::WaitOutcome::Reached { now_ns } => Response::success(0, now_ns),
+                clock::WaitOutcome::Pending { now_ns } => {
+                    Response::refusal(IpcError::WouldBlock, now_ns)
+                }
+            }),
+        _ => clocks
+            .advance(capability.clock, words[1])
+            .map(|released| Response::success(released as i64, words[1])),
+    };
+    answered.unwrap_or_else(|error| {
+        sel4::debug_println!(
+            "SLIME_GRAPH clock refused task={} clock={:?} error={error:?}",
+            id.0,
+            capability.clock,
+        );
+        Response::error(IpcError::InvalidOperation)
+    })
+}
+
+/// Drop a dead task's clock waits, so the next task at its index inherits none.
+fn forget_clock_waits(clocks: &mut RootClocks, id: TaskId) {
+    if let Err(error) = clocks.forget(TaskEpoch::new(id.0, 0)) {
+        sel4::debug_println!("SLIME_GRAPH clock waits kept task={} error={error:?}", id.0);
+    }
+}
+
+/// Serve `SET_PRIORITY` and `SET_CLASS`: move a supervised child within the
+/// caller's controlled ceiling.
+///
+/// Two capabilities, because they are two questions. The supervision handle
+/// says which child; the `SchedulingControl` capability says the caller may
+/// move anything at all. Neither alone suffices: a parent holds a handle for
+/// every child it spawns, and a scheduling grant names no child.
+fn serve_supervision_schedule(
+    generation: &Generation<'_>,
+    tasks: &TaskTable<MAX_TASKS>,
+    placements: &mut schedule::Placements,
+    id: TaskId,
+    label: sel4::Word,
+    words: &[sel4::Word; ipc::FAST_MESSAGE_REGISTERS],
+) -> Response {
+    let slot = words[0] as u32;
+    let Ok(child) = tasks
+        .authority(id)
+        .ok_or(IpcError::InvalidOperation)
+        .and_then(|table| {
+            table.resolve_scheduling_control(words[1] as u32)?;
+            table.resolve_supervision(slot, RIGHT_SUPERVISE)
+        })
+    else {
+        return Response::error(IpcError::BadCapability);
+    };
+    // A handle outlives its task, and an exited child has nothing to move.
+    let Some(instance) = tasks.get(child.task).and_then(|task| task.instance) else {
+        return Response::error(IpcError::BadCapability);
+    };
+    // The caller's MCP, as seL4 would bound it, and never above the priority
+    // the root gives an undeclared child: the root's own TCB is the kernel
+    // authority here, and it must not lend out more than it would use itself.
+    let ceiling = match tasks
+        .get(id)
+        .and_then(|task| task.instance)
+        .map(|caller| generation.instance_max_controlled_priority(caller))
+    {
+        Some(Ok(Some(ceiling))) => ceiling.min(task::CHILD_PRIORITY as u32),
+        _ => return Response::error(IpcError::BadCapability),
+    };
+    let declared = match declared_priorities(generation, instance) {
+        Ok(declared) => declared,
+        Err(error) => return Response::error(error),
+    };
+    let current = placements.get(instance);
+    let candidate = match label {
+        supervision_labels::SET_PRIORITY => match u32::try_from(words[2]) {
+            Ok(priority) => current.with_priority(priority, ceiling, declared),
+            Err(_) => return Response::error(IpcError::InvalidOperation),
+        },
+        _ => match schedule::SchedulingClass::decode(words[2]) {
+            Some(class) => current.with_class(class, ceiling, declared),
+            None => return Response::error(IpcError::InvalidOperation),
+        },
+    };
+    let placement = match candidate {
+        Ok(placement) => placement,
+        Err(schedule::ScheduleError::AboveCeiling { priority, ceiling }) => {
+            sel4::debug_println!(
+                "SLIME_GRAPH schedule refused task={} child={} priority={priority} ceiling={ceiling}",
+                id.0,
+                child.task.0,
+            );
+            return Response::refusal(IpcError::InvalidOperation, sel4::Word::from(ceiling));
+        }
+    };
+    let placed = placement.priorities(declared);
+    if let Err(error) = tasks.place(child.task, &placed.map(sel4::Word::from)) {
+        sel4::debug_println!(
+            "SLIME_GRAPH schedule failed task={} child={} error={error:?}",
+            id.0,
+            child.task.0,
+        );
+        return Response::error(IpcError::InvalidOperation);
+    }
+    placements.record(instance, placement);
+    sel4::debug_println!(
+        "SLIME_GRAPH schedule placed task={} child={} class={} priority={}",
+        id.0,
+        child.task.0,
+        placement.class().code(),
+        placed[0],
+    );
+    Response::success(i64::from(placed[0]), placement.class().code())
+}
+
 fn record_termination(
     terminations: &mut supervision::Terminations,
     tasks: &TaskTable<MAX_TASKS>,
@@ -4432,6 +5806,8 @@ fn reclaim_task_objects(
     id: TaskId,
 ) {
     launched.release_by_task(id);
+    let exports = unsafe { &mut *ptr::addr_of_mut!(CAPABILITY_EXPORTS) };
+    exports.forget_receiver(id);
     match tasks.reclaim(allocator, id) {
         Ok(record) => *reclaimed += record.slot_count(),
         Err(error) => sel4::debug_println!(
@@ -4701,6 +6077,16 @@ fn serve_capability_finalize(
     Response::success(0, 0)
 }
 
+/// Claim an export addressed to the caller.
+///
+/// `words[0]` names the export, or is zero for the oldest finalized one. An
+/// oldest-first claim may be narrowed by `words[1]`: its low half is the kind
+/// the caller expects (zero for any), so a service never takes up a loan as
+/// the Directory a request travels with; and bit 32 requires the export's
+/// sender to be the one the caller's previous import came from, so the
+/// capabilities a peer sent together in one message are never mixed with
+/// another peer's. Peer endpoints carry no badge, so this is how a receiver
+/// tells whose authority it is holding.
 fn serve_capability_import(
     allocator: &mut ObjectAllocator,
     tasks: &mut TaskTable<MAX_TASKS>,
@@ -4708,13 +6094,24 @@ fn serve_capability_import(
     words: &[sel4::Word; ipc::FAST_MESSAGE_REGISTERS],
 ) -> Response {
     let id = words[0] as u32;
+    let kind = words[1] as u32;
     let exports = unsafe { &mut *ptr::addr_of_mut!(CAPABILITY_EXPORTS) };
+    let sender = if words[1] >> 32 & 1 != 0 {
+        match exports.last_sender(receiver) {
+            Some(sender) => Some(sender),
+            None => return Response::error(IpcError::BadCapability),
+        }
+    } else {
+        None
+    };
     let id = if id == 0 {
         let Some(oldest) = exports
             .entries
             .iter()
             .flatten()
             .filter(|entry| entry.receiver == receiver && entry.finalized)
+            .filter(|entry| kind == 0 || capability_kind(entry.capability) == kind)
+            .filter(|entry| sender.is_none_or(|sender| entry.sender == sender))
             .map(|entry| entry.id)
             .min()
         else {
@@ -4750,6 +6147,7 @@ fn serve_capability_import(
         return Response::error(IpcError::BadCapability);
     };
     cleanup_export_ticket(allocator, tasks, export);
+    exports.note_import(receiver, export.sender);
     exports.imported = exports.imported.saturating_add(1);
     sel4::debug_println!(
         "SLIME_GRAPH capability imported task={} id={} kind={} rights={:#x} retain={}",
@@ -5282,6 +6680,31 @@ const fn pack_slot_occupancy(declared: u32, declared_peak: u32, populated: u32)
         | occupancy_field(populated) << (OCCUPANCY_FIELD_BITS * 2)
 }
 
+/// Pack one task's private-memory occupancy into the reply's auxiliary word:
+/// private pages live and at their peak, heap pages backed, and the heap
+/// quota, from the low 16 bits up.
+///
+/// All four fit: a child holds at most `MAX_CHILD_IMAGE_PAGES` of image, two
+/// pages per thread, and `MAX_HEAP_PAGES` of heap.
+const fn pack_memory_occupancy(
+    private: u32,
+    private_peak: u32,
+    heap: u32,
+    heap_budget: u32,
+) -> sel4::Word {
+    occupancy_field(private)
+        | occupancy_field(private_peak) << OCCUPANCY_FIELD_BITS
+        | occupancy_field(heap) << (OCCUPANCY_FIELD_BITS * 2)
+        | occupancy_field(heap_budget) << (OCCUPANCY_FIELD_BITS * 3)
+}
+
+/// Pack a refused heap growth's evidence: the pages the window held and the
+/// quota it was held to, in the layout `pack_memory_occupancy` gives the same
+/// two counts' low fields.
+const fn pack_heap_refusal(held: u32, budget: u32) -> sel4::Word {
+    occupancy_field(held) | occupancy_field(budget) << OCCUPANCY_FIELD_BITS
+}
+
 /// Answer map/unmap/seal/release for a region the caller already holds.
 ///
 /// Every one resolves through the table, which is where rights and quota live,
@@ -5439,7 +6862,7 @@ const fn buffer_operation_name(operation: BufferLifecycleRequest) -> &'static st
 ///
 /// Requests and faults arrive on the same endpoint object under different
 /// badges, because the non-MCS kernel resolves a thread's fault handler in that
-/// thread's own CSpace; see `task.rs`.
+/// thread's own CSpace, and the MCS profile keeps the same badges; see `task.rs`.
 fn serve(
     endpoint: sel4::cap::Endpoint,
     index: usize,
@@ -5452,7 +6875,7 @@ fn serve(
         if fixtures[index].is_none_or(|fixture| fixture.terminated) {
             return;
         }
-        let (info, badge) = endpoint.recv(());
+        let (info, badge) = endpoint.recv(ipc::service_reply());
         let Some((id, arrival)) = TaskId::from_badge(badge) else {
             sel4::debug_println!("SLIME_ROOT unbadged arrival badge={badge:#x} rejected");
             ipc::reply(Response::error(IpcError::InvalidOperation));
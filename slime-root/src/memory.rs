@@ -0,0 +1,87 @@
+//! Live private-memory occupancy per task, in pages.
+//!
+//! A task's private memory is every frame the root mapped into its VSpace for
+//! it alone: the image, one stack and one IPC buffer per thread, and whatever
+//! heap it has grown ([`crate::child_vspace::grow_heap`]). Shared-buffer pages
+//! are not private — `crate::shared_buffer` charges them to `SharedBufferBudget`
+//! and reports them through its own occupancy operation — so they are not
+//! counted here.
+//!
+//! The *budget* is the heap half only. Construction frames are fixed by the
+//! image and thread count the generation already admitted, so the one private
+//! charge a running component controls is growth, and the generation declares
+//! a ceiling on it per instance (`heapPages`), deny-by-default the way
+//! `SharedBufferBudget` is: an instance that declares none may grow by none.
+//!
+//! Every private page enters through a root operation, so the root accumulates
+//! the count and holds the high-water mark, as [`crate::cspace::CSpaceLedger`]
+//! does for declared installs. Pages leave only with the task today — there is
+//! no release — so live and peak agree for now. The two are kept distinct
+//! because a report that shipped one number would have to change shape the day
+//! a release operation lands.
+
+/// One task's private pages, live and at its high-water mark.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PageLedger {
+    live: u32,
+    peak: u32,
+}
+
+impl PageLedger {
+    pub const EMPTY: Self = Self { live: 0, peak: 0 };
+
+    /// Private pages mapped for the task right now.
+    pub const fn live(&self) -> u32 {
+        self.live
+    }
+
+    /// The most private pages this task ever held at once.
+    pub const fn peak(&self) -> u32 {
+        self.peak
+    }
+
+    /// Credit `pages` newly mapped private pages, raising the high-water mark.
+    ///
+    /// Saturating for the reason [`crate::cspace::CSpaceLedger::installed`] is:
+    /// a wrapped count reads as a task holding nothing.
+    pub const fn mapped(&mut self, pages: u32) {
+        self.live = self.live.saturating_add(pages);
+        if self.live > self.peak {
+            self.peak = self.live;
+        }
+    }
+}
+
+impl Default for PageLedger {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PageLedger;
+
+    #[test]
+    fn the_peak_is_a_high_water_mark_over_credits() {
+        let mut ledger = PageLedger::EMPTY;
+        assert_eq!((ledger.live(), ledger.peak()), (0, 0));
+
+        ledger.mapped(12);
+        assert_eq!((ledger.live(), ledger.peak()), (12, 12), "construction");
+
+        ledger.mapped(3);
+        assert_eq!((ledger.live(), ledger.peak()), (15, 15), "a growth");
+    }
+
+    /// A wrapped credit would read as a task holding no memory, which is the
+    /// one answer a bounded count must never give.
+    #[test]
+    fn credits_saturate_rather_than_wrap() {
+        let mut ledger = PageLedger::EMPTY;
+        ledger.mapped(u32::MAX);
+        ledger.mapped(2);
+        assert_eq!(ledger.live(), u32::MAX);
+        assert_eq!(ledger.peak(), u32::MAX);
+    }
+}
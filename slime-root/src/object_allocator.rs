@@ -26,8 +26,10 @@ pub const MAX_DEVICE_UNTYPEDS: usize = 64;
 pub const MAX_ROOT_CSLOTS: usize = 262_144;
 /// Maximum simultaneously provisioned task-arena parents.
 pub const MAX_TASK_ARENAS: usize = 48;
-/// Root capabilities a single accepted task image can consume.
-pub const MAX_TASK_SLOTS: usize = crate::child_vspace::MAX_CHILD_IMAGE_PAGES + 16;
+/// Root capabilities a single accepted task image can consume, heap window
+/// included: its frames are retyped from the same arena as the image's.
+pub const MAX_TASK_SLOTS: usize =
+    crate::child_vspace::MAX_CHILD_IMAGE_PAGES + crate::child_vspace::MAX_HEAP_PAGES + 16;
 
 const SLOT_WORD_BITS: usize = usize::BITS as usize;
 const SLOT_WORDS: usize = MAX_ROOT_CSLOTS.div_ceil(SLOT_WORD_BITS);
@@ -41,6 +41,9 @@
 //! non-MCS one-shot compare timer shared with nothing, and a scheduling
 //! delay of arbitrary length can still separate the compare condition
 //! becoming true from this task next running.
+//!
+//! This is the product kernel's backing. The MCS profile compiles
+//! `timeout_timer.rs` in its place and exports neither timer register set.
 
 use crate::event::MonotonicInstant;
 use crate::object_allocator::{AllocError, ObjectAllocator};
@@ -0,0 +1,506 @@
+//! Where a spawned child's threads sit in the scheduler, and who may move them.
+//!
+//! The generation fixes each thread's priority and, per instance, the ceiling
+//! its main thread may assign to others (`max_controlled_priority`). The root
+//! applies the priorities at construction; this module is the runtime half.
+//! A supervisor holding a `SchedulingControl` capability may place a child it
+//! holds a supervision handle for, and nothing it does may run any thread of
+//! that child above the supervisor's own declared ceiling — the rule seL4
+//! applies to a thread setting another's priority, applied here to the
+//! logical holder because the kernel call is the root's.
+//!
+//! A placement is kept per *instance*, not per task, and outlives the task: a
+//! supervised restart is a respawn of the same declaration, and a child that
+//! could leave its demotion behind by faulting would make the demotion
+//! advisory. It is the same reasoning `crate::launched` keeps `launched_once`
+//! past collection for.
+//!
+//! Under the MCS kernel profile a thread also has a [`Reservation`]: the
+//! budget and period of the scheduling context it runs on. Priority decides
+//! who runs first. The reservation bounds how long it runs, which a priority
+//! alone cannot do for a thread that never blocks.
+
+use crate::generation::MAX_ADMITTED_INSTANCES as MAX_INSTANCES;
+use slime_proto::syscall_abi::{SCHEDULING_CLASS_BACKGROUND, SCHEDULING_CLASS_DECLARED};
+
+/// The priority every thread of a background child runs at: the lowest seL4
+/// has, so it runs only when nothing declared is runnable.
+pub const BACKGROUND_PRIORITY: u32 = 0;
+
+/// The scheduling class a child runs in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SchedulingClass {
+    /// The priorities the generation declares, or the one a supervisor set.
+    Declared,
+    /// Every thread at [`BACKGROUND_PRIORITY`].
+    Background,
+}
+
+impl SchedulingClass {
+    /// The class a wire code names, or `None` for one this root does not know.
+    /// An unknown class is refused rather than read as `Declared`: a caller
+    /// that asked for something else would otherwise run where it did not ask.
+    pub const fn decode(code: u64) -> Option<Self> {
+        match code {
+            SCHEDULING_CLASS_DECLARED => Some(Self::Declared),
+            SCHEDULING_CLASS_BACKGROUND => Some(Self::Background),
+            _ => None,
+        }
+    }
+
+    pub const fn code(self) -> u64 {
+        match self {
+            Self::Declared => SCHEDULING_CLASS_DECLARED,
+            Self::Background => SCHEDULING_CLASS_BACKGROUND,
+        }
+    }
+}
+
+/// Why a placement was refused.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ScheduleError {
+    /// The placement would run a thread at `priority`, above the ceiling the
+    /// caller's generation declares for it.
+    AboveCeiling { priority: u32, ceiling: u32 },
+}
+
+/// One instance's placement: its class, and the main-thread priority a
+/// supervisor set, if one did.
+///
+/// The two are independent. A supervisor that sends a child to the background
+/// and later brings it back finds the priority it set still in force, rather
+/// than the class change having silently discarded it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Placement {
+    class: SchedulingClass,
+    priority: Option<u32>,
+}
+
+impl Placement {
+    /// Where the generation put it.
+    pub const DECLARED: Self = Self {
+        class: SchedulingClass::Declared,
+        priority: None,
+    };
+
+    pub const fn class(&self) -> SchedulingClass {
+        self.class
+    }
+
+    /// The priority each thread runs at, main first, given what the
+    /// generation declares for each.
+    ///
+    /// A set priority moves the main thread to it and holds every worker at
+    /// or below it: a worker declared beneath its main thread stays where it
+    /// was declared, and one that would now outrank the main thread is
+    /// brought down with it. Raising the main thread never raises a worker.
+    pub fn priorities<const N: usize>(&self, declared: [u32; N]) -> [u32; N] {
+        let mut placed = declared;
+        match (self.class, self.priority) {
+            (SchedulingClass::Background, _) => placed = [BACKGROUND_PRIORITY; N],
+            (SchedulingClass::Declared, None) => {}
+            (SchedulingClass::Declared, Some(priority)) => {
+                for (thread, slot) in placed.iter_mut().enumerate() {
+                    *slot = if thread == 0 {
+                        priority
+                    } else {
+                        (*slot).min(priority)
+                    };
+                }
+            }
+        }
+        placed
+    }
+
+    /// This placement with its main thread set to `priority`.
+    ///
+    /// Refused when `priority` itself is above `ceiling`, even while the
+    /// child is in the background and would not run there yet: the number is
+    /// what the caller asserted, and it takes effect the moment the class
+    /// changes back.
+    pub fn with_priority<const N: usize>(
+        self,
+        priority: u32,
+        ceiling: u32,
+        declared: [u32; N],
+    ) -> Result<Self, ScheduleError> {
+        if priority > ceiling {
+            return Err(ScheduleError::AboveCeiling { priority, ceiling });
+        }
+        Self {
+            priority: Some(priority),
+            ..self
+        }
+        .within(ceiling, declared)
+    }
+
+    /// This placement moved to `class`.
+    ///
+    /// Bounded like [`Self::with_priority`]: returning a child to its declared
+    /// class is refused when that would run one of its threads above the
+    /// caller's ceiling, so a supervisor cannot raise a child past itself by
+    /// restoring priorities its generation gave the child rather than the
+    /// supervisor.
+    pub fn with_class<const N: usize>(
+        self,
+        class: SchedulingClass,
+        ceiling: u32,
+        declared: [u32; N],
+    ) -> Result<Self, ScheduleError> {
+        Self { class, ..self }.within(ceiling, declared)
+    }
+
+    /// The placement a spawn asking for `class` starts the child in.
+    ///
+    /// A spawn may only lower: asking for the background needs no scheduling
+    /// authority, since it only gives time away, but asking for the declared
+    /// class does not lift a recorded demotion — that takes a
+    /// `SchedulingControl` capability. Without this a restart would be a way
+    /// out of the background for any child whose supervisor can spawn.
+    pub const fn at_spawn(self, class: SchedulingClass) -> Self {
+        match class {
+            SchedulingClass::Background => Self {
+                class: SchedulingClass::Background,
+                ..self
+            },
+            SchedulingClass::Declared => self,
+        }
+    }
+
+    fn within<const N: usize>(
+        self,
+        ceiling: u32,
+        declared: [u32; N],
+    ) -> Result<Self, ScheduleError> {
+        let highest = self
+            .priorities(declared)
+            .into_iter()
+            .max()
+            .unwrap_or(BACKGROUND_PRIORITY);
+        if highest > ceiling {
+            return Err(ScheduleError::AboveCeiling {
+                priority: highest,
+                ceiling,
+            });
+        }
+        Ok(self)
+    }
+}
+
+impl Default for Placement {
+    fn default() -> Self {
+        Self::DECLARED
+    }
+}
+
+/// Every instance's placement, kept past the instance's tasks.
+pub struct Placements {
+    entries: [Placement; MAX_INSTANCES],
+}
+
+impl Placements {
+    pub const fn new() -> Self {
+        Self {
+            entries: [Placement::DECLARED; MAX_INSTANCES],
+        }
+    }
+
+    /// Where `instance` runs: [`Placement::DECLARED`] until a supervisor or a
+    /// spawn moved it.
+    pub fn get(&self, instance: usize) -> Placement {
+        self.entries
+            .get(instance)
+            .copied()
+            .unwrap_or(Placement::DECLARED)
+    }
+
+    /// Keep `placement` for `instance`, for its live task and every later one.
+    pub fn record(&mut self, instance: usize, placement: Placement) {
+        if let Some(entry) = self.entries.get_mut(instance) {
+            *entry = placement;
+        }
+    }
+}
+
+impl Default for Placements {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Shortest budget a generation may declare, in microseconds.
+///
+/// The kernel refuses a budget below twice its own worst-case execution time,
+/// which is far less than this. The bound is set so that a budget the kernel
+/// would accept but which could not cover one IPC round trip is refused at
+/// admission instead of surfacing as a thread that never finishes a request.
+/// `build-generation.py` holds manifests to the same figure.
+pub const MIN_BUDGET_US: u64 = 100;
+
+/// Longest period a generation may declare, in microseconds: ten seconds.
+pub const MAX_PERIOD_US: u64 = 10_000_000;
+
+/// The round-robin timeslice a thread with no declared reservation runs
+/// under: seL4's default `KernelTimerTickMS` times `KernelTimeSlice`.
+pub const DEFAULT_TIMESLICE_US: u64 = 5_000;
+
+/// A thread's CPU reservation: at most `budget_us` of execution in every
+/// `period_us`.
+///
+/// A budget equal to its period is a round-robin thread that the kernel
+/// never throttles, only preempts. That is what an undeclared thread gets,
+/// so a generation with no reservations runs under MCS as it does without it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Reservation {
+    pub budget_us: u64,
+    pub period_us: u64,
+}
+
+/// Why a declared reservation was refused.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReservationError {
+    /// One of budget and period was declared without the other.
+    Incomplete,
+    /// The budget is below [`MIN_BUDGET_US`] or above the period, or the
+    /// period is above [`MAX_PERIOD_US`].
+    OutOfRange { budget_us: u64, period_us: u64 },
+}
+
+impl Reservation {
+    pub const ROUND_ROBIN: Self = Self {
+        budget_us: DEFAULT_TIMESLICE_US,
+        period_us: DEFAULT_TIMESLICE_US,
+    };
+
+    /// The reservation a schedule record declares. Both zero is no
+    /// reservation at all, which is every record a non-MCS generation
+    /// carries.
+    pub const fn admit(budget_us: u64, period_us: u64) -> Result<Option<Self>, ReservationError> {
+        match (budget_us, period_us) {
+            (0, 0) => Ok(None),
+            (0, _) | (_, 0) => Err(ReservationError::Incomplete),
+            _ if budget_us < MIN_BUDGET_US
+                || budget_us > period_us
+                || period_us > MAX_PERIOD_US =>
+            {
+                Err(ReservationError::OutOfRange {
+                    budget_us,
+                    period_us,
+                })
+            }
+            _ => Ok(Some(Self {
+                budget_us,
+                period_us,
+            })),
+        }
+    }
+
+    /// Whether the kernel will throttle a thread under this reservation.
+    pub const fn is_throttled(&self) -> bool {
+        self.budget_us < self.period_us
+    }
+
+    /// The reservation whose first replenishment falls `remaining_ticks` of a
+    /// `frequency_hz` counter from now: what the MCS timer gives the thread
+    /// that signals a deadline (`timeout_timer.rs`).
+    ///
+    /// The period is rounded up, so the signal is never early on account of
+    /// the conversion. It is at least twice the budget, so the thread is
+    /// throttled at all, and at most [`MAX_PERIOD_US`]; a deadline further out
+    /// is signalled at each period until it is reached. A deadline already due
+    /// gets a round-robin reservation, which the kernel never delays, so it is
+    /// raised promptly rather than a period late.
+    pub const fn timeout(remaining_ticks: u64, frequency_hz: u64) -> Self {
+        if remaining_ticks == 0 || frequency_hz == 0 {
+            return Self {
+                budget_us: MIN_BUDGET_US,
+                period_us: MIN_BUDGET_US,
+            };
+        }
+        let micros = (remaining_ticks as u128 * 1_000_000).div_ceil(frequency_hz as u128);
+        let period_us = if micros > MAX_PERIOD_US as u128 {
+            MAX_PERIOD_US
+        } else if micros < 2 * MIN_BUDGET_US as u128 {
+            2 * MIN_BUDGET_US
+        } else {
+            micros as u64
+        };
+        Self {
+            budget_us: MIN_BUDGET_US,
+            period_us,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        BACKGROUND_PRIORITY, MAX_PERIOD_US, MIN_BUDGET_US, Placement, Placements, Reservation,
+        ReservationError, ScheduleError, SchedulingClass,
+    };
+
+    #[test]
+    fn a_set_priority_moves_the_main_thread_and_caps_its_workers() {
+        let declared = [100, 120, 80];
+        assert_eq!(Placement::DECLARED.priorities(declared), declared);
+
+        let demoted = Placement::DECLARED
+            .with_priority(90, 150, declared)
+            .unwrap();
+        assert_eq!(demoted.priorities(declared), [90, 90, 80]);
+
+        // Raising the main thread within the ceiling leaves the workers where
+        // they were declared.
+        let raised = Placement::DECLARED
+            .with_priority(140, 150, declared)
+            .unwrap();
+        assert_eq!(raised.priorities(declared), [140, 120, 80]);
+    }
+
+    /// The bound is the caller's ceiling over every thread the placement
+    /// would run, not only the number it named.
+    #[test]
+    fn nothing_runs_above_the_callers_ceiling() {
+        let declared = [100, 120];
+        assert_eq!(
+            Placement::DECLARED.with_priority(101, 100, declared),
+            Err(ScheduleError::AboveCeiling {
+                priority: 101,
+                ceiling: 100
+            })
+        );
+        // A worker declared above the ceiling is brought under it by the
+        // set priority, so the placement is admitted.
+        assert!(
+            Placement::DECLARED
+                .with_priority(100, 100, declared)
+                .is_ok()
+        );
+
+        let background = Placement::DECLARED
+            .with_class(SchedulingClass::Background, 0, declared)
+            .unwrap();
+        assert_eq!(background.priorities(declared), [BACKGROUND_PRIORITY; 2]);
+        // Back to the declared class would run the worker at 120.
+        assert_eq!(
+            background.with_class(SchedulingClass::Declared, 110, declared),
+            Err(ScheduleError::AboveCeiling {
+                priority: 120,
+                ceiling: 110
+            })
+        );
+    }
+
+    /// The class and the set priority are independent: a round trip through
+    /// the background comes back to the priority the supervisor set.
+    #[test]
+    fn the_background_keeps_a_set_priority_for_its_return() {
+        let declared = [100];
+        let placed = Placement::DECLARED
+            .with_priority(60, 100, declared)
+            .and_then(|placement| placement.with_class(SchedulingClass::Background, 100, declared))
+            .and_then(|placement| placement.with_class(SchedulingClass::Declared, 100, declared))
+            .unwrap();
+        assert_eq!(placed.priorities(declared), [60]);
+    }
+
+    /// A spawn can send a child to the background, but not bring one back: a
+    /// restart must not be a way out of a demotion.
+    #[test]
+    fn a_spawn_lowers_a_placement_and_never_lifts_one() {
+        let background = Placement::DECLARED.at_spawn(SchedulingClass::Background);
+        assert_eq!(background.class(), SchedulingClass::Background);
+        assert_eq!(
+            background.at_spawn(SchedulingClass::Declared),
+            background,
+            "a respawn asking for the declared class"
+        );
+    }
+
+    /// The placement outlives the task, and an instance nothing moved stays
+    /// where the generation put it.
+    #[test]
+    fn placements_are_kept_per_instance() {
+        let mut placements = Placements::new();
+        let demoted = Placement::DECLARED.with_priority(10, 100, [50]).unwrap();
+        placements.record(3, demoted);
+        assert_eq!(placements.get(3), demoted);
+        assert_eq!(placements.get(4), Placement::DECLARED);
+        assert_eq!(placements.get(usize::MAX), Placement::DECLARED);
+    }
+
+    #[test]
+    fn class_codes_round_trip_and_unknown_ones_are_refused() {
+        for class in [SchedulingClass::Declared, SchedulingClass::Background] {
+            assert_eq!(SchedulingClass::decode(class.code()), Some(class));
+        }
+        assert_eq!(SchedulingClass::decode(2), None);
+        assert_eq!(SchedulingClass::decode(u64::MAX), None);
+    }
+
+    #[test]
+    fn a_reservation_is_both_figures_or_neither() {
+        assert_eq!(Reservation::admit(0, 0), Ok(None));
+        assert_eq!(
+            Reservation::admit(1_000, 0),
+            Err(ReservationError::Incomplete)
+        );
+        assert_eq!(
+            Reservation::admit(0, 1_000),
+            Err(ReservationError::Incomplete)
+        );
+        let control = Reservation::admit(2_000, 10_000).unwrap().unwrap();
+        assert!(control.is_throttled());
+        assert!(!Reservation::ROUND_ROBIN.is_throttled());
+    }
+
+    #[test]
+    fn a_reservation_outside_the_admitted_range_is_refused() {
+        for (budget_us, period_us) in [
+            (MIN_BUDGET_US - 1, 10_000),
+            (10_001, 10_000),
+            (MIN_BUDGET_US, MAX_PERIOD_US + 1),
+        ] {
+            assert_eq!(
+                Reservation::admit(budget_us, period_us),
+                Err(ReservationError::OutOfRange {
+                    budget_us,
+                    period_us
+                })
+            );
+        }
+        assert!(
+            Reservation::admit(MAX_PERIOD_US, MAX_PERIOD_US)
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn a_timeout_period_rounds_up_within_the_admitted_range() {
+        // QEMU's virt counter runs at 62.5 MHz: 62_500 ticks is one
+        // millisecond, and one tick more is a microsecond later, not earlier.
+        let frequency_hz = 62_500_000;
+        assert_eq!(Reservation::timeout(62_500, frequency_hz).period_us, 1_000);
+        assert_eq!(Reservation::timeout(62_501, frequency_hz).period_us, 1_001);
+        // Below the floor, and beyond the ceiling.
+        assert_eq!(
+            Reservation::timeout(1, frequency_hz).period_us,
+            2 * MIN_BUDGET_US
+        );
+        assert_eq!(
+            Reservation::timeout(u64::MAX, frequency_hz).period_us,
+            MAX_PERIOD_US
+        );
+        for remaining in [1, 62_500, u64::MAX] {
+            let timeout = Reservation::timeout(remaining, frequency_hz);
+            assert!(timeout.is_throttled());
+            assert_eq!(
+                Reservation::admit(timeout.budget_us, timeout.period_us),
+                Ok(Some(timeout))
+            );
+        }
+        // A deadline already due is never throttled.
+        assert!(!Reservation::timeout(0, frequency_hz).is_throttled());
+    }
+}
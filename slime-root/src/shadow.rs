@@ -0,0 +1,433 @@
+//! Shadow boot: a pending generation's graph run beside the running one.
+//!
+//! Promotion used to rest on one observation — the candidate booted for real,
+//! spent an attempt, and its required instances came up. A shadow boot gathers
+//! the same kind of evidence without the candidate ever being the running
+//! generation: the root admits it through the same preflight, builds its root
+//! instances in a carved-out untyped budget, and watches them while the known-
+//! good graph keeps serving.
+//!
+//! What a shadow task is *not* given is the point of the design. It holds its
+//! service endpoint and nothing else: no block, input, directory, or factory
+//! binding is installed, no peer endpoint is materialized, and its console
+//! traffic lands on the root's own endpoint rather than the console thread's.
+//! The only root requests answered are its own lifecycle reports; every other
+//! request parks the caller for good. So a shadow can fault, exit, or report
+//! itself unhealthy, and it can reach its first real service call, and it can
+//! do nothing else — in particular it cannot reach a device or BootState.
+//!
+//! The same limits bound what a shadow can prove. A candidate's `RUNNING`
+//! probes are what it exercises; a `NO_FAULT` window outlasts a run that ends
+//! once every instance settles, and a `ROUTE` is never answered by a task
+//! whose every service call parks. Those are counted as unverified rather than
+//! passed, and the evidence that carries the count promotes nothing.
+//!
+//! This module is the bookkeeping: the budget and the per-instance verdict.
+//! `main.rs` does the construction and the dispatch.
+
+use crate::generation::MAX_ADMITTED_INSTANCES;
+use crate::task::TaskId;
+use boot_contracts::bootstate::ShadowOutcome;
+
+/// The first task id a shadow table issues.
+///
+/// The shadow's tasks share the root's service endpoint with the running
+/// graph's, and a badge is all that tells them apart. Starting far above any id
+/// the running table can reach keeps the two ranges disjoint for the life of
+/// the boot without either table knowing about the other.
+pub const SHADOW_FIRST_TASK: u32 = 1 << 16;
+
+/// Root instances a shadow will construct. Far below `MAX_TASKS`: a shadow
+/// proves a candidate's root graph comes up, and the graphs this repository
+/// builds launch a handful of root instances and spawn the rest.
+pub const MAX_SHADOW_TASKS: usize = 16;
+
+/// Monotonic nanoseconds a shadow has to settle, from its launch, before it is
+/// declared timed out. Timer time rather than a count of anything the root
+/// does, so how busy the running graph is neither shortens nor stretches it.
+pub const SHADOW_WINDOW_NS: u64 = 2_000_000_000;
+
+/// Whether `id` belongs to a shadow table.
+pub const fn is_shadow_task(id: TaskId) -> bool {
+    id.0 >= SHADOW_FIRST_TASK
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ShadowError {
+    /// Construction would spend more than the carved budget.
+    BudgetExceeded {
+        class: BudgetClass,
+        requested: usize,
+        remaining: usize,
+    },
+    /// More root instances than [`MAX_SHADOW_TASKS`].
+    TooManyInstances,
+    /// An arrival named a task the shadow did not launch.
+    UnknownTask(TaskId),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BudgetClass {
+    Bytes,
+    Slots,
+}
+
+/// The untyped bytes and root CSlots a shadow may spend.
+///
+/// Carved once, from what the root has left *after* the running graph is
+/// built, so the running graph is never the one that runs short. Charged from
+/// the allocator's own before/after readings rather than from the plan, so the
+/// ceiling bounds what construction actually took.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ShadowBudget {
+    bytes: usize,
+    slots: usize,
+    used_bytes: usize,
+    used_slots: usize,
+}
+
+impl ShadowBudget {
+    /// Half of each remaining resource. The other half stays with the running
+    /// graph, whose spawns draw on the same allocator for as long as it runs.
+    pub const fn carve(untyped_bytes_remaining: usize, free_slots: usize) -> Self {
+        Self {
+            bytes: untyped_bytes_remaining / 2,
+            slots: free_slots / 2,
+            used_bytes: 0,
+            used_slots: 0,
+        }
+    }
+
+    pub const fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    pub const fn slots(&self) -> usize {
+        self.slots
+    }
+
+    pub const fn used_bytes(&self) -> usize {
+        self.used_bytes
+    }
+
+    pub const fn used_slots(&self) -> usize {
+        self.used_slots
+    }
+
+    /// Record one construction's cost. A charge over either ceiling is refused
+    /// and leaves the budget unchanged; the caller unwinds what it built.
+    pub fn charge(&mut self, bytes: usize, slots: usize) -> Result<(), ShadowError> {
+        let remaining_bytes = self.bytes - self.used_bytes;
+        if bytes > remaining_bytes {
+            return Err(ShadowError::BudgetExceeded {
+                class: BudgetClass::Bytes,
+                requested: bytes,
+                remaining: remaining_bytes,
+            });
+        }
+        let remaining_slots = self.slots - self.used_slots;
+        if slots > remaining_slots {
+            return Err(ShadowError::BudgetExceeded {
+                class: BudgetClass::Slots,
+                requested: slots,
+                remaining: remaining_slots,
+            });
+        }
+        self.used_bytes += bytes;
+        self.used_slots += slots;
+        Ok(())
+    }
+}
+
+/// Where one shadow instance has got to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ShadowState {
+    /// Constructed and started; nothing heard yet.
+    Launched,
+    /// Reached a service call the shadow does not answer. Its startup ran to
+    /// completion without a fault or an unhealthy report.
+    Parked,
+    /// Exited with status zero.
+    Completed,
+    /// Faulted, exited nonzero, or reported itself unhealthy.
+    Failed,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct ShadowInstance {
+    task: TaskId,
+    instance: usize,
+    required: bool,
+    state: ShadowState,
+}
+
+/// The per-instance record of one shadow run, and the verdict it supports.
+pub struct ShadowRun {
+    instances: [Option<ShadowInstance>; MAX_SHADOW_TASKS],
+    len: usize,
+    required: usize,
+    withheld: usize,
+    unverified: usize,
+    console_writes: usize,
+    started_ns: u64,
+}
+
+impl ShadowRun {
+    /// `required` counts the candidate's autostart instances declared
+    /// `Required`; `withheld` the bindings construction declined to install;
+    /// `unverified` the declared probes no shadow run can pass. The window
+    /// opens at `started_ns` on the monotonic clock.
+    pub const fn new(required: usize, withheld: usize, unverified: usize, started_ns: u64) -> Self {
+        Self {
+            instances: [None; MAX_SHADOW_TASKS],
+            len: 0,
+            required,
+            withheld,
+            unverified,
+            console_writes: 0,
+            started_ns,
+        }
+    }
+
+    pub fn launched(
+        &mut self,
+        task: TaskId,
+        instance: usize,
+        required: bool,
+    ) -> Result<(), ShadowError> {
+        if instance >= MAX_ADMITTED_INSTANCES {
+            return Err(ShadowError::TooManyInstances);
+        }
+        let slot = self
+            .instances
+            .iter_mut()
+            .find(|entry| entry.is_none())
+            .ok_or(ShadowError::TooManyInstances)?;
+        *slot = Some(ShadowInstance {
+            task,
+            instance,
+            required,
+            state: ShadowState::Launched,
+        });
+        self.len += 1;
+        Ok(())
+    }
+
+    /// The candidate instance `task` was built for.
+    pub fn instance_for(&self, task: TaskId) -> Option<usize> {
+        self.entry(task).map(|entry| entry.instance)
+    }
+
+    pub fn state(&self, task: TaskId) -> Option<ShadowState> {
+        self.entry(task).map(|entry| entry.state)
+    }
+
+    /// Move `task` on. A settled instance stays settled: a parked task cannot
+    /// un-park, and a failure is never overwritten by a later arrival.
+    pub fn observe(&mut self, task: TaskId, state: ShadowState) -> Result<(), ShadowError> {
+        let entry = self
+            .instances
+            .iter_mut()
+            .flatten()
+            .find(|entry| entry.task == task)
+            .ok_or(ShadowError::UnknownTask(task))?;
+        entry.state = match (entry.state, state) {
+            (ShadowState::Failed, _) | (ShadowState::Completed, _) => entry.state,
+            (_, next) => next,
+        };
+        Ok(())
+    }
+
+    pub fn console_write(&mut self) {
+        self.console_writes += 1;
+    }
+
+    /// The run's outcome at `now_ns` on the monotonic clock, or `None` while
+    /// it is still open.
+    ///
+    /// Any failure decides at once, required or not: the running graph
+    /// tolerates an optional instance dying, but a candidate whose instance
+    /// dies at startup is not evidence of a healthy generation. Health needs
+    /// every exercised instance settled — parked or completed — and at least
+    /// one exercised.
+    pub fn verdict(&self, now_ns: u64) -> Option<ShadowOutcome> {
+        if self.count(ShadowState::Failed) != 0 {
+            return Some(ShadowOutcome::Unhealthy);
+        }
+        if self.len != 0 && self.count(ShadowState::Launched) == 0 {
+            return Some(ShadowOutcome::Healthy);
+        }
+        if now_ns.saturating_sub(self.started_ns) >= SHADOW_WINDOW_NS {
+            return Some(ShadowOutcome::TimedOut);
+        }
+        None
+    }
+
+    pub fn tasks(&self) -> impl Iterator<Item = TaskId> + '_ {
+        self.instances.iter().flatten().map(|entry| entry.task)
+    }
+
+    pub const fn exercised(&self) -> usize {
+        self.len
+    }
+
+    pub const fn required(&self) -> usize {
+        self.required
+    }
+
+    /// Required instances the shadow actually launched.
+    pub fn exercised_required(&self) -> usize {
+        self.instances
+            .iter()
+            .flatten()
+            .filter(|entry| entry.required)
+            .count()
+    }
+
+    pub const fn withheld(&self) -> usize {
+        self.withheld
+    }
+
+    pub const fn unverified(&self) -> usize {
+        self.unverified
+    }
+
+    pub const fn console_writes(&self) -> usize {
+        self.console_writes
+    }
+
+    pub fn count(&self, state: ShadowState) -> usize {
+        self.instances
+            .iter()
+            .flatten()
+            .filter(|entry| entry.state == state)
+            .count()
+    }
+
+    fn entry(&self, task: TaskId) -> Option<&ShadowInstance> {
+        self.instances
+            .iter()
+            .flatten()
+            .find(|entry| entry.task == task)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: TaskId = TaskId(SHADOW_FIRST_TASK);
+    const B: TaskId = TaskId(SHADOW_FIRST_TASK + 1);
+
+    const START: u64 = 5_000;
+
+    fn run() -> ShadowRun {
+        let mut run = ShadowRun::new(1, 2, 0, START);
+        run.launched(A, 0, true).unwrap();
+        run.launched(B, 3, false).unwrap();
+        run
+    }
+
+    #[test]
+    fn shadow_badges_never_name_a_running_task() {
+        assert!(is_shadow_task(A));
+        assert!(!is_shadow_task(TaskId(crate::task::MAX_TASKS as u32)));
+        assert_ne!(A.service_badge(), TaskId(0).service_badge());
+        assert_eq!(
+            TaskId::from_badge(A.fault_badge()).map(|(id, _)| id),
+            Some(A)
+        );
+    }
+
+    #[test]
+    fn every_instance_settled_without_failure_is_healthy() {
+        let mut run = run();
+        run.observe(A, ShadowState::Parked).unwrap();
+        assert_eq!(run.verdict(START), None, "one instance has not settled");
+        run.observe(B, ShadowState::Completed).unwrap();
+        assert_eq!(run.verdict(START), Some(ShadowOutcome::Healthy));
+        assert_eq!(run.exercised(), 2);
+        assert_eq!(run.exercised_required(), 1);
+    }
+
+    #[test]
+    fn any_failure_is_unhealthy_and_stays_so() {
+        let mut run = run();
+        run.observe(A, ShadowState::Parked).unwrap();
+        run.observe(B, ShadowState::Failed).unwrap();
+        assert_eq!(run.verdict(START), Some(ShadowOutcome::Unhealthy));
+        run.observe(B, ShadowState::Completed).unwrap();
+        assert_eq!(run.state(B), Some(ShadowState::Failed));
+    }
+
+    #[test]
+    fn an_empty_run_proves_nothing() {
+        let run = ShadowRun::new(0, 0, 0, START);
+        assert_eq!(run.verdict(START), None);
+        assert_eq!(
+            run.verdict(START + SHADOW_WINDOW_NS),
+            Some(ShadowOutcome::TimedOut)
+        );
+    }
+
+    #[test]
+    fn an_unsettled_run_times_out_at_the_window() {
+        let mut run = run();
+        run.observe(A, ShadowState::Parked).unwrap();
+        assert_eq!(run.verdict(START + SHADOW_WINDOW_NS - 1), None);
+        assert_eq!(
+            run.verdict(START + SHADOW_WINDOW_NS),
+            Some(ShadowOutcome::TimedOut)
+        );
+    }
+
+    #[test]
+    fn a_reading_before_the_launch_does_not_close_the_window() {
+        assert_eq!(run().verdict(0), None);
+    }
+
+    #[test]
+    fn an_unknown_task_is_refused() {
+        assert_eq!(
+            run().observe(TaskId(SHADOW_FIRST_TASK + 9), ShadowState::Parked),
+            Err(ShadowError::UnknownTask(TaskId(SHADOW_FIRST_TASK + 9)))
+        );
+    }
+
+    #[test]
+    fn the_table_bounds_the_instances() {
+        let mut run = ShadowRun::new(0, 0, 0, START);
+        for index in 0..MAX_SHADOW_TASKS {
+            run.launched(TaskId(SHADOW_FIRST_TASK + index as u32), index, false)
+                .unwrap();
+        }
+        assert_eq!(
+            run.launched(TaskId(SHADOW_FIRST_TASK + 99), 0, false),
+            Err(ShadowError::TooManyInstances)
+        );
+    }
+
+    #[test]
+    fn the_budget_is_half_of_what_remains_and_refuses_overdraw() {
+        let mut budget = ShadowBudget::carve(1 << 20, 100);
+        assert_eq!((budget.bytes(), budget.slots()), (1 << 19, 50));
+        budget.charge(1 << 18, 20).unwrap();
+        assert_eq!(
+            budget.charge(1 << 19, 1),
+            Err(ShadowError::BudgetExceeded {
+                class: BudgetClass::Bytes,
+                requested: 1 << 19,
+                remaining: 1 << 18,
+            })
+        );
+        assert_eq!(
+            budget.charge(0, 31),
+            Err(ShadowError::BudgetExceeded {
+                class: BudgetClass::Slots,
+                requested: 31,
+                remaining: 30,
+            })
+        );
+        assert_eq!((budget.used_bytes(), budget.used_slots()), (1 << 18, 20));
+    }
+}
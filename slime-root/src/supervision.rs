@@ -24,6 +24,9 @@ pub enum Termination {
     /// reason code, not an address: an address would leak the child's layout
     /// to its parent.
     Fault(u64),
+    /// Stopped by its supervisor through `SUPERVISION KILL`, which gave up
+    /// waiting for it.
+    Timeout,
 }
 
 impl Termination {
@@ -32,6 +35,7 @@ impl Termination {
         match self {
             Self::Exit(status) => (0, status as u64),
             Self::Fault(reason) => (1, reason),
+            Self::Timeout => (2, 0),
         }
     }
 }
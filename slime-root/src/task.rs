@@ -9,6 +9,8 @@
 //! | 1 | root service endpoint, badged, rights derived from declared grants |
 //! | 2 | the task's own TCB, only when supervision requires it |
 //! | 3 | root service endpoint, badged as this task's fault handler |
+//! | 30..31 | each thread's Reply object, under the MCS kernel only |
+//! | 126 | the Notification the task's threads block on for a shared lock |
 //!
 //! Slot 3 exists because the non-MCS kernel resolves a thread's fault handler
 //! CPtr *in that thread's own CSpace* (`sendFaultIPC` in
@@ -17,6 +19,14 @@
 //! exactly one endpoint and tells requests from faults by badge. No untyped,
 //! CNode, VSpace, ASID pool, or IRQ authority is ever placed in a child CSpace.
 //!
+//! The MCS kernel profile (B48) binds the fault handler differently:
+//! `seL4_TCB_SetSchedParams` resolves it in the *root's* CSpace and copies it
+//! into the TCB, so slot 3 is no longer what the kernel reads. It is installed
+//! anyway, so a child's layout does not depend on which kernel it runs on.
+//! Under MCS each thread also runs on its own scheduling context, configured
+//! from the plan's [`Reservation`], and a receive names an explicit Reply
+//! object, which is what slots 30 and 31 hold.
+//!
 //! Construction is staged: every object is allocated and every capability
 //! installed before any thread is activated, so a failure part-way through
 //! leaves a task that has never run, plus a cleanup record naming exactly the
@@ -26,12 +36,14 @@ use sel4::{CapTypeForObjectOfFixedSize, CapTypeForObjectOfVariableSize};
 
 use crate::child_vspace::{
     ChildImage, ChildVSpace, MAX_CHILD_THREADS, ScratchPage, VSpaceError, admit_thread_count,
-    create_child_vspace,
+    create_child_vspace, grow_heap,
 };
 use crate::cspace::CSpaceLedger;
 use crate::generation::Authority;
 use crate::graph::{AuthorityTable, CapabilityEntry};
-use crate::object_allocator::{AllocError, ObjectAllocator, TaskArenaId};
+use crate::memory::PageLedger;
+use crate::object_allocator::{AllocError, ArenaPlan, ObjectAllocator, TaskArenaId};
+use crate::schedule::Reservation;
 
 /// Child tasks one generation may run.
 ///
@@ -76,6 +88,25 @@ pub const CHILD_SLOT_ENDPOINT_BASE: sel4::CPtrBits = 33;
 pub const CHILD_SLOT_NOTIFICATION_BASE: sel4::CPtrBits = 64;
 /// First child CSpace slot holding a badged logical-authority mirror.
 pub const CHILD_SLOT_AUTHORITY_BASE: sel4::CPtrBits = 95;
+/// First child CSpace slot of the per-thread Reply objects (B48's MCS half):
+/// thread `i` receives through the Reply at `CHILD_SLOT_REPLY_BASE + i`.
+///
+/// Only the MCS kernel has Reply objects, so only that profile installs them,
+/// but the slots are reserved on both: a plan valid for one kernel stays valid
+/// for the other. They take the top of the runtime's transfer region, directly
+/// below the console, which no plan can name; the runtime stops its transfer
+/// search at this base.
+pub const CHILD_SLOT_REPLY_BASE: sel4::CPtrBits =
+    CHILD_SLOT_CONSOLE - MAX_CHILD_THREADS as sel4::CPtrBits;
+/// Child CSpace slot holding the task's thread-lock Notification.
+///
+/// A thread that finds a lock its sibling holds waits here rather than
+/// spinning, so a lower-priority holder still runs and releases it (the
+/// runtime's growable heap is the user). Installed for every task on both
+/// kernels, like the Reply slots, so the layout does not depend on the thread
+/// count; it sits in the one slot between the authority mirrors and the
+/// receive slot, which no plan can name.
+pub const CHILD_SLOT_THREAD_LOCK: sel4::CPtrBits = 126;
 /// Slot where native endpoint receive deposits its sole transferred capability.
 pub const CHILD_SLOT_RECEIVE: sel4::CPtrBits = 127;
 /// Number of slots in each declared native/mirror region.
@@ -115,12 +146,15 @@ impl ChildSlots {
     ///
     /// `CHILD_SLOT_CNODE` is declared only for a self-managed child, matching
     /// the audit: an externally supervised child holds neither its own TCB nor
-    /// its CNode root.
+    /// its CNode root. Every thread's Reply slot is declared only under MCS;
+    /// the thread-lock Notification on both kernels.
     pub fn declares(self, slot: sel4::CPtrBits, expect_tcb: bool) -> bool {
         slot == self.service
             || slot == self.console
             || slot == self.fault
+            || slot == CHILD_SLOT_THREAD_LOCK
             || (expect_tcb && (slot == self.tcb || slot == CHILD_SLOT_CNODE))
+            || (sel4::sel4_cfg_bool!(KERNEL_MCS) && is_reply_slot(slot))
     }
 
     /// The lowest slot above null the plan leaves empty, or `None` when the
@@ -163,15 +197,13 @@ impl ChildSlots {
         if self.tcb == 0 || self.fault == 0 || self.console == 0 {
             return mismatch(0);
         }
-        let slots = [
-            self.service,
-            self.console,
-            self.tcb,
-            self.fault,
-            CHILD_SLOT_CNODE,
-        ];
+        let slots = [self.service, self.console, self.tcb, self.fault];
         for (index, slot) in slots.iter().enumerate() {
-            if slots[index + 1..].contains(slot) {
+            if slots[index + 1..].contains(slot)
+                || *slot == CHILD_SLOT_CNODE
+                || *slot == CHILD_SLOT_THREAD_LOCK
+                || is_reply_slot(*slot)
+            {
                 return mismatch(*slot);
             }
         }
@@ -179,6 +211,11 @@ impl ChildSlots {
     }
 }
 
+/// Whether `slot` holds some thread's Reply object under MCS.
+fn is_reply_slot(slot: sel4::CPtrBits) -> bool {
+    (CHILD_SLOT_REPLY_BASE..CHILD_SLOT_CONSOLE).contains(&slot)
+}
+
 /// Refuse a declared priority the root cannot safely run a child at.
 ///
 /// Refused rather than clamped: a child at or above the root's priority can
@@ -193,6 +230,22 @@ pub const fn admit_priority(priority: sel4::Word) -> Result<sel4::Word, TaskErro
     Ok(priority)
 }
 
+/// Refuse a CPU reservation the running kernel cannot enforce.
+///
+/// Only the MCS kernel has scheduling contexts. `build-generation.py` refuses
+/// `budgetUs` and `periodUs` for a product build, so this is the side that
+/// holds when a generation built for the MCS profile boots on the product
+/// kernel. Running it unreserved would give the control loop none of the
+/// isolation its generation declared (B48).
+pub fn admit_reservation(
+    reservation: Option<Reservation>,
+) -> Result<Option<Reservation>, TaskError> {
+    if reservation.is_some() && !sel4::sel4_cfg_bool!(KERNEL_MCS) {
+        return Err(TaskError::ReservationUnsupported);
+    }
+    Ok(reservation)
+}
+
 /// Child scheduling priority. Strictly below the root task's own priority so
 /// the root service loop always preempts a child that becomes runnable.
 pub const CHILD_PRIORITY: sel4::Word = 254;
@@ -258,6 +311,9 @@ pub enum TaskError {
     PriorityAboveRoot {
         priority: sel4::Word,
     },
+    /// A plan declared a budget and period for a kernel built without MCS,
+    /// which has no notion of either (B48).
+    ReservationUnsupported,
     VSpace(VSpaceError),
     /// [`MAX_TASKS`] child tasks already exist.
     TableFull {
@@ -278,6 +334,10 @@ pub enum TaskError {
     Configure(sel4::Error),
     /// `seL4_TCB_SetSchedParams` failed.
     SchedParams(sel4::Error),
+    /// `seL4_SchedControl_ConfigureFlags` refused a thread's reservation.
+    SchedContext(sel4::Error),
+    /// `seL4_TCB_SetPriority` failed moving a running task.
+    SetPriority(sel4::Error),
     /// Writing the initial register state failed.
     WriteRegisters(sel4::Error),
     /// Resuming the thread failed.
@@ -399,6 +459,9 @@ pub struct Task {
     /// the child filled itself — a native Endpoint transfer moved out of its
     /// receive slot — has no logical entry at all. Only a census answers both.
     pub cspace: CSpaceLedger,
+    /// Private pages mapped for this task, live and at their high-water mark:
+    /// construction frames at creation, then every heap growth.
+    pub memory: PageLedger,
     pub supervision: Supervision,
     pub entry: u64,
     pub activated: bool,
@@ -493,6 +556,15 @@ impl<const CAPACITY: usize> TaskTable<CAPACITY> {
         }
     }
 
+    /// A table whose ids start at `first_id`, so its badges stay disjoint
+    /// from a table started at zero and both can share one endpoint.
+    pub const fn starting_at(first_id: u32) -> Self {
+        Self {
+            next_id: first_id,
+            ..Self::new()
+        }
+    }
+
     pub const fn len(&self) -> usize {
         self.len
     }
@@ -615,16 +687,25 @@ impl<const CAPACITY: usize> TaskTable<CAPACITY> {
         // is unused -- the main thread takes `priority` above -- and the rest
         // come from the plan's per-thread schedule records (B48).
         worker_priorities: [sel4::Word; MAX_CHILD_THREADS],
+        // Each thread's CPU reservation, indexed like the priorities but with
+        // the main thread at 0. `None` for every thread on the product kernel,
+        // and under MCS for every thread whose instance declares none.
+        reservations: [Option<Reservation>; MAX_CHILD_THREADS],
+        // Pages the child's heap window may grow to, from the plan's resource
+        // quota. Zero for the fixture paths and every instance that declares
+        // none, which then has no window at all.
+        heap_pages: usize,
     ) -> Result<TaskId, TaskError> {
         admit_priority(priority)?;
         admit_thread_count(threads)?;
+        for reservation in reservations.iter().take(threads) {
+            admit_reservation(*reservation)?;
+        }
         let Some(index) = self.tasks.iter().position(Option::is_none) else {
             return Err(TaskError::TableFull { limit: CAPACITY });
         };
         let id = TaskId(self.next_id);
-        let mut plan = image
-            .vspace_arena_plan(threads)
-            .map_err(VSpaceError::Image)?;
+        let mut plan = image.vspace_arena_plan(threads, heap_pages)?;
         plan.add(sel4::cap_type::CNode::object_blueprint(cnode_size_bits))
             .ok_or(TaskError::Alloc(AllocError::UntypedExhausted {
                 size_bits: usize::BITS as usize,
@@ -640,6 +721,17 @@ impl<const CAPACITY: usize> TaskTable<CAPACITY> {
                     remaining: 0,
                 }))?;
         }
+        plan.add(sel4::cap_type::Notification::object_blueprint())
+            .ok_or(TaskError::Alloc(AllocError::UntypedExhausted {
+                size_bits: usize::BITS as usize,
+                remaining: 0,
+            }))?;
+        plan_schedule_objects(&mut plan, threads).ok_or(TaskError::Alloc(
+            AllocError::UntypedExhausted {
+                size_bits: usize::BITS as usize,
+                remaining: 0,
+            },
+        ))?;
         let arena_bits =
             plan.required_size_bits()
                 .ok_or(TaskError::Alloc(AllocError::UntypedExhausted {
@@ -657,6 +749,7 @@ impl<const CAPACITY: usize> TaskTable<CAPACITY> {
                 scratch,
                 asid_pool,
                 threads,
+                heap_pages,
             )?;
             let cnode = allocator
                 .allocate_variable_in::<sel4::cap_type::CNode>(arena, cnode_size_bits)?
@@ -799,6 +892,11 @@ impl<const CAPACITY: usize> TaskTable<CAPACITY> {
                 }
             }
 
+            // Before the audit, which declares every thread's Reply slot
+            // under MCS and the thread lock on both kernels.
+            install_replies(allocator, arena, cnode, cnode_size_bits, &mut ledger)?;
+            install_thread_lock(allocator, arena, cnode, cnode_size_bits, &mut ledger)?;
+
             // Audit the constructed CSpace against what the plan declared, by
             // asking the kernel rather than trusting the loop above. Every
             // slot the plan named must be occupied and every slot it did not
@@ -812,17 +910,28 @@ impl<const CAPACITY: usize> TaskTable<CAPACITY> {
             // Type is not an occupancy question, so it is probed separately.
             audit_child_types(cnode, cnode_size_bits, child_slots, supervision)?;
 
-            tcb.tcb_configure(
-                sel4::CPtr::from_bits(child_slots.fault),
-                cnode,
-                sel4::CNodeCapData::new(0, sel4::WORD_SIZE - cnode_size_bits),
-                vspace.vspace,
-                vspace.main().ipc_buffer_addr as sel4::Word,
-                vspace.main().ipc_buffer,
-            )
-            .map_err(TaskError::Configure)?;
-            tcb.tcb_set_sched_params(sel4::init_thread::slot::TCB.cap(), priority, priority)
-                .map_err(TaskError::SchedParams)?;
+            let fault = fault_handler(
+                allocator,
+                arena,
+                service_endpoint,
+                child_slots,
+                id.fault_badge(),
+            )?;
+            configure_thread(
+                allocator,
+                arena,
+                tcb,
+                &ThreadSetup {
+                    cnode,
+                    cnode_size_bits,
+                    vspace: vspace.vspace,
+                    ipc_buffer_addr: vspace.main().ipc_buffer_addr as sel4::Word,
+                    ipc_buffer: vspace.main().ipc_buffer,
+                    fault,
+                    priority,
+                    reservation: reservations[0],
+                },
+            )?;
 
             let entry = image.entry();
             let mut context = sel4::UserContext::default();
@@ -849,28 +958,26 @@ impl<const CAPACITY: usize> TaskTable<CAPACITY> {
                 let worker_tcb = allocator
                     .allocate_fixed_in::<sel4::cap_type::Tcb>(arena)?
                     .cap();
-                worker_tcb
-                    .tcb_configure(
-                        sel4::CPtr::from_bits(child_slots.fault),
-                        cnode,
-                        sel4::CNodeCapData::new(0, sel4::WORD_SIZE - cnode_size_bits),
-                        vspace.vspace,
-                        vspace.pages[index].ipc_buffer_addr as sel4::Word,
-                        vspace.pages[index].ipc_buffer,
-                    )
-                    .map_err(TaskError::Configure)?;
                 // The worker's own declared priority, not its main thread's
                 // (B48). Below it, a component can hold a busy thread while
                 // its own IPC stays responsive and unrelated services keep
                 // running; the `ScheduleRecord` has always been per-thread.
                 let worker_priority = admit_priority(worker_priorities[index])?;
-                worker_tcb
-                    .tcb_set_sched_params(
-                        sel4::init_thread::slot::TCB.cap(),
-                        worker_priority,
-                        worker_priority,
-                    )
-                    .map_err(TaskError::SchedParams)?;
+                configure_thread(
+                    allocator,
+                    arena,
+                    worker_tcb,
+                    &ThreadSetup {
+                        cnode,
+                        cnode_size_bits,
+                        vspace: vspace.vspace,
+                        ipc_buffer_addr: vspace.pages[index].ipc_buffer_addr as sel4::Word,
+                        ipc_buffer: vspace.pages[index].ipc_buffer,
+                        fault,
+                        priority: worker_priority,
+                        reservation: reservations[index],
+                    },
+                )?;
                 // The thread index, in the register the runtime reads through
                 // `TPIDR_EL0`. This is what lets a thread find its own IPC
                 // buffer and transfer window without any shared state: the
@@ -911,6 +1018,8 @@ impl<const CAPACITY: usize> TaskTable<CAPACITY> {
         };
 
         let cleanup = construction_record(id, arena, allocator.arena_slot_count(arena)?);
+        let mut memory = PageLedger::EMPTY;
+        memory.mapped(vspace.frames_mapped as u32);
         self.tasks[index] = Some(Task {
             cnode_size_bits,
             workers,
@@ -921,6 +1030,7 @@ impl<const CAPACITY: usize> TaskTable<CAPACITY> {
             authority,
             capabilities: AuthorityTable::new(),
             cspace: CSpaceLedger::EMPTY,
+            memory,
             supervision,
             entry,
             activated: false,
@@ -973,6 +1083,56 @@ impl<const CAPACITY: usize> TaskTable<CAPACITY> {
         Ok(started)
     }
 
+    /// Grow a task's heap window by `pages`, answering how many it then holds.
+    ///
+    /// The frames come from the task's own arena, so [`Self::reclaim`] takes
+    /// them back with everything else and nothing here needs undoing.
+    pub fn grow_heap(
+        &mut self,
+        allocator: &mut ObjectAllocator,
+        id: TaskId,
+        pages: usize,
+    ) -> Result<usize, TaskError> {
+        let task = self.get_mut(id).ok_or(TaskError::UnknownTask(id))?;
+        let before = task.vspace.heap_mapped;
+        let mapped = grow_heap(allocator, task.cleanup.arena, &mut task.vspace, pages);
+        // Credited from what the window holds rather than from the request, so
+        // a growth that fails part-way still counts the pages it did map.
+        task.memory
+            .mapped((task.vspace.heap_mapped - before) as u32);
+        Ok(mapped?)
+    }
+
+    /// Move a live task's threads to `priorities`, main thread first.
+    ///
+    /// Every priority is admitted as a construction-time one is, so a
+    /// placement cannot put a child where the plan could not have. The kernel
+    /// call carries the root's own TCB as authority, as construction does:
+    /// the child's controlled ceiling stays its declared priority, so it
+    /// cannot use the new one to raise anything itself.
+    pub fn place(
+        &self,
+        id: TaskId,
+        priorities: &[sel4::Word; MAX_CHILD_THREADS],
+    ) -> Result<(), TaskError> {
+        let task = self.get(id).ok_or(TaskError::UnknownTask(id))?;
+        for priority in priorities {
+            admit_priority(*priority)?;
+        }
+        let authority = sel4::init_thread::slot::TCB.cap();
+        task.tcb
+            .tcb_set_priority(authority, priorities[0])
+            .map_err(TaskError::SetPriority)?;
+        for (worker, priority) in task.workers.iter().zip(priorities).skip(1) {
+            if let Some(worker) = worker {
+                worker
+                    .tcb_set_priority(authority, *priority)
+                    .map_err(TaskError::SetPriority)?;
+            }
+        }
+        Ok(())
+    }
+
     /// Suspend a task, revoke everything derived from its objects, and drop it.
     pub fn reclaim(
         &mut self,
@@ -1158,9 +1318,10 @@ struct InstallLedger {
 }
 
 /// Distinct capabilities the root installs into one child: service, console,
-/// fault, TCB, and the child's CNode root. The input slot names the console's
+/// fault, TCB, the child's CNode root, the thread-lock Notification, and under
+/// MCS each thread's Reply object. The input slot names the console's
 /// endpoint, so it is not a separate install.
-const MAX_CHILD_INSTALLS: usize = 5;
+const MAX_CHILD_INSTALLS: usize = 6 + MAX_CHILD_THREADS;
 
 impl InstallLedger {
     /// Record one install, refusing a source/badge pair already present.
@@ -1216,6 +1377,293 @@ impl InstallLedger {
     }
 }
 
+/// Size of each child thread's scheduling context under MCS: the kernel's
+/// minimum, which holds the refills a sporadic reservation needs and no more.
+#[sel4::sel4_cfg(KERNEL_MCS)]
+const SCHED_CONTEXT_BITS: usize = sel4::sys::seL4_MinSchedContextBits as usize;
+
+/// The root's `SchedControl` capability, installed once at boot.
+///
+/// Every scheduling context the root configures goes through it, for children
+/// and for the root's own helper threads alike. Kept here rather than threaded
+/// through [`TaskTable::create`], because bootinfo is gone by the time a spawn
+/// builds a task.
+#[sel4::sel4_cfg(KERNEL_MCS)]
+static SCHED_CONTROL: core::sync::atomic::AtomicU64 = core::sync::atomic::AtomicU64::new(0);
+
+/// Record the first node's `SchedControl` capability from bootinfo.
+#[sel4::sel4_cfg(KERNEL_MCS)]
+pub fn install_sched_control(bootinfo: &sel4::BootInfo) {
+    let cap = bootinfo.sched_control().index(0).cap();
+    SCHED_CONTROL.store(cap.bits() as u64, core::sync::atomic::Ordering::Relaxed);
+}
+
+/// The `SchedControl` capability [`install_sched_control`] recorded.
+#[sel4::sel4_cfg(KERNEL_MCS)]
+pub fn sched_control() -> sel4::cap::SchedControl {
+    sel4::cap::SchedControl::from_bits(
+        SCHED_CONTROL.load(core::sync::atomic::Ordering::Relaxed) as sel4::CPtrBits
+    )
+}
+
+/// Allocate a scheduling context in `arena` and give it `reservation`.
+///
+/// Configured before any thread is bound to it, so a thread never runs on a
+/// context that has no budget.
+#[sel4::sel4_cfg(KERNEL_MCS)]
+pub fn reserve_sched_context(
+    allocator: &mut ObjectAllocator,
+    arena: Option<TaskArenaId>,
+    reservation: Reservation,
+) -> Result<sel4::cap::SchedContext, TaskError> {
+    let sched_context = match arena {
+        Some(arena) => allocator
+            .allocate_variable_in::<sel4::cap_type::SchedContext>(arena, SCHED_CONTEXT_BITS)?
+            .cap(),
+        None => allocator
+            .allocate_variable::<sel4::cap_type::SchedContext>(SCHED_CONTEXT_BITS)?
+            .cap(),
+    };
+    sched_control()
+        .sched_control_configure_flags(
+            sched_context,
+            reservation.budget_us,
+            reservation.period_us,
+            0,
+            0,
+            0,
+        )
+        .map_err(TaskError::SchedContext)?;
+    Ok(sched_context)
+}
+
+/// Add each thread's kernel-profile-specific objects to a task's arena plan.
+///
+/// Nothing on the product kernel. Under MCS, a scheduling context per thread
+/// and a Reply object per reserved Reply slot, whether or not a thread runs
+/// behind it.
+#[sel4::sel4_cfg(not(KERNEL_MCS))]
+fn plan_schedule_objects(_plan: &mut ArenaPlan, _threads: usize) -> Option<()> {
+    Some(())
+}
+
+#[sel4::sel4_cfg(KERNEL_MCS)]
+fn plan_schedule_objects(plan: &mut ArenaPlan, threads: usize) -> Option<()> {
+    for _ in 0..threads {
+        plan.add(sel4::cap_type::SchedContext::object_blueprint(
+            SCHED_CONTEXT_BITS,
+        ))?;
+    }
+    for _ in 0..MAX_CHILD_THREADS {
+        plan.add(sel4::cap_type::Reply::object_blueprint())?;
+    }
+    Some(())
+}
+
+/// How a thread names its fault handler: a CPtr into its own CSpace on the
+/// product kernel, and an endpoint in the root's CSpace under MCS.
+#[sel4::sel4_cfg(not(KERNEL_MCS))]
+type FaultHandler = sel4::CPtr;
+#[sel4::sel4_cfg(KERNEL_MCS)]
+type FaultHandler = sel4::cap::Endpoint;
+
+/// The fault handler every thread of one task is configured with.
+///
+/// The product kernel resolves it in the child's CSpace at fault time, so it is
+/// slot 3. MCS copies it into the TCB from the root's CSpace when scheduling
+/// parameters are set, so it is a root-held mint under the same fault badge,
+/// in a slot the task's arena owns and reclaims.
+#[sel4::sel4_cfg(not(KERNEL_MCS))]
+fn fault_handler(
+    _allocator: &mut ObjectAllocator,
+    _arena: TaskArenaId,
+    _service_endpoint: sel4::cap::Endpoint,
+    child_slots: ChildSlots,
+    _badge: sel4::Badge,
+) -> Result<FaultHandler, TaskError> {
+    Ok(sel4::CPtr::from_bits(child_slots.fault))
+}
+
+#[sel4::sel4_cfg(KERNEL_MCS)]
+fn fault_handler(
+    allocator: &mut ObjectAllocator,
+    arena: TaskArenaId,
+    service_endpoint: sel4::cap::Endpoint,
+    child_slots: ChildSlots,
+    badge: sel4::Badge,
+) -> Result<FaultHandler, TaskError> {
+    let handler = allocator
+        .reserve_slot_in::<sel4::cap_type::Endpoint>(arena)?
+        .cap();
+    let root = sel4::init_thread::slot::CNODE.cap();
+    root.absolute_cptr(handler)
+        .mint(
+            &root.absolute_cptr(service_endpoint),
+            sel4::CapRightsBuilder::none()
+                .write(true)
+                .grant_reply(true)
+                .build(),
+            badge,
+        )
+        .map_err(|error| TaskError::Mint {
+            slot: child_slots.fault,
+            error,
+        })?;
+    Ok(handler)
+}
+
+/// Install each thread's Reply object from [`CHILD_SLOT_REPLY_BASE`]; nothing
+/// on the product kernel, which has none.
+#[sel4::sel4_cfg(not(KERNEL_MCS))]
+fn install_replies(
+    _allocator: &mut ObjectAllocator,
+    _arena: TaskArenaId,
+    _cnode: sel4::cap::CNode,
+    _cnode_size_bits: usize,
+    _ledger: &mut InstallLedger,
+) -> Result<(), TaskError> {
+    Ok(())
+}
+
+/// A Reply is bound by the receive that uses it, so two threads blocked at
+/// once each need their own; a worker sharing the main thread's would have its
+/// caller's reply overwritten by the next receive on either thread.
+///
+/// Every reserved slot is filled, not only those of threads that run: the
+/// audit declares the whole range, so a single-threaded task with an empty
+/// worker slot would be refused as a layout mismatch.
+#[sel4::sel4_cfg(KERNEL_MCS)]
+fn install_replies(
+    allocator: &mut ObjectAllocator,
+    arena: TaskArenaId,
+    cnode: sel4::cap::CNode,
+    cnode_size_bits: usize,
+    ledger: &mut InstallLedger,
+) -> Result<(), TaskError> {
+    for thread in 0..MAX_CHILD_THREADS {
+        let reply = allocator
+            .allocate_fixed_in::<sel4::cap_type::Reply>(arena)?
+            .cap();
+        mint_child_slot(
+            cnode,
+            cnode_size_bits,
+            CHILD_SLOT_REPLY_BASE + thread as sel4::CPtrBits,
+            &sel4::init_thread::slot::CNODE.cap().absolute_cptr(reply),
+            sel4::CapRights::all(),
+            0,
+            false,
+            ledger,
+        )?;
+    }
+    Ok(())
+}
+
+/// Install the Notification a task's threads block on for a lock one of them
+/// holds ([`CHILD_SLOT_THREAD_LOCK`]).
+///
+/// The child both waits and signals on it, and nothing else holds it, so it
+/// carries no badge and never reaches another task.
+fn install_thread_lock(
+    allocator: &mut ObjectAllocator,
+    arena: TaskArenaId,
+    cnode: sel4::cap::CNode,
+    cnode_size_bits: usize,
+    ledger: &mut InstallLedger,
+) -> Result<(), TaskError> {
+    let notification = allocator
+        .allocate_fixed_in::<sel4::cap_type::Notification>(arena)?
+        .cap();
+    mint_child_slot(
+        cnode,
+        cnode_size_bits,
+        CHILD_SLOT_THREAD_LOCK,
+        &sel4::init_thread::slot::CNODE
+            .cap()
+            .absolute_cptr(notification),
+        sel4::CapRightsBuilder::none()
+            .read(true)
+            .write(true)
+            .build(),
+        0,
+        false,
+        ledger,
+    )
+}
+
+/// One child thread's binding, beyond its TCB.
+struct ThreadSetup {
+    cnode: sel4::cap::CNode,
+    cnode_size_bits: usize,
+    vspace: sel4::cap::VSpace,
+    ipc_buffer_addr: sel4::Word,
+    ipc_buffer: sel4::cap::Granule,
+    fault: FaultHandler,
+    priority: sel4::Word,
+    reservation: Option<Reservation>,
+}
+
+/// Bind a thread to its spaces, IPC buffer, fault handler, and schedule.
+///
+/// The root's own TCB is the scheduling authority, so the child's controlled
+/// ceiling is its declared priority and no higher.
+#[sel4::sel4_cfg(not(KERNEL_MCS))]
+fn configure_thread(
+    _allocator: &mut ObjectAllocator,
+    _arena: TaskArenaId,
+    tcb: sel4::cap::Tcb,
+    thread: &ThreadSetup,
+) -> Result<(), TaskError> {
+    debug_assert!(thread.reservation.is_none(), "refused by admission");
+    tcb.tcb_configure(
+        thread.fault,
+        thread.cnode,
+        sel4::CNodeCapData::new(0, sel4::WORD_SIZE - thread.cnode_size_bits),
+        thread.vspace,
+        thread.ipc_buffer_addr,
+        thread.ipc_buffer,
+    )
+    .map_err(TaskError::Configure)?;
+    tcb.tcb_set_sched_params(
+        sel4::init_thread::slot::TCB.cap(),
+        thread.priority,
+        thread.priority,
+    )
+    .map_err(TaskError::SchedParams)
+}
+
+/// Under MCS the thread also gets its own scheduling context. One that
+/// declares no reservation gets [`Reservation::ROUND_ROBIN`], which is what
+/// the product kernel's timeslice gives every thread anyway.
+#[sel4::sel4_cfg(KERNEL_MCS)]
+fn configure_thread(
+    allocator: &mut ObjectAllocator,
+    arena: TaskArenaId,
+    tcb: sel4::cap::Tcb,
+    thread: &ThreadSetup,
+) -> Result<(), TaskError> {
+    let sched_context = reserve_sched_context(
+        allocator,
+        Some(arena),
+        thread.reservation.unwrap_or(Reservation::ROUND_ROBIN),
+    )?;
+    tcb.tcb_configure(
+        thread.cnode,
+        sel4::CNodeCapData::new(0, sel4::WORD_SIZE - thread.cnode_size_bits),
+        thread.vspace,
+        thread.ipc_buffer_addr,
+        thread.ipc_buffer,
+    )
+    .map_err(TaskError::Configure)?;
+    tcb.tcb_set_sched_params(
+        sel4::init_thread::slot::TCB.cap(),
+        thread.priority,
+        thread.priority,
+        sched_context,
+        thread.fault,
+    )
+    .map_err(TaskError::SchedParams)
+}
+
 fn mint_child_slot(
     cnode: sel4::cap::CNode,
     // Depth must match the CNode's own size: the child's CSpace is sized from
@@ -1253,11 +1701,13 @@ fn mint_child_slot(
 mod tests {
     use super::{
         Arrival, CHILD_CNODE_SIZE_BITS, CHILD_PRIORITY, CHILD_SLOT_CONSOLE, CHILD_SLOT_FAULT,
-        CHILD_SLOT_SERVICE, ChildSlots, ConstructionStage, InstallLedger, MAX_CHILD_INSTALLS,
-        TaskError, TaskId, admit_priority, child_service_rights, construction_record,
+        CHILD_SLOT_REPLY_BASE, CHILD_SLOT_SERVICE, CHILD_SLOT_THREAD_LOCK, ChildSlots,
+        ConstructionStage, InstallLedger, MAX_CHILD_INSTALLS, MAX_CHILD_THREADS, TaskError, TaskId,
+        admit_priority, admit_reservation, child_service_rights, construction_record,
     };
     use crate::generation::Authority;
     use crate::object_allocator::TaskArenaId;
+    use crate::schedule::Reservation;
 
     /// B48: a declared priority at or above the root's is refused, not clamped.
     ///
@@ -1332,6 +1782,25 @@ mod tests {
         assert_eq!(declared_transfer, ordinary);
     }
 
+    /// B48's MCS half: a reservation is admitted exactly when the kernel can
+    /// enforce one. On the product kernel it is refused rather than dropped,
+    /// so a generation built for the MCS profile does not boot there believing
+    /// its control loop is isolated.
+    #[test]
+    fn a_reservation_needs_the_mcs_kernel() {
+        assert_eq!(admit_reservation(None), Ok(None));
+        let reservation = Reservation {
+            budget_us: 2_000,
+            period_us: 10_000,
+        };
+        let admitted = admit_reservation(Some(reservation));
+        if sel4::sel4_cfg_bool!(KERNEL_MCS) {
+            assert_eq!(admitted, Ok(Some(reservation)));
+        } else {
+            assert_eq!(admitted, Err(TaskError::ReservationUnsupported));
+        }
+    }
+
     #[test]
     fn construction_cleanup_owns_every_failure_transition() {
         let task = TaskId(7);
@@ -1495,6 +1964,46 @@ mod tests {
         assert!(CHILD_SLOT_CONSOLE < (1 << CHILD_CNODE_SIZE_BITS));
     }
 
+    /// Every thread has its own reply slot, between the fixed shell and the
+    /// console, and no plan may name any of them on either kernel.
+    #[test]
+    fn each_threads_reply_slot_is_reserved_below_the_console() {
+        assert!(CHILD_SLOT_REPLY_BASE > super::CHILD_SLOT_CNODE);
+        assert_eq!(
+            CHILD_SLOT_REPLY_BASE + MAX_CHILD_THREADS as sel4::CPtrBits,
+            CHILD_SLOT_CONSOLE
+        );
+        for thread in 0..MAX_CHILD_THREADS {
+            let reply = CHILD_SLOT_REPLY_BASE + thread as sel4::CPtrBits;
+            let collided = ChildSlots {
+                tcb: reply,
+                ..ChildSlots::SHELL
+            };
+            assert!(collided.validate().is_err());
+            assert_eq!(
+                ChildSlots::SHELL.declares(reply, true),
+                sel4::sel4_cfg_bool!(KERNEL_MCS)
+            );
+        }
+    }
+
+    /// The thread lock sits between the authority mirrors and the receive
+    /// slot, is declared on both kernels, and no plan may name it.
+    #[test]
+    fn the_thread_lock_slot_is_reserved_above_the_mirrors() {
+        assert_eq!(
+            super::CHILD_SLOT_AUTHORITY_BASE + super::CHILD_NATIVE_REGION_SLOTS,
+            CHILD_SLOT_THREAD_LOCK
+        );
+        assert!(CHILD_SLOT_THREAD_LOCK < super::CHILD_SLOT_RECEIVE);
+        assert!(ChildSlots::SHELL.declares(CHILD_SLOT_THREAD_LOCK, false));
+        let collided = ChildSlots {
+            fault: CHILD_SLOT_THREAD_LOCK,
+            ..ChildSlots::SHELL
+        };
+        assert!(collided.validate().is_err());
+    }
+
     /// A layout naming one slot twice is refused: one install would silently
     /// overwrite another.
     #[test]
@@ -0,0 +1,317 @@
+//! MCS scheduling-context backing for [`crate::timer::PlatformTimer`].
+//!
+//! # Mechanism
+//!
+//! The product kernel leaves the root one architected-timer PPI and exports
+//! the EL1 physical timer's registers, and the counter, to EL0 so the root can
+//! program it (`platform_timer.rs`). That is a workaround, and the MCS profile
+//! (`sel4/config/qemu-arm-virt-mcs.cmake`) does without both exports: the
+//! kernel already keeps a timer for replenishing scheduling contexts, so a
+//! deadline can be expressed as one, and so can the passage of time.
+//!
+//! The adapter owns two small threads in the root's own CSpace and VSpace,
+//! with no IPC buffer and no stack. Each runs a loop that yields, which under
+//! MCS gives up the rest of its budget until the next replenishment, and then
+//! does one thing.
+//!
+//! - The clock thread (`slime_mcs_clock_entry`) has a fixed period of
+//!   [`CLOCK_PERIOD_US`] and increments [`CLOCK_TICKS`]. That count is the
+//!   root's monotonic clock, at [`CLOCK_FREQUENCY_HZ`]. Replenishments are
+//!   anchored to the period, not to when the thread last ran, so a late tick
+//!   delays the count without making it drift.
+//! - The tick thread (`slime_mcs_tick_entry`) signals a badged notification
+//!   copy. Programming a deadline configures its scheduling context with a
+//!   period ending at the deadline ([`Reservation::timeout`]) and restarts the
+//!   loop, so its first signal lands one period later.
+//!
+//! The kernel's own timer raises both. The root claims no IRQ and reads no
+//! `CNT*` register, which is why this profile exports none of them: a
+//! component on this kernel has no way to observe time except through a clock
+//! grant.
+//!
+//! # What this establishes, and what it does not
+//!
+//! The deadline and the clock are delivered by the same kernel mechanism that
+//! enforces every child's [`Reservation`], so the timer and CPU isolation
+//! share one clock. It is not more punctual than the physical timer. Both
+//! threads wait behind any other thread at [`TICK_PRIORITY`], a deadline
+//! closer than twice [`crate::schedule::MIN_BUDGET_US`] is rounded out to it,
+//! and the clock resolves no finer than one period. Nothing here has been
+//! observed on a running MCS kernel yet; the boot gate still covers only the
+//! product timer.
+
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use crate::child_vspace;
+use crate::event::MonotonicInstant;
+use crate::object_allocator::{AllocError, ObjectAllocator};
+use crate::schedule::{MIN_BUDGET_US, Reservation};
+use crate::task::{self, TaskError};
+use crate::timer::PlatformTimer;
+
+/// Priority of the thread that signals deadlines: the root's own, so a
+/// runnable child never delays a wake.
+pub const TICK_PRIORITY: sel4::Word = 255;
+
+/// Period of the clock thread, and so the resolution of the root's monotonic
+/// clock on this profile.
+pub const CLOCK_PERIOD_US: u64 = 1_000;
+
+/// Ticks per second of [`CLOCK_TICKS`].
+pub const CLOCK_FREQUENCY_HZ: u64 = 1_000_000 / CLOCK_PERIOD_US;
+
+/// Periods the clock thread has completed since it started. The clock thread
+/// is the only writer; the root only loads it.
+static CLOCK_TICKS: AtomicU64 = AtomicU64::new(0);
+
+/// Badge minted onto the notification copy the tick thread signals, for the
+/// same reason as the product timer's: a polled `0` must mean "nothing yet".
+const SIGNAL_BADGE: sel4::Badge = 1;
+
+// The tick loop. `x0` holds the signal capability on entry and moves to a
+// register the kernel preserves across both syscalls. `-11` is `seL4_Yield`
+// and `-5` is `seL4_Send` in the MCS syscall numbering; a send with a zero
+// message info on a notification is `seL4_Signal`. It touches no memory, so
+// it needs neither a stack nor an IPC buffer.
+core::arch::global_asm!(
+    ".global slime_mcs_tick_entry",
+    "slime_mcs_tick_entry:",
+    "    mov x19, x0",
+    "1:  mov x7, #-11",
+    "    svc #0",
+    "    mov x0, x19",
+    "    mov x1, #0",
+    "    mov x7, #-5",
+    "    svc #0",
+    "    b 1b",
+);
+
+// The clock loop. `x0` holds the address of `CLOCK_TICKS` on entry and
+// moves to a preserved register like the tick loop's capability. The thread
+// yields first, so the count is the number of periods completed. It is the
+// counter's only writer, and an aligned 64-bit store is single-copy atomic,
+// so the root's load never sees a torn value. It needs no stack and no IPC
+// buffer either.
+core::arch::global_asm!(
+    ".global slime_mcs_clock_entry",
+    "slime_mcs_clock_entry:",
+    "    mov x19, x0",
+    "1:  mov x7, #-11",
+    "    svc #0",
+    "    ldr x1, [x19]",
+    "    add x1, x1, #1",
+    "    stlr x1, [x19]",
+    "    b 1b",
+);
+
+unsafe extern "C" {
+    /// Entry of the tick loop above.
+    fn slime_mcs_tick_entry() -> !;
+    /// Entry of the clock loop above.
+    fn slime_mcs_clock_entry() -> !;
+}
+
+/// Failure to create and wire the tick and clock threads. Every step names
+/// exactly which seL4 invocation or allocator call failed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TimeoutTimerSetupError {
+    /// A CSlot, a TCB, or the notification could not be allocated.
+    Allocator(AllocError),
+    /// Minting the badged sender copy of the notification failed.
+    MintSignalCap(sel4::Error),
+    /// A thread's scheduling context could not be created or configured.
+    SchedContext(TaskError),
+    /// `seL4_TCB_Configure` failed.
+    Configure(sel4::Error),
+    /// `seL4_TCB_SetSchedParams` failed.
+    SchedParams(sel4::Error),
+    /// Starting the clock thread with `seL4_TCB_WriteRegisters` failed.
+    StartClock(sel4::Error),
+}
+
+/// Failure moving the tick thread to a new deadline. Reading the clock
+/// cannot fail, so this is the only error [`TimeoutTimerAdapter`] reports
+/// through [`PlatformTimer::Error`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TimeoutTimerError(pub sel4::Error);
+
+/// A deadline timer carried by a scheduling context this root task owns.
+pub struct TimeoutTimerAdapter {
+    tick: sel4::cap::Tcb,
+    sched_context: sel4::cap::SchedContext,
+    /// The badged, send-only copy the tick thread signals through.
+    signal: sel4::cap::Notification,
+    /// The unbadged, full-rights capability: used to wait/poll, never to
+    /// signal.
+    notification: sel4::cap::Notification,
+}
+
+impl TimeoutTimerAdapter {
+    /// Create the notification, the suspended tick thread and the running
+    /// clock thread, and return the live adapter. Nothing is signalled until
+    /// a deadline is programmed.
+    pub fn acquire(
+        bootinfo: &sel4::BootInfo,
+        allocator: &mut ObjectAllocator,
+    ) -> Result<Self, TimeoutTimerSetupError> {
+        let notification = allocator
+            .allocate_fixed::<sel4::cap_type::Notification>()
+            .map_err(TimeoutTimerSetupError::Allocator)?
+            .cap();
+        let signal = allocator
+            .reserve_slot::<sel4::cap_type::Notification>()
+            .map_err(TimeoutTimerSetupError::Allocator)?
+            .cap();
+        let tick = allocator
+            .allocate_fixed::<sel4::cap_type::Tcb>()
+            .map_err(TimeoutTimerSetupError::Allocator)?
+            .cap();
+
+        let root_cnode = sel4::init_thread::slot::CNODE.cap();
+        root_cnode
+            .absolute_cptr(signal)
+            .mint(
+                &root_cnode.absolute_cptr(notification),
+                sel4::CapRightsBuilder::none().write(true).build(),
+                SIGNAL_BADGE,
+            )
+            .map_err(TimeoutTimerSetupError::MintSignalCap)?;
+
+        // Configured at once so the context is never bound without a budget;
+        // every `program_deadline` replaces this period.
+        let sched_context =
+            task::reserve_sched_context(allocator, None, Reservation::timeout(0, 1))
+                .map_err(TimeoutTimerSetupError::SchedContext)?;
+        configure_thread(bootinfo, tick, sched_context)?;
+
+        // The clock thread keeps one fixed reservation for the life of the
+        // root: the least budget the kernel admits, once a period.
+        let clock = allocator
+            .allocate_fixed::<sel4::cap_type::Tcb>()
+            .map_err(TimeoutTimerSetupError::Allocator)?
+            .cap();
+        let clock_context = task::reserve_sched_context(
+            allocator,
+            None,
+            Reservation {
+                budget_us: MIN_BUDGET_US,
+                period_us: CLOCK_PERIOD_US,
+            },
+        )
+        .map_err(TimeoutTimerSetupError::SchedContext)?;
+        configure_thread(bootinfo, clock, clock_context)?;
+        let entry: unsafe extern "C" fn() -> ! = slime_mcs_clock_entry;
+        let mut registers = sel4::UserContext::default();
+        *registers.pc_mut() = entry as usize as sel4::Word;
+        *registers.c_param_mut(0) = CLOCK_TICKS.as_ptr() as usize as sel4::Word;
+        clock
+            .tcb_write_all_registers(true, &mut registers)
+            .map_err(TimeoutTimerSetupError::StartClock)?;
+
+        Ok(Self {
+            tick,
+            sched_context,
+            signal,
+            notification,
+        })
+    }
+
+    /// The capability a caller waits or polls on to observe delivery.
+    pub const fn notification(&self) -> sel4::cap::Notification {
+        self.notification
+    }
+
+    /// The badge a genuine deadline signal carries.
+    pub const fn signal_badge(&self) -> sel4::Badge {
+        SIGNAL_BADGE
+    }
+
+    /// Ticks per second of the clock deadlines are measured in.
+    pub const fn frequency_hz(&self) -> u64 {
+        CLOCK_FREQUENCY_HZ
+    }
+}
+
+impl PlatformTimer for TimeoutTimerAdapter {
+    type Error = TimeoutTimerError;
+
+    fn monotonic_now(&mut self) -> Result<MonotonicInstant, Self::Error> {
+        Ok(MonotonicInstant(clock_ticks()))
+    }
+
+    fn program_deadline(&mut self, deadline: MonotonicInstant) -> Result<(), Self::Error> {
+        // Stopped first, so the reconfigured context starts a fresh period
+        // rather than finishing the one a previous deadline set.
+        self.tick.tcb_suspend().map_err(TimeoutTimerError)?;
+        let remaining = deadline.0.saturating_sub(clock_ticks());
+        let reservation = Reservation::timeout(remaining, CLOCK_FREQUENCY_HZ);
+        task::sched_control()
+            .sched_control_configure_flags(
+                self.sched_context,
+                reservation.budget_us,
+                reservation.period_us,
+                0,
+                0,
+                0,
+            )
+            .map_err(TimeoutTimerError)?;
+        let entry: unsafe extern "C" fn() -> ! = slime_mcs_tick_entry;
+        let mut registers = sel4::UserContext::default();
+        *registers.pc_mut() = entry as usize as sel4::Word;
+        *registers.c_param_mut(0) = self.signal.bits() as sel4::Word;
+        self.tick
+            .tcb_write_all_registers(true, &mut registers)
+            .map_err(TimeoutTimerError)
+    }
+
+    fn disarm_timer(&mut self) -> Result<(), Self::Error> {
+        // A suspended thread signals nothing, and suspension also drops it
+        // from the release queue, so no replenishment is left to fire.
+        self.tick.tcb_suspend().map_err(TimeoutTimerError)
+    }
+
+    fn acknowledge_timer_irq(&mut self) -> Result<(), Self::Error> {
+        // There is no interrupt to acknowledge: the tick thread's signal is an
+        // ordinary notification, cleared by the wait that observed it. A
+        // deadline past `MAX_PERIOD_US` is signalled again each period until
+        // it is reached, and each early signal drains no wake.
+        Ok(())
+    }
+}
+
+/// Binds `thread` to the root's CSpace and VSpace and to `sched_context` at
+/// [`TICK_PRIORITY`], leaving it suspended.
+fn configure_thread(
+    bootinfo: &sel4::BootInfo,
+    thread: sel4::cap::Tcb,
+    sched_context: sel4::cap::SchedContext,
+) -> Result<(), TimeoutTimerSetupError> {
+    thread
+        .tcb_configure(
+            sel4::init_thread::slot::CNODE.cap(),
+            child_vspace::root_cspace_guard(bootinfo),
+            sel4::init_thread::slot::VSPACE.cap(),
+            // No IPC buffer: neither loop carries anything in message
+            // registers.
+            0,
+            sel4::cap::Granule::from_bits(0),
+        )
+        .map_err(TimeoutTimerSetupError::Configure)?;
+    // No fault handler, as for the console thread: a fault here is a root
+    // defect, and the kernel reports it rather than delivering it.
+    thread
+        .tcb_set_sched_params(
+            sel4::init_thread::slot::TCB.cap(),
+            TICK_PRIORITY,
+            TICK_PRIORITY,
+            sched_context,
+            sel4::cap::Endpoint::from_bits(0),
+        )
+        .map_err(TimeoutTimerSetupError::SchedParams)
+}
+
+/// The clock thread's count, the clock deadlines are measured against.
+#[inline]
+fn clock_ticks() -> u64 {
+    CLOCK_TICKS.load(Ordering::Acquire)
+}
@@ -1,4 +1,4 @@
-//! Bounded timer and wake scheduling semantics for a non-MCS seL4 runtime.
+//! Bounded timer and wake scheduling semantics for the seL4 runtime.
 //!
 //! The queue and its transitions are pure mechanism over a fixed-capacity
 //! array: no allocation, no interior mutability, no ambient clock. Platform
@@ -16,12 +16,18 @@
 //! and saw the monotonic counter advance across the wait. Hardware delivery
 //! into this state machine is therefore established, not assumed.
 //!
+//! The MCS kernel profile backs the same trait with
+//! `crate::timeout_timer::TimeoutTimerAdapter`, which takes its deadlines from
+//! a scheduling context's period rather than the physical timer (B48). The
+//! boot proof runs unchanged against it, but no gate has observed that yet.
+//!
 //! # What is still not established
 //!
 //! No temporal isolation: nothing bounds how long another thread may keep the
 //! CPU between the compare condition becoming true and this queue being
 //! serviced. No CPU reservation: non-MCS seL4 has no budget to charge, so a
-//! wake only re-enters the ready order. No deadline guarantee: a programmed
+//! wake only re-enters the ready order. Under MCS each child thread does run
+//! on a reservation (`task.rs`), but this queue still charges nothing to it. No deadline guarantee: a programmed
 //! deadline is a one-shot compare, and lateness is neither bounded nor
 //! reported. Only one deadline is ever armed in hardware — the earliest queued
 //! one — and the ordering of wake decisions is all this module promises.
@@ -113,7 +119,8 @@ pub enum ServiceTimerError<E, const CAPACITY: usize> {
 
 /// Platform timer/IRQ operations, kept behind a trait so the queue above stays
 /// host-testable against a fake. The live implementation is
-/// `crate::platform_timer::PhysicalTimerAdapter` over the EL1 physical timer;
+/// `crate::platform_timer::PhysicalTimerAdapter` over the EL1 physical timer,
+/// or `crate::timeout_timer::TimeoutTimerAdapter` under MCS;
 /// implementing this trait is not by itself evidence that a notification path
 /// exists — the boot gate's ordered `SLIME_TIMER` markers are.
 pub trait PlatformTimer {
@@ -4,7 +4,10 @@
 #![deny(clippy::unwrap_used, clippy::expect_used, clippy::panic)]
 #![deny(clippy::indexing_slicing)]
 
-use boot_contracts::bootstate::{BootState, SLOT_BYTES, SLOT_COUNT};
+use boot_contracts::bootstate::{
+    BOOTSTORE_SEALED_OFFSET, BOOTSTORE_VERDICT_OFFSET, BootState, HEALTH_VERDICT_BYTES,
+    HealthVerdict, SLOT_BYTES,
+};
 use boot_contracts::component_image::{self, ComponentTargetError};
 use boot_contracts::generation::{
     Generation, KIND_BOOTSTRAP, KIND_COMPONENT, KIND_KERNEL, generation_identity,
@@ -24,7 +27,7 @@ pub use boot_contracts::bootstate::{
     BOOTSTORE_HEADER_REQUIRED_FLAGS_OFFSET, BOOTSTORE_HEADER_RESERVED_OFFSET, BOOTSTORE_MAGIC,
     BOOTSTORE_RELEASES_OFFSET, BOOTSTORE_VERSION,
 };
-use boot_contracts::release::{INITIAL_TRUST_ROOT, RELEASE_BYTES, Release};
+use boot_contracts::release::{INITIAL_TRUST_ROOT, RELEASE_BYTES, Release, TrustRoot};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BootError {
@@ -44,6 +47,9 @@ pub enum BootError {
     BadComponentImage(ComponentTargetError),
     BadExecutableKind,
     BadRelease,
+    /// BootState names a rotated trust root and the record read back is not
+    /// it: missing, a different hash, damaged, or another version.
+    BadTrustRoot,
     Target(TargetError),
     KernelImage(ImageError),
     TooManyMemoryEntries,
@@ -163,7 +169,7 @@ pub fn decode_directory(bytes: &[u8]) -> Result<BootDirectory<'_>, BootError> {
     hasher.update(
         bytes
             .get(
-                SLOT_BYTES * SLOT_COUNT
+                BOOTSTORE_SEALED_OFFSET
                     ..BOOTSTORE_DIRECTORY_OFFSET + BOOTSTORE_HEADER_CHECKSUM_OFFSET,
             )
             .ok_or(BootError::Truncated)?,
@@ -255,6 +261,19 @@ fn directory_root(directory: &BootDirectory<'_>) -> Result<[u8; 32], BootError>
     Ok(root.finalize())
 }
 
+/// The health verdict a booted root left in the store, if it left one.
+///
+/// The sector lies below the sealed region, so it is covered by its own
+/// checksum rather than the store's; a damaged or absent record reads as no
+/// verdict, which leaves the pending generation to its remaining attempts.
+pub fn health_verdict(store: &[u8]) -> Option<HealthVerdict> {
+    let bytes: &[u8; HEALTH_VERDICT_BYTES] = store
+        .get(BOOTSTORE_VERDICT_OFFSET..BOOTSTORE_VERDICT_OFFSET + HEALTH_VERDICT_BYTES)?
+        .try_into()
+        .ok()?;
+    HealthVerdict::decode(bytes).ok()
+}
+
 pub fn selected_generation_identity(state: &BootState) -> [u8; 32] {
     match (state.pending, state.remaining_attempts) {
         (Some(pending), attempts) if attempts > 0 => pending,
@@ -326,15 +345,40 @@ pub fn admit_generation_closure<'a>(
     Ok(kernel)
 }
 
+/// The trust root releases are verified against: the compiled-in one while
+/// BootState names none, otherwise the `record` it names, which must hash to
+/// the named object and carry the named version.
+///
+/// A named root that cannot be read back fails the boot rather than falling
+/// back to the compiled-in one, which would re-accept the keys a rotation
+/// retired.
+pub fn trust_root(state: &BootState, record: Option<&[u8]>) -> Result<TrustRoot, BootError> {
+    let Some(reference) = state.trust_root else {
+        return Ok(INITIAL_TRUST_ROOT);
+    };
+    let record = record.ok_or(BootError::BadTrustRoot)?;
+    let mut hash = Sha256::new();
+    hash.update(record);
+    if hash.finalize() != reference.object {
+        return Err(BootError::BadTrustRoot);
+    }
+    let root = TrustRoot::decode(record).map_err(|_| BootError::BadTrustRoot)?;
+    if root.version != reference.version {
+        return Err(BootError::BadTrustRoot);
+    }
+    Ok(root)
+}
+
 pub fn verify_release(
     entry: &DirectoryEntry<'_>,
     generation: &Generation<'_>,
     state: &BootState,
+    root: &TrustRoot,
     running_pending: bool,
 ) -> Result<u64, BootError> {
     let release = Release::decode(entry.release_bytes).map_err(|_| BootError::BadRelease)?;
     release
-        .verify_generation(generation, &INITIAL_TRUST_ROOT)
+        .verify_generation(generation, root)
         .map_err(|_| BootError::BadRelease)?;
     if running_pending {
         if release.sequence <= state.accepted_release_sequence {
@@ -7,6 +7,7 @@
 extern crate alloc;
 
 use alloc::vec::Vec;
+use boot_contracts::bootstate::{BOOTSTORE_VERDICT_OFFSET, HEALTH_VERDICT_BYTES, HealthOutcome};
 use boot_contracts::handoff::{
     HandoffFramebuffer, HandoffMemoryEntry, KernelHandoffV1, MAX_MEMORY_ENTRIES, MEMORY_RESERVED,
     MEMORY_USABLE,
@@ -14,8 +15,9 @@ use boot_contracts::handoff::{
 use boot_contracts::kernel_image::{KernelImage, LOAD_BASE};
 use boot_contracts::trace;
 use slime_stage0::{
-    BootError, Slot, admit_generation_closure, decode_directory, select_bootstate_for_directory,
-    select_generation, verify_generation, verify_release,
+    BootError, Slot, admit_generation_closure, decode_directory, health_verdict,
+    select_bootstate_for_directory, select_generation, trust_root, verify_generation,
+    verify_release,
 };
 use uefi::boot::{self, AllocateType, MemoryType, PAGE_SIZE};
 use uefi::mem::memory_map::MemoryMap;
@@ -24,7 +26,10 @@ use uefi::proto::media::file::{File, FileAttribute, FileMode, FileType, RegularF
 use uefi::{CString16, Status};
 
 const BOOT_STORE_PATH: &str = "\\boot\\boot-store.bin";
-const HEALTH_CONFIRM_PATH: &str = "\\boot\\health-confirm.bin";
+/// The rotated trust root, read only when BootState names one. The bootstore
+/// has no room for it below the sealed region, and it needs none: BootState
+/// carries its hash and version.
+const TRUST_ROOT_PATH: &str = "\\boot\\trust-root.bin";
 const KERNEL_STACK_BYTES: usize = 256 * 1024;
 
 mod arch;
@@ -65,6 +70,39 @@ fn boot() -> Result<(), BootError> {
         .and_then(|slice| slice.try_into().ok())
         .ok_or(BootError::Truncated)?;
     let mut selected_state = select_bootstate_for_directory(slot_a, slot_b, &directory)?;
+    // A failed verdict for the pending generation rolls it back before an
+    // attempt is spent on it: the probes it declared already ran and said no,
+    // and booting it again would only repeat the observation.
+    let verdict = health_verdict(&store);
+    if let Some(pending) = selected_state.state.pending
+        && verdict.is_some_and(|verdict| verdict.rejects(pending))
+    {
+        let before = selected_state.state;
+        selected_state.state = before
+            .rollback_pending()
+            .map_err(|_| BootError::NoValidBootState)?;
+        let target = match selected_state.slot {
+            Slot::A => Slot::B,
+            Slot::B => Slot::A,
+        };
+        clear_health_verdict()?;
+        persist_bootstate(target, selected_state.state)?;
+        emit_trace(&trace::Record {
+            action: trace::Action::Rollback,
+            commit: trace::Commit::RollbackUpdate,
+            selected_slot: slot_index(selected_state.slot),
+            target_slot: Some(slot_index(target)),
+            sequence_before: before.sequence,
+            sequence_after: selected_state.state.sequence,
+            attempts_before: before.remaining_attempts,
+            attempts_after: selected_state.state.remaining_attempts,
+            known_good: selected_state.state.known_good,
+            pending: selected_state.state.pending,
+            generation_root: selected_state.state.generation_root,
+            state_root: selected_state.state.state_root,
+        });
+        selected_state.slot = target;
+    }
     let selection_state = selected_state.state;
     let running_pending =
         selection_state.pending.is_some() && selection_state.remaining_attempts > 0;
@@ -98,18 +136,37 @@ fn boot() -> Result<(), BootError> {
         });
         selected_state.slot = target;
     }
+    let record = match selection_state.trust_root {
+        Some(_) => Some(read_file(TRUST_ROOT_PATH).map_err(|_| BootError::BadTrustRoot)?),
+        None => None,
+    };
+    let root = trust_root(&selection_state, record.as_deref())?;
     let selected = select_generation(&directory, &selection_state)?;
     let generation = verify_generation(selected.bytes, &selected.identity)?;
     let kernel = admit_generation_closure(&generation)?;
-    let confirmation_pending =
-        selection_state.pending.is_some() && health_confirmation_matches(selection_state.pending);
-    if confirmation_pending {
-        verify_pending_for_promotion(&directory, &selection_state)?;
+    // A passed verdict promotes only the exact pending generation and release
+    // it was reached for, so the pending release is verified before the
+    // verdict is compared against it.
+    let passed_for_pending = selection_state.pending.is_some_and(|pending| {
+        verdict.is_some_and(|verdict| {
+            verdict.outcome == HealthOutcome::Passed && verdict.generation == pending
+        })
+    });
+    let mut promoted_release = None;
+    if passed_for_pending {
+        let pending_release = verify_pending_for_promotion(&directory, &selection_state, &root)?;
+        if let (Some(pending), Some(verdict)) = (selection_state.pending, verdict)
+            && verdict.promotes(pending, pending_release)
+        {
+            promoted_release = Some(pending_release);
+        }
     }
+    let confirmation_pending = promoted_release.is_some();
     let release_sequence = verify_release(
         &selected,
         &generation,
         &selection_state,
+        &root,
         confirmation_pending
             || (selection_state.pending.is_some() && selection_state.remaining_attempts > 0),
     )?;
@@ -138,15 +195,16 @@ fn boot() -> Result<(), BootError> {
     if confirmation_pending {
         let before = selected_state.state;
         let pending = before.pending.ok_or(BootError::NoValidBootState)?;
+        let pending_release = promoted_release.ok_or(BootError::NoValidBootState)?;
         selected_state.state = selected_state
             .state
-            .promote_pending(pending, release_sequence)
+            .promote_pending(pending, pending_release)
             .map_err(|_| BootError::NoValidBootState)?;
         let target = match selected_state.slot {
             Slot::A => Slot::B,
             Slot::B => Slot::A,
         };
-        consume_health_confirmation()?;
+        clear_health_verdict()?;
         persist_bootstate(target, selected_state.state)?;
         emit_trace(&trace::Record {
             action: trace::Action::Promotion,
@@ -281,37 +339,36 @@ fn read_file(path: &str) -> Result<Vec<u8>, BootError> {
     read_regular(&mut file)
 }
 
+/// Verify the pending generation as a promotion candidate, answering the
+/// sequence of its release.
 fn verify_pending_for_promotion(
     directory: &slime_stage0::BootDirectory<'_>,
     state: &boot_contracts::bootstate::BootState,
-) -> Result<(), BootError> {
+    root: &boot_contracts::release::TrustRoot,
+) -> Result<u64, BootError> {
     let pending = state.pending.ok_or(BootError::MissingGeneration)?;
     for index in 0..directory.count() {
         let entry = directory.entry(index)?;
         if entry.identity == pending {
             let generation = verify_generation(entry.bytes, &entry.identity)?;
             admit_generation_closure(&generation)?;
-            verify_release(&entry, &generation, state, true)?;
-            return Ok(());
+            return verify_release(&entry, &generation, state, root, true);
         }
     }
     Err(BootError::MissingGeneration)
 }
 
-fn consume_health_confirmation() -> Result<(), BootError> {
-    open_regular(HEALTH_CONFIRM_PATH, FileMode::ReadWrite)?
-        .delete()
-        .map_err(|_| BootError::Truncated)
-}
-
-fn health_confirmation_matches(pending: Option<[u8; 32]>) -> bool {
-    let Some(pending) = pending else {
-        return false;
-    };
-    let Ok(bytes) = read_file(HEALTH_CONFIRM_PATH) else {
-        return false;
-    };
-    bytes.as_slice() == pending
+/// Consume the health verdict once it has decided a transition. Zeroed rather
+/// than left in place so a later pending generation with the same identity is
+/// judged by its own boot, not by this one's.
+fn clear_health_verdict() -> Result<(), BootError> {
+    let mut file = open_regular(BOOT_STORE_PATH, FileMode::ReadWrite)?;
+    file.set_position(BOOTSTORE_VERDICT_OFFSET as u64)
+        .map_err(|_| BootError::Truncated)?;
+    file.write(&[0u8; HEALTH_VERDICT_BYTES])
+        .map_err(|_| BootError::Truncated)?;
+    file.flush().map_err(|_| BootError::Truncated)?;
+    Ok(())
 }
 
 fn persist_bootstate(